@@ -5,6 +5,7 @@ import {
   uninstallCli,
   checkCliUpdate,
   updateCli,
+  copyText,
   CliStatus,
   BinaryUpdateInfo
 } from '../lib/api'
@@ -86,7 +87,7 @@ function CopyableCommand({ cmd, desc }: CliCommand) {
 
   const handleCopy = async () => {
     try {
-      await navigator.clipboard.writeText(cmd)
+      await copyText(cmd)
       setCopied(true)
       setTimeout(() => setCopied(false), 1500)
     } catch { /* noop */ }
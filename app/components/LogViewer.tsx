@@ -5,7 +5,7 @@ import {
   ChevronLeft, ChevronRight, Eye, XCircle, Layers,
   Copy, Check
 } from 'lucide-react';
-import { getLogFiles, readLogFile, clearLogFile, clearAllLogs, LogFile, LogEntry } from '../lib/api';
+import { getLogFiles, readLogFile, clearLogFile, clearAllLogs, copyText, LogFile, LogEntry } from '../lib/api';
 import { useApp } from '../lib/AppContext';
 
 // Format bytes to human readable
@@ -240,7 +240,7 @@ export function LogViewer() {
   // Copy log entry to clipboard
   const handleCopyEntry = async (raw: string) => {
     try {
-      await navigator.clipboard.writeText(raw);
+      await copyText(raw);
       setCopied(true);
       setTimeout(() => setCopied(false), 2000);
     } catch {
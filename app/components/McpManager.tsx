@@ -7,6 +7,7 @@ import {
   stopMcp,
   checkMcpUpdate,
   updateMcp,
+  copyText,
   McpStatus,
   BinaryUpdateInfo
 } from '../lib/api'
@@ -88,7 +89,7 @@ export function McpManager() {
 
   const handleCopy = async () => {
     try {
-      await navigator.clipboard.writeText(CONFIG_SNIPPET)
+      await copyText(CONFIG_SNIPPET)
       setCopied(true)
       setTimeout(() => setCopied(false), 2000)
     } catch {
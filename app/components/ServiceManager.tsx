@@ -14,6 +14,8 @@ import {
   listServiceVersions,
   setActiveServiceVersion,
   removeServiceVersion,
+  getPendingRestarts,
+  PendingRestart,
 } from '../lib/api';
 import { useApp } from '../lib/AppContext';
 import { settingsStore } from '../lib/store';
@@ -70,6 +72,7 @@ export function ServiceManager() {
   const [reloading, setReloading] = useState<string | null>(null);
   const [pathStatuses, setPathStatuses] = useState<Record<string, ServicePathStatus>>({});
   const [pathProcessing, setPathProcessing] = useState<string | null>(null);
+  const [pendingRestarts, setPendingRestarts] = useState<Record<string, PendingRestart>>({});
 
   // Config drawer state
   const [configDrawer, setConfigDrawer] = useState<{
@@ -259,6 +262,16 @@ export function ServiceManager() {
     setInstalledDirs(next);
   };
 
+  // Services whose config was edited while running (php.ini, my.ini, ...)
+  const fetchPendingRestarts = async () => {
+    try {
+      const entries = await getPendingRestarts();
+      setPendingRestarts(Object.fromEntries(entries.map((e) => [e.service, e])));
+    } catch {
+      // Older backends don't expose the command — treat as none pending
+    }
+  };
+
   // Check PATH status for all services
   const checkAllPathStatuses = async () => {
     const statuses: Record<string, ServicePathStatus> = {};
@@ -309,6 +322,15 @@ export function ServiceManager() {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [services.length]);
 
+  // Re-check pending restarts after start/stop and when the config drawer closes
+  const statusKey = services.map((s) => `${s.name}:${s.status}`).join(',');
+  useEffect(() => {
+    if (!configDrawer.isOpen) {
+      fetchPendingRestarts();
+    }
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [statusKey, configDrawer.isOpen]);
+
   // Check PATH statuses when services change (debounced)
   useEffect(() => {
     if (services.length > 0) {
@@ -334,7 +356,7 @@ export function ServiceManager() {
       // Pass the explicit version string so the backend knows where to land
       // it under bin/.versions/<svc>/<ver>/ (irrelevant for PHP, which keeps
       // its own `bin/php/<ver>/` layout, but harmless).
-      await downloadService(version.download_url, filename, typeParam, version.version);
+      await downloadService(version.download_url, filename, typeParam, version.version, version.sha256);
       await Promise.all([refreshServices(), fetchInstalledDirs()]);
       addToast({ type: 'success', message: `${serviceType} ${version.version} installed successfully` });
       setSubTab('manage');
@@ -603,6 +625,16 @@ export function ServiceManager() {
                       </div>
                     )}
 
+                    {/* Config changed while running — needs a restart to apply */}
+                    {service.status === 'running' && pendingRestarts[service.name] && (
+                      <div
+                        className="px-3 py-1.5 rounded-lg text-xs font-medium bg-amber-500/10 text-amber-500"
+                        title={`${pendingRestarts[service.name].reason} — restart to apply`}
+                      >
+                        RESTART NEEDED
+                      </div>
+                    )}
+
                     {/* Configure button (for services with settings) */}
                     {hasConfiguration(service.service_type) && (
                       <button
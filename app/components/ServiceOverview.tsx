@@ -1,7 +1,8 @@
 import { useEffect, useState } from 'react'
 import { Power, RefreshCw, StopCircle, Download, AlertTriangle, ExternalLink, Loader2 } from 'lucide-react'
 import { useApp, ServiceWithStatus } from '../lib/AppContext'
-import { checkSystemRequirements } from '../lib/api'
+import { checkSystemRequirements, fixPermissions } from '../lib/api'
+import type { PathPermission } from '../lib/api'
 import { getServiceIcon } from '../lib/serviceIcons'
 import { open } from '@tauri-apps/plugin-shell'
 
@@ -22,6 +23,8 @@ export function ServiceOverview({ onNavigateToInstall }: ServiceOverviewProps) {
   } = useApp()
 
   const [vcRedistMissing, setVcRedistMissing] = useState(false)
+  const [permissionIssues, setPermissionIssues] = useState<PathPermission[]>([])
+  const [fixingPermissions, setFixingPermissions] = useState(false)
   const [refreshing, setRefreshing] = useState(false)
   const [startingAll, setStartingAll] = useState(false)
   const [stoppingAll, setStoppingAll] = useState(false)
@@ -29,9 +32,22 @@ export function ServiceOverview({ onNavigateToInstall }: ServiceOverviewProps) {
   useEffect(() => {
     checkSystemRequirements().then(reqs => {
       setVcRedistMissing(!reqs.vc_redist_installed)
+      setPermissionIssues(reqs.permissions.filter(p => p.exists && !p.writable))
     })
   }, [])
 
+  const handleFixPermissions = async () => {
+    setFixingPermissions(true)
+    try {
+      const results = await fixPermissions()
+      setPermissionIssues(results.filter(p => p.exists && !p.writable))
+    } catch {
+      // Keep the current list; the remediation commands are still shown
+    } finally {
+      setFixingPermissions(false)
+    }
+  }
+
   const handleRefresh = async () => {
     setRefreshing(true)
     try {
@@ -145,6 +161,33 @@ export function ServiceOverview({ onNavigateToInstall }: ServiceOverviewProps) {
         </div>
       )}
 
+      {/* Permissions Warning */}
+      {permissionIssues.length > 0 && (
+        <div className="mb-6 p-4 bg-amber-500/10 border border-amber-500/20 rounded-xl flex items-start gap-3">
+          <AlertTriangle className="text-amber-500 shrink-0 mt-0.5" size={20} />
+          <div className="min-w-0">
+            <h3 className="font-semibold text-amber-500 mb-1">Orbit Can't Write Some Paths</h3>
+            <ul className="text-sm text-content-secondary mb-3 space-y-2">
+              {permissionIssues.map(p => (
+                <li key={p.label}>
+                  <span className="font-mono text-xs">{p.path}</span>
+                  {p.problem && <p className="text-xs text-content-muted">{p.repair ?? p.problem}</p>}
+                  {p.remediation && <code className="block text-xs text-content-muted break-all">{p.remediation}</code>}
+                </li>
+              ))}
+            </ul>
+            <button
+              onClick={handleFixPermissions}
+              disabled={fixingPermissions}
+              className="flex items-center gap-2 px-3 py-1.5 bg-amber-500/20 hover:bg-amber-500/30 text-amber-500 rounded-lg text-sm font-medium transition-colors"
+            >
+              {fixingPermissions && <Loader2 size={14} className="animate-spin" />}
+              Fix Permissions
+            </button>
+          </div>
+        </div>
+      )}
+
       {/* Service Cards */}
       <div className="grid grid-cols-1 sm:grid-cols-2 xl:grid-cols-3 gap-4 mb-6">
         {services.map(service => (
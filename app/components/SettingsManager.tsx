@@ -9,8 +9,8 @@ function GithubIcon({ size = 18, className = '' }: { size?: number; className?:
     </svg>
   );
 }
-import { clearAllCaches, deployListConnections, deployAddConnection, deployRemoveConnection, deployTestConnection } from '../lib/api';
-import type { ServerConnection } from '../lib/api';
+import { clearAllCaches, getDataDirInfo, setDataDirOverride, getNetworkSettings, setNetworkSettings, checkConnectivity, deployListConnections, deployAddConnection, deployRemoveConnection, deployTestConnection } from '../lib/api';
+import type { ServerConnection, DataDirInfo, NetworkSettings } from '../lib/api';
 import { useApp } from '../lib/AppContext';
 import { PathEditorModal } from './PathEditorModal';
 import { HostsEditorModal } from './HostsEditorModal';
@@ -56,6 +56,15 @@ export function SettingsManager() {
   const [workspacePath, setWorkspacePath] = useState<string>('');
   const [workspaceLoading, setWorkspaceLoading] = useState(false);
 
+  // Data Directory State
+  const [dataDir, setDataDir] = useState<DataDirInfo | null>(null);
+  const [dataDirLoading, setDataDirLoading] = useState(false);
+
+  // Network Limits State
+  const [network, setNetwork] = useState<NetworkSettings | null>(null);
+  const [networkSaving, setNetworkSaving] = useState(false);
+  const [suggestOffline, setSuggestOffline] = useState(false);
+
   // Ngrok Token State
   const [ngrokToken, setNgrokToken] = useState<string>('');
   const [ngrokTokenSaving, setNgrokTokenSaving] = useState(false);
@@ -74,6 +83,8 @@ export function SettingsManager() {
     loadVersionInfo();
     handleCheckForUpdates();
     loadWorkspaceSettings();
+    loadDataDir();
+    loadNetworkSettings();
     loadConnections();
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []);
@@ -114,6 +125,66 @@ export function SettingsManager() {
     }
   };
 
+  const loadDataDir = async () => {
+    try {
+      setDataDir(await getDataDirInfo());
+    } catch (e) {
+      console.error('Failed to load data directory:', e);
+    }
+  };
+
+  const handleChangeDataDir = async (reset = false) => {
+    try {
+      setDataDirLoading(true);
+      let selected: string | null = null;
+      if (!reset) {
+        const picked = await openDialog({
+          directory: true,
+          multiple: false,
+          title: 'Select Orbit Data Directory',
+        });
+        if (!picked || typeof picked !== 'string') return;
+        selected = picked;
+      }
+      const message = await setDataDirOverride(selected);
+      addToast({ type: 'success', message });
+      await loadDataDir();
+    } catch (e) {
+      addToast({ type: 'error', message: `Could not change data directory: ${e}` });
+    } finally {
+      setDataDirLoading(false);
+    }
+  };
+
+  const loadNetworkSettings = async () => {
+    try {
+      setNetwork(await getNetworkSettings());
+      const connectivity = await checkConnectivity();
+      setSuggestOffline(connectivity.suggest_offline);
+    } catch (e) {
+      console.error('Failed to load network settings:', e);
+    }
+  };
+
+  const handleSaveNetworkSettings = async () => {
+    if (!network) return;
+    try {
+      setNetworkSaving(true);
+      await setNetworkSettings(network);
+      if (network.offline) setSuggestOffline(false);
+      addToast({ type: 'success', message: 'Network limits saved!' });
+    } catch (e) {
+      addToast({ type: 'error', message: `Could not save network limits: ${e}` });
+    } finally {
+      setNetworkSaving(false);
+    }
+  };
+
+  const updateNetwork = (key: Exclude<keyof NetworkSettings, 'offline'>, value: string) => {
+    if (!network) return;
+    setNetwork({ ...network, [key]: Math.max(0, parseInt(value, 10) || 0) });
+  };
+
   const handleSaveNgrokToken = async () => {
     try {
       setNgrokTokenSaving(true);
@@ -325,6 +396,106 @@ export function SettingsManager() {
               </p>
             </div>
 
+            <div className="border-t border-edge-subtle pt-4">
+              <label className="block text-sm font-medium mb-3">Data Directory</label>
+              <div className="flex gap-3">
+                <input
+                  type="text"
+                  value={dataDir?.configured ?? dataDir?.resolved ?? ''}
+                  readOnly
+                  className="flex-1 px-4 py-2 border border-edge bg-surface-inset rounded-lg text-sm text-content-secondary min-w-0"
+                />
+                {dataDir?.configured && (
+                  <button
+                    onClick={() => handleChangeDataDir(true)}
+                    disabled={dataDirLoading}
+                    className="px-3 py-2 bg-surface-inset hover:bg-hover border border-edge rounded-lg text-sm transition-colors"
+                    title="Reset to default"
+                  >
+                    <RotateCcw size={16} />
+                  </button>
+                )}
+                <button
+                  onClick={() => handleChangeDataDir()}
+                  disabled={dataDirLoading || dataDir?.source === 'env'}
+                  className="px-4 py-2 bg-emerald-600 hover:bg-emerald-500 disabled:bg-neutral-700 rounded-lg text-sm font-medium transition-colors flex items-center justify-center min-w-[120px] text-white"
+                >
+                  {dataDirLoading ? <Loader2 size={16} className="animate-spin" /> : 'Change Folder'}
+                </button>
+              </div>
+              <p className="text-xs text-content-muted mt-2">
+                {dataDir?.source === 'env'
+                  ? 'Set by the ORBIT_DATA_DIR environment variable.'
+                  : 'Where services, configs and logs live. Shared with orbit-cli and the MCP server. Takes effect after restarting Orbit; existing data is not moved.'}
+              </p>
+            </div>
+
+            {network && (
+              <div className="border-t border-edge-subtle pt-4">
+                <label className="block text-sm font-medium mb-3">Network Limits</label>
+                <div className="grid grid-cols-2 gap-3">
+                  {([
+                    ['max_concurrent_fetches', 'Parallel version fetches', 1],
+                    ['max_concurrent_downloads', 'Parallel downloads (0 = unlimited)', 0],
+                    ['request_timeout_secs', 'Request timeout (s)', 1],
+                    ['download_timeout_secs', 'Download timeout (s)', 1],
+                    ['version_cache_ttl_secs', 'Version cache TTL (s, 0 = always refetch)', 0],
+                  ] as [Exclude<keyof NetworkSettings, 'offline'>, string, number][]).map(([key, label, min]) => (
+                    <label key={key} className="text-xs text-content-secondary">
+                      {label}
+                      <input
+                        type="number"
+                        min={min}
+                        value={network[key]}
+                        onChange={(e) => updateNetwork(key, e.target.value)}
+                        className="mt-1 w-full px-3 py-2 border border-edge bg-surface-inset rounded-lg text-sm text-content-secondary"
+                      />
+                    </label>
+                  ))}
+                </div>
+                <div className="flex items-center gap-2 mt-3">
+                  <input
+                    type="checkbox"
+                    id="offline_mode"
+                    checked={network.offline}
+                    onChange={(e) => setNetwork({ ...network, offline: e.target.checked })}
+                    className="w-4 h-4 rounded border-edge bg-surface text-emerald-500 focus:ring-emerald-500 focus:ring-offset-0"
+                  />
+                  <label htmlFor="offline_mode" className="text-sm text-content-secondary">
+                    Offline mode
+                  </label>
+                  <span className="text-xs text-content-muted">
+                    Use cached versions and fail downloads immediately instead of waiting on timeouts.
+                  </span>
+                </div>
+                {suggestOffline && !network.offline && (
+                  <div className="flex items-center justify-between gap-3 mt-2 px-3 py-2 rounded-lg bg-amber-500/10 border border-amber-500/20">
+                    <p className="text-xs text-amber-400">
+                      No network connection detected. Turn on offline mode to skip slow network timeouts.
+                    </p>
+                    <button
+                      onClick={() => setNetwork({ ...network, offline: true })}
+                      className="text-xs text-amber-400 hover:underline whitespace-nowrap"
+                    >
+                      Enable
+                    </button>
+                  </div>
+                )}
+                <div className="flex items-center justify-between gap-3 mt-3">
+                  <p className="text-xs text-content-muted">
+                    Throttle version refreshes and service downloads on slow or metered connections.
+                  </p>
+                  <button
+                    onClick={handleSaveNetworkSettings}
+                    disabled={networkSaving}
+                    className="px-4 py-2 bg-emerald-600 hover:bg-emerald-500 disabled:bg-neutral-700 rounded-lg text-sm font-medium transition-colors flex items-center justify-center min-w-[120px] text-white"
+                  >
+                    {networkSaving ? <Loader2 size={16} className="animate-spin" /> : 'Save Limits'}
+                  </button>
+                </div>
+              </div>
+            )}
+
             <div className="border-t border-edge-subtle pt-4">
               <label className="block text-sm font-medium mb-3">Ngrok Auth Token</label>
               <div className="flex gap-3">
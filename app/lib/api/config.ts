@@ -116,6 +116,40 @@ export const saveApacheConfRaw = async (content: string): Promise<string> => {
   }
 };
 
+// Apache Modules
+export interface ApacheModule {
+  name: string;
+  file: string;
+  enabled: boolean;
+}
+
+export const listApacheModules = async (): Promise<ApacheModule[]> => {
+  try {
+    return await invoke('list_apache_modules');
+  } catch (error) {
+    console.error('Failed to list Apache modules:', error);
+    throw error;
+  }
+};
+
+export const toggleApacheModule = async (module: string, enabled: boolean): Promise<string> => {
+  try {
+    return await invoke('toggle_apache_module', { module, enabled });
+  } catch (error) {
+    console.error('Failed to toggle Apache module:', error);
+    throw error;
+  }
+};
+
+export const getApacheTemplateModules = async (template: string): Promise<ApacheModule[]> => {
+  try {
+    return await invoke('get_apache_template_modules', { template });
+  } catch (error) {
+    console.error('Failed to get Apache template modules:', error);
+    throw error;
+  }
+};
+
 // PHP Mailpit Integration
 export const configurePhpMailpit = async (version: string, enabled: boolean, smtpPort: number = 1025): Promise<string> => {
   try {
@@ -437,3 +471,73 @@ export const searchPeclExtensions = async (query: string, phpVersion: string): P
     throw error;
   }
 };
+
+// Data Directory
+export interface DataDirInfo {
+  resolved: string;
+  default: string;
+  source: 'env' | 'settings' | 'default';
+  configured: string | null;
+  env_override: string | null;
+}
+
+export const getDataDirInfo = async (): Promise<DataDirInfo> => {
+  try {
+    return await invoke('get_data_dir_info');
+  } catch (error) {
+    console.error('Failed to get data directory info:', error);
+    throw error;
+  }
+};
+
+export const setDataDirOverride = async (path: string | null): Promise<string> => {
+  try {
+    return await invoke('set_data_dir_override', { path });
+  } catch (error) {
+    console.error('Failed to set data directory:', error);
+    throw error;
+  }
+};
+
+// Network limits (version refresh + downloads)
+export interface NetworkSettings {
+  max_concurrent_fetches: number;
+  max_concurrent_downloads: number; // 0 = unlimited
+  request_timeout_secs: number;
+  download_timeout_secs: number;
+  offline: boolean; // skip network requests, fail downloads immediately
+  version_cache_ttl_secs: number; // reuse fetched version lists this long (0 = always refetch)
+}
+
+export interface ConnectivityStatus {
+  offline_mode: boolean;
+  reachable: boolean;
+  suggest_offline: boolean;
+}
+
+export const getNetworkSettings = async (): Promise<NetworkSettings> => {
+  try {
+    return await invoke('get_network_settings');
+  } catch (error) {
+    console.error('Failed to get network settings:', error);
+    throw error;
+  }
+};
+
+export const setNetworkSettings = async (settings: NetworkSettings): Promise<void> => {
+  try {
+    return await invoke('set_network_settings', { settings });
+  } catch (error) {
+    console.error('Failed to save network settings:', error);
+    throw error;
+  }
+};
+
+export const checkConnectivity = async (): Promise<ConnectivityStatus> => {
+  try {
+    return await invoke('check_connectivity');
+  } catch (error) {
+    console.error('Failed to check connectivity:', error);
+    throw error;
+  }
+};
@@ -12,6 +12,7 @@ export interface ServiceVersion {
   filename: string;
   release_date?: string;
   source?: 'Api' | 'Cache' | 'Fallback';
+  sha256?: string;
 }
 
 export interface InstalledService {
@@ -49,14 +50,26 @@ export const reloadService = async (name: string): Promise<string> => {
   }
 };
 
+// Services whose config was edited after they were started
+export interface PendingRestart {
+  service: string;
+  reason: string;
+  since: string;
+}
+
+export const getPendingRestarts = async (): Promise<PendingRestart[]> => {
+  return await invoke('get_pending_restarts');
+};
+
 export const downloadService = async (
   url: string,
   filename: string,
   serviceType: string,
   version?: string,
+  sha256?: string,
 ): Promise<string> => {
   try {
-    return await invoke('download_service', { url, filename, serviceType, version });
+    return await invoke('download_service', { url, filename, serviceType, version, sha256 });
   } catch (error) {
     console.error('Failed to download service:', error);
     throw error;
@@ -190,8 +203,19 @@ export const autoStartServices = async (installedServices: string[]): Promise<st
 };
 
 // System Requirements
+export interface PathPermission {
+  label: string;
+  path: string;
+  exists: boolean;
+  writable: boolean;
+  problem: string | null;
+  remediation: string | null;
+  repair: string | null;
+}
+
 export interface SystemRequirements {
   vc_redist_installed: boolean;
+  permissions: PathPermission[];
 }
 
 export const checkSystemRequirements = async (): Promise<SystemRequirements> => {
@@ -199,6 +223,15 @@ export const checkSystemRequirements = async (): Promise<SystemRequirements> =>
     return await invoke('check_system_requirements');
   } catch (error) {
     console.error('Failed to check system requirements:', error);
-    return { vc_redist_installed: true };
+    return { vc_redist_installed: true, permissions: [] };
+  }
+};
+
+export const fixPermissions = async (): Promise<PathPermission[]> => {
+  try {
+    return await invoke('fix_permissions');
+  } catch (error) {
+    console.error('Failed to fix permissions:', error);
+    throw error;
   }
 };
@@ -800,3 +800,13 @@ export const getTunnelUrl = async (): Promise<string> => {
     throw error;
   }
 };
+
+// Clipboard
+export const copyText = async (text: string): Promise<void> => {
+  try {
+    await invoke('copy_text', { text });
+  } catch (error) {
+    console.error('Failed to copy to clipboard:', error);
+    throw error;
+  }
+};
@@ -3,42 +3,62 @@
 //! Usage:
 //!   orbit-cli status              Show status of all services
 //!   orbit-cli start <service>     Start a service
-//!   orbit-cli start --all         Start all installed services
+//!   orbit-cli start --all         Start all installed services (after a preflight check)
 //!   orbit-cli stop <service>      Stop a service
 //!   orbit-cli stop --all          Stop all services
 //!   orbit-cli restart <service>   Restart a service
 //!   orbit-cli restart --all       Restart all services
+//!   orbit-cli apply               Restart services with pending config changes
 //!   orbit-cli list                List available services to install
-//!   orbit-cli sites [--json]      List configured sites
+//!   orbit-cli sites               List configured sites
+//!   orbit-cli sites php <domain> <version>  Switch a site's PHP version
+//!   orbit-cli sites delete <domain>  Delete a site (--dry-run shows what would be removed)
 //!   orbit-cli info                Show environment info
 //!   orbit-cli logs list           List log files
-//!   orbit-cli logs show <name>    Show log contents
+//!   orbit-cli logs show <name>    Show log contents (-f to follow)
 //!   orbit-cli logs clear <name>   Clear a log file
+//!   orbit-cli logs rotate <name>  Rotate a log file (--keep <n> old copies, default 5)
 //!   orbit-cli db list             List databases
 //!   orbit-cli db create <name>    Create a database
 //!   orbit-cli db drop <name>      Drop a database
 //!   orbit-cli db export <name>    Export a database
 //!   orbit-cli db import <name>    Import a SQL file
-//!   orbit-cli open <target>       Open a site/tool in browser
+//!   orbit-cli db shell [name]     Open an interactive MariaDB/PostgreSQL/MongoDB client
+//!   orbit-cli open <target>       Open a site (or site/path) or tool in browser
 //!   orbit-cli php list            List PHP versions
 //!   orbit-cli php ext <version>   Manage PHP extensions
 //!   orbit-cli hosts list|add|remove  Manage hosts file
+//!   orbit-cli ssl regenerate --all   Reissue certificates of all SSL sites
+//!   orbit-cli doctor              Health report (0–100 score) and permission checks
 //!   orbit-cli composer <args>     Run composer via Orbit's PHP
-//!   orbit-cli install <service>   Install a service (nginx, php, redis, etc.)
+//!   orbit-cli install <service>   Install a service (nginx, php, redis, etc.; --locked pins to versions.lock)
+//!   orbit-cli versions [--refresh]  Installed vs latest (and locked) service versions
 //!   orbit-cli uninstall <service> Uninstall a service
+//!   orbit-cli watch [--services a,b]  Restart watched services that crash (--clear to stop)
+//!   orbit-cli completions <shell> Print a bash/zsh/fish/powershell completion script
+//!
+//! Global: --timeout <secs> caps every subprocess (defaults: config/timeouts.json)
+//!         --offline skips the network: bundled registry, downloads fail immediately
+//!         --json prints status, list, sites, sites delete --dry-run, info, doctor, versions, logs list, db list and hosts list as JSON
 
 use clap::{Parser, Subcommand};
 use colored::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::io::{BufRead, BufReader, Read as IoRead, Seek, SeekFrom, Write as IoWrite};
+use std::io::{self, Read as IoRead, Seek, SeekFrom, Write as IoWrite};
 use std::path::PathBuf;
 use std::process::Command;
 
 // ─── Path Resolution ──────────────────────────────────────────────
 
-/// Get the Orbit data directory (matches Tauri's app_local_data_dir).
-fn get_orbit_data_dir() -> PathBuf {
+/// Environment variable that relocates the Orbit data directory.
+const DATA_DIR_ENV: &str = "ORBIT_DATA_DIR";
+
+/// Settings entry (inside the default data dir) holding a user-chosen data directory.
+const DATA_DIR_POINTER: &str = "data_dir.txt";
+
+/// Default per-OS data directory (matches Tauri's app_local_data_dir).
+fn default_orbit_data_dir() -> PathBuf {
     #[cfg(target_os = "windows")]
     {
         let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_else(|_| {
@@ -62,6 +82,50 @@ fn get_orbit_data_dir() -> PathBuf {
     }
 }
 
+/// Configured data directory override and where it came from ("env" or "settings").
+fn data_dir_override() -> Option<(PathBuf, &'static str)> {
+    if let Ok(value) = std::env::var(DATA_DIR_ENV) {
+        if !value.trim().is_empty() {
+            return Some((PathBuf::from(value.trim()), "env"));
+        }
+    }
+    fs::read_to_string(default_orbit_data_dir().join(DATA_DIR_POINTER))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(|s| (PathBuf::from(s), "settings"))
+}
+
+/// Ensure a data directory is absolute, exists and is writable.
+fn validate_data_dir(dir: &std::path::Path) -> Result<(), String> {
+    if !dir.is_absolute() {
+        return Err(format!("{} is not an absolute path", dir.display()));
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    let probe = dir.join(".orbit-write-test");
+    fs::write(&probe, b"ok").map_err(|e| format!("{} is not writable: {}", dir.display(), e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Get the Orbit data directory: `ORBIT_DATA_DIR`, then the settings entry, then the
+/// per-OS default. An invalid override is reported once and ignored.
+fn get_orbit_data_dir() -> PathBuf {
+    static RESOLVED: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+    RESOLVED
+        .get_or_init(|| match data_dir_override() {
+            Some((dir, source)) => match validate_data_dir(&dir) {
+                Ok(()) => dir,
+                Err(e) => {
+                    eprintln!("Ignoring {} data dir override: {}", source, e);
+                    default_orbit_data_dir()
+                }
+            },
+            None => default_orbit_data_dir(),
+        })
+        .clone()
+}
+
 fn get_bin_dir() -> PathBuf {
     get_orbit_data_dir().join("bin")
 }
@@ -70,11 +134,42 @@ fn get_config_dir() -> PathBuf {
     get_orbit_data_dir().join("config")
 }
 
+/// Orbit's tool directories, most specific first. A copy of the one in
+/// services/terminal.rs (the app's terminal); keep the two identical.
+fn orbit_path_dirs(bin_dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    // We want the most specific binaries in front.
+    let mut paths_to_inject = vec![
+        bin_dir.join("mariadb").join("bin"),
+        bin_dir.join("mariadb"),
+        bin_dir.join("nginx"),
+        bin_dir.join("nodejs"),
+        bin_dir.join("bun"),
+        bin_dir.join("go").join("bin"),
+        bin_dir.join("deno"),
+        bin_dir.join("python"),
+        bin_dir.join("apache").join("bin"),
+        bin_dir.join("composer"),
+        bin_dir.join("phpmyadmin"),
+        bin_dir.join("tools"), // Catch-all
+    ];
+
+    // Push all installed PHP versions into the path list dynamically
+    if let Ok(entries) = std::fs::read_dir(bin_dir.join("php")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                paths_to_inject.push(path);
+            }
+        }
+    }
+
+    paths_to_inject.into_iter().filter(|p| p.exists()).collect()
+}
+
 // ─── Site Store Types (CLI-only, Tauri-free) ─────────────────────
 
 #[derive(Deserialize)]
 struct CliSiteStore {
-    #[allow(dead_code)]
     version: String,
     sites: Vec<CliSiteMetadata>,
 }
@@ -82,6 +177,8 @@ struct CliSiteStore {
 #[derive(Deserialize, Clone)]
 struct CliSiteMetadata {
     domain: String,
+    #[serde(default)]
+    aliases: Vec<String>,
     path: String,
     port: u16,
     php_version: Option<String>,
@@ -91,6 +188,9 @@ struct CliSiteMetadata {
     web_server: String,
     #[serde(default)]
     created_at: String,
+    #[allow(dead_code)]
+    #[serde(default)]
+    updated_at: String,
 }
 
 fn default_web_server() -> String {
@@ -107,8 +207,274 @@ fn read_sites_store() -> Result<CliSiteStore, String> {
     }
     let content = fs::read_to_string(&store_path)
         .map_err(|e| format!("Failed to read sites.json: {}", e))?;
-    serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse sites.json: {}", e))
+    let mut store: CliSiteStore = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse sites.json: {}", e))?;
+    // Read older layouts as the current one, as orbit-mcp's migrate_store does
+    if sites_store_layout(&store.version) < 2 {
+        store.sites.iter_mut().for_each(migrate_site_to_v2);
+    }
+    Ok(store)
+}
+
+/// Layout version of a sites.json `version` field; "1" (CLI/MCP) and "1.0"
+/// (app) are the same layout
+fn sites_store_layout(version: &str) -> u32 {
+    version.split('.').next()
+        .and_then(|major| major.trim().parse().ok())
+        .unwrap_or(1)
+}
+
+/// v2: lowercase web_server, a port for every site, updated_at set, and
+/// aliases without blanks, repeats or the domain itself
+fn migrate_site_to_v2(site: &mut CliSiteMetadata) {
+    site.web_server = site.web_server.to_lowercase();
+    if site.port == 0 {
+        site.port = if site.ssl_enabled { 443 } else { 80 };
+    }
+    if site.updated_at.is_empty() {
+        site.updated_at = site.created_at.clone();
+    }
+    let mut aliases: Vec<String> = Vec::new();
+    for alias in site.aliases.drain(..) {
+        let alias = alias.trim().to_lowercase();
+        if !alias.is_empty() && alias != site.domain && !aliases.contains(&alias) {
+            aliases.push(alias);
+        }
+    }
+    site.aliases = aliases;
+}
+
+/// How long to wait for another process to release a lock
+const LOCK_WAIT: std::time::Duration = std::time::Duration::from_secs(10);
+/// A lock file older than this was left behind by a crashed process
+const LOCK_STALE: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Advisory lock shared with the app and orbit-mcp: a lock file created
+/// exclusively next to the data it guards and removed on drop (same scheme
+/// as services/file_lock.rs).
+struct FileLock {
+    path: PathBuf,
+    /// What this holder wrote into the file: its PID and a per-process count
+    owner: String,
+}
+
+impl FileLock {
+    fn acquire(path: &std::path::Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let deadline = std::time::Instant::now() + LOCK_WAIT;
+        loop {
+            match fs::OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(mut file) => {
+                    static ACQUIRED: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+                    let owner = format!("{} {}", std::process::id(), ACQUIRED.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+                    let _ = write!(file, "{}", owner);
+                    return Ok(FileLock { path: path.to_path_buf(), owner });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > LOCK_STALE);
+                    if stale {
+                        let _ = fs::remove_file(path);
+                        continue;
+                    }
+                    if std::time::Instant::now() >= deadline {
+                        return Err(format!(
+                            "Timed out waiting for {} (another Orbit process is updating it; delete the file if none is)",
+                            path.display()
+                        ));
+                    }
+                    std::thread::sleep(std::time::Duration::from_millis(25));
+                }
+                Err(e) => return Err(format!("Failed to create lock file {}: {}", path.display(), e)),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // A holder that outlived LOCK_STALE may have had its lock taken over;
+        // only remove the file while it is still ours
+        if fs::read_to_string(&self.path).is_ok_and(|content| content == self.owner) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Hold this across a read-modify-write of sites.json
+fn lock_sites_store() -> Result<FileLock, String> {
+    FileLock::acquire(&get_config_dir().join("sites.json.lock"))
+}
+
+/// Write `content` to a temp file next to `path`, then rename it over `path`,
+/// so a crash mid-write leaves either the old file or the new one, never a
+/// truncated one. The temp file is removed if anything fails. Each call gets
+/// its own temp name, and the new file keeps the old one's permissions.
+fn write_atomic(path: &std::path::Path, content: &str) -> io::Result<()> {
+    let file_name = path.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    static WRITES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let write = WRITES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    tmp_name.push(format!(".orbit-tmp-{}-{}", std::process::id(), write));
+    let tmp = path.with_file_name(tmp_name);
+    let permissions = fs::metadata(path).ok().map(|meta| meta.permissions());
+
+    let result = fs::File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            if let Some(permissions) = permissions {
+                file.set_permissions(permissions)?;
+            }
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+// ─── Subprocess Runner (shared with mcp.rs) ───────────────────────
+
+/// Default subprocess deadlines in seconds, per operation. Any entry (plus
+/// "default") can be overridden in config/timeouts.json, e.g. `{ "composer": 1800 }`.
+const DEFAULT_TIMEOUTS: &[(&str, u64)] = &[
+    ("version", 10),
+    ("db", 120),
+    ("db_export", 1800),
+    ("db_import", 1800),
+    ("composer", 900),
+    ("scaffold", 900),
+    ("install", 1800),
+    ("default", 300),
+];
+
+/// Set from `--timeout <secs>`; applies to every operation when present.
+static TIMEOUT_OVERRIDE: std::sync::OnceLock<std::time::Duration> = std::sync::OnceLock::new();
+
+fn operation_timeout(operation: &str) -> std::time::Duration {
+    if let Some(timeout) = TIMEOUT_OVERRIDE.get() {
+        return *timeout;
+    }
+    let configured: std::collections::HashMap<String, u64> = fs::read_to_string(get_config_dir().join("timeouts.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let lookup = |key: &str| {
+        configured.get(key).copied()
+            .or_else(|| DEFAULT_TIMEOUTS.iter().find(|(k, _)| *k == key).map(|(_, v)| *v))
+    };
+    let secs = lookup(operation).or_else(|| lookup("default")).unwrap_or(300);
+    std::time::Duration::from_secs(secs.max(1))
+}
+
+/// Like `Command::output()`, but kills the child once `timeout` elapses.
+fn run_with_timeout(cmd: &mut Command, timeout: std::time::Duration) -> Result<std::process::Output, String> {
+    run_with_timeout_input(cmd, None, timeout)
+}
+
+/// `run_with_timeout` that also feeds `input` to the child's stdin.
+fn run_with_timeout_input(
+    cmd: &mut Command,
+    input: Option<Vec<u8>>,
+    timeout: std::time::Duration,
+) -> Result<std::process::Output, String> {
+    use std::process::Stdio;
+
+    cmd.stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+
+    // Feed stdin and drain both pipes on threads so a chatty child can't block on a full pipe
+    if let (Some(data), Some(mut stdin)) = (input, child.stdin.take()) {
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(&data);
+        });
+    }
+    let drain = |pipe: Option<Box<dyn IoRead + Send>>| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|p| Box::new(p) as Box<dyn IoRead + Send>));
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as Box<dyn IoRead + Send>));
+
+    let deadline = std::time::Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if std::time::Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {:?} (process killed)", timeout));
+            }
+            Ok(None) => std::thread::sleep(std::time::Duration::from_millis(50)),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    Ok(std::process::Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// A finished subprocess with its streams kept apart, so callers can branch on
+/// the exit code instead of string-matching merged output.
+#[derive(Debug, Serialize)]
+struct CommandResult {
+    stdout: String,
+    stderr: String,
+    /// `None` when the process was terminated by a signal.
+    code: Option<i32>,
+}
+
+impl CommandResult {
+    fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+
+    /// Both streams concatenated, for version probes that print to either.
+    fn combined(&self) -> String {
+        format!("{}{}", self.stdout, self.stderr)
+    }
+
+    /// The most useful error text (stderr, else stdout) plus the exit code.
+    fn error_detail(&self) -> String {
+        let message = if self.stderr.trim().is_empty() { self.stdout.trim() } else { self.stderr.trim() };
+        match self.code {
+            Some(code) => format!("{} (exit code {})", message, code),
+            None => format!("{} (terminated by signal)", message),
+        }
+    }
+}
+
+impl From<std::process::Output> for CommandResult {
+    fn from(output: std::process::Output) -> Self {
+        CommandResult {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            code: output.status.code(),
+        }
+    }
+}
+
+/// Run `cmd` under the deadline for `operation` and capture a `CommandResult`.
+fn run_command(cmd: &mut Command, operation: &str) -> Result<CommandResult, String> {
+    run_with_timeout(cmd, operation_timeout(operation)).map(CommandResult::from)
 }
 
 // ─── Service Discovery ────────────────────────────────────────────
@@ -121,7 +487,7 @@ struct ServiceInfo {
     service_type: String,
 }
 
-fn hidden_command(program: &PathBuf) -> Command {
+fn hidden_command(program: impl AsRef<std::ffi::OsStr>) -> Command {
     let mut cmd = Command::new(program);
     #[cfg(target_os = "windows")]
     {
@@ -133,15 +499,11 @@ fn hidden_command(program: &PathBuf) -> Command {
 }
 
 fn parse_version_output(exe_path: &PathBuf, args: &[&str], pattern: &str, offset: usize) -> String {
-    let output = hidden_command(exe_path)
-        .args(args)
-        .output();
+    let output = run_command(hidden_command(exe_path).args(args), "version");
 
     match output {
         Ok(out) => {
-            let stdout = String::from_utf8_lossy(&out.stdout);
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            let combined = format!("{}{}", stdout, stderr);
+            let combined = out.combined();
 
             if let Some(pos) = combined.find(pattern) {
                 let version_start = pos + offset;
@@ -160,6 +522,15 @@ fn parse_version_output(exe_path: &PathBuf, args: &[&str], pattern: &str, offset
     }
 }
 
+/// Platform-specific executable name: `nginx` → `nginx.exe` on Windows, bare elsewhere.
+fn exe_name(base: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!("{}.exe", base)
+    } else {
+        base.to_string()
+    }
+}
+
 fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     let mut services = Vec::new();
 
@@ -168,7 +539,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Nginx
-    let nginx_exe = bin_path.join("nginx").join("nginx.exe");
+    let nginx_exe = bin_path.join("nginx").join(exe_name("nginx"));
     if nginx_exe.exists() {
         let version = parse_version_output(&nginx_exe, &["-v"], "nginx/", 6);
         services.push(ServiceInfo {
@@ -181,10 +552,10 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
 
     // MariaDB
     let mariadb_paths = [
-        bin_path.join("mariadb").join("mariadbd.exe"),
-        bin_path.join("mariadb").join("bin").join("mariadbd.exe"),
-        bin_path.join("mariadb").join("mysqld.exe"),
-        bin_path.join("mariadb").join("bin").join("mysqld.exe"),
+        bin_path.join("mariadb").join(exe_name("mariadbd")),
+        bin_path.join("mariadb").join("bin").join(exe_name("mariadbd")),
+        bin_path.join("mariadb").join(exe_name("mysqld")),
+        bin_path.join("mariadb").join("bin").join(exe_name("mysqld")),
     ];
     for exe_path in &mariadb_paths {
         if exe_path.exists() {
@@ -208,7 +579,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
                     if ft.is_dir() {
                         let version_dir = entry.file_name();
                         let version_str = version_dir.to_string_lossy().to_string();
-                        let exe_path = entry.path().join("php-cgi.exe");
+                        let exe_path = entry.path().join(exe_name("php-cgi"));
                         if exe_path.exists() {
                             let version = parse_version_output(&exe_path, &["-v"], "PHP ", 4);
                             services.push(ServiceInfo {
@@ -225,7 +596,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Redis
-    let redis_exe = bin_path.join("redis").join("redis-server.exe");
+    let redis_exe = bin_path.join("redis").join(exe_name("redis-server"));
     if redis_exe.exists() {
         let version = parse_version_output(&redis_exe, &["--version"], "v=", 2);
         services.push(ServiceInfo {
@@ -238,8 +609,8 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
 
     // Apache
     let apache_paths = [
-        bin_path.join("apache").join("bin").join("httpd.exe"),
-        bin_path.join("apache").join("httpd.exe"),
+        bin_path.join("apache").join("bin").join(exe_name("httpd")),
+        bin_path.join("apache").join(exe_name("httpd")),
     ];
     for exe_path in &apache_paths {
         if exe_path.exists() {
@@ -255,14 +626,12 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Node.js
-    let node_exe = bin_path.join("nodejs").join("node.exe");
+    let node_exe = bin_path.join("nodejs").join(exe_name("node"));
     if node_exe.exists() {
-        let output = hidden_command(&node_exe)
-            .arg("--version")
-            .output();
+        let output = run_command(hidden_command(&node_exe).arg("--version"), "version");
         let version = match output {
             Ok(out) => {
-                let v = String::from_utf8_lossy(&out.stdout).trim().trim_start_matches('v').to_string();
+                let v = out.stdout.trim().trim_start_matches('v').to_string();
                 if v.is_empty() { "unknown".to_string() } else { v }
             }
             Err(_) => "unknown".to_string(),
@@ -276,7 +645,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Mailpit
-    let mailpit_exe = bin_path.join("mailpit").join("mailpit.exe");
+    let mailpit_exe = bin_path.join("mailpit").join(exe_name("mailpit"));
     if mailpit_exe.exists() {
         services.push(ServiceInfo {
             name: "mailpit".to_string(),
@@ -287,7 +656,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Meilisearch
-    let meilisearch_exe = bin_path.join("meilisearch").join("meilisearch.exe");
+    let meilisearch_exe = bin_path.join("meilisearch").join(exe_name("meilisearch"));
     if meilisearch_exe.exists() {
         services.push(ServiceInfo {
             name: "meilisearch".to_string(),
@@ -310,8 +679,8 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
 
     // PostgreSQL (check both flattened and nested structures)
     let pg_paths = [
-        bin_path.join("postgresql").join("bin").join("postgres.exe"),
-        bin_path.join("postgresql").join("pgsql").join("bin").join("postgres.exe"),
+        bin_path.join("postgresql").join("bin").join(exe_name("postgres")),
+        bin_path.join("postgresql").join("pgsql").join("bin").join(exe_name("postgres")),
     ];
     for pg_exe in &pg_paths {
         if pg_exe.exists() {
@@ -327,7 +696,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // MongoDB
-    let mongo_exe = bin_path.join("mongodb").join("bin").join("mongod.exe");
+    let mongo_exe = bin_path.join("mongodb").join("bin").join(exe_name("mongod"));
     if mongo_exe.exists() {
         services.push(ServiceInfo {
             name: "mongodb".to_string(),
@@ -339,8 +708,8 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
 
     // Go
     let go_paths = [
-        bin_path.join("go").join("bin").join("go.exe"),
-        bin_path.join("go").join("go.exe"),
+        bin_path.join("go").join("bin").join(exe_name("go")),
+        bin_path.join("go").join(exe_name("go")),
     ];
     for go_exe in &go_paths {
         if go_exe.exists() {
@@ -356,7 +725,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Deno
-    let deno_exe = bin_path.join("deno").join("deno.exe");
+    let deno_exe = bin_path.join("deno").join(exe_name("deno"));
     if deno_exe.exists() {
         let version = parse_version_output(&deno_exe, &["--version"], "deno ", 5);
         services.push(ServiceInfo {
@@ -368,7 +737,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Bun
-    let bun_exe = bin_path.join("bun").join("bun.exe");
+    let bun_exe = bin_path.join("bun").join(exe_name("bun"));
     if bun_exe.exists() {
         let version = parse_version_output(&bun_exe, &["--version"], "", 0);
         services.push(ServiceInfo {
@@ -380,7 +749,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Python
-    let python_exe = bin_path.join("python").join("python.exe");
+    let python_exe = bin_path.join("python").join(exe_name("python"));
     if python_exe.exists() {
         let version = parse_version_output(&python_exe, &["--version"], "Python ", 7);
         services.push(ServiceInfo {
@@ -393,8 +762,8 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
 
     // Rust
     let rust_paths = [
-        bin_path.join("rust").join("rustup-init.exe"),
-        bin_path.join("misc").join("rust").join("rustup-init.exe"),
+        bin_path.join("rust").join(exe_name("rustup-init")),
+        bin_path.join("misc").join("rust").join(exe_name("rustup-init")),
     ];
     for rust_exe in &rust_paths {
         if rust_exe.exists() {
@@ -408,35 +777,121 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
         }
     }
 
+    // SQLite (client only, no server process)
+    let sqlite_exe = bin_path.join("sqlite").join(exe_name("sqlite3"));
+    if sqlite_exe.exists() {
+        // `3.45.1 2024-01-30 16:01:20 ...`
+        let version = parse_version_output(&sqlite_exe, &["--version"], "", 0)
+            .split_whitespace()
+            .next()
+            .unwrap_or("unknown")
+            .to_string();
+        services.push(ServiceInfo {
+            name: "sqlite".to_string(),
+            version,
+            path: sqlite_exe.to_string_lossy().to_string(),
+            service_type: "sqlite".to_string(),
+        });
+    }
+
+    services
+}
+
+/// Last scan: when it ran, which bin dir, and what it found.
+type ServicesScan = (std::time::Instant, PathBuf, Vec<ServiceInfo>);
+
+static SERVICES_CACHE: std::sync::OnceLock<std::sync::Mutex<Option<ServicesScan>>> = std::sync::OnceLock::new();
+
+/// `scan_services` memoized for a few seconds. Every scan spawns a version probe
+/// per binary, so read-only paths that scan repeatedly share one result.
+/// `cmd_install`/`cmd_uninstall` keep calling `scan_services` directly.
+fn scan_services_cached(bin_path: &PathBuf) -> Vec<ServiceInfo> {
+    const TTL: std::time::Duration = std::time::Duration::from_secs(5);
+    let mut cache = SERVICES_CACHE
+        .get_or_init(|| std::sync::Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some((at, path, services)) = cache.as_ref() {
+        if at.elapsed() < TTL && path == bin_path {
+            return services.clone();
+        }
+    }
+    let services = scan_services(bin_path);
+    *cache = Some((std::time::Instant::now(), bin_path.clone(), services.clone()));
     services
 }
 
+/// Installed services that run a server process. SQLite is only a client, so
+/// status and health views skip it rather than report it stopped.
+fn scan_server_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
+    scan_services_cached(bin_path).into_iter().filter(|svc| svc.service_type != "sqlite").collect()
+}
+
 // ─── Process Management ───────────────────────────────────────────
 
 fn is_port_in_use(port: u16) -> bool {
-    std::net::TcpListener::bind(format!("127.0.0.1:{}", port)).is_err()
+    // A listener accepting on loopback answers within the timeout; one bound only
+    // to another interface refuses (or a busy one times out), so any failure
+    // falls back to the bind check.
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    std::net::TcpStream::connect_timeout(&addr, std::time::Duration::from_millis(250)).is_ok()
+        || std::net::TcpListener::bind(format!("127.0.0.1:{}", port)).is_err()
         || std::net::TcpListener::bind(format!("0.0.0.0:{}", port)).is_err()
 }
 
+/// Port used when a PHP version can't be parsed (that of the default PHP 8.4)
+const DEFAULT_PHP_PORT: u16 = 9004;
+
+/// FastCGI port for a PHP version, as the GUI's PHP registry assigns it.
+/// PHP 8 uses 9000 + minor (8.4 → 9004, 8.14 → 9014); other majors get their
+/// own decade (7.4 → 9074). `None` unless the version starts with major.minor.
+fn php_port(version: &str) -> Option<u16> {
+    let mut parts = version.trim().split('.');
+    let major: u16 = parts.next()?.parse().ok()?;
+    let minor: u16 = parts.next()?.parse().ok()?;
+    if minor > 99 {
+        return None;
+    }
+    Some(if major == 8 { 9000 + minor } else { 9000 + (major % 10) * 10 + minor })
+}
+
+/// Port Orbit's installer gives Apache, clear of nginx on 80
+const APACHE_DEFAULT_PORT: u16 = 8082;
+
+/// Port Apache listens on: the first `Listen` in its httpd.conf, else
+/// `APACHE_DEFAULT_PORT`
+fn apache_listen_port(bin_dir: &std::path::Path) -> u16 {
+    fs::read_to_string(bin_dir.join("apache").join("conf").join("httpd.conf"))
+        .ok()
+        .and_then(|conf| conf.lines().find_map(|line| {
+            // `Listen 8082`, `Listen 127.0.0.1:8082` or `Listen [::1]:8082 http`
+            let address = line.trim().strip_prefix("Listen")?.split_whitespace().next()?;
+            address.rsplit(':').next()?.parse().ok()
+        }))
+        .unwrap_or(APACHE_DEFAULT_PORT)
+}
+
+/// Port a service binds, reading Apache's from the httpd.conf under `bin_dir`
+fn service_port_in(name: &str, bin_dir: &std::path::Path) -> Option<u16> {
+    if name.contains("apache") {
+        Some(apache_listen_port(bin_dir))
+    } else {
+        get_service_port(name)
+    }
+}
+
 fn get_service_port(name: &str) -> Option<u16> {
     if name.contains("nginx") {
         Some(80)
     } else if name.contains("apache") {
-        Some(80)
+        Some(apache_listen_port(&get_bin_dir()))
     } else if name.contains("mariadb") {
         Some(3306)
     } else if name.contains("redis") {
         Some(6379)
     } else if name.contains("php") {
-        // php-8.4 → minor=4 → 9000+4=9004 (matches GUI logic)
-        let version_str = name.strip_prefix("php-").unwrap_or("8.4");
-        let parts: Vec<&str> = version_str.split('.').collect();
-        if parts.len() >= 2 {
-            let minor: u16 = parts[1].parse().unwrap_or(4);
-            Some(9000 + minor)
-        } else {
-            Some(9004)
-        }
+        let version = name.strip_prefix("php-").unwrap_or_default();
+        Some(php_port(version).unwrap_or(DEFAULT_PHP_PORT))
     } else if name.contains("mailpit") {
         Some(8025)
     } else if name.contains("meilisearch") {
@@ -454,7 +909,7 @@ fn get_process_image_names(name: &str) -> Vec<&'static str> {
     if name.contains("nginx") {
         vec!["nginx.exe"]
     } else if name.contains("php") {
-        vec!["php-cgi.exe"]
+        vec!["php-cgi.exe", "php-fpm.exe"]
     } else if name.contains("mariadb") {
         vec!["mariadbd.exe", "mysqld.exe"]
     } else if name.contains("redis") {
@@ -482,10 +937,140 @@ fn is_service_running(name: &str) -> bool {
     }
 }
 
+/// Most workers a PHP pool may ask for
+const PHP_POOL_MAX_CHILDREN: u32 = 64;
+
+/// How a PHP version is served, from config/php-pools.json: keyed by version,
+/// with "default" for the rest, e.g.
+/// `{ "default": { "children": 8 }, "8.4": { "mode": "fpm" } }`.
+/// Same format as the app's services/php_pool.rs.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+struct PhpPool {
+    /// "cgi": php-cgi forking `children` workers on the one port (Unix only;
+    /// php-cgi on Windows can't fork and serves one request at a time).
+    /// "fpm": php-fpm with a static pool, when the version ships it; else cgi.
+    mode: String,
+    children: u32,
+    /// Requests a worker serves before it is replaced
+    max_requests: u32,
+}
+
+impl Default for PhpPool {
+    fn default() -> Self {
+        PhpPool { mode: "cgi".to_string(), children: 4, max_requests: 1000 }
+    }
+}
+
+fn php_pool(config_dir: &std::path::Path, version: &str) -> PhpPool {
+    let pools: serde_json::Map<String, serde_json::Value> = fs::read_to_string(config_dir.join("php-pools.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let mut pool: PhpPool = pools.get(version)
+        .or_else(|| pools.get("default"))
+        .and_then(|entry| serde_json::from_value(entry.clone()).ok())
+        .unwrap_or_default();
+    pool.children = pool.children.clamp(1, PHP_POOL_MAX_CHILDREN);
+    pool.max_requests = pool.max_requests.max(1);
+    pool
+}
+
+/// php-fpm shipped with the PHP whose php-cgi is `php_cgi`: alongside it, or
+/// in the sbin/ of a bin/ + sbin/ layout
+fn find_php_fpm(php_cgi: &std::path::Path) -> Option<PathBuf> {
+    let dir = php_cgi.parent()?;
+    let name = exe_name("php-fpm");
+    let mut candidates = vec![dir.join(&name), dir.join("sbin").join(&name)];
+    if let Some(root) = dir.parent() {
+        candidates.push(root.join("sbin").join(&name));
+    }
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+fn php_fpm_config(pool: &PhpPool, port: u16, php_dir: &std::path::Path) -> String {
+    format!(
+        "; Generated by Orbit from config/php-pools.json; edits are overwritten\n\
+         [global]\n\
+         pid = {}\n\
+         error_log = {}\n\
+         daemonize = no\n\
+         \n\
+         [orbit]\n\
+         listen = 127.0.0.1:{}\n\
+         pm = static\n\
+         pm.max_children = {}\n\
+         pm.max_requests = {}\n\
+         catch_workers_output = yes\n\
+         clear_env = no\n",
+        php_dir.join("orbit-fpm.pid").display(),
+        php_dir.join("logs").join("php-fpm.log").display(),
+        port,
+        pool.children,
+        pool.max_requests,
+    )
+}
+
+/// How to start PHP for a pool
+struct PhpLaunch {
+    exe: PathBuf,
+    args: Vec<String>,
+    env: Vec<(&'static str, String)>,
+    /// Set when fpm mode had to fall back to php-cgi
+    warning: Option<String>,
+}
+
+/// Environment php-cgi reads to fork its workers. php-cgi can't fork on
+/// Windows, so there it only gets the request limit.
+fn php_cgi_env(pool: &PhpPool) -> Vec<(&'static str, String)> {
+    let mut env = vec![("PHP_FCGI_MAX_REQUESTS", pool.max_requests.to_string())];
+    if cfg!(not(target_os = "windows")) {
+        env.push(("PHP_FCGI_CHILDREN", pool.children.to_string()));
+    }
+    env
+}
+
+/// How to serve the PHP whose php-cgi is `php_cgi` on `port`. fpm mode
+/// writes orbit-fpm.conf next to php-cgi, which sizes the pool instead of
+/// the PHP_FCGI_* environment.
+fn php_launch(pool: &PhpPool, php_cgi: &std::path::Path, port: u16) -> PhpLaunch {
+    let cgi = |warning: Option<String>| PhpLaunch {
+        exe: php_cgi.to_path_buf(),
+        args: vec!["-b".to_string(), format!("127.0.0.1:{}", port)],
+        env: php_cgi_env(pool),
+        warning,
+    };
+    if pool.mode != "fpm" {
+        return cgi(None);
+    }
+    let (Some(fpm), Some(php_dir)) = (find_php_fpm(php_cgi), php_cgi.parent()) else {
+        return cgi(Some(format!("php-fpm not found next to {}; using php-cgi", php_cgi.display())));
+    };
+
+    let config_path = php_dir.join("orbit-fpm.conf");
+    let _ = fs::create_dir_all(php_dir.join("logs"));
+    if let Err(e) = fs::write(&config_path, php_fpm_config(pool, port, php_dir)) {
+        return cgi(Some(format!("Failed to write {}: {}; using php-cgi", config_path.display(), e)));
+    }
+    PhpLaunch {
+        exe: fpm,
+        args: vec![
+            "--nodaemonize".to_string(),
+            "--fpm-config".to_string(),
+            config_path.display().to_string(),
+            "-c".to_string(),
+            php_dir.display().to_string(),
+        ],
+        env: vec![],
+        warning: None,
+    }
+}
+
 fn start_service_process(service: &ServiceInfo) -> Result<u32, String> {
     let exe_path = PathBuf::from(&service.path);
     let bin_dir = get_bin_dir();
 
+    let mut env: Vec<(&'static str, String)> = Vec::new();
     let (exe, args) = match service.service_type.as_str() {
         "nginx" => {
             let nginx_dir = bin_dir.join("nginx");
@@ -494,7 +1079,13 @@ fn start_service_process(service: &ServiceInfo) -> Result<u32, String> {
         }
         "php" => {
             let port = get_service_port(&service.name).unwrap_or(9084);
-            (exe_path.clone(), vec!["-b".to_string(), format!("127.0.0.1:{}", port)])
+            let version = service.name.strip_prefix("php-").unwrap_or(&service.version);
+            let launch = php_launch(&php_pool(&get_config_dir(), version), &exe_path, port);
+            if let Some(warning) = &launch.warning {
+                eprintln!("  {} {}", "!".yellow(), warning);
+            }
+            env = launch.env;
+            (launch.exe, launch.args)
         }
         "mariadb" => {
             let data_dir = bin_dir.join("data").join("mariadb");
@@ -558,6 +1149,7 @@ fn start_service_process(service: &ServiceInfo) -> Result<u32, String> {
     for arg in &args {
         cmd.arg(arg);
     }
+    cmd.envs(env);
 
     // Set working directory to exe's parent (needed for Redis relative config path)
     if let Some(parent) = exe.parent() {
@@ -565,89 +1157,607 @@ fn start_service_process(service: &ServiceInfo) -> Result<u32, String> {
     }
 
     match cmd.spawn() {
-        Ok(child) => Ok(child.id()),
+        Ok(child) => {
+            record_session_entry(&service.name, child.id());
+            Ok(child.id())
+        }
         Err(e) => Err(format!("Failed to start {}: {}", service.name, e)),
     }
 }
 
-fn stop_service_process(name: &str) -> Result<(), String> {
+/// What `stop_service_process` did. `method` is "pid" (the process Orbit
+/// recorded in config/session.json), "graceful" (MariaDB shutdown), "image"
+/// (matched by executable name) or "already_stopped".
+#[derive(Serialize)]
+struct StopOutcome {
+    stopped: bool,
+    was_running: bool,
+    method: &'static str,
+}
+
+impl StopOutcome {
+    fn by(method: &'static str) -> Self {
+        StopOutcome { stopped: true, was_running: true, method }
+    }
+}
+
+fn read_session_entries() -> Vec<serde_json::Value> {
+    fs::read_to_string(get_config_dir().join("session.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_session_entries(entries: &[serde_json::Value]) {
+    let path = get_config_dir().join("session.json");
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let _ = fs::write(path, serde_json::to_string_pretty(entries).unwrap());
+}
+
+/// The service's entry in config/session.json, whether or not it's still alive.
+fn session_entry(name: &str) -> Option<serde_json::Value> {
+    read_session_entries().into_iter().find(|e| e["service"].as_str() == Some(name))
+}
+
+/// Record a started service in config/session.json, in the format the GUI
+/// writes, so either side can recognise (or miss) the process later.
+fn record_session_entry(name: &str, pid: u32) {
+    let mut sys = sysinfo::System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+    let Some(process) = sys.process(sys_pid) else { return };
+
+    let mut entries = read_session_entries();
+    entries.retain(|e| e["service"].as_str() != Some(name));
+    entries.push(serde_json::json!({
+        "service": name,
+        "pid": pid,
+        "exe": process.exe().map(|p| p.to_string_lossy().to_string()).unwrap_or_default(),
+        "started_at": process.start_time()
+    }));
+    write_session_entries(&entries);
+}
+
+fn forget_session_entry(name: &str) {
+    let mut entries = read_session_entries();
+    let before = entries.len();
+    entries.retain(|e| e["service"].as_str() != Some(name));
+    if entries.len() != before {
+        write_session_entries(&entries);
+    }
+}
+
+/// PID recorded for the service in the GUI's session file, if that process is
+/// still alive with the same executable and start time (i.e. not a reused PID).
+fn tracked_service_pid(name: &str) -> Option<u32> {
+    let entry = session_entry(name)?;
+    let pid = entry["pid"].as_u64()? as u32;
+
+    let mut sys = sysinfo::System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+    let process = sys.process(sys_pid)?;
+
+    let recorded_exe = entry["exe"].as_str()?;
+    let same_exe = process.exe().is_some_and(|exe| exe == std::path::Path::new(recorded_exe));
+    let same_start = entry["started_at"].as_u64()
+        .is_some_and(|t| process.start_time().abs_diff(t) <= 1);
+    (same_exe && same_start).then_some(pid)
+}
+
+/// Whether `pid` is a live process. Exited children this process never waited
+/// on linger as zombies, which don't count.
+fn pid_alive(pid: u32) -> bool {
+    let mut sys = sysinfo::System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+    sys.process(sys_pid).is_some_and(|p| p.status() != sysinfo::ProcessStatus::Zombie)
+}
+
+/// PIDs of the direct children of `pid`
+#[cfg(not(target_os = "windows"))]
+fn child_pids(pid: u32) -> Vec<u32> {
+    let parent = sysinfo::Pid::from_u32(pid);
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    sys.processes().iter()
+        .filter(|(_, process)| process.parent() == Some(parent))
+        .map(|(child, _)| child.as_u32())
+        .collect()
+}
+
+/// Terminate one process tree and wait for it to exit.
+fn kill_pid(pid: u32) -> bool {
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    let alive = || {
+        let mut sys = sysinfo::System::new();
+        sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+        sys.process(sys_pid).is_some()
+    };
+
+    #[cfg(target_os = "windows")]
+    let _ = hidden_command("taskkill").args(["/F", "/T", "/PID", &pid.to_string()]).output();
+    // php-cgi and php-fpm workers are children of the started process;
+    // signal them too in case the parent dies before reaping them
+    #[cfg(not(target_os = "windows"))]
+    let children: Vec<String> = child_pids(pid).iter().map(|c| c.to_string()).collect();
+    #[cfg(not(target_os = "windows"))]
+    let _ = Command::new("kill").args(["-TERM", &pid.to_string()]).args(&children).output();
+
+    for _ in 0..25 {
+        if !alive() {
+            return true;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = Command::new("kill").args(["-KILL", &pid.to_string()]).args(&children).output();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    !alive()
+}
+
+fn stop_service_process(name: &str) -> Result<StopOutcome, String> {
+    let outcome = terminate_service(name)?;
+    // Stopped on purpose: don't report it as crashed later
+    forget_session_entry(name);
+    Ok(outcome)
+}
+
+fn terminate_service(name: &str) -> Result<StopOutcome, String> {
     let image_names = get_process_image_names(name);
 
     if image_names.is_empty() {
         return Err(format!("Don't know how to stop: {}", name));
     }
 
-    let mut any_killed = false;
-    for process_name in &image_names {
-        let mut cmd = Command::new("taskkill");
-        cmd.args(["/F", "/IM", process_name]);
+    let tracked_pid = tracked_service_pid(name);
+    if tracked_pid.is_none() && !is_service_running(name) {
+        return Ok(StopOutcome { stopped: false, was_running: false, method: "already_stopped" });
+    }
 
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            cmd.creation_flags(CREATE_NO_WINDOW);
-        }
+    // Let InnoDB flush and close cleanly before resorting to a kill
+    if name == "mariadb" && is_service_running(name) && stop_mariadb_graceful(&get_bin_dir()) {
+        return Ok(StopOutcome::by("graceful"));
+    }
 
-        if let Ok(output) = cmd.output() {
-            if output.status.success() {
-                any_killed = true;
-            }
+    // Prefer the exact process Orbit started; killing by image name could hit
+    // a foreign process using the same executable
+    if let Some(pid) = tracked_pid {
+        if kill_pid(pid) {
+            return Ok(StopOutcome::by("pid"));
         }
     }
 
-    if any_killed {
-        Ok(())
-    } else {
-        if let Some(port) = get_service_port(name) {
-            if !is_port_in_use(port) {
-                return Ok(());
-            }
+    if kill_service_processes(name, &image_names) {
+        return Ok(StopOutcome::by("image"));
+    }
+    if let Some(port) = get_service_port(name) {
+        if !is_port_in_use(port) {
+            return Ok(StopOutcome::by("image"));
         }
-        Err(format!("Could not stop {}", name))
     }
+    Err(format!("Could not stop {}", name))
 }
 
-// ─── Helper: MariaDB client discovery ─────────────────────────────
+/// How long `stop_mariadb_graceful` waits for a clean shutdown before force-killing.
+const MARIADB_SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
-fn find_mariadb_client(bin_dir: &PathBuf) -> Result<PathBuf, String> {
-    let mariadb_root = bin_dir.join("mariadb");
-    let paths = [
-        mariadb_root.join("mariadb.exe"),
-        mariadb_root.join("mysql.exe"),
-        mariadb_root.join("bin").join("mariadb.exe"),
-        mariadb_root.join("bin").join("mysql.exe"),
-    ];
-    for path in paths {
-        if path.exists() {
-            return Ok(path);
+/// Ask MariaDB to shut down via `mariadb-admin shutdown` and wait for port 3306
+/// to close. Returns false when the caller should fall back to killing it.
+fn stop_mariadb_graceful(bin_dir: &std::path::Path) -> bool {
+    let admin = match find_mariadb_admin(bin_dir) {
+        Ok(admin) => admin,
+        Err(e) => {
+            println!("  {}", format!("{}; force-stopping MariaDB", e).dimmed());
+            return false;
+        }
+    };
+
+    let deadline = std::time::Instant::now() + MARIADB_SHUTDOWN_TIMEOUT;
+    let result = run_with_timeout(
+        hidden_command(&admin)
+            .arg("--host=127.0.0.1").arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg("shutdown"),
+        MARIADB_SHUTDOWN_TIMEOUT,
+    ).map(CommandResult::from);
+    match result {
+        Ok(out) if out.success() => {}
+        Ok(out) => {
+            println!("  {}", format!("mariadb-admin shutdown failed: {}; force-stopping MariaDB", out.error_detail()).dimmed());
+            return false;
+        }
+        Err(e) => {
+            println!("  {}", format!("mariadb-admin shutdown failed: {}; force-stopping MariaDB", e).dimmed());
+            return false;
         }
     }
-    Err("MariaDB client not found (mysql.exe / mariadb.exe)".to_string())
-}
 
-fn find_mariadb_dump(bin_dir: &PathBuf) -> Result<PathBuf, String> {
-    let mariadb_root = bin_dir.join("mariadb");
-    let paths = [
-        mariadb_root.join("mariadb-dump.exe"),
-        mariadb_root.join("mysqldump.exe"),
-        mariadb_root.join("bin").join("mariadb-dump.exe"),
-        mariadb_root.join("bin").join("mysqldump.exe"),
-    ];
-    for path in paths {
-        if path.exists() {
-            return Ok(path);
+    // mariadb-admin returns once shutdown starts; wait for the server to exit
+    while std::time::Instant::now() < deadline {
+        if !is_port_in_use(3306) {
+            println!("  {}", "MariaDB shut down cleanly via mariadb-admin".dimmed());
+            return true;
         }
+        std::thread::sleep(std::time::Duration::from_millis(200));
     }
-    Err("MariaDB dump not found (mysqldump.exe / mariadb-dump.exe)".to_string())
+    println!("  {}", format!("MariaDB still running after {:?}; force-stopping", MARIADB_SHUTDOWN_TIMEOUT).dimmed());
+    false
 }
 
-// ─── Helper: Log file discovery ───────────────────────────────────
+#[cfg(target_os = "windows")]
+fn kill_service_processes(_name: &str, image_names: &[&str]) -> bool {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
 
-struct LogFile {
-    name: String,
-    path: PathBuf,
-    size: u64,
+    let mut any_killed = false;
+    for process_name in image_names {
+        let mut cmd = Command::new("taskkill");
+        cmd.args(["/F", "/IM", process_name]);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        if let Ok(output) = cmd.output() {
+            if output.status.success() {
+                any_killed = true;
+            }
+        }
+    }
+    any_killed
+}
+
+/// Find the PIDs listening on the service's port (lsof), falling back to
+/// Orbit-installed binaries by command line (pgrep), then SIGTERM them and
+/// SIGKILL whatever is still alive after a grace period.
+#[cfg(not(target_os = "windows"))]
+fn kill_service_processes(name: &str, image_names: &[&str]) -> bool {
+    fn parse_pids(output: std::io::Result<std::process::Output>) -> Vec<String> {
+        output
+            .map(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .split_whitespace()
+                    .filter(|p| p.chars().all(|c| c.is_ascii_digit()))
+                    .map(|p| p.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    let mut pids = Vec::new();
+    if let Some(port) = get_service_port(name) {
+        pids = parse_pids(
+            Command::new("lsof")
+                .args(["-ti", &format!("tcp:{}", port), "-sTCP:LISTEN"])
+                .output(),
+        );
+    }
+    if pids.is_empty() {
+        let bin_dir = get_bin_dir();
+        for image in image_names {
+            let pattern = format!("{}.*/{}", bin_dir.display(), image.trim_end_matches(".exe"));
+            pids.extend(parse_pids(Command::new("pgrep").args(["-f", &pattern]).output()));
+        }
+    }
+    pids.sort();
+    pids.dedup();
+    if pids.is_empty() {
+        return false;
+    }
+
+    let _ = Command::new("kill").arg("-TERM").args(&pids).output();
+
+    let alive = |pid: &String| {
+        Command::new("kill")
+            .args(["-0", pid])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    };
+    for _ in 0..25 {
+        if !pids.iter().any(alive) {
+            return true;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+
+    let remaining: Vec<&String> = pids.iter().filter(|p| alive(p)).collect();
+    if !remaining.is_empty() {
+        let _ = Command::new("kill").arg("-KILL").args(&remaining).output();
+    }
+    true
+}
+
+// ─── Pending Restarts (shared with mcp.rs) ────────────────────────
+
+#[derive(Deserialize, Serialize)]
+struct PendingRestart {
+    service: String,
+    reason: String,
+    since: String,
+}
+
+fn pending_restarts_path() -> PathBuf {
+    get_config_dir().join("pending_restarts.json")
+}
+
+fn read_pending_restarts() -> Vec<PendingRestart> {
+    fs::read_to_string(pending_restarts_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn clear_pending_restart(service: &str) {
+    let mut entries = read_pending_restarts();
+    let before = entries.len();
+    let prefix = format!("{}-", service);
+    entries.retain(|e| e.service != service && !e.service.starts_with(&prefix));
+    if entries.len() != before {
+        if let Ok(json) = serde_json::to_string_pretty(&entries) {
+            fs::write(pending_restarts_path(), json).ok();
+        }
+    }
+}
+
+/// Data stores first, then PHP, then the web servers in front of it.
+fn restart_priority(service: &str) -> u8 {
+    if service.starts_with("php") {
+        1
+    } else if service == "nginx" || service == "apache" {
+        2
+    } else {
+        0
+    }
+}
+
+fn nginx_config_test(bin_dir: &std::path::Path) -> Result<(), String> {
+    let nginx = bin_dir.join("nginx").join(exe_name("nginx"));
+    let nginx_dir = bin_dir.join("nginx");
+    let nginx_conf = nginx_dir.join("conf").join("nginx.conf");
+
+    let test = hidden_command(&nginx)
+        .current_dir(&nginx_dir)
+        .args(["-t", "-c"])
+        .arg(&nginx_conf)
+        .output()
+        .map_err(|e| format!("Failed to test nginx config: {}", e))?;
+    if !test.status.success() {
+        let stderr = String::from_utf8_lossy(&test.stderr);
+        return Err(format!("Nginx config test failed: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+fn nginx_test_and_reload(bin_dir: &std::path::Path) -> Result<(), String> {
+    nginx_config_test(bin_dir)?;
+    let nginx = bin_dir.join("nginx").join(exe_name("nginx"));
+    let nginx_dir = bin_dir.join("nginx");
+
+    let reload = hidden_command(&nginx)
+        .current_dir(&nginx_dir)
+        .args(["-s", "reload"])
+        .output()
+        .map_err(|e| format!("Failed to reload nginx: {}", e))?;
+    if !reload.status.success() {
+        let stderr = String::from_utf8_lossy(&reload.stderr);
+        return Err(format!("Nginx reload failed: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+/// PHP versions installed under `bin/php/<version>`, sorted.
+fn installed_php_versions(bin_dir: &std::path::Path) -> Vec<String> {
+    let mut versions: Vec<String> = fs::read_dir(bin_dir.join("php"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| name.starts_with(|c: char| c.is_ascii_digit()))
+                .collect()
+        })
+        .unwrap_or_default();
+    versions.sort();
+    versions
+}
+
+/// Point every `fastcgi_pass 127.0.0.1:<port>;` line at `port`, leaving the rest
+/// of the config untouched. Returns `None` if the config has no such line.
+fn replace_fastcgi_port(config: &str, port: u16) -> Option<String> {
+    let mut found = false;
+    let mut out = String::with_capacity(config.len());
+    for line in config.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("fastcgi_pass") && trimmed.contains("127.0.0.1:") {
+            let indent = &line[..line.len() - trimmed.len()];
+            let eol = if line.ends_with("\r\n") { "\r\n" } else if line.ends_with('\n') { "\n" } else { "" };
+            out.push_str(&format!("{}fastcgi_pass 127.0.0.1:{};{}", indent, port, eol));
+            found = true;
+        } else {
+            out.push_str(line);
+        }
+    }
+    found.then_some(out)
+}
+
+fn find_apache_exe(bin_dir: &std::path::Path) -> Result<PathBuf, String> {
+    let paths = [
+        bin_dir.join("apache").join("bin").join(exe_name("httpd")),
+        bin_dir.join("apache").join(exe_name("httpd")),
+    ];
+    paths.into_iter().find(|p| p.exists()).ok_or_else(|| "Apache not found".to_string())
+}
+
+/// `httpd -t`; Apache prints "Syntax OK" on stderr.
+fn apache_config_test(bin_dir: &std::path::Path) -> Result<(), String> {
+    let httpd = find_apache_exe(bin_dir)?;
+    let output = hidden_command(&httpd)
+        .current_dir(bin_dir.join("apache"))
+        .arg("-t")
+        .output()
+        .map_err(|e| format!("Failed to test Apache config: {}", e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() || stderr.contains("Syntax OK") {
+        Ok(())
+    } else {
+        Err(format!("Apache config test failed: {}", stderr.trim()))
+    }
+}
+
+// ─── Preflight (shared with mcp.rs) ───────────────────────────────
+
+/// Problems that would stop `svc` from starting, found without starting it:
+/// missing binary, port held by another process, failing config test or an
+/// uninitialized data directory. Empty when the service looks ready.
+fn preflight_check(svc: &ServiceInfo, bin_dir: &std::path::Path) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if !std::path::Path::new(&svc.path).exists() {
+        problems.push(format!("executable not found: {}", svc.path));
+    }
+
+    if let Some(port) = service_port_in(&svc.name, bin_dir) {
+        if is_port_in_use(port) {
+            problems.push(format!("port {} is already in use by another process", port));
+        }
+    }
+
+    let config_test = match svc.service_type.as_str() {
+        "nginx" => nginx_config_test(bin_dir),
+        "apache" => apache_config_test(bin_dir),
+        _ => Ok(()),
+    };
+    if let Err(e) = config_test {
+        problems.push(e);
+    }
+
+    let data_dir = bin_dir.join("data");
+    match svc.service_type.as_str() {
+        "mariadb" if !data_dir.join("mariadb").join("mysql").is_dir() => {
+            problems.push(format!("MariaDB data directory is not initialized: {}", data_dir.join("mariadb").display()));
+        }
+        "postgresql" if !data_dir.join("postgres").join("PG_VERSION").exists() => {
+            problems.push(format!("PostgreSQL data directory is not initialized (run initdb): {}", data_dir.join("postgres").display()));
+        }
+        _ => {}
+    }
+
+    problems
+}
+
+/// Services in `ports` that would bind a port an earlier one already claims,
+/// with the problem to report for each
+fn port_clashes<'a>(ports: &[(&'a str, Option<u16>)]) -> Vec<(&'a str, String)> {
+    let mut claimed: Vec<(u16, &str)> = Vec::new();
+    let mut clashes = Vec::new();
+    for &(name, port) in ports {
+        let Some(port) = port else { continue };
+        match claimed.iter().find(|(p, _)| *p == port) {
+            Some((_, other)) => clashes.push((name, format!("port {} is also used by {}", port, other))),
+            None => claimed.push((port, name)),
+        }
+    }
+    clashes
+}
+
+/// Preflight every stopped service in `targets`, including two services that
+/// would both bind the same port. Returns (service name, problems) for each failure.
+fn preflight_all(targets: &[&ServiceInfo], bin_dir: &std::path::Path) -> Vec<(String, Vec<String>)> {
+    let stopped: Vec<&ServiceInfo> = targets.iter().copied().filter(|s| !is_service_running(&s.name)).collect();
+    let ports: Vec<(&str, Option<u16>)> = stopped.iter().map(|s| (s.name.as_str(), service_port_in(&s.name, bin_dir))).collect();
+    let clashes = port_clashes(&ports);
+    let mut failures = Vec::new();
+
+    for svc in stopped {
+        let mut problems = preflight_check(svc, bin_dir);
+        problems.extend(clashes.iter().filter(|(name, _)| *name == svc.name).map(|(_, problem)| problem.clone()));
+        if !problems.is_empty() {
+            failures.push((svc.name.clone(), problems));
+        }
+    }
+
+    failures
+}
+
+// ─── Helper: MariaDB client discovery ─────────────────────────────
+
+fn find_mariadb_client(bin_dir: &PathBuf) -> Result<PathBuf, String> {
+    let mariadb_root = bin_dir.join("mariadb");
+    let paths = [
+        mariadb_root.join("mariadb.exe"),
+        mariadb_root.join("mysql.exe"),
+        mariadb_root.join("bin").join("mariadb.exe"),
+        mariadb_root.join("bin").join("mysql.exe"),
+    ];
+    for path in paths {
+        if path.exists() {
+            return Ok(path);
+        }
+    }
+    Err("MariaDB client not found (mysql.exe / mariadb.exe)".to_string())
+}
+
+fn find_psql_client(bin_dir: &std::path::Path) -> Result<PathBuf, String> {
+    let pg_root = bin_dir.join("postgresql");
+    let paths = [
+        pg_root.join("bin").join(exe_name("psql")),
+        pg_root.join("pgsql").join("bin").join(exe_name("psql")),
+    ];
+    paths.into_iter().find(|p| p.exists()).ok_or_else(|| "PostgreSQL client (psql) not found".to_string())
+}
+
+fn find_mongosh_client(bin_dir: &std::path::Path) -> Result<PathBuf, String> {
+    let mongo_root = bin_dir.join("mongodb");
+    let paths = [
+        mongo_root.join("bin").join(exe_name("mongosh")),
+        mongo_root.join(exe_name("mongosh")),
+        mongo_root.join("bin").join(exe_name("mongo")),
+    ];
+    if let Some(path) = paths.into_iter().find(|p| p.exists()) {
+        return Ok(path);
+    }
+    // A separately installed mongosh on PATH
+    let on_path = PathBuf::from(exe_name("mongosh"));
+    if run_command(hidden_command(&on_path).arg("--version"), "version").is_ok_and(|o| o.success()) {
+        return Ok(on_path);
+    }
+    Err("mongosh/mongo client not found. Is MongoDB installed?".to_string())
+}
+
+fn find_mariadb_admin(bin_dir: &std::path::Path) -> Result<PathBuf, String> {
+    let mariadb_root = bin_dir.join("mariadb");
+    let paths = [
+        mariadb_root.join(exe_name("mariadb-admin")),
+        mariadb_root.join(exe_name("mysqladmin")),
+        mariadb_root.join("bin").join(exe_name("mariadb-admin")),
+        mariadb_root.join("bin").join(exe_name("mysqladmin")),
+    ];
+    paths.into_iter().find(|p| p.exists()).ok_or_else(|| "mariadb-admin not found".to_string())
+}
+
+fn find_mariadb_dump(bin_dir: &PathBuf) -> Result<PathBuf, String> {
+    let mariadb_root = bin_dir.join("mariadb");
+    let paths = [
+        mariadb_root.join("mariadb-dump.exe"),
+        mariadb_root.join("mysqldump.exe"),
+        mariadb_root.join("bin").join("mariadb-dump.exe"),
+        mariadb_root.join("bin").join("mysqldump.exe"),
+    ];
+    for path in paths {
+        if path.exists() {
+            return Ok(path);
+        }
+    }
+    Err("MariaDB dump not found (mysqldump.exe / mariadb-dump.exe)".to_string())
+}
+
+// ─── Helper: Log file discovery ───────────────────────────────────
+
+struct LogFile {
+    name: String,
+    path: PathBuf,
+    size: u64,
 }
 
 fn scan_log_files(bin_dir: &PathBuf) -> Vec<LogFile> {
@@ -798,6 +1908,23 @@ fn scan_log_files(bin_dir: &PathBuf) -> Vec<LogFile> {
         }
     }
 
+    // Dev server output captured by start_site_app
+    let site_app_log_dir = get_orbit_data_dir().join("logs").join("site-apps");
+    if let Ok(entries) = fs::read_dir(&site_app_log_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map(|e| e == "log").unwrap_or(false) {
+                let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                let fname = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                logs.push(LogFile {
+                    name: format!("site-apps/{}", fname),
+                    path,
+                    size,
+                });
+            }
+        }
+    }
+
     logs
 }
 
@@ -833,6 +1960,18 @@ fn open_in_browser(url: &str) {
     long_about = "Manage Nginx, PHP, MariaDB, Redis and more from the command line.\nThe modern alternative to XAMPP and Laragon."
 )]
 struct Cli {
+    /// Kill any subprocess (DB client, version probe, ...) still running after this many seconds
+    #[arg(long, global = true, value_name = "SECS")]
+    timeout: Option<u64>,
+
+    /// Don't touch the network (same as offline mode in Settings)
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Print machine-readable JSON instead of the formatted report
+    #[arg(long, global = true)]
+    json: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -849,6 +1988,9 @@ enum Commands {
         /// Start all installed services
         #[arg(long)]
         all: bool,
+        /// With --all: start the services that pass preflight and skip the rest
+        #[arg(long, requires = "all")]
+        skip_failing: bool,
     },
 
     /// Stop a service (or all with --all)
@@ -869,19 +2011,28 @@ enum Commands {
         all: bool,
     },
 
+    /// Restart only the services with pending config changes
+    Apply,
+
     /// List all installed services
     List,
 
     /// List configured sites
     Sites {
-        /// Output as JSON
-        #[arg(long)]
-        json: bool,
+        #[command(subcommand)]
+        action: Option<SitesCommands>,
     },
 
     /// Show environment info and paths
     Info,
 
+    /// Score environment health (services, ports, logs, sites) and check permissions
+    Doctor {
+        /// Try to take ownership / grant write access on failing paths
+        #[arg(long)]
+        fix_permissions: bool,
+    },
+
     /// Manage log files
     #[command(subcommand)]
     Logs(LogsCommands),
@@ -892,8 +2043,12 @@ enum Commands {
 
     /// Open a site or tool in the browser
     Open {
-        /// Domain name, 'adminer', or 'mailpit'
+        /// Site domain, optionally with a path (myapp.test/admin), or a tool:
+        /// adminer, mailpit, meilisearch, phpmyadmin, redis
         target: String,
+        /// Path (and query string) to open on the site, e.g. /admin?tab=users
+        #[arg(long)]
+        path: Option<String>,
         /// Use HTTPS
         #[arg(long)]
         https: bool,
@@ -907,6 +2062,10 @@ enum Commands {
     #[command(subcommand)]
     Hosts(HostsCommands),
 
+    /// Manage site SSL certificates
+    #[command(subcommand)]
+    Ssl(SslCommands),
+
     /// Run composer via Orbit's PHP
     Composer {
         /// Arguments to pass to composer
@@ -921,6 +2080,16 @@ enum Commands {
         /// Version to install (e.g., 8.4 for PHP, 1.28 for Nginx)
         #[arg(long)]
         version: Option<String>,
+        /// Install exactly the version recorded in config/versions.lock
+        #[arg(long)]
+        locked: bool,
+    },
+
+    /// Show installed service versions next to the latest available and the locked version
+    Versions {
+        /// Also expire the GUI's cached version lists so they are refetched
+        #[arg(long)]
+        refresh: bool,
     },
 
     /// Uninstall a service
@@ -931,6 +2100,60 @@ enum Commands {
         #[arg(long)]
         yes: bool,
     },
+
+    /// Restart watched services when they crash (runs until Ctrl+C)
+    Watch {
+        /// Comma-separated services to watch, saved to config/watch.json
+        #[arg(long, value_delimiter = ',')]
+        services: Option<Vec<String>>,
+        /// Stop watching everything (also turns off the orbit-mcp standby supervisor)
+        #[arg(long, conflicts_with = "services")]
+        clear: bool,
+    },
+
+    /// Run scheduled tasks from config/cron.json
+    #[command(subcommand)]
+    Cron(CronCommands),
+
+    /// Print a shell completion script (bash, zsh, fish, powershell)
+    #[command(after_help = COMPLETIONS_HELP)]
+    Completions {
+        /// Shell to generate the script for
+        shell: clap_complete::Shell,
+    },
+}
+
+const COMPLETIONS_HELP: &str = "\
+Install:
+  bash        orbit-cli completions bash > ~/.local/share/bash-completion/completions/orbit-cli
+  zsh         orbit-cli completions zsh > ~/.zfunc/_orbit-cli   (with fpath+=~/.zfunc in ~/.zshrc)
+  fish        orbit-cli completions fish > ~/.config/fish/completions/orbit-cli.fish
+  powershell  orbit-cli completions powershell >> $PROFILE
+
+Installed services and site domains are baked into the bash, zsh and fish
+scripts for `start`, `stop`, `restart` and `open`; re-run after installing
+a service or adding a site.";
+
+#[derive(Subcommand)]
+enum SitesCommands {
+    /// Switch a site to another installed PHP version
+    Php {
+        /// Site domain
+        domain: String,
+        /// PHP version (e.g., 8.3)
+        version: String,
+    },
+    /// Delete a site: its sites.json entry, web server config and hosts entries
+    Delete {
+        /// Site domain
+        domain: String,
+        /// Only show what would be removed
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip confirmation
+        #[arg(long)]
+        yes: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -953,6 +2176,14 @@ enum LogsCommands {
         /// Log name to clear
         name: String,
     },
+    /// Rotate a log file: keep it as <name>.1 and start a fresh one
+    Rotate {
+        /// Log name to rotate
+        name: String,
+        /// Rotated copies to keep
+        #[arg(long, default_value_t = LOG_ROTATE_KEEP)]
+        keep: usize,
+    },
 }
 
 #[derive(Subcommand)]
@@ -987,6 +2218,14 @@ enum DbCommands {
         /// SQL file path
         file: String,
     },
+    /// Open an interactive client connected with Orbit's credentials
+    Shell {
+        /// Database to select
+        name: Option<String>,
+        /// mariadb, postgresql or mongodb
+        #[arg(long, default_value = "mariadb")]
+        engine: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -1006,6 +2245,19 @@ enum PhpCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum SslCommands {
+    /// Reissue site certificates with mkcert (e.g. after its root CA changed)
+    Regenerate {
+        /// Site domain
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        domain: Option<String>,
+        /// Every site with SSL enabled
+        #[arg(long)]
+        all: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum HostsCommands {
     /// List entries in the hosts file
@@ -1022,6 +2274,13 @@ enum HostsCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum CronCommands {
+    /// Start every due task once, then exit (call it from a system scheduler
+    /// to run tasks while Orbit is closed)
+    Run,
+}
+
 // ─── Command Handlers ─────────────────────────────────────────────
 
 fn print_header() {
@@ -1031,10 +2290,39 @@ fn print_header() {
     println!();
 }
 
-fn cmd_status(bin_dir: &PathBuf) {
-    print_header();
+/// Output for `--json`
+fn print_json(value: &serde_json::Value) {
+    println!("{}", serde_json::to_string_pretty(value).unwrap_or_default());
+}
 
-    let services = scan_services(bin_dir);
+/// Report a failed command: under `--json`, `{"error": …}` on stdout and
+/// exit 1; otherwise the usual line on stderr
+fn print_error(json: bool, message: &str) {
+    if json {
+        print_json(&serde_json::json!({ "error": message }));
+        std::process::exit(1);
+    }
+    eprintln!("  {} {}", "✗".red(), message);
+}
+
+fn cmd_status(bin_dir: &PathBuf, json: bool) {
+    let services = scan_server_services(bin_dir);
+
+    if json {
+        let services_json: Vec<serde_json::Value> = services.iter().map(|svc| {
+            serde_json::json!({
+                "name": svc.name,
+                "version": svc.version,
+                "type": svc.service_type,
+                "port": get_service_port(&svc.name),
+                "running": is_service_running(&svc.name),
+            })
+        }).collect();
+        print_json(&serde_json::json!(services_json));
+        return;
+    }
+
+    print_header();
 
     if services.is_empty() {
         println!("  {} No services installed yet.", "!".yellow());
@@ -1088,8 +2376,8 @@ fn cmd_status(bin_dir: &PathBuf) {
     println!();
 }
 
-fn cmd_start(bin_dir: &PathBuf, service_name: Option<String>, all: bool) {
-    let services = scan_services(bin_dir);
+fn cmd_start(bin_dir: &PathBuf, service_name: Option<String>, all: bool, skip_failing: bool) {
+    let services = scan_services_cached(bin_dir);
 
     if services.is_empty() {
         println!("  {} No services installed.", "✗".red());
@@ -1123,12 +2411,35 @@ fn cmd_start(bin_dir: &PathBuf, service_name: Option<String>, all: bool) {
         return;
     }
 
+    // Batch starts are checked up front so one broken service doesn't fail mid-way
+    let failures = if all { preflight_all(&targets, bin_dir) } else { Vec::new() };
+    if !failures.is_empty() {
+        println!();
+        println!("  {}", "PREFLIGHT".dimmed().bold());
+        for (name, problems) in &failures {
+            for problem in problems {
+                println!("  {} {} — {}", "✗".red(), name.white(), problem.dimmed());
+            }
+        }
+        if !skip_failing {
+            println!();
+            println!("  {} Nothing was started. Fix the problems above, or start the rest with:", "!".yellow());
+            println!("  {} orbit start --all --skip-failing", "→".dimmed());
+            println!();
+            return;
+        }
+    }
+
     println!();
     for svc in &targets {
         if is_service_running(&svc.name) {
             println!("  {} {} already running", "—".dimmed(), svc.name.white());
             continue;
         }
+        if failures.iter().any(|(name, _)| *name == svc.name) {
+            println!("  {} {} skipped (preflight failed)", "—".dimmed(), svc.name.white());
+            continue;
+        }
 
         match start_service_process(svc) {
             Ok(pid) => {
@@ -1148,7 +2459,7 @@ fn cmd_start(bin_dir: &PathBuf, service_name: Option<String>, all: bool) {
 }
 
 fn cmd_stop(bin_dir: &PathBuf, service_name: Option<String>, all: bool) {
-    let services = scan_services(bin_dir);
+    let services = scan_services_cached(bin_dir);
 
     let targets: Vec<&ServiceInfo> = if all {
         services.iter().collect()
@@ -1176,8 +2487,11 @@ fn cmd_stop(bin_dir: &PathBuf, service_name: Option<String>, all: bool) {
         }
 
         match stop_service_process(&svc.name) {
-            Ok(()) => {
-                println!("  {} {} stopped", "✓".bright_green(), svc.name.white().bold());
+            Ok(outcome) if !outcome.was_running => {
+                println!("  {} {} not running", "—".dimmed(), svc.name.dimmed());
+            }
+            Ok(outcome) => {
+                println!("  {} {} stopped {}", "✓".bright_green(), svc.name.white().bold(), format!("({})", outcome.method).dimmed());
             }
             Err(e) => {
                 println!("  {} {} — {}", "✗".red(), svc.name.white(), e.dimmed());
@@ -1188,7 +2502,7 @@ fn cmd_stop(bin_dir: &PathBuf, service_name: Option<String>, all: bool) {
 }
 
 fn cmd_restart(bin_dir: &PathBuf, service_name: Option<String>, all: bool) {
-    let services = scan_services(bin_dir);
+    let services = scan_services_cached(bin_dir);
 
     let targets: Vec<&ServiceInfo> = if all {
         services.iter().filter(|s| {
@@ -1220,7 +2534,7 @@ fn cmd_restart(bin_dir: &PathBuf, service_name: Option<String>, all: bool) {
         // Stop if running
         if is_service_running(&svc.name) {
             match stop_service_process(&svc.name) {
-                Ok(()) => {
+                Ok(_) => {
                     println!("  {} {} stopped", "↻".yellow(), svc.name.white());
                 }
                 Err(e) => {
@@ -1250,10 +2564,59 @@ fn cmd_restart(bin_dir: &PathBuf, service_name: Option<String>, all: bool) {
     println!();
 }
 
-fn cmd_list(bin_dir: &PathBuf) {
-    print_header();
+fn cmd_apply(bin_dir: &PathBuf) {
+    let mut entries = read_pending_restarts();
+    if entries.is_empty() {
+        println!("  {} No config changes waiting for a restart.", "—".dimmed());
+        return;
+    }
+    entries.sort_by_key(|e| restart_priority(&e.service));
 
-    let services = scan_services(bin_dir);
+    let services = scan_services_cached(bin_dir);
+
+    println!();
+    for entry in &entries {
+        let name = entry.service.as_str();
+
+        if !is_service_running(name) {
+            clear_pending_restart(name);
+            println!("  {} {} not running — changes apply on next start", "—".dimmed(), name.dimmed());
+            continue;
+        }
+
+        let outcome = if name == "nginx" {
+            nginx_test_and_reload(bin_dir).map(|_| "reloaded".to_string())
+        } else {
+            match services.iter().find(|s| s.name == name) {
+                Some(svc) => stop_service_process(&svc.name)
+                    .and_then(|_| {
+                        std::thread::sleep(std::time::Duration::from_millis(500));
+                        start_service_process(svc)
+                    })
+                    .map(|pid| format!("restarted (PID {})", pid)),
+                None => Err(format!("{} is no longer installed", name)),
+            }
+        };
+
+        match outcome {
+            Ok(action) => {
+                clear_pending_restart(name);
+                println!(
+                    "  {} {} {} {}",
+                    "✓".bright_green(),
+                    name.white().bold(),
+                    action,
+                    format!("({})", entry.reason).dimmed()
+                );
+            }
+            Err(e) => println!("  {} {} — {}", "✗".red(), name.white(), e.dimmed()),
+        }
+    }
+    println!();
+}
+
+fn cmd_list(bin_dir: &PathBuf, json: bool) {
+    let services = scan_services_cached(bin_dir);
 
     let known_services = vec![
         ("nginx", "Nginx", "High-performance web server"),
@@ -1274,13 +2637,30 @@ fn cmd_list(bin_dir: &PathBuf) {
         ("composer", "Composer", "PHP dependency manager"),
     ];
 
-    println!("  {}", "AVAILABLE SERVICES".dimmed().bold());
-    println!("  {}", "─".repeat(55).dimmed());
-
-    for (stype, label, description) in &known_services {
-        let installed = services.iter().find(|s| s.service_type == *stype);
-        let status = if let Some(svc) = installed {
-            format!("{} {}", "✓".bright_green(), svc.version.dimmed())
+    if json {
+        let list_json: Vec<serde_json::Value> = known_services.iter().map(|(stype, label, description)| {
+            let installed = services.iter().find(|s| s.service_type == *stype);
+            serde_json::json!({
+                "service": stype,
+                "name": label,
+                "description": description,
+                "installed": installed.is_some(),
+                "version": installed.map(|s| &s.version),
+            })
+        }).collect();
+        print_json(&serde_json::json!(list_json));
+        return;
+    }
+
+    print_header();
+
+    println!("  {}", "AVAILABLE SERVICES".dimmed().bold());
+    println!("  {}", "─".repeat(55).dimmed());
+
+    for (stype, label, description) in &known_services {
+        let installed = services.iter().find(|s| s.service_type == *stype);
+        let status = if let Some(svc) = installed {
+            format!("{} {}", "✓".bright_green(), svc.version.dimmed())
         } else {
             format!("{}", "not installed".dimmed())
         };
@@ -1301,7 +2681,7 @@ fn cmd_sites(json: bool) {
     let store = match read_sites_store() {
         Ok(s) => s,
         Err(e) => {
-            eprintln!("  {} {}", "✗".red(), e);
+            print_error(json, &e);
             return;
         }
     };
@@ -1319,7 +2699,7 @@ fn cmd_sites(json: bool) {
                 "created_at": s.created_at,
             })
         }).collect();
-        println!("{}", serde_json::to_string_pretty(&sites_json).unwrap_or_default());
+        print_json(&serde_json::json!(sites_json));
         return;
     }
 
@@ -1366,20 +2746,51 @@ fn cmd_sites(json: bool) {
     println!();
 }
 
-fn cmd_info(bin_dir: &PathBuf) {
-    print_header();
-
+fn cmd_info(bin_dir: &PathBuf, json: bool) {
     let data_dir = get_orbit_data_dir();
     let config_dir = get_config_dir();
 
+    if json {
+        let services_json: Vec<serde_json::Value> = scan_services_cached(bin_dir).iter().map(|svc| {
+            serde_json::json!({
+                "name": svc.name,
+                "version": svc.version,
+                "running": is_service_running(&svc.name),
+            })
+        }).collect();
+        let data_dir_override = data_dir_override().map(|(dir, source)| serde_json::json!({
+            "path": dir,
+            "source": source,
+            "active": dir == data_dir,
+        }));
+        print_json(&serde_json::json!({
+            "data_dir": data_dir,
+            "data_dir_override": data_dir_override,
+            "bin_dir": bin_dir,
+            "config_dir": config_dir,
+            "services": services_json,
+            "sites": read_sites_store().map(|store| store.sites.len()).unwrap_or(0),
+        }));
+        return;
+    }
+
+    print_header();
+
     println!("  {}", "PATHS".dimmed().bold());
     println!("  {}", "─".repeat(50).dimmed());
     println!("  {:<16} {}", "Data Dir:".white().bold(), data_dir.display().to_string().cyan());
+    if let Some((dir, source)) = data_dir_override() {
+        if dir == data_dir {
+            println!("  {:<16} {}", "Override:".white().bold(), format!("from {}", source).dimmed());
+        } else {
+            println!("  {:<16} {}", "Override:".white().bold(), format!("{} ignored (not writable)", dir.display()).yellow());
+        }
+    }
     println!("  {:<16} {}", "Bin Dir:".white().bold(), bin_dir.display().to_string().cyan());
     println!("  {:<16} {}", "Config Dir:".white().bold(), config_dir.display().to_string().cyan());
     println!();
 
-    let services = scan_services(bin_dir);
+    let services = scan_services_cached(bin_dir);
 
     if !services.is_empty() {
         println!("  {}", "INSTALLED".dimmed().bold());
@@ -1408,11 +2819,24 @@ fn cmd_info(bin_dir: &PathBuf) {
 
 // ─── Log Commands ─────────────────────────────────────────────────
 
-fn cmd_logs_list(bin_dir: &PathBuf) {
-    print_header();
-
+fn cmd_logs_list(bin_dir: &PathBuf, json: bool) {
     let logs = scan_log_files(bin_dir);
 
+    if json {
+        let logs_json: Vec<serde_json::Value> = logs.iter().map(|log| {
+            serde_json::json!({
+                "name": log.name,
+                "path": log.path,
+                "size": format_size(log.size),
+                "size_bytes": log.size,
+            })
+        }).collect();
+        print_json(&serde_json::json!(logs_json));
+        return;
+    }
+
+    print_header();
+
     if logs.is_empty() {
         println!("  {} No log files found.", "!".yellow());
         println!();
@@ -1439,14 +2863,31 @@ fn cmd_logs_list(bin_dir: &PathBuf) {
     println!();
 }
 
+/// Match a log by full name or by suffix. A bare suffix such as
+/// `php_errors.log` must name a single log, not whichever PHP version sorts first.
+fn resolve_log<'a>(logs: &'a [LogFile], name: &str) -> Result<&'a LogFile, String> {
+    if let Some(log) = logs.iter().find(|l| l.name == name) {
+        return Ok(log);
+    }
+    let matches: Vec<&LogFile> = logs.iter().filter(|l| l.name.ends_with(name)).collect();
+    match matches.as_slice() {
+        [log] => Ok(log),
+        [] => Err(format!("Log '{}' not found.", name)),
+        _ => Err(format!(
+            "'{}' matches several logs: {}.",
+            name,
+            matches.iter().map(|l| l.name.as_str()).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
 fn cmd_logs_show(bin_dir: &PathBuf, name: &str, lines: usize, follow: bool) {
     let logs = scan_log_files(bin_dir);
 
-    let log = logs.iter().find(|l| l.name == name || l.name.ends_with(name));
-    let log = match log {
-        Some(l) => l,
-        None => {
-            eprintln!("  {} Log '{}' not found. Use 'orbit logs list' to see available logs.", "✗".red(), name);
+    let log = match resolve_log(&logs, name) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("  {} {} Use 'orbit logs list' to see available logs.", "✗".red(), e);
             return;
         }
     };
@@ -1475,41 +2916,61 @@ fn cmd_logs_show(bin_dir: &PathBuf, name: &str, lines: usize, follow: bool) {
 
     if follow {
         println!("{}", "─ Following (Ctrl+C to stop) ─".dimmed());
-        // Poll-based tail
-        let mut file = match std::fs::File::open(&log.path) {
-            Ok(f) => f,
-            Err(e) => {
-                eprintln!("  {} Failed to open log for tailing: {}", "✗".red(), e);
-                return;
-            }
-        };
-        let _ = file.seek(SeekFrom::End(0));
-        let mut reader = BufReader::new(file);
+        follow_log(&log.path, content.len() as u64);
+    }
+}
 
-        loop {
-            let mut line = String::new();
-            match reader.read_line(&mut line) {
-                Ok(0) => {
-                    // No new data, sleep
-                    std::thread::sleep(std::time::Duration::from_secs(1));
+/// How often `logs show -f` checks the file for new data
+const FOLLOW_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Print whatever is appended to `path` after `offset` until Ctrl+C. The file
+/// is reopened on every poll so rotation and `logs clear` are picked up, and
+/// no handle is held that would block rotation on Windows.
+fn follow_log(path: &PathBuf, mut offset: u64) {
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    {
+        let stop = stop.clone();
+        let _ = ctrlc::set_handler(move || stop.store(true, std::sync::atomic::Ordering::Relaxed));
+    }
+
+    let mut stdout = std::io::stdout();
+    let mut missing = false;
+    while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+        match fs::metadata(path) {
+            Ok(meta) => {
+                let len = meta.len();
+                if missing || len < offset {
+                    println!("{}", "─ Log truncated or rotated, reading from the start ─".dimmed());
+                    offset = 0;
+                    missing = false;
                 }
-                Ok(_) => {
-                    print!("{}", line);
+                if len > offset {
+                    if let Ok(mut file) = fs::File::open(path) {
+                        let mut chunk = Vec::new();
+                        if file.seek(SeekFrom::Start(offset)).is_ok() && file.read_to_end(&mut chunk).is_ok() {
+                            offset += chunk.len() as u64;
+                            let _ = stdout.write_all(&chunk);
+                            let _ = stdout.flush();
+                        }
+                    }
                 }
-                Err(_) => break,
             }
+            // Removed mid-rotation; wait for the new file
+            Err(_) => missing = true,
         }
+        std::thread::sleep(FOLLOW_POLL_INTERVAL);
     }
+    println!();
+    println!("{}", "─ Stopped following ─".dimmed());
 }
 
 fn cmd_logs_clear(bin_dir: &PathBuf, name: &str) {
     let logs = scan_log_files(bin_dir);
 
-    let log = logs.iter().find(|l| l.name == name || l.name.ends_with(name));
-    let log = match log {
-        Some(l) => l,
-        None => {
-            eprintln!("  {} Log '{}' not found.", "✗".red(), name);
+    let log = match resolve_log(&logs, name) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("  {} {}", "✗".red(), e);
             return;
         }
     };
@@ -1520,35 +2981,226 @@ fn cmd_logs_clear(bin_dir: &PathBuf, name: &str) {
     }
 }
 
+fn cmd_logs_rotate(bin_dir: &PathBuf, name: &str, keep: usize) {
+    let logs = scan_log_files(bin_dir);
+
+    let log = match resolve_log(&logs, name) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("  {} {}", "✗".red(), e);
+            return;
+        }
+    };
+
+    match rotate_log(bin_dir, &log.name, keep) {
+        Ok(rotation) => {
+            println!(
+                "  {} {} rotated ({} moved to {}.1, keeping {})",
+                "✓".bright_green(),
+                log.name.white().bold(),
+                format_size(rotation.size),
+                log.name,
+                rotation.keep
+            );
+            for path in &rotation.removed {
+                println!("  {} Removed {}", "→".dimmed(), path.display());
+            }
+            if let Some(e) = rotation.reopen_error {
+                println!("  {} nginx -s reopen failed, restart nginx to write to the fresh log: {}", "!".yellow(), e);
+            }
+        }
+        Err(e) => eprintln!("  {} Failed to rotate {}: {}", "✗".red(), log.name, e),
+    }
+}
+
+// ─── Log Rotation (shared with mcp.rs) ────────────────────────────
+
+/// Rotated copies kept when the caller doesn't pass `keep`
+const LOG_ROTATE_KEEP: usize = 5;
+
+/// Most rotated copies `keep` may ask for; larger values are clamped
+const LOG_ROTATE_MAX_KEEP: usize = 100;
+
+/// Outcome of `rotate_log`
+struct LogRotation {
+    /// Size of the live log before rotation
+    size: u64,
+    /// Rotated copies kept, after clamping
+    keep: usize,
+    /// Old copies deleted because they fell beyond `keep`
+    removed: Vec<PathBuf>,
+    /// Set when nginx is running but `nginx -s reopen` failed
+    reopen_error: Option<String>,
+}
+
+fn rotated_log_path(path: &std::path::Path, index: usize) -> PathBuf {
+    let mut rotated = path.as_os_str().to_os_string();
+    rotated.push(format!(".{}", index));
+    PathBuf::from(rotated)
+}
+
+/// Rotate a log from `scan_log_files`: `<log>.N` moves to `<log>.N+1`, the live
+/// file becomes `<log>.1` and starts empty, and copies past `keep` are deleted.
+/// nginx logs are renamed and nginx reopens its files; other services hold
+/// their log open, so theirs are copied and truncated in place instead.
+fn rotate_log(bin_dir: &PathBuf, name: &str, keep: usize) -> Result<LogRotation, String> {
+    // Only names from the scanned set, so a crafted name can't reach other files
+    if name.is_empty() || name.contains("..") {
+        return Err("Invalid log name".to_string());
+    }
+    if keep == 0 {
+        return Err("keep must be at least 1".to_string());
+    }
+    let keep = keep.min(LOG_ROTATE_MAX_KEEP);
+    let log = scan_log_files(bin_dir)
+        .into_iter()
+        .find(|l| l.name == name)
+        .ok_or_else(|| format!("Log file not found: {}", name))?;
+
+    let file_name = log.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let prefix = format!("{}.", file_name);
+    let mut removed = Vec::new();
+    if let Some(dir) = log.path.parent() {
+        for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read log directory: {}", e))?.flatten() {
+            let entry_name = entry.file_name().to_string_lossy().to_string();
+            let index = entry_name.strip_prefix(&prefix).and_then(|n| n.parse::<usize>().ok());
+            if index.is_some_and(|n| n >= keep) {
+                fs::remove_file(entry.path())
+                    .map_err(|e| format!("Failed to remove {}: {}", entry_name, e))?;
+                removed.push(entry.path());
+            }
+        }
+    }
+
+    for index in (1..keep).rev() {
+        let from = rotated_log_path(&log.path, index);
+        if from.exists() {
+            fs::rename(&from, rotated_log_path(&log.path, index + 1))
+                .map_err(|e| format!("Failed to shift {}: {}", from.display(), e))?;
+        }
+    }
+
+    let is_nginx = name.starts_with("nginx/");
+    let first = rotated_log_path(&log.path, 1);
+    // Fall back to copying if Windows refuses to rename a file nginx holds open
+    if !(is_nginx && fs::rename(&log.path, &first).is_ok()) {
+        fs::copy(&log.path, &first).map_err(|e| format!("Failed to copy {}: {}", name, e))?;
+    }
+    fs::File::create(&log.path).map_err(|e| format!("Failed to truncate {}: {}", name, e))?;
+
+    let mut reopen_error = None;
+    if is_nginx && is_service_running("nginx") {
+        let nginx_dir = bin_dir.join("nginx");
+        let nginx = nginx_dir.join(exe_name("nginx"));
+        let output = hidden_command(&nginx)
+            .current_dir(&nginx_dir)
+            .args(["-s", "reopen"])
+            .output();
+        reopen_error = match output {
+            Ok(o) if o.status.success() => None,
+            Ok(o) => Some(String::from_utf8_lossy(&o.stderr).trim().to_string()),
+            Err(e) => Some(e.to_string()),
+        };
+    }
+
+    Ok(LogRotation { size: log.size, keep, removed, reopen_error })
+}
+
+// ─── Database Config (shared with mcp.rs) ─────────────────────────
+
+/// Optional database settings from config/db.json; missing fields use defaults.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct DbConfig {
+    mariadb_user: Option<String>,
+    mariadb_password: Option<String>,
+    pg_user: Option<String>,
+    pg_password: Option<String>,
+    mongo_uri: Option<String>,
+    mongo_user: Option<String>,
+    mongo_password: Option<String>,
+    mongo_auth_db: Option<String>,
+}
+
+fn read_db_config() -> DbConfig {
+    fs::read_to_string(get_config_dir().join("db.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Database login used by the MariaDB and PostgreSQL commands.
+struct DbCredentials {
+    mariadb_user: String,
+    mariadb_password: String,
+    pg_user: String,
+    pg_password: String,
+}
+
+impl DbCredentials {
+    /// `-u <user> -p<password>` for mysql/mysqldump. `-p` is omitted for an
+    /// empty password so the client doesn't stop to prompt for one.
+    fn mariadb_auth_args(&self) -> Vec<String> {
+        let mut args = vec!["-u".to_string(), self.mariadb_user.clone()];
+        if !self.mariadb_password.is_empty() {
+            args.push(format!("-p{}", self.mariadb_password));
+        }
+        args
+    }
+}
+
+/// Credentials from config/db.json, falling back to Orbit's defaults
+/// (root/root for MariaDB, postgres/postgres for PostgreSQL).
+fn db_credentials() -> DbCredentials {
+    let config = read_db_config();
+    DbCredentials {
+        mariadb_user: config.mariadb_user.unwrap_or_else(|| "root".to_string()),
+        mariadb_password: config.mariadb_password.unwrap_or_else(|| "root".to_string()),
+        pg_user: config.pg_user.unwrap_or_else(|| "postgres".to_string()),
+        pg_password: config.pg_password.unwrap_or_else(|| "postgres".to_string()),
+    }
+}
+
 // ─── Database Commands ────────────────────────────────────────────
 
-fn cmd_db_list(bin_dir: &PathBuf) {
+fn cmd_db_list(bin_dir: &PathBuf, json: bool) {
     let client = match find_mariadb_client(bin_dir) {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("  {} {}", "✗".red(), e);
+            print_error(json, &e);
             return;
         }
     };
 
-    let output = hidden_command(&client)
-        .arg("--host=127.0.0.1")
-        .arg("--port=3306")
-        .arg("-u").arg("root").arg("-proot")
-        .arg("-e").arg("SHOW DATABASES")
-        .arg("--batch").arg("--skip-column-names")
-        .output();
+    let output = run_command(
+        hidden_command(&client)
+            .arg("--host=127.0.0.1")
+            .arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg("-e").arg("SHOW DATABASES")
+            .arg("--batch").arg("--skip-column-names"),
+        "db",
+    );
 
     match output {
         Ok(out) => {
-            if !out.status.success() {
-                let stderr = String::from_utf8_lossy(&out.stderr);
-                eprintln!("  {} {}", "✗".red(), stderr.trim());
+            if !out.success() {
+                print_error(json, &out.error_detail());
                 return;
             }
-            let stdout = String::from_utf8_lossy(&out.stdout);
+            let stdout = out.stdout;
             let system_dbs = ["information_schema", "performance_schema", "mysql", "sys"];
 
+            if json {
+                let dbs_json: Vec<serde_json::Value> = stdout.lines()
+                    .map(str::trim)
+                    .filter(|db| !db.is_empty())
+                    .map(|db| serde_json::json!({ "name": db, "system": system_dbs.contains(&db) }))
+                    .collect();
+                print_json(&serde_json::json!(dbs_json));
+                return;
+            }
+
             print_header();
             println!("  {}", "DATABASES".dimmed().bold());
             println!("  {}", "─".repeat(40).dimmed());
@@ -1571,33 +3223,37 @@ fn cmd_db_list(bin_dir: &PathBuf) {
             println!();
         }
         Err(e) => {
-            eprintln!("  {} Failed to connect to MariaDB: {}", "✗".red(), e);
+            print_error(json, &format!("Failed to connect to MariaDB: {}", e));
             eprintln!("  {} Is MariaDB running?", "→".dimmed());
         }
     }
 }
 
+/// Quote a MariaDB identifier, doubling any backticks in it
+fn escape_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
 fn cmd_db_create(bin_dir: &PathBuf, name: &str) {
     let client = match find_mariadb_client(bin_dir) {
         Ok(c) => c,
         Err(e) => { eprintln!("  {} {}", "✗".red(), e); return; }
     };
 
-    let sql = format!("CREATE DATABASE `{}` CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci", name);
-    let output = hidden_command(&client)
-        .arg("--host=127.0.0.1").arg("--port=3306")
-        .arg("-u").arg("root").arg("-proot")
-        .arg("-e").arg(&sql)
-        .output();
+    let sql = format!("CREATE DATABASE {} CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci", escape_identifier(name));
+    let output = run_command(
+        hidden_command(&client)
+            .arg("--host=127.0.0.1").arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg("-e").arg(&sql),
+        "db",
+    );
 
     match output {
-        Ok(out) if out.status.success() => {
+        Ok(out) if out.success() => {
             println!("  {} Database '{}' created", "✓".bright_green(), name.white().bold());
         }
-        Ok(out) => {
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            eprintln!("  {} {}", "✗".red(), stderr.trim());
-        }
+        Ok(out) => eprintln!("  {} {}", "✗".red(), out.error_detail()),
         Err(e) => eprintln!("  {} {}", "✗".red(), e),
     }
 }
@@ -1624,21 +3280,20 @@ fn cmd_db_drop(bin_dir: &PathBuf, name: &str, yes: bool) {
         Err(e) => { eprintln!("  {} {}", "✗".red(), e); return; }
     };
 
-    let sql = format!("DROP DATABASE `{}`", name);
-    let output = hidden_command(&client)
-        .arg("--host=127.0.0.1").arg("--port=3306")
-        .arg("-u").arg("root").arg("-proot")
-        .arg("-e").arg(&sql)
-        .output();
+    let sql = format!("DROP DATABASE {}", escape_identifier(name));
+    let output = run_command(
+        hidden_command(&client)
+            .arg("--host=127.0.0.1").arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg("-e").arg(&sql),
+        "db",
+    );
 
     match output {
-        Ok(out) if out.status.success() => {
+        Ok(out) if out.success() => {
             println!("  {} Database '{}' dropped", "✓".bright_green(), name.white().bold());
         }
-        Ok(out) => {
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            eprintln!("  {} {}", "✗".red(), stderr.trim());
-        }
+        Ok(out) => eprintln!("  {} {}", "✗".red(), out.error_detail()),
         Err(e) => eprintln!("  {} {}", "✗".red(), e),
     }
 }
@@ -1651,12 +3306,14 @@ fn cmd_db_export(bin_dir: &PathBuf, name: &str, output_path: Option<String>) {
 
     let out_file = output_path.unwrap_or_else(|| format!("{}.sql", name));
 
-    let output = hidden_command(&dump_exe)
-        .arg("--host=127.0.0.1").arg("--port=3306")
-        .arg("-u").arg("root").arg("-proot")
-        .arg("--routines").arg("--triggers").arg("--single-transaction")
-        .arg(name)
-        .output();
+    let output = run_with_timeout(
+        hidden_command(&dump_exe)
+            .arg("--host=127.0.0.1").arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg("--routines").arg("--triggers").arg("--single-transaction")
+            .arg(name),
+        operation_timeout("db_export"),
+    );
 
     match output {
         Ok(out) if out.status.success() => {
@@ -1673,10 +3330,7 @@ fn cmd_db_export(bin_dir: &PathBuf, name: &str, output_path: Option<String>) {
                 Err(e) => eprintln!("  {} Failed to write file: {}", "✗".red(), e),
             }
         }
-        Ok(out) => {
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            eprintln!("  {} Export failed: {}", "✗".red(), stderr.trim());
-        }
+        Ok(out) => eprintln!("  {} Export failed: {}", "✗".red(), CommandResult::from(out).error_detail()),
         Err(e) => eprintln!("  {} {}", "✗".red(), e),
     }
 }
@@ -1699,25 +3353,18 @@ fn cmd_db_import(bin_dir: &PathBuf, name: &str, file: &str) {
 
     let file_size = sql_content.len();
 
-    let mut child = match hidden_command(&client)
-        .arg("--host=127.0.0.1").arg("--port=3306")
-        .arg("-u").arg("root").arg("-proot")
-        .arg(name)
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-    {
-        Ok(c) => c,
-        Err(e) => { eprintln!("  {} Failed to start mysql client: {}", "✗".red(), e); return; }
-    };
-
-    if let Some(mut stdin) = child.stdin.take() {
-        let _ = stdin.write_all(&sql_content);
-    }
+    let output = run_with_timeout_input(
+        hidden_command(&client)
+            .arg("--host=127.0.0.1").arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg(name),
+        Some(sql_content),
+        operation_timeout("db_import"),
+    )
+    .map(CommandResult::from);
 
-    match child.wait_with_output() {
-        Ok(out) if out.status.success() => {
+    match output {
+        Ok(out) if out.success() => {
             println!(
                 "  {} Imported {} into '{}'",
                 "✓".bright_green(),
@@ -1725,53 +3372,171 @@ fn cmd_db_import(bin_dir: &PathBuf, name: &str, file: &str) {
                 name.white().bold()
             );
         }
-        Ok(out) => {
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            eprintln!("  {} Import failed: {}", "✗".red(), stderr.trim());
-        }
+        Ok(out) => eprintln!("  {} Import failed: {}", "✗".red(), out.error_detail()),
         Err(e) => eprintln!("  {} {}", "✗".red(), e),
     }
 }
 
-// ─── Open Command ─────────────────────────────────────────────────
+/// Run a database client in the foreground with the terminal attached, unlike
+/// the other db commands which capture output through `hidden_command`.
+fn cmd_db_shell(bin_dir: &PathBuf, engine: &str, name: Option<&str>) {
+    let (service, display) = match engine.to_lowercase().as_str() {
+        "mariadb" | "mysql" => ("mariadb", "MariaDB"),
+        "postgresql" | "postgres" | "pg" => ("postgresql", "PostgreSQL"),
+        "mongodb" | "mongo" => ("mongodb", "MongoDB"),
+        _ => {
+            eprintln!("  {} Unknown engine '{}'. Use mariadb, postgresql or mongodb.", "✗".red(), engine);
+            std::process::exit(1);
+        }
+    };
+    if !is_service_running(service) {
+        eprintln!("  {} {} is not running. Start it with: orbit start {}", "✗".red(), display, service);
+        std::process::exit(1);
+    }
 
-fn cmd_open(target: &str, https: bool) {
-    match target.to_lowercase().as_str() {
-        "adminer" => {
-            open_in_browser("http://localhost:8080");
-            println!("  {} Opening Adminer...", "✓".bright_green());
+    let client = match service {
+        "mariadb" => find_mariadb_client(bin_dir),
+        "postgresql" => find_psql_client(bin_dir),
+        _ => find_mongosh_client(bin_dir),
+    };
+    let client = match client {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("  {} {}", "✗".red(), e);
+            std::process::exit(1);
         }
-        "mailpit" => {
-            open_in_browser("http://localhost:8025");
-            println!("  {} Opening Mailpit...", "✓".bright_green());
+    };
+
+    let mut cmd = Command::new(&client);
+    match service {
+        "mariadb" => {
+            cmd.arg("--host=127.0.0.1").arg("--port=3306")
+                .args(db_credentials().mariadb_auth_args());
+            cmd.args(name);
         }
-        "meilisearch" | "meili" => {
-            open_in_browser("http://localhost:7700");
-            println!("  {} Opening Meilisearch...", "✓".bright_green());
+        "postgresql" => {
+            let creds = db_credentials();
+            cmd.arg("-U").arg(&creds.pg_user)
+                .arg("-h").arg("127.0.0.1")
+                .arg("-p").arg("5432")
+                .env("PGPASSWORD", &creds.pg_password);
+            if let Some(db) = name {
+                cmd.arg("-d").arg(db);
+            }
         }
         _ => {
-            // Try to find in sites
-            if let Ok(store) = read_sites_store() {
-                if let Some(site) = store.sites.iter().find(|s| s.domain == target) {
-                    let proto = if https || site.ssl_enabled { "https" } else { "http" };
-                    let port_suffix = if (site.port == 80 && !site.ssl_enabled) || (site.port == 443 && site.ssl_enabled) {
-                        String::new()
-                    } else {
-                        format!(":{}", site.port)
-                    };
-                    let url = format!("{}://{}{}", proto, site.domain, port_suffix);
-                    open_in_browser(&url);
-                    println!("  {} Opening {}...", "✓".bright_green(), url.cyan());
-                    return;
+            let config = read_db_config();
+            match config.mongo_uri.as_deref().filter(|u| !u.is_empty()) {
+                // The URI carries its own default database
+                Some(uri) => {
+                    cmd.arg(uri);
                 }
+                None => {
+                    cmd.arg("--host").arg("127.0.0.1").arg("--port").arg("27017");
+                    cmd.args(name);
+                }
+            }
+            if let Some(user) = config.mongo_user.as_deref().filter(|u| !u.is_empty()) {
+                cmd.arg("-u").arg(user)
+                    .arg("-p").arg(config.mongo_password.as_deref().unwrap_or(""))
+                    .arg("--authenticationDatabase").arg(config.mongo_auth_db.as_deref().unwrap_or("admin"));
             }
-            // Fallback: treat as domain directly
-            let proto = if https { "https" } else { "http" };
-            let url = format!("{}://{}", proto, target);
-            open_in_browser(&url);
-            println!("  {} Opening {}...", "✓".bright_green(), url.cyan());
         }
     }
+
+    println!("  {} Connecting to {}{}...", "→".dimmed(), display,
+        name.map(|n| format!(" ({})", n)).unwrap_or_default());
+    // The client owns the terminal from here: on Unix it replaces this
+    // process, elsewhere Ctrl+C is left to it instead of killing orbit.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let e = cmd.exec();
+        eprintln!("  {} Failed to run {}: {}", "✗".red(), client.display(), e);
+        std::process::exit(1);
+    }
+    #[cfg(not(unix))]
+    let _ = ctrlc::set_handler(|| {});
+    #[cfg(not(unix))]
+    match cmd.status() {
+        Ok(status) if !status.success() => std::process::exit(status.code().unwrap_or(1)),
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("  {} Failed to run {}: {}", "✗".red(), client.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// ─── Open Command ─────────────────────────────────────────────────
+
+/// Tools `orbit open` knows by name (see `open_tool_url`)
+const OPEN_TOOLS: &[&str] = &["adminer", "mailpit", "meilisearch", "phpmyadmin", "redis"];
+
+/// Base URL of a tool `orbit open` knows by name. Redis has no web UI, so it
+/// opens as a redis:// link for whichever client handles those.
+fn open_tool_url(name: &str) -> Option<(&'static str, String)> {
+    let (label, url) = match name {
+        "adminer" => ("Adminer", "http://localhost:8080".to_string()),
+        "phpmyadmin" | "pma" => ("phpMyAdmin", "http://localhost:8081".to_string()),
+        "mailpit" => ("Mailpit", format!("http://localhost:{}", get_service_port("mailpit")?)),
+        "meilisearch" | "meili" => ("Meilisearch", format!("http://localhost:{}", get_service_port("meilisearch")?)),
+        "redis" => ("Redis", format!("redis://127.0.0.1:{}", get_service_port("redis")?)),
+        _ => return None,
+    };
+    Some((label, url))
+}
+
+/// Split `myapp.test/admin?x=1` into the host part and `/admin?x=1`.
+fn split_open_target(target: &str) -> (&str, &str) {
+    match target.find(['/', '?', '#']) {
+        Some(i) => (&target[..i], &target[i..]),
+        None => (target, ""),
+    }
+}
+
+fn cmd_open(target: &str, path: Option<&str>, https: bool) {
+    let (name, target_path) = split_open_target(target);
+    let path = match (target_path, path) {
+        ("", None) => String::new(),
+        (p, None) => p.to_string(),
+        ("", Some(p)) if p.starts_with(['/', '?', '#']) => p.to_string(),
+        ("", Some(p)) => format!("/{}", p),
+        (_, Some(_)) => {
+            eprintln!("  {} Give the path either in the target or with --path, not both.", "✗".red());
+            std::process::exit(1);
+        }
+    };
+
+    if let Some((label, base)) = open_tool_url(&name.to_lowercase()) {
+        let url = format!("{}{}", base, path);
+        open_in_browser(&url);
+        println!("  {} Opening {}...", "✓".bright_green(), label);
+        return;
+    }
+
+    let sites = read_sites_store().map(|store| store.sites).unwrap_or_default();
+    let Some(site) = sites.iter().find(|s| s.domain == name || s.aliases.iter().any(|a| a == name)) else {
+        eprintln!("  {} '{}' is not a site or a known tool.", "✗".red(), name);
+        if sites.is_empty() {
+            eprintln!("  No sites configured yet.");
+        } else {
+            let domains: Vec<&str> = sites.iter().map(|s| s.domain.as_str()).collect();
+            eprintln!("  Known sites: {}", domains.join(", "));
+        }
+        eprintln!("  Tools: {}", OPEN_TOOLS.join(", "));
+        std::process::exit(1);
+    };
+
+    let proto = if https || site.ssl_enabled { "https" } else { "http" };
+    let port_suffix = if (site.port == 80 && !site.ssl_enabled) || (site.port == 443 && site.ssl_enabled) {
+        String::new()
+    } else {
+        format!(":{}", site.port)
+    };
+    let url = format!("{}://{}{}{}", proto, name, port_suffix, path);
+    open_in_browser(&url);
+    println!("  {} Opening {}...", "✓".bright_green(), url.cyan());
 }
 
 // ─── PHP Commands ─────────────────────────────────────────────────
@@ -1911,16 +3676,111 @@ fn get_hosts_path() -> PathBuf {
     { PathBuf::from("/etc/hosts") }
 }
 
-fn cmd_hosts_list() {
+/// The address and hostnames of a hosts line; comments and blank lines
+/// have none
+fn hosts_line_fields(line: &str) -> Option<(&str, Vec<&str>)> {
+    let code = line.split('#').next().unwrap_or("");
+    let mut fields = code.split_whitespace();
+    let addr = fields.next()?;
+    let names: Vec<&str> = fields.collect();
+    (!names.is_empty()).then_some((addr, names))
+}
+
+/// Whether a hosts line maps `domain`, as any of its hostnames
+fn hosts_line_maps(line: &str, domain: &str) -> bool {
+    hosts_line_fields(line).is_some_and(|(_, names)| names.contains(&domain))
+}
+
+/// A hosts line with `domain` taken out of its hostnames, keeping the other
+/// names and any comment; `None` when `domain` was its only name
+fn hosts_line_without(line: &str, domain: &str) -> Option<String> {
+    let (addr, names) = hosts_line_fields(line)?;
+    let others: Vec<&str> = names.into_iter().filter(|name| *name != domain).collect();
+    if others.is_empty() {
+        return None;
+    }
+    let comment = line.find('#').map(|i| format!(" {}", &line[i..])).unwrap_or_default();
+    Some(format!("{} {}{}", addr, others.join(" "), comment))
+}
+
+/// `content` without `domain`, whatever its address: lines of its own are
+/// dropped, lines shared with other names only lose the domain
+fn hosts_drop_domain(content: &str, domain: &str) -> String {
+    let kept: Vec<String> = content.lines()
+        .filter_map(|line| if hosts_line_maps(line, domain) { hosts_line_without(line, domain) } else { Some(line.to_string()) })
+        .collect();
+    format!("{}\n", kept.join("\n").trim_end())
+}
+
+/// Backups of the hosts file kept next to it
+const HOSTS_BACKUPS_KEPT: usize = 5;
+
+/// Where hosts file backups go. Under config/ rather than next to the hosts
+/// file, which only an administrator may write to (System32\drivers\etc on
+/// Windows), so backing up never needs elevation.
+fn hosts_backup_dir() -> PathBuf {
+    get_config_dir().join("hosts-backups")
+}
+
+/// Orbit's backups in `backup_dir` (`hosts.orbit-<timestamp>.bak`), oldest first
+fn hosts_backups(backup_dir: &std::path::Path) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(backup_dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    backups.retain(|path| {
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        file.starts_with("hosts.orbit-") && file.ends_with(".bak")
+    });
+    backups.sort();
+    backups
+}
+
+/// Copy the hosts file to `hosts.orbit-<stamp>.bak` in `backup_dir` and drop
+/// all but the newest `keep` backups
+fn backup_hosts_file(hosts_path: &std::path::Path, backup_dir: &std::path::Path, stamp: &str, keep: usize) -> Result<PathBuf, String> {
+    fs::create_dir_all(backup_dir)
+        .map_err(|e| format!("Failed to create {}: {}", backup_dir.display(), e))?;
+    let backup = backup_dir.join(format!("hosts.orbit-{}.bak", stamp));
+    fs::copy(hosts_path, &backup).map_err(|e| format!("Failed to back up hosts file: {}", e))?;
+
+    let backups = hosts_backups(backup_dir);
+    for old in &backups[..backups.len().saturating_sub(keep)] {
+        let _ = fs::remove_file(old);
+    }
+    Ok(backup)
+}
+
+fn hosts_backup_stamp() -> String {
+    chrono::Local::now().format("%Y%m%d-%H%M%S").to_string()
+}
+
+fn cmd_hosts_list(json: bool) {
     let hosts_path = get_hosts_path();
     let content = match fs::read_to_string(&hosts_path) {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("  {} Failed to read hosts file: {}", "✗".red(), e);
+            print_error(json, &format!("Failed to read hosts file: {}", e));
             return;
         }
     };
 
+    if json {
+        let entries_json: Vec<serde_json::Value> = content.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                (parts.len() >= 2).then(|| serde_json::json!({
+                    "ip": parts[0],
+                    "domain": parts[1],
+                    "local": parts[0] == "127.0.0.1" || parts[0] == "::1",
+                }))
+            })
+            .collect();
+        print_json(&serde_json::json!(entries_json));
+        return;
+    }
+
     print_header();
     println!("  {}", "HOSTS FILE".dimmed().bold());
     println!("  {}", "─".repeat(50).dimmed());
@@ -1946,32 +3806,252 @@ fn cmd_hosts_list() {
     println!();
 }
 
-fn cmd_hosts_add(domain: &str) {
-    let hosts_path = get_hosts_path();
-    let content = match fs::read_to_string(&hosts_path) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("  {} Failed to read hosts file: {}", "✗".red(), e);
-            return;
-        }
-    };
+/// Rewrite the site's fastcgi_pass port and php_version. sites.json is edited as
+/// raw JSON so fields the CLI doesn't model are preserved. Returns the old version.
+fn set_site_php_version(bin_dir: &std::path::Path, domain: &str, version: &str) -> Result<String, String> {
+    let installed = installed_php_versions(bin_dir);
+    if !installed.iter().any(|v| v == version) {
+        let list = if installed.is_empty() { "none".to_string() } else { installed.join(", ") };
+        return Err(format!("PHP {} is not installed. Installed versions: {}", version, list));
+    }
 
-    // Check if already exists
-    for line in content.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with('#') { continue; }
-        let parts: Vec<&str> = trimmed.split_whitespace().collect();
-        if parts.len() >= 2 && parts[1] == domain {
-            println!("  {} Domain '{}' already in hosts file", "—".dimmed(), domain);
-            return;
+    let _lock = lock_sites_store()?;
+    let store_path = get_config_dir().join("sites.json");
+    let content = fs::read_to_string(&store_path)
+        .map_err(|e| format!("Failed to read sites.json: {}", e))?;
+    let mut store: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse sites.json: {}", e))?;
+    let site = store.get_mut("sites")
+        .and_then(|s| s.as_array_mut())
+        .and_then(|sites| sites.iter_mut().find(|s| s.get("domain").and_then(|d| d.as_str()) == Some(domain)))
+        .ok_or_else(|| format!("Site '{}' not found", domain))?;
+
+    let web_server = site.get("web_server").and_then(|v| v.as_str()).unwrap_or("nginx");
+    if web_server != "nginx" {
+        return Err(format!("Site '{}' is served by {}; only nginx sites are supported", domain, web_server));
+    }
+    let previous = site.get("php_version").and_then(|v| v.as_str())
+        .ok_or_else(|| format!("Site '{}' does not use PHP", domain))?
+        .to_string();
+    if previous == version {
+        return Ok(previous);
+    }
+
+    let port = php_port(version).unwrap_or(DEFAULT_PHP_PORT);
+    let conf_path = bin_dir.join("nginx").join("conf").join("sites-enabled").join(format!("{}.conf", domain));
+    let config = fs::read_to_string(&conf_path)
+        .map_err(|e| format!("Failed to read nginx config for '{}': {}", domain, e))?;
+    let updated = replace_fastcgi_port(&config, port)
+        .ok_or_else(|| format!("No fastcgi_pass line found in {}", conf_path.display()))?;
+
+    fs::write(&conf_path, &updated)
+        .map_err(|e| format!("Failed to write nginx config: {}", e))?;
+    if is_service_running("nginx") {
+        if let Err(e) = nginx_test_and_reload(bin_dir) {
+            let _ = fs::write(&conf_path, &config);
+            return Err(format!("{} (rolled back)", e));
         }
     }
 
-    let entry = format!("\n127.0.0.1  {}\n", domain);
-    let mut new_content = content;
-    new_content.push_str(&entry);
+    site["php_version"] = serde_json::json!(version);
+    if site.get("php_port").is_some_and(|p| !p.is_null()) {
+        site["php_port"] = serde_json::json!(port);
+    }
+    site["updated_at"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
+    let json = serde_json::to_string_pretty(&store)
+        .map_err(|e| format!("Failed to serialize sites: {}", e))?;
+    if let Err(e) = fs::write(&store_path, json) {
+        let _ = fs::write(&conf_path, &config);
+        return Err(format!("Failed to write sites.json: {} (nginx config rolled back)", e));
+    }
+    Ok(previous)
+}
 
-    match fs::write(&hosts_path, new_content) {
+fn cmd_sites_php(bin_dir: &std::path::Path, domain: &str, version: &str) {
+    let version = version.trim().trim_start_matches("php-");
+    match set_site_php_version(bin_dir, domain, version) {
+        Ok(previous) if previous == version => {
+            println!("  {} '{}' already uses PHP {}", "—".dimmed(), domain, version);
+        }
+        Ok(previous) => {
+            println!("  {} '{}' switched from PHP {} to PHP {}", "✓".bright_green(), domain.white().bold(), previous, version.bright_green());
+            let php_service = format!("php-{}", version);
+            if !is_service_running(&php_service) {
+                println!("  {} {} is not running — start it with: orbit start {}", "!".yellow(), php_service, php_service);
+            }
+        }
+        Err(e) => eprintln!("  {} {}", "✗".red(), e),
+    }
+}
+
+fn cmd_sites_delete(bin_dir: &std::path::Path, domain: &str, dry_run: bool, skip_confirm: bool, json: bool) {
+    let store_path = get_config_dir().join("sites.json");
+    let load_store = || -> Result<serde_json::Value, String> {
+        fs::read_to_string(&store_path)
+            .map_err(|e| format!("Failed to read sites.json: {}", e))
+            .and_then(|content| serde_json::from_str(&content).map_err(|e| format!("Failed to parse sites.json: {}", e)))
+    };
+    let store = match load_store() {
+        Ok(store) => store,
+        Err(e) => {
+            print_error(json, &e);
+            return;
+        }
+    };
+    let Some(site) = store["sites"].as_array()
+        .and_then(|sites| sites.iter().find(|s| s["domain"].as_str() == Some(domain)))
+        .cloned()
+    else {
+        print_error(json, &format!("Site '{}' not found", domain));
+        return;
+    };
+
+    let mut names = vec![domain.to_string()];
+    if let Some(aliases) = site["aliases"].as_array() {
+        names.extend(aliases.iter().filter_map(|a| a.as_str().map(String::from)));
+    }
+    let web_server = site["web_server"].as_str().unwrap_or("nginx").to_string();
+    let conf_path = match web_server.as_str() {
+        "apache" => bin_dir.join("apache").join("conf").join("vhosts").join(format!("{}.conf", domain)),
+        _ => bin_dir.join("nginx").join("conf").join("sites-enabled").join(format!("{}.conf", domain)),
+    };
+    let hosts_path = get_hosts_path();
+    let hosts_lines_in = |content: &str| -> Vec<String> {
+        content.lines()
+            .filter(|line| names.iter().any(|name| hosts_line_maps(line, name)))
+            .map(|line| line.trim().to_string())
+            .collect()
+    };
+    let hosts_lines = hosts_lines_in(&fs::read_to_string(&hosts_path).unwrap_or_default());
+    let reload = is_service_running(&web_server);
+
+    if dry_run {
+        if json {
+            print_json(&serde_json::json!({
+                "dry_run": true,
+                "domain": domain,
+                "conf_path": conf_path.display().to_string(),
+                "conf_exists": conf_path.exists(),
+                "hosts_file": hosts_path.display().to_string(),
+                "hosts_lines": hosts_lines,
+                "reload": reload.then_some(web_server.as_str()),
+            }));
+            return;
+        }
+        println!("  {} Dry run: nothing will be changed", "—".dimmed());
+        println!("  {} Remove '{}' from {}", "→".dimmed(), domain.white().bold(), store_path.display());
+        if conf_path.exists() {
+            println!("  {} Delete {}", "→".dimmed(), conf_path.display());
+        }
+        for line in &hosts_lines {
+            println!("  {} Remove '{}' from {}", "→".dimmed(), line, hosts_path.display());
+        }
+        if reload {
+            println!("  {} Reload {}", "→".dimmed(), web_server);
+        }
+        println!();
+        return;
+    }
+
+    if !skip_confirm {
+        print!("  {} Delete site {}? [y/N] ", "?".yellow(), domain.white().bold());
+        std::io::stdout().flush().unwrap_or(());
+
+        let mut input = String::new();
+        if std::io::stdin().read_line(&mut input).is_err() || !input.trim().eq_ignore_ascii_case("y") {
+            println!("  {} Cancelled.", "—".dimmed());
+            println!();
+            return;
+        }
+    }
+
+    // Re-read under the lock: another process may have changed sites meanwhile
+    let locked = lock_sites_store().and_then(|lock| load_store().map(|store| (lock, store)));
+    let (_lock, mut store) = match locked {
+        Ok(locked) => locked,
+        Err(e) => {
+            print_error(json, &e);
+            return;
+        }
+    };
+    if let Some(sites) = store["sites"].as_array_mut() {
+        sites.retain(|s| s["domain"].as_str() != Some(domain));
+    }
+    let written = serde_json::to_string_pretty(&store)
+        .map_err(|e| e.to_string())
+        .and_then(|content| write_atomic(&store_path, &content).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        print_error(json, &format!("Failed to write sites.json: {}", e));
+        return;
+    }
+    let conf_removed = conf_path.exists() && fs::remove_file(&conf_path).is_ok();
+
+    // Re-read the hosts file: it may have changed while the prompt was up
+    let mut warnings = Vec::new();
+    let hosts_content = fs::read_to_string(&hosts_path).unwrap_or_default();
+    let hosts_lines = hosts_lines_in(&hosts_content);
+    if !hosts_lines.is_empty() {
+        if let Err(e) = backup_hosts_file(&hosts_path, &hosts_backup_dir(), &hosts_backup_stamp(), HOSTS_BACKUPS_KEPT) {
+            warnings.push(e);
+        }
+        let kept = names.iter().fold(hosts_content, |content, name| hosts_drop_domain(&content, name));
+        if fs::write(&hosts_path, kept).is_err() {
+            warnings.push(format!("Could not update the hosts file; remove the entries with: orbit hosts remove {}", domain));
+        }
+    }
+    if reload && web_server == "nginx" {
+        if let Err(e) = nginx_test_and_reload(bin_dir) {
+            warnings.push(e);
+        }
+    } else if reload {
+        warnings.push(format!("Restart {} to apply: orbit restart {}", web_server, web_server));
+    }
+
+    if json {
+        print_json(&serde_json::json!({
+            "deleted": true,
+            "domain": domain,
+            "conf_path": conf_path.display().to_string(),
+            "conf_removed": conf_removed,
+            "hosts_file": hosts_path.display().to_string(),
+            "hosts_lines": hosts_lines,
+            "warnings": warnings,
+        }));
+        return;
+    }
+    for warning in &warnings {
+        println!("  {} {}", "!".yellow(), warning);
+    }
+    println!("  {} Site '{}' deleted", "✓".bright_green(), domain.white().bold());
+    println!();
+}
+
+fn cmd_hosts_add(domain: &str) {
+    let hosts_path = get_hosts_path();
+    let content = match fs::read_to_string(&hosts_path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("  {} Failed to read hosts file: {}", "✗".red(), e);
+            return;
+        }
+    };
+
+    // Check if already exists
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') { continue; }
+        let parts: Vec<&str> = trimmed.split_whitespace().collect();
+        if parts.len() >= 2 && parts[1] == domain {
+            println!("  {} Domain '{}' already in hosts file", "—".dimmed(), domain);
+            return;
+        }
+    }
+
+    let entry = format!("\n127.0.0.1  {}\n", domain);
+    let mut new_content = content;
+    new_content.push_str(&entry);
+
+    match fs::write(&hosts_path, new_content) {
         Ok(_) => println!("  {} Added '{}' to hosts file", "✓".bright_green(), domain.white().bold()),
         Err(_) => {
             eprintln!("  {} Failed to write hosts file. Run as Administrator.", "✗".red());
@@ -1990,14 +4070,7 @@ fn cmd_hosts_remove(domain: &str) {
         }
     };
 
-    let new_lines: Vec<&str> = content.lines().filter(|line| {
-        let trimmed = line.trim();
-        if trimmed.starts_with('#') || trimmed.is_empty() { return true; }
-        let parts: Vec<&str> = trimmed.split_whitespace().collect();
-        !(parts.len() >= 2 && parts[1] == domain)
-    }).collect();
-
-    let new_content = new_lines.join("\n");
+    let new_content = hosts_drop_domain(&content, domain);
 
     match fs::write(&hosts_path, new_content) {
         Ok(_) => println!("  {} Removed '{}' from hosts file", "✓".bright_green(), domain.white().bold()),
@@ -2007,6 +4080,523 @@ fn cmd_hosts_remove(domain: &str) {
     }
 }
 
+// ─── SSL Commands ─────────────────────────────────────────────────
+
+fn find_mkcert(bin_dir: &std::path::Path) -> Result<PathBuf, String> {
+    let path = bin_dir.join("mkcert").join(exe_name("mkcert"));
+    if path.exists() {
+        return Ok(path);
+    }
+    Err("mkcert not found".to_string())
+}
+
+/// Issue `<domain>.pem` and `<domain>-key.pem` in `ssl_dir` with mkcert, as
+/// the MCP generate_ssl tool does.
+fn mkcert_site_cert(mkcert: &PathBuf, ssl_dir: &std::path::Path, domain: &str) -> Result<(), String> {
+    let output = run_command(
+        hidden_command(mkcert)
+            .arg("-cert-file").arg(ssl_dir.join(format!("{}.pem", domain)))
+            .arg("-key-file").arg(ssl_dir.join(format!("{}-key.pem", domain)))
+            .arg(domain)
+            .arg(format!("*.{}", domain))
+            .arg("localhost")
+            .arg("127.0.0.1")
+            .arg("::1"),
+        "default",
+    )
+        .map_err(|e| format!("Failed to run mkcert: {}", e))?;
+
+    if !output.success() {
+        return Err(format!("mkcert error: {}", output.error_detail()));
+    }
+    Ok(())
+}
+
+/// Reissue one site's certificate, or (without a domain) those of every
+/// SSL-enabled site, then reload nginx once.
+fn cmd_ssl_regenerate(bin_dir: &std::path::Path, domain: Option<&str>) {
+    let sites = match read_sites_store() {
+        Ok(store) => store.sites,
+        Err(e) => {
+            eprintln!("  {} {}", "✗".red(), e);
+            std::process::exit(1);
+        }
+    };
+    let domains: Vec<&str> = match domain {
+        Some(domain) => {
+            if !sites.iter().any(|s| s.domain == domain) {
+                eprintln!("  {} Site '{}' not found.", "✗".red(), domain);
+                std::process::exit(1);
+            }
+            vec![domain]
+        }
+        None => sites.iter().filter(|s| s.ssl_enabled).map(|s| s.domain.as_str()).collect(),
+    };
+    if domains.is_empty() {
+        println!("  {} No SSL-enabled sites found.", "!".yellow());
+        return;
+    }
+
+    let mkcert = match find_mkcert(bin_dir) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("  {} {}", "✗".red(), e);
+            std::process::exit(1);
+        }
+    };
+    let ssl_dir = bin_dir.join("nginx").join("ssl");
+    if let Err(e) = fs::create_dir_all(&ssl_dir) {
+        eprintln!("  {} Failed to create ssl dir: {}", "✗".red(), e);
+        std::process::exit(1);
+    }
+
+    let mut failed = 0;
+    for domain in &domains {
+        match mkcert_site_cert(&mkcert, &ssl_dir, domain) {
+            Ok(()) => println!("  {} {}", "✓".bright_green(), domain),
+            Err(e) => {
+                failed += 1;
+                eprintln!("  {} {}: {}", "✗".red(), domain, e);
+            }
+        }
+    }
+
+    if failed < domains.len() && is_service_running("nginx") {
+        match nginx_test_and_reload(bin_dir) {
+            Ok(()) => println!("  {} nginx reloaded", "✓".bright_green()),
+            Err(e) => eprintln!("  {} nginx reload failed: {}", "✗".red(), e),
+        }
+    }
+    println!();
+    println!("  {} regenerated, {} failed", domains.len() - failed, failed);
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+// ─── Health Report (shared with mcp.rs) ───────────────────────────
+
+/// Score (0–100) the environment: service state, port conflicts, oversized
+/// logs, broken sites and key services that are installed but stopped.
+fn compute_health_report(bin_dir: &PathBuf) -> serde_json::Value {
+    let data_dir = get_orbit_data_dir();
+    let services = scan_server_services(bin_dir);
+    let logs = scan_log_files(bin_dir);
+    let store = read_sites_store().unwrap_or(CliSiteStore { version: "1".into(), sites: vec![] });
+
+    let mut score: i32 = 100;
+    let mut issues: Vec<String> = Vec::new();
+    let mut service_list = Vec::new();
+
+    // Check all services
+    for svc in &services {
+        let running = is_service_running(&svc.name);
+        let port = get_service_port(&svc.name);
+        service_list.push(serde_json::json!({
+            "name": svc.name,
+            "version": svc.version,
+            "status": if running { "running" } else { "stopped" },
+            "port": port
+        }));
+    }
+
+    // Check for port conflicts
+    let mut port_map: std::collections::HashMap<u16, Vec<String>> = std::collections::HashMap::new();
+    for svc in &services {
+        if let Some(port) = get_service_port(&svc.name) {
+            port_map.entry(port).or_default().push(svc.name.clone());
+        }
+    }
+    let mut port_conflicts = Vec::new();
+    for (port, svcs) in &port_map {
+        if svcs.len() > 1 {
+            port_conflicts.push(serde_json::json!({
+                "port": port,
+                "services": svcs
+            }));
+            score -= 15;
+            issues.push(format!("Port {} conflict: {}", port, svcs.join(", ")));
+        }
+    }
+
+    // Disk usage for bin/ and data/
+    let bin_size = dir_size(bin_dir);
+    let data_size = dir_size(&data_dir.join("data"));
+
+    // Large log files (>100MB)
+    let mut large_logs = Vec::new();
+    for log in &logs {
+        if log.size > 100 * 1024 * 1024 {
+            large_logs.push(serde_json::json!({
+                "name": log.name,
+                "size": format_size(log.size)
+            }));
+            score -= 5;
+            issues.push(format!("Large log file: {} ({})", log.name, format_size(log.size)));
+        }
+    }
+
+    // MongoDB storage on disk (>1GB is unusual for a local dev database)
+    let mongo_dir = bin_dir.join("data").join("mongodb");
+    let mut mongo_size = None;
+    if mongo_dir.exists() {
+        let bytes = dir_size(&mongo_dir);
+        if bytes > 1024 * 1024 * 1024 {
+            score -= 5;
+            issues.push(format!("MongoDB data is large ({})", format_size(bytes)));
+        }
+        mongo_size = Some(format_size(bytes));
+    }
+
+    // Site health
+    let mut site_issues_list = Vec::new();
+    for site in &store.sites {
+        let mut site_problems: Vec<String> = Vec::new();
+        let doc_root = std::path::Path::new(&site.path);
+        if !doc_root.exists() {
+            site_problems.push("Document root missing".into());
+            score -= 5;
+        }
+        if let Some(ref php_ver) = site.php_version {
+            let php_name = format!("php-{}", php_ver);
+            if !is_service_running(&php_name) {
+                site_problems.push(format!("PHP {} not running", php_ver));
+                score -= 3;
+            }
+        }
+        if !site_problems.is_empty() {
+            site_issues_list.push(serde_json::json!({
+                "domain": site.domain,
+                "issues": site_problems
+            }));
+        }
+    }
+
+    // Check key services not running
+    let key_services = ["nginx", "mariadb"];
+    for key in key_services {
+        let installed = services.iter().any(|s| s.service_type == key);
+        if installed && !is_service_running(key) {
+            score -= 10;
+            issues.push(format!("{} is installed but not running", key));
+        }
+    }
+
+    // Clamp score
+    if score < 0 { score = 0; }
+
+    serde_json::json!({
+        "score": score,
+        "status": if score >= 80 { "good" } else if score >= 50 { "fair" } else { "poor" },
+        "services": service_list,
+        "port_conflicts": port_conflicts,
+        "disk_usage": {
+            "bin_directory": format_size(bin_size),
+            "data_directory": format_size(data_size),
+            "mongodb": mongo_size
+        },
+        "large_logs": large_logs,
+        "site_issues": site_issues_list,
+        "issues": issues,
+        "sites_count": store.sites.len(),
+        "services_count": services.len(),
+        "services_running": services.iter().filter(|s| is_service_running(&s.name)).count()
+    })
+}
+
+fn dir_size(path: &std::path::Path) -> u64 {
+    if !path.exists() { return 0; }
+    let mut total: u64 = 0;
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                total += dir_size(&path);
+            } else {
+                total += fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            }
+        }
+    }
+    total
+}
+
+// ─── Permissions (shared with mcp.rs) ─────────────────────────────
+
+/// Write-access check for one path Orbit needs to modify.
+#[derive(Serialize)]
+struct PermissionCheck {
+    label: String,
+    path: String,
+    exists: bool,
+    writable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    problem: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remediation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repair: Option<String>,
+}
+
+/// Paths Orbit writes to: (label, path, is_dir)
+fn permission_targets() -> Vec<(&'static str, PathBuf, bool)> {
+    let mut targets = vec![
+        ("data_dir", get_orbit_data_dir(), true),
+        ("config_dir", get_config_dir(), true),
+    ];
+    // Elsewhere hosts edits fall back to sudo, so only Windows
+    // expects the elevated app to write it directly.
+    if cfg!(target_os = "windows") {
+        targets.insert(0, ("hosts_file", get_hosts_path(), false));
+    }
+    let mariadb_data = get_bin_dir().join("data").join("mariadb");
+    if mariadb_data.exists() {
+        targets.push(("mariadb_data_dir", mariadb_data, true));
+    }
+    targets
+}
+
+/// Probe write access without changing content: open files for append,
+/// create and remove a marker file in directories.
+fn probe_write(path: &std::path::Path, is_dir: bool) -> std::io::Result<()> {
+    if is_dir {
+        let probe = path.join(".orbit-write-test");
+        fs::write(&probe, b"ok")?;
+        fs::remove_file(&probe)
+    } else {
+        fs::OpenOptions::new().append(true).open(path).map(|_| ())
+    }
+}
+
+/// Describe why a write failed, including the ACL entries on Windows.
+fn describe_permission_problem(path: &std::path::Path, err: &std::io::Error) -> String {
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(output) = hidden_command("icacls").arg(path).output() {
+            let acl = String::from_utf8_lossy(&output.stdout);
+            let path_str = path.to_string_lossy();
+            let entries: Vec<String> = acl
+                .lines()
+                .map(|l| l.trim().trim_start_matches(path_str.as_ref()).trim().to_string())
+                .filter(|l| !l.is_empty() && !l.starts_with("Successfully processed"))
+                .collect();
+            if !entries.is_empty() {
+                return format!("{} (ACL: {})", err, entries.join("; "));
+            }
+        }
+        format!("{}", err)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::os::unix::fs::MetadataExt;
+        use std::os::unix::fs::PermissionsExt;
+        match fs::metadata(path) {
+            Ok(meta) => format!(
+                "{} (owner uid {}, mode {:o})",
+                err,
+                meta.uid(),
+                meta.permissions().mode() & 0o7777
+            ),
+            Err(_) => format!("{}", err),
+        }
+    }
+}
+
+/// Shell command that grants the current user write access.
+fn permission_remediation(path: &std::path::Path, is_dir: bool) -> String {
+    let p = path.display();
+    #[cfg(target_os = "windows")]
+    {
+        if is_dir {
+            format!("takeown /F \"{}\" /R /D Y && icacls \"{}\" /grant \"%USERNAME%\":(OI)(CI)F /T", p, p)
+        } else {
+            format!("takeown /F \"{}\" && icacls \"{}\" /grant \"%USERNAME%\":F", p, p)
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let recursive = if is_dir { " -R" } else { "" };
+        format!("sudo chown{} \"$(whoami)\" \"{}\" && sudo chmod{} u+w \"{}\"", recursive, p, recursive, p)
+    }
+}
+
+fn check_path_permission(label: &str, path: &std::path::Path, is_dir: bool) -> PermissionCheck {
+    let exists = path.exists();
+    let result = if exists {
+        probe_write(path, is_dir)
+    } else {
+        Err(std::io::Error::new(std::io::ErrorKind::NotFound, "path does not exist"))
+    };
+    let (problem, remediation) = match &result {
+        Ok(()) => (None, None),
+        Err(_) if !exists => (Some("Path does not exist".to_string()), None),
+        Err(e) => (
+            Some(describe_permission_problem(path, e)),
+            Some(permission_remediation(path, is_dir)),
+        ),
+    };
+    PermissionCheck {
+        label: label.to_string(),
+        path: path.to_string_lossy().to_string(),
+        exists,
+        writable: result.is_ok(),
+        problem,
+        remediation,
+        repair: None,
+    }
+}
+
+/// Try to grant the current user write access (needs elevation on Windows).
+fn repair_path_permission(path: &std::path::Path, is_dir: bool) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let path_str = path.to_string_lossy().to_string();
+        let user = std::env::var("USERNAME").map_err(|_| "USERNAME is not set".to_string())?;
+        let mut takeown = hidden_command("takeown");
+        takeown.args(["/F", path_str.as_str()]);
+        if is_dir {
+            takeown.args(["/R", "/D", "Y"]);
+        }
+        let grant = if is_dir { format!("{}:(OI)(CI)F", user) } else { format!("{}:F", user) };
+        let mut icacls = hidden_command("icacls");
+        icacls.args([path_str.as_str(), "/grant", grant.as_str()]);
+        if is_dir {
+            icacls.arg("/T");
+        }
+        for (name, mut cmd) in [("takeown", takeown), ("icacls", icacls)] {
+            let output = cmd.output().map_err(|e| format!("Failed to run {}: {}", name, e))?;
+            if !output.status.success() {
+                let msg = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                let msg = if msg.is_empty() { String::from_utf8_lossy(&output.stdout).trim().to_string() } else { msg };
+                return Err(format!("{} failed (run as Administrator?): {}", name, msg));
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let mut chmod = hidden_command("chmod");
+        if is_dir {
+            chmod.arg("-R");
+        }
+        let output = chmod
+            .arg("u+w")
+            .arg(path)
+            .output()
+            .map_err(|e| format!("Failed to run chmod: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "chmod failed (needs sudo?): {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+}
+
+/// Check every Orbit path, optionally repairing the ones that are not writable.
+fn check_permissions(fix: bool) -> Vec<PermissionCheck> {
+    permission_targets()
+        .into_iter()
+        .map(|(label, path, is_dir)| {
+            let check = check_path_permission(label, &path, is_dir);
+            if !fix || check.writable || !check.exists {
+                return check;
+            }
+            let outcome = repair_path_permission(&path, is_dir);
+            let mut rechecked = check_path_permission(label, &path, is_dir);
+            rechecked.repair = Some(match outcome {
+                Ok(()) if rechecked.writable => "repaired".to_string(),
+                Ok(()) => "repair ran but path is still not writable".to_string(),
+                Err(e) => e,
+            });
+            rechecked
+        })
+        .collect()
+}
+
+fn cmd_doctor(bin_dir: &PathBuf, fix_permissions: bool, json: bool) {
+    let report = compute_health_report(bin_dir);
+    // Same shape as the MCP get_health_report tool
+    if json {
+        print_json(&report);
+        return;
+    }
+
+    print_header();
+
+    let score = report["score"].as_i64().unwrap_or(0);
+    let score_str = format!("{}/100", score);
+    let score_colored = if score >= 80 {
+        score_str.bright_green().bold()
+    } else if score >= 50 {
+        score_str.yellow().bold()
+    } else {
+        score_str.red().bold()
+    };
+    println!("  {}", "HEALTH".dimmed().bold());
+    println!("  {}", "─".repeat(50).dimmed());
+    println!("  {:<16} {} {}", "Score:".white().bold(), score_colored, report["status"].as_str().unwrap_or("").dimmed());
+    println!(
+        "  {:<16} {} / {} running, {} sites",
+        "Services:".white().bold(),
+        report["services_running"],
+        report["services_count"],
+        report["sites_count"]
+    );
+    println!(
+        "  {:<16} bin {}, data {}",
+        "Disk:".white().bold(),
+        report["disk_usage"]["bin_directory"].as_str().unwrap_or("—"),
+        report["disk_usage"]["data_directory"].as_str().unwrap_or("—")
+    );
+    println!();
+
+    let issues = report["issues"].as_array().cloned().unwrap_or_default();
+    let site_issues = report["site_issues"].as_array().cloned().unwrap_or_default();
+    println!("  {}", "ISSUES".dimmed().bold());
+    println!("  {}", "─".repeat(50).dimmed());
+    if issues.is_empty() && site_issues.is_empty() {
+        println!("  {}  No issues found", "✓".bright_green());
+    }
+    for issue in &issues {
+        println!("  {}  {}", "✗".red(), issue.as_str().unwrap_or(""));
+    }
+    for site in &site_issues {
+        let problems: Vec<&str> = site["issues"].as_array()
+            .map(|p| p.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        println!("  {}  {} {}", "✗".red(), site["domain"].as_str().unwrap_or("").white().bold(), problems.join(", ").dimmed());
+    }
+    println!();
+
+    println!("  {}", "PERMISSIONS".dimmed().bold());
+    println!("  {}", "─".repeat(50).dimmed());
+
+    let checks = check_permissions(fix_permissions);
+    for check in &checks {
+        let mark = if check.writable { "✓".bright_green() } else { "✗".red() };
+        println!("  {}  {:<18} {}", mark, check.label.white().bold(), check.path.dimmed());
+        if let Some(problem) = &check.problem {
+            println!("     {}", problem.yellow());
+        }
+        if let Some(repair) = &check.repair {
+            println!("     {} {}", "repair:".dimmed(), repair);
+        }
+        if !check.writable {
+            if let Some(fix) = &check.remediation {
+                println!("     {} {}", "fix:".dimmed(), fix.cyan());
+            }
+        }
+    }
+    println!();
+
+    if checks.iter().any(|c| !c.writable && c.exists) && !fix_permissions {
+        println!("  {} Run {} to attempt a repair.", "→".dimmed(), "orbit-cli doctor --fix-permissions".cyan());
+        println!();
+    }
+}
+
 // ─── Composer Command ─────────────────────────────────────────────
 
 fn cmd_composer(bin_dir: &PathBuf, args: Vec<String>) {
@@ -2071,11 +4661,12 @@ const CLI_FALLBACK_REGISTRY: &str = include_str!("../dist/libraries.json");
 struct RegistryPlatformDownload {
     url: String,
     filename: String,
+    #[serde(default)]
+    sha256: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct RegistryVersionInfo {
-    #[allow(dead_code)]
     latest: String,
     #[serde(default)]
     windows: Option<RegistryPlatformDownload>,
@@ -2086,6 +4677,8 @@ struct RegistryVersionInfo {
 #[derive(Deserialize)]
 struct RegistryServiceInfo {
     name: String,
+    #[serde(default)]
+    latest: Option<String>,
     #[serde(default, rename = "availableVersions")]
     available_versions: Option<Vec<String>>,
     #[serde(default)]
@@ -2104,10 +4697,14 @@ struct CliRegistry {
 
 fn load_registry() -> Result<CliRegistry, String> {
     // Try remote first, fall back to embedded
-    let client = reqwest::blocking::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .ok();
+    let client = if is_offline() {
+        None
+    } else {
+        reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .ok()
+    };
 
     if let Some(client) = client {
         if let Ok(resp) = client.get("https://raw.githubusercontent.com/alinsgit/orbit-libraries/main/dist/libraries.json").send() {
@@ -2126,7 +4723,33 @@ fn load_registry() -> Result<CliRegistry, String> {
         .map_err(|e| format!("Failed to parse registry: {}", e))
 }
 
-fn get_download_info(service_info: &RegistryServiceInfo, version: Option<&str>) -> Option<(String, String)> {
+/// Download of a service resolved from the registry; recorded as-is in
+/// config/versions.lock once installed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LockedVersion {
+    version: String,
+    url: String,
+    filename: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+    #[serde(default)]
+    installed_at: String,
+}
+
+impl LockedVersion {
+    fn from_download(version: &str, dl: &RegistryPlatformDownload) -> Self {
+        LockedVersion {
+            version: version.to_string(),
+            url: dl.url.clone(),
+            filename: dl.filename.clone(),
+            sha256: dl.sha256.clone(),
+            installed_at: String::new(),
+        }
+    }
+}
+
+/// Download for a service version (the newest available if none is given)
+fn get_download_info(service_info: &RegistryServiceInfo, version: Option<&str>) -> Option<LockedVersion> {
     // Multi-version service
     if let Some(versions) = &service_info.versions {
         let ver = version.or_else(|| {
@@ -2139,28 +4762,108 @@ fn get_download_info(service_info: &RegistryServiceInfo, version: Option<&str>)
 
         #[cfg(target_os = "windows")]
         if let Some(dl) = &version_info.windows {
-            return Some((dl.url.clone(), dl.filename.clone()));
+            return Some(LockedVersion::from_download(&version_info.latest, dl));
         }
         if let Some(dl) = &version_info.all_platforms {
-            return Some((dl.url.clone(), dl.filename.clone()));
+            return Some(LockedVersion::from_download(&version_info.latest, dl));
         }
         return None;
     }
 
     // Single-version service
+    let latest = service_info.latest.as_deref().unwrap_or("latest");
     #[cfg(target_os = "windows")]
     if let Some(dl) = &service_info.windows {
-        return Some((dl.url.clone(), dl.filename.clone()));
+        return Some(LockedVersion::from_download(latest, dl));
     }
     if let Some(dl) = &service_info.all_platforms {
-        return Some((dl.url.clone(), dl.filename.clone()));
+        return Some(LockedVersion::from_download(latest, dl));
     }
     None
 }
 
+/// Newest registry release in the line of an installed version, e.g. 8.4.12
+/// for an installed 8.4.3 (the longest version key the install starts with)
+fn registry_latest_for(service_info: &RegistryServiceInfo, installed: &str) -> Option<String> {
+    match &service_info.versions {
+        Some(versions) => versions.iter()
+            .filter(|(key, _)| installed == key.as_str() || installed.starts_with(&format!("{}.", key)))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, info)| info.latest.clone()),
+        None => service_info.latest.clone(),
+    }
+}
+
+// ─── Version Lock ────────────────────────────────────────────────
+
+/// Installed service (e.g. "nginx", "php-8.4") → the exact download used
+type VersionLock = std::collections::BTreeMap<String, LockedVersion>;
+
+fn versions_lock_path() -> PathBuf {
+    get_config_dir().join("versions.lock")
+}
+
+fn read_versions_lock() -> VersionLock {
+    fs::read_to_string(versions_lock_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Record what was just installed. Entries outlive an uninstall so that
+/// `orbit install --locked` can bring the same version back.
+fn record_locked_version(install_type: &str, installed: &LockedVersion) {
+    let mut lock = read_versions_lock();
+    let mut entry = installed.clone();
+    entry.installed_at = chrono::Utc::now().to_rfc3339();
+    lock.insert(install_type.to_string(), entry);
+
+    let path = versions_lock_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    let result = serde_json::to_string_pretty(&lock)
+        .map_err(|e| e.to_string())
+        .and_then(|content| fs::write(&path, content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        println!("  {} Could not update {}: {}", "!".yellow(), path.display(), e);
+    }
+}
+
+/// The lock entry `orbit install --locked` should use. PHP entries are keyed
+/// per version, so `--version` picks one when several are locked.
+fn find_locked_version(lock: &VersionLock, service_key: &str, version: Option<&str>) -> Result<(String, LockedVersion), String> {
+    let key = if service_key == "php" {
+        match version {
+            Some(ver) => format!("php-{}", ver),
+            None => {
+                let php: Vec<&String> = lock.keys().filter(|k| k.starts_with("php-")).collect();
+                match php.as_slice() {
+                    [only] => only.to_string(),
+                    [] => "php".to_string(),
+                    several => return Err(format!(
+                        "Several PHP versions are locked ({}); pick one with --version",
+                        several.iter().map(|k| k.trim_start_matches("php-")).collect::<Vec<_>>().join(", ")
+                    )),
+                }
+            }
+        }
+    } else if let Some(ver) = version {
+        return Err(format!(
+            "--version {} can't be combined with --locked for {}: only one version of it is locked",
+            ver, service_key
+        ));
+    } else {
+        service_key.to_string()
+    };
+    lock.get(&key)
+        .map(|entry| (key.clone(), entry.clone()))
+        .ok_or_else(|| format!("'{}' is not in {}", key, versions_lock_path().display()))
+}
+
 // ─── Install/Uninstall Commands ──────────────────────────────────
 
-fn cmd_install(bin_dir: &PathBuf, service: &str, version: Option<String>) {
+fn cmd_install(bin_dir: &PathBuf, service: &str, version: Option<String>, locked: bool) {
     println!();
 
     // Normalize service name
@@ -2202,8 +4905,23 @@ fn cmd_install(bin_dir: &PathBuf, service: &str, version: Option<String>) {
         }
     };
 
+    let locked_entry = if locked {
+        match find_locked_version(&read_versions_lock(), service_key, version.as_deref()) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                println!("  {} {}", "✗".red(), e);
+                println!();
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
     // For PHP, build the service_type with version prefix
-    let install_type = if service_key == "php" {
+    let install_type = if let Some((key, _)) = &locked_entry {
+        key.clone()
+    } else if service_key == "php" {
         let ver = version.as_deref().unwrap_or_else(|| {
             service_info.available_versions.as_ref()
                 .and_then(|v| v.first())
@@ -2215,8 +4933,8 @@ fn cmd_install(bin_dir: &PathBuf, service: &str, version: Option<String>) {
         service_key.to_string()
     };
 
-    // Get download URL
-    let (url, filename) = match get_download_info(service_info, version.as_deref()) {
+    // Get download URL (the locked one as-is with --locked)
+    let download = match locked_entry.map(|(_, entry)| entry).or_else(|| get_download_info(service_info, version.as_deref())) {
         Some(info) => info,
         None => {
             println!("  {} No download available for '{}' on this platform.", "✗".red(), service);
@@ -2228,7 +4946,12 @@ fn cmd_install(bin_dir: &PathBuf, service: &str, version: Option<String>) {
         }
     };
 
-    let ver_display = version.as_deref().unwrap_or("latest");
+    let (url, filename, sha256) = (&download.url, &download.filename, &download.sha256);
+    let ver_display = if locked {
+        format!("{} (locked)", download.version)
+    } else {
+        version.clone().unwrap_or_else(|| "latest".to_string())
+    };
     println!("  {} Installing {} {}...", "→".dimmed(), service_info.name.white().bold(), ver_display.dimmed());
     println!("  {} {}", "↓".dimmed(), url.dimmed());
 
@@ -2236,22 +4959,35 @@ fn cmd_install(bin_dir: &PathBuf, service: &str, version: Option<String>) {
     let downloads_dir = bin_dir.join("downloads");
     fs::create_dir_all(&downloads_dir).ok();
 
-    let dest_path = downloads_dir.join(&filename);
+    let dest_path = downloads_dir.join(filename);
 
     // Download
     print!("  {} Downloading...", "⟳".dimmed());
     std::io::stdout().flush().unwrap_or(());
 
-    match cli_download_file(&url, &dest_path) {
+    // Clears what's left of the progress bar when the status line is reprinted
+    let clear = if std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+        " ".repeat(PROGRESS_BAR_WIDTH + 30)
+    } else {
+        String::new()
+    };
+    match cli_download_file(url, &dest_path, sha256.as_deref(), &mut download_progress_printer()) {
         Ok(size) => {
-            println!("\r  {} Downloaded ({})", "✓".bright_green(), format_size(size));
+            println!("\r  {} Downloaded ({}){}", "✓".bright_green(), format_size(size), clear);
         }
         Err(e) => {
-            println!("\r  {} Download failed: {}", "✗".red(), e);
+            println!("\r  {} Download failed: {}{}", "✗".red(), e, clear);
+            if !is_offline() && !network_reachable() {
+                println!("  {} No network connection detected. Use --offline to skip network access.", "→".dimmed());
+            }
             return;
         }
     }
 
+    if sha256.is_some() {
+        println!("  {} Checksum verified", "✓".bright_green());
+    }
+
     // Determine extraction target and strip_root
     let (extract_target, strip_root) = match install_type.as_str() {
         "nginx" => (bin_dir.join("nginx"), true),
@@ -2279,10 +5015,11 @@ fn cmd_install(bin_dir: &PathBuf, service: &str, version: Option<String>) {
     // Handle raw executables (rust, composer)
     if service_key == "rust" {
         fs::create_dir_all(&extract_target).ok();
-        let target_exe = extract_target.join(&filename);
+        let target_exe = extract_target.join(filename);
         match fs::copy(&dest_path, &target_exe) {
             Ok(_) => {
                 let _ = fs::remove_file(&dest_path);
+                record_locked_version(&install_type, &download);
                 println!("  {} Installed to {}", "✓".bright_green(), extract_target.display().to_string().cyan());
                 println!();
                 return;
@@ -2300,6 +5037,7 @@ fn cmd_install(bin_dir: &PathBuf, service: &str, version: Option<String>) {
         match fs::copy(&dest_path, &target_phar) {
             Ok(_) => {
                 let _ = fs::remove_file(&dest_path);
+                record_locked_version(&install_type, &download);
                 println!("  {} Installed to {}", "✓".bright_green(), extract_target.display().to_string().cyan());
                 println!();
                 return;
@@ -2352,21 +5090,96 @@ fn cmd_install(bin_dir: &PathBuf, service: &str, version: Option<String>) {
     // Note: PostgreSQL ZIP extracts to postgresql/pgsql/bin/ (nested).
     // Scanner handles both flattened and nested structures.
 
+    record_locked_version(&install_type, &download);
+
     // Verify installation
     let services = scan_services(bin_dir);
     let found = services.iter().find(|s| {
         s.service_type == service_key || s.name == install_type
     });
 
-    if let Some(svc) = found {
-        println!("  {} {} {} installed successfully",
-            "✓".bright_green(),
-            svc.name.white().bold(),
-            svc.version.dimmed()
-        );
-    } else {
-        println!("  {} Installed to {}", "✓".bright_green(), extract_target.display().to_string().cyan());
+    if let Some(svc) = found {
+        println!("  {} {} {} installed successfully",
+            "✓".bright_green(),
+            svc.name.white().bold(),
+            svc.version.dimmed()
+        );
+    } else {
+        println!("  {} Installed to {}", "✓".bright_green(), extract_target.display().to_string().cyan());
+    }
+    println!();
+}
+
+/// Ask the GUI to refetch its cached version lists: entries fetched before the
+/// time in config/versions-refresh count as expired.
+fn request_versions_refresh() -> Result<(), String> {
+    let dir = get_config_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    fs::write(dir.join("versions-refresh"), chrono::Utc::now().timestamp().to_string())
+        .map_err(|e| format!("Failed to write refresh marker: {}", e))
+}
+
+fn cmd_versions(bin_dir: &PathBuf, refresh: bool, json: bool) {
+    if refresh {
+        if let Err(e) = request_versions_refresh() {
+            print_error(json, &e);
+            std::process::exit(1);
+        }
+        if !json {
+            println!();
+            println!("  {} Version cache expired; Orbit refetches version lists on next use", "✓".bright_green());
+        }
+    }
+    let services = scan_services(bin_dir);
+    let lock = read_versions_lock();
+    // A missing registry only costs the "latest" column
+    let registry = load_registry().ok();
+
+    let rows: Vec<(String, String, Option<String>, Option<String>)> = services.iter().map(|svc| {
+        let latest = registry.as_ref()
+            .and_then(|r| r.services.get(&svc.service_type))
+            .and_then(|info| registry_latest_for(info, &svc.version));
+        let locked = lock.get(&svc.name).map(|entry| entry.version.clone());
+        (svc.name.clone(), svc.version.clone(), latest, locked)
+    }).collect();
+    let update_available = |installed: &str, latest: &Option<String>| {
+        latest.as_deref().is_some_and(|latest| latest != installed && latest != "latest")
+    };
+
+    if json {
+        let list: Vec<serde_json::Value> = rows.iter().map(|(name, installed, latest, locked)| {
+            serde_json::json!({
+                "service": name,
+                "installed": installed,
+                "latest": latest,
+                "locked": locked,
+                "update_available": update_available(installed, latest),
+            })
+        }).collect();
+        print_json(&serde_json::json!(list));
+        return;
+    }
+
+    print_header();
+    println!("  {}", "SERVICE VERSIONS".dimmed().bold());
+    println!("  {}", "─".repeat(55).dimmed());
+    if rows.is_empty() {
+        println!("  {}", "No services installed".dimmed());
+    }
+    for (name, installed, latest, locked) in &rows {
+        let status = if update_available(installed, latest) {
+            format!("{} {}", "↑".yellow(), latest.as_deref().unwrap_or_default().yellow())
+        } else if latest.is_some() {
+            format!("{}", "✓ up to date".bright_green())
+        } else {
+            format!("{}", "unknown".dimmed())
+        };
+        let locked = locked.as_deref()
+            .map(|v| format!("locked {}", v))
+            .unwrap_or_default();
+        println!("  {:<14} {:<14} {:<16} {}", name.white().bold(), installed, locked.dimmed(), status);
     }
+    println!("  {}", "─".repeat(55).dimmed());
     println!();
 }
 
@@ -2418,7 +5231,7 @@ fn cmd_uninstall(bin_dir: &PathBuf, service: &str, skip_confirm: bool) {
     // Stop service if running
     if is_service_running(service_key) {
         match stop_service_process(service_key) {
-            Ok(()) => println!("  {} Stopped {}", "✓".bright_green(), service_key.white()),
+            Ok(_) => println!("  {} Stopped {}", "✓".bright_green(), service_key.white()),
             Err(_) => println!("  {} Could not stop {} (may need manual cleanup)", "!".yellow(), service_key),
         }
     }
@@ -2435,9 +5248,95 @@ fn cmd_uninstall(bin_dir: &PathBuf, service: &str, skip_confirm: bool) {
     println!();
 }
 
+// ─── Offline Mode ────────────────────────────────────────────────
+
+/// Set from `--offline`
+static OFFLINE_FLAG: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// `--offline`, or offline mode saved from the GUI in config/network.json
+fn is_offline() -> bool {
+    OFFLINE_FLAG.load(std::sync::atomic::Ordering::Relaxed)
+        || fs::read_to_string(get_config_dir().join("network.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|settings| settings["offline"].as_bool())
+            .unwrap_or(false)
+}
+
+/// Whether the registry host resolves and accepts a connection
+fn network_reachable() -> bool {
+    use std::net::ToSocketAddrs;
+    ("raw.githubusercontent.com", 443)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .is_some_and(|addr| std::net::TcpStream::connect_timeout(&addr, std::time::Duration::from_secs(3)).is_ok())
+}
+
 // ─── CLI Download & Extract ──────────────────────────────────────
 
-fn cli_download_file(url: &str, dest_path: &PathBuf) -> Result<u64, String> {
+/// Prefix of the machine-readable progress lines `orbit install` writes to
+/// stderr when stdout is not a terminal: `[progress] <downloaded> <total|->`.
+/// orbit-mcp turns them into progress notifications.
+const PROGRESS_PREFIX: &str = "[progress]";
+
+/// Minimum gap between progress bar redraws / progress lines
+const PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+const PROGRESS_BAR_WIDTH: usize = 30;
+
+/// Progress bar for a download; bytes only when the size is unknown.
+fn progress_bar(downloaded: u64, total: Option<u64>) -> String {
+    match total {
+        Some(total) if total > 0 => {
+            let ratio = (downloaded as f64 / total as f64).min(1.0);
+            let filled = (ratio * PROGRESS_BAR_WIDTH as f64) as usize;
+            format!("{}{} {:>3}%  {} / {}",
+                "█".repeat(filled).cyan(),
+                "░".repeat(PROGRESS_BAR_WIDTH - filled).dimmed(),
+                (ratio * 100.0) as u32,
+                format_size(downloaded),
+                format_size(total))
+        }
+        _ => format!("{} downloaded", format_size(downloaded)),
+    }
+}
+
+/// Progress reporter for `cli_download_file`: redraws a bar on a terminal,
+/// otherwise writes `PROGRESS_PREFIX` lines to stderr.
+fn download_progress_printer() -> impl FnMut(u64, Option<u64>) {
+    use std::io::IsTerminal;
+    let interactive = std::io::stdout().is_terminal();
+    let mut last: Option<std::time::Instant> = None;
+    move |downloaded, total| {
+        let finished = total == Some(downloaded);
+        if !finished && last.is_some_and(|t| t.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        last = Some(std::time::Instant::now());
+        if interactive {
+            print!("\r  {} {}", "⟳".dimmed(), progress_bar(downloaded, total));
+            std::io::stdout().flush().unwrap_or(());
+        } else {
+            let total = total.map(|t| t.to_string()).unwrap_or_else(|| "-".to_string());
+            eprintln!("{} {} {}", PROGRESS_PREFIX, downloaded, total);
+        }
+    }
+}
+
+/// Download `url` to `dest_path`, trying the MariaDB mirror as a fallback.
+/// Bytes go to `<file>.part`, which is renamed only after the size and (when
+/// given) SHA-256 check out.
+fn cli_download_file(
+    url: &str,
+    dest_path: &PathBuf,
+    expected_sha256: Option<&str>,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<u64, String> {
+    if is_offline() {
+        return Err("offline mode is on; run without --offline (or turn off offline mode in Settings) to download".to_string());
+    }
+
     // Try mirror URL for MariaDB
     let urls_to_try: Vec<String> = {
         let mut urls = vec![url.to_string()];
@@ -2456,14 +5355,68 @@ fn cli_download_file(url: &str, dest_path: &PathBuf) -> Result<u64, String> {
         .build()
         .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
+    if let Some(parent) = dest_path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    let mut part_name = dest_path.file_name().unwrap_or_default().to_os_string();
+    part_name.push(".part");
+    let part = dest_path.with_file_name(part_name);
+
     let mut last_error = String::new();
 
     for attempt_url in &urls_to_try {
-        let resp = match client.get(attempt_url)
+        let downloaded = match cli_download_with_retries(&client, attempt_url, &part, on_progress) {
+            Ok(downloaded) => downloaded,
+            Err(e) => {
+                last_error = e;
+                continue;
+            }
+        };
+        if let Some(expected) = expected_sha256 {
+            if let Err(e) = cli_verify_sha256(&part, expected) {
+                let _ = fs::remove_file(&part);
+                return Err(e);
+            }
+        }
+        fs::rename(&part, dest_path)
+            .map_err(|e| format!("Failed to move download into place: {}", e))?;
+        return Ok(downloaded);
+    }
+
+    let _ = fs::remove_file(&part);
+    Err(last_error)
+}
+
+/// Up to 3 attempts with backoff, on network errors, 5xx and 429. A retry
+/// resumes the .part file with a Range request when the server sent
+/// `Accept-Ranges: bytes`, otherwise starts over.
+fn cli_download_with_retries(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part: &std::path::Path,
+    on_progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<u64, String> {
+    let _ = fs::remove_file(part);
+    let mut accepts_ranges = false;
+    let mut last_error = String::new();
+
+    for attempt in 0..3u32 {
+        if attempt > 0 {
+            std::thread::sleep(std::time::Duration::from_secs(2u64.pow(attempt)));
+        }
+
+        let resume_from = if accepts_ranges {
+            fs::metadata(part).map(|m| m.len()).unwrap_or(0)
+        } else {
+            0
+        };
+        let mut request = client.get(url)
             .header("Accept", "application/octet-stream, application/zip, */*;q=0.1")
-            .header("Accept-Encoding", "identity")
-            .send()
-        {
+            .header("Accept-Encoding", "identity");
+        if resume_from > 0 {
+            request = request.header("Range", format!("bytes={}-", resume_from));
+        }
+        let mut resp = match request.send() {
             Ok(r) => r,
             Err(e) => {
                 last_error = format!("Request failed: {}", e);
@@ -2473,34 +5426,104 @@ fn cli_download_file(url: &str, dest_path: &PathBuf) -> Result<u64, String> {
 
         if !resp.status().is_success() {
             last_error = format!("HTTP {}", resp.status());
-            continue;
+            if resp.status().as_u16() == 416 {
+                let _ = fs::remove_file(part);
+                accepts_ranges = false;
+                continue;
+            }
+            // Server errors and rate limits may clear up; other HTTP errors
+            // won't change on retry, so try the next URL
+            if resp.status().is_server_error() || resp.status().as_u16() == 429 {
+                continue;
+            }
+            return Err(last_error);
         }
 
         // Reject HTML responses
         if let Some(ct) = resp.headers().get("content-type") {
             if ct.to_str().unwrap_or("").contains("text/html") {
-                last_error = "Received HTML instead of file".to_string();
-                continue;
+                return Err("Received HTML instead of file".to_string());
             }
         }
 
-        let total_size = resp.content_length().unwrap_or(0);
+        accepts_ranges = accepts_ranges || resp.headers().get("accept-ranges")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case("bytes"));
+        // Continue the .part only if the server answered with the range we asked for
+        let offset = if resp.status().as_u16() == 206 {
+            let start = resp.headers().get("content-range")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("bytes "))
+                .and_then(|v| v.split('-').next())
+                .and_then(|v| v.trim().parse::<u64>().ok());
+            if start != Some(resume_from) || resume_from == 0 {
+                last_error = "Server returned an unexpected byte range".to_string();
+                let _ = fs::remove_file(part);
+                accepts_ranges = false;
+                continue;
+            }
+            resume_from
+        } else {
+            0
+        };
+        let total = resp.content_length().filter(|&size| size > 0).map(|size| offset + size);
+
+        let file = if offset > 0 {
+            fs::OpenOptions::new().append(true).open(part)
+        } else {
+            fs::File::create(part)
+        };
+        let mut file = file.map_err(|e| format!("Failed to create file: {}", e))?;
+
+        // Stream to disk in chunks so progress can be reported
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut downloaded: u64 = offset;
+        let read_result = loop {
+            match resp.read(&mut buf) {
+                Ok(0) => break Ok(()),
+                Ok(n) => {
+                    if let Err(e) = file.write_all(&buf[..n]) {
+                        break Err(format!("Failed to write file: {}", e));
+                    }
+                    downloaded += n as u64;
+                    on_progress(downloaded, total);
+                }
+                Err(e) => break Err(format!("Failed to read response: {}", e)),
+            }
+        };
+        drop(file);
 
-        if let Some(parent) = dest_path.parent() {
-            fs::create_dir_all(parent).ok();
+        if let Err(e) = read_result {
+            last_error = e;
+        } else if let Some(total) = total.filter(|&t| t != downloaded) {
+            last_error = format!("Download incomplete: {} of {} bytes", downloaded, total);
+        } else {
+            return Ok(downloaded);
         }
+        // Keep the partial file only if the next attempt can resume it
+        if !accepts_ranges {
+            let _ = fs::remove_file(part);
+        }
+    }
 
-        let bytes = resp.bytes()
-            .map_err(|e| format!("Failed to read response: {}", e))?;
+    Err(last_error)
+}
 
-        fs::write(dest_path, &bytes)
-            .map_err(|e| format!("Failed to write file: {}", e))?;
+fn cli_verify_sha256(path: &std::path::Path, expected: &str) -> Result<(), String> {
+    use sha2::{Digest, Sha256};
 
-        let written = if total_size > 0 { total_size } else { bytes.len() as u64 };
-        return Ok(written);
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let actual: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!("Checksum mismatch: expected SHA-256 {}, got {}. The download may be corrupted or tampered with.",
+            expected.trim(), actual))
     }
-
-    Err(last_error)
 }
 
 fn cli_extract_zip(zip_path: &PathBuf, extract_path: &PathBuf, strip_root: bool) -> Result<(), String> {
@@ -2697,44 +5720,615 @@ fn cli_configure_apache(apache_path: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
+fn cmd_watch(bin_dir: &PathBuf, services: Option<Vec<String>>, clear: bool) {
+    if clear {
+        match write_watch_config(&WatchConfig::default()) {
+            Ok(_) => println!("  {} No services are watched any more", "✓".bright_green()),
+            Err(e) => eprintln!("  {} {}", "✗".red(), e),
+        }
+        return;
+    }
+
+    let installed = scan_services_cached(bin_dir);
+    if let Some(names) = services {
+        let unknown: Vec<&String> = names.iter().filter(|n| !installed.iter().any(|s| s.name == **n)).collect();
+        if !unknown.is_empty() {
+            eprintln!(
+                "  {} Not installed: {}. Installed: {}",
+                "✗".red(),
+                unknown.iter().map(|n| n.as_str()).collect::<Vec<_>>().join(", "),
+                installed.iter().map(|s| s.name.as_str()).collect::<Vec<_>>().join(", ")
+            );
+            return;
+        }
+        let config = WatchConfig { services: names, restarts: Default::default() };
+        if let Err(e) = write_watch_config(&config) {
+            eprintln!("  {} {}", "✗".red(), e);
+            return;
+        }
+    }
+
+    let config = read_watch_config();
+    if config.services.is_empty() {
+        println!("  {} No services to watch.", "!".yellow());
+        println!("  {} orbit watch --services mariadb,php-8.4", "→".dimmed());
+        return;
+    }
+
+    println!(
+        "  {} Watching {} every {}s (Ctrl+C to stop)",
+        "●".bright_green(),
+        config.services.join(", ").white().bold(),
+        WATCH_INTERVAL_SECS
+    );
+    for name in config.services.iter().filter(|n| !is_service_running(n)) {
+        println!("  {} {} is not running; it is watched once started", "→".dimmed(), name);
+    }
+
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    {
+        let stop = stop.clone();
+        let _ = ctrlc::set_handler(move || stop.store(true, std::sync::atomic::Ordering::Relaxed));
+    }
+
+    if !claim_watch_lock() {
+        println!("  {} Another watcher (orbit watch or the Orbit app) is supervising; this one takes over if it exits", "→".dimmed());
+    }
+
+    let mut given_up = std::collections::HashSet::new();
+    while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+        watch_tick(&mut given_up, bin_dir);
+        // Sleep in short steps so Ctrl+C exits promptly
+        for _ in 0..WATCH_INTERVAL_SECS * 2 {
+            if stop.load(std::sync::atomic::Ordering::Relaxed) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
+    }
+    release_watch_lock();
+    println!();
+    println!("  {} Stopped watching", "✓".bright_green());
+}
+
+/// Give up config/watch.pid if this process holds it
+fn release_watch_lock() {
+    let path = get_config_dir().join("watch.pid");
+    if fs::read_to_string(&path).is_ok_and(|c| c.trim() == std::process::id().to_string()) {
+        let _ = fs::remove_file(&path);
+    }
+}
+
+fn cmd_cron_run() {
+    let tasks = read_scheduled_tasks();
+    if tasks.is_empty() {
+        println!("  {} No scheduled tasks. Add one with the add_scheduled_task MCP tool.", "!".yellow());
+        return;
+    }
+
+    // cron_tick decides what is due under cron.json.lock; the tasks it
+    // starts keep running after this process exits
+    let started = cron_tick(&mut std::collections::HashMap::new());
+    println!("  {} Started {} of {} scheduled tasks", "✓".bright_green(), started.len(), tasks.len());
+    for name in &started {
+        println!("  {} {}: {}", "→".dimmed(), name, scheduled_task_log_path(name).display());
+    }
+}
+
+// ─── Watch Supervisor (shared with mcp.rs) ────────────────────────
+
+/// Seconds between supervisor checks
+const WATCH_INTERVAL_SECS: u64 = 10;
+/// Restarts in a row before the supervisor gives up on a service
+const WATCH_MAX_RESTARTS: u32 = 5;
+/// Wait before the second restart; doubles with each further one
+const WATCH_BACKOFF_BASE_SECS: u64 = 5;
+const WATCH_BACKOFF_MAX_SECS: u64 = 300;
+/// A service that stays up this long after a restart has its count cleared
+const WATCH_STABLE_SECS: u64 = 600;
+
+/// config/watch.json: which services to keep alive and how often each was restarted
+#[derive(Serialize, Deserialize, Default)]
+struct WatchConfig {
+    #[serde(default)]
+    services: Vec<String>,
+    #[serde(default)]
+    restarts: std::collections::HashMap<String, WatchRestarts>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct WatchRestarts {
+    count: u32,
+    /// Unix seconds of the last restart
+    last: u64,
+}
+
+fn read_watch_config() -> WatchConfig {
+    fs::read_to_string(get_config_dir().join("watch.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_watch_config(config: &WatchConfig) -> Result<(), String> {
+    let path = get_config_dir().join("watch.json");
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let content = serde_json::to_string_pretty(config).unwrap_or_default();
+    fs::write(&path, content).map_err(|e| format!("Failed to write watch.json: {}", e))
+}
+
+/// Delay required since the last restart before trying again
+fn watch_backoff_secs(count: u32) -> u64 {
+    if count == 0 {
+        return 0;
+    }
+    (WATCH_BACKOFF_BASE_SECS << (count - 1).min(16)).min(WATCH_BACKOFF_MAX_SECS)
+}
+
+fn watch_log(message: &str) {
+    eprintln!("[watch {}] {}", chrono::Local::now().format("%H:%M:%S"), message);
+}
+
+/// config/watch.pid names the one process whose supervisor acts, so `orbit
+/// watch` and the orbit-mcp standby loop never both restart a service. Takes
+/// the lock when it is free or its holder has exited.
+fn claim_watch_lock() -> bool {
+    let path = get_config_dir().join("watch.pid");
+    let me = std::process::id();
+    for _ in 0..2 {
+        match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                use std::io::Write;
+                return write!(file, "{}", me).is_ok();
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                match fs::read_to_string(&path).ok().and_then(|c| c.trim().parse::<u32>().ok()) {
+                    Some(pid) if pid == me => return true,
+                    Some(pid) if pid_alive(pid) => return false,
+                    // Empty: another watcher is between creating and writing it
+                    None if fs::metadata(&path).and_then(|m| m.modified()).is_ok_and(|t| t.elapsed().unwrap_or_default().as_secs() < WATCH_INTERVAL_SECS) => return false,
+                    _ => {
+                        let _ = fs::remove_file(&path);
+                    }
+                }
+            }
+            Err(_) => return false,
+        }
+    }
+    false
+}
+
+/// One supervisor pass over the watched set in config/watch.json. Only a
+/// crash counts: the service still has its config/session.json entry but the
+/// process is gone. Every stop path (orbit stop, the app, stop_service)
+/// removes the entry, so a service stopped on purpose stays down, and one
+/// Orbit never started isn't started. `given_up` holds services past
+/// WATCH_MAX_RESTARTS until they run again.
+fn watch_tick(given_up: &mut std::collections::HashSet<String>, bin_dir: &PathBuf) {
+    let mut config = read_watch_config();
+    if config.services.is_empty() || !claim_watch_lock() {
+        return;
+    }
+    given_up.retain(|name| config.services.contains(name));
+
+    let services = scan_services_cached(bin_dir);
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let mut changed = false;
+
+    for name in config.services.clone() {
+        if is_service_running(&name) {
+            let stable = config.restarts.get(&name).is_some_and(|r| now.saturating_sub(r.last) >= WATCH_STABLE_SECS);
+            if stable {
+                config.restarts.remove(&name);
+                changed = true;
+                watch_log(&format!("{} has been up for {}s, restart count cleared", name, WATCH_STABLE_SECS));
+            }
+            given_up.remove(&name);
+            continue;
+        }
+        if session_entry(&name).is_none() || given_up.contains(&name) {
+            continue;
+        }
+
+        let restarts = config.restarts.get(&name).cloned().unwrap_or_default();
+        if restarts.count >= WATCH_MAX_RESTARTS {
+            watch_log(&format!(
+                "{} is down, giving up after {} restarts; start it by hand once fixed",
+                name, restarts.count
+            ));
+            given_up.insert(name);
+            continue;
+        }
+        let wait = watch_backoff_secs(restarts.count);
+        if now < restarts.last + wait {
+            watch_log(&format!("{} is down, next restart in {}s", name, restarts.last + wait - now));
+            continue;
+        }
+        let Some(svc) = services.iter().find(|s| s.name == name) else {
+            watch_log(&format!("{} is down and no longer installed, not restarting", name));
+            given_up.insert(name);
+            continue;
+        };
+
+        watch_log(&format!("{} crashed, restarting (attempt {}/{})", name, restarts.count + 1, WATCH_MAX_RESTARTS));
+        match start_service_process(svc) {
+            Ok(pid) => watch_log(&format!("{} restarted (PID {})", name, pid)),
+            Err(e) => watch_log(&format!("{} restart failed: {}", name, e)),
+        }
+        config.restarts.insert(name, WatchRestarts { count: restarts.count + 1, last: now });
+        changed = true;
+    }
+
+    if changed {
+        if let Err(e) = write_watch_config(&config) {
+            watch_log(&e);
+        }
+    }
+}
+
+// ─── Scheduled Tasks (shared with mcp.rs) ─────────────────────────
+
+/// A task log is moved to `<name>.log.1` once it grows past this
+const CRON_LOG_MAX_BYTES: u64 = 1024 * 1024;
+
+/// An entry of config/cron.json: a site command run on a fixed interval,
+/// e.g. Laravel's `php artisan schedule:run` every 60 seconds
+#[derive(Serialize, Deserialize, Clone)]
+struct ScheduledTask {
+    name: String,
+    command: String,
+    interval_secs: u64,
+    working_dir: String,
+    /// Unix seconds of the last run (or skipped run)
+    #[serde(default)]
+    last_run: Option<u64>,
+    /// PID and process start time of the last invocation, so any runner can
+    /// tell whether it is still going without trusting a recycled PID
+    #[serde(default)]
+    last_pid: Option<u32>,
+    #[serde(default)]
+    last_pid_started_at: Option<u64>,
+}
+
+fn read_scheduled_tasks() -> Vec<ScheduledTask> {
+    fs::read_to_string(get_config_dir().join("cron.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Hold this across a read-modify-write of cron.json
+fn lock_cron_store() -> Result<FileLock, String> {
+    FileLock::acquire(&get_config_dir().join("cron.json.lock"))
+}
+
+fn write_scheduled_tasks(tasks: &[ScheduledTask]) -> Result<(), String> {
+    let path = get_config_dir().join("cron.json");
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let content = serde_json::to_string_pretty(tasks).unwrap_or_default();
+    fs::write(&path, content).map_err(|e| format!("Failed to write cron.json: {}", e))
+}
+
+fn scheduled_task_due(task: &ScheduledTask, now: u64) -> bool {
+    task.last_run.map_or(true, |last| now >= last + task.interval_secs)
+}
+
+/// logs/cron/<name>.log
+fn scheduled_task_log_path(name: &str) -> PathBuf {
+    get_orbit_data_dir().join("logs").join("cron").join(format!("{}.log", name))
+}
+
+fn process_start_time(pid: u32) -> Option<u64> {
+    let mut sys = sysinfo::System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+    sys.process(sys_pid).map(|p| p.start_time())
+}
+
+/// Whether the last invocation (possibly started by another runner) is alive
+fn scheduled_task_running(task: &ScheduledTask) -> bool {
+    match (task.last_pid, task.last_pid_started_at) {
+        (Some(pid), Some(started_at)) => process_start_time(pid).is_some_and(|t| t.abs_diff(started_at) <= 1),
+        _ => false,
+    }
+}
+
+/// Run the command through the shell in its working dir, appending output to its log
+fn spawn_scheduled_task(task: &ScheduledTask) -> Result<std::process::Child, String> {
+    let log_path = scheduled_task_log_path(&task.name);
+    if let Some(dir) = log_path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create log dir: {}", e))?;
+    }
+    if fs::metadata(&log_path).is_ok_and(|m| m.len() > CRON_LOG_MAX_BYTES) {
+        let _ = fs::rename(&log_path, log_path.with_extension("log.1"));
+    }
+    let mut log = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| format!("Failed to open {}: {}", log_path.display(), e))?;
+    let _ = writeln!(log, "=== {} $ {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), task.command);
+    let stderr = log.try_clone().map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "windows")]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(target_os = "windows"))]
+    let (shell, flag) = ("sh", "-c");
+
+    // Orbit's PHP, Node, Composer... first, so `php artisan schedule:run`
+    // works without them on the system PATH
+    let inherited = std::env::var_os("PATH").unwrap_or_default();
+    let dirs = orbit_path_dirs(&get_bin_dir()).into_iter().chain(std::env::split_paths(&inherited));
+    let path = std::env::join_paths(dirs).unwrap_or(inherited);
+
+    let shell = PathBuf::from(shell);
+    hidden_command(&shell)
+        .arg(flag)
+        .arg(&task.command)
+        .current_dir(&task.working_dir)
+        .env("PATH", path)
+        .stdin(std::process::Stdio::null())
+        .stdout(log)
+        .stderr(stderr)
+        .spawn()
+        .map_err(|e| e.to_string())
+}
+
+fn cron_log(message: &str) {
+    eprintln!("[cron {}] {}", chrono::Local::now().format("%H:%M:%S"), message);
+}
+
+/// Start every due task in config/cron.json that isn't still running from its
+/// previous run. `running` holds the children this runner started so they get
+/// reaped; a one-shot runner can pass an empty map. Returns the names of the
+/// tasks it started.
+fn cron_tick(running: &mut std::collections::HashMap<String, std::process::Child>) -> Vec<String> {
+    running.retain(|_, child| matches!(child.try_wait(), Ok(None)));
+
+    // The app, orbit-cli and MCP tools edit cron.json too
+    let _lock = match lock_cron_store() {
+        Ok(lock) => lock,
+        Err(e) => {
+            cron_log(&e);
+            return Vec::new();
+        }
+    };
+    let mut tasks = read_scheduled_tasks();
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let mut changed = false;
+    let mut started = Vec::new();
+
+    for task in tasks.iter_mut().filter(|t| scheduled_task_due(t, now)) {
+        // The slot is used up either way, like cron skipping an overlapping run
+        task.last_run = Some(now);
+        changed = true;
+
+        if running.contains_key(&task.name) || scheduled_task_running(task) {
+            cron_log(&format!("{} is still running, skipping this run", task.name));
+            continue;
+        }
+        match spawn_scheduled_task(task) {
+            Ok(child) => {
+                cron_log(&format!("{} started (PID {})", task.name, child.id()));
+                task.last_pid = Some(child.id());
+                task.last_pid_started_at = process_start_time(child.id());
+                running.insert(task.name.clone(), child);
+                started.push(task.name.clone());
+            }
+            Err(e) => cron_log(&format!("{} failed to start: {}", task.name, e)),
+        }
+    }
+
+    if changed {
+        if let Err(e) = write_scheduled_tasks(&tasks) {
+            cron_log(&e);
+        }
+    }
+    started
+}
+
+// ─── Shell Completions ────────────────────────────────────────────
+
+/// Services `start`/`stop`/`restart` can act on, and targets `open` accepts
+fn completion_candidates(bin_dir: &PathBuf) -> (Vec<String>, Vec<String>) {
+    let services = scan_services(bin_dir)
+        .into_iter()
+        .filter(|s| {
+            matches!(s.service_type.as_str(), "nginx" | "php" | "mariadb" | "redis" | "apache" | "mailpit" | "meilisearch" | "postgresql" | "mongodb")
+        })
+        .map(|s| s.name)
+        .collect();
+
+    let mut open_targets: Vec<String> = read_sites_store()
+        .map(|store| store.sites.into_iter().map(|site| site.domain).collect())
+        .unwrap_or_default();
+    open_targets.extend(OPEN_TOOLS.iter().map(|t| t.to_string()));
+
+    (services, open_targets)
+}
+
+fn cmd_completions(bin_dir: &PathBuf, shell: clap_complete::Shell) {
+    use clap::builder::PossibleValuesParser;
+    use clap::CommandFactory;
+
+    let (services, open_targets) = completion_candidates(bin_dir);
+    let mut command = Cli::command();
+
+    // Only the generated script sees these value lists; parsing still accepts
+    // aliases like `pg` or an unconfigured domain
+    if !services.is_empty() {
+        for sub in ["start", "stop", "restart"] {
+            command = command.mut_subcommand(sub, |c| {
+                c.mut_arg("service", |a| a.value_parser(PossibleValuesParser::new(services.clone())))
+            });
+        }
+    }
+    command = command.mut_subcommand("open", |c| {
+        c.mut_arg("target", |a| a.value_parser(PossibleValuesParser::new(open_targets.clone())))
+    });
+
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut command, "orbit-cli", &mut script);
+    let mut script = String::from_utf8_lossy(&script).into_owned();
+    // The bash generator mangles the dash in the binary name differently in its
+    // subcommand case labels, so no subcommand would ever match
+    if shell == clap_complete::Shell::Bash {
+        script = script.replace("orbit__subcmd__cli", "orbit__cli");
+    }
+    print!("{}", script);
+
+    // The fish generator skips positional values, so add them by hand
+    if shell == clap_complete::Shell::Fish {
+        let mut lines: Vec<(&str, &Vec<String>)> = vec![("open", &open_targets)];
+        if !services.is_empty() {
+            lines.extend(["start", "stop", "restart"].iter().map(|sub| (*sub, &services)));
+        }
+        for (sub, values) in lines {
+            println!(
+                "complete -c orbit-cli -n \"__fish_orbit_cli_using_subcommand {}\" -f -a \"{}\"",
+                sub,
+                values.join(" ")
+            );
+        }
+    }
+}
+
 // ─── Main ─────────────────────────────────────────────────────────
 
 fn main() {
     let cli = Cli::parse();
+    if let Some(secs) = cli.timeout {
+        let _ = TIMEOUT_OVERRIDE.set(std::time::Duration::from_secs(secs.max(1)));
+    }
+    if cli.offline {
+        OFFLINE_FLAG.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+    // Keep escape codes out of anything a script might capture
+    if cli.json {
+        colored::control::set_override(false);
+    }
+    let json = cli.json;
     let bin_dir = get_bin_dir();
 
     match cli.command {
-        Commands::Status => cmd_status(&bin_dir),
-        Commands::Start { service, all } => cmd_start(&bin_dir, service, all),
+        Commands::Status => cmd_status(&bin_dir, json),
+        Commands::Start { service, all, skip_failing } => cmd_start(&bin_dir, service, all, skip_failing),
         Commands::Stop { service, all } => cmd_stop(&bin_dir, service, all),
         Commands::Restart { service, all } => cmd_restart(&bin_dir, service, all),
-        Commands::List => cmd_list(&bin_dir),
-        Commands::Sites { json } => cmd_sites(json),
-        Commands::Info => cmd_info(&bin_dir),
+        Commands::Apply => cmd_apply(&bin_dir),
+        Commands::List => cmd_list(&bin_dir, json),
+        Commands::Sites { action } => match action {
+            None => cmd_sites(json),
+            Some(SitesCommands::Php { domain, version }) => cmd_sites_php(&bin_dir, &domain, &version),
+            Some(SitesCommands::Delete { domain, dry_run, yes }) => cmd_sites_delete(&bin_dir, &domain, dry_run, yes, json),
+        },
+        Commands::Info => cmd_info(&bin_dir, json),
+        Commands::Doctor { fix_permissions } => cmd_doctor(&bin_dir, fix_permissions, json),
         Commands::Logs(sub) => match sub {
-            LogsCommands::List => cmd_logs_list(&bin_dir),
+            LogsCommands::List => cmd_logs_list(&bin_dir, json),
             LogsCommands::Show { name, lines, follow } => cmd_logs_show(&bin_dir, &name, lines, follow),
             LogsCommands::Clear { name } => cmd_logs_clear(&bin_dir, &name),
+            LogsCommands::Rotate { name, keep } => cmd_logs_rotate(&bin_dir, &name, keep),
         },
         Commands::Db(sub) => match sub {
-            DbCommands::List => cmd_db_list(&bin_dir),
+            DbCommands::List => cmd_db_list(&bin_dir, json),
             DbCommands::Create { name } => cmd_db_create(&bin_dir, &name),
             DbCommands::Drop { name, yes } => cmd_db_drop(&bin_dir, &name, yes),
             DbCommands::Export { name, output } => cmd_db_export(&bin_dir, &name, output),
             DbCommands::Import { name, file } => cmd_db_import(&bin_dir, &name, &file),
+            DbCommands::Shell { name, engine } => cmd_db_shell(&bin_dir, &engine, name.as_deref()),
         },
-        Commands::Open { target, https } => cmd_open(&target, https),
+        Commands::Open { target, path, https } => cmd_open(&target, path.as_deref(), https),
         Commands::Php(sub) => match sub {
             PhpCommands::List => cmd_php_list(&bin_dir),
             PhpCommands::Ext { version, enable, disable } => cmd_php_ext(&bin_dir, &version, enable, disable),
         },
         Commands::Hosts(sub) => match sub {
-            HostsCommands::List => cmd_hosts_list(),
+            HostsCommands::List => cmd_hosts_list(json),
             HostsCommands::Add { domain } => cmd_hosts_add(&domain),
             HostsCommands::Remove { domain } => cmd_hosts_remove(&domain),
         },
+        Commands::Ssl(sub) => match sub {
+            SslCommands::Regenerate { domain, all: _ } => cmd_ssl_regenerate(&bin_dir, domain.as_deref()),
+        },
         Commands::Composer { args } => cmd_composer(&bin_dir, args),
-        Commands::Install { service, version } => cmd_install(&bin_dir, &service, version),
+        Commands::Install { service, version, locked } => cmd_install(&bin_dir, &service, version, locked),
+        Commands::Versions { refresh } => cmd_versions(&bin_dir, refresh, json),
         Commands::Uninstall { service, yes } => cmd_uninstall(&bin_dir, &service, yes),
+        Commands::Watch { services, clear } => cmd_watch(&bin_dir, services, clear),
+        Commands::Cron(CronCommands::Run) => cmd_cron_run(),
+        Commands::Completions { shell } => cmd_completions(&bin_dir, shell),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_port_in_use_detects_listener() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(is_port_in_use(port));
+        drop(listener);
+    }
+
+    fn locked(version: &str) -> LockedVersion {
+        LockedVersion {
+            version: version.to_string(),
+            url: format!("https://example.com/{}.zip", version),
+            filename: format!("{}.zip", version),
+            sha256: None,
+            installed_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_find_locked_version() {
+        let mut lock = VersionLock::new();
+        lock.insert("php".to_string(), locked("8.2.20"));
+        lock.insert("nginx".to_string(), locked("1.28.0"));
+
+        // Only the unversioned "php" key: used as is
+        assert_eq!(find_locked_version(&lock, "php", None).unwrap().0, "php");
+
+        // One per-version entry wins over the "php" key
+        lock.insert("php-8.4".to_string(), locked("8.4.3"));
+        assert_eq!(find_locked_version(&lock, "php", None).unwrap().1.version, "8.4.3");
+
+        // Several: --version picks one, without it the choice is refused
+        lock.insert("php-8.3".to_string(), locked("8.3.9"));
+        let err = find_locked_version(&lock, "php", None).unwrap_err();
+        assert!(err.contains("8.3, 8.4"), "{}", err);
+        assert_eq!(find_locked_version(&lock, "php", Some("8.3")).unwrap().1.version, "8.3.9");
+        assert!(find_locked_version(&lock, "php", Some("7.4")).is_err());
+
+        assert_eq!(find_locked_version(&lock, "nginx", None).unwrap().1.version, "1.28.0");
+        assert!(find_locked_version(&lock, "nginx", Some("1.26.0")).is_err());
+        assert!(find_locked_version(&lock, "redis", None).is_err());
+    }
+
+    #[test]
+    fn test_registry_latest_for() {
+        let service: RegistryServiceInfo = serde_json::from_str(r#"{
+            "name": "php",
+            "versions": {
+                "8.4": { "latest": "8.4.12" },
+                "8.4.3": { "latest": "8.4.3" },
+                "8.3": { "latest": "8.3.25" }
+            }
+        }"#).unwrap();
+        // The longest key the installed version starts with
+        assert_eq!(registry_latest_for(&service, "8.4.3").as_deref(), Some("8.4.3"));
+        assert_eq!(registry_latest_for(&service, "8.4.1").as_deref(), Some("8.4.12"));
+        assert_eq!(registry_latest_for(&service, "8.4").as_deref(), Some("8.4.12"));
+        assert_eq!(registry_latest_for(&service, "8.30.1"), None);
+
+        let single: RegistryServiceInfo = serde_json::from_str(r#"{ "name": "redis", "latest": "7.4.1" }"#).unwrap();
+        assert_eq!(registry_latest_for(&single, "7.2.0").as_deref(), Some("7.4.1"));
     }
 }
@@ -1,4 +1,5 @@
 use crate::services::ai_tools::{AiToolStatus, ClaudeCodeManager, GeminiCliManager};
+use crate::services::paths::OrbitPathExt;
 use tauri::{command, AppHandle, Manager};
 
 /// Get Claude Code status
@@ -44,7 +45,7 @@ pub fn uninstall_gemini_cli(app: AppHandle) -> Result<String, String> {
 /// Generate and write AI context files for a site project
 #[command]
 pub fn generate_ai_context_cmd(app: AppHandle, domain: String) -> Result<String, String> {
-    let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    let data_dir = app.path().orbit_data_dir().map_err(|e| e.to_string())?;
     crate::services::ai_tools::write_context_file(&app, &domain, &data_dir)
 }
 
@@ -60,7 +61,7 @@ pub fn setup_mcp_config(app: AppHandle) -> Result<String, String> {
 pub fn open_in_terminal(app: AppHandle, tool: String, project_path: String, domain: Option<String>) -> Result<String, String> {
     // Generate context if domain provided
     if let Some(d) = &domain {
-        let data_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+        let data_dir = app.path().orbit_data_dir().map_err(|e| e.to_string())?;
         crate::services::ai_tools::write_context_file(&app, d, &data_dir).ok();
     }
 
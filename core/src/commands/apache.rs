@@ -0,0 +1,31 @@
+use tauri::{command, AppHandle};
+use crate::services::apache::{ApacheManager, ApacheModule};
+use crate::services::templates::SiteTemplate;
+
+#[command]
+pub fn list_apache_modules(app: AppHandle) -> Result<Vec<ApacheModule>, String> {
+    ApacheManager::list_modules(&app)
+}
+
+#[command]
+pub fn toggle_apache_module(app: AppHandle, module: String, enabled: bool) -> Result<String, String> {
+    ApacheManager::toggle_module(&app, &module, enabled)
+}
+
+/// Modules the Apache vhost for a site template needs, with their current state
+#[command]
+pub fn get_apache_template_modules(app: AppHandle, template: String) -> Result<Vec<ApacheModule>, String> {
+    let installed = ApacheManager::list_modules(&app)?;
+    Ok(SiteTemplate::from_name(&template)
+        .required_apache_modules()
+        .into_iter()
+        .map(|name| match installed.iter().find(|m| m.name == name) {
+            Some(module) => module.clone(),
+            None => ApacheModule {
+                name: name.to_string(),
+                file: String::new(),
+                enabled: false,
+            },
+        })
+        .collect())
+}
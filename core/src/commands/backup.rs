@@ -1,10 +1,11 @@
 use crate::services::backup::BackupManager;
+use crate::services::paths::OrbitPathExt;
 use tauri::{command, AppHandle, Manager};
 
 fn get_mariadb_root(app: &AppHandle) -> Result<std::path::PathBuf, String> {
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("mariadb");
@@ -19,7 +20,7 @@ fn get_mariadb_root(app: &AppHandle) -> Result<std::path::PathBuf, String> {
 fn get_pg_root(app: &AppHandle) -> Result<std::path::PathBuf, String> {
     let bin_base = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -85,6 +85,7 @@ pub fn create_from_blueprint(
 
     // Set dev_command on site metadata if present
     if let Some(ref dev_cmd) = bp.dev_command {
+        let _lock = SiteStore::lock(&app)?;
         match SiteStore::load(&app) {
             Ok(mut store) => {
                 if let Some(site_meta) = store.get_site_mut(&domain) {
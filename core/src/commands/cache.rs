@@ -1,5 +1,6 @@
 use crate::services::cache::{CacheConfig, CacheManager, CacheStatus};
 use crate::services::download::{download_file, extract_zip};
+use crate::services::paths::OrbitPathExt;
 use tauri::{command, AppHandle, Manager};
 use std::fs;
 
@@ -14,7 +15,7 @@ pub fn get_cache_status(app: AppHandle) -> Result<CacheStatus, String> {
 pub async fn install_redis(app: AppHandle) -> Result<String, String> {
     let bin_dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -55,7 +56,7 @@ pub async fn install_redis(app: AppHandle) -> Result<String, String> {
 pub fn uninstall_redis(app: AppHandle) -> Result<String, String> {
     let bin_dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -87,7 +88,7 @@ pub fn update_redis_config(app: AppHandle, port: u16, max_memory: String) -> Res
 pub fn get_redis_exe_path(app: AppHandle) -> Result<String, String> {
     let bin_dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -0,0 +1,106 @@
+use crate::services::cache::CacheManager;
+use crate::services::connection_info::{self, DbConfig};
+use crate::services::paths::OrbitPathExt;
+use crate::services::site_store::SiteStore;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use tauri::{command, AppHandle, Manager};
+
+/// Pipe `input` into a clipboard tool's stdin.
+fn pipe_to(mut cmd: Command, input: &[u8]) -> Result<(), String> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input).map_err(|e| e.to_string())?;
+    }
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("exited with {status}"))
+    }
+}
+
+/// Copy text to the system clipboard.
+#[command]
+pub fn copy_text(text: String) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        // clip.exe only round-trips non-ASCII text when given UTF-16LE with a BOM
+        let mut input = vec![0xFF, 0xFE];
+        input.extend(text.encode_utf16().flat_map(|u| u.to_le_bytes()));
+        pipe_to(crate::services::hidden_command("clip"), &input)
+            .map_err(|e| format!("Failed to copy to clipboard: {e}"))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        pipe_to(Command::new("pbcopy"), text.as_bytes())
+            .map_err(|e| format!("Failed to copy to clipboard: {e}"))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let tools: &[(&str, &[&str])] = &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ];
+        for (tool, args) in tools {
+            let mut cmd = Command::new(tool);
+            cmd.args(*args);
+            if pipe_to(cmd, text.as_bytes()).is_ok() {
+                return Ok(());
+            }
+        }
+        Err("Failed to copy to clipboard: install wl-clipboard, xclip or xsel".to_string())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        let _ = text;
+        Err("Copying to the clipboard is not supported on this platform".to_string())
+    }
+}
+
+/// Connection string for a local service (mariadb, postgresql, mongodb, redis, meilisearch, mailpit).
+#[command]
+pub fn get_connection_string(app: AppHandle, service: String) -> Result<String, String> {
+    let config_dir = app
+        .path()
+        .orbit_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("config");
+    let redis_port = CacheManager::get_status(&app)?.redis_port;
+    connection_info::connection_string(&DbConfig::load(&config_dir), &service, redis_port)
+}
+
+/// Browser URL for a configured site.
+#[command]
+pub fn get_site_url(app: AppHandle, domain: String) -> Result<String, String> {
+    let store = SiteStore::load(&app)?;
+    let site = store
+        .get_site(&domain)
+        .ok_or_else(|| format!("Site not found: {domain}"))?;
+    Ok(connection_info::site_url(site))
+}
+
+/// Copy a service's connection string and return what was copied.
+#[command]
+pub fn copy_connection_string(app: AppHandle, service: String) -> Result<String, String> {
+    let value = get_connection_string(app, service)?;
+    copy_text(value.clone())?;
+    Ok(value)
+}
+
+/// Copy a site's URL and return what was copied.
+#[command]
+pub fn copy_site_url(app: AppHandle, domain: String) -> Result<String, String> {
+    let value = get_site_url(app, domain)?;
+    copy_text(value.clone())?;
+    Ok(value)
+}
@@ -0,0 +1,24 @@
+use crate::services::cron::{CronStore, ScheduledTask};
+use tauri::command;
+
+#[command]
+pub fn get_scheduled_tasks() -> Vec<ScheduledTask> {
+    CronStore::load()
+}
+
+/// Add a task, or update the one with the same name. Tasks run from the
+/// orbit-mcp standby process, so they only fire while that is running.
+#[command]
+pub fn add_scheduled_task(
+    name: String,
+    command: String,
+    interval_secs: u64,
+    working_dir: String,
+) -> Result<ScheduledTask, String> {
+    CronStore::add(&name, &command, interval_secs, &working_dir)
+}
+
+#[command]
+pub fn remove_scheduled_task(name: String) -> Result<(), String> {
+    CronStore::remove(&name)
+}
@@ -0,0 +1,65 @@
+use crate::services::paths::{self, OrbitPathExt};
+use serde::Serialize;
+use std::fs;
+use tauri::{command, AppHandle, Manager};
+
+#[derive(Debug, Serialize)]
+pub struct DataDirInfo {
+    pub resolved: String,
+    pub default: String,
+    pub source: String, // "env", "settings" or "default"
+    pub configured: Option<String>, // settings entry, if any
+    pub env_override: Option<String>, // ORBIT_DATA_DIR, if set
+}
+
+/// Report where Orbit (and orbit-cli/orbit-mcp) keep their data
+#[command]
+pub fn get_data_dir_info(app: AppHandle) -> Result<DataDirInfo, String> {
+    let resolved = app.path().orbit_data_dir().map_err(|e| e.to_string())?;
+    let default = paths::default_orbit_data_dir();
+    let source = match paths::data_dir_override() {
+        Some((dir, source)) if dir == resolved => source,
+        _ => "default",
+    };
+    let configured = fs::read_to_string(default.join(paths::DATA_DIR_POINTER))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let env_override = std::env::var(paths::DATA_DIR_ENV)
+        .ok()
+        .filter(|s| !s.trim().is_empty());
+
+    Ok(DataDirInfo {
+        resolved: resolved.to_string_lossy().to_string(),
+        default: default.to_string_lossy().to_string(),
+        source: source.to_string(),
+        configured,
+        env_override,
+    })
+}
+
+/// Save (or clear, with `None`) the data directory settings entry.
+/// Takes effect after Orbit is restarted; ORBIT_DATA_DIR still wins if set.
+#[command]
+pub fn set_data_dir_override(path: Option<String>) -> Result<String, String> {
+    let default = paths::default_orbit_data_dir();
+    let pointer = default.join(paths::DATA_DIR_POINTER);
+
+    match path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(dir) => {
+            paths::validate_data_dir(std::path::Path::new(&dir))?;
+            fs::create_dir_all(&default)
+                .map_err(|e| format!("Failed to create {}: {e}", default.display()))?;
+            fs::write(&pointer, &dir)
+                .map_err(|e| format!("Failed to save data dir setting: {e}"))?;
+            Ok(format!("Data directory set to {dir}. Restart Orbit to apply."))
+        }
+        None => {
+            if pointer.exists() {
+                fs::remove_file(&pointer)
+                    .map_err(|e| format!("Failed to clear data dir setting: {e}"))?;
+            }
+            Ok("Data directory reset to default. Restart Orbit to apply.".to_string())
+        }
+    }
+}
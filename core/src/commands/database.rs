@@ -1,12 +1,13 @@
 use crate::services::database::{DatabaseManager, DatabaseStatus};
 use crate::services::nginx::NginxManager;
 use crate::services::phpmyadmin::{PhpMyAdminManager, PhpMyAdminStatus};
+use crate::services::paths::OrbitPathExt;
 use serde::{Deserialize, Serialize};
 use tauri::{command, AppHandle, Manager};
 
 fn get_bin_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
     app.path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())
         .map(|p| p.join("bin"))
 }
@@ -1,4 +1,5 @@
 use crate::services::hosts::HostsManager;
+use crate::services::paths::OrbitPathExt;
 use tauri::command;
 
 #[command]
@@ -60,7 +61,7 @@ pub fn save_hosts_file(app: tauri::AppHandle, new_content: String) -> Result<Str
     // 1. Write the new content to a temporary file in our app data dir
     let app_dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?;
         
     let temp_hosts_path = app_dir.join("temp_hosts.txt");
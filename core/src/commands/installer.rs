@@ -1,13 +1,28 @@
 use tauri::command;
 use std::path::{Path, PathBuf};
-use crate::services::download::{download_file, extract_archive};
+use crate::services::download::{download_file_with_progress, extract_archive};
 // `hidden_command` is only needed on Windows (taskkill / mklink). Importing
 // it unconditionally trips `unused_imports` in CI's clippy-with-Dwarnings.
 #[cfg(target_os = "windows")]
 use crate::services::hidden_command;
+use crate::services::paths::OrbitPathExt;
+use crate::services::version_lock::{self, LockedVersion};
 use tauri::AppHandle;
+use tauri::Emitter;
 use tauri::Manager;
 
+/// Payload of the `download-progress` event emitted while a service downloads.
+/// `total` is None when the server sends no Content-Length.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DownloadProgress {
+    pub service_type: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+/// Minimum gap between `download-progress` events
+const PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
 /// Image names of binaries we ship per service type. Killed before reinstall
 /// so an in-flight nginx.exe / php-cgi.exe doesn't keep its install dir as
 /// CWD and block `remove_dir_all` / `rename`.
@@ -70,11 +85,38 @@ pub async fn download_service(
     filename: String,
     service_type: String,
     version: Option<String>,
+    sha256: Option<String>,
+) -> Result<String, String> {
+    let message = install_service(&app, &url, &filename, service_type.clone(), version.clone(), sha256.clone()).await?;
+
+    // Record the download so `orbit install --locked` can reproduce it
+    let entry = LockedVersion {
+        version: version.filter(|v| !v.is_empty())
+            .unwrap_or_else(|| service_type.strip_prefix("php-").unwrap_or("latest").to_string()),
+        url,
+        filename,
+        sha256,
+        installed_at: String::new(),
+    };
+    let config_dir = app.path().orbit_data_dir().map_err(|e| e.to_string())?.join("config");
+    if let Err(e) = version_lock::record(&config_dir, &service_type, entry) {
+        log::warn!("Installed {service_type} but could not update versions.lock: {e}");
+    }
+    Ok(message)
+}
+
+async fn install_service(
+    app: &AppHandle,
+    url: &str,
+    filename: &str,
+    service_type: String,
+    version: Option<String>,
+    sha256: Option<String>,
 ) -> Result<String, String> {
     use crate::services::version_manager;
 
     // Base bin path - use app local data dir for portable storage
-    let bin_path = app.path().app_local_data_dir()
+    let bin_path = app.path().orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -87,12 +129,23 @@ pub async fn download_service(
         std::fs::create_dir_all(&downloads_dir).map_err(|e| format!("Failed to create downloads dir: {e}"))?;
     }
 
-    let dest_path = downloads_dir.join(&filename);
+    let dest_path = downloads_dir.join(filename);
 
     log::info!("Downloading {service_type} from {url} to {dest_path:?}");
 
-    // Download the file
-    download_file(&url, &dest_path).await?;
+    // Download the file, reporting progress to the UI
+    let mut last_emit: Option<std::time::Instant> = None;
+    download_file_with_progress(url, &dest_path, sha256.as_deref(), &mut |downloaded, total| {
+        let finished = total == Some(downloaded);
+        if finished || last_emit.map_or(true, |t| t.elapsed() >= PROGRESS_INTERVAL) {
+            last_emit = Some(std::time::Instant::now());
+            app.emit("download-progress", DownloadProgress {
+                service_type: service_type.clone(),
+                downloaded,
+                total,
+            }).ok();
+        }
+    }).await?;
 
     // Resolve the version we'll record under .versions/<svc>/<ver>/. For
     // PHP the version lives in service_type ("php-8.4"), for everything
@@ -290,7 +343,7 @@ pub fn list_service_versions(
 
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -342,7 +395,7 @@ pub fn set_active_service_version(
 
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -370,7 +423,7 @@ pub fn remove_service_version(
 
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -451,7 +504,7 @@ fn dir_size(path: &Path) -> u64 {
 pub fn list_migration_backups(app: AppHandle) -> Result<Vec<MigrationBackup>, String> {
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
     if !bin_path.exists() {
@@ -525,7 +578,7 @@ pub fn list_migration_backups(app: AppHandle) -> Result<Vec<MigrationBackup>, St
 pub fn delete_migration_backup(app: AppHandle, path: String) -> Result<String, String> {
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
     let target = std::path::PathBuf::from(&path);
@@ -957,6 +1010,7 @@ fn configure_python(python_path: &Path) -> Result<(), String> {
     let get_pip_path = python_path.join("get-pip.py");
 
     // Download get-pip.py
+    crate::services::network::require_online("download get-pip.py")?;
     let resp = reqwest::blocking::get("https://bootstrap.pypa.io/get-pip.py")
         .map_err(|e| format!("Failed to download get-pip.py: {e}"))?;
     let bytes = resp.bytes().map_err(|e| format!("Failed to read get-pip.py: {e}"))?;
@@ -1,12 +1,13 @@
 use crate::services::logs::{LogFile, LogManager, LogReadResult};
 use crate::services::validation::validate_log_path;
+use crate::services::paths::OrbitPathExt;
 use tauri::{command, AppHandle, Manager};
 
 #[command]
 pub fn get_log_files(app: AppHandle) -> Result<Vec<LogFile>, String> {
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -16,7 +17,7 @@ pub fn get_log_files(app: AppHandle) -> Result<Vec<LogFile>, String> {
 /// Get allowed base path for log files
 fn get_allowed_log_base(app: &AppHandle) -> Result<std::path::PathBuf, String> {
     app.path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .canonicalize()
         .map_err(|e| format!("Failed to resolve app data dir: {e}"))
@@ -60,7 +61,7 @@ pub fn clear_log_file(app: AppHandle, path: String) -> Result<(), String> {
 pub fn clear_all_logs(app: AppHandle) -> Result<usize, String> {
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
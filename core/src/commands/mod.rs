@@ -29,3 +29,8 @@ pub mod mongodb;
 pub mod blueprints;
 pub mod ai_tools;
 pub mod deploy;
+pub mod data_dir;
+pub mod apache;
+pub mod reveal;
+pub mod clipboard;
+pub mod cron;
@@ -2,6 +2,7 @@ use tauri::{command, AppHandle, Manager};
 use std::path::{Path, PathBuf};
 #[cfg(windows)]
 use crate::services::hidden_command;
+use crate::services::paths::OrbitPathExt;
 
 // ── Service directory resolution (cross-platform) ───────────────────────────
 
@@ -211,7 +212,7 @@ fn unix_is_in_current_path(path_str: &str) -> bool {
 /// Add a specific service to the user PATH.
 #[command]
 pub fn add_service_to_path(app: AppHandle, service_type: String) -> Result<String, String> {
-    let bin_path = app.path().app_local_data_dir()
+    let bin_path = app.path().orbit_data_dir()
         .map_err(|e| e.to_string())?.join("bin");
 
     if !bin_path.exists() {
@@ -237,7 +238,7 @@ pub fn add_service_to_path(app: AppHandle, service_type: String) -> Result<Strin
 /// Remove a specific service from the user PATH.
 #[command]
 pub fn remove_service_from_path(app: AppHandle, service_type: String) -> Result<String, String> {
-    let bin_path = app.path().app_local_data_dir()
+    let bin_path = app.path().orbit_data_dir()
         .map_err(|e| e.to_string())?.join("bin");
 
     let svc_path = service_dir(&bin_path, &service_type)
@@ -255,7 +256,7 @@ pub fn remove_service_from_path(app: AppHandle, service_type: String) -> Result<
 /// Check if a specific service directory is in the user PATH.
 #[command]
 pub fn check_service_path_status(app: AppHandle, service_type: String) -> Result<ServicePathStatus, String> {
-    let bin_path = app.path().app_local_data_dir()
+    let bin_path = app.path().orbit_data_dir()
         .map_err(|e| e.to_string())?.join("bin");
 
     let svc_path = service_dir(&bin_path, &service_type)
@@ -275,7 +276,7 @@ pub fn check_service_path_status(app: AppHandle, service_type: String) -> Result
 /// Add all installed services to the user PATH.
 #[command]
 pub fn add_to_path(app: AppHandle) -> Result<String, String> {
-    let bin_path = app.path().app_local_data_dir()
+    let bin_path = app.path().orbit_data_dir()
         .map_err(|e| e.to_string())?.join("bin");
 
     if !bin_path.exists() {
@@ -335,7 +336,7 @@ pub fn add_to_path(app: AppHandle) -> Result<String, String> {
 /// Check if orbit bin dirs are in PATH (legacy bulk check).
 #[command]
 pub fn check_path_status(app: AppHandle) -> Result<PathStatus, String> {
-    let bin_path = app.path().app_local_data_dir()
+    let bin_path = app.path().orbit_data_dir()
         .map_err(|e| e.to_string())?.join("bin");
     let bin_str  = bin_path.to_string_lossy().to_string();
 
@@ -359,7 +360,7 @@ pub fn check_path_status(app: AppHandle) -> Result<PathStatus, String> {
 #[allow(clippy::needless_return, unused_variables)]
 pub fn remove_from_path(app: AppHandle) -> Result<String, String> {
     #[cfg(windows)]
-    let bin_path = app.path().app_local_data_dir()
+    let bin_path = app.path().orbit_data_dir()
         .map_err(|e| e.to_string())?.join("bin");
     #[cfg(windows)]
     let bin_str = bin_path.to_string_lossy().to_string();
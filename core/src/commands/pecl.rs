@@ -6,6 +6,7 @@
 #[cfg(target_os = "windows")]
 use crate::services::download::download_file;
 use crate::services::validation::validate_php_version;
+use crate::services::paths::OrbitPathExt;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
@@ -37,7 +38,7 @@ pub async fn get_available_extensions(
 
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -206,7 +207,7 @@ pub async fn install_pecl_extension(
 
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -263,7 +264,7 @@ async fn install_extension_windows(
     // Create temp directory
     let temp_dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("temp");
@@ -491,7 +492,7 @@ pub async fn uninstall_pecl_extension(
 
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
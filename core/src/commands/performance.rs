@@ -1,6 +1,8 @@
 use std::fs;
 use tauri::{command, AppHandle, Manager};
 use crate::services::nginx::NginxManager;
+use crate::services::pending_restarts::PendingRestarts;
+use crate::services::paths::OrbitPathExt;
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct PerformanceStatus {
@@ -53,7 +55,7 @@ pub fn get_performance_status(app: AppHandle, php_version: Option<String>) -> Re
 pub fn get_opcache_config(app: AppHandle, version: String) -> Result<OpcacheConfig, String> {
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -103,7 +105,7 @@ pub fn get_opcache_config(app: AppHandle, version: String) -> Result<OpcacheConf
 pub fn set_opcache_config(app: AppHandle, version: String, config: OpcacheConfig) -> Result<String, String> {
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -161,6 +163,8 @@ opcache.fast_shutdown=1
     fs::write(&ini_path, content)
         .map_err(|e| format!("Failed to write php.ini: {e}"))?;
 
+    PendingRestarts::mark(&app, &format!("php-{version}"), "OPcache settings changed");
+
     Ok("OPcache configuration updated. Restart PHP to apply changes.".to_string())
 }
 
@@ -325,7 +329,7 @@ pub fn save_nginx_conf_raw(app: AppHandle, content: String) -> Result<String, St
 pub fn get_mariadb_conf_raw(app: AppHandle) -> Result<String, String> {
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("data")
@@ -348,7 +352,7 @@ pub fn save_mariadb_conf_raw(app: AppHandle, content: String) -> Result<String,
 
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("data")
@@ -364,6 +368,8 @@ pub fn save_mariadb_conf_raw(app: AppHandle, content: String) -> Result<String,
 
     fs::write(&ini_path, &content).map_err(|e| format!("Failed to save my.ini: {e}"))?;
 
+    PendingRestarts::mark(&app, "mariadb", "my.ini edited");
+
     Ok("my.ini saved successfully".to_string())
 }
 
@@ -372,7 +378,7 @@ pub fn save_mariadb_conf_raw(app: AppHandle, content: String) -> Result<String,
 pub fn get_apache_conf_raw(app: AppHandle) -> Result<String, String> {
     let conf_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("apache")
@@ -395,7 +401,7 @@ pub fn save_apache_conf_raw(app: AppHandle, content: String) -> Result<String, S
 
     let conf_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("apache")
@@ -411,6 +417,8 @@ pub fn save_apache_conf_raw(app: AppHandle, content: String) -> Result<String, S
 
     fs::write(&conf_path, &content).map_err(|e| format!("Failed to save httpd.conf: {e}"))?;
 
+    PendingRestarts::mark(&app, "apache", "httpd.conf edited");
+
     Ok("httpd.conf saved successfully".to_string())
 }
 
@@ -419,7 +427,7 @@ pub fn save_apache_conf_raw(app: AppHandle, content: String) -> Result<String, S
 fn get_opcache_status(app: &AppHandle, version: &str) -> Result<(bool, String), String> {
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -528,7 +536,7 @@ pub fn clear_all_caches(app: AppHandle, php_version: Option<String>) -> Result<C
         }
     } else {
         // Clear for all installed PHP versions in the bin/php directory
-        let php_dir_result = app.path().app_local_data_dir().map(|dir| dir.join("bin").join("php"));
+        let php_dir_result = app.path().orbit_data_dir().map(|dir| dir.join("bin").join("php"));
         
         if let Ok(php_dir) = php_dir_result {
             if php_dir.exists() {
@@ -596,7 +604,7 @@ pub fn clear_all_caches(app: AppHandle, php_version: Option<String>) -> Result<C
 fn clear_opcache(app: &AppHandle, version: &str) -> Result<(), String> {
     let php_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -610,7 +618,7 @@ fn clear_opcache(app: &AppHandle, version: &str) -> Result<(), String> {
     // Create a temp PHP script to clear OPcache
     let temp_dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("temp");
 
@@ -656,14 +664,14 @@ if (function_exists('opcache_reset')) {
 fn clear_temp_files(app: &AppHandle) -> Result<u32, String> {
     let downloads_dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("downloads");
 
     let temp_dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("temp");
 
@@ -703,7 +711,7 @@ fn clear_temp_files(app: &AppHandle) -> Result<u32, String> {
 fn clear_nginx_cache(app: &AppHandle) -> Result<bool, String> {
     let cache_dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("nginx")
@@ -1,4 +1,6 @@
+use crate::services::pending_restarts::PendingRestarts;
 use crate::services::validation::{validate_ini_key, validate_ini_value, validate_php_version};
+use crate::services::paths::OrbitPathExt;
 use std::collections::HashMap;
 use std::fs;
 use tauri::{command, AppHandle, Manager};
@@ -26,7 +28,7 @@ pub fn get_php_config(app: AppHandle, version: String) -> Result<PhpConfig, Stri
 
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -130,7 +132,7 @@ pub fn set_php_extension(
 
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -164,6 +166,9 @@ pub fn set_php_extension(
 
     fs::write(&ini_path, content).map_err(|e| format!("Failed to write php.ini: {e}"))?;
 
+    let action = if enabled { "enabled" } else { "disabled" };
+    PendingRestarts::mark(&app, &format!("php-{version}"), &format!("extension {extension} {action}"));
+
     Ok(format!(
         "Extension {} {}",
         extension,
@@ -186,7 +191,7 @@ pub fn set_php_setting(
 
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -204,6 +209,8 @@ pub fn set_php_setting(
 
     fs::write(&ini_path, new_content).map_err(|e| format!("Failed to write php.ini: {e}"))?;
 
+    PendingRestarts::mark(&app, &format!("php-{version}"), &format!("{key} changed in php.ini"));
+
     Ok(format!("Setting {key} updated to {value}"))
 }
 
@@ -270,7 +277,7 @@ pub fn get_php_ini_raw(app: AppHandle, version: String) -> Result<String, String
 
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -301,7 +308,7 @@ pub fn save_php_ini_raw(app: AppHandle, version: String, content: String) -> Res
 
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -316,6 +323,8 @@ pub fn save_php_ini_raw(app: AppHandle, version: String, content: String) -> Res
 
     fs::write(&ini_path, &content).map_err(|e| format!("Failed to save php.ini: {e}"))?;
 
+    PendingRestarts::mark(&app, &format!("php-{version}"), "php.ini edited");
+
     Ok("php.ini saved successfully".to_string())
 }
 
@@ -326,7 +335,7 @@ pub fn configure_php_mailpit(app: AppHandle, version: String, enabled: bool, smt
 
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -378,6 +387,8 @@ pub fn configure_php_mailpit(app: AppHandle, version: String, enabled: bool, smt
 
     fs::write(&ini_path, &content).map_err(|e| format!("Failed to save php.ini: {e}"))?;
 
+    PendingRestarts::mark(&app, &format!("php-{version}"), "Mailpit mail settings changed");
+
     if enabled {
         Ok(format!("PHP configured to use Mailpit (SMTP port {smtp_port})"))
     } else {
@@ -392,7 +403,7 @@ pub fn get_php_mailpit_status(app: AppHandle, version: String) -> Result<bool, S
 
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -416,7 +427,7 @@ pub fn configure_php_redis_session(app: AppHandle, version: String, enabled: boo
 
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -430,7 +441,7 @@ pub fn configure_php_redis_session(app: AppHandle, version: String, enabled: boo
     // Check if redis extension is available
     let ext_dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -482,6 +493,8 @@ pub fn configure_php_redis_session(app: AppHandle, version: String, enabled: boo
 
     fs::write(&ini_path, &content).map_err(|e| format!("Failed to save php.ini: {e}"))?;
 
+    PendingRestarts::mark(&app, &format!("php-{version}"), "Redis session settings changed");
+
     if enabled {
         Ok(format!("PHP sessions configured to use Redis (port {redis_port})"))
     } else {
@@ -496,7 +509,7 @@ pub fn get_php_redis_session_status(app: AppHandle, version: String) -> Result<b
 
     let ini_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin")
         .join("php")
@@ -1,8 +1,10 @@
+use crate::services::permissions::{self, PathPermission};
 use tauri::command;
 
 #[derive(serde::Serialize)]
 pub struct SystemRequirements {
     pub vc_redist_installed: bool,
+    pub permissions: Vec<PathPermission>,
 }
 
 #[command]
@@ -11,9 +13,16 @@ pub fn check_system_requirements() -> SystemRequirements {
     
     SystemRequirements {
         vc_redist_installed,
+        permissions: permissions::check_all(false),
     }
 }
 
+/// Try to repair write access on the paths flagged by check_system_requirements
+#[command]
+pub fn fix_permissions() -> Vec<PathPermission> {
+    permissions::check_all(true)
+}
+
 #[cfg(target_os = "windows")]
 fn check_vc_redist() -> bool {
     use winreg::enums::*;
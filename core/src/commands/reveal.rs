@@ -0,0 +1,55 @@
+use std::path::Path;
+use std::process::Command;
+use tauri::command;
+
+/// Build the per-OS command that shows `path` in the file manager.
+/// Explorer and Finder select the file; xdg-open can only open its folder.
+fn reveal_command(path: &Path) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        let mut cmd = Command::new("explorer");
+        if path.is_dir() {
+            cmd.arg(path);
+        } else {
+            // explorer parses its own command line, so the quotes must be passed through verbatim
+            cmd.raw_arg(format!("/select,\"{}\"", path.display()));
+        }
+        cmd
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let mut cmd = Command::new("open");
+        if !path.is_dir() {
+            cmd.arg("-R");
+        }
+        cmd.arg(path);
+        cmd
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let folder = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+        let mut cmd = Command::new("xdg-open");
+        cmd.arg(folder);
+        cmd
+    }
+}
+
+/// Show a file or folder in Explorer/Finder/the desktop file manager.
+#[command]
+pub fn reveal_path(path: String) -> Result<(), String> {
+    let target = Path::new(&path);
+    if !target.exists() {
+        return Err(format!("Path not found: {path}"));
+    }
+    let target = target
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve path: {e}"))?;
+    #[cfg(target_os = "windows")]
+    let target = std::path::PathBuf::from(target.to_string_lossy().trim_start_matches(r"\\?\"));
+
+    reveal_command(&target)
+        .spawn()
+        .map_err(|e| format!("Failed to open file manager: {e}"))?;
+    Ok(())
+}
@@ -3,6 +3,8 @@ use tauri::command;
 use tauri::AppHandle;
 use tauri::Manager;
 use crate::services::hidden_command;
+use crate::services::paths::OrbitPathExt;
+use crate::services::php_registry::php_port;
 
 #[derive(serde::Serialize)]
 pub struct InstalledService {
@@ -19,7 +21,7 @@ pub fn get_installed_services(app: AppHandle) -> Result<Vec<InstalledService>, S
 
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -86,14 +88,13 @@ pub fn get_installed_services(app: AppHandle) -> Result<Vec<InstalledService>, S
                         if exe_path.exists() {
                             let actual_version = parse_php_version(&exe_path)
                                 .unwrap_or_else(|_| version_str.clone());
-                            // Calculate PHP port from version
-                            let php_port = parse_php_port(&actual_version);
+                            let port = php_port(&actual_version);
                             services.push(InstalledService {
                                 name: format!("php-{version_str}"),
                                 version: actual_version,
                                 path: exe_path.to_string_lossy().to_string(),
                                 service_type: "php".to_string(),
-                                port: php_port,
+                                port,
                             });
                         }
                     }
@@ -583,17 +584,6 @@ fn parse_mariadb_port(bin_path: &std::path::Path) -> Option<u16> {
     None
 }
 
-/// Calculate PHP FastCGI port from version string
-fn parse_php_port(version: &str) -> Option<u16> {
-    let parts: Vec<&str> = version.split('.').collect();
-    if parts.len() >= 2 {
-        if let (Ok(major), Ok(minor)) = (parts[0].parse::<u16>(), parts[1].parse::<u16>()) {
-            return Some(9000 + (major * 10 + minor) - 80);
-        }
-    }
-    None
-}
-
 /// Parse Listen port from Apache httpd.conf
 fn parse_apache_port(bin_path: &std::path::Path) -> Option<u16> {
     let conf_path = bin_path.join("apache").join("conf").join("httpd.conf");
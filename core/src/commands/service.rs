@@ -1,5 +1,9 @@
+use crate::services::pending_restarts::{PendingRestart, PendingRestarts};
+use crate::services::php_pool::PhpPool;
 use crate::services::php_registry::PhpRegistry;
 use crate::services::process::{ServiceManager, ServiceType};
+use crate::services::session::SessionReport;
+use crate::services::paths::OrbitPathExt;
 use tauri::{command, AppHandle, Manager, State};
 
 /// Parse PHP version from service name (e.g., "php-8.4" -> "8.4")
@@ -81,22 +85,26 @@ pub fn start_service(
         ServiceType::MariaDB
     };
 
-    // Build args based on service type
+    // Build args based on service type; PHP may be served by php-fpm instead
     let bin_path_buf = std::path::PathBuf::from(&bin_path);
+    let mut program = bin_path.clone();
 
     let args: Vec<String> = match service_type {
         ServiceType::Nginx => vec![],
         ServiceType::Php(_) => {
             // Get port from registry
             let port = get_php_port(&app, &php_version_str);
-            vec!["-b".to_string(), format!("127.0.0.1:{}", port)]
+            let config_dir = app.path().orbit_data_dir().map_err(|e| e.to_string())?.join("config");
+            let launch = PhpPool::load(&config_dir, &php_version_str).launch(&bin_path_buf, port);
+            program = launch.program.display().to_string();
+            launch.args
         }
         ServiceType::MariaDB => {
             use crate::services::mariadb::MariaDBManager;
 
             let app_bin = app
                 .path()
-                .app_local_data_dir()
+                .orbit_data_dir()
                 .map_err(|e| e.to_string())?
                 .join("bin");
 
@@ -131,7 +139,7 @@ pub fn start_service(
 
             let app_bin = app
                 .path()
-                .app_local_data_dir()
+                .orbit_data_dir()
                 .map_err(|e| e.to_string())?
                 .join("bin");
 
@@ -156,7 +164,7 @@ pub fn start_service(
 
             let app_bin = app
                 .path()
-                .app_local_data_dir()
+                .orbit_data_dir()
                 .map_err(|e| e.to_string())?
                 .join("bin");
 
@@ -207,8 +215,9 @@ pub fn start_service(
     // Convert Vec<String> to Vec<&str> for the function call
     let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
 
-    match state.start_with_name(name.clone(), service_type, &bin_path, &args_refs) {
+    match state.start_with_name(name.clone(), service_type, &program, &args_refs) {
         Ok(pid) => {
+            PendingRestarts::clear(&app, &name);
             // Update PHP registry if it's a PHP service
             if is_php {
                 if let Ok(mut registry) = PhpRegistry::load(&app) {
@@ -234,6 +243,7 @@ pub fn stop_service(
 ) -> Result<String, String> {
     match state.stop(&name) {
         Ok(_) => {
+            PendingRestarts::clear(&app, &name);
             // Update PHP registry if it's a PHP service
             if name.contains("php") {
                 let php_version_str = parse_php_version_string(&name);
@@ -248,6 +258,24 @@ pub fn stop_service(
     }
 }
 
+/// Match the session file against running processes (run on launch): adopts
+/// Orbit's own services from a crashed session and reports orphans.
+#[command]
+pub fn reconcile_services(state: State<'_, ServiceManager>) -> Result<SessionReport, String> {
+    state.reconcile_session()
+}
+
+/// Take over an orphaned Orbit process reported by `reconcile_services`.
+#[command]
+pub fn adopt_service(
+    state: State<'_, ServiceManager>,
+    name: String,
+    pid: u32,
+) -> Result<String, String> {
+    state.adopt(&name, pid)?;
+    Ok(format!("Service {name} (PID {pid}) is now managed by Orbit"))
+}
+
 #[command]
 pub fn get_service_status(
     state: State<'_, ServiceManager>,
@@ -265,7 +293,7 @@ pub fn initialize_mariadb(app: AppHandle, _root_password: String) -> Result<Stri
 
     let bin_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("bin");
 
@@ -318,6 +346,12 @@ pub fn check_port_conflict(port: u16) -> Result<bool, String> {
     }
 }
 
+/// List services whose config changed since they were last started
+#[command]
+pub fn get_pending_restarts(app: AppHandle) -> Result<Vec<PendingRestart>, String> {
+    Ok(PendingRestarts::load(&app))
+}
+
 #[command]
 pub fn reload_service(app: AppHandle, name: String) -> Result<String, String> {
     use crate::services::nginx::NginxManager;
@@ -330,7 +364,7 @@ pub fn reload_service(app: AppHandle, name: String) -> Result<String, String> {
         // Apache graceful restart
         let bin_path = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("apache");
@@ -354,6 +388,7 @@ pub fn reload_service(app: AppHandle, name: String) -> Result<String, String> {
             .map_err(|e| format!("Failed to reload Apache: {e}"))?;
 
         if output.status.success() {
+            PendingRestarts::clear(&app, "apache");
             Ok("Apache configuration reloaded".to_string())
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
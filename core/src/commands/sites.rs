@@ -2,6 +2,7 @@ use crate::services::nginx::NginxManager;
 use crate::services::site_process::SiteProcessManager;
 use crate::services::site_store::{SiteMetadata, SiteStore};
 use crate::services::sites::{Site, SiteManager, SiteWithStatus};
+use crate::services::paths::OrbitPathExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -307,7 +308,7 @@ pub fn write_site_config(app: AppHandle, domain: String, content: String) -> Res
 fn site_app_log_path(app: &AppHandle, domain: &str) -> Result<std::path::PathBuf, String> {
     let dir = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("logs")
         .join("site-apps");
@@ -457,7 +458,7 @@ pub struct RecoveryReport {
 fn read_deploy_target_domains(app: &AppHandle) -> Result<HashMap<String, Vec<String>>, String> {
     let targets_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())?
         .join("config")
         .join("deploy-targets.json");
@@ -540,7 +541,7 @@ fn guess_local_path_for_domain(workspace: &std::path::Path, domain: &str) -> Opt
 fn workspace_path(app: &AppHandle) -> Option<std::path::PathBuf> {
     let settings_path = app
         .path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .ok()?
         .join(".settings.json");
     let raw = fs::read_to_string(settings_path).ok()?;
@@ -561,6 +562,7 @@ fn workspace_path(app: &AppHandle) -> Option<std::path::PathBuf> {
 #[command]
 pub fn recover_sites_from_deploy_targets(app: AppHandle) -> Result<RecoveryReport, String> {
     let deploy_domains = read_deploy_target_domains(&app)?;
+    let _lock = SiteStore::lock(&app)?;
     let mut store = SiteStore::load(&app)?;
     let existing: std::collections::HashSet<String> =
         store.sites.iter().map(|s| s.domain.clone()).collect();
@@ -582,11 +584,15 @@ pub fn recover_sites_from_deploy_targets(app: AppHandle) -> Result<RecoveryRepor
             .unwrap_or_default();
         store.add_site(SiteMetadata {
             domain: domain.clone(),
+            aliases: Vec::new(),
             path: guessed_path,
             port: 80,
             php_version: None,
             php_port: None,
             ssl_enabled: false,
+            hsts: false,
+            gzip: false,
+            brotli: false,
             ssl_cert_path: None,
             ssl_key_path: None,
             template: Some("http".to_string()),
@@ -1,9 +1,10 @@
 use crate::services::ssl::{SSLManager, SslCertificate, SslStatus};
+use crate::services::paths::OrbitPathExt;
 use tauri::{command, AppHandle, Manager};
 
 fn get_bin_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
     app.path()
-        .app_local_data_dir()
+        .orbit_data_dir()
         .map_err(|e| e.to_string())
         .map(|p| p.join("bin"))
 }
@@ -1,6 +1,7 @@
 use serde::Serialize;
 use tauri::{AppHandle, Manager};
 use crate::services::tunnel::TunnelManager;
+use crate::services::paths::OrbitPathExt;
 
 #[derive(Serialize)]
 pub struct TunnelResponse {
@@ -27,7 +28,7 @@ pub fn start_tunnel(
     auth_token: String,
     app: AppHandle,
 ) -> Result<TunnelResponse, String> {
-    let base_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    let base_dir = app.path().orbit_data_dir().map_err(|e| e.to_string())?;
     let manager = TunnelManager::new(base_dir);
     
     match manager.start_tunnel(&domain, port, &auth_token) {
@@ -42,7 +43,7 @@ pub fn start_tunnel(
 
 #[tauri::command]
 pub fn stop_tunnel(app: AppHandle) -> Result<TunnelResponse, String> {
-    let base_dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    let base_dir = app.path().orbit_data_dir().map_err(|e| e.to_string())?;
     let manager = TunnelManager::new(base_dir);
     
     match manager.stop_tunnel() {
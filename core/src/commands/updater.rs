@@ -50,6 +50,8 @@ fn is_newer_version(current: &str, latest: &str) -> bool {
 
 #[command]
 pub async fn check_for_updates() -> Result<UpdateInfo, String> {
+    crate::services::network::require_online("check for updates")?;
+
     let url = format!(
         "https://api.github.com/repos/{GITHUB_REPO}/releases/latest"
     );
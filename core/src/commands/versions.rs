@@ -1,4 +1,6 @@
+use serde::Serialize;
 use tauri::{command, AppHandle};
+use crate::services::network::{probe_network, NetworkSettings};
 use crate::services::versions::{VersionFetcher, ServiceVersion};
 
 #[command]
@@ -15,3 +17,36 @@ pub async fn get_available_versions(
 pub async fn refresh_all_versions(app: AppHandle) -> Result<(), String> {
     VersionFetcher::refresh_all(&app).await
 }
+
+/// Concurrency and timeout limits for version lookups and downloads
+#[command]
+pub fn get_network_settings() -> NetworkSettings {
+    NetworkSettings::load()
+}
+
+#[command]
+pub fn set_network_settings(settings: NetworkSettings) -> Result<(), String> {
+    settings.save()
+}
+
+#[derive(Serialize)]
+pub struct ConnectivityStatus {
+    pub offline_mode: bool,
+    pub reachable: bool,
+    /// Network looks down while offline mode is off
+    pub suggest_offline: bool,
+}
+
+/// Probe the network so the UI can suggest offline mode
+#[command]
+pub async fn check_connectivity() -> Result<ConnectivityStatus, String> {
+    let offline_mode = NetworkSettings::load().offline;
+    let reachable = tauri::async_runtime::spawn_blocking(probe_network)
+        .await
+        .map_err(|e| format!("Connectivity check failed: {e}"))?;
+    Ok(ConnectivityStatus {
+        offline_mode,
+        reachable,
+        suggest_offline: !offline_mode && !reachable,
+    })
+}
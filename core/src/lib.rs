@@ -3,6 +3,7 @@ mod services;
 
 use services::process::ServiceManager;
 use services::site_process::SiteProcessManager;
+use services::paths::OrbitPathExt;
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::{TrayIconBuilder, TrayIconEvent};
 use tauri::Manager;
@@ -27,7 +28,7 @@ pub fn run() {
         // into the new multi-version layout (`bin/.versions/<svc>/<ver>/`
         // + `bin/<svc>` junction). Idempotent — already-junctioned services
         // are skipped. Failures are logged but never block startup.
-        if let Ok(app_data) = app.path().app_local_data_dir() {
+        if let Ok(app_data) = app.path().orbit_data_dir() {
             let bin_path = app_data.join("bin");
             if bin_path.exists() {
                 let migrated = services::version_manager::migrate_legacy(&bin_path);
@@ -63,6 +64,21 @@ pub fn run() {
             }
         }
 
+        // Pick up services left running by a previous session (e.g. after a crash)
+        match app.state::<ServiceManager>().reconcile_session() {
+            Ok(report) => {
+                if !report.adopted.is_empty() || !report.orphans.is_empty() {
+                    log::info!(
+                        "Session reconcile: adopted [{}], {} orphaned Orbit process(es), {} stale session entries",
+                        report.adopted.iter().map(|e| e.service.as_str()).collect::<Vec<_>>().join(", "),
+                        report.orphans.len(),
+                        report.stale.len()
+                    );
+                }
+            }
+            Err(e) => log::warn!("Session reconcile failed: {e}"),
+        }
+
         // System Tray Setup
         let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
         let show_i = MenuItem::with_id(app, "show", "Show Dashboard", true, None::<&str>)?;
@@ -122,10 +138,15 @@ pub fn run() {
         commands::service::stop_service,
         commands::service::reload_service,
         commands::service::get_service_status,
+        commands::service::reconcile_services,
+        commands::service::adopt_service,
         commands::service::uninstall_service,
         commands::service::initialize_mariadb,
         commands::service::assign_php_port,
         commands::service::check_port_conflict,
+        commands::service::get_pending_restarts,
+        commands::data_dir::get_data_dir_info,
+        commands::data_dir::set_data_dir_override,
         // Hosts file
         commands::hosts::add_host,
         commands::hosts::add_host_elevated,
@@ -140,6 +161,9 @@ pub fn run() {
         commands::installer::delete_migration_backup,
         commands::versions::get_available_versions,
         commands::versions::refresh_all_versions,
+        commands::versions::get_network_settings,
+        commands::versions::set_network_settings,
+        commands::versions::check_connectivity,
         commands::scanner::get_installed_services,
         // Sites
         commands::sites::create_site,
@@ -171,6 +195,18 @@ pub fn run() {
         commands::logs::read_log_file,
         commands::logs::clear_log_file,
         commands::logs::clear_all_logs,
+        // File manager
+        commands::reveal::reveal_path,
+        // Clipboard
+        commands::clipboard::copy_text,
+        commands::clipboard::get_connection_string,
+        commands::clipboard::get_site_url,
+        commands::clipboard::copy_connection_string,
+        commands::clipboard::copy_site_url,
+        // Scheduled tasks
+        commands::cron::get_scheduled_tasks,
+        commands::cron::add_scheduled_task,
+        commands::cron::remove_scheduled_task,
         // SSL
         commands::ssl::get_ssl_status,
         commands::ssl::install_mkcert,
@@ -229,6 +265,7 @@ pub fn run() {
         commands::autostart::auto_start_services,
         // Requirements
         commands::requirements::check_system_requirements,
+        commands::requirements::fix_permissions,
         // Templates
         commands::templates::list_templates,
         commands::templates::get_template,
@@ -264,6 +301,9 @@ pub fn run() {
         commands::performance::save_mariadb_conf_raw,
         commands::performance::get_apache_conf_raw,
         commands::performance::save_apache_conf_raw,
+        commands::apache::list_apache_modules,
+        commands::apache::toggle_apache_module,
+        commands::apache::get_apache_template_modules,
         commands::performance::clear_all_caches,
         // Mailpit (Mail server)
         commands::mailpit::get_mailpit_status,
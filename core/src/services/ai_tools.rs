@@ -4,7 +4,9 @@ use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 
 use super::hidden_command;
+use crate::services::php_registry::PhpRegistry;
 use crate::services::site_store::SiteStore;
+use crate::services::paths::OrbitPathExt;
 
 /// AI tool status information
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -168,16 +170,6 @@ fn is_port_in_use(port: u16) -> bool {
     || TcpListener::bind(format!("0.0.0.0:{port}")).is_err()
 }
 
-/// Parse PHP minor version from a version string like "8.4" → 4
-fn php_port_from_version(php_version: &str) -> u16 {
-  let minor = php_version
-    .split('.')
-    .nth(1)
-    .and_then(|s| s.parse::<u16>().ok())
-    .unwrap_or(0);
-  9000 + minor
-}
-
 /// Generate AI context markdown for a site project
 pub fn generate_ai_context(
   app: &AppHandle,
@@ -251,7 +243,7 @@ pub fn generate_ai_context(
 
   // Check PHP if site has a PHP version
   if let Some(php_ver) = &site.php_version {
-    let php_port = php_port_from_version(php_ver);
+    let php_port = PhpRegistry::calculate_port(php_ver);
     if is_port_in_use(php_port) {
       active_services.push(format!("- **php-{php_ver}** (port {php_port})"));
     }
@@ -478,7 +470,7 @@ pub fn write_context_file(
 pub fn setup_mcp_for_claude(app: &AppHandle) -> Result<(), String> {
   let mcp_exe = app
     .path()
-    .app_local_data_dir()
+    .orbit_data_dir()
     .map_err(|e| e.to_string())?
     .join("bin")
     .join("mcp")
@@ -538,7 +530,7 @@ impl GeminiCliManager {
   pub fn get_npm_path(app: &AppHandle) -> Result<PathBuf, String> {
     let base = app
       .path()
-      .app_local_data_dir()
+      .orbit_data_dir()
       .map_err(|e| e.to_string())?
       .join("bin")
       .join("nodejs");
@@ -554,7 +546,7 @@ impl GeminiCliManager {
   pub fn get_orbit_exe_path(app: &AppHandle) -> Result<PathBuf, String> {
     let base = app
       .path()
-      .app_local_data_dir()
+      .orbit_data_dir()
       .map_err(|e| e.to_string())?
       .join("bin")
       .join("nodejs");
@@ -1,11 +1,35 @@
+use serde::Serialize;
 use std::fs;
 use std::path::PathBuf;
 #[cfg(not(windows))]
 use std::process::Command;
 use tauri::{AppHandle, Manager};
+use crate::services::paths::OrbitPathExt;
 
 use super::hidden_command;
 
+/// A `LoadModule` entry in httpd.conf
+#[derive(Debug, Clone, Serialize)]
+pub struct ApacheModule {
+    pub name: String, // e.g., "rewrite_module"
+    pub file: String, // e.g., "modules/mod_rewrite.so"
+    pub enabled: bool,
+}
+
+/// Split a (possibly commented) LoadModule line into (enabled, name, file)
+fn parse_load_module(line: &str) -> Option<(bool, &str, &str)> {
+    let trimmed = line.trim();
+    let (enabled, rest) = match trimmed.strip_prefix('#') {
+        Some(r) => (false, r.trim_start()),
+        None => (true, trimmed),
+    };
+    let parts: Vec<&str> = rest.split_whitespace().collect();
+    if parts.len() != 3 || parts[0] != "LoadModule" {
+        return None;
+    }
+    Some((enabled, parts[1], parts[2].trim_matches('"')))
+}
+
 pub struct ApacheManager;
 
 impl ApacheManager {
@@ -13,7 +37,7 @@ impl ApacheManager {
     pub fn get_apache_path(app: &AppHandle) -> Result<PathBuf, String> {
         let apache_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("apache");
@@ -40,7 +64,6 @@ impl ApacheManager {
     }
 
     /// Check if Apache is installed
-    #[allow(dead_code)]
     pub fn is_installed(app: &AppHandle) -> bool {
         if let Ok(apache_path) = Self::get_apache_path(app) {
             apache_path.join("bin").join("httpd.exe").exists()
@@ -146,8 +169,81 @@ impl ApacheManager {
         Ok(())
     }
 
+    /// List LoadModule entries from httpd.conf
+    pub fn list_modules(app: &AppHandle) -> Result<Vec<ApacheModule>, String> {
+        let httpd_conf = Self::get_config_dir(app)?.join("httpd.conf");
+        let content = fs::read_to_string(&httpd_conf)
+            .map_err(|e| format!("Failed to read httpd.conf: {e}"))?;
+
+        Ok(content
+            .lines()
+            .filter_map(parse_load_module)
+            .map(|(enabled, name, file)| ApacheModule {
+                name: name.to_string(),
+                file: file.to_string(),
+                enabled,
+            })
+            .collect())
+    }
+
+    /// Comment/uncomment a module's LoadModule line. httpd.conf is backed up,
+    /// validated with `httpd -t` (restored on failure) and Apache reloaded if running.
+    pub fn toggle_module(app: &AppHandle, name: &str, enabled: bool) -> Result<String, String> {
+        let base = name.trim().trim_start_matches("mod_").trim_end_matches("_module");
+        let module = format!("{base}_module");
+        let httpd_conf = Self::get_config_dir(app)?.join("httpd.conf");
+        let content = fs::read_to_string(&httpd_conf)
+            .map_err(|e| format!("Failed to read httpd.conf: {e}"))?;
+
+        let mut found = false;
+        let mut changed = false;
+        // Line by line with each line's own ending, so a CRLF httpd.conf stays CRLF
+        let new_content: String = content
+            .split_inclusive('\n')
+            .map(|line| match parse_load_module(line) {
+                Some((is_enabled, n, file)) if n == module => {
+                    found = true;
+                    if is_enabled == enabled {
+                        return line.to_string();
+                    }
+                    changed = true;
+                    let eol = &line[line.trim_end_matches(['\r', '\n']).len()..];
+                    let directive = format!("LoadModule {n} {file}");
+                    if enabled { format!("{directive}{eol}") } else { format!("#{directive}{eol}") }
+                }
+                _ => line.to_string(),
+            })
+            .collect();
+
+        let action = if enabled { "enabled" } else { "disabled" };
+        if !found {
+            return Err(format!("Module '{module}' has no LoadModule line in httpd.conf"));
+        }
+        if !changed {
+            return Ok(format!("Module '{module}' is already {action}"));
+        }
+
+        let backup_path = httpd_conf.with_extension("conf.bak");
+        fs::copy(&httpd_conf, &backup_path)
+            .map_err(|e| format!("Failed to back up httpd.conf: {e}"))?;
+        fs::write(&httpd_conf, &new_content)
+            .map_err(|e| format!("Failed to write httpd.conf: {e}"))?;
+
+        if Self::is_installed(app) {
+            if let Err(e) = Self::test_config(app) {
+                let _ = fs::write(&httpd_conf, &content);
+                return Err(format!("{e} — httpd.conf restored"));
+            }
+        }
+
+        if Self::is_running() {
+            Self::reload(app)?;
+            return Ok(format!("Module '{module}' {action} and Apache reloaded"));
+        }
+        Ok(format!("Module '{module}' {action}"))
+    }
+
     /// Check if Apache is running (check for httpd process)
-    #[allow(dead_code)]
     pub fn is_running() -> bool {
         #[cfg(windows)]
         {
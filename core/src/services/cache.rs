@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
+use crate::services::paths::OrbitPathExt;
 
 /// Cache service configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,7 +38,7 @@ impl CacheManager {
     fn get_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
         let bin_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin");
         Ok(bin_dir)
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
+use crate::services::paths::OrbitPathExt;
 
 use super::hidden_command;
 
@@ -27,7 +28,7 @@ impl CliManager {
     fn get_cli_dir(app: &AppHandle) -> Result<PathBuf, String> {
         let bin_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("cli");
@@ -103,6 +104,7 @@ impl CliManager {
 
     /// Download and install CLI binary from GitHub releases
     pub async fn install(app: &AppHandle) -> Result<(), String> {
+        crate::services::network::require_online("download the CLI")?;
         let cli_dir = Self::get_cli_dir(app)?;
 
         // Create directory
@@ -172,6 +174,7 @@ impl CliManager {
 
     /// Check if an update is available
     pub async fn check_for_update(app: &AppHandle) -> Result<BinaryUpdateInfo, String> {
+        crate::services::network::require_online("check for updates")?;
         let current = Self::get_version(app).unwrap_or_else(|| "0.0.0".to_string());
         let latest = Self::fetch_latest_version().await
             .ok_or_else(|| "Failed to fetch latest version from GitHub".to_string())?;
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
+use crate::services::paths::OrbitPathExt;
 
 use super::hidden_command;
 
@@ -38,7 +39,7 @@ impl ComposerManager {
     fn get_composer_dir(app: &AppHandle) -> Result<PathBuf, String> {
         let bin_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("composer");
@@ -61,7 +62,7 @@ impl ComposerManager {
     fn get_php_exe(app: &AppHandle) -> Result<PathBuf, String> {
         let bin_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("php");
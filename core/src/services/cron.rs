@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::services::file_lock::FileLock;
+
+/// A site command run on a fixed interval, e.g. Laravel's
+/// `php artisan schedule:run` every 60 seconds. Stored in config/cron.json;
+/// orbit-mcp runs due tasks from its standby loop (and `orbit cron run` from
+/// the CLI), logging to logs/cron/<name>.log.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduledTask {
+    pub name: String,
+    pub command: String,
+    pub interval_secs: u64,
+    pub working_dir: String,
+    /// Unix seconds of the last run (or skipped run)
+    #[serde(default)]
+    pub last_run: Option<u64>,
+    /// PID and process start time of the last invocation, so any runner can
+    /// tell whether it is still going without trusting a recycled PID
+    #[serde(default)]
+    pub last_pid: Option<u32>,
+    #[serde(default)]
+    pub last_pid_started_at: Option<u64>,
+}
+
+/// The orbit-mcp standby loop checks for due tasks this often
+const CRON_MIN_INTERVAL_SECS: u64 = 10;
+
+pub struct CronStore;
+
+impl CronStore {
+    const FILENAME: &'static str = "cron.json";
+
+    fn get_path() -> PathBuf {
+        crate::services::paths::get_orbit_data_dir()
+            .join("config")
+            .join(Self::FILENAME)
+    }
+
+    /// Load scheduled tasks (empty if the file is missing or unreadable)
+    pub fn load() -> Vec<ScheduledTask> {
+        fs::read_to_string(Self::get_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Hold this across a read-modify-write; orbit-cli and orbit-mcp's task
+    /// runner take the same lock file
+    fn lock() -> Result<FileLock, String> {
+        FileLock::acquire(&Self::get_path().with_file_name("cron.json.lock"))
+    }
+
+    fn save(tasks: &[ScheduledTask]) -> Result<(), String> {
+        let path = Self::get_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {e}"))?;
+        }
+        let content = serde_json::to_string_pretty(tasks)
+            .map_err(|e| format!("Failed to serialize scheduled tasks: {e}"))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write scheduled tasks: {e}"))
+    }
+
+    /// Add a task, or replace the definition of one with the same name
+    pub fn add(name: &str, command: &str, interval_secs: u64, working_dir: &str) -> Result<ScheduledTask, String> {
+        validate_task(name, command, interval_secs, working_dir)?;
+
+        let _lock = Self::lock()?;
+        let mut tasks = Self::load();
+        let previous = tasks.iter().position(|t| t.name == name).map(|i| tasks.remove(i));
+        let task = ScheduledTask {
+            name: name.to_string(),
+            command: command.to_string(),
+            interval_secs,
+            working_dir: working_dir.to_string(),
+            last_run: previous.as_ref().and_then(|t| t.last_run),
+            last_pid: previous.as_ref().and_then(|t| t.last_pid),
+            last_pid_started_at: previous.and_then(|t| t.last_pid_started_at),
+        };
+        tasks.push(task.clone());
+        Self::save(&tasks)?;
+        Ok(task)
+    }
+
+    pub fn remove(name: &str) -> Result<(), String> {
+        let _lock = Self::lock()?;
+        let mut tasks = Self::load();
+        let before = tasks.len();
+        tasks.retain(|t| t.name != name);
+        if tasks.len() == before {
+            return Err(format!("No scheduled task named '{name}'"));
+        }
+        Self::save(&tasks)
+    }
+}
+
+fn validate_task(name: &str, command: &str, interval_secs: u64, working_dir: &str) -> Result<(), String> {
+    // The name becomes a log file name
+    if name.is_empty() || name.len() > 64 || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("Task name must be 1-64 letters, digits, '-' or '_'".to_string());
+    }
+    if command.trim().is_empty() {
+        return Err("Command is required".to_string());
+    }
+    if interval_secs < CRON_MIN_INTERVAL_SECS {
+        return Err(format!("Interval must be at least {CRON_MIN_INTERVAL_SECS} seconds"));
+    }
+    let dir = Path::new(working_dir);
+    if !dir.is_absolute() || !dir.is_dir() {
+        return Err(format!("Working directory must be an existing absolute path: {working_dir}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_validation() {
+        let dir = std::env::temp_dir();
+        let dir = dir.to_string_lossy();
+        assert!(validate_task("schedule-run", "php artisan schedule:run", 60, &dir).is_ok());
+        assert!(validate_task("../escape", "true", 60, &dir).is_err());
+        assert!(validate_task("fast", "true", CRON_MIN_INTERVAL_SECS - 1, &dir).is_err());
+        assert!(validate_task("relative", "true", 60, "some/dir").is_err());
+        assert!(validate_task("empty", "  ", 60, &dir).is_err());
+    }
+}
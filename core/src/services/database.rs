@@ -41,6 +41,7 @@ impl DatabaseManager {
         let adminer_path = Self::get_adminer_path(bin_path);
 
         // Download Adminer
+        crate::services::network::require_online("download Adminer")?;
         let response = reqwest::get(ADMINER_DOWNLOAD_URL)
             .await
             .map_err(|e| format!("Failed to download Adminer: {e}"))?;
@@ -1,4 +1,5 @@
 use crate::services::deploy_store::{AuthMethod, DeployStore, Protocol, ServerConnection};
+use crate::services::paths::OrbitPathExt;
 use serde::{Deserialize, Serialize};
 use ssh2::Session;
 use std::fs;
@@ -595,7 +596,7 @@ impl DeployService {
     fn lock_path(app: &AppHandle, domain: &str) -> Result<std::path::PathBuf, String> {
         let dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("config")
             .join("deploy-locks");
@@ -624,7 +625,7 @@ impl DeployService {
     fn manifest_dir(app: &AppHandle, domain: &str) -> Result<std::path::PathBuf, String> {
         let dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("config")
             .join("deploy-manifests")
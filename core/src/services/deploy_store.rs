@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
+use crate::services::paths::OrbitPathExt;
 
 // ─── New Data Model ───────────────────────────────────────────────
 
@@ -59,7 +60,7 @@ impl DeployStore {
     fn connections_path(app: &AppHandle) -> Result<PathBuf, String> {
         let config_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("config");
         fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
@@ -69,7 +70,7 @@ impl DeployStore {
     fn targets_path(app: &AppHandle) -> Result<PathBuf, String> {
         let config_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("config");
         fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
@@ -3,6 +3,8 @@ use std::fs::File;
 use std::io::Write;
 use futures_util::StreamExt;
 use reqwest::Client;
+use sha2::{Digest, Sha256};
+use crate::services::network::{download_slots, NetworkSettings};
 
 /// Known mirror fallbacks for URLs that serve HTML instead of direct downloads.
 /// Returns an alternative URL if one is known, otherwise None.
@@ -18,7 +20,25 @@ fn get_mirror_url(url: &str) -> Option<String> {
     None
 }
 
+/// Callback for `download_file_with_progress`: bytes downloaded so far and the
+/// total size, which is `None` when the server sends no Content-Length.
+pub type ProgressCallback<'a> = dyn FnMut(u64, Option<u64>) + Send + 'a;
+
 pub async fn download_file(url: &str, dest_path: &PathBuf) -> Result<(), String> {
+    download_file_with_progress(url, dest_path, None, &mut |_, _| {}).await
+}
+
+/// `download_file` that reports progress after every chunk written and, when
+/// `expected_sha256` is given (the registry's `sha256`), rejects a download
+/// whose checksum differs.
+pub async fn download_file_with_progress(
+    url: &str,
+    dest_path: &PathBuf,
+    expected_sha256: Option<&str>,
+    on_progress: &mut ProgressCallback<'_>,
+) -> Result<(), String> {
+    crate::services::network::require_online(&format!("download {url}"))?;
+
     // Try the primary URL first, then fall back to mirror if HTML is received
     let urls_to_try: Vec<String> = {
         let mut urls = vec![url.to_string()];
@@ -31,7 +51,7 @@ pub async fn download_file(url: &str, dest_path: &PathBuf) -> Result<(), String>
     let mut last_error = String::new();
 
     for attempt_url in &urls_to_try {
-        match download_file_single(attempt_url, dest_path).await {
+        match download_file_single(attempt_url, dest_path, expected_sha256, on_progress).await {
             Ok(()) => return Ok(()),
             Err(e) => {
                 log::warn!("Download failed for {attempt_url}: {e}");
@@ -43,15 +63,67 @@ pub async fn download_file(url: &str, dest_path: &PathBuf) -> Result<(), String>
     Err(last_error)
 }
 
-async fn download_file_single(url: &str, dest_path: &PathBuf) -> Result<(), String> {
+/// Where a download is written until it has been validated
+fn part_path(dest_path: &Path) -> PathBuf {
+    let mut name = dest_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest_path.with_file_name(name)
+}
+
+/// Offset a ranged response continues from: `Some(requested)` for a 206 whose
+/// Content-Range starts where we asked, `None` when the download must restart
+/// from zero (200, or a range the server didn't honour).
+fn resume_offset(status: u16, content_range: Option<&str>, requested: u64) -> Option<u64> {
+    if status != 206 || requested == 0 {
+        return None;
+    }
+    // e.g. "bytes 1000-9999/10000"
+    let start: u64 = content_range?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()?;
+    (start == requested).then_some(requested)
+}
+
+async fn download_file_single(
+    url: &str,
+    dest_path: &PathBuf,
+    expected_sha256: Option<&str>,
+    on_progress: &mut ProgressCallback<'_>,
+) -> Result<(), String> {
+    let settings = NetworkSettings::load();
+
+    // Wait for a download slot when the user has limited concurrent downloads
+    let slots = download_slots(settings.max_concurrent_downloads);
+    let _permit = match &slots {
+        Some(slots) => Some(slots.acquire().await.map_err(|e| format!("Download queue closed: {e}"))?),
+        None => None,
+    };
+
     // Create client with proper User-Agent and redirect policy
     let client = Client::builder()
         .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36")
         .redirect(reqwest::redirect::Policy::limited(10))
-        .timeout(std::time::Duration::from_secs(300)) // 5 min timeout for large files
+        .timeout(settings.download_timeout()) // 5 min by default for large files
         .build()
         .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
-    
+
+    // Ensure parent directory exists
+    if let Some(parent) = dest_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {e}"))?;
+    }
+
+    // Bytes land in <file>.part and are only renamed once size and checksum
+    // check out. A retry resumes the .part with a Range request when the
+    // server advertised `Accept-Ranges: bytes`, otherwise it starts over.
+    let part = part_path(dest_path);
+    let _ = std::fs::remove_file(&part);
+    let mut accepts_ranges = false;
+
     // Retry up to 3 times with exponential backoff
     let max_retries = 3;
     let mut last_error = String::new();
@@ -63,14 +135,20 @@ async fn download_file_single(url: &str, dest_path: &PathBuf) -> Result<(), Stri
             tokio::time::sleep(delay).await;
         }
 
-        log::info!("Downloading from: {} (attempt {})", url, attempt + 1);
-        
-        let res = match client.get(url)
+        let resume_from = if accepts_ranges {
+            std::fs::metadata(&part).map(|m| m.len()).unwrap_or(0)
+        } else {
+            0
+        };
+        log::info!("Downloading from: {} (attempt {}, from byte {})", url, attempt + 1, resume_from);
+
+        let mut request = client.get(url)
             .header("Accept", "application/octet-stream, application/zip, application/x-gzip, */*;q=0.1")
-            .header("Accept-Encoding", "identity") // Don't compress the binary download
-            .send()
-            .await
-        {
+            .header("Accept-Encoding", "identity"); // Don't compress the binary download
+        if resume_from > 0 {
+            request = request.header("Range", format!("bytes={resume_from}-"));
+        }
+        let res = match request.send().await {
             Ok(r) => r,
             Err(e) => {
                 last_error = format!("Failed to send request: {e}");
@@ -80,6 +158,11 @@ async fn download_file_single(url: &str, dest_path: &PathBuf) -> Result<(), Stri
 
         if !res.status().is_success() {
             last_error = format!("Download failed with status: {}", res.status());
+            // 416: the .part no longer lines up with the file, start over
+            if res.status().as_u16() == 416 {
+                let _ = std::fs::remove_file(&part);
+                accepts_ranges = false;
+            }
             continue;
         }
 
@@ -91,25 +174,43 @@ async fn download_file_single(url: &str, dest_path: &PathBuf) -> Result<(), Stri
                     "Download blocked: received HTML instead of file (possibly CloudFlare protection). URL: {url}"
                 );
                 // Don't retry for HTML responses - this won't change with retries
+                let _ = std::fs::remove_file(&part);
                 return Err(last_error);
             }
         }
 
+        accepts_ranges = accepts_ranges || res.headers()
+            .get("accept-ranges")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case("bytes"));
+        let content_range = res.headers().get("content-range").and_then(|v| v.to_str().ok());
+        let offset = resume_offset(res.status().as_u16(), content_range, resume_from).unwrap_or(0);
+        if res.status().as_u16() == 206 && offset == 0 {
+            // A range we can't line up with the .part: fetch the whole file
+            last_error = "Server returned an unexpected byte range".to_string();
+            let _ = std::fs::remove_file(&part);
+            accepts_ranges = false;
+            continue;
+        }
+        if offset > 0 {
+            log::info!("Resuming download at byte {offset}");
+        }
+
         // Get content length for progress (optional)
-        let total_size = res.content_length().unwrap_or(0);
+        let remaining = res.content_length().unwrap_or(0);
+        let total_size = if remaining > 0 { offset + remaining } else { 0 };
         log::info!("Download size: {total_size} bytes");
+        let total = (total_size > 0).then_some(total_size);
 
-        // Ensure parent directory exists
-        if let Some(parent) = dest_path.parent() {
-            std::fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create directory: {e}"))?;
-        }
-
-        let mut file = File::create(dest_path)
-            .map_err(|e| format!("Failed to create file: {e}"))?;
+        let file = if offset > 0 {
+            std::fs::OpenOptions::new().append(true).open(&part)
+        } else {
+            File::create(&part)
+        };
+        let mut file = file.map_err(|e| format!("Failed to create file: {e}"))?;
 
         let mut stream = res.bytes_stream();
-        let mut downloaded: u64 = 0;
+        let mut downloaded: u64 = offset;
         let mut stream_error = false;
 
         while let Some(item) = stream.next().await {
@@ -121,6 +222,7 @@ async fn download_file_single(url: &str, dest_path: &PathBuf) -> Result<(), Stri
                         break;
                     }
                     downloaded += chunk.len() as u64;
+                    on_progress(downloaded, total);
                 }
                 Err(e) => {
                     last_error = format!("Error while downloading chunk: {e}");
@@ -129,27 +231,68 @@ async fn download_file_single(url: &str, dest_path: &PathBuf) -> Result<(), Stri
                 }
             }
         }
+        drop(file);
 
         if stream_error {
-            // Clean up partial file
-            let _ = std::fs::remove_file(dest_path);
+            // Keep the partial file only if the next attempt can resume it
+            if !accepts_ranges {
+                let _ = std::fs::remove_file(&part);
+            }
             continue;
         }
 
         // Verify download completed
         if total_size > 0 && downloaded != total_size {
             last_error = format!("Download incomplete: {downloaded} of {total_size} bytes");
-            let _ = std::fs::remove_file(dest_path);
+            if !accepts_ranges || downloaded > total_size {
+                let _ = std::fs::remove_file(&part);
+            }
             continue;
         }
 
+        if let Some(expected) = expected_sha256 {
+            if let Err(e) = verify_sha256(&part, expected) {
+                // A corrupt file won't get better by resuming it
+                let _ = std::fs::remove_file(&part);
+                return Err(e);
+            }
+        }
+
+        std::fs::rename(&part, dest_path).map_err(|e| {
+            let _ = std::fs::remove_file(&part);
+            format!("Failed to move download into place: {e}")
+        })?;
         log::info!("Download complete: {downloaded} bytes written to {dest_path:?}");
         return Ok(());
     }
 
+    // Out of attempts: the next call starts over, so don't leave the .part behind
+    let _ = std::fs::remove_file(&part);
     Err(last_error)
 }
 
+/// Lowercase hex SHA-256 of a file
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok(hasher.finalize().iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Compare a file against the expected SHA-256 (hex, any case)
+pub fn verify_sha256(path: &Path, expected: &str) -> Result<(), String> {
+    let actual = sha256_file(path)?;
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        Err(format!(
+            "Checksum mismatch for {name}: expected SHA-256 {}, got {actual}. The download may be corrupted or tampered with.",
+            expected.trim()
+        ))
+    }
+}
+
 /// Extracts a zip file, optionally stripping a common root folder
 pub fn extract_zip(zip_path: &Path, extract_path: &Path) -> Result<(), String> {
     extract_zip_with_strip(zip_path, extract_path, true)
@@ -331,4 +474,36 @@ pub fn extract_zip_with_strip(zip_path: &Path, extract_path: &Path, strip_root:
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_sha256() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archive.zip");
+        std::fs::write(&path, b"hello world").unwrap();
+
+        let good = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        assert_eq!(sha256_file(&path).unwrap(), good);
+        assert!(verify_sha256(&path, good).is_ok());
+        assert!(verify_sha256(&path, &good.to_uppercase()).is_ok());
+
+        // A truncated download no longer matches
+        std::fs::write(&path, b"hello wor").unwrap();
+        let err = verify_sha256(&path, good).unwrap_err();
+        assert!(err.contains("Checksum mismatch for archive.zip"));
+    }
+
+    #[test]
+    fn test_resume_offset() {
+        assert_eq!(part_path(Path::new("/tmp/downloads/nginx.zip")), PathBuf::from("/tmp/downloads/nginx.zip.part"));
+        assert_eq!(resume_offset(206, Some("bytes 1000-9999/10000"), 1000), Some(1000));
+        // Full body instead of the range: start over
+        assert_eq!(resume_offset(200, None, 1000), None);
+        // Range that doesn't start where the .part ends
+        assert_eq!(resume_offset(206, Some("bytes 0-9999/10000"), 1000), None);
+        assert_eq!(resume_offset(206, None, 1000), None);
+    }
+}
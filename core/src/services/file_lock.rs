@@ -0,0 +1,113 @@
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// How long to wait for another process to release a lock
+const LOCK_WAIT: Duration = Duration::from_secs(10);
+/// A lock file older than this was left behind by a crashed process
+const LOCK_STALE: Duration = Duration::from_secs(30);
+/// Locks taken by this process, so two holders in one process differ too
+static ACQUIRED: AtomicU64 = AtomicU64::new(0);
+
+/// Advisory lock shared by the app, orbit-cli and orbit-mcp: a lock file
+/// created exclusively next to the data it guards and removed on drop.
+/// orbit-cli and orbit-mcp carry their own copy with the same file names.
+pub struct FileLock {
+    path: PathBuf,
+    /// What this holder wrote into the file: its PID and a per-process count
+    owner: String,
+}
+
+impl FileLock {
+    pub fn acquire(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        let deadline = Instant::now() + LOCK_WAIT;
+        loop {
+            match fs::OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(mut file) => {
+                    let owner = format!("{} {}", std::process::id(), ACQUIRED.fetch_add(1, Ordering::Relaxed));
+                    let _ = write!(file, "{owner}");
+                    return Ok(FileLock { path: path.to_path_buf(), owner });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > LOCK_STALE);
+                    if stale {
+                        log::warn!("Removing stale lock {}", path.display());
+                        let _ = fs::remove_file(path);
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        return Err(format!(
+                            "Timed out waiting for {} (another Orbit process is updating it; delete the file if none is)",
+                            path.display()
+                        ));
+                    }
+                    std::thread::sleep(Duration::from_millis(25));
+                }
+                Err(e) => return Err(format!("Failed to create lock file {}: {e}", path.display())),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // A holder that outlived LOCK_STALE may have had its lock taken over;
+        // only remove the file while it is still ours
+        if fs::read_to_string(&self.path).is_ok_and(|content| content == self.owner) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_lock_serializes_writers() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join("sites.json.lock");
+        let counter = dir.path().join("counter");
+        fs::write(&counter, "0").unwrap();
+
+        let handles: Vec<_> = (0..4).map(|_| {
+            let (lock_path, counter) = (lock_path.clone(), counter.clone());
+            std::thread::spawn(move || {
+                let _lock = FileLock::acquire(&lock_path).unwrap();
+                let n: u32 = fs::read_to_string(&counter).unwrap().parse().unwrap();
+                std::thread::sleep(Duration::from_millis(20));
+                fs::write(&counter, (n + 1).to_string()).unwrap();
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&counter).unwrap(), "4");
+        assert!(!lock_path.exists());
+    }
+
+    #[test]
+    fn test_file_lock_keeps_a_lock_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join("sites.json.lock");
+
+        let first = FileLock::acquire(&lock_path).unwrap();
+        // The first holder ran past LOCK_STALE and another took the lock over
+        fs::remove_file(&lock_path).unwrap();
+        let second = FileLock::acquire(&lock_path).unwrap();
+        drop(first);
+        assert!(lock_path.exists());
+        drop(second);
+        assert!(!lock_path.exists());
+    }
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
+use crate::services::paths::OrbitPathExt;
 
 use super::hidden_command;
 
@@ -28,7 +29,7 @@ impl McpManager {
     fn get_mcp_dir(app: &AppHandle) -> Result<PathBuf, String> {
         let bin_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("mcp");
@@ -132,6 +133,7 @@ impl McpManager {
 
     /// Download and install MCP binary from GitHub releases
     pub async fn install(app: &AppHandle) -> Result<(), String> {
+        crate::services::network::require_online("download the MCP server")?;
         let mcp_dir = Self::get_mcp_dir(app)?;
 
         // Create directory
@@ -328,6 +330,7 @@ impl McpManager {
 
     /// Check if an update is available
     pub async fn check_for_update(app: &AppHandle) -> Result<BinaryUpdateInfo, String> {
+        crate::services::network::require_online("check for updates")?;
         let current = Self::get_version(app).unwrap_or_else(|| "0.0.0".to_string());
         let latest = Self::fetch_latest_version().await
             .ok_or_else(|| "Failed to fetch latest version from GitHub".to_string())?;
@@ -1,6 +1,7 @@
 pub mod apache;
 pub mod cache;
 pub mod paths;
+pub mod permissions;
 pub mod composer;
 pub mod config;
 pub mod database;
@@ -13,6 +14,8 @@ pub mod postgresql;
 pub mod mongodb;
 pub mod nginx;
 pub mod php_registry;
+pub mod php_pool;
+pub mod pending_restarts;
 pub mod phpmyadmin;
 pub mod process;
 pub mod registry;
@@ -25,6 +28,7 @@ pub mod backup;
 pub mod validation;
 pub mod versions;
 pub mod version_manager;
+pub mod version_lock;
 pub mod shared_data;
 pub mod tunnel;
 pub mod terminal;
@@ -34,7 +38,12 @@ pub mod meilisearch;
 pub mod blueprints;
 pub mod ai_tools;
 pub mod deploy_store;
+pub mod file_lock;
 pub mod deploy;
+pub mod connection_info;
+pub mod session;
+pub mod network;
+pub mod cron;
 
 use std::process::Command;
 
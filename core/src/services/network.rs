@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+
+/// Limits for version lookups and binary downloads, for users on slow or
+/// metered connections. Stored in config/network.json; the defaults match
+/// the behaviour before these settings existed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// Version lookups running at once during a full refresh (1 = one after
+    /// another)
+    pub max_concurrent_fetches: usize,
+    /// Binary downloads running at once (0 = unlimited)
+    pub max_concurrent_downloads: usize,
+    /// Timeout for registry and version requests
+    pub request_timeout_secs: u64,
+    /// Timeout for a single binary download
+    pub download_timeout_secs: u64,
+    /// Skip all network requests: versions come from cache or the bundled
+    /// registry and anything that must download fails immediately
+    pub offline: bool,
+    /// How long fetched version lists are reused before being refetched
+    /// (0 = always refetch)
+    pub version_cache_ttl_secs: u64,
+}
+
+pub const DEFAULT_VERSION_CACHE_TTL_SECS: u64 = 86_400; // 24 hours
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            max_concurrent_fetches: 1,
+            max_concurrent_downloads: 0,
+            request_timeout_secs: 10,
+            download_timeout_secs: 300,
+            offline: false,
+            version_cache_ttl_secs: DEFAULT_VERSION_CACHE_TTL_SECS,
+        }
+    }
+}
+
+impl NetworkSettings {
+    const FILENAME: &'static str = "network.json";
+
+    fn get_path() -> PathBuf {
+        crate::services::paths::get_orbit_data_dir()
+            .join("config")
+            .join(Self::FILENAME)
+    }
+
+    /// Load settings (defaults if the file is missing or unreadable)
+    pub fn load() -> Self {
+        fs::read_to_string(Self::get_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        self.validate()?;
+        let path = Self::get_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {e}"))?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize network settings: {e}"))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write network settings: {e}"))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.max_concurrent_fetches == 0 {
+            return Err("At least one concurrent version fetch is required".to_string());
+        }
+        if self.request_timeout_secs == 0 || self.download_timeout_secs == 0 {
+            return Err("Timeouts must be at least one second".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn request_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.request_timeout_secs.max(1))
+    }
+
+    pub fn download_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.download_timeout_secs.max(1))
+    }
+}
+
+/// Host probed to tell whether the machine has a usable connection
+const PROBE_HOST: (&str, u16) = ("raw.githubusercontent.com", 443);
+
+pub fn is_offline() -> bool {
+    NetworkSettings::load().offline
+}
+
+/// Fail fast with a clear message instead of waiting on network timeouts
+pub fn require_online(action: &str) -> Result<(), String> {
+    if is_offline() {
+        return Err(format!(
+            "Offline mode is on: cannot {action}. Turn off offline mode in Settings to use the network."
+        ));
+    }
+    Ok(())
+}
+
+/// Whether the registry host resolves and accepts a connection. A DNS failure
+/// or refused connection is the usual sign the machine is offline. Blocking.
+pub fn probe_network() -> bool {
+    use std::net::{TcpStream, ToSocketAddrs};
+    PROBE_HOST
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .is_some_and(|addr| TcpStream::connect_timeout(&addr, std::time::Duration::from_secs(3)).is_ok())
+}
+
+/// Shared download permits, rebuilt when `max_concurrent_downloads` changes.
+/// `None` means downloads are unlimited.
+static DOWNLOAD_SLOTS: Mutex<Option<(usize, Arc<Semaphore>)>> = Mutex::new(None);
+
+pub fn download_slots(limit: usize) -> Option<Arc<Semaphore>> {
+    if limit == 0 {
+        return None;
+    }
+    let mut slots = DOWNLOAD_SLOTS.lock().unwrap_or_else(|e| e.into_inner());
+    match slots.as_ref() {
+        Some((current, semaphore)) if *current == limit => Some(semaphore.clone()),
+        _ => {
+            let semaphore = Arc::new(Semaphore::new(limit));
+            *slots = Some((limit, semaphore.clone()));
+            Some(semaphore)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_settings_fill_in_defaults() {
+        let settings: NetworkSettings = serde_json::from_str(r#"{ "max_concurrent_fetches": 2 }"#).unwrap();
+        assert_eq!(settings.max_concurrent_fetches, 2);
+        assert_eq!(settings.download_timeout_secs, NetworkSettings::default().download_timeout_secs);
+
+        assert!(!settings.offline);
+        // Refreshes ran one service at a time before this setting existed
+        assert_eq!(NetworkSettings::default().max_concurrent_fetches, 1);
+        assert!(NetworkSettings { max_concurrent_fetches: 0, ..Default::default() }.validate().is_err());
+        assert!(download_slots(0).is_none());
+        assert_eq!(download_slots(2).unwrap().available_permits(), 2);
+    }
+}
@@ -2,6 +2,7 @@ use std::path::PathBuf;
 #[cfg(not(windows))]
 use std::process::Command;
 use tauri::{AppHandle, Manager};
+use crate::services::paths::OrbitPathExt;
 
 use super::hidden_command;
 
@@ -12,7 +13,7 @@ impl NginxManager {
     pub fn get_nginx_path(app: &AppHandle) -> Result<PathBuf, String> {
         let nginx_exe = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("nginx")
@@ -29,7 +30,7 @@ impl NginxManager {
     pub fn get_config_dir(app: &AppHandle) -> Result<PathBuf, String> {
         let config_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("nginx")
@@ -54,7 +55,7 @@ impl NginxManager {
     pub fn get_logs_dir(app: &AppHandle) -> Result<PathBuf, String> {
         let logs_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("nginx")
@@ -3,13 +3,24 @@
 //!
 //! Windows: %LOCALAPPDATA%/com.orbit.dev/
 //! This matches Tauri's `app_local_data_dir()` for the "com.orbit.dev" identifier.
+//!
+//! The location can be moved with the `ORBIT_DATA_DIR` env var or the
+//! `data_dir.txt` settings entry in the default directory. orbit-cli and
+//! orbit-mcp resolve it the same way.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable that relocates the Orbit data directory.
+pub const DATA_DIR_ENV: &str = "ORBIT_DATA_DIR";
 
-use std::path::PathBuf;
+/// Settings entry (inside the default data dir) holding a user-chosen data directory.
+pub const DATA_DIR_POINTER: &str = "data_dir.txt";
 
-/// Get the Orbit data directory.
+/// Default per-OS data directory.
 /// This must match Tauri's app_local_data_dir for "com.orbit.dev".
-#[allow(dead_code)]
-pub fn get_orbit_data_dir() -> PathBuf {
+pub fn default_orbit_data_dir() -> PathBuf {
     #[cfg(target_os = "windows")]
     {
         let local_app_data = std::env::var("LOCALAPPDATA")
@@ -36,6 +47,71 @@ pub fn get_orbit_data_dir() -> PathBuf {
     }
 }
 
+/// Configured data directory override and where it came from ("env" or "settings").
+pub fn data_dir_override() -> Option<(PathBuf, &'static str)> {
+    if let Ok(value) = std::env::var(DATA_DIR_ENV) {
+        if !value.trim().is_empty() {
+            return Some((PathBuf::from(value.trim()), "env"));
+        }
+    }
+    fs::read_to_string(default_orbit_data_dir().join(DATA_DIR_POINTER))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(|s| (PathBuf::from(s), "settings"))
+}
+
+/// Ensure a data directory is absolute, exists and is writable.
+pub fn validate_data_dir(dir: &Path) -> Result<(), String> {
+    if !dir.is_absolute() {
+        return Err(format!("{} is not an absolute path", dir.display()));
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {e}", dir.display()))?;
+    let probe = dir.join(".orbit-write-test");
+    fs::write(&probe, b"ok").map_err(|e| format!("{} is not writable: {e}", dir.display()))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// The validated override, resolved once per process.
+/// An invalid override is logged and ignored so Orbit still starts.
+fn resolved_override() -> Option<&'static PathBuf> {
+    static RESOLVED: OnceLock<Option<PathBuf>> = OnceLock::new();
+    RESOLVED
+        .get_or_init(|| {
+            let (dir, source) = data_dir_override()?;
+            match validate_data_dir(&dir) {
+                Ok(()) => Some(dir),
+                Err(e) => {
+                    log::warn!("Ignoring {source} data dir override: {e}");
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+/// Get the Orbit data directory: `ORBIT_DATA_DIR`, then the settings entry,
+/// then the per-OS default.
+pub fn get_orbit_data_dir() -> PathBuf {
+    resolved_override().cloned().unwrap_or_else(default_orbit_data_dir)
+}
+
+/// Resolves the Orbit data directory through Tauri's path resolver.
+/// Use this instead of `app_local_data_dir()` so overrides are honored.
+pub trait OrbitPathExt {
+    fn orbit_data_dir(&self) -> tauri::Result<PathBuf>;
+}
+
+impl<R: tauri::Runtime> OrbitPathExt for tauri::path::PathResolver<R> {
+    fn orbit_data_dir(&self) -> tauri::Result<PathBuf> {
+        match resolved_override() {
+            Some(dir) => Ok(dir.clone()),
+            None => self.app_local_data_dir(),
+        }
+    }
+}
+
 /// Get the bin directory where services are installed.
 /// e.g. %LOCALAPPDATA%/com.orbit.dev/bin/
 #[allow(dead_code)]
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use crate::services::paths::OrbitPathExt;
+
+/// A service whose config was edited after it was started.
+/// Shared with orbit-mcp through config/pending_restarts.json.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRestart {
+    pub service: String, // e.g., "php-8.4", "mariadb", "nginx"
+    pub reason: String,  // e.g., "memory_limit changed in php.ini"
+    pub since: String,   // Unix timestamp (seconds)
+}
+
+pub struct PendingRestarts;
+
+impl PendingRestarts {
+    const FILENAME: &'static str = "pending_restarts.json";
+
+    fn get_path(app: &AppHandle) -> Result<PathBuf, String> {
+        let config_dir = app
+            .path()
+            .orbit_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("config");
+
+        if !config_dir.exists() {
+            fs::create_dir_all(&config_dir)
+                .map_err(|e| format!("Failed to create config dir: {e}"))?;
+        }
+
+        Ok(config_dir.join(Self::FILENAME))
+    }
+
+    /// Load all pending entries (empty if the file is missing or unreadable)
+    pub fn load(app: &AppHandle) -> Vec<PendingRestart> {
+        Self::get_path(app)
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(app: &AppHandle, entries: &[PendingRestart]) -> Result<(), String> {
+        let path = Self::get_path(app)?;
+        let content = serde_json::to_string_pretty(entries)
+            .map_err(|e| format!("Failed to serialize pending restarts: {e}"))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write pending restarts: {e}"))
+    }
+
+    /// Flag a service as needing a restart to pick up a config change
+    pub fn mark(app: &AppHandle, service: &str, reason: &str) {
+        let mut entries = Self::load(app);
+        match entries.iter_mut().find(|e| e.service == service) {
+            Some(entry) => entry.reason = reason.to_string(),
+            None => entries.push(PendingRestart {
+                service: service.to_string(),
+                reason: reason.to_string(),
+                since: chrono::Utc::now().timestamp().to_string(),
+            }),
+        }
+        if let Err(e) = Self::save(app, &entries) {
+            log::warn!("Failed to record pending restart for {service}: {e}");
+        }
+    }
+
+    /// Clear the flag once the service has been (re)started or stopped
+    pub fn clear(app: &AppHandle, service: &str) {
+        let mut entries = Self::load(app);
+        let before = entries.len();
+        entries.retain(|e| e.service != service);
+        if entries.len() != before {
+            if let Err(e) = Self::save(app, &entries) {
+                log::warn!("Failed to clear pending restart for {service}: {e}");
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Write-access checks for the paths Orbit modifies.
+//! Mirrors the `check_permissions` logic in orbit-cli/orbit-mcp.
+
+use super::hidden_command;
+use crate::services::paths::{get_bin_dir, get_orbit_data_dir};
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Result of checking (and optionally repairing) one path
+#[derive(Debug, Clone, Serialize)]
+pub struct PathPermission {
+    pub label: String, // "hosts_file", "data_dir", "config_dir", "mariadb_data_dir"
+    pub path: String,
+    pub exists: bool,
+    pub writable: bool,
+    pub problem: Option<String>,     // error plus ACL entries / owner and mode
+    pub remediation: Option<String>, // command that grants write access
+    pub repair: Option<String>,      // outcome of a repair attempt
+}
+
+/// Paths Orbit writes to: (label, path, is_dir)
+fn targets() -> Vec<(&'static str, PathBuf, bool)> {
+    let data_dir = get_orbit_data_dir();
+    let mut targets = vec![
+        ("data_dir", data_dir.clone(), true),
+        ("config_dir", data_dir.join("config"), true),
+    ];
+    // Elsewhere hosts edits go through pkexec, so only Windows
+    // expects the elevated app to write it directly.
+    #[cfg(target_os = "windows")]
+    targets.insert(
+        0,
+        ("hosts_file", PathBuf::from(r"C:\Windows\System32\drivers\etc\hosts"), false),
+    );
+    let mariadb_data = get_bin_dir().join("data").join("mariadb");
+    if mariadb_data.exists() {
+        targets.push(("mariadb_data_dir", mariadb_data, true));
+    }
+    targets
+}
+
+/// Probe write access without changing content: open files for append,
+/// create and remove a marker file in directories.
+fn probe_write(path: &Path, is_dir: bool) -> io::Result<()> {
+    if is_dir {
+        let probe = path.join(".orbit-write-test");
+        fs::write(&probe, b"ok")?;
+        fs::remove_file(&probe)
+    } else {
+        fs::OpenOptions::new().append(true).open(path).map(|_| ())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn describe_problem(path: &Path, err: &io::Error) -> String {
+    if let Ok(output) = hidden_command("icacls").arg(path).output() {
+        let acl = String::from_utf8_lossy(&output.stdout);
+        let path_str = path.to_string_lossy();
+        let entries: Vec<String> = acl
+            .lines()
+            .map(|l| l.trim().trim_start_matches(path_str.as_ref()).trim().to_string())
+            .filter(|l| !l.is_empty() && !l.starts_with("Successfully processed"))
+            .collect();
+        if !entries.is_empty() {
+            return format!("{err} (ACL: {})", entries.join("; "));
+        }
+    }
+    err.to_string()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn describe_problem(path: &Path, err: &io::Error) -> String {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    match fs::metadata(path) {
+        Ok(meta) => format!(
+            "{err} (owner uid {}, mode {:o})",
+            meta.uid(),
+            meta.permissions().mode() & 0o7777
+        ),
+        Err(_) => err.to_string(),
+    }
+}
+
+fn remediation(path: &Path, is_dir: bool) -> String {
+    let p = path.display();
+    if cfg!(target_os = "windows") {
+        if is_dir {
+            format!("takeown /F \"{p}\" /R /D Y && icacls \"{p}\" /grant \"%USERNAME%\":(OI)(CI)F /T")
+        } else {
+            format!("takeown /F \"{p}\" && icacls \"{p}\" /grant \"%USERNAME%\":F")
+        }
+    } else {
+        let recursive = if is_dir { " -R" } else { "" };
+        format!("sudo chown{recursive} \"$(whoami)\" \"{p}\" && sudo chmod{recursive} u+w \"{p}\"")
+    }
+}
+
+pub fn check_path(label: &str, path: &Path, is_dir: bool) -> PathPermission {
+    let exists = path.exists();
+    let result = if exists {
+        probe_write(path, is_dir)
+    } else {
+        Err(io::Error::new(io::ErrorKind::NotFound, "path does not exist"))
+    };
+    let (problem, remediation) = match &result {
+        Ok(()) => (None, None),
+        Err(_) if !exists => (Some("Path does not exist".to_string()), None),
+        Err(e) => (Some(describe_problem(path, e)), Some(remediation(path, is_dir))),
+    };
+    PathPermission {
+        label: label.to_string(),
+        path: path.to_string_lossy().to_string(),
+        exists,
+        writable: result.is_ok(),
+        problem,
+        remediation,
+        repair: None,
+    }
+}
+
+/// Take ownership and grant the current user full control (needs elevation)
+#[cfg(target_os = "windows")]
+fn repair(path: &Path, is_dir: bool) -> Result<(), String> {
+    let path_str = path.to_string_lossy().to_string();
+    let user = std::env::var("USERNAME").map_err(|_| "USERNAME is not set".to_string())?;
+    let mut takeown = hidden_command("takeown");
+    takeown.args(["/F", path_str.as_str()]);
+    if is_dir {
+        takeown.args(["/R", "/D", "Y"]);
+    }
+    let grant = if is_dir { format!("{user}:(OI)(CI)F") } else { format!("{user}:F") };
+    let mut icacls = hidden_command("icacls");
+    icacls.args([path_str.as_str(), "/grant", grant.as_str()]);
+    if is_dir {
+        icacls.arg("/T");
+    }
+    for (name, mut cmd) in [("takeown", takeown), ("icacls", icacls)] {
+        let output = cmd.output().map_err(|e| format!("Failed to run {name}: {e}"))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            let msg = if stderr.is_empty() {
+                String::from_utf8_lossy(&output.stdout).trim().to_string()
+            } else {
+                stderr
+            };
+            return Err(format!("{name} failed (run Orbit as Administrator?): {msg}"));
+        }
+    }
+    Ok(())
+}
+
+/// Add the owner write bit; paths owned by another user still need sudo
+#[cfg(not(target_os = "windows"))]
+fn repair(path: &Path, is_dir: bool) -> Result<(), String> {
+    let mut chmod = hidden_command("chmod");
+    if is_dir {
+        chmod.arg("-R");
+    }
+    let output = chmod
+        .arg("u+w")
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to run chmod: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "chmod failed (needs sudo?): {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Check every Orbit path, optionally repairing the ones that are not writable
+pub fn check_all(fix: bool) -> Vec<PathPermission> {
+    targets()
+        .into_iter()
+        .map(|(label, path, is_dir)| {
+            let check = check_path(label, &path, is_dir);
+            if !fix || check.writable || !check.exists {
+                return check;
+            }
+            let outcome = repair(&path, is_dir);
+            let mut rechecked = check_path(label, &path, is_dir);
+            rechecked.repair = Some(match outcome {
+                Ok(()) if rechecked.writable => "repaired".to_string(),
+                Ok(()) => "repair ran but path is still not writable".to_string(),
+                Err(e) => e,
+            });
+            rechecked
+        })
+        .collect()
+}
@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Most workers a pool may ask for
+const MAX_CHILDREN: u32 = 64;
+
+/// How a PHP version is served, from config/php-pools.json. Entries are keyed
+/// by version, with "default" for versions without one, e.g.
+/// `{ "default": { "children": 8 }, "8.4": { "mode": "fpm" } }`.
+/// Shared with orbit-cli and orbit-mcp, which keep their own copy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhpPool {
+    /// "cgi": php-cgi forking `children` workers on the one port (Unix only;
+    /// php-cgi on Windows can't fork and serves one request at a time).
+    /// "fpm": php-fpm with a static pool, when the version ships it; else cgi.
+    pub mode: String,
+    pub children: u32,
+    /// Requests a worker serves before it is replaced
+    pub max_requests: u32,
+}
+
+impl Default for PhpPool {
+    fn default() -> Self {
+        Self { mode: "cgi".to_string(), children: 4, max_requests: 1000 }
+    }
+}
+
+/// Program and arguments that serve a PHP version
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhpLaunch {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+}
+
+impl PhpPool {
+    /// Pool for `version` from `<config_dir>/php-pools.json`; the defaults
+    /// when the file or entry is missing or unreadable
+    pub fn load(config_dir: &Path, version: &str) -> Self {
+        let pools: serde_json::Map<String, serde_json::Value> = fs::read_to_string(config_dir.join("php-pools.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let mut pool: Self = pools.get(version)
+            .or_else(|| pools.get("default"))
+            .and_then(|entry| serde_json::from_value(entry.clone()).ok())
+            .unwrap_or_default();
+        pool.children = pool.children.clamp(1, MAX_CHILDREN);
+        pool.max_requests = pool.max_requests.max(1);
+        pool
+    }
+
+    /// Environment php-cgi reads to fork its workers. php-cgi can't fork on
+    /// Windows, so there it only gets the request limit.
+    pub fn cgi_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![("PHP_FCGI_MAX_REQUESTS", self.max_requests.to_string())];
+        if cfg!(not(target_os = "windows")) {
+            env.push(("PHP_FCGI_CHILDREN", self.children.to_string()));
+        }
+        env
+    }
+
+    /// php-fpm.conf for a static pool of `children` workers on `port`
+    pub fn fpm_config(&self, port: u16, php_dir: &Path) -> String {
+        format!(
+            "; Generated by Orbit from config/php-pools.json; edits are overwritten\n\
+             [global]\n\
+             pid = {pid}\n\
+             error_log = {log}\n\
+             daemonize = no\n\
+             \n\
+             [orbit]\n\
+             listen = 127.0.0.1:{port}\n\
+             pm = static\n\
+             pm.max_children = {children}\n\
+             pm.max_requests = {max_requests}\n\
+             catch_workers_output = yes\n\
+             clear_env = no\n",
+            pid = php_dir.join("orbit-fpm.pid").display(),
+            log = php_dir.join("logs").join("php-fpm.log").display(),
+            children = self.children,
+            max_requests = self.max_requests,
+        )
+    }
+
+    /// How to serve the PHP whose php-cgi is `php_cgi` on `port`. In fpm mode
+    /// this writes orbit-fpm.conf next to php-cgi; when php-fpm is missing it
+    /// falls back to php-cgi.
+    pub fn launch(&self, php_cgi: &Path, port: u16) -> PhpLaunch {
+        let cgi = PhpLaunch {
+            program: php_cgi.to_path_buf(),
+            args: vec!["-b".to_string(), format!("127.0.0.1:{port}")],
+        };
+        if self.mode != "fpm" {
+            return cgi;
+        }
+        let (Some(fpm), Some(php_dir)) = (find_php_fpm(php_cgi), php_cgi.parent()) else {
+            log::warn!("php-fpm not found next to {}; using php-cgi", php_cgi.display());
+            return cgi;
+        };
+
+        let config_path = php_dir.join("orbit-fpm.conf");
+        let _ = fs::create_dir_all(php_dir.join("logs"));
+        if let Err(e) = fs::write(&config_path, self.fpm_config(port, php_dir)) {
+            log::warn!("Failed to write {}: {e}; using php-cgi", config_path.display());
+            return cgi;
+        }
+        PhpLaunch {
+            program: fpm,
+            args: vec![
+                "--nodaemonize".to_string(),
+                "--fpm-config".to_string(),
+                config_path.display().to_string(),
+                "-c".to_string(),
+                php_dir.display().to_string(),
+            ],
+        }
+    }
+}
+
+/// php-fpm shipped with the PHP whose php-cgi is `php_cgi`: alongside it, or
+/// in the sbin/ of a bin/ + sbin/ layout
+pub fn find_php_fpm(php_cgi: &Path) -> Option<PathBuf> {
+    let dir = php_cgi.parent()?;
+    let name = if cfg!(target_os = "windows") { "php-fpm.exe" } else { "php-fpm" };
+    let mut candidates = vec![dir.join(name), dir.join("sbin").join(name)];
+    if let Some(root) = dir.parent() {
+        candidates.push(root.join("sbin").join(name));
+    }
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_pool_per_version() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(PhpPool::load(dir.path(), "8.4"), PhpPool::default());
+
+        fs::write(
+            dir.path().join("php-pools.json"),
+            r#"{ "default": { "children": 8 }, "8.4": { "mode": "fpm", "children": 500 } }"#,
+        ).unwrap();
+        let pool = PhpPool::load(dir.path(), "8.4");
+        assert_eq!((pool.mode.as_str(), pool.children, pool.max_requests), ("fpm", MAX_CHILDREN, 1000));
+        assert_eq!(PhpPool::load(dir.path(), "8.3").children, 8);
+        let env = PhpPool::default().cgi_env();
+        assert_eq!(env[0], ("PHP_FCGI_MAX_REQUESTS", "1000".to_string()));
+        assert_eq!(env.contains(&("PHP_FCGI_CHILDREN", "4".to_string())), cfg!(not(target_os = "windows")));
+    }
+
+    #[test]
+    fn test_launch_fpm_falls_back_to_cgi() {
+        let dir = tempfile::tempdir().unwrap();
+        let php_cgi = dir.path().join("php-cgi");
+        fs::write(&php_cgi, "").unwrap();
+        let fpm = PhpPool { mode: "fpm".to_string(), ..PhpPool::default() };
+
+        // No php-fpm shipped: php-cgi on the port
+        let launch = fpm.launch(&php_cgi, 9084);
+        assert_eq!((launch.program, launch.args), (php_cgi.clone(), vec!["-b".to_string(), "127.0.0.1:9084".to_string()]));
+
+        let fpm_name = if cfg!(target_os = "windows") { "php-fpm.exe" } else { "php-fpm" };
+        fs::create_dir_all(dir.path().join("sbin")).unwrap();
+        fs::write(dir.path().join("sbin").join(fpm_name), "").unwrap();
+        let launch = fpm.launch(&php_cgi, 9084);
+        assert_eq!(launch.program, dir.path().join("sbin").join(fpm_name));
+        let config = fs::read_to_string(dir.path().join("orbit-fpm.conf")).unwrap();
+        assert!(config.contains("listen = 127.0.0.1:9084") && config.contains("pm.max_children = 4"));
+    }
+}
@@ -2,6 +2,25 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
+use crate::services::paths::OrbitPathExt;
+
+/// Port used when a PHP version can't be parsed (that of the default PHP 8.4)
+pub const DEFAULT_PHP_PORT: u16 = 9004;
+
+/// FastCGI port for a PHP version. PHP 8 uses 9000 + minor (8.4 -> 9004,
+/// 8.14 -> 9014); other majors get their own decade (7.4 -> 9074).
+/// `None` unless the version starts with numeric major.minor.
+///
+/// orbit-mcp and orbit-cli carry a copy; keep them in step.
+pub fn php_port(version: &str) -> Option<u16> {
+    let mut parts = version.trim().split('.');
+    let major: u16 = parts.next()?.parse().ok()?;
+    let minor: u16 = parts.next()?.parse().ok()?;
+    if minor > 99 {
+        return None;
+    }
+    Some(if major == 8 { 9000 + minor } else { 9000 + (major % 10) * 10 + minor })
+}
 
 /// PHP Service entry in the registry
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,7 +53,7 @@ impl PhpRegistry {
     fn get_registry_path(app: &AppHandle) -> Result<PathBuf, String> {
         let config_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("config");
 
@@ -80,20 +99,9 @@ impl PhpRegistry {
         }
     }
 
-    /// Calculate port from PHP version
-    /// PHP 8.4 -> 9004, PHP 8.5 -> 9005, PHP 7.4 -> 9074
+    /// Calculate port from PHP version (see `php_port`)
     pub fn calculate_port(version: &str) -> u16 {
-        let parts: Vec<&str> = version.split('.').collect();
-        if parts.len() >= 2 {
-            if let (Ok(major), Ok(minor)) = (parts[0].parse::<u16>(), parts[1].parse::<u16>()) {
-                if major == 8 {
-                    return 9000 + minor;
-                }
-                // For PHP 7.x: 9070 + minor (7.4 -> 9074)
-                return 9000 + (major % 10) * 10 + minor;
-            }
-        }
-        9000 // Default fallback
+        php_port(version).unwrap_or(DEFAULT_PHP_PORT)
     }
 
     /// Register a new PHP version
@@ -192,7 +200,7 @@ impl PhpRegistry {
     pub fn scan_installed_versions(&mut self, app: &AppHandle) -> Result<usize, String> {
         let php_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("php");
@@ -296,6 +304,12 @@ mod tests {
         assert_eq!(PhpRegistry::calculate_port("8.3"), 9003);
         assert_eq!(PhpRegistry::calculate_port("7.4"), 9074);
         assert_eq!(PhpRegistry::calculate_port("7.3"), 9073);
+        assert_eq!(PhpRegistry::calculate_port("8.14"), 9014);
+        assert_eq!(PhpRegistry::calculate_port("8.4.12"), 9004);
+        assert_eq!(php_port("8"), None);
+        assert_eq!(php_port("8.x"), None);
+        assert_eq!(php_port(""), None);
+        assert_eq!(PhpRegistry::calculate_port("latest"), DEFAULT_PHP_PORT);
     }
 
     #[test]
@@ -53,6 +53,7 @@ impl PhpMyAdminManager {
         let zip_path = temp_dir.join("phpmyadmin.zip");
 
         // Download PhpMyAdmin
+        crate::services::network::require_online("download PhpMyAdmin")?;
         let response = reqwest::get(PHPMYADMIN_DOWNLOAD_URL)
             .await
             .map_err(|e| format!("Failed to download PhpMyAdmin: {e}"))?;
@@ -1,4 +1,7 @@
 use crate::services::config::ConfigManager;
+use crate::services::php_pool::PhpPool;
+use crate::services::php_registry::{php_port, DEFAULT_PHP_PORT};
+use crate::services::session::{ServiceSession, SessionEntry, SessionReport};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command};
@@ -48,17 +51,8 @@ fn get_service_port(service_name: &str) -> Option<u16> {
     } else if service_name.contains("nginx") || service_name.contains("apache") || service_name.contains("httpd") {
         Some(80)
     } else if service_name.contains("php") {
-        if let Some(version_str) = service_name.strip_prefix("php-") {
-            let parts: Vec<&str> = version_str.split('.').collect();
-            if parts.len() >= 2 {
-                let minor: u16 = parts[1].parse().unwrap_or(4);
-                Some(9000 + minor)
-            } else {
-                Some(9004)
-            }
-        } else {
-            Some(9004)
-        }
+        let version = service_name.strip_prefix("php-").unwrap_or_default();
+        Some(php_port(version).unwrap_or(DEFAULT_PHP_PORT))
     } else if service_name.contains("redis") {
         Some(6379)
     } else if service_name.contains("mailpit") {
@@ -83,7 +77,7 @@ fn get_process_names(service_name: &str) -> Vec<&'static str> {
     } else if service_name.contains("apache") || service_name.contains("httpd") {
         vec!["httpd.exe"]
     } else if service_name.contains("php") {
-        vec!["php-cgi.exe"]
+        vec!["php-cgi.exe", "php-fpm.exe"]
     } else if service_name.contains("redis") {
         vec!["redis-server.exe"]
     } else if service_name.contains("mailpit") {
@@ -95,18 +89,70 @@ fn get_process_names(service_name: &str) -> Vec<&'static str> {
     }
 }
 
+/// Kill a process and its children
+fn kill_process_tree(pid: u32) {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = hidden_command("taskkill")
+            .args(["/F", "/PID", &pid.to_string(), "/T"])
+            .output();
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        // php-cgi and php-fpm workers are children of the started process;
+        // kill them too in case the parent dies before reaping them
+        let children = child_pids(pid);
+        let _ = Command::new("kill").arg(pid.to_string()).args(children.iter().map(|c| c.to_string())).output();
+    }
+}
+
+/// PIDs of the direct children of `pid`
+#[cfg(not(target_os = "windows"))]
+fn child_pids(pid: u32) -> Vec<u32> {
+    let parent = sysinfo::Pid::from_u32(pid);
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    sys.processes()
+        .iter()
+        .filter(|(_, process)| process.parent() == Some(parent))
+        .map(|(child, _)| child.as_u32())
+        .collect()
+}
+
 // Global state to hold running processes
 pub struct ServiceManager {
     processes: Arc<Mutex<HashMap<String, Child>>>,
+    /// Services started by a previous (crashed) Orbit session and taken over on launch
+    adopted: Arc<Mutex<HashMap<String, SessionEntry>>>,
 }
 
 impl ServiceManager {
     pub fn new() -> Self {
         Self {
             processes: Arc::new(Mutex::new(HashMap::new())),
+            adopted: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// Reconcile the session file with running processes: services whose PID,
+    /// image and start time still match are adopted; the rest are reported.
+    pub fn reconcile_session(&self) -> Result<SessionReport, String> {
+        let report = ServiceSession::reconcile();
+        let mut adopted = self.adopted.lock().map_err(|e| e.to_string())?;
+        for entry in &report.adopted {
+            adopted.insert(entry.service.clone(), entry.clone());
+        }
+        Ok(report)
+    }
+
+    /// Take over an orphaned Orbit process so it can be stopped and monitored by name
+    pub fn adopt(&self, service_name: &str, pid: u32) -> Result<(), String> {
+        let entry = ServiceSession::adopt(service_name, pid)?;
+        let mut adopted = self.adopted.lock().map_err(|e| e.to_string())?;
+        adopted.insert(service_name.to_string(), entry);
+        Ok(())
+    }
+
     fn ensure_config(service_type: ServiceType, bin_path_buf: &Path) {
         if let Some(parent) = bin_path_buf.parent() {
             let root = parent.to_path_buf();
@@ -146,6 +192,21 @@ impl ServiceManager {
         service_type: ServiceType,
         bin_path: &str,
         args: &[&str],
+    ) -> Result<u32, String> {
+        let pid = self.spawn_with_name(name.clone(), service_type, bin_path, args)?;
+        // 0 means an already-running Orbit process was reused
+        if pid != 0 {
+            ServiceSession::record(&name, pid, bin_path);
+        }
+        Ok(pid)
+    }
+
+    fn spawn_with_name(
+        &self,
+        name: String,
+        service_type: ServiceType,
+        bin_path: &str,
+        args: &[&str],
     ) -> Result<u32, String> {
         // Check if service is already running
         if let Some(port) = get_service_port(&name) {
@@ -172,13 +233,13 @@ impl ServiceManager {
             command.current_dir(parent);
         }
 
-        // Set environment variables for PHP FastCGI
-        if matches!(service_type, ServiceType::Php(_)) {
-            #[cfg(not(target_os = "windows"))]
-            {
-                command.env("PHP_FCGI_CHILDREN", "4");
-            }
-            command.env("PHP_FCGI_MAX_REQUESTS", "1000");
+        // Worker count for php-cgi, from config/php-pools.json; php-fpm
+        // sizes its pool from its generated config and gets none of it
+        let is_fpm = bin_path_buf.file_stem().is_some_and(|stem| stem == "php-fpm");
+        if matches!(service_type, ServiceType::Php(_)) && !is_fpm {
+            let version = name.strip_prefix("php-").unwrap_or_default();
+            let config_dir = crate::services::paths::get_orbit_data_dir().join("config");
+            command.envs(PhpPool::load(&config_dir, version).cgi_env());
         }
 
         #[cfg(target_os = "windows")]
@@ -242,20 +303,17 @@ impl ServiceManager {
         let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
 
         if let Some(mut child) = processes.remove(service_name) {
-            let pid = child.id();
-
-            #[cfg(target_os = "windows")]
-            {
-                let _ = hidden_command("taskkill")
-                    .args(["/F", "/PID", &pid.to_string(), "/T"])
-                    .output();
-            }
-            #[cfg(not(target_os = "windows"))]
-            {
-                let _ = Command::new("kill").arg(pid.to_string()).output();
-            }
+            kill_process_tree(child.id());
 
             let _ = child.wait();
+            ServiceSession::remove(service_name);
+            Ok(())
+        } else if let Some(entry) = self.adopted.lock().map_err(|e| e.to_string())?.remove(service_name) {
+            // Adopted from a previous session — kill exactly the recorded process
+            if ServiceSession::is_running(&entry) {
+                kill_process_tree(entry.pid);
+            }
+            ServiceSession::remove(service_name);
             Ok(())
         } else {
             // Not in HashMap — find and kill orphaned Orbit processes by checking bin dir
@@ -309,6 +367,7 @@ impl ServiceManager {
             }
 
             if killed {
+                ServiceSession::remove(service_name);
                 Ok(())
             } else {
                 Err("Service not found or not running".to_string())
@@ -334,6 +393,18 @@ impl ServiceManager {
             }
         }
 
+        drop(processes);
+
+        // Adopted from a previous session
+        if let Ok(mut adopted) = self.adopted.lock() {
+            if let Some(entry) = adopted.get(service_name) {
+                if ServiceSession::is_running(entry) {
+                    return Some("running".to_string());
+                }
+                adopted.remove(service_name);
+            }
+        }
+
         // Not tracked or exited — check if running externally (orphan from previous session)
         if let Some(port) = get_service_port(service_name) {
             if is_port_in_use(port) {
@@ -406,6 +477,12 @@ impl ServiceManager {
         }
         drop(processes); // Release lock before phase 2
 
+        // Adopted processes run from Orbit's bin dir, so phase 2 kills them too
+        if let Ok(mut adopted) = self.adopted.lock() {
+            adopted.clear();
+        }
+        ServiceSession::clear();
+
         // Phase 2: Kill any orphaned Orbit processes by checking known service executables
         // This catches processes from previous sessions or started externally
         let orbit_bin_dir = crate::services::paths::get_bin_dir();
@@ -488,7 +565,8 @@ mod tests {
     fn test_get_service_port_php() {
         assert_eq!(get_service_port("php-8.4"), Some(9004));
         assert_eq!(get_service_port("php-8.0"), Some(9000));
-        assert_eq!(get_service_port("php-7.4"), Some(9004));
+        assert_eq!(get_service_port("php-7.4"), Some(9074));
+        assert_eq!(get_service_port("php-8.14"), Some(9014));
         assert_eq!(get_service_port("php-8.5"), Some(9005));
         assert_eq!(get_service_port("php-8.2"), Some(9002));
         assert_eq!(get_service_port("php-8"), Some(9004)); // length < 2, default 9004
@@ -511,7 +589,7 @@ mod tests {
         assert_eq!(get_process_names("postgres"), vec!["postgres.exe"]);
         assert_eq!(get_process_names("mongo"), vec!["mongod.exe"]);
         assert_eq!(get_process_names("redis"), vec!["redis-server.exe"]);
-        assert_eq!(get_process_names("php-8.4"), vec!["php-cgi.exe"]);
+        assert_eq!(get_process_names("php-8.4"), vec!["php-cgi.exe", "php-fpm.exe"]);
         assert_eq!(get_process_names("mailpit"), vec!["mailpit.exe"]);
         assert_eq!(get_process_names("meilisearch"), vec!["meilisearch.exe"]);
         let empty: Vec<&'static str> = vec![];
@@ -17,6 +17,12 @@ const FALLBACK_REGISTRY: &str = include_str!("../../dist/libraries.json");
 /// Global cached registry
 static REGISTRY_CACHE: Lazy<RwLock<Option<LibraryRegistry>>> = Lazy::new(|| RwLock::new(None));
 
+/// Last copy fetched from the remote URL, with its Unix fetch time
+static REMOTE_REGISTRY: Lazy<RwLock<Option<(i64, LibraryRegistry)>>> = Lazy::new(|| RwLock::new(None));
+
+/// Held while fetching, so concurrent callers share one download
+static FETCH_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
 /// Platform-specific download info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlatformDownload {
@@ -82,6 +88,16 @@ pub struct LibraryRegistry {
 impl LibraryRegistry {
     /// Load registry from remote URL with fallback
     pub async fn fetch() -> Result<Self, String> {
+        // Offline: keep whatever was fetched earlier, else the bundled copy
+        if crate::services::network::is_offline() {
+            if let Ok(cache) = REGISTRY_CACHE.read() {
+                if let Some(ref registry) = *cache {
+                    return Ok(registry.clone());
+                }
+            }
+            return Self::load_fallback();
+        }
+
         let registry = match Self::fetch_remote().await {
             Ok(r) => r,
             Err(e) => {
@@ -97,10 +113,38 @@ impl LibraryRegistry {
         Ok(registry)
     }
 
+    /// Fetch from the remote URL only, without falling back to the bundled
+    /// copy, so callers can tell a failed fetch apart (and keep older data)
+    pub async fn fetch_fresh() -> Result<Self, String> {
+        crate::services::network::require_online("fetch the library registry")?;
+        let registry = Self::fetch_remote().await?;
+        if let Ok(mut cache) = REGISTRY_CACHE.write() {
+            *cache = Some(registry.clone());
+        }
+        if let Ok(mut remote) = REMOTE_REGISTRY.write() {
+            *remote = Some((chrono::Utc::now().timestamp(), registry.clone()));
+        }
+        Ok(registry)
+    }
+
+    /// Like `fetch_fresh`, but reuses a copy fetched at or after `since`
+    /// (Unix time) instead of downloading it again
+    pub async fn fetch_fresh_since(since: i64) -> Result<Self, String> {
+        let _fetching = FETCH_LOCK.lock().await;
+        if let Ok(remote) = REMOTE_REGISTRY.read() {
+            if let Some((fetched_at, registry)) = remote.as_ref() {
+                if *fetched_at >= since {
+                    return Ok(registry.clone());
+                }
+            }
+        }
+        Self::fetch_fresh().await
+    }
+
     /// Fetch from remote URL
     async fn fetch_remote() -> Result<Self, String> {
         let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
+            .timeout(crate::services::network::NetworkSettings::load().request_timeout())
             .build()
             .map_err(|e| format!("Failed to create client: {e}"))?;
 
@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+/// A service process Orbit started, persisted so a relaunch after a GUI crash
+/// can tell its own processes apart from foreign ones (or a recycled PID).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionEntry {
+    pub service: String, // e.g., "php-8.4", "mariadb", "nginx"
+    pub pid: u32,
+    pub exe: String,
+    pub started_at: u64, // Process start time, Unix seconds
+}
+
+/// A live process seen on launch.
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveProcess {
+    pub pid: u32,
+    pub name: String,
+    pub exe: String,
+    pub started_at: u64,
+}
+
+/// Outcome of matching the session file against running processes.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionReport {
+    /// Recorded services still running with the same PID, image and start time
+    pub adopted: Vec<SessionEntry>,
+    /// Recorded services that exited, or whose PID now belongs to another process
+    pub stale: Vec<SessionEntry>,
+    /// Service processes running from Orbit's bin directory that nothing recorded
+    pub orphans: Vec<LiveProcess>,
+}
+
+/// Image names of the daemons Orbit manages
+const SERVICE_EXECUTABLES: &[&str] = &[
+    "nginx", "php-cgi", "php-fpm", "mariadbd", "mysqld", "postgres", "mongod", "httpd",
+    "redis-server", "mailpit", "meilisearch",
+];
+
+/// Start times from sysinfo are whole seconds; allow for rounding
+const START_TIME_TOLERANCE_SECS: u64 = 1;
+
+pub struct ServiceSession;
+
+impl ServiceSession {
+    const FILENAME: &'static str = "session.json";
+
+    fn get_path() -> PathBuf {
+        crate::services::paths::get_orbit_data_dir()
+            .join("config")
+            .join(Self::FILENAME)
+    }
+
+    /// Load recorded services (empty if the file is missing or unreadable)
+    pub fn load() -> Vec<SessionEntry> {
+        fs::read_to_string(Self::get_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(entries: &[SessionEntry]) -> Result<(), String> {
+        let path = Self::get_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {e}"))?;
+        }
+        let content = serde_json::to_string_pretty(entries)
+            .map_err(|e| format!("Failed to serialize session: {e}"))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write session: {e}"))
+    }
+
+    /// Record a started service. Image and start time come from the OS so they
+    /// compare exactly against what `reconcile` sees after a relaunch.
+    pub fn record(service: &str, pid: u32, fallback_exe: &str) {
+        let live = live_process(pid);
+        let entry = SessionEntry {
+            service: service.to_string(),
+            pid,
+            exe: live.as_ref().map(|p| p.exe.clone()).unwrap_or_else(|| fallback_exe.to_string()),
+            started_at: live
+                .map(|p| p.started_at)
+                .unwrap_or_else(|| chrono::Utc::now().timestamp() as u64),
+        };
+
+        let mut entries = Self::load();
+        entries.retain(|e| e.service != service);
+        entries.push(entry);
+        if let Err(e) = Self::save(&entries) {
+            log::warn!("Could not record {service} in session file: {e}");
+        }
+    }
+
+    /// Forget a service once Orbit has stopped it
+    pub fn remove(service: &str) {
+        let mut entries = Self::load();
+        let before = entries.len();
+        entries.retain(|e| e.service != service);
+        if entries.len() != before {
+            let _ = Self::save(&entries);
+        }
+    }
+
+    pub fn clear() {
+        let _ = Self::save(&[]);
+    }
+
+    /// Whether the recorded process is still the one Orbit started
+    pub fn is_running(entry: &SessionEntry) -> bool {
+        live_process(entry.pid).is_some_and(|p| matches_entry(entry, &p))
+    }
+
+    /// Take over an unrecorded service process found by `reconcile`. Only
+    /// service executables running from Orbit's bin directory are accepted.
+    pub fn adopt(service: &str, pid: u32) -> Result<SessionEntry, String> {
+        let process = live_process(pid).ok_or_else(|| format!("No process with PID {pid}"))?;
+        let bin_dir = crate::services::paths::get_bin_dir();
+        if !is_orbit_service_process(&process, &bin_dir.to_string_lossy()) {
+            return Err(format!("PID {pid} ({}) is not an Orbit service process", process.name));
+        }
+        Self::record(service, pid, &process.exe);
+        Ok(SessionEntry {
+            service: service.to_string(),
+            pid,
+            exe: process.exe,
+            started_at: process.started_at,
+        })
+    }
+
+    /// Match the session file against running processes, drop stale entries and
+    /// report what can be adopted and which Orbit processes nobody recorded.
+    pub fn reconcile() -> SessionReport {
+        let bin_dir = crate::services::paths::get_bin_dir();
+        let report = reconcile_entries(&Self::load(), &live_processes(), &bin_dir.to_string_lossy());
+        if !report.stale.is_empty() {
+            let _ = Self::save(&report.adopted);
+        }
+        report
+    }
+}
+
+fn to_live(pid: u32, process: &sysinfo::Process) -> LiveProcess {
+    LiveProcess {
+        pid,
+        name: process.name().to_string_lossy().to_string(),
+        exe: process.exe().map(|p| p.to_string_lossy().to_string()).unwrap_or_default(),
+        started_at: process.start_time(),
+    }
+}
+
+fn live_process(pid: u32) -> Option<LiveProcess> {
+    let mut sys = System::new();
+    let sys_pid = Pid::from_u32(pid);
+    sys.refresh_processes(ProcessesToUpdate::Some(&[sys_pid]), true);
+    sys.process(sys_pid).map(|p| to_live(pid, p))
+}
+
+fn live_processes() -> Vec<LiveProcess> {
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    sys.processes()
+        .iter()
+        .map(|(pid, p)| to_live(pid.as_u32(), p))
+        .collect()
+}
+
+/// Normalize a path for comparison (case, separators and `\\?\` prefixes differ by OS API)
+fn clean_path(path: &str) -> String {
+    path.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+fn is_orbit_service_process(process: &LiveProcess, bin_dir: &str) -> bool {
+    let name = process.name.to_lowercase();
+    let stem = name.strip_suffix(".exe").unwrap_or(&name);
+    SERVICE_EXECUTABLES.contains(&stem) && clean_path(&process.exe).contains(&clean_path(bin_dir))
+}
+
+fn matches_entry(entry: &SessionEntry, process: &LiveProcess) -> bool {
+    process.pid == entry.pid
+        && clean_path(&process.exe) == clean_path(&entry.exe)
+        && process.started_at.abs_diff(entry.started_at) <= START_TIME_TOLERANCE_SECS
+}
+
+/// Pure reconciliation step, split out from `ServiceSession::reconcile` for tests.
+fn reconcile_entries(entries: &[SessionEntry], live: &[LiveProcess], bin_dir: &str) -> SessionReport {
+    let mut report = SessionReport::default();
+
+    for entry in entries {
+        if live.iter().any(|p| matches_entry(entry, p)) {
+            report.adopted.push(entry.clone());
+        } else {
+            report.stale.push(entry.clone());
+        }
+    }
+
+    report.orphans = live
+        .iter()
+        .filter(|p| is_orbit_service_process(p, bin_dir))
+        .filter(|p| !report.adopted.iter().any(|e| e.pid == p.pid))
+        .cloned()
+        .collect();
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn live(pid: u32, name: &str, exe: &str, started_at: u64) -> LiveProcess {
+        LiveProcess { pid, name: name.to_string(), exe: exe.to_string(), started_at }
+    }
+
+    fn entry(service: &str, pid: u32, exe: &str, started_at: u64) -> SessionEntry {
+        SessionEntry { service: service.to_string(), pid, exe: exe.to_string(), started_at }
+    }
+
+    #[test]
+    fn test_reconcile_adopts_matching_and_reports_orphans() {
+        let bin = "/orbit/bin";
+        let entries = vec![
+            entry("nginx", 100, "/orbit/bin/nginx/nginx", 1_000),
+            // PID recycled by an unrelated process
+            entry("mariadb", 200, "/orbit/bin/mariadb/bin/mariadbd", 1_000),
+            // Same image and PID, but started later than recorded
+            entry("redis", 300, "/orbit/bin/redis/redis-server", 1_000),
+            // Exited
+            entry("php-8.4", 400, "/orbit/bin/php/8.4/php-cgi", 1_000),
+        ];
+        let processes = vec![
+            live(100, "nginx", "/orbit/bin/nginx/nginx", 1_001),
+            live(200, "bash", "/usr/bin/bash", 1_000),
+            live(300, "redis-server", "/orbit/bin/redis/redis-server", 5_000),
+            live(500, "php-cgi", "/orbit/bin/php/8.3/php-cgi", 2_000),
+            live(600, "nginx", "/usr/sbin/nginx", 2_000),
+        ];
+
+        let report = reconcile_entries(&entries, &processes, bin);
+        assert_eq!(report.adopted.iter().map(|e| e.service.as_str()).collect::<Vec<_>>(), vec!["nginx"]);
+        assert_eq!(
+            report.stale.iter().map(|e| e.service.as_str()).collect::<Vec<_>>(),
+            vec!["mariadb", "redis", "php-8.4"]
+        );
+        // The restarted redis and the unrecorded php-cgi are Orbit's but unmanaged;
+        // the system nginx outside the bin dir is not ours
+        assert_eq!(report.orphans.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![300, 500]);
+    }
+}
@@ -2,15 +2,28 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
+use crate::services::paths::OrbitPathExt;
+use crate::services::file_lock::FileLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SiteMetadata {
     pub domain: String,
+    /// Extra server names for the same vhost (written by the CLI/MCP tools)
+    #[serde(default)]
+    pub aliases: Vec<String>,
     pub path: String,
     pub port: u16,
     pub php_version: Option<String>,
     pub php_port: Option<u16>,
     pub ssl_enabled: bool,
+    /// Send Strict-Transport-Security on the SSL vhost (written by the CLI/MCP tools)
+    #[serde(default)]
+    pub hsts: bool,
+    /// Per-site gzip/brotli in the nginx vhost (written by the CLI/MCP tools)
+    #[serde(default)]
+    pub gzip: bool,
+    #[serde(default)]
+    pub brotli: bool,
     pub ssl_cert_path: Option<String>,
     pub ssl_key_path: Option<String>,
     #[serde(default)]
@@ -37,6 +50,38 @@ fn default_timestamp() -> String {
     chrono::Utc::now().to_rfc3339()
 }
 
+/// Layout version written to sites.json; `SiteStore::load` upgrades older
+/// ones (same layouts as orbit-cli and orbit-mcp)
+pub const STORE_VERSION: u32 = 2;
+
+/// Layout version of a sites.json `version` field; "1" (CLI/MCP) and "1.0"
+/// (app) are the same layout
+fn sites_store_layout(version: &str) -> u32 {
+    version.split('.').next()
+        .and_then(|major| major.trim().parse().ok())
+        .unwrap_or(1)
+}
+
+/// v2: lowercase web_server, a port for every site, updated_at set, and
+/// aliases without blanks, repeats or the domain itself
+fn migrate_site_to_v2(site: &mut SiteMetadata) {
+    site.web_server = site.web_server.to_lowercase();
+    if site.port == 0 {
+        site.port = if site.ssl_enabled { 443 } else { 80 };
+    }
+    if site.updated_at.is_empty() {
+        site.updated_at = site.created_at.clone();
+    }
+    let mut aliases: Vec<String> = Vec::new();
+    for alias in site.aliases.drain(..) {
+        let alias = alias.trim().to_lowercase();
+        if !alias.is_empty() && alias != site.domain && !aliases.contains(&alias) {
+            aliases.push(alias);
+        }
+    }
+    site.aliases = aliases;
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SiteStore {
     pub version: String,
@@ -47,7 +92,7 @@ impl SiteStore {
     fn get_store_path(app: &AppHandle) -> Result<PathBuf, String> {
         let config_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("config");
 
@@ -59,6 +104,12 @@ impl SiteStore {
         Ok(config_dir.join("sites.json"))
     }
 
+    /// Hold this across a load-modify-save so concurrent changes from the
+    /// app, orbit-cli and orbit-mcp don't overwrite each other
+    pub fn lock(app: &AppHandle) -> Result<FileLock, String> {
+        FileLock::acquire(&Self::get_store_path(app)?.with_file_name("sites.json.lock"))
+    }
+
     pub fn load(app: &AppHandle) -> Result<Self, String> {
         let path = Self::get_store_path(app)?;
 
@@ -72,8 +123,24 @@ impl SiteStore {
         let content = fs::read_to_string(&path)
             .map_err(|e| format!("Failed to read sites store: {e}"))?;
 
-        serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse sites store: {e}"))
+        let store: Self = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse sites store: {e}"))?;
+        Ok(store.migrate())
+    }
+
+    /// Upgrade a store read from disk to the current layout, one version at
+    /// a time; the new version is written with the next save
+    fn migrate(mut self) -> Self {
+        let version = sites_store_layout(&self.version);
+        if version > STORE_VERSION {
+            log::warn!("sites.json is version {}, newer than this build understands ({STORE_VERSION}); reading it as is", self.version);
+            return self;
+        }
+        if version < 2 {
+            self.sites.iter_mut().for_each(migrate_site_to_v2);
+        }
+        self.version = STORE_VERSION.to_string();
+        self
     }
 
     pub fn save(&self, app: &AppHandle) -> Result<(), String> {
@@ -123,7 +190,7 @@ impl SiteStore {
     pub fn migrate_from_nginx_configs(&mut self, app: &AppHandle) -> Result<usize, String> {
         let sites_dir = app
             .path()
-            .app_local_data_dir()
+            .orbit_data_dir()
             .map_err(|e| e.to_string())?
             .join("bin")
             .join("nginx")
@@ -196,11 +263,15 @@ impl SiteStore {
 
         Some(SiteMetadata {
             domain,
+            aliases: Vec::new(),
             path,
             port,
             php_version: None, // Can't determine from config
             php_port,
             ssl_enabled,
+            hsts: false,
+            gzip: false,
+            brotli: false,
             ssl_cert_path: None,
             ssl_key_path: None,
             template: None,
@@ -237,11 +308,15 @@ mod tests {
     fn create_test_site(domain: &str) -> SiteMetadata {
         SiteMetadata {
             domain: domain.to_string(),
+            aliases: Vec::new(),
             path: "/var/www/test".to_string(),
             port: 80,
             php_version: Some("8.4".to_string()),
             php_port: Some(9004),
             ssl_enabled: false,
+            hsts: false,
+            gzip: false,
+            brotli: false,
             ssl_cert_path: None,
             ssl_key_path: None,
             template: Some("laravel".to_string()),
@@ -360,6 +435,24 @@ mod tests {
         assert_eq!(deserialized.web_server, "nginx"); // Default was used
     }
 
+    #[test]
+    fn test_site_store_migrates_v1() {
+        let mut site = create_test_site("shop.test");
+        site.web_server = "Nginx".to_string();
+        site.port = 0;
+        site.ssl_enabled = true;
+        site.aliases = vec!["WWW.shop.test".to_string(), "".to_string(), "shop.test".to_string()];
+        let store = SiteStore { version: "1.0".to_string(), sites: vec![site] }.migrate();
+
+        assert_eq!(store.version, STORE_VERSION.to_string());
+        let site = &store.sites[0];
+        assert_eq!((site.web_server.as_str(), site.port), ("nginx", 443));
+        assert_eq!(site.aliases, vec!["www.shop.test"]);
+
+        let future = SiteStore { version: "3".to_string(), sites: vec![] }.migrate();
+        assert_eq!(future.version, "3");
+    }
+
     #[test]
     fn test_site_store_serde() {
         let mut store = SiteStore {
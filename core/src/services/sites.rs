@@ -6,6 +6,7 @@ use crate::services::site_store::{SiteMetadata, SiteStore};
 use crate::services::ssl::SSLManager;
 use crate::services::templates::{SiteTemplate, TemplateEngine, TEMPLATE_LITECART_SSL, TEMPLATE_REVERSE_PROXY_SSL};
 use crate::services::validation::{validate_domain, validate_port, validate_site_path, sanitize_for_nginx};
+use crate::services::paths::OrbitPathExt;
 use std::collections::HashMap;
 use std::fs;
 use tauri::{AppHandle, Manager};
@@ -105,6 +106,13 @@ impl SiteManager {
 
     /// Create a new site with full transaction support
     pub fn create_site(app: &AppHandle, site: Site) -> Result<SiteWithStatus, String> {
+        Self::create_site_keeping(app, site, None)
+    }
+
+    /// Create a site, carrying over from `previous` (the entry it replaces) the
+    /// settings `Site` doesn't have: aliases, hsts, gzip and brotli, which only
+    /// the CLI/MCP tools edit
+    fn create_site_keeping(app: &AppHandle, site: Site, previous: Option<&SiteMetadata>) -> Result<SiteWithStatus, String> {
         // SECURITY: Validate all input first
         Self::validate_site_input(&site)?;
 
@@ -128,6 +136,7 @@ impl SiteManager {
         }
 
         // Load store
+        let lock = SiteStore::lock(app)?;
         let mut store = SiteStore::load(app)?;
 
         // Check if site already exists
@@ -147,15 +156,7 @@ impl SiteManager {
         let template = site
             .template
             .clone()
-            .map(|t| match t.as_str() {
-                "laravel" => SiteTemplate::Laravel,
-                "wordpress" => SiteTemplate::WordPress,
-                "litecart" => SiteTemplate::LiteCart,
-                "static" => SiteTemplate::Static,
-                "https" => SiteTemplate::Https,
-                "nextjs" | "astro" | "nuxt" | "vue" => SiteTemplate::ReverseProxy,
-                _ => SiteTemplate::Http,
-            })
+            .map(|t| SiteTemplate::from_name(&t))
             .unwrap_or_else(|| {
                 if site.php_version.is_some() {
                     SiteTemplate::detect_from_path(&site.path)
@@ -197,7 +198,7 @@ impl SiteManager {
             // Get bin path for SSL operations
             let bin_path = app
                 .path()
-                .app_local_data_dir()
+                .orbit_data_dir()
                 .map_err(|e| e.to_string())?
                 .join("bin");
 
@@ -302,11 +303,15 @@ impl SiteManager {
 
         let metadata = SiteMetadata {
             domain: site.domain.clone(),
+            aliases: previous.map(|p| p.aliases.clone()).unwrap_or_default(),
             path: site.path.clone(),
             port: site.port,
             php_version: site.php_version.clone(),
             php_port,
             ssl_enabled: site.ssl_enabled,
+            hsts: previous.is_some_and(|p| p.hsts),
+            gzip: previous.is_some_and(|p| p.gzip),
+            brotli: previous.is_some_and(|p| p.brotli),
             ssl_cert_path,
             ssl_key_path,
             template: site.template.clone(),
@@ -321,6 +326,7 @@ impl SiteManager {
         // Save to store
         store.add_site(metadata);
         store.save(app)?;
+        drop(lock);
 
         // Try to reload the appropriate web server if running
         if use_apache {
@@ -353,7 +359,9 @@ impl SiteManager {
 
         // Migration: if store is empty, try to import from nginx configs
         if store.sites.is_empty() {
-            let migrated = store.migrate_from_nginx_configs(app)?;
+            let _lock = SiteStore::lock(app)?;
+            store = SiteStore::load(app)?;
+            let migrated = if store.sites.is_empty() { store.migrate_from_nginx_configs(app)? } else { 0 };
             if migrated > 0 {
                 store.save(app)?;
                 log::info!("Migrated {migrated} sites from nginx configs");
@@ -441,6 +449,7 @@ impl SiteManager {
         // Validate update data
         Self::validate_site_input(&updates)?;
 
+        let lock = SiteStore::lock(app)?;
         let mut store = SiteStore::load(app)?;
 
         let existing = store
@@ -474,6 +483,7 @@ impl SiteManager {
         // Remove from store
         store.remove_site(domain);
         store.save(app)?;
+        drop(lock);
 
         // Determine php_port: if php_version changed, recalculate port from new version
         let new_php_port = if let Some(ref new_version) = updates.php_version {
@@ -503,15 +513,17 @@ impl SiteManager {
             dev_working_dir: updates.dev_working_dir,
         };
 
-        Self::create_site(app, new_site)
+        Self::create_site_keeping(app, new_site, Some(&existing))
     }
 
     /// Delete a site
     pub fn delete_site(app: &AppHandle, domain: &str) -> Result<(), String> {
         // Load and update store
+        let lock = SiteStore::lock(app)?;
         let mut store = SiteStore::load(app)?;
         let site = store.remove_site(domain);
         store.save(app)?;
+        drop(lock);
 
         // Determine web server from site metadata
         let use_apache = site.as_ref()
@@ -620,11 +632,13 @@ impl SiteManager {
         }
 
         // Recreate
+        let lock = SiteStore::lock(app)?;
         let mut store = SiteStore::load(app)?;
         store.remove_site(domain);
         store.save(app)?;
+        drop(lock);
 
-        Self::create_site(app, site_data)?;
+        Self::create_site_keeping(app, site_data, Some(site))?;
 
         Ok(())
     }
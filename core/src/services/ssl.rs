@@ -52,6 +52,7 @@ impl SSLManager {
         let mkcert_path = Self::get_mkcert_path(bin_path);
 
         // Download mkcert
+        crate::services::network::require_online("download mkcert")?;
         let response = reqwest::get(MKCERT_DOWNLOAD_URL)
             .await
             .map_err(|e| format!("Failed to download mkcert: {e}"))?;
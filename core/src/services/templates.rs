@@ -956,6 +956,33 @@ pub const TEMPLATE_REMIX: &str = r#"server {
 }
 "#;
 
+/// Modules an Apache vhost relies on, detected from the directives it uses.
+/// Mirrored in orbit-mcp's `required_apache_modules`.
+pub fn required_apache_modules(vhost: &str) -> Vec<&'static str> {
+    let rules: [(bool, &[&'static str]); 5] = [
+        (vhost.contains("proxy:fcgi://"), &["proxy_module", "proxy_fcgi_module"]),
+        (vhost.contains("ProxyPass "), &["proxy_module", "proxy_http_module"]),
+        (vhost.contains("ws://"), &["proxy_module", "proxy_wstunnel_module"]),
+        // AllowOverride All is there for framework .htaccess rewrites
+        (vhost.contains("RewriteEngine") || vhost.contains("AllowOverride All"), &["rewrite_module"]),
+        (vhost.contains("SSLEngine"), &["ssl_module"]),
+    ];
+    let mut modules: Vec<&'static str> = Vec::new();
+    for (needed, mods) in rules {
+        if needed {
+            for m in mods {
+                if !modules.contains(m) {
+                    modules.push(m);
+                }
+            }
+        }
+    }
+    if vhost.lines().any(|l| l.trim_start().starts_with("Header ")) {
+        modules.push("headers_module");
+    }
+    modules
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[allow(dead_code)]
 pub enum SiteTemplate {
@@ -1009,6 +1036,24 @@ impl SiteTemplate {
         }
     }
 
+    /// Map a site's template name (as stored in sites.json) to a template
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "laravel" => SiteTemplate::Laravel,
+            "wordpress" => SiteTemplate::WordPress,
+            "litecart" => SiteTemplate::LiteCart,
+            "static" => SiteTemplate::Static,
+            "https" => SiteTemplate::Https,
+            "nextjs" | "astro" | "nuxt" | "vue" => SiteTemplate::ReverseProxy,
+            _ => SiteTemplate::Http,
+        }
+    }
+
+    /// Apache modules (LoadModule names) the Apache vhost for this template needs
+    pub fn required_apache_modules(&self) -> Vec<&'static str> {
+        required_apache_modules(self.get_apache_template())
+    }
+
     pub fn detect_from_path(path: &str) -> Self {
         let path = std::path::Path::new(path);
 
@@ -1160,6 +1205,19 @@ mod tests {
         assert!(t.contains("wp-config.php"));
     }
 
+    #[test]
+    fn test_required_apache_modules() {
+        assert_eq!(
+            SiteTemplate::Laravel.required_apache_modules(),
+            vec!["proxy_module", "proxy_fcgi_module", "rewrite_module"]
+        );
+        assert_eq!(
+            SiteTemplate::ReverseProxy.required_apache_modules(),
+            vec!["proxy_module", "proxy_http_module", "proxy_wstunnel_module", "rewrite_module"]
+        );
+        assert_eq!(SiteTemplate::from_name("nuxt"), SiteTemplate::ReverseProxy);
+    }
+
     #[test]
     fn test_apache_template_content() {
         let t = SiteTemplate::Laravel.get_apache_template();
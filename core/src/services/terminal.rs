@@ -22,10 +22,9 @@ impl Default for TerminalState {
     }
 }
 
-pub fn build_orbit_path(_app_handle: &AppHandle) -> String {
-    let app_dir = crate::services::paths::get_orbit_data_dir();
-    let bin_dir = app_dir.join("bin");
-    
+/// Orbit's tool directories, most specific first. The MCP server keeps a
+/// copy for run_in_project; keep the two identical.
+pub fn orbit_path_dirs(bin_dir: &std::path::Path) -> Vec<std::path::PathBuf> {
     // We want the most specific binaries in front.
     let mut paths_to_inject = vec![
         bin_dir.join("mariadb").join("bin"),
@@ -51,11 +50,17 @@ pub fn build_orbit_path(_app_handle: &AppHandle) -> String {
             }
         }
     }
-    
+
+    paths_to_inject.into_iter().filter(|p| p.exists()).collect()
+}
+
+pub fn build_orbit_path(_app_handle: &AppHandle) -> String {
+    let app_dir = crate::services::paths::get_orbit_data_dir();
+    let paths_to_inject = orbit_path_dirs(&app_dir.join("bin"));
+
     // Convert to strings and filter valid
     let mut custom_paths: Vec<String> = paths_to_inject
         .into_iter()
-        .filter(|p| p.exists())
         .filter_map(|p| p.to_str().map(|s| s.to_string()))
         .collect();
         
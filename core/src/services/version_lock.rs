@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// The exact download an installed service came from, kept in
+/// config/versions.lock under its install type ("nginx", "php-8.4") so
+/// `orbit install --locked` can reproduce it. Same format as orbit-cli's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedVersion {
+    pub version: String,
+    pub url: String,
+    pub filename: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub installed_at: String,
+}
+
+/// Record what was just installed under `install_type` in
+/// `<config_dir>/versions.lock`, replacing any earlier entry
+pub fn record(config_dir: &Path, install_type: &str, mut entry: LockedVersion) -> Result<(), String> {
+    let path = config_dir.join("versions.lock");
+    let mut lock: BTreeMap<String, LockedVersion> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    entry.installed_at = chrono::Utc::now().to_rfc3339();
+    lock.insert(install_type.to_string(), entry);
+
+    fs::create_dir_all(config_dir).map_err(|e| format!("Failed to create config dir: {e}"))?;
+    let content = serde_json::to_string_pretty(&lock)
+        .map_err(|e| format!("Failed to serialize versions.lock: {e}"))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write versions.lock: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_keeps_other_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = |version: &str| LockedVersion {
+            version: version.to_string(),
+            url: format!("https://example.com/nginx-{version}.zip"),
+            filename: format!("nginx-{version}.zip"),
+            sha256: None,
+            installed_at: String::new(),
+        };
+        record(dir.path(), "nginx", entry("1.26.0")).unwrap();
+        record(dir.path(), "php-8.4", entry("8.4.3")).unwrap();
+        record(dir.path(), "nginx", entry("1.28.0")).unwrap();
+
+        let content = fs::read_to_string(dir.path().join("versions.lock")).unwrap();
+        let lock: BTreeMap<String, LockedVersion> = serde_json::from_str(&content).unwrap();
+        assert_eq!(lock.keys().collect::<Vec<_>>(), ["nginx", "php-8.4"]);
+        assert_eq!(lock["nginx"].version, "1.28.0");
+        assert!(!lock["nginx"].installed_at.is_empty() && !content.contains("sha256"));
+    }
+}
@@ -3,41 +3,52 @@ use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 
 use super::types::{CachedVersions, ServiceVersion, VersionCache, VersionSource};
+use crate::services::network::NetworkSettings;
 
 const CACHE_KEY: &str = "version_cache";
-const CACHE_TTL_SECS: i64 = 86400; // 24 hours
+
+/// config/versions-refresh holds the Unix time of the last
+/// `orbit versions --refresh` (also run by the MCP `refresh_versions` tool);
+/// entries fetched before it count as expired. Writing it fetches nothing:
+/// each service's list is refetched the next time the app asks for it.
+const REFRESH_MARKER: &str = "versions-refresh";
 
 pub struct VersionCacheManager;
 
 impl VersionCacheManager {
-    fn now() -> i64 {
+    pub fn now() -> i64 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64
     }
 
-    pub fn is_valid(cached: &CachedVersions) -> bool {
-        let now = Self::now();
-        now - cached.fetched_at < CACHE_TTL_SECS
+    /// When the CLI/MCP last asked for a refresh
+    pub fn refresh_requested_at() -> Option<i64> {
+        let path = crate::services::paths::get_orbit_data_dir().join("config").join(REFRESH_MARKER);
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    pub fn is_valid(cached: &CachedVersions, now: i64, refresh_requested_at: Option<i64>) -> bool {
+        now - cached.fetched_at < cached.ttl_secs
+            && refresh_requested_at.map_or(true, |requested| cached.fetched_at >= requested)
     }
 
-    pub async fn get(app: &AppHandle, service: &str) -> Option<Vec<ServiceVersion>> {
+    /// The cached entry for a service, expired or not
+    pub async fn get(app: &AppHandle, service: &str) -> Option<CachedVersions> {
         let store = app.store(CACHE_KEY).ok()?;
         let json_value = store.get("data")?;
-        let cache: VersionCache = serde_json::from_value(json_value).ok()?;
-
-        let cached = cache.services.get(service)?;
+        let mut cache: VersionCache = serde_json::from_value(json_value).ok()?;
+        cache.services.remove(service)
+    }
 
-        if Self::is_valid(cached) {
-            let mut versions = cached.versions.clone();
-            for v in &mut versions {
-                v.source = VersionSource::Cache;
-            }
-            Some(versions)
-        } else {
-            None
+    /// An entry's versions, marked as served from the cache
+    pub fn versions(cached: &CachedVersions) -> Vec<ServiceVersion> {
+        let mut versions = cached.versions.clone();
+        for v in &mut versions {
+            v.source = VersionSource::Cache;
         }
+        versions
     }
 
     pub async fn set(app: &AppHandle, service: &str, versions: Vec<ServiceVersion>) -> Result<(), String> {
@@ -52,6 +63,7 @@ impl VersionCacheManager {
             versions,
             fetched_at: Self::now(),
             service: service.to_string(),
+            ttl_secs: NetworkSettings::load().version_cache_ttl_secs as i64,
         };
 
         cache.services.insert(service.to_string(), cached);
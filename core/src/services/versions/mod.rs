@@ -2,51 +2,120 @@ mod types;
 mod cache;
 
 pub use types::ServiceVersion;
-use types::VersionSource;
+use types::{CachedVersions, VersionSource};
 use cache::VersionCacheManager;
+use crate::services::network::{is_offline, NetworkSettings};
 use crate::services::registry::LibraryRegistry;
+use futures_util::future::join_all;
+use std::future::Future;
 use tauri::AppHandle;
+use tokio::sync::Semaphore;
 
 pub struct VersionFetcher;
 
 impl VersionFetcher {
     /// Fetch versions for any service from the registry
     pub async fn fetch_versions(app: &AppHandle, service: &str, force_refresh: bool) -> Result<Vec<ServiceVersion>, String> {
-        // Check cache first
-        if !force_refresh {
-            if let Some(cached) = VersionCacheManager::get(app, service).await {
-                return Ok(cached);
-            }
-        }
+        Self::fetch_versions_since(app, service, force_refresh, VersionCacheManager::now()).await
+    }
 
-        // Fetch registry (remote with fallback)
-        let registry = if force_refresh {
-            LibraryRegistry::fetch().await.unwrap_or_else(|_| {
-                LibraryRegistry::load_fallback().unwrap_or_else(|e| panic!("Fallback registry broken: {e}"))
-            })
+    /// `fetch_versions`, where a forced refresh may reuse a registry fetched
+    /// at or after `refresh_started` so `refresh_all` downloads it only once
+    async fn fetch_versions_since(app: &AppHandle, service: &str, force_refresh: bool, refresh_started: i64) -> Result<Vec<ServiceVersion>, String> {
+        // A forced refresh can't fetch anything new offline; serve the cache
+        let force_refresh = force_refresh && !is_offline();
+
+        let now = VersionCacheManager::now();
+        let refresh_requested_at = VersionCacheManager::refresh_requested_at();
+        // Oldest registry download still good enough to build the list from
+        let fetched_since = if force_refresh {
+            refresh_started
         } else {
-            LibraryRegistry::get().await.unwrap_or_else(|_| {
-                LibraryRegistry::load_fallback().unwrap_or_else(|e| panic!("Fallback registry broken: {e}"))
-            })
+            let ttl = NetworkSettings::load().version_cache_ttl_secs as i64;
+            (now - ttl).max(refresh_requested_at.unwrap_or(0))
         };
 
-        // Convert registry data to ServiceVersion[]
-        let versions = Self::registry_to_versions(&registry, service)?;
+        let cached = VersionCacheManager::get(app, service).await;
+        let fetched = Self::cached_or_fetch(
+            cached,
+            now,
+            refresh_requested_at,
+            force_refresh,
+            || async {
+                let registry = LibraryRegistry::fetch_fresh_since(fetched_since).await?;
+                let versions = Self::registry_to_versions(&registry, service)?;
+                let _ = VersionCacheManager::set(app, service, versions.clone()).await;
+                Ok(versions)
+            },
+        ).await;
+        match fetched {
+            Ok(versions) => Ok(versions),
+            // Nothing cached and the fetch failed: the bundled registry, not
+            // cached so the next call tries the network again
+            Err(e) => {
+                log::warn!("Version fetch for {service} failed ({e}), using the bundled registry");
+                Self::registry_to_versions(&LibraryRegistry::load_fallback()?, service)
+            }
+        }
+    }
 
-        // Cache result
-        let _ = VersionCacheManager::set(app, service, versions.clone()).await;
-        Ok(versions)
+    /// Serve a cache entry younger than its TTL, otherwise run `fetch`. An
+    /// expired entry is still better than nothing when the fetch fails.
+    async fn cached_or_fetch<F, Fut>(
+        cached: Option<CachedVersions>,
+        now: i64,
+        refresh_requested_at: Option<i64>,
+        force_refresh: bool,
+        fetch: F,
+    ) -> Result<Vec<ServiceVersion>, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<ServiceVersion>, String>>,
+    {
+        if let Some(entry) = &cached {
+            if !force_refresh && VersionCacheManager::is_valid(entry, now, refresh_requested_at) {
+                return Ok(VersionCacheManager::versions(entry));
+            }
+        }
+        match fetch().await {
+            Ok(versions) => Ok(versions),
+            Err(e) => match cached {
+                Some(entry) => {
+                    log::warn!("Version fetch failed ({e}), using versions cached at {}", entry.fetched_at);
+                    Ok(VersionCacheManager::versions(&entry))
+                }
+                None => Err(e),
+            },
+        }
     }
 
     /// Refresh all service versions
     pub async fn refresh_all(app: &AppHandle) -> Result<(), String> {
+        if is_offline() {
+            log::info!("Offline mode: keeping cached versions");
+            return Ok(());
+        }
+
         let _ = VersionCacheManager::clear_all(app).await;
+        let started = VersionCacheManager::now();
 
         let services = ["php", "nginx", "apache", "mariadb", "nodejs", "python", "bun"];
-        let mut errors = Vec::new();
 
-        for service in &services {
-            if let Err(e) = Self::fetch_versions(app, service, true).await {
+        // Bound the fan-out so slow links aren't saturated
+        let limit = NetworkSettings::load().max_concurrent_fetches.max(1);
+        let permits = Semaphore::new(limit);
+        let results = join_all(services.iter().map(|service| {
+            let permits = &permits;
+            async move {
+                let _permit = permits.acquire().await.map_err(|e| e.to_string())?;
+                Self::fetch_versions_since(app, service, true, started).await
+            }
+        }))
+        .await;
+
+        let mut errors = Vec::new();
+        for (service, result) in services.iter().zip(results) {
+            if let Err(e) = result {
                 log::error!("{service} refresh failed: {e}");
                 errors.push(format!("{service}: {e}"));
             }
@@ -92,6 +161,7 @@ impl VersionFetcher {
                             filename: dl.filename.clone(),
                             release_date: None,
                             source: VersionSource::Api,
+                            sha256: dl.sha256.clone(),
                         });
                     }
                 }
@@ -106,6 +176,7 @@ impl VersionFetcher {
                         filename: dl.filename.clone(),
                         release_date: None,
                         source: VersionSource::Api,
+                        sha256: dl.sha256.clone(),
                     });
                 }
             }
@@ -155,3 +226,65 @@ impl VersionFetcher {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt;
+
+    fn entry(fetched_at: i64, ttl_secs: i64) -> CachedVersions {
+        CachedVersions {
+            versions: vec![ServiceVersion {
+                version: "1.28.0".to_string(),
+                download_url: "https://example.com/nginx-1.28.0.zip".to_string(),
+                filename: "nginx-1.28.0.zip".to_string(),
+                release_date: None,
+                source: VersionSource::Api,
+                sha256: None,
+            }],
+            fetched_at,
+            service: "nginx".to_string(),
+            ttl_secs,
+        }
+    }
+
+    fn fetched() -> Result<Vec<ServiceVersion>, String> {
+        let mut versions = entry(0, 0).versions;
+        versions[0].version = "1.28.2".to_string();
+        Ok(versions)
+    }
+
+    fn resolve(cached: CachedVersions, now: i64, fetch: Result<Vec<ServiceVersion>, String>) -> (Vec<ServiceVersion>, bool) {
+        let mut called = false;
+        let versions = VersionFetcher::cached_or_fetch(Some(cached), now, None, false, || {
+            called = true;
+            async { fetch }
+        })
+        .now_or_never()
+        .unwrap()
+        .unwrap();
+        (versions, called)
+    }
+
+    #[test]
+    fn test_version_cache_ttl() {
+        // Fresh: served from the cache without fetching
+        let (versions, called) = resolve(entry(1_000, 3_600), 2_000, fetched());
+        assert!(!called);
+        assert_eq!(versions[0].version, "1.28.0");
+        assert_eq!(versions[0].source, VersionSource::Cache);
+
+        // Older than its TTL: refetched
+        let (versions, called) = resolve(entry(1_000, 3_600), 5_000, fetched());
+        assert!(called);
+        assert_eq!(versions[0].version, "1.28.2");
+
+        // Expired and the network is down: the stale entry is still used
+        let (versions, called) = resolve(entry(1_000, 3_600), 5_000, Err("offline".to_string()));
+        assert!(called);
+        assert_eq!(versions[0].version, "1.28.0");
+
+        // A refresh requested after the fetch expires the entry early
+        assert!(!VersionCacheManager::is_valid(&entry(1_000, 3_600), 2_000, Some(1_500)));
+    }
+}
@@ -18,6 +18,9 @@ pub struct ServiceVersion {
     pub release_date: Option<String>,
     #[serde(default)]
     pub source: VersionSource,
+    /// Expected SHA-256 of the download, when the registry lists one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
 }
 
 
@@ -26,6 +29,14 @@ pub struct CachedVersions {
     pub versions: Vec<ServiceVersion>,
     pub fetched_at: i64,
     pub service: String,
+    /// TTL in effect when the entry was written (entries from before the
+    /// setting existed get the default)
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: i64,
+}
+
+fn default_ttl_secs() -> i64 {
+    crate::services::network::DEFAULT_VERSION_CACHE_TTL_SECS as i64
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -406,6 +406,26 @@ const tests: TestCase[] = [
     },
   },
 
+  {
+    name: "List DB users",
+    tool: "list_db_users",
+    requiresRunning: "mariadb",
+    validate: (r) => {
+      if (isError(r)) return `Failed to list users: ${getContent(r)}`;
+      if (!getContent(r).includes("root")) return "Expected root user in list";
+      return null;
+    },
+  },
+  {
+    name: "Create DB user (invalid name → error)",
+    tool: "create_db_user",
+    args: { username: "app'; DROP USER root; --", password: "secret" },
+    validate: (r) => {
+      if (!isError(r)) return "Expected error for invalid user name";
+      return null;
+    },
+  },
+
   // ═══════════════════ PostgreSQL ═══════════════════
   {
     name: "PG list databases",
@@ -456,6 +476,15 @@ const tests: TestCase[] = [
       return null;
     },
   },
+  {
+    name: "Get OPcache stats (nonexistent version → error)",
+    tool: "get_opcache_stats",
+    args: { version: "0.1" },
+    validate: (r) => {
+      if (!isError(r)) return "Expected error for a PHP version that isn't installed";
+      return null;
+    },
+  },
 
   // ═══════════════════ SSL ═══════════════════
   {
@@ -602,6 +631,28 @@ const tests: TestCase[] = [
     },
   },
 
+  {
+    name: "Run in project (not allowlisted → refused)",
+    tool: "run_in_project",
+    args: { project_path: "C:\\Windows\\Temp", command: "del /q *" },
+    validate: (r) => {
+      if (!isError(r)) return "Expected refusal without confirm";
+      if (!getContent(r).includes("confirm")) return `Unexpected error: ${getContent(r)}`;
+      return null;
+    },
+  },
+
+  {
+    name: "Run in project (inline code → refused)",
+    tool: "run_in_project",
+    args: { project_path: "C:\\Windows\\Temp", command: "php -r phpinfo()", timeout_secs: 10 },
+    validate: (r) => {
+      if (!isError(r)) return "Expected refusal without confirm";
+      if (!getContent(r).includes("confirm")) return `Unexpected error: ${getContent(r)}`;
+      return null;
+    },
+  },
+
   // ═══════════════════ Composer ═══════════════════
   {
     name: "Composer install (nonexistent path → error)",
@@ -630,6 +681,55 @@ const tests: TestCase[] = [
       return null;
     },
   },
+  {
+    name: "Composer outdated (nonexistent path → error)",
+    tool: "composer_outdated",
+    args: { project_path: "C:\\nonexistent\\path\\__mcp_test__" },
+    validate: (r) => {
+      if (!isError(r)) return "Expected error for nonexistent project path";
+      return null;
+    },
+  },
+  {
+    name: "Composer why (option as package → error)",
+    tool: "composer_why",
+    args: { project_path: "C:\\nonexistent\\path\\__mcp_test__", package: "--help" },
+    validate: (r) => {
+      if (!isError(r)) return "Expected error for invalid package name";
+      return null;
+    },
+  },
+
+  // ═══════════════════ Node Scripts ═══════════════════
+  {
+    name: "npm run (nonexistent path → error)",
+    tool: "npm_run",
+    args: { project_path: "C:\\nonexistent\\path\\__mcp_test__", script: "build" },
+    validate: (r) => {
+      if (!isError(r)) return "Expected error for nonexistent project path";
+      return null;
+    },
+  },
+
+  // ═══════════════════ Laravel Artisan ═══════════════════
+  {
+    name: "Artisan migrate (nonexistent site → error)",
+    tool: "artisan_migrate",
+    args: { domain: "__nonexistent-domain__.test" },
+    validate: (r) => {
+      if (!isError(r)) return "Expected error for nonexistent site";
+      return null;
+    },
+  },
+  {
+    name: "Artisan tinker (empty expression → error)",
+    tool: "artisan_tinker",
+    args: { domain: "__nonexistent-domain__.test", expr: "" },
+    validate: (r) => {
+      if (!isError(r)) return "Expected error for empty expression";
+      return null;
+    },
+  },
 
   // ═══════════════════ Site Config ═══════════════════
   {